@@ -0,0 +1,2 @@
+drop table if exists run_is_rerun;
+drop type if exists rerun_mode_enum;
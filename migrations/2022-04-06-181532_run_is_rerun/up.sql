@@ -0,0 +1,10 @@
+create type rerun_mode_enum as enum('full', 'eval_only');
+
+create table run_is_rerun (
+    run_id uuid primary key references run(run_id),
+    source_run_id uuid not null references run(run_id),
+    mode rerun_mode_enum not null,
+    created_at timestamptz not null default current_timestamp
+);
+
+create index on run_is_rerun(source_run_id);
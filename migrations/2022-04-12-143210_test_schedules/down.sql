@@ -0,0 +1,2 @@
+drop table if exists run_is_from_schedule;
+drop table if exists test_schedule;
@@ -0,0 +1,24 @@
+create table test_schedule (
+    test_schedule_id uuid primary key DEFAULT uuid_generate_v4(),
+    test_id uuid not null references test(test_id),
+    cron_expression text not null,
+    test_input jsonb,
+    test_options jsonb,
+    eval_input jsonb,
+    eval_options jsonb,
+    enabled boolean not null default true,
+    last_scheduled_at timestamptz,
+    created_at timestamptz not null default current_timestamp,
+    created_by text
+);
+
+create index on test_schedule(test_id);
+
+create table run_is_from_schedule (
+    run_id uuid primary key references run(run_id),
+    test_schedule_id uuid not null references test_schedule(test_schedule_id),
+    scheduled_for timestamptz not null,
+    created_at timestamptz not null default current_timestamp
+);
+
+create index on run_is_from_schedule(test_schedule_id);
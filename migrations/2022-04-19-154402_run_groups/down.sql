@@ -0,0 +1,2 @@
+drop table if exists run_in_group;
+drop table if exists run_group;
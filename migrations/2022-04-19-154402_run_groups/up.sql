@@ -0,0 +1,16 @@
+create table run_group (
+    run_group_id uuid primary key DEFAULT uuid_generate_v4(),
+    name text not null unique,
+    description text,
+    created_at timestamptz not null default current_timestamp,
+    created_by text
+);
+
+create table run_in_group (
+    run_id uuid not null references run(run_id),
+    run_group_id uuid not null references run_group(run_group_id),
+    created_at timestamptz not null default current_timestamp,
+    primary key (run_id, run_group_id)
+);
+
+create index on run_in_group(run_group_id);
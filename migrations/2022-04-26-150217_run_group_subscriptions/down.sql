@@ -0,0 +1,7 @@
+-- Removing the 'run_group' entity type requires getting rid of any subscriptions to run groups and
+-- then replacing the enum
+delete from subscription where entity_type = 'run_group';
+alter type entity_type_enum rename to old_entity_type_enum;
+create type entity_type_enum as enum('pipeline', 'template', 'test');
+alter table subscription alter column entity_type type entity_type_enum using (entity_type::text::entity_type_enum);
+drop type old_entity_type_enum;
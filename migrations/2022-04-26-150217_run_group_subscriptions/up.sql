@@ -0,0 +1,6 @@
+-- Adding the 'run_group' entity type requires replacing the enum
+-- (Diesel runs these in a transaction, and postgres won't let you add values to a type in a transaction)
+alter type entity_type_enum rename to old_entity_type_enum;
+create type entity_type_enum as enum('pipeline', 'run_group', 'template', 'test');
+alter table subscription alter column entity_type type entity_type_enum using (entity_type::text::entity_type_enum);
+drop type old_entity_type_enum;
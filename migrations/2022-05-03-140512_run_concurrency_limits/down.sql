@@ -0,0 +1,63 @@
+alter table test
+    drop column if exists priority;
+
+alter table test
+    drop column if exists max_concurrent_runs;
+
+alter table pipeline
+    drop column if exists max_concurrent_runs;
+
+drop view if exists run_with_results_and_errors;
+
+-- Any runs that are still queued will never be started, so mark them as failed
+update run set status = 'carrot_failed', finished_at = current_timestamp where status = 'queued';
+
+alter type run_status_enum rename to old_run_status_enum;
+create type run_status_enum as enum(
+    'build_failed',
+    'building',
+    'carrot_failed',
+    'created',
+    'eval_aborted',
+    'eval_aborting',
+    'eval_failed',
+    'eval_queued_in_cromwell',
+    'eval_running',
+    'eval_starting',
+    'eval_submitted',
+    'eval_waiting_for_queue_space',
+    'succeeded',
+    'test_aborted',
+    'test_aborting',
+    'test_failed',
+    'test_queued_in_cromwell',
+    'test_running',
+    'test_starting',
+    'test_submitted',
+    'test_waiting_for_queue_space'
+);
+
+alter table run
+    alter column status
+    set data type run_status_enum
+    using (status::text::run_status_enum);
+
+drop type old_run_status_enum;
+
+create view run_with_results_and_errors as
+select run_id, test_id, name, status, test_input, test_options, eval_input,
+       eval_options, test_cromwell_job_id, eval_cromwell_job_id, created_at, created_by,
+       finished_at, results, errors
+from run
+ left join
+ (
+     select run_id, jsonb_agg(to_char(created_at, 'YYYY-MM-DD HH24:MI:SS.MS') || ': ' || error) as errors
+     from run_error
+     group by run_id
+ ) as errors using (run_id)
+ left join
+ (
+     select run_id, jsonb_object_agg(name, value) as results
+     from run_result inner join result using (result_id)
+     group by run_id
+ ) as results using (run_id);
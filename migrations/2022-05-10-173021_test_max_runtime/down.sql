@@ -0,0 +1,2 @@
+alter table test
+    drop column if exists max_runtime_in_secs;
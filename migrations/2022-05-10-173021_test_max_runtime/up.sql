@@ -0,0 +1,4 @@
+-- Optional maximum time, in seconds, that each of a test's workflows can run in cromwell before
+-- being aborted
+alter table test
+    add max_runtime_in_secs integer;
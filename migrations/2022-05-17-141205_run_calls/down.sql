@@ -0,0 +1,2 @@
+drop table if exists run_call;
+drop type if exists run_call_workflow_enum;
@@ -0,0 +1,23 @@
+create type run_call_workflow_enum as enum('test', 'eval');
+
+-- Per-task call metadata retrieved from cromwell for the test and eval workflows of a run
+create table run_call (
+    run_call_id uuid primary key DEFAULT uuid_generate_v4(),
+    run_id uuid not null references run(run_id),
+    workflow run_call_workflow_enum not null,
+    cromwell_job_id text not null,
+    task_name text not null,
+    shard_index integer not null,
+    attempt integer not null,
+    execution_status text,
+    backend_status text,
+    return_code integer,
+    docker_image text,
+    runtime_attributes jsonb,
+    started_at timestamptz,
+    finished_at timestamptz,
+    created_at timestamptz not null default current_timestamp
+);
+
+create index on run_call(run_id);
+create index on run_call(cromwell_job_id);
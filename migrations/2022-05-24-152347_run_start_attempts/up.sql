@@ -0,0 +1,9 @@
+-- Number of attempts made at starting the test and eval workflows of a run, so transient failures
+-- can be retried a limited number of times
+create table run_start_attempt (
+    run_id uuid not null references run(run_id),
+    workflow run_call_workflow_enum not null,
+    attempts integer not null,
+    last_attempted_at timestamptz not null default current_timestamp,
+    primary key (run_id, workflow)
+);
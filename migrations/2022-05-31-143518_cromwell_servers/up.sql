@@ -0,0 +1,42 @@
+-- Optional name of the cromwell server (or pool of servers) that runs for a pipeline, template, or
+-- test should be sent to
+alter table pipeline
+    add cromwell_server text;
+
+alter table template
+    add cromwell_server text;
+
+alter table test
+    add cromwell_server text;
+
+-- Name of the cromwell server that a run, build, or run_report was submitted to, so its status can
+-- be checked on the right server.  Null for anything submitted before servers were named, which
+-- means the default server
+alter table run
+    add cromwell_server text;
+
+alter table software_build
+    add cromwell_server text;
+
+alter table run_report
+    add cromwell_server text;
+
+drop view if exists run_with_results_and_errors;
+
+create view run_with_results_and_errors as
+select run_id, test_id, name, status, test_input, test_options, eval_input,
+       eval_options, test_cromwell_job_id, eval_cromwell_job_id, created_at, created_by,
+       finished_at, cromwell_server, results, errors
+from run
+ left join
+ (
+     select run_id, jsonb_agg(to_char(created_at, 'YYYY-MM-DD HH24:MI:SS.MS') || ': ' || error) as errors
+     from run_error
+     group by run_id
+ ) as errors using (run_id)
+ left join
+ (
+     select run_id, jsonb_object_agg(name, value) as results
+     from run_result inner join result using (result_id)
+     group by run_id
+ ) as results using (run_id);
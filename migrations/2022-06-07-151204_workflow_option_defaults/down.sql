@@ -0,0 +1,5 @@
+alter table template
+    drop column if exists workflow_option_defaults;
+
+alter table pipeline
+    drop column if exists workflow_option_defaults;
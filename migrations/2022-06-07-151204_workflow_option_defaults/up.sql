@@ -0,0 +1,7 @@
+-- Optional cromwell workflow options that apply to every run for a pipeline or template, under the
+-- test_option_defaults and eval_option_defaults of its tests
+alter table pipeline
+    add workflow_option_defaults jsonb;
+
+alter table template
+    add workflow_option_defaults jsonb;
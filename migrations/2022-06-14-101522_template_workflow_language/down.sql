@@ -0,0 +1,4 @@
+alter table template
+    drop column if exists workflow_language;
+
+drop type if exists workflow_language_enum;
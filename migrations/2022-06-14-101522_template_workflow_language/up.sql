@@ -0,0 +1,6 @@
+-- The language a template's test and eval workflows are written in, so CWL workflows can be
+-- submitted and validated as CWL instead of WDL
+create type workflow_language_enum as enum('wdl', 'cwl');
+
+alter table template
+    add workflow_language workflow_language_enum not null default 'wdl';
@@ -0,0 +1,11 @@
+-- Secrets that can be referenced in test and eval inputs as secret:name.  Values are encrypted
+-- with the key from the secrets config before they're stored, so only the encrypted value (nonce,
+-- ciphertext, and tag) is kept here
+create table secret(
+    secret_id uuid primary key DEFAULT uuid_generate_v4(),
+    name text not null unique,
+    description text,
+    encrypted_value bytea not null,
+    created_at timestamptz not null default current_timestamp,
+    created_by text
+);
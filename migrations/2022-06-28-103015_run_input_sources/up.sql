@@ -0,0 +1,15 @@
+-- Records of inputs to a run that were resolved from the outputs of another run (via a
+-- run_output: or latest_successful_output: reference), so it's possible to see where those values
+-- came from
+create table run_input_source (
+    run_id uuid not null references run(run_id),
+    workflow run_call_workflow_enum not null,
+    input_key text not null,
+    reference text not null,
+    source_run_id uuid not null references run(run_id),
+    value jsonb not null,
+    created_at timestamptz not null default current_timestamp,
+    primary key (run_id, workflow, input_key)
+);
+
+create index on run_input_source(source_run_id);
@@ -0,0 +1,2 @@
+alter table run_group
+    drop column if exists notified_at;
@@ -0,0 +1,4 @@
+-- When the completion notification for a run group was sent, so it's only sent once, even if
+-- multiple runs in the group finish at the same time
+alter table run_group
+    add notified_at timestamptz;
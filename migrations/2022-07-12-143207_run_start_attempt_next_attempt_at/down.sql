@@ -0,0 +1,2 @@
+alter table run_start_attempt
+    drop column if exists next_attempt_at;
@@ -0,0 +1,4 @@
+-- When the next attempt at starting the workflow should be made, if the last attempt failed and is
+-- waiting to be retried
+alter table run_start_attempt
+    add next_attempt_at timestamptz;
@@ -2,13 +2,16 @@
 
 use crate::config::{Config, PrivateGithubAccessConfig, WdlStorageConfig};
 use crate::db::DbPool;
+use crate::manager::log_retriever::LogRetriever;
 use crate::manager::report_builder::ReportBuilder;
+use crate::manager::server_router::ServerRouter;
 use crate::manager::test_runner::TestRunner;
-use crate::requests::cromwell_requests::CromwellClient;
+use crate::manager::workflow_reconciler::WorkflowReconciler;
 use crate::requests::test_resource_requests::TestResourceClient;
 use crate::routes;
 use crate::storage::gcloud_storage::GCloudClient;
 use crate::util::git_repos::GitRepoChecker;
+use crate::util::secrets::{self, SecretEncryptor};
 use crate::util::wdl_storage::WdlStorageClient;
 use crate::validation::womtool::WomtoolRunner;
 use actix_rt::System;
@@ -40,6 +43,7 @@ pub fn run_app(
         // software and report mappings
         let enable_reporting: bool = carrot_config.reporting().is_some();
         let enable_custom_image_builds: bool = carrot_config.custom_image_build().is_some();
+        let enable_secrets: bool = carrot_config.secrets().is_some();
 
         // Set up stuff we need to include as data for some of the routes to access
         // Make a client that'll be used for http requests
@@ -51,23 +55,29 @@ pub fn run_app(
             },
             None => None
         };
-        // Create a test resource client and cromwell client for the test runner
+        // Create a test resource client and server router for the test runner
         let test_resource_client: TestResourceClient = TestResourceClient::new(http_client.clone(), gcloud_client.clone());
-        let cromwell_client: CromwellClient = CromwellClient::new(http_client, carrot_config.cromwell().address());
+        let server_router: ServerRouter = ServerRouter::new(http_client, &carrot_config);
+        // Create a secret encryptor (if secrets are enabled) for the secret routes and the test runner
+        let secret_encryptor: Option<SecretEncryptor> = secrets::get_secret_encryptor(&carrot_config);
         // Create a test runner
         let test_runner: TestRunner = match carrot_config.custom_image_build() {
             Some(image_build_config) => {
-                TestRunner::new(cromwell_client.clone(), test_resource_client.clone(), Some(image_build_config.image_registry_host()))
+                TestRunner::new(server_router.clone(), test_resource_client.clone(), Some(image_build_config.image_registry_host()), carrot_config.status_manager().max_concurrent_runs(), carrot_config.retry().clone(), carrot_config.cromwell().workflow_option_defaults().cloned(), secret_encryptor.clone())
             },
             None => {
-                TestRunner::new(cromwell_client.clone(), test_resource_client.clone(), None)
+                TestRunner::new(server_router.clone(), test_resource_client.clone(), None, carrot_config.status_manager().max_concurrent_runs(), carrot_config.retry().clone(), carrot_config.cromwell().workflow_option_defaults().cloned(), secret_encryptor.clone())
             }
         };
+        // Create a log retriever for getting logs and failures from cromwell in the run routes
+        let log_retriever: LogRetriever = LogRetriever::new(server_router.clone(), test_resource_client.clone());
+        // Create a workflow reconciler for finding orphaned cromwell workflows in the admin routes
+        let workflow_reconciler: WorkflowReconciler = WorkflowReconciler::new(server_router.clone());
         // Create report builder
         let report_builder: Option<ReportBuilder> = match carrot_config.reporting() {
             Some(reporting_config) => {
                 // We can unwrap gcloud_client because reporting won't work without it
-                Some(ReportBuilder::new(cromwell_client, gcloud_client.clone().expect("Failed to unwrap gcloud_client to create report builder.  This should not happen"), reporting_config))
+                Some(ReportBuilder::new(server_router, gcloud_client.clone().expect("Failed to unwrap gcloud_client to create report builder.  This should not happen"), reporting_config))
             },
             None => None
         };
@@ -95,19 +105,26 @@ pub fn run_app(
             }
         };
 
-        App::new()
+        let mut app = App::new()
             .wrap(Logger::default()) // Use default logger as configured in .env file
             .data(pool.clone()) // Give app access to clone of DB pool so other threads can use it
             .data(git_repo_checker) // For verifying github repos for software routes
             .data(test_runner) // For starting test runs in the run routes
+            .data(log_retriever) // For retrieving cromwell logs and failures in the run routes
             .data(report_builder) // For starting report builds in the run_report routes
-            .data(womtool_runner) // For validating wdls in the template routes
-            .data(test_resource_client) // For retrieving WDLs in the template routes
+            .data(workflow_reconciler) // For reconciling cromwell workflows in the admin routes
+            .data(womtool_runner) // For validating wdls in the template routes and inputs in the test and run routes
+            .data(test_resource_client) // For retrieving WDLs in the template and test routes
             .data(wdl_storage_client) // For storing wdls in the template routes
-            .data(carrot_config.clone())// Allow worker threads to access config variables
-            .service(web::scope("/api/v1/").configure(move |cfg: &mut web::ServiceConfig| {
-                routes_config(cfg, enable_reporting, enable_custom_image_builds)
-            })) //Get route mappings for v1 api
+            .data(carrot_config.clone()); // Allow worker threads to access config variables
+        // Only give the routes a secret encryptor if secrets are enabled, so the secret routes can
+        // encrypt values and the run routes know whether they have secrets to redact
+        if let Some(secret_encryptor) = secret_encryptor {
+            app = app.data(secret_encryptor);
+        }
+        app.service(web::scope("/api/v1/").configure(move |cfg: &mut web::ServiceConfig| {
+            routes_config(cfg, enable_reporting, enable_custom_image_builds, enable_secrets)
+        })) //Get route mappings for v1 api
     })
     .bind(format!("{}:{}", host, port))?
     .run();
@@ -128,11 +145,15 @@ pub fn routes_config(
     cfg: &mut web::ServiceConfig,
     enable_reporting: bool,
     enable_custom_image_builds: bool,
+    enable_secrets: bool,
 ) {
+    routes::admin::init_routes(cfg);
     routes::pipeline::init_routes(cfg);
     routes::template::init_routes(cfg);
     routes::test::init_routes(cfg);
+    routes::test_schedule::init_routes(cfg);
     routes::run::init_routes(cfg);
+    routes::run_group::init_routes(cfg);
     routes::result::init_routes(cfg);
     routes::template_result::init_routes(cfg);
     routes::subscription::init_routes(cfg);
@@ -142,4 +163,5 @@ pub fn routes_config(
     routes::report::init_routes(cfg, enable_reporting);
     routes::run_report::init_routes(cfg, enable_reporting);
     routes::template_report::init_routes(cfg, enable_reporting);
+    routes::secret::init_routes(cfg, enable_secrets);
 }
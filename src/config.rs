@@ -3,6 +3,7 @@
 //! from here instead of loaded again elsewhere
 
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::HashMap;
 
 /// Top level config struct that holds other area-specific configs
@@ -15,15 +16,23 @@ pub struct Config {
     // We don't need to specify a function for these defaults because we implemented Default for
     // the structs
     #[serde(default)]
+    execution_backend: ExecutionBackendConfig,
+    #[serde(default)]
     status_manager: StatusManagerConfig,
     #[serde(default)]
+    scheduler: SchedulerConfig,
+    #[serde(default)]
     wdl_storage: WdlStorageConfig,
+    #[serde(default)]
+    retry: RetryConfig,
+    leader_election: Option<LeaderElectionConfig>,
     email: Option<EmailConfig>,
     gcloud: Option<GCloudConfig>,
     github: Option<GithubConfig>,
     custom_image_build: Option<CustomImageBuildConfig>,
     validation: ValidationConfig,
     reporting: Option<ReportingConfig>,
+    secrets: Option<SecretsConfig>,
 }
 
 impl Config {
@@ -39,14 +48,19 @@ impl Config {
         api: ApiConfig,
         database: DatabaseConfig,
         cromwell: CromwellConfig,
+        execution_backend: ExecutionBackendConfig,
         status_manager: StatusManagerConfig,
+        scheduler: SchedulerConfig,
         wdl_storage: WdlStorageConfig,
+        retry: RetryConfig,
+        leader_election: Option<LeaderElectionConfig>,
         email: Option<EmailConfig>,
         gcloud: Option<GCloudConfig>,
         github: Option<GithubConfig>,
         custom_image_build: Option<CustomImageBuildConfig>,
         validation: ValidationConfig,
         reporting: Option<ReportingConfig>,
+        secrets: Option<SecretsConfig>,
     ) -> Self {
         // Create new config from params
         let new_config = Config {
@@ -54,14 +68,19 @@ impl Config {
             api,
             database,
             cromwell,
+            execution_backend,
             status_manager,
+            scheduler,
             wdl_storage,
+            retry,
+            leader_election,
             email,
             gcloud,
             github,
             custom_image_build,
             validation,
             reporting,
+            secrets,
         };
         // Validate it
         new_config.validate();
@@ -76,7 +95,8 @@ impl Config {
     /// 1. A value for `github` and None for `custom_image_build`,
     /// 2. A value for `custom_image_builds` and None for `gcloud`, or
     /// 3. A value for `reporting` and None for `gcloud`,
-    /// 4. A GCS value for `wdl_storage` and None for `gcloud`
+    /// 4. A GCS value for `wdl_storage` and None for `gcloud`, or
+    /// 5. Cromwell server names or pools that are invalid (see `CromwellConfig::validate`)
     pub fn validate(&self) {
         if self.github.is_some() && self.custom_image_build.is_none() {
             panic!("In order to enable Github integration, it is necessary to specify a configuration for \"custom_image_build\"");
@@ -90,6 +110,7 @@ impl Config {
         if self.wdl_storage.is_gcs() && self.gcloud.is_none() {
             panic!("In order to store WDLs in a GCS location, it is necessary to specify a configuration for \"gcloud\"");
         }
+        self.cromwell.validate();
     }
     pub fn logging(&self) -> &LoggingConfig {
         &self.logging
@@ -103,12 +124,24 @@ impl Config {
     pub fn cromwell(&self) -> &CromwellConfig {
         &self.cromwell
     }
+    pub fn execution_backend(&self) -> &ExecutionBackendConfig {
+        &self.execution_backend
+    }
     pub fn status_manager(&self) -> &StatusManagerConfig {
         &self.status_manager
     }
+    pub fn scheduler(&self) -> &SchedulerConfig {
+        &self.scheduler
+    }
     pub fn wdl_storage(&self) -> &WdlStorageConfig {
         &self.wdl_storage
     }
+    pub fn retry(&self) -> &RetryConfig {
+        &self.retry
+    }
+    pub fn leader_election(&self) -> Option<&LeaderElectionConfig> {
+        self.leader_election.as_ref()
+    }
     pub fn email(&self) -> Option<&EmailConfig> {
         self.email.as_ref()
     }
@@ -127,6 +160,9 @@ impl Config {
     pub fn reporting(&self) -> Option<&ReportingConfig> {
         self.reporting.as_ref()
     }
+    pub fn secrets(&self) -> Option<&SecretsConfig> {
+        self.secrets.as_ref()
+    }
 
     // For tests, we want these to be mutable so we can change them if we need to
     #[cfg(test)]
@@ -146,14 +182,30 @@ impl Config {
         self.cromwell = cromwell;
     }
     #[cfg(test)]
+    pub fn set_execution_backend(&mut self, execution_backend: ExecutionBackendConfig) {
+        self.execution_backend = execution_backend;
+    }
+    #[cfg(test)]
     pub fn set_status_manager(&mut self, status_manager: StatusManagerConfig) {
         self.status_manager = status_manager;
     }
     #[cfg(test)]
+    pub fn set_scheduler(&mut self, scheduler: SchedulerConfig) {
+        self.scheduler = scheduler;
+    }
+    #[cfg(test)]
     pub fn set_wdl_storage(&mut self, wdl_storage: WdlStorageConfig) {
         self.wdl_storage = wdl_storage;
     }
     #[cfg(test)]
+    pub fn set_retry(&mut self, retry: RetryConfig) {
+        self.retry = retry;
+    }
+    #[cfg(test)]
+    pub fn set_leader_election(&mut self, leader_election: Option<LeaderElectionConfig>) {
+        self.leader_election = leader_election;
+    }
+    #[cfg(test)]
     pub fn set_email(&mut self, email: Option<EmailConfig>) {
         self.email = email;
     }
@@ -177,6 +229,10 @@ impl Config {
     pub fn set_reporting(&mut self, reporting: Option<ReportingConfig>) {
         self.reporting = reporting;
     }
+    #[cfg(test)]
+    pub fn set_secrets(&mut self, secrets: Option<SecretsConfig>) {
+        self.secrets = secrets;
+    }
 }
 
 /// Config for setting up logging
@@ -250,17 +306,345 @@ impl DatabaseConfig {
 /// Config for dispatching jobs to Cromwell
 #[derive(Serialize, Deserialize, Clone)]
 pub struct CromwellConfig {
-    /// The address for the cromwell server that will be used to run tests
+    /// The address for the default cromwell server that will be used to run tests
     address: String,
+    /// Name identifying this CARROT instance in the labels of workflows it submits to cromwell, or
+    /// default to "carrot".  Should be unique among CARROT instances sharing a cromwell server
+    #[serde(default = "instance_name_default")]
+    instance_name: String,
+    /// Name pipelines, templates, and tests can use to refer to the server at `address`, or
+    /// default to "default"
+    #[serde(default = "default_server_name_default")]
+    default_server_name: String,
+    /// Additional named cromwell servers that pipelines, templates, and tests can choose to run on
+    #[serde(default)]
+    servers: Vec<CromwellServerConfig>,
+    /// Named groups of equivalent servers.  Runs for pipelines, templates, or tests that choose a
+    /// pool are sent to whichever server in the pool has the fewest active runs
+    #[serde(default)]
+    pools: HashMap<String, Vec<String>>,
+    /// Optional credentials for the server at `address`, for servers behind an auth proxy
+    #[serde(default)]
+    auth: Option<CromwellAuthConfig>,
+    /// Optional workflow options for the test and eval workflows of every run, which the workflow
+    /// option defaults of its pipeline, template, and test and the run's own options are merged
+    /// over
+    #[serde(default)]
+    workflow_option_defaults: Option<Value>,
+}
+
+// Functions for providing the default values
+fn instance_name_default() -> String {
+    String::from("carrot")
+}
+fn default_server_name_default() -> String {
+    String::from("default")
 }
 
 impl CromwellConfig {
-    pub fn new(address: String) -> Self {
-        CromwellConfig { address }
+    pub fn new(
+        address: String,
+        instance_name: String,
+        default_server_name: String,
+        servers: Vec<CromwellServerConfig>,
+        pools: HashMap<String, Vec<String>>,
+        auth: Option<CromwellAuthConfig>,
+        workflow_option_defaults: Option<Value>,
+    ) -> Self {
+        CromwellConfig {
+            address,
+            instance_name,
+            default_server_name,
+            servers,
+            pools,
+            auth,
+            workflow_option_defaults,
+        }
     }
     pub fn address(&self) -> &String {
         &self.address
     }
+    pub fn instance_name(&self) -> &String {
+        &self.instance_name
+    }
+    pub fn default_server_name(&self) -> &String {
+        &self.default_server_name
+    }
+    pub fn servers(&self) -> &Vec<CromwellServerConfig> {
+        &self.servers
+    }
+    pub fn pools(&self) -> &HashMap<String, Vec<String>> {
+        &self.pools
+    }
+    pub fn auth(&self) -> Option<&CromwellAuthConfig> {
+        self.auth.as_ref()
+    }
+    pub fn workflow_option_defaults(&self) -> Option<&Value> {
+        self.workflow_option_defaults.as_ref()
+    }
+    /// Validates that the server names are unique, that the pool names don't clash with the
+    /// server names, that the pools only contain servers that are configured, and that any token
+    /// commands specify a program to run.  Panics if not
+    ///
+    /// # Panics
+    /// Panics if a server name is used more than once, a pool has the same name as a server, a
+    /// pool contains a name that does not match any of the servers, or a token command is empty
+    fn validate(&self) {
+        let auths = std::iter::once((&self.default_server_name, &self.auth)).chain(
+            self.servers
+                .iter()
+                .map(|server| (&server.name, &server.auth)),
+        );
+        for (server_name, auth) in auths {
+            if let Some(CromwellAuthConfig::TokenCommand(token_command_config)) = auth {
+                if token_command_config.command.is_empty() {
+                    panic!(
+                        "The token command for the cromwell server \"{}\" is empty",
+                        server_name
+                    );
+                }
+            }
+        }
+        let mut server_names: Vec<&str> = vec![&self.default_server_name];
+        for server in &self.servers {
+            if server_names.contains(&server.name.as_str()) {
+                panic!(
+                    "The cromwell server name \"{}\" is used for more than one server",
+                    server.name
+                );
+            }
+            server_names.push(&server.name);
+        }
+        for (pool_name, pool_servers) in &self.pools {
+            if server_names.contains(&pool_name.as_str()) {
+                panic!(
+                    "The cromwell server pool name \"{}\" is also used for a server",
+                    pool_name
+                );
+            }
+            for server_name in pool_servers {
+                if !server_names.contains(&server_name.as_str()) {
+                    panic!(
+                        "The cromwell server pool \"{}\" contains \"{}\", which is not the name of a configured server",
+                        pool_name, server_name
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Config for an additional named cromwell server
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CromwellServerConfig {
+    /// The name pipelines, templates, and tests can use to choose this server
+    name: String,
+    /// The address for the cromwell server
+    address: String,
+    /// Optional credentials for the cromwell server, for servers behind an auth proxy
+    #[serde(default)]
+    auth: Option<CromwellAuthConfig>,
+}
+
+impl CromwellServerConfig {
+    pub fn new(name: String, address: String, auth: Option<CromwellAuthConfig>) -> Self {
+        CromwellServerConfig {
+            name,
+            address,
+            auth,
+        }
+    }
+    pub fn name(&self) -> &String {
+        &self.name
+    }
+    pub fn address(&self) -> &String {
+        &self.address
+    }
+    pub fn auth(&self) -> Option<&CromwellAuthConfig> {
+        self.auth.as_ref()
+    }
+}
+
+/// Config for the credentials to send with requests to a cromwell server
+#[derive(Serialize, Deserialize, Clone)]
+pub enum CromwellAuthConfig {
+    /// Mode for sending a static bearer token
+    #[serde(rename = "bearer")]
+    Bearer(CromwellBearerAuthConfig),
+    /// Mode for sending a username and password with basic auth
+    #[serde(rename = "basic")]
+    Basic(CromwellBasicAuthConfig),
+    /// Mode for sending a bearer token read from a file
+    #[serde(rename = "token_file")]
+    TokenFile(CromwellTokenFileAuthConfig),
+    /// Mode for sending a bearer token printed by a command
+    #[serde(rename = "token_command")]
+    TokenCommand(CromwellTokenCommandAuthConfig),
+}
+
+/// Config for authenticating to cromwell with a static bearer token
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CromwellBearerAuthConfig {
+    /// The token to send in the Authorization header
+    token: String,
+}
+
+impl CromwellBearerAuthConfig {
+    pub fn new(token: String) -> Self {
+        CromwellBearerAuthConfig { token }
+    }
+    pub fn token(&self) -> &String {
+        &self.token
+    }
+}
+
+/// Config for authenticating to cromwell with basic auth
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CromwellBasicAuthConfig {
+    username: String,
+    password: String,
+}
+
+impl CromwellBasicAuthConfig {
+    pub fn new(username: String, password: String) -> Self {
+        CromwellBasicAuthConfig { username, password }
+    }
+    pub fn username(&self) -> &String {
+        &self.username
+    }
+    pub fn password(&self) -> &String {
+        &self.password
+    }
+}
+
+/// Config for authenticating to cromwell with a bearer token read from a file (e.g. one kept
+/// up to date by a sidecar)
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CromwellTokenFileAuthConfig {
+    /// The location of the file containing the token
+    path: String,
+    /// Optional time, in seconds, after which the token is read from the file again.  The token is
+    /// also read again if cromwell rejects it.  If not specified, the token is only read again
+    /// when cromwell rejects it
+    refresh_interval_in_secs: Option<u64>,
+}
+
+impl CromwellTokenFileAuthConfig {
+    pub fn new(path: String, refresh_interval_in_secs: Option<u64>) -> Self {
+        CromwellTokenFileAuthConfig {
+            path,
+            refresh_interval_in_secs,
+        }
+    }
+    pub fn path(&self) -> &String {
+        &self.path
+    }
+    pub fn refresh_interval_in_secs(&self) -> Option<u64> {
+        self.refresh_interval_in_secs
+    }
+}
+
+/// Config for authenticating to cromwell with a bearer token printed to stdout by a command (e.g.
+/// `gcloud auth print-access-token`)
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CromwellTokenCommandAuthConfig {
+    /// The command to run, as the program followed by its arguments
+    command: Vec<String>,
+    /// Optional time, in seconds, after which the command is run again to get a new token.  The
+    /// command is also run again if cromwell rejects the token.  If not specified, it is only run
+    /// again when cromwell rejects the token
+    refresh_interval_in_secs: Option<u64>,
+    /// Time, in seconds, to let the command run before killing it and failing the request, or
+    /// default to 30 seconds
+    #[serde(default = "token_command_timeout_in_secs_default")]
+    timeout_in_secs: u64,
+}
+
+fn token_command_timeout_in_secs_default() -> u64 {
+    30
+}
+
+impl CromwellTokenCommandAuthConfig {
+    pub fn new(
+        command: Vec<String>,
+        refresh_interval_in_secs: Option<u64>,
+        timeout_in_secs: u64,
+    ) -> Self {
+        CromwellTokenCommandAuthConfig {
+            command,
+            refresh_interval_in_secs,
+            timeout_in_secs,
+        }
+    }
+    pub fn command(&self) -> &Vec<String> {
+        &self.command
+    }
+    pub fn refresh_interval_in_secs(&self) -> Option<u64> {
+        self.refresh_interval_in_secs
+    }
+    pub fn timeout_in_secs(&self) -> u64 {
+        self.timeout_in_secs
+    }
+}
+
+/// Config for which api CARROT uses to run workflows
+#[derive(Serialize, Deserialize, Clone)]
+pub enum ExecutionBackendConfig {
+    /// Mode for running workflows through cromwell's native api, at the address in the cromwell
+    /// config
+    #[serde(rename = "cromwell")]
+    Cromwell,
+    /// Mode for running workflows through a GA4GH WES api
+    #[serde(rename = "wes")]
+    Wes(WesConfig),
+}
+
+impl ExecutionBackendConfig {
+    pub fn is_wes(&self) -> bool {
+        matches!(self, ExecutionBackendConfig::Wes(_))
+    }
+    pub fn as_wes(&self) -> Option<&WesConfig> {
+        match self {
+            ExecutionBackendConfig::Wes(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl Default for ExecutionBackendConfig {
+    fn default() -> Self {
+        ExecutionBackendConfig::Cromwell
+    }
+}
+
+/// Config for running workflows through a GA4GH WES api
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WesConfig {
+    /// The address for the WES server (everything before /ga4gh/wes/v1)
+    address: String,
+    /// The version of WDL to report to the WES server when submitting workflows, or default to 1.0
+    #[serde(default = "workflow_type_version_default")]
+    workflow_type_version: String,
+}
+
+// Function for providing the default value
+fn workflow_type_version_default() -> String {
+    String::from("1.0")
+}
+
+impl WesConfig {
+    pub fn new(address: String, workflow_type_version: String) -> Self {
+        WesConfig {
+            address,
+            workflow_type_version,
+        }
+    }
+    pub fn address(&self) -> &String {
+        &self.address
+    }
+    pub fn workflow_type_version(&self) -> &String {
+        &self.workflow_type_version
+    }
 }
 
 /// Config for the status manager
@@ -272,6 +656,32 @@ pub struct StatusManagerConfig {
     /// Number of consecutive status check failures to allow before panicking, or default to 5
     #[serde(default = "allowed_consecutive_status_check_failures_default")]
     allowed_consecutive_status_check_failures: u32,
+    /// Maximum number of runs that can be active at once across all tests, or no limit if not
+    /// specified.  Runs created while at the limit are queued until a slot frees up
+    #[serde(default)]
+    max_concurrent_runs: Option<u32>,
+    /// If true, queued runs are started in order of the priority of their tests (highest first),
+    /// and then in the order they were created.  Otherwise, they are started strictly in the order
+    /// they were created.  Defaults to false
+    #[serde(default)]
+    prioritize_queued_runs: bool,
+    /// Maximum time, in seconds, that a run's test or eval workflow can run in cromwell before it
+    /// is aborted, for tests that do not specify their own max_runtime_in_secs.  No limit if not
+    /// specified
+    #[serde(default)]
+    default_max_runtime_in_secs: Option<u64>,
+    /// Maximum number of runs, builds, or reports whose statuses are checked at the same time, or
+    /// default to 10.  Each check uses its own DB connection, so this is capped at the number of
+    /// DB threads
+    #[serde(default = "max_concurrent_status_checks_default")]
+    max_concurrent_status_checks: u32,
+    /// Time, in seconds, after which a run or software build that is still `Created` is considered
+    /// stranded (e.g. because CARROT stopped while it was being started), or default to 10 minutes
+    #[serde(default = "created_recovery_threshold_in_secs_default")]
+    created_recovery_threshold_in_secs: u64,
+    /// What to do with stranded `Created` runs and software builds, or default to `fail`
+    #[serde(default)]
+    created_recovery_policy: CreatedRecoveryPolicy,
 }
 
 // Functions for providing the default values
@@ -281,6 +691,12 @@ fn status_check_wait_time_in_secs_default() -> u64 {
 fn allowed_consecutive_status_check_failures_default() -> u32 {
     5
 }
+fn max_concurrent_status_checks_default() -> u32 {
+    10
+}
+fn created_recovery_threshold_in_secs_default() -> u64 {
+    600
+}
 
 impl Default for StatusManagerConfig {
     fn default() -> Self {
@@ -288,6 +704,12 @@ impl Default for StatusManagerConfig {
             status_check_wait_time_in_secs: status_check_wait_time_in_secs_default(),
             allowed_consecutive_status_check_failures:
                 allowed_consecutive_status_check_failures_default(),
+            max_concurrent_runs: None,
+            prioritize_queued_runs: false,
+            default_max_runtime_in_secs: None,
+            max_concurrent_status_checks: max_concurrent_status_checks_default(),
+            created_recovery_threshold_in_secs: created_recovery_threshold_in_secs_default(),
+            created_recovery_policy: CreatedRecoveryPolicy::default(),
         }
     }
 }
@@ -296,10 +718,22 @@ impl StatusManagerConfig {
     pub fn new(
         status_check_wait_time_in_secs: u64,
         allowed_consecutive_status_check_failures: u32,
+        max_concurrent_runs: Option<u32>,
+        prioritize_queued_runs: bool,
+        default_max_runtime_in_secs: Option<u64>,
+        max_concurrent_status_checks: u32,
+        created_recovery_threshold_in_secs: u64,
+        created_recovery_policy: CreatedRecoveryPolicy,
     ) -> Self {
         StatusManagerConfig {
             status_check_wait_time_in_secs,
             allowed_consecutive_status_check_failures,
+            max_concurrent_runs,
+            prioritize_queued_runs,
+            default_max_runtime_in_secs,
+            max_concurrent_status_checks,
+            created_recovery_threshold_in_secs,
+            created_recovery_policy,
         }
     }
     pub fn status_check_wait_time_in_secs(&self) -> u64 {
@@ -308,6 +742,339 @@ impl StatusManagerConfig {
     pub fn allowed_consecutive_status_check_failures(&self) -> u32 {
         self.allowed_consecutive_status_check_failures
     }
+    pub fn max_concurrent_runs(&self) -> Option<u32> {
+        self.max_concurrent_runs
+    }
+    pub fn prioritize_queued_runs(&self) -> bool {
+        self.prioritize_queued_runs
+    }
+    pub fn default_max_runtime_in_secs(&self) -> Option<u64> {
+        self.default_max_runtime_in_secs
+    }
+    pub fn max_concurrent_status_checks(&self) -> u32 {
+        self.max_concurrent_status_checks
+    }
+    pub fn created_recovery_threshold_in_secs(&self) -> u64 {
+        self.created_recovery_threshold_in_secs
+    }
+    pub fn created_recovery_policy(&self) -> CreatedRecoveryPolicy {
+        self.created_recovery_policy
+    }
+}
+
+/// Policies for handling runs and software builds that were stranded in `Created` (e.g. because
+/// CARROT stopped after creating them but before submitting them to cromwell)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum CreatedRecoveryPolicy {
+    /// Start stranded runs and builds again, unless a workflow was already submitted to cromwell
+    /// for a stranded run, in which case the run is updated to track it instead
+    #[serde(rename = "resubmit")]
+    Resubmit,
+    /// Mark stranded runs as `CarrotFailed` (with a run_error explaining why) and stranded builds
+    /// as `Failed`
+    #[serde(rename = "fail")]
+    Fail,
+}
+
+impl Default for CreatedRecoveryPolicy {
+    fn default() -> Self {
+        CreatedRecoveryPolicy::Fail
+    }
+}
+
+/// Config for the run scheduler
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SchedulerConfig {
+    /// Time to wait between checks for scheduled runs that are due, or default to 1 minute
+    #[serde(default = "schedule_check_wait_time_in_secs_default")]
+    schedule_check_wait_time_in_secs: u64,
+    /// What to do about scheduled runs that were missed (e.g. because CARROT was not running when
+    /// they came due), or default to `run_once`
+    #[serde(default)]
+    catch_up_policy: ScheduleCatchUpPolicy,
+    /// The maximum number of missed runs to start for a single schedule if `catch_up_policy` is
+    /// `run_all`, or default to 10
+    #[serde(default = "max_catch_up_runs_default")]
+    max_catch_up_runs: u32,
+}
+
+// Functions for providing the default values
+fn schedule_check_wait_time_in_secs_default() -> u64 {
+    60
+}
+fn max_catch_up_runs_default() -> u32 {
+    10
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        SchedulerConfig {
+            schedule_check_wait_time_in_secs: schedule_check_wait_time_in_secs_default(),
+            catch_up_policy: ScheduleCatchUpPolicy::default(),
+            max_catch_up_runs: max_catch_up_runs_default(),
+        }
+    }
+}
+
+impl SchedulerConfig {
+    pub fn new(
+        schedule_check_wait_time_in_secs: u64,
+        catch_up_policy: ScheduleCatchUpPolicy,
+        max_catch_up_runs: u32,
+    ) -> Self {
+        SchedulerConfig {
+            schedule_check_wait_time_in_secs,
+            catch_up_policy,
+            max_catch_up_runs,
+        }
+    }
+    pub fn schedule_check_wait_time_in_secs(&self) -> u64 {
+        self.schedule_check_wait_time_in_secs
+    }
+    pub fn catch_up_policy(&self) -> ScheduleCatchUpPolicy {
+        self.catch_up_policy
+    }
+    pub fn max_catch_up_runs(&self) -> u32 {
+        self.max_catch_up_runs
+    }
+}
+
+/// Config for electing a leader among multiple CARROT instances that share a database, so that only
+/// the leader runs the status manager, run scheduler, and gcloud subscriber
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LeaderElectionConfig {
+    /// Key for the Postgres advisory lock held by the leader, or default to 5460172.  Only needs
+    /// to be changed if something else using the database takes an advisory lock with the same key
+    #[serde(default = "lock_key_default")]
+    lock_key: i64,
+    /// Time to wait between attempts to become the leader (or, for the leader, between checks that
+    /// it still holds the lock), or default to 10 seconds
+    #[serde(default = "leader_check_wait_time_in_secs_default")]
+    check_wait_time_in_secs: u64,
+    /// Time the DB server will wait without hearing from the leader on its lock connection before
+    /// it starts checking that the leader is still there (the Postgres `tcp_keepalives_idle`
+    /// setting), or default to `check_wait_time_in_secs`
+    keepalive_idle_in_secs: Option<u64>,
+    /// Time between those checks (the Postgres `tcp_keepalives_interval` setting), or default to
+    /// `check_wait_time_in_secs`
+    keepalive_interval_in_secs: Option<u64>,
+    /// Number of those checks that can go unanswered before the DB server closes the connection and
+    /// releases the lock (the Postgres `tcp_keepalives_count` setting), or default to 3
+    #[serde(default = "keepalive_count_default")]
+    keepalive_count: u32,
+    /// Maximum time a query on the lock connection is allowed to take (the Postgres
+    /// `statement_timeout` setting), or default to `check_wait_time_in_secs`
+    statement_timeout_in_secs: Option<u64>,
+}
+
+// Functions for providing the default values
+fn lock_key_default() -> i64 {
+    5460172
+}
+fn leader_check_wait_time_in_secs_default() -> u64 {
+    10
+}
+fn keepalive_count_default() -> u32 {
+    3
+}
+
+impl Default for LeaderElectionConfig {
+    fn default() -> Self {
+        LeaderElectionConfig {
+            lock_key: lock_key_default(),
+            check_wait_time_in_secs: leader_check_wait_time_in_secs_default(),
+            keepalive_idle_in_secs: None,
+            keepalive_interval_in_secs: None,
+            keepalive_count: keepalive_count_default(),
+            statement_timeout_in_secs: None,
+        }
+    }
+}
+
+impl LeaderElectionConfig {
+    pub fn new(lock_key: i64, check_wait_time_in_secs: u64) -> Self {
+        LeaderElectionConfig {
+            lock_key,
+            check_wait_time_in_secs,
+            keepalive_idle_in_secs: None,
+            keepalive_interval_in_secs: None,
+            keepalive_count: keepalive_count_default(),
+            statement_timeout_in_secs: None,
+        }
+    }
+    pub fn lock_key(&self) -> i64 {
+        self.lock_key
+    }
+    pub fn check_wait_time_in_secs(&self) -> u64 {
+        self.check_wait_time_in_secs
+    }
+    pub fn keepalive_idle_in_secs(&self) -> u64 {
+        self.keepalive_idle_in_secs
+            .unwrap_or(self.check_wait_time_in_secs)
+    }
+    pub fn keepalive_interval_in_secs(&self) -> u64 {
+        self.keepalive_interval_in_secs
+            .unwrap_or(self.check_wait_time_in_secs)
+    }
+    pub fn keepalive_count(&self) -> u32 {
+        self.keepalive_count
+    }
+    pub fn statement_timeout_in_secs(&self) -> u64 {
+        self.statement_timeout_in_secs
+            .unwrap_or(self.check_wait_time_in_secs)
+    }
+    /// Returns roughly the longest it will take for another instance to take over if the leader
+    /// becomes unreachable (e.g. its host goes down), which is the time it takes the DB server to
+    /// give up on the leader's connection plus the time until another instance next tries for the
+    /// lock
+    pub fn failover_window_in_secs(&self) -> u64 {
+        self.keepalive_idle_in_secs()
+            + self.keepalive_interval_in_secs() * u64::from(self.keepalive_count)
+            + self.check_wait_time_in_secs
+    }
+}
+
+/// Config for retrying failed attempts to start the test or eval workflow for a run, so transient
+/// problems communicating with cromwell (or retrieving WDLs) don't fail the run outright
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RetryConfig {
+    /// Maximum number of attempts to make at starting each workflow for a run before marking the
+    /// run as failed, or default to 1 (i.e. no retries)
+    #[serde(default = "max_attempts_default")]
+    max_attempts: u32,
+    /// Time to wait before the first retry, or default to 5 seconds
+    #[serde(default = "initial_backoff_in_secs_default")]
+    initial_backoff_in_secs: u64,
+    /// Factor to multiply the wait time by for each subsequent retry, or default to 2
+    #[serde(default = "backoff_multiplier_default")]
+    backoff_multiplier: u32,
+    /// Maximum time to wait before a retry, or default to 1 minute
+    #[serde(default = "max_backoff_in_secs_default")]
+    max_backoff_in_secs: u64,
+    /// Types of errors that should be retried, or default to cromwell request, payload, and failure
+    /// errors and errors retrieving WDLs
+    #[serde(default = "retriable_errors_default")]
+    retriable_errors: Vec<RetriableError>,
+}
+
+// Functions for providing the default values
+fn max_attempts_default() -> u32 {
+    1
+}
+fn initial_backoff_in_secs_default() -> u64 {
+    5
+}
+fn backoff_multiplier_default() -> u32 {
+    2
+}
+fn max_backoff_in_secs_default() -> u64 {
+    60
+}
+fn retriable_errors_default() -> Vec<RetriableError> {
+    vec![
+        RetriableError::CromwellRequest,
+        RetriableError::CromwellPayload,
+        RetriableError::CromwellFailed,
+        RetriableError::ResourceRequest,
+    ]
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            max_attempts: max_attempts_default(),
+            initial_backoff_in_secs: initial_backoff_in_secs_default(),
+            backoff_multiplier: backoff_multiplier_default(),
+            max_backoff_in_secs: max_backoff_in_secs_default(),
+            retriable_errors: retriable_errors_default(),
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn new(
+        max_attempts: u32,
+        initial_backoff_in_secs: u64,
+        backoff_multiplier: u32,
+        max_backoff_in_secs: u64,
+        retriable_errors: Vec<RetriableError>,
+    ) -> Self {
+        RetryConfig {
+            max_attempts,
+            initial_backoff_in_secs,
+            backoff_multiplier,
+            max_backoff_in_secs,
+            retriable_errors,
+        }
+    }
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+    pub fn initial_backoff_in_secs(&self) -> u64 {
+        self.initial_backoff_in_secs
+    }
+    pub fn backoff_multiplier(&self) -> u32 {
+        self.backoff_multiplier
+    }
+    pub fn max_backoff_in_secs(&self) -> u64 {
+        self.max_backoff_in_secs
+    }
+    pub fn retriable_errors(&self) -> &Vec<RetriableError> {
+        &self.retriable_errors
+    }
+    /// Returns the time to wait before retrying after failed attempt number `attempt` (starting
+    /// from 1), which grows by `backoff_multiplier` with each attempt up to `max_backoff_in_secs`
+    pub fn backoff_in_secs(&self, attempt: u32) -> u64 {
+        let multiplier =
+            u64::from(self.backoff_multiplier).saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff_in_secs
+            .saturating_mul(multiplier)
+            .min(self.max_backoff_in_secs)
+    }
+}
+
+/// Types of errors encountered while starting a run that can be configured as retriable
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum RetriableError {
+    /// Failures to send a request to cromwell (e.g. connection errors and timeouts)
+    #[serde(rename = "cromwell_request")]
+    CromwellRequest,
+    /// Failures reading the body of a response from cromwell
+    #[serde(rename = "cromwell_payload")]
+    CromwellPayload,
+    /// Error responses from cromwell with a 5xx status (e.g. a 503).  Other error responses mean
+    /// the request itself was rejected, so they are never retried
+    #[serde(rename = "cromwell_failed")]
+    CromwellFailed,
+    /// Failures writing or reading the files submitted to cromwell
+    #[serde(rename = "cromwell_io")]
+    CromwellIo,
+    /// Failures retrieving WDLs or their dependencies
+    #[serde(rename = "resource_request")]
+    ResourceRequest,
+    /// Failures querying or updating the database
+    #[serde(rename = "db")]
+    DB,
+}
+
+/// Policies for handling scheduled runs that came due while they could not be started
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ScheduleCatchUpPolicy {
+    /// Don't start missed runs; only start a run if it came due since the last check
+    #[serde(rename = "skip")]
+    Skip,
+    /// Start one run in place of all the missed runs for a schedule
+    #[serde(rename = "run_once")]
+    RunOnce,
+    /// Start each of the missed runs for a schedule (up to `max_catch_up_runs`)
+    #[serde(rename = "run_all")]
+    RunAll,
+}
+
+impl Default for ScheduleCatchUpPolicy {
+    fn default() -> Self {
+        ScheduleCatchUpPolicy::RunOnce
+    }
 }
 
 /// Config for sending email notifications
@@ -708,3 +1475,19 @@ impl ReportingConfig {
         &self.report_docker_location
     }
 }
+
+/// Config for storing secrets that can be referenced in test and eval inputs
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SecretsConfig {
+    /// Base64-encoded 256-bit key used to encrypt secret values before they're stored in the DB
+    encryption_key: String,
+}
+
+impl SecretsConfig {
+    pub fn new(encryption_key: String) -> Self {
+        SecretsConfig { encryption_key }
+    }
+    pub fn encryption_key(&self) -> &String {
+        &self.encryption_key
+    }
+}
@@ -26,6 +26,7 @@ pub enum RunStatusEnum {
     EvalStarting,
     EvalSubmitted,
     EvalWaitingForQueueSpace,
+    Queued,
     Succeeded,
     TestAborted,
     TestAborting,
@@ -52,6 +53,7 @@ impl fmt::Display for RunStatusEnum {
             RunStatusEnum::EvalStarting => write!(f, "eval_starting"),
             RunStatusEnum::EvalSubmitted => write!(f, "eval_submitted"),
             RunStatusEnum::EvalWaitingForQueueSpace => write!(f, "eval_waiting_for_queue_space"),
+            RunStatusEnum::Queued => write!(f, "queued"),
             RunStatusEnum::Succeeded => write!(f, "succeeded"),
             RunStatusEnum::TestAborted => write!(f, "test_aborted"),
             RunStatusEnum::TestAborting => write!(f, "test_aborting"),
@@ -161,15 +163,88 @@ pub enum ResultTypeEnum {
     Text,
 }
 
+impl fmt::Display for ResultTypeEnum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ResultTypeEnum::Numeric => write!(f, "numeric"),
+            ResultTypeEnum::File => write!(f, "file"),
+            ResultTypeEnum::Text => write!(f, "text"),
+        }
+    }
+}
+
 /// Maps to the custom type `entity_type_enum` in the DB
 ///
 /// Represents th enum used in the DB for representing a type of entity to which a user can
 /// subscribe
 #[derive(Debug, PartialEq, DbEnum, Serialize, Deserialize, Clone, Copy)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 #[DieselType = "Entity_type_enum"]
 pub enum EntityTypeEnum {
     Pipeline,
+    RunGroup,
     Template,
     Test,
 }
+
+/// Maps to the custom type `rerun_mode_enum` in the DB
+///
+/// Represents the enum used in the DB for storing how a run was rerun from another run: either in
+/// full or by re-executing only its eval step
+#[derive(Debug, PartialEq, DbEnum, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+#[DieselType = "Rerun_mode_enum"]
+pub enum RerunModeEnum {
+    Full,
+    EvalOnly,
+}
+
+impl fmt::Display for RerunModeEnum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RerunModeEnum::Full => write!(f, "full"),
+            RerunModeEnum::EvalOnly => write!(f, "eval_only"),
+        }
+    }
+}
+
+/// Maps to the custom type `run_call_workflow_enum` in the DB
+///
+/// Represents the enum used in the DB for storing which of a run's workflows a call belongs to
+#[derive(Debug, PartialEq, DbEnum, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+#[DieselType = "Run_call_workflow_enum"]
+pub enum RunCallWorkflowEnum {
+    Test,
+    Eval,
+}
+
+impl fmt::Display for RunCallWorkflowEnum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunCallWorkflowEnum::Test => write!(f, "test"),
+            RunCallWorkflowEnum::Eval => write!(f, "eval"),
+        }
+    }
+}
+
+/// Maps to the custom type `workflow_language_enum` in the DB
+///
+/// Represents the enum used in the DB for storing the language a template's workflows are written
+/// in
+#[derive(Debug, PartialEq, DbEnum, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[DieselType = "Workflow_language_enum"]
+pub enum WorkflowLanguageEnum {
+    Wdl,
+    Cwl,
+}
+
+impl fmt::Display for WorkflowLanguageEnum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkflowLanguageEnum::Wdl => write!(f, "wdl"),
+            WorkflowLanguageEnum::Cwl => write!(f, "cwl"),
+        }
+    }
+}
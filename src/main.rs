@@ -29,6 +29,8 @@ extern crate regex;
 extern crate threadpool;
 
 use crate::manager::gcloud_subscriber;
+use crate::manager::leader_elector::LeaderElector;
+use crate::manager::run_scheduler;
 use crate::manager::status_manager;
 use actix_rt::System;
 use futures::executor::block_on;
@@ -41,12 +43,24 @@ use std::thread::JoinHandle;
 
 embed_migrations!("migrations");
 
+/// Sets its flag when it is dropped, so a thread can hold one to mark that it has exited, whether
+/// it returned or panicked
+struct ExitFlag(Arc<AtomicBool>);
+
+impl Drop for ExitFlag {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
 /// Creates a status manager and starts it running in its own thread.  Uses `db_pool` for database
-/// connections and `carrot_config` for configuring the status manager and related entities.
-/// Returns a sender for sending a terminate message, and a join handle for joining to the thread
+/// connections and `carrot_config` for configuring the status manager and related entities.  Sets
+/// `exited` when the thread exits.  Returns a sender for sending a terminate message, and a join
+/// handle for joining to the thread
 pub fn run_status_manager(
     db_pool: db::DbPool,
     carrot_config: config::Config,
+    exited: Arc<AtomicBool>,
 ) -> (
     mpsc::Sender<()>,
     JoinHandle<Result<(), status_manager::StatusManagerError>>,
@@ -57,6 +71,7 @@ pub fn run_status_manager(
     let status_manager_thread = thread::Builder::new()
         .name(String::from("Status Manager Thread"))
         .spawn(move || {
+            let _exit_flag = ExitFlag(exited);
             let mut status_manager_system = System::new("StatusManagerSystem");
             status_manager_system.block_on(status_manager::init_and_run(
                 db_pool,
@@ -70,11 +85,13 @@ pub fn run_status_manager(
 }
 
 /// Creates a gcloud subscriber and starts it running in its own thread.  Uses `db_pool` for database
-/// connections and `carrot_config` for configuring the status manager and related entities.
-/// Returns a sender for sending a terminate message, and a join handle for joining to the thread
+/// connections and `carrot_config` for configuring the status manager and related entities.  Sets
+/// `exited` when the thread exits.  Returns a sender for sending a terminate message, and a join
+/// handle for joining to the thread
 pub fn run_gcloud_subscriber(
     db_pool: db::DbPool,
     carrot_config: config::Config,
+    exited: Arc<AtomicBool>,
 ) -> (mpsc::Sender<()>, JoinHandle<()>) {
     // Create channel for sending terminate signal to status manager thread
     let (subscriber_send, subscriber_receive) = mpsc::channel();
@@ -82,6 +99,7 @@ pub fn run_gcloud_subscriber(
     let gcloud_subscriber_thread = thread::Builder::new()
         .name(String::from("GCloud Subscriber Thread"))
         .spawn(move || {
+            let _exit_flag = ExitFlag(exited);
             let mut gcloud_subscriber_system = System::new("GCloudSubscriberSystem");
             gcloud_subscriber_system.block_on(gcloud_subscriber::init_and_run(
                 db_pool,
@@ -94,6 +112,162 @@ pub fn run_gcloud_subscriber(
     (subscriber_send, gcloud_subscriber_thread)
 }
 
+/// Creates a run scheduler and starts it running in its own thread.  Uses `db_pool` for database
+/// connections and `carrot_config` for configuring the run scheduler and the test runner it uses.
+/// Sets `exited` when the thread exits.  Returns a sender for sending a terminate message, and a
+/// join handle for joining to the thread
+pub fn run_run_scheduler(
+    db_pool: db::DbPool,
+    carrot_config: config::Config,
+    exited: Arc<AtomicBool>,
+) -> (mpsc::Sender<()>, JoinHandle<()>) {
+    // Create channel for sending terminate signal to run scheduler thread
+    let (scheduler_send, scheduler_receive) = mpsc::channel();
+    info!("Starting run scheduler thread");
+    let run_scheduler_thread = thread::Builder::new()
+        .name(String::from("Run Scheduler Thread"))
+        .spawn(move || {
+            let _exit_flag = ExitFlag(exited);
+            let mut run_scheduler_system = System::new("RunSchedulerSystem");
+            run_scheduler_system.block_on(run_scheduler::init_and_run(
+                db_pool,
+                carrot_config,
+                scheduler_receive,
+            ))
+        })
+        .expect("Failed to spawn run scheduler thread");
+
+    (scheduler_send, run_scheduler_thread)
+}
+
+/// Senders for sending terminate messages to, and join handles for joining to, the threads for the
+/// background managers (the status manager, run scheduler, and gcloud subscriber), and a flag that
+/// is set when any of them exits
+pub struct BackgroundManagers {
+    exited: Arc<AtomicBool>,
+    manager_send: mpsc::Sender<()>,
+    manager_thread: JoinHandle<Result<(), status_manager::StatusManagerError>>,
+    scheduler_send: mpsc::Sender<()>,
+    scheduler_thread: JoinHandle<()>,
+    gcloud_subscriber_send: Option<mpsc::Sender<()>>,
+    gcloud_subscriber_thread: Option<JoinHandle<()>>,
+}
+
+/// Starts the status manager and run scheduler, and the gcloud subscriber if configured to use it,
+/// each in its own thread.  Uses `db_pool` for database connections and `carrot_config` for
+/// configuring them.  Returns the senders and join handles for their threads
+pub fn start_background_managers(
+    db_pool: db::DbPool,
+    carrot_config: config::Config,
+) -> BackgroundManagers {
+    // Create flag for the threads to mark when they exit
+    let exited = Arc::new(AtomicBool::new(false));
+
+    // Start status manager in its own thread, and get sender for sending terminate signal and join
+    // handle for joining to it
+    let (manager_send, manager_thread): (
+        mpsc::Sender<()>,
+        JoinHandle<Result<(), status_manager::StatusManagerError>>,
+    ) = run_status_manager(db_pool.clone(), carrot_config.clone(), exited.clone());
+
+    // Do the same for the run scheduler
+    let (scheduler_send, scheduler_thread): (mpsc::Sender<()>, JoinHandle<()>) =
+        run_run_scheduler(db_pool.clone(), carrot_config.clone(), exited.clone());
+
+    // Do the same for the gcloud subscriber thread if configured to use it
+    let (gcloud_subscriber_send, gcloud_subscriber_thread): (
+        Option<mpsc::Sender<()>>,
+        Option<JoinHandle<()>>,
+    ) = match carrot_config.github() {
+        Some(_) => {
+            let (sender, join_handle) =
+                run_gcloud_subscriber(db_pool, carrot_config, exited.clone());
+            (Some(sender), Some(join_handle))
+        }
+        None => (None, None),
+    };
+
+    BackgroundManagers {
+        exited,
+        manager_send,
+        manager_thread,
+        scheduler_send,
+        scheduler_thread,
+        gcloud_subscriber_send,
+        gcloud_subscriber_thread,
+    }
+}
+
+/// Returns true if none of the threads in `managers` have exited
+pub fn background_managers_running(managers: &BackgroundManagers) -> bool {
+    !managers.exited.load(Ordering::SeqCst)
+}
+
+/// Sends terminate messages to the threads in `managers` and waits for them to finish.  Threads
+/// that have already exited (e.g. because of an error) are logged instead of causing a panic, so
+/// the rest can still be stopped
+pub fn stop_background_managers(managers: BackgroundManagers) {
+    // Sending fails if the thread has already exited, in which case there's nothing to stop
+    if managers.manager_send.send(()).is_err() {
+        error!("Status manager thread exited before it was sent a terminate message");
+    }
+    if managers.scheduler_send.send(()).is_err() {
+        error!("Run scheduler thread exited before it was sent a terminate message");
+    }
+    if let Some(sender) = managers.gcloud_subscriber_send {
+        if sender.send(()).is_err() {
+            error!("GCloud subscriber thread exited before it was sent a terminate message");
+        }
+    }
+
+    match managers.manager_thread.join() {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => error!("Status manager thread exited with an error: {}", e),
+        Err(_) => error!("Status manager thread panicked"),
+    }
+    if managers.scheduler_thread.join().is_err() {
+        error!("Run scheduler thread panicked");
+    }
+    if let Some(thread) = managers.gcloud_subscriber_thread {
+        if thread.join().is_err() {
+            error!("GCloud subscriber thread panicked");
+        }
+    }
+}
+
+/// Creates a leader elector and starts it running in its own thread.  The elector will start the
+/// background managers (using `db_pool` and `carrot_config`) if and when this instance becomes the
+/// leader, and stop them if it stops being the leader.  Returns a sender for sending a terminate
+/// message, and a join handle for joining to the thread
+pub fn run_leader_elector(
+    db_pool: db::DbPool,
+    carrot_config: config::Config,
+) -> (mpsc::Sender<()>, JoinHandle<()>) {
+    // Create channel for sending terminate signal to leader elector thread
+    let (elector_send, elector_receive) = mpsc::channel();
+    info!("Starting leader elector thread");
+    let leader_elector_thread = thread::Builder::new()
+        .name(String::from("Leader Elector Thread"))
+        .spawn(move || {
+            let mut leader_elector = LeaderElector::new(
+                carrot_config.database().url(),
+                carrot_config
+                    .leader_election()
+                    .expect("Failed to get leader election config.  This should not happen.")
+                    .clone(),
+                elector_receive,
+            );
+            leader_elector.run(
+                || start_background_managers(db_pool.clone(), carrot_config.clone()),
+                stop_background_managers,
+                background_managers_running,
+            );
+        })
+        .expect("Failed to spawn leader elector thread");
+
+    (elector_send, leader_elector_thread)
+}
+
 fn main() {
     // Initialize the command line config
     let cli_app: clap::App = cli::configure();
@@ -147,23 +321,25 @@ fn main() {
         panic!();
     }
 
-    // Start status manager in its own thread, and get sender for sending terminate signal and join
-    // handle for joining to it
-    let (manager_send, manager_thread): (
-        mpsc::Sender<()>,
-        JoinHandle<Result<(), status_manager::StatusManagerError>>,
-    ) = run_status_manager(pool.clone(), carrot_config.clone());
-
-    // Do the same for the gcloud subscriber thread if configured to use it
-    let (gcloud_subscriber_send, gcloud_subscriber_thread): (
+    // If we're configured to elect a leader, start the leader elector, which will start the
+    // background managers if this instance becomes the leader.  Otherwise, just start them
+    let (leader_elector_send, leader_elector_thread, background_managers): (
         Option<mpsc::Sender<()>>,
         Option<JoinHandle<()>>,
-    ) = match carrot_config.github() {
+        Option<BackgroundManagers>,
+    ) = match carrot_config.leader_election() {
         Some(_) => {
-            let (sender, join_handle) = run_gcloud_subscriber(pool.clone(), carrot_config.clone());
-            (Some(sender), Some(join_handle))
+            let (sender, join_handle) = run_leader_elector(pool.clone(), carrot_config.clone());
+            (Some(sender), Some(join_handle), None)
         }
-        None => (None, None),
+        None => (
+            None,
+            None,
+            Some(start_background_managers(
+                pool.clone(),
+                carrot_config.clone(),
+            )),
+        ),
     };
 
     // Create channel for getting app server controller from app thread
@@ -185,27 +361,25 @@ fn main() {
     // Wait for Ctrl-C to terminate
     while user_term.load(Ordering::SeqCst) {}
 
-    // Once we've received a Ctrl-C send message to receivers to terminate
-    manager_send
-        .send(())
-        .expect("Failed to send terminate message to manager thread");
-    if let Some(sender) = gcloud_subscriber_send {
+    // Once we've received a Ctrl-C send message to the leader elector to terminate (which will
+    // stop the background managers if it started them)
+    if let Some(sender) = leader_elector_send {
         sender
             .send(())
-            .expect("Failed to send terminate message to gcloud subscriber thread");
+            .expect("Failed to send terminate message to leader elector thread");
     }
     // Then tell app server to stop
     let app_server_stop_future = app_srv_controller.stop(true);
-    // Then wait for both to finish
+    // Then wait for everything to finish
     block_on(app_server_stop_future);
 
-    manager_thread
-        .join()
-        .expect("Failed to join to manager thread")
-        .expect("Manager thread exited with an error");
-    if let Some(thread) = gcloud_subscriber_thread {
+    if let Some(thread) = leader_elector_thread {
         thread
             .join()
-            .expect("Failed to join to gcloud subscriber thread");
+            .expect("Failed to join to leader elector thread");
+    }
+    // If we started the background managers ourselves, stop them
+    if let Some(managers) = background_managers {
+        stop_background_managers(managers);
     }
 }
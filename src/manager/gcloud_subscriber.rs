@@ -8,14 +8,15 @@ use crate::config::{Config, GCloudConfig, GithubConfig};
 use crate::db::DbPool;
 use crate::manager::github_runner::{GithubRunRequest, GithubRunner};
 use crate::manager::notification_handler::NotificationHandler;
+use crate::manager::server_router::ServerRouter;
 use crate::manager::test_runner::TestRunner;
 use crate::manager::util::{check_for_terminate_message, check_for_terminate_message_with_timeout};
 use crate::notifications::emailer::Emailer;
 use crate::notifications::github_commenter::GithubCommenter;
-use crate::requests::cromwell_requests::CromwellClient;
 use crate::requests::github_requests::GithubClient;
 use crate::requests::test_resource_requests::TestResourceClient;
 use crate::storage::gcloud_storage::GCloudClient;
+use crate::util::secrets::{self, SecretEncryptor};
 use actix_web::client::Client;
 use base64;
 use diesel::PgConnection;
@@ -142,19 +143,32 @@ pub async fn init_and_run(
     // Create a notification handler
     let notification_handler: NotificationHandler =
         NotificationHandler::new(emailer, Some(github_commenter));
-    // Create a test resource client and cromwell client for the test runner
+    // Create a test resource client and server router for the test runner
     let test_resource_client: TestResourceClient =
         TestResourceClient::new(http_client.clone(), Some(gcloud_client));
-    let cromwell_client: CromwellClient =
-        CromwellClient::new(http_client.clone(), carrot_config.cromwell().address());
+    let server_router: ServerRouter = ServerRouter::new(http_client.clone(), &carrot_config);
     // Create a test runner
+    // Create a secret encryptor (if secrets are enabled) so the test runner can fill in secrets
+    let secret_encryptor: Option<SecretEncryptor> = secrets::get_secret_encryptor(&carrot_config);
     let test_runner: TestRunner = match carrot_config.custom_image_build() {
         Some(image_build_config) => TestRunner::new(
-            cromwell_client,
+            server_router,
             test_resource_client,
             Some(image_build_config.image_registry_host()),
+            carrot_config.status_manager().max_concurrent_runs(),
+            carrot_config.retry().clone(),
+            carrot_config.cromwell().workflow_option_defaults().cloned(),
+            secret_encryptor.clone(),
+        ),
+        None => TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            carrot_config.status_manager().max_concurrent_runs(),
+            carrot_config.retry().clone(),
+            carrot_config.cromwell().workflow_option_defaults().cloned(),
+            secret_encryptor.clone(),
         ),
-        None => TestRunner::new(cromwell_client, test_resource_client, None),
     };
     let gcloud_subscriber: GCloudSubscriber = GCloudSubscriber::new(
         db_pool,
@@ -371,6 +385,7 @@ mod tests {
     use crate::manager::gcloud_subscriber::{GCloudSubscriber, ParseMessageError};
     use crate::manager::github_runner::GithubRunner;
     use crate::manager::notification_handler::NotificationHandler;
+    use crate::manager::server_router::ServerRouter;
     use crate::manager::test_runner::TestRunner;
     use crate::models::pipeline::{NewPipeline, PipelineData};
     use crate::models::run::RunData;
@@ -383,7 +398,6 @@ mod tests {
     use crate::models::test::{NewTest, TestData};
     use crate::notifications::emailer::Emailer;
     use crate::notifications::github_commenter::GithubCommenter;
-    use crate::requests::cromwell_requests::CromwellClient;
     use crate::requests::github_requests::GithubClient;
     use crate::requests::test_resource_requests::TestResourceClient;
     use crate::storage::gcloud_storage::GCloudClient;
@@ -448,6 +462,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline")
@@ -463,6 +480,9 @@ mod tests {
             eval_wdl: format!("{}/eval_software_params", mockito::server_url()),
             eval_wdl_dependencies: None,
             created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(&conn, new_template).expect("Failed to insert test")
@@ -478,6 +498,10 @@ mod tests {
             eval_input_defaults: None,
             eval_option_defaults: None,
             created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(&conn, new_test).expect("Failed to insert test")
@@ -523,19 +547,30 @@ mod tests {
         // Create a notification handler
         let notification_handler: NotificationHandler =
             NotificationHandler::new(emailer, Some(github_commenter));
-        // Create a test resource client and cromwell client for the test runner
+        // Create a test resource client and server router for the test runner
         let test_resource_client: TestResourceClient =
             TestResourceClient::new(http_client.clone(), Some(gcloud_client));
-        let cromwell_client: CromwellClient =
-            CromwellClient::new(http_client.clone(), carrot_config.cromwell().address());
+        let server_router: ServerRouter = ServerRouter::new(http_client.clone(), &carrot_config);
         // Create a test runner
         let test_runner: TestRunner = match carrot_config.custom_image_build() {
             Some(image_build_config) => TestRunner::new(
-                cromwell_client,
+                server_router,
                 test_resource_client,
                 Some(image_build_config.image_registry_host()),
+                carrot_config.status_manager().max_concurrent_runs(),
+                carrot_config.retry().clone(),
+                carrot_config.cromwell().workflow_option_defaults().cloned(),
+                None,
+            ),
+            None => TestRunner::new(
+                server_router,
+                test_resource_client,
+                None,
+                carrot_config.status_manager().max_concurrent_runs(),
+                carrot_config.retry().clone(),
+                carrot_config.cromwell().workflow_option_defaults().cloned(),
+                None,
             ),
-            None => TestRunner::new(cromwell_client, test_resource_client, None),
         };
         GCloudSubscriber::new(
             db_pool,
@@ -182,6 +182,8 @@ impl GithubRunner {
                 eval_input,
                 None,
                 None,
+                None,
+                None,
             )
             .await?)
     }
@@ -220,10 +222,11 @@ impl GithubRunner {
 
 #[cfg(test)]
 mod tests {
-    use crate::config::{EmailConfig, EmailSendmailConfig};
+    use crate::config::{EmailConfig, EmailSendmailConfig, RetryConfig};
     use crate::custom_sql_types::{BuildStatusEnum, EntityTypeEnum, RunStatusEnum};
     use crate::manager::github_runner::{GithubRunRequest, GithubRunner};
     use crate::manager::notification_handler::NotificationHandler;
+    use crate::manager::server_router::ServerRouter;
     use crate::manager::test_runner::TestRunner;
     use crate::models::pipeline::{NewPipeline, PipelineData};
     use crate::models::run::RunData;
@@ -237,10 +240,9 @@ mod tests {
     use crate::models::test::{NewTest, TestData};
     use crate::notifications::emailer::Emailer;
     use crate::notifications::github_commenter::GithubCommenter;
-    use crate::requests::cromwell_requests::CromwellClient;
     use crate::requests::github_requests::GithubClient;
     use crate::requests::test_resource_requests::TestResourceClient;
-    use crate::unit_test_util::get_test_db_connection;
+    use crate::unit_test_util::{get_test_db_connection, load_default_config};
     use actix_web::client::Client;
     use diesel::PgConnection;
     use mailparse::MailHeaderMap;
@@ -299,6 +301,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline")
@@ -314,6 +319,9 @@ mod tests {
             eval_wdl: format!("{}/eval_software_params", mockito::server_url()),
             eval_wdl_dependencies: None,
             created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(&conn, new_template).expect("Failed to insert test")
@@ -329,6 +337,10 @@ mod tests {
             eval_input_defaults: None,
             eval_option_defaults: None,
             created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(&conn, new_test).expect("Failed to insert test")
@@ -360,12 +372,16 @@ mod tests {
         let notification_handler =
             NotificationHandler::new(Some(test_emailer), Some(github_commenter));
         // Make the stuff we need for a test runner
-        let cromwell_client = CromwellClient::new(Client::default(), &mockito::server_url());
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
         let test_resource_client = TestResourceClient::new(Client::default(), None);
         let test_runner = TestRunner::new(
-            cromwell_client,
+            server_router,
             test_resource_client,
             Some("https://example.com"),
+            None,
+            RetryConfig::default(),
+            None,
+            None,
         );
         // Create and return the github runner
         GithubRunner::new(test_runner, notification_handler)
@@ -0,0 +1,441 @@
+//! Defines functionality for electing a leader among multiple CARROT instances that share a DB
+//!
+//! Only one instance at a time should run the background managers (the status manager, run
+//! scheduler, and gcloud subscriber), or they will do things like submit the same eval job twice
+//! or send the same notifications twice.  Leadership is represented by holding a session-level
+//! Postgres advisory lock on a dedicated DB connection.  If the leader dies, its connection closes,
+//! Postgres releases the lock, and one of the other instances acquires it and takes over.  If the
+//! leader's host becomes unreachable without closing the connection, TCP keepalives on the
+//! connection make Postgres drop it (and release the lock) within the failover window described
+//! by [`LeaderElectionConfig::failover_window_in_secs`].
+//!
+//! The `run` function is meant to be called in its own thread.  It will run in a cycle attempting
+//! to acquire the lock (and starting the background managers once it has it) or, once it is the
+//! leader, making sure its connection (and therefore its lock) is still alive (and stopping the
+//! background managers if not) and that the background managers are still running (and giving up
+//! leadership if not, so another instance can take over running them)
+
+use crate::config::LeaderElectionConfig;
+use crate::manager::util::check_for_terminate_message_with_timeout;
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool};
+use log::{debug, error, info};
+use std::fmt;
+use std::sync::mpsc;
+use std::time::Duration;
+
+sql_function!(fn pg_try_advisory_lock(key: BigInt) -> Bool);
+sql_function!(fn pg_advisory_unlock(key: BigInt) -> Bool);
+
+#[derive(Debug)]
+pub enum Error {
+    DB(diesel::result::Error),
+    Connection(diesel::ConnectionError),
+    NotLeader,
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DB(e) => write!(f, "Error DB {}", e),
+            Error::Connection(e) => write!(f, "Error Connection {}", e),
+            Error::NotLeader => write!(f, "Error NotLeader"),
+        }
+    }
+}
+
+impl From<diesel::result::Error> for Error {
+    fn from(e: diesel::result::Error) -> Error {
+        Error::DB(e)
+    }
+}
+
+impl From<diesel::ConnectionError> for Error {
+    fn from(e: diesel::ConnectionError) -> Error {
+        Error::Connection(e)
+    }
+}
+
+/// A struct that handles competing with other CARROT instances to be the leader, and starting and
+/// stopping the background managers as it gains or loses leadership
+pub struct LeaderElector {
+    db_url: String,
+    config: LeaderElectionConfig,
+    channel_recv: mpsc::Receiver<()>,
+    /// The connection on which the lock is held (or on which we attempt to acquire it).  This is
+    /// its own connection instead of one from the DB pool because the lock is tied to the session,
+    /// so the connection has to stay open for as long as we're the leader
+    lock_conn: Option<PgConnection>,
+    is_leader: bool,
+}
+
+impl LeaderElector {
+    /// Creates a new instance of LeaderElector which will use `db_url` to open its connection to
+    /// the db, `config` for determining the lock key and how often to check it, and `channel_recv`
+    /// for checking for termination messages from its parent thread
+    pub fn new(
+        db_url: &str,
+        config: LeaderElectionConfig,
+        channel_recv: mpsc::Receiver<()>,
+    ) -> LeaderElector {
+        LeaderElector {
+            db_url: String::from(db_url),
+            config,
+            channel_recv,
+            lock_conn: None,
+            is_leader: false,
+        }
+    }
+
+    /// Main loop function for this elector.  Attempts to become the leader, calling
+    /// `start_managers` once it does, and then checks that it is still the leader, calling
+    /// `stop_managers` with the value returned by `start_managers` if it is not.  While it is the
+    /// leader, it also checks that the managers are still running by calling `managers_running`
+    /// with that value.  If any of them have exited, it stops the rest and gives up leadership, and
+    /// then sits out its next attempt to become the leader so another instance can take over.
+    /// When a terminate message is received, stops the managers (if they are running) and gives up
+    /// leadership
+    pub fn run<T, S, E, R>(
+        &mut self,
+        mut start_managers: S,
+        mut stop_managers: E,
+        mut managers_running: R,
+    ) where
+        S: FnMut() -> T,
+        E: FnMut(T),
+        R: FnMut(&T) -> bool,
+    {
+        info!(
+            "If the leader becomes unreachable, another instance will take over within about {} seconds",
+            self.config.failover_window_in_secs()
+        );
+        // The handle for the running managers, if we're the leader
+        let mut managers: Option<T> = None;
+        // Whether we just gave up leadership because the managers exited, in which case we skip
+        // our next attempt at becoming the leader to give the other instances a chance
+        let mut stepped_down = false;
+        // Main loop
+        loop {
+            if self.is_leader {
+                // Make sure we still hold the lock, and stop the managers if we don't, so we don't
+                // run them at the same time as whichever instance is the leader now
+                if let Err(e) = self.check_leadership() {
+                    error!(
+                        "Lost leadership due to {}. Stopping status manager, run scheduler, and gcloud subscriber",
+                        e
+                    );
+                    if let Some(managers) = managers.take() {
+                        stop_managers(managers);
+                    }
+                    self.is_leader = false;
+                    self.lock_conn = None;
+                }
+                // If any of the managers have exited, the work they do isn't getting done, so stop
+                // the rest and let another instance take over
+                else if let Some(false) = managers.as_ref().map(&mut managers_running) {
+                    error!("A background manager exited unexpectedly. Stopping status manager, run scheduler, and gcloud subscriber and giving up leadership");
+                    if let Some(managers) = managers.take() {
+                        stop_managers(managers);
+                    }
+                    self.give_up_leadership();
+                    stepped_down = true;
+                }
+            } else if stepped_down {
+                stepped_down = false;
+            } else {
+                match self.try_become_leader() {
+                    Ok(true) => {
+                        info!("Became leader. Starting status manager, run scheduler, and gcloud subscriber");
+                        managers = Some(start_managers());
+                    }
+                    Ok(false) => {
+                        debug!(
+                            "Another instance is the leader, so not starting background managers"
+                        )
+                    }
+                    Err(e) => error!("Failed to attempt to become leader due to: {}", e),
+                }
+            }
+            // Wait until it's time to check again, or until we get a message from the main thread
+            // to exit
+            if check_for_terminate_message_with_timeout(
+                &self.channel_recv,
+                Duration::new(self.config.check_wait_time_in_secs(), 0),
+            )
+            .is_some()
+            {
+                if let Some(managers) = managers.take() {
+                    stop_managers(managers);
+                }
+                self.give_up_leadership();
+                return;
+            }
+        }
+    }
+
+    /// Attempts to acquire the leader lock, opening a connection for it first if necessary.
+    /// Returns Ok(true) if it was acquired, Ok(false) if another instance holds it, or an error if
+    /// connecting or querying fails
+    pub fn try_become_leader(&mut self) -> Result<bool, Error> {
+        // Advisory locks are reentrant, so don't try to get it again if we already have it
+        if self.is_leader {
+            return Ok(true);
+        }
+        if self.lock_conn.is_none() {
+            self.lock_conn = Some(self.open_lock_connection()?);
+        }
+        let conn = self
+            .lock_conn
+            .as_ref()
+            .expect("Failed to unwrap lock connection.  This should not happen.");
+        match diesel::select(pg_try_advisory_lock(self.config.lock_key())).get_result::<bool>(conn)
+        {
+            Ok(acquired) => {
+                self.is_leader = acquired;
+                Ok(acquired)
+            }
+            Err(e) => {
+                // The connection might be broken, so drop it so we'll open a new one next time
+                self.lock_conn = None;
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Opens a new connection for holding the lock, configured so a dead leader's connection (and
+    /// therefore its lock) doesn't outlive it for long, and so queries on it can't hang
+    ///
+    /// If the leader's host goes down or becomes unreachable without closing its connection, the DB
+    /// server would otherwise keep the session (and the lock) around until the OS gives up on the
+    /// connection, which can take hours.  TCP keepalives make the server notice within roughly
+    /// `keepalive_idle_in_secs + keepalive_interval_in_secs * keepalive_count` seconds instead.
+    /// Returns an error if connecting or applying the settings fails
+    fn open_lock_connection(&self) -> Result<PgConnection, Error> {
+        let conn = PgConnection::establish(&self.db_url)?;
+        conn.batch_execute(&format!(
+            "SET tcp_keepalives_idle = {}; \
+            SET tcp_keepalives_interval = {}; \
+            SET tcp_keepalives_count = {}; \
+            SET statement_timeout = '{}s';",
+            self.config.keepalive_idle_in_secs(),
+            self.config.keepalive_interval_in_secs(),
+            self.config.keepalive_count(),
+            self.config.statement_timeout_in_secs()
+        ))?;
+        Ok(conn)
+    }
+
+    /// Checks that this elector is still the leader.  The lock is held for as long as the session
+    /// that acquired it is alive, so this just makes sure the connection still works.  Returns an
+    /// error if it doesn't, or if this elector wasn't the leader
+    pub fn check_leadership(&self) -> Result<(), Error> {
+        if !self.is_leader {
+            return Err(Error::NotLeader);
+        }
+        match &self.lock_conn {
+            Some(conn) => {
+                conn.execute("SELECT 1")?;
+                Ok(())
+            }
+            None => Err(Error::NotLeader),
+        }
+    }
+
+    /// Releases the leader lock, if this elector holds it, and closes the lock connection
+    pub fn give_up_leadership(&mut self) {
+        if self.is_leader {
+            if let Some(conn) = &self.lock_conn {
+                // If this fails, the lock will still be released when we close the connection
+                if let Err(e) = diesel::select(pg_advisory_unlock(self.config.lock_key()))
+                    .get_result::<bool>(conn)
+                {
+                    error!("Failed to release leader lock due to: {}", e);
+                }
+            }
+            info!("Gave up leadership");
+        }
+        self.is_leader = false;
+        self.lock_conn = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::LeaderElectionConfig;
+    use crate::manager::leader_elector::{Error, LeaderElector};
+    use crate::unit_test_util::load_default_config;
+    use diesel::prelude::*;
+    use diesel::sql_types::Text;
+    use std::sync::mpsc;
+
+    sql_function!(fn current_setting(setting_name: Text) -> Text);
+
+    // Use a different lock key in each test so they don't interfere with each other
+    fn create_test_leader_elector(lock_key: i64) -> (LeaderElector, mpsc::Sender<()>) {
+        let carrot_config = load_default_config();
+        let (channel_send, channel_recv) = mpsc::channel();
+        let leader_elector = LeaderElector::new(
+            carrot_config.database().url(),
+            LeaderElectionConfig::new(lock_key, 1),
+            channel_recv,
+        );
+        (leader_elector, channel_send)
+    }
+
+    #[test]
+    fn test_try_become_leader() {
+        let (mut first_elector, _first_send) = create_test_leader_elector(94211001);
+        let (mut second_elector, _second_send) = create_test_leader_elector(94211001);
+
+        // The first one should get the lock
+        assert!(first_elector.try_become_leader().unwrap());
+        assert!(first_elector.is_leader);
+        // Trying again should be fine
+        assert!(first_elector.try_become_leader().unwrap());
+        // The second one shouldn't
+        assert!(!second_elector.try_become_leader().unwrap());
+        assert!(!second_elector.is_leader);
+        // Once the first gives it up, the second should get it
+        first_elector.give_up_leadership();
+        assert!(!first_elector.is_leader);
+        assert!(second_elector.try_become_leader().unwrap());
+        assert!(second_elector.is_leader);
+
+        second_elector.give_up_leadership();
+    }
+
+    #[test]
+    fn test_try_become_leader_after_leader_dies() {
+        let (mut first_elector, _first_send) = create_test_leader_elector(94211002);
+        let (mut second_elector, _second_send) = create_test_leader_elector(94211002);
+
+        assert!(first_elector.try_become_leader().unwrap());
+        assert!(!second_elector.try_become_leader().unwrap());
+        // Dropping the first one (and its connection) without giving up the lock should still
+        // release it
+        drop(first_elector);
+        // The server ends the first session asynchronously after the connection closes, so give it
+        // a moment
+        let mut became_leader = false;
+        for _ in 0..10 {
+            if second_elector.try_become_leader().unwrap() {
+                became_leader = true;
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        assert!(became_leader);
+
+        second_elector.give_up_leadership();
+    }
+
+    #[test]
+    fn test_try_become_leader_lock_connection_settings() {
+        let (mut test_elector, _test_send) = create_test_leader_elector(94211007);
+
+        test_elector.try_become_leader().unwrap();
+        let conn = test_elector.lock_conn.as_ref().unwrap();
+        let statement_timeout = diesel::select(current_setting("statement_timeout"))
+            .get_result::<String>(conn)
+            .unwrap();
+        assert_eq!(statement_timeout, "1s");
+        let keepalives_count = diesel::select(current_setting("tcp_keepalives_count"))
+            .get_result::<String>(conn)
+            .unwrap();
+        // Keepalive settings only apply to TCP connections, so they read as 0 over a Unix socket
+        assert!(keepalives_count == "3" || keepalives_count == "0");
+
+        test_elector.give_up_leadership();
+    }
+
+    #[test]
+    fn test_check_leadership() {
+        let (mut test_elector, _test_send) = create_test_leader_elector(94211003);
+
+        assert!(matches!(
+            test_elector.check_leadership(),
+            Err(Error::NotLeader)
+        ));
+        test_elector.try_become_leader().unwrap();
+        test_elector.check_leadership().unwrap();
+        test_elector.give_up_leadership();
+        assert!(matches!(
+            test_elector.check_leadership(),
+            Err(Error::NotLeader)
+        ));
+    }
+
+    #[test]
+    fn test_run_leader() {
+        let (mut test_elector, test_send) = create_test_leader_elector(94211004);
+        // Send the terminate message first so it only goes through the loop once
+        test_send.send(()).unwrap();
+
+        let mut started: u32 = 0;
+        let mut stopped: Vec<String> = Vec::new();
+        test_elector.run(
+            || {
+                started += 1;
+                String::from("managers")
+            },
+            |managers| stopped.push(managers),
+            |_| true,
+        );
+
+        assert_eq!(started, 1);
+        assert_eq!(stopped, vec![String::from("managers")]);
+        assert!(!test_elector.is_leader);
+    }
+
+    #[test]
+    fn test_run_follower() {
+        let (mut leader_elector, _leader_send) = create_test_leader_elector(94211005);
+        let (mut test_elector, test_send) = create_test_leader_elector(94211005);
+        // Make the other one the leader
+        assert!(leader_elector.try_become_leader().unwrap());
+        // Send the terminate message first so it only goes through the loop once
+        test_send.send(()).unwrap();
+
+        let mut started: u32 = 0;
+        let mut stopped: u32 = 0;
+        test_elector.run(|| started += 1, |_| stopped += 1, |_| true);
+
+        assert_eq!(started, 0);
+        assert_eq!(stopped, 0);
+
+        leader_elector.give_up_leadership();
+    }
+
+    #[test]
+    fn test_run_managers_exited() {
+        let (mut test_elector, test_send) = create_test_leader_elector(94211006);
+
+        let mut started: u32 = 0;
+        let mut stopped: u32 = 0;
+        let mut checks: u32 = 0;
+        test_elector.run(
+            || {
+                started += 1;
+                // Once the managers have been restarted, send the terminate message
+                if started == 2 {
+                    test_send.send(()).unwrap();
+                }
+            },
+            |_| stopped += 1,
+            |_| {
+                // Report that the managers exited the first time they're checked
+                checks += 1;
+                checks > 1
+            },
+        );
+
+        // The elector should have stopped the managers and given up leadership when they exited,
+        // then become the leader again and restarted them before being terminated
+        assert_eq!(started, 2);
+        assert_eq!(stopped, 2);
+        assert!(!test_elector.is_leader);
+    }
+}
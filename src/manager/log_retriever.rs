@@ -0,0 +1,526 @@
+//! Defines functionality for retrieving logs and failure messages from cromwell for runs
+//!
+//! Users don't always have direct access to cromwell, so this allows pulling the locations (and,
+//! where possible, the contents) of the stdout and stderr files for the calls in a run's workflows,
+//! and the failure messages for those workflows, through carrot instead
+
+use crate::manager::server_router::{self, ServerRouter};
+use crate::models::run::RunData;
+use crate::requests::cromwell_requests::{CromwellRequestError, MetadataParams};
+use crate::requests::test_resource_requests::TestResourceClient;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Struct for retrieving logs and failure messages for runs from cromwell
+#[derive(Clone)]
+pub struct LogRetriever {
+    server_router: ServerRouter,
+    test_resource_client: TestResourceClient,
+}
+
+/// The logs for the test and eval workflows of a run.  A workflow will be None if it has not been
+/// submitted to cromwell
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RunLogs {
+    pub test: Option<WorkflowLogs>,
+    pub eval: Option<WorkflowLogs>,
+}
+
+/// The logs for each of the calls in a cromwell workflow, keyed by task name
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct WorkflowLogs {
+    pub cromwell_job_id: String,
+    pub calls: BTreeMap<String, Vec<CallLogs>>,
+}
+
+/// The locations of the stdout and stderr files for a single call, along with their contents if
+/// they could be read
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct CallLogs {
+    pub shard_index: i32,
+    pub attempt: i32,
+    pub stdout: Option<String>,
+    pub stderr: Option<String>,
+    pub stdout_contents: Option<String>,
+    pub stderr_contents: Option<String>,
+}
+
+/// The failures for the test and eval workflows of a run.  A workflow will be None if it has not
+/// been submitted to cromwell
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct RunFailures {
+    pub test: Option<WorkflowFailures>,
+    pub eval: Option<WorkflowFailures>,
+}
+
+/// The failures for a cromwell workflow, in the structure cromwell reports them (a list of
+/// failures, each with a message and a list of the failures that caused it)
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct WorkflowFailures {
+    pub cromwell_job_id: String,
+    pub failures: Vec<Value>,
+}
+
+/// Error type for possible errors returned by retrieving logs or failures
+#[derive(Debug)]
+pub enum Error {
+    Cromwell(CromwellRequestError),
+    Server(server_router::Error),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Cromwell(e) => write!(f, "Error Cromwell {}", e),
+            Error::Server(e) => write!(f, "Error Server {}", e),
+        }
+    }
+}
+
+// Implementing From for each of the error types so they map more easily
+impl From<CromwellRequestError> for Error {
+    fn from(e: CromwellRequestError) -> Error {
+        Error::Cromwell(e)
+    }
+}
+
+impl From<server_router::Error> for Error {
+    fn from(e: server_router::Error) -> Error {
+        Error::Server(e)
+    }
+}
+
+impl LogRetriever {
+    /// Creates a new LogRetriever that will use `server_router` to retrieve logs and failures
+    /// from the cromwell server each run was submitted to and `test_resource_client` to read the
+    /// contents of log files
+    pub fn new(
+        server_router: ServerRouter,
+        test_resource_client: TestResourceClient,
+    ) -> LogRetriever {
+        LogRetriever {
+            server_router,
+            test_resource_client,
+        }
+    }
+
+    /// Retrieves the log locations for the calls in the test and eval workflows for `run` from
+    /// cromwell, along with the contents of each log file that can be read with the test resource
+    /// client.  Returns an error if a request to cromwell fails
+    pub async fn get_logs_for_run(&self, run: &RunData) -> Result<RunLogs, Error> {
+        let test = match &run.test_cromwell_job_id {
+            Some(job_id) => Some(
+                self.get_logs_for_job(run.cromwell_server.as_deref(), job_id)
+                    .await?,
+            ),
+            None => None,
+        };
+        let eval = match &run.eval_cromwell_job_id {
+            Some(job_id) => Some(
+                self.get_logs_for_job(run.cromwell_server.as_deref(), job_id)
+                    .await?,
+            ),
+            None => None,
+        };
+        Ok(RunLogs { test, eval })
+    }
+
+    /// Retrieves the failures for the test and eval workflows for `run` from cromwell.  Returns an
+    /// error if a request to cromwell fails
+    pub async fn get_failures_for_run(&self, run: &RunData) -> Result<RunFailures, Error> {
+        let test = match &run.test_cromwell_job_id {
+            Some(job_id) => Some(
+                self.get_failures_for_job(run.cromwell_server.as_deref(), job_id)
+                    .await?,
+            ),
+            None => None,
+        };
+        let eval = match &run.eval_cromwell_job_id {
+            Some(job_id) => Some(
+                self.get_failures_for_job(run.cromwell_server.as_deref(), job_id)
+                    .await?,
+            ),
+            None => None,
+        };
+        Ok(RunFailures { test, eval })
+    }
+
+    /// Retrieves the log locations for the calls in the cromwell job with id `job_id` on
+    /// `cromwell_server` (or the default server if None) and attempts to read the contents of each
+    async fn get_logs_for_job(
+        &self,
+        cromwell_server: Option<&str>,
+        job_id: &str,
+    ) -> Result<WorkflowLogs, Error> {
+        let logs = self
+            .server_router
+            .cromwell_client(cromwell_server)?
+            .get_logs(job_id)
+            .await?;
+        let mut calls: BTreeMap<String, Vec<CallLogs>> = BTreeMap::new();
+        // Calls are keyed by task name, with an entry for each shard and attempt
+        if let Some(logs_calls) = logs.get("calls").and_then(|calls| calls.as_object()) {
+            for (task_name, task_calls) in logs_calls {
+                let task_calls = match task_calls.as_array() {
+                    Some(task_calls) => task_calls,
+                    None => continue,
+                };
+                let mut call_logs_list: Vec<CallLogs> = Vec::new();
+                for call in task_calls {
+                    let stdout = call
+                        .get("stdout")
+                        .and_then(|value| value.as_str())
+                        .map(String::from);
+                    let stderr = call
+                        .get("stderr")
+                        .and_then(|value| value.as_str())
+                        .map(String::from);
+                    let stdout_contents = self.read_log(stdout.as_deref()).await;
+                    let stderr_contents = self.read_log(stderr.as_deref()).await;
+                    call_logs_list.push(CallLogs {
+                        // Cromwell uses -1 as the shard index for calls that aren't scattered
+                        shard_index: call
+                            .get("shardIndex")
+                            .and_then(|value| value.as_i64())
+                            .unwrap_or(-1) as i32,
+                        attempt: call
+                            .get("attempt")
+                            .and_then(|value| value.as_i64())
+                            .unwrap_or(1) as i32,
+                        stdout,
+                        stderr,
+                        stdout_contents,
+                        stderr_contents,
+                    });
+                }
+                calls.insert(task_name.clone(), call_logs_list);
+            }
+        }
+        Ok(WorkflowLogs {
+            cromwell_job_id: String::from(job_id),
+            calls,
+        })
+    }
+
+    /// Retrieves the failures from the metadata for the cromwell job with id `job_id` on
+    /// `cromwell_server` (or the default server if None)
+    async fn get_failures_for_job(
+        &self,
+        cromwell_server: Option<&str>,
+        job_id: &str,
+    ) -> Result<WorkflowFailures, Error> {
+        let params = MetadataParams {
+            exclude_key: None,
+            expand_sub_workflows: None,
+            include_key: Some(vec![String::from("failures")]),
+            metadata_source: None,
+        };
+        let metadata = self
+            .server_router
+            .cromwell_client(cromwell_server)?
+            .get_metadata(job_id, &params)
+            .await?;
+        let failures = match metadata.get("failures").and_then(|value| value.as_array()) {
+            Some(failures) => failures.to_owned(),
+            None => Vec::new(),
+        };
+        Ok(WorkflowFailures {
+            cromwell_job_id: String::from(job_id),
+            failures,
+        })
+    }
+
+    /// Attempts to read the log file at `location` using the test resource client, returning
+    /// None if there is no location or it can't be read (e.g. because carrot does not have access
+    /// to where cromwell writes its logs)
+    async fn read_log(&self, location: Option<&str>) -> Option<String> {
+        let location = location?;
+        match self
+            .test_resource_client
+            .get_resource_as_string(location)
+            .await
+        {
+            Ok(contents) => Some(contents),
+            Err(e) => {
+                debug!("Unable to read log file at {}: {}", location, e);
+                None
+            }
+        }
+    }
+}
+
+/// Flattens `failures`, the failures from the metadata for a cromwell workflow, into a list of
+/// messages, one for each of the root causes of the failures.  Each message includes the messages
+/// of the failures it caused, in the form "Workflow failed: Job x exited with return code 1"
+pub fn get_failure_messages(failures: &[Value]) -> Vec<String> {
+    let mut messages: Vec<String> = Vec::new();
+    for failure in failures {
+        add_failure_messages(failure, None, &mut messages);
+    }
+    messages
+}
+
+/// Adds messages for the root causes of `failure` to `messages`, prefixed with `prefix` if it has
+/// a value
+fn add_failure_messages(failure: &Value, prefix: Option<&str>, messages: &mut Vec<String>) {
+    let message = failure
+        .get("message")
+        .and_then(|value| value.as_str())
+        .unwrap_or("Unknown failure");
+    let message = match prefix {
+        Some(prefix) => format!("{}: {}", prefix, message),
+        None => String::from(message),
+    };
+    match failure.get("causedBy").and_then(|value| value.as_array()) {
+        Some(causes) if !causes.is_empty() => {
+            for cause in causes {
+                add_failure_messages(cause, Some(&message), messages);
+            }
+        }
+        _ => messages.push(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::unit_test_util::load_default_config;
+    use actix_web::client::Client;
+    use chrono::Utc;
+    use serde_json::json;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+    use uuid::Uuid;
+
+    fn create_test_log_retriever() -> LogRetriever {
+        let client = Client::default();
+        let server_router = ServerRouter::new(client.clone(), &load_default_config());
+        let test_resource_client = TestResourceClient::new(client, None);
+        LogRetriever::new(server_router, test_resource_client)
+    }
+
+    fn create_test_run_data(
+        test_cromwell_job_id: Option<String>,
+        eval_cromwell_job_id: Option<String>,
+    ) -> RunData {
+        RunData {
+            run_id: Uuid::new_v4(),
+            test_id: Uuid::new_v4(),
+            name: String::from("Kevin's test run"),
+            status: RunStatusEnum::TestFailed,
+            test_input: json!({}),
+            test_options: None,
+            eval_input: json!({}),
+            eval_options: None,
+            test_cromwell_job_id,
+            eval_cromwell_job_id,
+            created_at: Utc::now().naive_utc(),
+            created_by: None,
+            finished_at: None,
+            cromwell_server: None,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn get_logs_for_run_success() {
+        let test_log_retriever = create_test_log_retriever();
+        let test_run = create_test_run_data(
+            Some(String::from("53709600-d114-4194-a7f7-9e41211ca2ce")),
+            None,
+        );
+        // Make a stdout file we can read
+        let mut stdout_file = NamedTempFile::new().unwrap();
+        write!(stdout_file, "Hello").unwrap();
+        let stdout_location = stdout_file.path().to_str().unwrap().to_string();
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "calls": {
+            "test_workflow.hello": [
+              {
+                "stdout": stdout_location,
+                "stderr": "/this/file/does/not/exist/stderr",
+                "shardIndex": -1,
+                "attempt": 1
+              }
+            ]
+          }
+        });
+        let mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/logs",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(mock_response_body.to_string())
+        .create();
+
+        let result = test_log_retriever
+            .get_logs_for_run(&test_run)
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        let mut expected_calls = BTreeMap::new();
+        expected_calls.insert(
+            String::from("test_workflow.hello"),
+            vec![CallLogs {
+                shard_index: -1,
+                attempt: 1,
+                stdout: Some(stdout_location),
+                stderr: Some(String::from("/this/file/does/not/exist/stderr")),
+                stdout_contents: Some(String::from("Hello")),
+                stderr_contents: None,
+            }],
+        );
+        assert_eq!(
+            result,
+            RunLogs {
+                test: Some(WorkflowLogs {
+                    cromwell_job_id: String::from("53709600-d114-4194-a7f7-9e41211ca2ce"),
+                    calls: expected_calls,
+                }),
+                eval: None,
+            }
+        );
+    }
+
+    #[actix_rt::test]
+    async fn get_logs_for_run_failure_cromwell() {
+        let test_log_retriever = create_test_log_retriever();
+        let test_run = create_test_run_data(
+            Some(String::from("53709600-d114-4194-a7f7-9e41211ca2ce")),
+            None,
+        );
+        // Define mockito mapping for cromwell response
+        let mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/logs",
+        )
+        .with_status(404)
+        .with_header("content_type", "application/json")
+        .with_body(json!({"status": "fail", "message": "Unrecognized workflow ID"}).to_string())
+        .create();
+
+        let result = test_log_retriever.get_logs_for_run(&test_run).await;
+
+        mock.assert();
+
+        assert!(matches!(result, Err(Error::Cromwell(_))));
+    }
+
+    #[actix_rt::test]
+    async fn get_failures_for_run_success() {
+        let test_log_retriever = create_test_log_retriever();
+        let test_run = create_test_run_data(
+            Some(String::from("53709600-d114-4194-a7f7-9e41211ca2ce")),
+            Some(String::from("12345612-d114-4194-a7f7-9e41211ca2ce")),
+        );
+        // Define mockito mappings for cromwell responses
+        let test_failures = json!([
+          {
+            "message": "Workflow failed",
+            "causedBy": [
+              {
+                "message": "Job test_workflow.hello:NA:1 exited with return code 1",
+                "causedBy": []
+              }
+            ]
+          }
+        ]);
+        let test_mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=failures",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(
+            json!({
+              "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+              "failures": test_failures
+            })
+            .to_string(),
+        )
+        .create();
+        let eval_mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/12345612-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=failures",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(json!({"id": "12345612-d114-4194-a7f7-9e41211ca2ce"}).to_string())
+        .create();
+
+        let result = test_log_retriever
+            .get_failures_for_run(&test_run)
+            .await
+            .unwrap();
+
+        test_mock.assert();
+        eval_mock.assert();
+
+        assert_eq!(
+            result,
+            RunFailures {
+                test: Some(WorkflowFailures {
+                    cromwell_job_id: String::from("53709600-d114-4194-a7f7-9e41211ca2ce"),
+                    failures: test_failures.as_array().unwrap().to_owned(),
+                }),
+                eval: Some(WorkflowFailures {
+                    cromwell_job_id: String::from("12345612-d114-4194-a7f7-9e41211ca2ce"),
+                    failures: Vec::new(),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn get_failure_messages_success() {
+        let failures = json!([
+          {
+            "message": "Workflow failed",
+            "causedBy": [
+              {
+                "message": "Job test_workflow.hello:NA:1 exited with return code 1",
+                "causedBy": []
+              },
+              {
+                "message": "Job test_workflow.goodbye:0:2 failed",
+                "causedBy": [
+                  {
+                    "message": "The job was preempted",
+                    "causedBy": []
+                  }
+                ]
+              }
+            ]
+          },
+          {
+            "message": "Failed to evaluate outputs"
+          }
+        ]);
+
+        let messages = get_failure_messages(failures.as_array().unwrap());
+
+        assert_eq!(
+            messages,
+            vec![
+                String::from(
+                    "Workflow failed: Job test_workflow.hello:NA:1 exited with return code 1"
+                ),
+                String::from(
+                    "Workflow failed: Job test_workflow.goodbye:0:2 failed: The job was preempted"
+                ),
+                String::from("Failed to evaluate outputs"),
+            ]
+        );
+    }
+}
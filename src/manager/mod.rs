@@ -1,8 +1,13 @@
 pub mod gcloud_subscriber;
 pub mod github_runner;
+pub mod leader_elector;
+pub mod log_retriever;
 pub mod notification_handler;
 pub mod report_builder;
+pub mod run_scheduler;
+pub mod server_router;
 pub mod software_builder;
 pub mod status_manager;
 pub mod test_runner;
+pub mod workflow_reconciler;
 mod util;
@@ -1,9 +1,11 @@
 //! Contains functions for sending notifications to users
 
+use crate::custom_sql_types::EntityTypeEnum;
 use crate::models::run::{RunData, RunWithResultsAndErrorsData};
+use crate::models::run_group::{RunGroupData, RunGroupStatusData, RunGroupWithStatusData};
 use crate::models::run_is_from_github::RunIsFromGithubData;
 use crate::models::run_report::RunReportData;
-use crate::models::subscription::SubscriptionData;
+use crate::models::subscription::{SubscriptionData, SubscriptionQuery};
 use crate::models::test::TestData;
 use crate::notifications::{emailer, github_commenter};
 use diesel::PgConnection;
@@ -90,6 +92,7 @@ impl NotificationHandler {
         // Send emails
         if self.emailer.is_some() {
             self.send_run_complete_emails(conn, run_id)?;
+            self.send_run_group_complete_emails(conn, run_id)?;
         }
         // Post github comments
         if self.github_commenter.is_some() {
@@ -286,6 +289,87 @@ impl NotificationHandler {
         }
     }
 
+    /// Sends email to the creator of and each user subscribed to each run group containing the run
+    /// specified by `run_id`, if all of the runs in that group have finished.  The email includes
+    /// the contents of the RunGroupWithStatusData instance for that run group.  The notification for
+    /// each group is claimed in the DB before it is sent, so it's only sent once even if multiple
+    /// runs in the group finish at the same time
+    fn send_run_group_complete_emails(
+        &self,
+        conn: &PgConnection,
+        run_id: Uuid,
+    ) -> Result<(), Error> {
+        // Obviously, we can only send emails if we have an emailer
+        match &self.emailer {
+            Some(emailer) => {
+                // Get the groups this run belongs to
+                let run_groups = RunGroupData::find_for_run(conn, run_id)?;
+
+                for run_group in run_groups {
+                    // We only want to notify once the whole group is done
+                    let group_status =
+                        RunGroupStatusData::find_for_run_group(conn, run_group.run_group_id)?;
+                    if !group_status.all_finished {
+                        continue;
+                    }
+                    // Claim the notification, and skip it if it's already been claimed (e.g. by
+                    // another run in the group that finished at the same time)
+                    let run_group = match RunGroupData::claim_completion_notification(
+                        conn,
+                        run_group.run_group_id,
+                    )? {
+                        Some(run_group) => run_group,
+                        None => continue,
+                    };
+                    let run_group = RunGroupWithStatusData::from_run_group(conn, run_group)?;
+                    // Get subscriptions
+                    let subs = SubscriptionData::find(
+                        conn,
+                        SubscriptionQuery {
+                            subscription_id: None,
+                            entity_type: Some(EntityTypeEnum::RunGroup),
+                            entity_id: Some(run_group.run_group_id),
+                            created_before: None,
+                            created_after: None,
+                            email: None,
+                            sort: None,
+                            limit: None,
+                            offset: None,
+                        },
+                    )?;
+
+                    // Assemble set of email addresses to notify
+                    let mut email_addresses = HashSet::new();
+                    if let Some(address) = &run_group.created_by {
+                        email_addresses.insert(address.as_str());
+                    }
+                    for sub in &subs {
+                        email_addresses.insert(&sub.email);
+                    }
+
+                    // Put together subject and message for emails
+                    let subject = format!(
+                        "Run group {} completed: {} succeeded, {} failed",
+                        run_group.name, run_group.status.succeeded, run_group.status.failed
+                    );
+                    let message = serde_json::to_string_pretty(&run_group)?;
+
+                    if !email_addresses.is_empty() {
+                        emailer.send_email(
+                            email_addresses.into_iter().collect(),
+                            &subject,
+                            &message,
+                        )?;
+                    }
+                }
+
+                Ok(())
+            }
+            // If we don't have an emailer, return a NoEmailer error
+            None => Err(Error::NoEmailer),
+        }
+    }
+
     /// Sends email to each user subscribed to the test, template, or pipeline for the test specified
     /// by `test_id`.  The email has `subject` for its subject and `message` for its message
     pub fn send_notification_emails_for_test(
@@ -468,7 +552,9 @@ mod tests {
     use crate::manager::notification_handler::{Error, NotificationHandler};
     use crate::models::pipeline::{NewPipeline, PipelineData};
     use crate::models::report::{NewReport, ReportData};
-    use crate::models::run::{NewRun, RunData, RunWithResultsAndErrorsData};
+    use crate::models::run::{NewRun, RunChangeset, RunData, RunWithResultsAndErrorsData};
+    use crate::models::run_group::{NewRunGroup, RunGroupData, RunGroupWithStatusData};
+    use crate::models::run_in_group::{NewRunInGroup, RunInGroupData};
     use crate::models::run_is_from_github::{NewRunIsFromGithub, RunIsFromGithubData};
     use crate::models::run_report::{NewRunReport, RunReportData};
     use crate::models::subscription::{NewSubscription, SubscriptionData};
@@ -548,6 +634,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline")
@@ -563,6 +652,9 @@ mod tests {
             eval_wdl: String::from(""),
             eval_wdl_dependencies: None,
             created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(&conn, new_template).expect("Failed to insert test")
@@ -578,6 +670,10 @@ mod tests {
             eval_input_defaults: None,
             eval_option_defaults: None,
             created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(&conn, new_test).expect("Failed to insert test")
@@ -605,6 +701,53 @@ mod tests {
         RunData::create(conn, new_run).expect("Failed inserting test run")
     }
 
+    fn insert_test_run_group_with_runs_and_subscription(
+        conn: &PgConnection,
+        runs: &[&RunData],
+        email_base_name: &str,
+    ) -> RunGroupData {
+        let new_run_group = NewRunGroup {
+            name: String::from("Kevin's Run Group"),
+            description: None,
+            created_by: Some(format!("{}@example.com", email_base_name)),
+        };
+
+        let run_group =
+            RunGroupData::create(conn, new_run_group).expect("Failed inserting test run group");
+
+        for run in runs {
+            let new_run_in_group = NewRunInGroup {
+                run_id: run.run_id,
+                run_group_id: run_group.run_group_id,
+            };
+            RunInGroupData::create(conn, new_run_in_group)
+                .expect("Failed inserting test run_in_group");
+        }
+
+        let new_subscription = NewSubscription {
+            entity_type: EntityTypeEnum::RunGroup,
+            entity_id: run_group.run_group_id,
+            email: format!("{}@example.org", email_base_name),
+        };
+        SubscriptionData::create(conn, new_subscription)
+            .expect("Failed inserting test subscription");
+
+        run_group
+    }
+
+    fn update_test_run_status(conn: &PgConnection, id: Uuid, status: RunStatusEnum) -> RunData {
+        let changes = RunChangeset {
+            name: None,
+            status: Some(status),
+            test_cromwell_job_id: None,
+            eval_cromwell_job_id: None,
+            finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
+        };
+
+        RunData::update(conn, id, changes).expect("Failed updating test run status")
+    }
+
     fn insert_test_run_is_from_github_with_run_id(
         conn: &PgConnection,
         id: Uuid,
@@ -647,6 +790,7 @@ mod tests {
             })),
             created_by: Some(format!("{}@example.com", email_base_name)),
             finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
         };
 
         RunReportData::create(conn, new_run_report).expect("Failed inserting test run_report")
@@ -733,6 +877,171 @@ mod tests {
         email_path.close().unwrap();
     }
 
+    #[test]
+    fn test_send_run_group_complete_emails_success() {
+        // Create an emailer
+        let test_email_config =
+            EmailConfig::Sendmail(EmailSendmailConfig::new(String::from("kevin@example.com")));
+        let test_emailer = Emailer::new(test_email_config);
+        // Create a notification handler
+        let test_handler = NotificationHandler {
+            emailer: Some(test_emailer),
+            github_commenter: None,
+        };
+
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+
+        let test = insert_test_test_with_subscriptions_with_entities(
+            &conn,
+            "test_send_run_group_complete_emails_tests",
+        );
+        let first_run = insert_test_run_with_test_id(
+            &conn,
+            test.test_id,
+            "test_send_run_group_complete_emails_runs",
+        );
+        // Rename the first run so the second doesn't conflict with it
+        let first_run = RunData::update(
+            &conn,
+            first_run.run_id,
+            RunChangeset {
+                name: Some(String::from("Kevin's First Run")),
+                status: None,
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                finished_at: None,
+                cromwell_server: None,
+            },
+        )
+        .expect("Failed renaming test run");
+        let second_run = insert_test_run_with_test_id(
+            &conn,
+            test.test_id,
+            "test_send_run_group_complete_emails_runs",
+        );
+        let run_group = insert_test_run_group_with_runs_and_subscription(
+            &conn,
+            &[&first_run, &second_run],
+            "test_send_run_group_complete_emails",
+        );
+
+        // Make temporary directory for the email
+        let email_path = Builder::new()
+            .prefix("test_send_run_group_complete_emails")
+            .rand_bytes(0)
+            .tempdir_in(temp_dir())
+            .unwrap();
+
+        // Finish one run and make sure we don't send an email yet
+        update_test_run_status(&conn, first_run.run_id, RunStatusEnum::Succeeded);
+        test_handler
+            .send_run_group_complete_emails(&conn, first_run.run_id)
+            .unwrap();
+
+        assert_eq!(read_dir(email_path.path()).unwrap().count(), 0);
+
+        // Finish the other one and now we should get an email
+        update_test_run_status(&conn, second_run.run_id, RunStatusEnum::TestFailed);
+        test_handler
+            .send_run_group_complete_emails(&conn, second_run.run_id)
+            .unwrap();
+
+        let run_group_with_status =
+            RunGroupWithStatusData::find_by_id(&conn, run_group.run_group_id).unwrap();
+        let test_subject = format!(
+            "Run group {} completed: 1 succeeded, 1 failed",
+            run_group.name
+        );
+        let test_message = serde_json::to_string_pretty(&run_group_with_status).unwrap();
+
+        // Verify that the email was created correctly
+        let files_in_dir = read_dir(email_path.path())
+            .unwrap()
+            .collect::<Vec<std::io::Result<DirEntry>>>();
+
+        assert_eq!(files_in_dir.len(), 1);
+
+        let test_email_string =
+            read_to_string(files_in_dir.get(0).unwrap().as_ref().unwrap().path()).unwrap();
+        let test_email: ParsedEmailFile = serde_json::from_str(&test_email_string).unwrap();
+
+        let mut forward_path: Vec<&str> = test_email
+            .envelope
+            .get("forward_path")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|address| address.as_str().unwrap())
+            .collect();
+        forward_path.sort();
+
+        assert_eq!(
+            forward_path,
+            vec![
+                "test_send_run_group_complete_emails@example.com",
+                "test_send_run_group_complete_emails@example.org"
+            ]
+        );
+
+        let parsed_mail = mailparse::parse_mail(&test_email.message).unwrap();
+
+        assert_eq!(
+            parsed_mail.subparts[0].get_body().unwrap().trim(),
+            test_message
+        );
+        assert_eq!(
+            parsed_mail.headers.get_first_value("Subject").unwrap(),
+            test_subject
+        );
+
+        // If it's called again (e.g. for another run that finished at the same time), it shouldn't
+        // send another email
+        test_handler
+            .send_run_group_complete_emails(&conn, first_run.run_id)
+            .unwrap();
+
+        assert_eq!(read_dir(email_path.path()).unwrap().count(), 1);
+
+        // If a run is added to the group after it was notified, it should be notified again once
+        // that run finishes.  Rename the second run first so the third doesn't conflict with it
+        RunData::update(
+            &conn,
+            second_run.run_id,
+            RunChangeset {
+                name: Some(String::from("Kevin's Second Run")),
+                status: None,
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                finished_at: None,
+                cromwell_server: None,
+            },
+        )
+        .expect("Failed renaming test run");
+        let third_run = insert_test_run_with_test_id(
+            &conn,
+            test.test_id,
+            "test_send_run_group_complete_emails_runs",
+        );
+        RunInGroupData::create(
+            &conn,
+            NewRunInGroup {
+                run_id: third_run.run_id,
+                run_group_id: run_group.run_group_id,
+            },
+        )
+        .expect("Failed inserting test run_in_group");
+        update_test_run_status(&conn, third_run.run_id, RunStatusEnum::Succeeded);
+        test_handler
+            .send_run_group_complete_emails(&conn, third_run.run_id)
+            .unwrap();
+
+        assert_eq!(read_dir(email_path.path()).unwrap().count(), 2);
+
+        email_path.close().unwrap();
+    }
+
     #[test]
     fn test_send_run_complete_emails_failure_no_run() {
         // Create an emailer
@@ -2,14 +2,16 @@
 //!
 
 use crate::config::ReportingConfig;
-use crate::custom_sql_types::{ReportStatusEnum, REPORT_FAILURE_STATUSES};
+use crate::custom_sql_types::{ReportStatusEnum, WorkflowLanguageEnum, REPORT_FAILURE_STATUSES};
+use crate::manager::server_router::{self, ServerRouter};
 use crate::manager::util;
+use crate::manager::util::{WorkflowLabels, WorkflowStage};
 use crate::models::report::ReportData;
 use crate::models::run::{RunData, RunWithResultsAndErrorsData};
 use crate::models::run_report::{NewRunReport, RunReportData};
 use crate::models::template::TemplateData;
 use crate::models::template_report::{TemplateReportData, TemplateReportQuery};
-use crate::requests::cromwell_requests::{CromwellClient, CromwellRequestError};
+use crate::requests::cromwell_requests::CromwellRequestError;
 use crate::storage::gcloud_storage;
 use crate::storage::gcloud_storage::GCloudClient;
 use crate::util::{run_csv, temp_storage};
@@ -26,7 +28,7 @@ use crate::models::test::TestData;
 /// Struct for assembling reports from runs and submitting jobs to cromwell to fill them
 #[derive(Clone)]
 pub struct ReportBuilder {
-    cromwell_client: CromwellClient,
+    server_router: ServerRouter,
     gcloud_client: GCloudClient,
     config: ReportingConfig,
 }
@@ -44,7 +46,8 @@ pub enum Error {
     Cromwell(CromwellRequestError),
     Prohibited(String),
     Autosize(String),
-    CSV(run_csv::Error)
+    CSV(run_csv::Error),
+    Server(server_router::Error),
 }
 
 impl std::error::Error for Error {}
@@ -62,6 +65,7 @@ impl fmt::Display for Error {
             Error::Prohibited(e) => write!(f, "report_builder Error Exists {}", e),
             Error::Autosize(e) => write!(f, "report_builder Error Autosize {}", e),
             Error::CSV(e) => write!(f, "report_builder Error CSV {}", e),
+            Error::Server(e) => write!(f, "report_builder Error Server {}", e),
         }
     }
 }
@@ -108,6 +112,12 @@ impl From<run_csv::Error> for Error {
     }
 }
 
+impl From<server_router::Error> for Error {
+    fn from(e: server_router::Error) -> Error {
+        Error::Server(e)
+    }
+}
+
 /// The name of the workflow in the jupyter_report_generator_template.wdl file
 const GENERATOR_WORKFLOW_NAME: &'static str = "generate_report_file_workflow";
 
@@ -125,16 +135,16 @@ const GENERATOR_WORKFLOW_RUNTIME_ATTRS: [&'static str; 9] = [
 ];
 
 impl ReportBuilder {
-    /// Creates a new ReportBuilder that will use `cromwell_client` for communicating with cromwell,
+    /// Creates a new ReportBuilder that will use `server_router` for running report jobs,
     /// `gcloud_client` for uploading report templates to GCS, and with behavior determined by
     /// `config`
     pub fn new(
-        cromwell_client: CromwellClient,
+        server_router: ServerRouter,
         gcloud_client: GCloudClient,
         config: &ReportingConfig,
     ) -> ReportBuilder {
         ReportBuilder {
-            cromwell_client,
+            server_router,
             gcloud_client,
             config: config.to_owned(),
         }
@@ -262,13 +272,22 @@ impl ReportBuilder {
         let json_file = temp_storage::get_temp_file(&input_json.to_string().as_bytes())?;
         // Write the wdl to a file
         let wdl_file = temp_storage::get_temp_file(generator_wdl.as_bytes())?;
-        // Submit report generation job to cromwell
+        // Submit report generation job to the default cromwell server
+        let cromwell_server = String::from(self.server_router.default_server());
         let start_job_response = util::start_job_from_file(
-            &self.cromwell_client,
+            self.server_router.execution_client(Some(&cromwell_server))?,
             &wdl_file.path(),
             None,
             &json_file.path(),
             None,
+            WorkflowLanguageEnum::Wdl,
+            &WorkflowLabels {
+                stage: WorkflowStage::Report,
+                run_id: Some(run.run_id),
+                test_id: Some(run.test_id),
+                software_build_id: None,
+                report_id: Some(report.report_id),
+            },
         )
         .await?;
         // Insert run_report into the DB
@@ -280,6 +299,7 @@ impl ReportBuilder {
             results: None,
             created_by: created_by.clone(),
             finished_at: None,
+            cromwell_server: Some(cromwell_server),
         };
         Ok(RunReportData::create(conn, new_run_report)?)
     }
@@ -607,6 +627,7 @@ mod tests {
     use crate::manager::report_builder::{
         Error, ReportBuilder
     };
+    use crate::manager::server_router::ServerRouter;
     use crate::models::pipeline::{NewPipeline, PipelineData};
     use crate::models::report::{NewReport, ReportData};
     use crate::models::result::{NewResult, ResultData};
@@ -617,7 +638,6 @@ mod tests {
     use crate::models::template_report::{NewTemplateReport, TemplateReportData};
     use crate::models::template_result::{NewTemplateResult, TemplateResultData};
     use crate::models::test::{NewTest, TestData};
-    use crate::requests::cromwell_requests::CromwellClient;
     use crate::storage::gcloud_storage::GCloudClient;
     use crate::unit_test_util::{get_test_db_connection, load_default_config};
     use actix_web::client::Client;
@@ -636,6 +656,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -650,6 +673,9 @@ mod tests {
             eval_wdl: format!("{}/eval.wdl", mockito::server_url()),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -664,6 +690,10 @@ mod tests {
             eval_input_defaults: None,
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let test = TestData::create(conn, new_test).expect("Failed inserting test test");
@@ -826,6 +856,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
         };
 
         RunReportData::create(conn, new_run_report).expect("Failed inserting test run_report")
@@ -844,6 +875,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
         };
 
         RunReportData::create(conn, new_run_report).expect("Failed inserting test run_report")
@@ -877,7 +909,7 @@ mod tests {
         let config = load_default_config();
         // Get client
         let client = Client::default();
-        let cromwell_client: CromwellClient = CromwellClient::new(client, &mockito::server_url());
+        let server_router = ServerRouter::new(client, &load_default_config());
         // Get gcloud client mock, setting up return values for its functions that are called by
         // report builder
         let mut gcloud_client = GCloudClient::new(&String::from("Test"));
@@ -900,7 +932,7 @@ mod tests {
             },
         ));
         // Create and return the report builder
-        ReportBuilder::new(cromwell_client, gcloud_client, config.reporting().unwrap())
+        ReportBuilder::new(server_router, gcloud_client, config.reporting().unwrap())
     }
 
     #[actix_rt::test]
@@ -1385,6 +1417,7 @@ mod tests {
             errors: Some(json!([
                 "2004-10-19 10:23:54+02: Failed to do an unimportant thing"
             ])),
+            cromwell_server: None,
         };
 
         let disk_size = test_report_builder
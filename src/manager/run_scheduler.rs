@@ -0,0 +1,652 @@
+//! Defines functionality for starting runs of tests on the schedules defined for them
+//!
+//! The `run` function is meant to be called in its own thread.  It will run in a cycle checking
+//! the DB for enabled test schedules, starting runs for any schedules that have come due since
+//! they were last checked, and recording which schedule started each run.  Schedule occurrences
+//! that were missed (e.g. because CARROT was not running when they came due) are handled
+//! according to the configured catch-up policy
+
+use crate::config::{Config, ScheduleCatchUpPolicy, SchedulerConfig};
+use crate::db::DbPool;
+use crate::manager::server_router::ServerRouter;
+use crate::manager::test_runner;
+use crate::manager::test_runner::TestRunner;
+use crate::manager::util::{check_for_terminate_message, check_for_terminate_message_with_timeout};
+use crate::models::run_is_from_schedule::{NewRunIsFromSchedule, RunIsFromScheduleData};
+use crate::models::test_schedule::TestScheduleData;
+use crate::requests::test_resource_requests::TestResourceClient;
+use crate::storage::gcloud_storage::GCloudClient;
+use crate::util::cron_parsing;
+use crate::util::secrets::{self, SecretEncryptor};
+use actix_web::client::Client;
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use cron::Schedule;
+use diesel::PgConnection;
+use log::{debug, error, info};
+use std::fmt;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug)]
+pub enum Error {
+    DB(diesel::result::Error),
+    Cron(cron_parsing::Error),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DB(e) => write!(f, "Error DB {}", e),
+            Error::Cron(e) => write!(f, "Error Cron {}", e),
+        }
+    }
+}
+
+impl From<diesel::result::Error> for Error {
+    fn from(e: diesel::result::Error) -> Error {
+        Error::DB(e)
+    }
+}
+
+impl From<cron_parsing::Error> for Error {
+    fn from(e: cron_parsing::Error) -> Error {
+        Error::Cron(e)
+    }
+}
+
+/// A struct that handles starting runs for test schedules when they come due
+pub struct RunScheduler {
+    db_pool: DbPool,
+    config: SchedulerConfig,
+    channel_recv: mpsc::Receiver<()>,
+    test_runner: TestRunner,
+}
+
+/// Convenience function for initializing and running a run scheduler.  Takes `db_pool` for
+/// connecting to the DB, `carrot_config` for initializing the test runner, and `channel_recv` for
+/// receiving signals to terminate
+pub async fn init_and_run(
+    db_pool: DbPool,
+    carrot_config: Config,
+    channel_recv: mpsc::Receiver<()>,
+) {
+    // Make a client that'll be used for http requests
+    let http_client: Client = Client::default();
+    // Make a gcloud client for interacting with gcs
+    let gcloud_client: Option<GCloudClient> = carrot_config
+        .gcloud()
+        .map(|gcloud_config| GCloudClient::new(gcloud_config.gcloud_sa_key_file()));
+    // Create a test resource client and server router for the test runner
+    let test_resource_client: TestResourceClient =
+        TestResourceClient::new(http_client.clone(), gcloud_client);
+    let server_router: ServerRouter = ServerRouter::new(http_client, &carrot_config);
+    // Create a test runner
+    // Create a secret encryptor (if secrets are enabled) so the test runner can fill in secrets
+    let secret_encryptor: Option<SecretEncryptor> = secrets::get_secret_encryptor(&carrot_config);
+    let test_runner: TestRunner = match carrot_config.custom_image_build() {
+        Some(image_build_config) => TestRunner::new(
+            server_router,
+            test_resource_client,
+            Some(image_build_config.image_registry_host()),
+            carrot_config.status_manager().max_concurrent_runs(),
+            carrot_config.retry().clone(),
+            carrot_config.cromwell().workflow_option_defaults().cloned(),
+            secret_encryptor.clone(),
+        ),
+        None => TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            carrot_config.status_manager().max_concurrent_runs(),
+            carrot_config.retry().clone(),
+            carrot_config.cromwell().workflow_option_defaults().cloned(),
+            secret_encryptor.clone(),
+        ),
+    };
+    // Create a run scheduler and start it running
+    let run_scheduler: RunScheduler = RunScheduler::new(
+        db_pool,
+        carrot_config.scheduler().to_owned(),
+        channel_recv,
+        test_runner,
+    );
+    run_scheduler.run().await
+}
+
+impl RunScheduler {
+    /// Creates a new instance of RunScheduler which will use `db_pool` for connecting to the db,
+    /// `config` for determining how often to check schedules and how to handle missed runs,
+    /// `channel_recv` for checking for termination messages from its parent thread, and
+    /// `test_runner` for starting runs
+    pub fn new(
+        db_pool: DbPool,
+        config: SchedulerConfig,
+        channel_recv: mpsc::Receiver<()>,
+        test_runner: TestRunner,
+    ) -> RunScheduler {
+        RunScheduler {
+            db_pool,
+            config,
+            channel_recv,
+            test_runner,
+        }
+    }
+
+    /// Main loop function for this scheduler.  Queries DB for enabled test schedules, and starts
+    /// runs for any that are due
+    pub async fn run(&self) {
+        // Keep track of when we last checked for due runs, so we know which occurrences came due
+        // since then.  For the first check, pretend we checked one wait period ago
+        let mut last_check_time: NaiveDateTime = Utc::now().naive_utc()
+            - ChronoDuration::seconds(self.config.schedule_check_wait_time_in_secs() as i64);
+        // Main loop
+        loop {
+            // Get the time we started this so we can sleep for a specified time between queries
+            let query_time = Instant::now();
+            let check_time: NaiveDateTime = Utc::now().naive_utc();
+            debug!("Starting scheduled run check");
+            let conn = self.db_pool.get().unwrap();
+            // Query DB for enabled schedules
+            match TestScheduleData::find_enabled(&conn) {
+                Ok(test_schedules) => {
+                    debug!("Checking {} enabled test schedules", test_schedules.len());
+                    for test_schedule in test_schedules {
+                        // Check for message from main thread to exit
+                        if check_for_terminate_message(&self.channel_recv).is_some() {
+                            return;
+                        };
+                        if let Err(e) = self
+                            .start_scheduled_runs(
+                                &conn,
+                                &test_schedule,
+                                last_check_time,
+                                check_time,
+                            )
+                            .await
+                        {
+                            error!(
+                                "Encountered error while starting scheduled runs for test schedule {}: {}",
+                                test_schedule.test_schedule_id, e
+                            );
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to retrieve test schedules due to: {}", e);
+                }
+            }
+            last_check_time = check_time;
+
+            debug!("Finished scheduled run check.  Run scheduler sleeping . . .");
+            // While the time since we last started a check hasn't exceeded
+            // SCHEDULE_CHECK_WAIT_TIME_IN_SECS, check for signal from main thread to terminate
+            let wait_timeout = Duration::new(self.config.schedule_check_wait_time_in_secs(), 0)
+                .checked_sub(Instant::now() - query_time);
+            if let Some(timeout) = wait_timeout {
+                if check_for_terminate_message_with_timeout(&self.channel_recv, timeout).is_some() {
+                    return;
+                }
+            }
+            // Check for message from main thread to exit
+            if check_for_terminate_message(&self.channel_recv).is_some() {
+                return;
+            }
+        }
+    }
+
+    /// Starts runs for `test_schedule` for any of its occurrences that have come due since it was
+    /// last scheduled, according to the catch-up policy in `self.config`, and records that the
+    /// runs were started by `test_schedule`.  `last_check_time` is the time at which the scheduler
+    /// last checked for due runs and `check_time` is the time of the current check.  Updates
+    /// `test_schedule`'s last_scheduled_at to its most recent due occurrence, whether or not runs
+    /// were started for it.  Failures to start individual runs are logged.  Returns an error if
+    /// the cron expression for `test_schedule` cannot be parsed or there is an issue updating the
+    /// DB
+    async fn start_scheduled_runs(
+        &self,
+        conn: &PgConnection,
+        test_schedule: &TestScheduleData,
+        last_check_time: NaiveDateTime,
+        check_time: NaiveDateTime,
+    ) -> Result<(), Error> {
+        let schedule = cron_parsing::parse_cron_expression(&test_schedule.cron_expression)?;
+        // Only occurrences after the last one we scheduled (or after the schedule was created, if
+        // we haven't scheduled any yet) are due
+        let last_scheduled_at: NaiveDateTime = test_schedule
+            .last_scheduled_at
+            .unwrap_or(test_schedule.created_at);
+        let most_recent_occurrence: NaiveDateTime = match cron_parsing::get_most_recent_occurrences(
+            &schedule,
+            last_scheduled_at,
+            check_time,
+            1,
+        )
+        .pop()
+        {
+            Some(occurrence) => occurrence,
+            // If nothing's due, there's nothing to do
+            None => return Ok(()),
+        };
+        // Figure out which of the due occurrences we should actually start runs for
+        let occurrences_to_run: Vec<NaiveDateTime> = get_occurrences_to_run(
+            &schedule,
+            self.config.catch_up_policy(),
+            self.config.max_catch_up_runs(),
+            last_scheduled_at,
+            last_check_time,
+            check_time,
+        );
+        if occurrences_to_run.is_empty() {
+            info!(
+                "Skipping missed runs for test schedule {}",
+                test_schedule.test_schedule_id
+            );
+        }
+        for occurrence in occurrences_to_run {
+            info!(
+                "Starting run for test schedule {} scheduled for {}",
+                test_schedule.test_schedule_id, occurrence
+            );
+            if let Err(e) = self
+                .start_scheduled_run(conn, test_schedule, occurrence)
+                .await
+            {
+                error!(
+                    "Failed to start run for test schedule {} scheduled for {} due to: {}",
+                    test_schedule.test_schedule_id, occurrence, e
+                );
+            }
+        }
+        // Mark that we've handled everything up to the most recent occurrence
+        TestScheduleData::update_last_scheduled_at(
+            conn,
+            test_schedule.test_schedule_id,
+            most_recent_occurrence,
+        )?;
+
+        Ok(())
+    }
+
+    /// Starts a run of the test for `test_schedule` using the inputs and options specified by
+    /// `test_schedule` and records that it was started by `test_schedule` for the occurrence at
+    /// `scheduled_for`.  Returns an error if starting the run fails
+    async fn start_scheduled_run(
+        &self,
+        conn: &PgConnection,
+        test_schedule: &TestScheduleData,
+        scheduled_for: NaiveDateTime,
+    ) -> Result<(), test_runner::Error> {
+        let run = self
+            .test_runner
+            .create_run(
+                conn,
+                &test_schedule.test_id.to_string(),
+                None,
+                test_schedule.test_input.clone(),
+                test_schedule.test_options.clone(),
+                test_schedule.eval_input.clone(),
+                test_schedule.eval_options.clone(),
+                test_schedule.created_by.clone(),
+                None,
+                None,
+            )
+            .await?;
+        RunIsFromScheduleData::create(
+            conn,
+            NewRunIsFromSchedule {
+                run_id: run.run_id,
+                test_schedule_id: test_schedule.test_schedule_id,
+                scheduled_for,
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Returns the occurrences of `schedule` after `last_scheduled_at` and no later than `check_time`
+/// that runs should be started for, according to `catch_up_policy`.  Occurrences after
+/// `last_check_time` came due since the last check, so they are not considered missed
+fn get_occurrences_to_run(
+    schedule: &Schedule,
+    catch_up_policy: ScheduleCatchUpPolicy,
+    max_catch_up_runs: u32,
+    last_scheduled_at: NaiveDateTime,
+    last_check_time: NaiveDateTime,
+    check_time: NaiveDateTime,
+) -> Vec<NaiveDateTime> {
+    match catch_up_policy {
+        // Only run the most recent occurrence, and only if it came due since the last check
+        ScheduleCatchUpPolicy::Skip => cron_parsing::get_most_recent_occurrences(
+            schedule,
+            std::cmp::max(last_scheduled_at, last_check_time),
+            check_time,
+            1,
+        ),
+        // Run the most recent occurrence in place of any that were missed
+        ScheduleCatchUpPolicy::RunOnce => {
+            cron_parsing::get_most_recent_occurrences(schedule, last_scheduled_at, check_time, 1)
+        }
+        // Run as many of the missed occurrences as we're allowed
+        ScheduleCatchUpPolicy::RunAll => cron_parsing::get_most_recent_occurrences(
+            schedule,
+            last_scheduled_at,
+            check_time,
+            max_catch_up_runs as usize,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetryConfig;
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{RunData, RunQuery};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::models::test_schedule::NewTestSchedule;
+    use crate::unit_test_util::*;
+    use chrono::NaiveDate;
+    use serde_json::json;
+    use std::fs::read_to_string;
+
+    fn insert_test_test_schedule(conn: &PgConnection) -> TestScheduleData {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's test template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: None,
+            test_wdl: format!("{}/test_no_software_params", mockito::server_url()),
+            test_wdl_dependencies: None,
+            eval_wdl: format!("{}/eval_no_software_params", mockito::server_url()),
+            eval_wdl_dependencies: None,
+            created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's test test"),
+            template_id: template.template_id,
+            description: None,
+            test_input_defaults: Some(json!({"test_test.in_pleasantry":"Yo"})),
+            test_option_defaults: None,
+            eval_input_defaults: Some(json!({"test_test.in_verb":"yelled"})),
+            eval_option_defaults: None,
+            created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        let new_test_schedule = NewTestSchedule {
+            test_id: test.test_id,
+            cron_expression: String::from("0 * * * *"),
+            test_input: Some(json!({"test_test.in_user_name":"Kevin"})),
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: true,
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        let test_schedule = TestScheduleData::create(conn, new_test_schedule)
+            .expect("Failed inserting test test_schedule");
+
+        // Pretend we last scheduled a run a few hours ago
+        TestScheduleData::update_last_scheduled_at(
+            conn,
+            test_schedule.test_schedule_id,
+            Utc::now().naive_utc() - ChronoDuration::hours(3),
+        )
+        .expect("Failed to update last_scheduled_at for test test_schedule")
+    }
+
+    fn create_test_run_scheduler(
+        catch_up_policy: ScheduleCatchUpPolicy,
+        max_catch_up_runs: u32,
+    ) -> RunScheduler {
+        let (_, channel_recv) = mpsc::channel();
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let test_runner = TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+        RunScheduler::new(
+            get_test_db_pool(),
+            SchedulerConfig::new(60, catch_up_policy, max_catch_up_runs),
+            channel_recv,
+            test_runner,
+        )
+    }
+
+    #[test]
+    fn get_occurrences_to_run_skip() {
+        let schedule = cron_parsing::parse_cron_expression("0 * * * *").unwrap();
+        let last_scheduled_at = NaiveDate::from_ymd(2022, 4, 1).and_hms(0, 0, 0);
+        // Last check was before an occurrence, so we should start it
+        let occurrences = get_occurrences_to_run(
+            &schedule,
+            ScheduleCatchUpPolicy::Skip,
+            10,
+            last_scheduled_at,
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(2, 59, 30),
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 0, 30),
+        );
+        assert_eq!(
+            occurrences,
+            vec![NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 0, 0)]
+        );
+        // Last check was after all the occurrences, so they were all missed
+        let occurrences = get_occurrences_to_run(
+            &schedule,
+            ScheduleCatchUpPolicy::Skip,
+            10,
+            last_scheduled_at,
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 10, 0),
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 11, 0),
+        );
+        assert!(occurrences.is_empty());
+    }
+
+    #[test]
+    fn get_occurrences_to_run_run_once() {
+        let schedule = cron_parsing::parse_cron_expression("0 * * * *").unwrap();
+        let occurrences = get_occurrences_to_run(
+            &schedule,
+            ScheduleCatchUpPolicy::RunOnce,
+            10,
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(0, 0, 0),
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 10, 0),
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 11, 0),
+        );
+        assert_eq!(
+            occurrences,
+            vec![NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn get_occurrences_to_run_run_all() {
+        let schedule = cron_parsing::parse_cron_expression("0 * * * *").unwrap();
+        let occurrences = get_occurrences_to_run(
+            &schedule,
+            ScheduleCatchUpPolicy::RunAll,
+            2,
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(0, 0, 0),
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 10, 0),
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 11, 0),
+        );
+        assert_eq!(
+            occurrences,
+            vec![
+                NaiveDate::from_ymd(2022, 4, 1).and_hms(2, 0, 0),
+                NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 0, 0)
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn start_scheduled_runs_run_all() {
+        let test_run_scheduler = create_test_run_scheduler(ScheduleCatchUpPolicy::RunAll, 2);
+        let conn = test_run_scheduler.db_pool.get().unwrap();
+
+        let test_test_schedule = insert_test_test_schedule(&conn);
+
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test_no_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/test_wdl_no_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(2)
+            .create();
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .expect(2)
+            .create();
+
+        let check_time = Utc::now().naive_utc();
+        test_run_scheduler
+            .start_scheduled_runs(
+                &conn,
+                &test_test_schedule,
+                check_time - ChronoDuration::seconds(60),
+                check_time,
+            )
+            .await
+            .unwrap();
+
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        let runs = RunData::find(
+            &conn,
+            RunQuery {
+                pipeline_id: None,
+                template_id: None,
+                test_id: Some(test_test_schedule.test_id),
+                run_group_id: None,
+                name: None,
+                status: None,
+                test_input: None,
+                test_options: None,
+                eval_input: None,
+                eval_options: None,
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                created_before: None,
+                created_after: None,
+                created_by: None,
+                finished_before: None,
+                finished_after: None,
+                sort: None,
+                limit: None,
+                offset: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(runs.len(), 2);
+        for run in runs {
+            assert_eq!(run.status, RunStatusEnum::TestSubmitted);
+            assert_eq!(
+                run.test_input,
+                json!({"test_test.in_pleasantry":"Yo", "test_test.in_user_name":"Kevin"})
+            );
+            let run_is_from_schedule = RunIsFromScheduleData::find_by_run_id(&conn, run.run_id)
+                .expect("Failed to retrieve run_is_from_schedule for scheduled run");
+            assert_eq!(
+                run_is_from_schedule.test_schedule_id,
+                test_test_schedule.test_schedule_id
+            );
+        }
+
+        let updated_test_schedule = TestScheduleData::find_by_test_and_id(
+            &conn,
+            test_test_schedule.test_id,
+            test_test_schedule.test_schedule_id,
+        )
+        .unwrap();
+        let expected_last_scheduled_at = cron_parsing::get_most_recent_occurrences(
+            &cron_parsing::parse_cron_expression("0 * * * *").unwrap(),
+            test_test_schedule.last_scheduled_at.unwrap(),
+            check_time,
+            1,
+        )
+        .pop();
+        assert_eq!(
+            updated_test_schedule.last_scheduled_at,
+            expected_last_scheduled_at
+        );
+    }
+
+    #[actix_rt::test]
+    async fn start_scheduled_runs_skip() {
+        let test_run_scheduler = create_test_run_scheduler(ScheduleCatchUpPolicy::Skip, 10);
+        let conn = test_run_scheduler.db_pool.get().unwrap();
+
+        let test_test_schedule = insert_test_test_schedule(&conn);
+
+        // Nothing should be submitted because the due occurrences were all missed
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .expect(0)
+            .create();
+
+        // Use the same time for the last check and this one so nothing came due in between
+        let check_time = Utc::now().naive_utc();
+        test_run_scheduler
+            .start_scheduled_runs(&conn, &test_test_schedule, check_time, check_time)
+            .await
+            .unwrap();
+
+        cromwell_mock.assert();
+
+        let updated_test_schedule = TestScheduleData::find_by_test_and_id(
+            &conn,
+            test_test_schedule.test_id,
+            test_test_schedule.test_schedule_id,
+        )
+        .unwrap();
+        assert!(
+            updated_test_schedule.last_scheduled_at.unwrap()
+                > test_test_schedule.last_scheduled_at.unwrap()
+        );
+    }
+}
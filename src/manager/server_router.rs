@@ -0,0 +1,427 @@
+//! Defines functionality for choosing which of the configured cromwell servers a workflow should
+//! be run on, and for getting the clients for communicating with them
+//!
+//! Pipelines, templates, and tests can each specify the name of a server or of a pool of
+//! equivalent servers.  A test's choice overrides its template's, which overrides its pipeline's,
+//! and anything that doesn't choose is run on the default server.  When a pool is chosen, the
+//! server in the pool with the fewest active runs is used
+
+use crate::config::Config;
+use crate::models::pipeline::PipelineData;
+use crate::models::run::RunData;
+use crate::models::template::TemplateData;
+use crate::models::test::TestData;
+use crate::requests::cromwell_requests::CromwellClient;
+use crate::requests::execution_backend::{self, ExecutionBackend};
+use actix_web::client::Client;
+use diesel::PgConnection;
+use log::debug;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// Struct for choosing servers for workflows and getting the clients for them
+#[derive(Clone)]
+pub struct ServerRouter {
+    /// Clients for running workflows on each of the servers, keyed by server name
+    execution_clients: HashMap<String, Rc<dyn ExecutionBackend>>,
+    /// Clients for the cromwell-specific features (logs, failures, and reconciliation) for each of
+    /// the servers, keyed by server name
+    cromwell_clients: HashMap<String, CromwellClient>,
+    /// The names of the servers, in the order they were configured, starting with the default
+    server_names: Vec<String>,
+    /// Pools of equivalent servers, keyed by pool name
+    pools: HashMap<String, Vec<String>>,
+    /// Whether workflows can be run on servers other than the default (they can't with WES)
+    named_servers_enabled: bool,
+}
+
+/// Error type for possible errors returned by choosing a server or getting its client
+#[derive(Debug)]
+pub enum Error {
+    DB(diesel::result::Error),
+    UnknownServer(String),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::DB(e) => write!(f, "Error DB {}", e),
+            Error::UnknownServer(name) => write!(
+                f,
+                "Error UnknownServer No cromwell server or pool is configured with the name {}",
+                name
+            ),
+        }
+    }
+}
+
+// Implementing From for each of the error types so they map more easily
+impl From<diesel::result::Error> for Error {
+    fn from(e: diesel::result::Error) -> Error {
+        Error::DB(e)
+    }
+}
+
+impl ServerRouter {
+    /// Creates a new ServerRouter that will use `client` to communicate with the servers specified
+    /// in `carrot_config`.  If the execution backend is WES, all workflows are run on the WES
+    /// server, so the additional cromwell servers are only used for cromwell-specific features
+    pub fn new(client: Client, carrot_config: &Config) -> ServerRouter {
+        let cromwell_config = carrot_config.cromwell();
+        let named_servers_enabled = !carrot_config.execution_backend().is_wes();
+        let default_server = cromwell_config.default_server_name().clone();
+        let mut execution_clients: HashMap<String, Rc<dyn ExecutionBackend>> = HashMap::new();
+        let mut cromwell_clients: HashMap<String, CromwellClient> = HashMap::new();
+        let mut server_names: Vec<String> = vec![default_server.clone()];
+        execution_clients.insert(
+            default_server.clone(),
+            execution_backend::create_execution_backend(client.clone(), carrot_config),
+        );
+        cromwell_clients.insert(
+            default_server,
+            CromwellClient::new(
+                client.clone(),
+                cromwell_config.address(),
+                cromwell_config.instance_name(),
+                cromwell_config.auth().cloned(),
+            ),
+        );
+        for server in cromwell_config.servers() {
+            let cromwell_client = CromwellClient::new(
+                client.clone(),
+                server.address(),
+                cromwell_config.instance_name(),
+                server.auth().cloned(),
+            );
+            if named_servers_enabled {
+                execution_clients.insert(server.name().clone(), Rc::new(cromwell_client.clone()));
+            }
+            cromwell_clients.insert(server.name().clone(), cromwell_client);
+            server_names.push(server.name().clone());
+        }
+        ServerRouter {
+            execution_clients,
+            cromwell_clients,
+            server_names,
+            pools: cromwell_config.pools().clone(),
+            named_servers_enabled,
+        }
+    }
+
+    /// Returns the name of the default server
+    pub fn default_server(&self) -> &str {
+        &self.server_names[0]
+    }
+
+    /// Returns the names of all the configured servers, starting with the default
+    pub fn server_names(&self) -> &Vec<String> {
+        &self.server_names
+    }
+
+    /// Returns the client for running workflows on the server named `server`, or on the default
+    /// server if `server` is None (which is the case for anything submitted before servers were
+    /// named).  Returns an error if there is no server that can run workflows with that name
+    pub fn execution_client(&self, server: Option<&str>) -> Result<&dyn ExecutionBackend, Error> {
+        let server = server.unwrap_or(self.default_server());
+        self.execution_clients
+            .get(server)
+            .map(|execution_client| execution_client.as_ref())
+            .ok_or_else(|| Error::UnknownServer(String::from(server)))
+    }
+
+    /// Returns the client for cromwell-specific features for the server named `server`, or for the
+    /// default server if `server` is None.  Returns an error if there is no server with that name
+    pub fn cromwell_client(&self, server: Option<&str>) -> Result<&CromwellClient, Error> {
+        let server = server.unwrap_or(self.default_server());
+        self.cromwell_clients
+            .get(server)
+            .ok_or_else(|| Error::UnknownServer(String::from(server)))
+    }
+
+    /// Chooses the server to run the workflows for a run of `test` on, based on the server or pool
+    /// specified by `test`, or, if it doesn't specify one, its template, or, if that doesn't
+    /// specify one, its pipeline (see `choose_server`).  Uses `conn` to retrieve the template and
+    /// pipeline and to count the active runs if a pool is specified.  Returns the name of the
+    /// server or an error if retrieving data from the DB fails or the specified name doesn't match
+    /// a server or pool
+    pub fn choose_server_for_test(
+        &self,
+        conn: &PgConnection,
+        test: &TestData,
+    ) -> Result<String, Error> {
+        let requested: Option<String> = match &test.cromwell_server {
+            Some(server) => Some(server.clone()),
+            None => {
+                let template = TemplateData::find_by_id(conn, test.template_id)?;
+                match template.cromwell_server {
+                    Some(server) => Some(server),
+                    None => PipelineData::find_by_id(conn, template.pipeline_id)?.cromwell_server,
+                }
+            }
+        };
+        self.choose_server(conn, requested.as_deref())
+    }
+
+    /// Chooses the server for `requested`, which can be the name of a server or of a pool.  If it
+    /// is a pool, uses `conn` to count the active runs on each server in the pool, and chooses the
+    /// one with the fewest (or the first configured, if there's a tie).  If `requested` is None,
+    /// chooses the default server.  If the execution backend is WES, the default server is always
+    /// chosen.  Returns the name of the server or an error if counting runs fails or `requested`
+    /// doesn't match a server or pool
+    pub fn choose_server(
+        &self,
+        conn: &PgConnection,
+        requested: Option<&str>,
+    ) -> Result<String, Error> {
+        let requested = match requested {
+            Some(requested) if self.named_servers_enabled => requested,
+            _ => return Ok(String::from(self.default_server())),
+        };
+        if self.execution_clients.contains_key(requested) {
+            return Ok(String::from(requested));
+        }
+        let pool = match self.pools.get(requested) {
+            Some(pool) => pool,
+            None => return Err(Error::UnknownServer(String::from(requested))),
+        };
+        let mut least_loaded: Option<(&String, i64)> = None;
+        for server in pool {
+            let active_runs =
+                RunData::count_active_for_server(conn, server, server == self.default_server())?;
+            debug!(
+                "Cromwell server {} in pool {} has {} active runs",
+                server, requested, active_runs
+            );
+            if least_loaded.map_or(true, |(_, fewest)| active_runs < fewest) {
+                least_loaded = Some((server, active_runs));
+            }
+        }
+        match least_loaded {
+            Some((server, _)) => Ok(server.clone()),
+            None => Err(Error::UnknownServer(String::from(requested))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CromwellConfig, CromwellServerConfig};
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::models::pipeline::NewPipeline;
+    use crate::models::run::{NewRun, RunChangeset};
+    use crate::models::template::NewTemplate;
+    use crate::models::test::NewTest;
+    use crate::unit_test_util::{get_test_db_connection, load_default_config};
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn create_test_server_router() -> ServerRouter {
+        let mut config = load_default_config();
+        let mut pools: HashMap<String, Vec<String>> = HashMap::new();
+        pools.insert(
+            String::from("gcp"),
+            vec![String::from("default"), String::from("gcp-2")],
+        );
+        config.set_cromwell(CromwellConfig::new(
+            mockito::server_url(),
+            String::from("carrot"),
+            String::from("default"),
+            vec![
+                CromwellServerConfig::new(
+                    String::from("hpc"),
+                    String::from("http://hpc:8000"),
+                    None,
+                ),
+                CromwellServerConfig::new(
+                    String::from("gcp-2"),
+                    String::from("http://gcp-2:8000"),
+                    None,
+                ),
+            ],
+            pools,
+            None,
+            None,
+        ));
+        ServerRouter::new(Client::default(), &config)
+    }
+
+    fn insert_test_test_with_servers(
+        conn: &PgConnection,
+        pipeline_server: Option<&str>,
+        template_server: Option<&str>,
+        test_server: Option<&str>,
+    ) -> TestData {
+        let pipeline = PipelineData::create(
+            conn,
+            NewPipeline {
+                name: format!("Kevin's Pipeline {}", Uuid::new_v4()),
+                description: None,
+                created_by: None,
+                max_concurrent_runs: None,
+                cromwell_server: pipeline_server.map(String::from),
+                workflow_option_defaults: None,
+            },
+        )
+        .expect("Failed inserting test pipeline");
+        let template = TemplateData::create(
+            conn,
+            NewTemplate {
+                name: format!("Kevin's Template {}", Uuid::new_v4()),
+                pipeline_id: pipeline.pipeline_id,
+                description: None,
+                test_wdl: String::from("testtest"),
+                test_wdl_dependencies: None,
+                eval_wdl: String::from("evaltest"),
+                eval_wdl_dependencies: None,
+                created_by: None,
+                cromwell_server: template_server.map(String::from),
+                workflow_option_defaults: None,
+                workflow_language: None,
+            },
+        )
+        .expect("Failed inserting test template");
+        TestData::create(
+            conn,
+            NewTest {
+                name: format!("Kevin's Test {}", Uuid::new_v4()),
+                template_id: template.template_id,
+                description: None,
+                test_input_defaults: None,
+                test_option_defaults: None,
+                eval_input_defaults: None,
+                eval_option_defaults: None,
+                created_by: None,
+                max_concurrent_runs: None,
+                priority: None,
+                max_runtime_in_secs: None,
+                cromwell_server: test_server.map(String::from),
+            },
+        )
+        .expect("Failed inserting test test")
+    }
+
+    fn insert_test_running_run_on_server(conn: &PgConnection, test_id: Uuid, server: &str) {
+        let run = RunData::create(
+            conn,
+            NewRun {
+                test_id,
+                name: format!("Kevin's run on {} {}", server, Uuid::new_v4()),
+                status: RunStatusEnum::TestRunning,
+                test_input: json!({}),
+                test_options: None,
+                eval_input: json!({}),
+                eval_options: None,
+                test_cromwell_job_id: Some(String::from("123456789")),
+                eval_cromwell_job_id: None,
+                created_by: None,
+                finished_at: None,
+            },
+        )
+        .expect("Failed inserting test run");
+        RunData::update(
+            conn,
+            run.run_id,
+            RunChangeset {
+                name: None,
+                status: None,
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                finished_at: None,
+                cromwell_server: Some(String::from(server)),
+            },
+        )
+        .expect("Failed updating test run");
+    }
+
+    #[actix_rt::test]
+    async fn test_choose_server_for_test_default() {
+        let conn = get_test_db_connection();
+        let router = create_test_server_router();
+
+        let test = insert_test_test_with_servers(&conn, None, None, None);
+
+        assert_eq!(
+            router.choose_server_for_test(&conn, &test).unwrap(),
+            "default"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_choose_server_for_test_overrides() {
+        let conn = get_test_db_connection();
+        let router = create_test_server_router();
+
+        let test = insert_test_test_with_servers(&conn, Some("hpc"), None, None);
+        assert_eq!(router.choose_server_for_test(&conn, &test).unwrap(), "hpc");
+
+        let test = insert_test_test_with_servers(&conn, Some("hpc"), Some("gcp-2"), None);
+        assert_eq!(
+            router.choose_server_for_test(&conn, &test).unwrap(),
+            "gcp-2"
+        );
+
+        let test =
+            insert_test_test_with_servers(&conn, Some("hpc"), Some("gcp-2"), Some("default"));
+        assert_eq!(
+            router.choose_server_for_test(&conn, &test).unwrap(),
+            "default"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_choose_server_pool_least_loaded() {
+        let conn = get_test_db_connection();
+        let router = create_test_server_router();
+
+        let test = insert_test_test_with_servers(&conn, None, None, Some("gcp"));
+        // With no active runs, the first server in the pool is chosen
+        assert_eq!(
+            router.choose_server_for_test(&conn, &test).unwrap(),
+            "default"
+        );
+        // Once the default server is busier, the other one is chosen
+        insert_test_running_run_on_server(&conn, test.test_id, "default");
+        assert_eq!(
+            router.choose_server_for_test(&conn, &test).unwrap(),
+            "gcp-2"
+        );
+        // Runs on servers outside the pool don't count
+        insert_test_running_run_on_server(&conn, test.test_id, "hpc");
+        insert_test_running_run_on_server(&conn, test.test_id, "gcp-2");
+        insert_test_running_run_on_server(&conn, test.test_id, "gcp-2");
+        assert_eq!(
+            router.choose_server_for_test(&conn, &test).unwrap(),
+            "default"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_choose_server_unknown() {
+        let conn = get_test_db_connection();
+        let router = create_test_server_router();
+
+        let error = router.choose_server(&conn, Some("aws")).unwrap_err();
+        assert!(matches!(error, Error::UnknownServer(name) if name == "aws"));
+    }
+
+    #[actix_rt::test]
+    async fn test_execution_client() {
+        let router = create_test_server_router();
+
+        assert!(router.execution_client(None).is_ok());
+        assert!(router.execution_client(Some("hpc")).is_ok());
+        assert!(matches!(
+            router.execution_client(Some("gcp")),
+            Err(Error::UnknownServer(_))
+        ));
+        assert!(router.cromwell_client(Some("hpc")).is_ok());
+        assert!(matches!(
+            router.cromwell_client(Some("aws")),
+            Err(Error::UnknownServer(_))
+        ));
+    }
+}
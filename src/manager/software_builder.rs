@@ -1,15 +1,17 @@
 //! This module contains functions for managing software builds
 
 use crate::config::CustomImageBuildConfig;
-use crate::custom_sql_types::BuildStatusEnum;
+use crate::custom_sql_types::{BuildStatusEnum, WorkflowLanguageEnum};
+use crate::manager::server_router::{self, ServerRouter};
 use crate::manager::util;
+use crate::manager::util::{WorkflowLabels, WorkflowStage};
 use crate::models::software_build::{
     NewSoftwareBuild, SoftwareBuildChangeset, SoftwareBuildData, SoftwareBuildQuery,
 };
 use crate::models::software_version::{
     NewSoftwareVersion, SoftwareVersionData, SoftwareVersionQuery,
 };
-use crate::requests::cromwell_requests::{CromwellClient, CromwellRequestError};
+use crate::requests::cromwell_requests::CromwellRequestError;
 use crate::util::temp_storage;
 use diesel::PgConnection;
 use serde_json::json;
@@ -19,7 +21,7 @@ use uuid::Uuid;
 
 /// Struct for handling setting up and starting software builds
 pub struct SoftwareBuilder {
-    cromwell_client: CromwellClient,
+    server_router: ServerRouter,
     config: CustomImageBuildConfig,
 }
 
@@ -28,6 +30,7 @@ pub enum Error {
     DB(diesel::result::Error),
     Cromwell(CromwellRequestError),
     TempFile(std::io::Error),
+    Server(server_router::Error),
 }
 
 impl std::error::Error for Error {}
@@ -38,6 +41,7 @@ impl fmt::Display for Error {
             Error::DB(e) => write!(f, "Error DB {}", e),
             Error::Cromwell(e) => write!(f, "Error Cromwell {}", e),
             Error::TempFile(e) => write!(f, "Error TempFile {}", e),
+            Error::Server(e) => write!(f, "Error Server {}", e),
         }
     }
 }
@@ -60,20 +64,24 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl From<server_router::Error> for Error {
+    fn from(e: server_router::Error) -> Error {
+        Error::Server(e)
+    }
+}
+
 impl SoftwareBuilder {
-    /// Creates a new SoftwareBuilder that will use `cromwell_client` for dispatching build jobs,
+    /// Creates a new SoftwareBuilder that will use `server_router` for dispatching build jobs,
     /// with behavior set by `config`
-    pub fn new(
-        cromwell_client: CromwellClient,
-        config: &CustomImageBuildConfig,
-    ) -> SoftwareBuilder {
+    pub fn new(server_router: ServerRouter, config: &CustomImageBuildConfig) -> SoftwareBuilder {
         SoftwareBuilder {
-            cromwell_client,
+            server_router,
             config: config.to_owned(),
         }
     }
-    /// Starts a cromwell job for building the software associated with the software_build specified
-    /// by `software_build_id` and updates the status of the software_build to `Submitted`
+    /// Starts a cromwell job on the default cromwell server for building the software associated
+    /// with the software_build specified by `software_build_id` and updates the status of the
+    /// software_build to `Submitted`
     pub async fn start_software_build(
         &self,
         conn: &PgConnection,
@@ -125,12 +133,22 @@ impl SoftwareBuilder {
         let json_file = temp_storage::get_temp_file(&json_to_submit.to_string().as_bytes())?;
 
         // Send job request to cromwell
+        let cromwell_server = String::from(self.server_router.default_server());
         let start_job_response = util::start_job_from_file(
-            &self.cromwell_client,
+            self.server_router
+                .execution_client(Some(&cromwell_server))?,
             wdl_file_path,
             None,
             &json_file.path(),
             None,
+            WorkflowLanguageEnum::Wdl,
+            &WorkflowLabels {
+                stage: WorkflowStage::Build,
+                run_id: None,
+                test_id: None,
+                software_build_id: Some(software_build_id),
+                report_id: None,
+            },
         )
         .await?;
 
@@ -140,6 +158,7 @@ impl SoftwareBuilder {
             finished_at: None,
             build_job_id: Some(start_job_response.id),
             status: Some(BuildStatusEnum::Submitted),
+            cromwell_server: Some(cromwell_server),
         };
 
         Ok(SoftwareBuildData::update(
@@ -264,14 +283,14 @@ pub fn get_or_create_software_build(
 mod tests {
     use crate::config::CustomImageBuildConfig;
     use crate::custom_sql_types::BuildStatusEnum;
+    use crate::manager::server_router::ServerRouter;
     use crate::manager::software_builder::{
         get_or_create_software_build, get_or_create_software_version, SoftwareBuilder,
     };
     use crate::models::software::{NewSoftware, SoftwareData};
     use crate::models::software_build::{NewSoftwareBuild, SoftwareBuildData};
     use crate::models::software_version::{NewSoftwareVersion, SoftwareVersionData};
-    use crate::requests::cromwell_requests::CromwellClient;
-    use crate::unit_test_util::get_test_db_connection;
+    use crate::unit_test_util::{get_test_db_connection, load_default_config};
     use actix_web::client::Client;
     use diesel::PgConnection;
     use serde_json::json;
@@ -482,10 +501,10 @@ mod tests {
 
         let conn = get_test_db_connection();
         let client = Client::default();
-        let cromwell_client = CromwellClient::new(client, &mockito::server_url());
+        let server_router = ServerRouter::new(client, &load_default_config());
         let config: CustomImageBuildConfig =
             CustomImageBuildConfig::new(String::from("https://example.com"), None);
-        let test_software_builder: SoftwareBuilder = SoftwareBuilder::new(cromwell_client, &config);
+        let test_software_builder: SoftwareBuilder = SoftwareBuilder::new(server_router, &config);
 
         let test_software_build = insert_test_software_build_created(&conn);
 
@@ -5,39 +5,52 @@
 //! Cromwell, and then updating accordingly.  It will also pull result data and add that to the DB
 //! for any tests runs that complete
 
-use crate::config::{Config, StatusManagerConfig};
-use crate::custom_sql_types::{BuildStatusEnum, ReportStatusEnum, RunStatusEnum};
+use crate::config::{Config, CreatedRecoveryPolicy, StatusManagerConfig};
+use crate::custom_sql_types::{
+    BuildStatusEnum, ReportStatusEnum, RunCallWorkflowEnum, RunStatusEnum,
+};
 use crate::db::DbPool;
+use crate::manager::log_retriever;
 use crate::manager::notification_handler::NotificationHandler;
 use crate::manager::report_builder;
 use crate::manager::report_builder::ReportBuilder;
+use crate::manager::server_router::ServerRouter;
 use crate::manager::software_builder::SoftwareBuilder;
 use crate::manager::test_runner::{RunBuildStatus, TestRunner};
 use crate::manager::util::{check_for_terminate_message, check_for_terminate_message_with_timeout};
-use crate::manager::{notification_handler, software_builder, test_runner};
+use crate::manager::{notification_handler, server_router, software_builder, test_runner};
 use crate::models::report::ReportData;
 use crate::models::run::{RunChangeset, RunData};
+use crate::models::run_call::{NewRunCall, RunCallData};
 use crate::models::run_report::{RunReportChangeset, RunReportData};
-use crate::models::run_result::{NewRunResult, RunResultData};
+use crate::models::run_result::RunResultData;
+use crate::models::run_start_attempt::RunStartAttemptData;
 use crate::models::software_build::{SoftwareBuildChangeset, SoftwareBuildData};
 use crate::models::template_result::TemplateResultData;
+use crate::models::test::TestData;
 use crate::notifications::emailer::Emailer;
 use crate::notifications::github_commenter::GithubCommenter;
-use crate::requests::cromwell_requests;
-use crate::requests::cromwell_requests::CromwellClient;
 use crate::requests::github_requests::GithubClient;
 use crate::requests::test_resource_requests::TestResourceClient;
 use crate::run_error_logger;
 use crate::storage::gcloud_storage::GCloudClient;
+use crate::util::secrets::{self, SecretEncryptor, SecretRedactor};
 use actix_web::client::Client;
 use chrono::{NaiveDateTime, Utc};
+use diesel::r2d2::{ConnectionManager, PooledConnection};
 use diesel::PgConnection;
+use futures::stream::{self, StreamExt};
+use futures::Future;
 use log::{debug, error};
 use serde_json::{Map, Value};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::error::Error;
 use std::fmt;
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
+use uuid::Uuid;
 
 /// Enum of cromwell statuses that can map to different statues in status updates for runs, reports,
 /// and builds
@@ -100,6 +113,7 @@ enum UpdateStatusError {
     Run(test_runner::Error),
     Report(report_builder::Error),
     Results(String),
+    Server(server_router::Error),
 }
 
 impl fmt::Display for UpdateStatusError {
@@ -112,6 +126,7 @@ impl fmt::Display for UpdateStatusError {
             UpdateStatusError::Run(e) => write!(f, "UpdateStatusError Run {}", e),
             UpdateStatusError::Report(e) => write!(f, "UpdateStatusError Report {}", e),
             UpdateStatusError::Results(e) => write!(f, "UpdateStatusError Results {}", e),
+            UpdateStatusError::Server(e) => write!(f, "UpdateStatusError Server {}", e),
         }
     }
 }
@@ -138,6 +153,15 @@ impl From<report_builder::Error> for UpdateStatusError {
         UpdateStatusError::Report(e)
     }
 }
+impl From<server_router::Error> for UpdateStatusError {
+    fn from(e: server_router::Error) -> UpdateStatusError {
+        UpdateStatusError::Server(e)
+    }
+}
+
+/// Maximum number of cromwell job ids to include in a single request to cromwell's query api when
+/// retrieving statuses in bulk
+const WORKFLOW_STATUS_QUERY_BATCH_SIZE: usize = 100;
 
 #[derive(Debug)]
 pub struct StatusManagerError {
@@ -161,7 +185,7 @@ pub struct StatusManager {
     notification_handler: NotificationHandler,
     test_runner: TestRunner,
     software_builder: Option<SoftwareBuilder>,
-    cromwell_client: CromwellClient,
+    server_router: ServerRouter,
     report_builder: Option<ReportBuilder>,
 }
 
@@ -201,24 +225,37 @@ pub async fn init_and_run(
     // Create a notification handler
     let notification_handler: NotificationHandler =
         NotificationHandler::new(emailer, github_commenter);
-    // Create a test resource client and cromwell client for the test runner
+    // Create a test resource client and server router for the test runner
     let test_resource_client: TestResourceClient =
         TestResourceClient::new(http_client.clone(), gcloud_client.clone());
-    let cromwell_client: CromwellClient =
-        CromwellClient::new(http_client.clone(), carrot_config.cromwell().address());
+    let server_router: ServerRouter = ServerRouter::new(http_client.clone(), &carrot_config);
     // Create a test runner and software builder
+    // Create a secret encryptor (if secrets are enabled) so the test runner can fill in secrets
+    let secret_encryptor: Option<SecretEncryptor> = secrets::get_secret_encryptor(&carrot_config);
     let test_runner: TestRunner = match carrot_config.custom_image_build() {
         Some(image_build_config) => TestRunner::new(
-            cromwell_client.clone(),
+            server_router.clone(),
             test_resource_client.clone(),
             Some(image_build_config.image_registry_host()),
+            carrot_config.status_manager().max_concurrent_runs(),
+            carrot_config.retry().clone(),
+            carrot_config.cromwell().workflow_option_defaults().cloned(),
+            secret_encryptor.clone(),
+        ),
+        None => TestRunner::new(
+            server_router.clone(),
+            test_resource_client.clone(),
+            None,
+            carrot_config.status_manager().max_concurrent_runs(),
+            carrot_config.retry().clone(),
+            carrot_config.cromwell().workflow_option_defaults().cloned(),
+            secret_encryptor.clone(),
         ),
-        None => TestRunner::new(cromwell_client.clone(), test_resource_client.clone(), None),
     };
     // Create a software builder
     let software_builder: Option<SoftwareBuilder> = match carrot_config.custom_image_build() {
         Some(image_build_config) => Some(SoftwareBuilder::new(
-            cromwell_client.clone(),
+            server_router.clone(),
             &image_build_config,
         )),
         None => None,
@@ -227,7 +264,7 @@ pub async fn init_and_run(
     let report_builder: Option<ReportBuilder> = match carrot_config.reporting() {
         Some(reporting_config) => {
             // We can unwrap gcloud_client because reporting won't work without it
-            Some(ReportBuilder::new(cromwell_client.clone(), gcloud_client.expect("Failed to unwrap gcloud_client to create report builder.  This should not happen").clone(), reporting_config))
+            Some(ReportBuilder::new(server_router.clone(), gcloud_client.expect("Failed to unwrap gcloud_client to create report builder.  This should not happen").clone(), reporting_config))
         }
         None => None,
     };
@@ -239,7 +276,7 @@ pub async fn init_and_run(
         notification_handler,
         test_runner,
         software_builder,
-        cromwell_client,
+        server_router,
         report_builder,
     );
     status_manager.run().await
@@ -249,8 +286,9 @@ impl StatusManager {
     /// Creates a new instance of StatusManager which will use `db_pool` for connecting to the db,
     /// `channel_recv` for checking for termination messages from its parent thread,
     /// `notification_handler` for sending notifications, `test_runner` for running tests,
-    /// `software_builder` for building docker images, `cromwell_client` for sending requests to
-    /// cromwell (for retrieving statuses), and `report_builder` for starting report build jobs
+    /// `software_builder` for building docker images, `server_router` for sending requests to
+    /// the execution backend servers (for retrieving statuses), and `report_builder` for starting
+    /// report build jobs
     pub fn new(
         db_pool: DbPool,
         config: StatusManagerConfig,
@@ -258,7 +296,7 @@ impl StatusManager {
         notification_handler: NotificationHandler,
         test_runner: TestRunner,
         software_builder: Option<SoftwareBuilder>,
-        cromwell_client: CromwellClient,
+        server_router: ServerRouter,
         report_builder: Option<ReportBuilder>,
     ) -> StatusManager {
         StatusManager {
@@ -268,7 +306,7 @@ impl StatusManager {
             notification_handler,
             test_runner,
             software_builder,
-            cromwell_client,
+            server_router,
             report_builder,
         }
     }
@@ -277,54 +315,74 @@ impl StatusManager {
     pub async fn run(&self) -> Result<(), StatusManagerError> {
         // Track consecutive failures to retrieve runs/builds so we can panic if there are too many
         let mut consecutive_failures: u32 = 0;
+        // Each status check uses its own DB connection, so we can't run more at once than there
+        // are connections in the pool
+        let concurrency: usize = std::cmp::min(
+            self.config.max_concurrent_status_checks(),
+            self.db_pool.max_size(),
+        )
+        .max(1) as usize;
         // Main loop
         loop {
             // Get the time we started this so we can sleep for a specified time between queries
             let query_time = Instant::now();
             debug!("Starting status check");
+            // Deal with any runs or builds that were stranded while they were being started (e.g.
+            // because CARROT stopped before submitting them to cromwell)
+            match self.get_db_connection() {
+                Ok(conn) => {
+                    self.recover_stranded_created_runs_and_builds(&conn, &mut consecutive_failures)
+                        .await?
+                }
+                Err(e) => {
+                    error!(
+                        "Failed to check for stranded created runs and builds due to: {}",
+                        e
+                    );
+                    self.increment_consecutive_failures(&mut consecutive_failures, e)?;
+                }
+            }
             // Update report statuses if reporting is enabled
             if self.report_builder.is_some() {
                 // Query DB for unfinished run reports
-                let unfinished_run_reports =
-                    RunReportData::find_unfinished(&self.db_pool.get().unwrap());
+                let unfinished_run_reports = self.get_db_connection().and_then(|conn| {
+                    RunReportData::find_unfinished(&conn)
+                        .map_err(|e| UpdateStatusError::DB(e.to_string()))
+                });
                 match unfinished_run_reports {
                     // If we got them successfully, check and update their statuses
                     Ok(run_reports) => {
                         // Reset the consecutive failures counter
                         consecutive_failures = 0;
                         debug!("Checking status of {} run_reports", run_reports.len());
-                        for run_report in run_reports {
-                            // Check for message from main thread to exit
-                            if let Some(_) = check_for_terminate_message(&self.channel_recv) {
-                                return Ok(());
-                            };
-                            // Check and update status
-                            debug!(
-                                "Checking status of run_report with run_id {} and report_id: {}",
-                                run_report.run_id, run_report.report_id
-                            );
-                            match self
-                                .check_and_update_run_report_status(
-                                    &run_report,
-                                    &self.db_pool.get().unwrap(),
-                                )
-                                .await
-                            {
-                                Err(e) => {
-                                    error!("Encountered error while trying to update status for run_report with run_id {} and report_id {} : {}", run_report.run_id, run_report.report_id, e);
-                                    self.increment_consecutive_failures(
-                                        &mut consecutive_failures,
-                                        e,
-                                    )?;
-                                }
-                                Ok(_) => {
-                                    debug!(
-                                        "Successfully checked/updated status for run_report with run_id {} and report_id {}",
-                                        run_report.run_id,
-                                        run_report.report_id
-                                    );
-                                }
-                            }
+                        // Get the statuses of their cromwell jobs in bulk
+                        let jobs: Vec<(Option<String>, String)> = run_reports
+                            .iter()
+                            .filter_map(|run_report| {
+                                run_report
+                                    .cromwell_job_id
+                                    .clone()
+                                    .map(|job_id| (run_report.cromwell_server.clone(), job_id))
+                            })
+                            .collect();
+                        let workflow_statuses = self
+                            .get_workflow_statuses(jobs, &mut consecutive_failures)
+                            .await?;
+                        if self
+                            .process_concurrently(
+                                run_reports,
+                                concurrency,
+                                &mut consecutive_failures,
+                                |run_report| {
+                                    self.check_and_update_run_report_status_with_own_conn(
+                                        run_report,
+                                        &workflow_statuses,
+                                    )
+                                },
+                            )
+                            .await?
+                        {
+                            return Ok(());
                         }
                     }
                     // If we failed, panic if there are too many failures
@@ -335,32 +393,33 @@ impl StatusManager {
                 }
             }
             // Query DB for unfinished runs
-            let unfinished_runs = RunData::find_unfinished(&self.db_pool.get().unwrap());
+            let unfinished_runs = self.get_db_connection().and_then(|conn| {
+                RunData::find_unfinished(&conn).map_err(|e| UpdateStatusError::DB(e.to_string()))
+            });
             match unfinished_runs {
                 // If we got them successfully, check and update their statuses
                 Ok(runs) => {
                     // Reset the consecutive failures counter
                     consecutive_failures = 0;
                     debug!("Checking status of {} runs", runs.len());
-                    for run in runs {
-                        // Check for message from main thread to exit
-                        if let Some(_) = check_for_terminate_message(&self.channel_recv) {
-                            return Ok(());
-                        };
-                        // Check and update status in new thread
-                        debug!("Checking status of run with id: {}", run.run_id);
-                        if let Err(e) = self
-                            .check_and_update_run_status(&run, &self.db_pool.get().unwrap())
-                            .await
-                        {
-                            let error_message = format!("Encountered error while trying to update status for run with id {}: {}", run.run_id, e);
-                            run_error_logger::log_error(
-                                &self.db_pool.get().unwrap(),
-                                run.run_id,
-                                error_message,
-                            );
-                            self.increment_consecutive_failures(&mut consecutive_failures, e)?;
-                        }
+                    // Get the statuses of their active cromwell jobs in bulk
+                    let jobs: Vec<(Option<String>, String)> = runs
+                        .iter()
+                        .filter_map(|run| {
+                            StatusManager::get_active_cromwell_job_id(run)
+                                .map(|job_id| (run.cromwell_server.clone(), job_id))
+                        })
+                        .collect();
+                    let workflow_statuses = self
+                        .get_workflow_statuses(jobs, &mut consecutive_failures)
+                        .await?;
+                    if self
+                        .process_concurrently(runs, concurrency, &mut consecutive_failures, |run| {
+                            self.check_and_update_run_status_with_own_conn(run, &workflow_statuses)
+                        })
+                        .await?
+                    {
+                        return Ok(());
                     }
                 }
                 // If we failed, panic if there are too many failures
@@ -369,45 +428,108 @@ impl StatusManager {
                     self.increment_consecutive_failures(&mut consecutive_failures, e)?;
                 }
             }
+            // Query DB for queued runs so we can start any there's now room for
+            let queued_runs = self.get_db_connection().and_then(|conn| {
+                RunData::find_queued(&conn, self.config.prioritize_queued_runs())
+                    .map_err(|e| UpdateStatusError::DB(e.to_string()))
+            });
+            match queued_runs {
+                // If we got them successfully, try to start them
+                Ok(runs) => {
+                    // Reset the consecutive failures counter
+                    consecutive_failures = 0;
+                    debug!("Attempting to start {} queued runs", runs.len());
+                    // Starting a queued run depends on how many runs are already active, so these
+                    // have to be started one at a time
+                    if self
+                        .process_concurrently(runs, 1, &mut consecutive_failures, |run| {
+                            self.start_queued_run_with_own_conn(run)
+                        })
+                        .await?
+                    {
+                        return Ok(());
+                    }
+                }
+                // If we failed, panic if there are too many failures
+                Err(e) => {
+                    error!("Failed to retrieve queued runs due to: {}", e);
+                    self.increment_consecutive_failures(&mut consecutive_failures, e)?;
+                }
+            }
+            // Query DB for runs with failed start attempts that are due to be retried
+            let due_retries = self.get_db_connection().and_then(|conn| {
+                RunStartAttemptData::find_due_retries(&conn, Utc::now().naive_utc())
+                    .map_err(|e| UpdateStatusError::DB(e.to_string()))
+            });
+            match due_retries {
+                // If we got them successfully, retry them
+                Ok(run_start_attempts) => {
+                    // Reset the consecutive failures counter
+                    consecutive_failures = 0;
+                    debug!("Retrying {} run starts", run_start_attempts.len());
+                    // Starting a run depends on how many runs are already active, so these have to
+                    // be retried one at a time, same as queued runs
+                    if self
+                        .process_concurrently(
+                            run_start_attempts,
+                            1,
+                            &mut consecutive_failures,
+                            |run_start_attempt| {
+                                self.retry_run_start_with_own_conn(run_start_attempt)
+                            },
+                        )
+                        .await?
+                    {
+                        return Ok(());
+                    }
+                }
+                // If we failed, panic if there are too many failures
+                Err(e) => {
+                    error!("Failed to retrieve run start retries due to: {}", e);
+                    self.increment_consecutive_failures(&mut consecutive_failures, e)?;
+                }
+            }
             // Update build statuses if software building is enabled
             if self.software_builder.is_some() {
                 // Query DB for unfinished builds
-                let unfinished_builds =
-                    SoftwareBuildData::find_unfinished(&self.db_pool.get().unwrap());
+                let unfinished_builds = self.get_db_connection().and_then(|conn| {
+                    SoftwareBuildData::find_unfinished(&conn)
+                        .map_err(|e| UpdateStatusError::DB(e.to_string()))
+                });
                 match unfinished_builds {
                     // If we got them successfully, check and update their statuses
                     Ok(builds) => {
                         // Reset the consecutive failures counter
                         consecutive_failures = 0;
                         debug!("Checking status of {} builds", builds.len());
-                        for build in builds {
-                            // Check for message from main thread to exit
-                            if let Some(_) = check_for_terminate_message(&self.channel_recv) {
-                                return Ok(());
-                            };
-                            // Check and update status
-                            debug!(
-                                "Checking status of build with id: {}",
-                                build.software_build_id
-                            );
-                            match self
-                                .check_and_update_build_status(&build, &self.db_pool.get().unwrap())
-                                .await
-                            {
-                                Err(e) => {
-                                    error!("Encountered error while trying to update status for build with id {}: {}", build.software_build_id, e);
-                                    self.increment_consecutive_failures(
-                                        &mut consecutive_failures,
-                                        e,
-                                    )?;
-                                }
-                                Ok(_) => {
-                                    debug!(
-                                        "Successfully checked/updated status for build with id {}",
-                                        build.software_build_id
-                                    );
-                                }
-                            }
+                        // Get the statuses of their cromwell jobs in bulk
+                        let jobs: Vec<(Option<String>, String)> = builds
+                            .iter()
+                            .filter_map(|build| {
+                                build
+                                    .build_job_id
+                                    .clone()
+                                    .map(|job_id| (build.cromwell_server.clone(), job_id))
+                            })
+                            .collect();
+                        let workflow_statuses = self
+                            .get_workflow_statuses(jobs, &mut consecutive_failures)
+                            .await?;
+                        if self
+                            .process_concurrently(
+                                builds,
+                                concurrency,
+                                &mut consecutive_failures,
+                                |build| {
+                                    self.check_and_update_build_status_with_own_conn(
+                                        build,
+                                        &workflow_statuses,
+                                    )
+                                },
+                            )
+                            .await?
+                        {
+                            return Ok(());
                         }
                     }
                     // If we failed, panic if there are too many failures
@@ -436,6 +558,419 @@ impl StatusManager {
             }
         }
     }
+
+    /// Calls `process` for each of `items`, with up to `concurrency` of them running at once, and
+    /// counts any errors they return toward `consecutive_failures`
+    ///
+    /// Checks for a terminate message from the main thread as each one finishes.  If one is
+    /// received, no more items are started, but any that have already started are allowed to
+    /// finish so they don't leave things in an inconsistent state.  Returns Ok(true) if a terminate
+    /// message was received, Ok(false) if all the items were processed, or an error if there are
+    /// too many consecutive failures
+    async fn process_concurrently<T, F, Fut>(
+        &self,
+        items: Vec<T>,
+        concurrency: usize,
+        consecutive_failures: &mut u32,
+        process: F,
+    ) -> Result<bool, StatusManagerError>
+    where
+        F: Fn(T) -> Fut,
+        Fut: Future<Output = Result<(), UpdateStatusError>>,
+    {
+        // Check for message from main thread to exit before we start anything
+        if let Some(_) = check_for_terminate_message(&self.channel_recv) {
+            return Ok(true);
+        }
+        let terminated: Cell<bool> = Cell::new(false);
+        let mut results = stream::iter(items)
+            .map(|item| {
+                let terminated = &terminated;
+                let process_future = process(item);
+                async move {
+                    // Skip anything that hadn't started yet when we got the terminate message
+                    if terminated.get() {
+                        return Ok(());
+                    }
+                    process_future.await
+                }
+            })
+            .buffer_unordered(concurrency);
+        while let Some(result) = results.next().await {
+            // Check for message from main thread to exit
+            if !terminated.get() && check_for_terminate_message(&self.channel_recv).is_some() {
+                terminated.set(true);
+            }
+            if let Err(e) = result {
+                self.increment_consecutive_failures(consecutive_failures, e)?;
+            }
+        }
+        Ok(terminated.get())
+    }
+
+    /// Gets a connection from the DB pool, or returns an error if that fails
+    fn get_db_connection(
+        &self,
+    ) -> Result<PooledConnection<ConnectionManager<PgConnection>>, UpdateStatusError> {
+        self.db_pool.get().map_err(|e| {
+            UpdateStatusError::DB(format!("Failed to get DB connection from pool: {}", e))
+        })
+    }
+
+    /// Checks and updates the status of `run_report` (see `check_and_update_run_report_status`)
+    /// using its own connection from the DB pool and its job's status from `workflow_statuses`, if
+    /// it's there, and logs the outcome
+    async fn check_and_update_run_report_status_with_own_conn(
+        &self,
+        run_report: RunReportData,
+        workflow_statuses: &HashMap<String, Map<String, Value>>,
+    ) -> Result<(), UpdateStatusError> {
+        let conn = self.get_db_connection()?;
+        debug!(
+            "Checking status of run_report with run_id {} and report_id: {}",
+            run_report.run_id, run_report.report_id
+        );
+        let queried_status = run_report
+            .cromwell_job_id
+            .as_ref()
+            .and_then(|job_id| workflow_statuses.get(job_id));
+        match self
+            .check_and_update_run_report_status(&run_report, &conn, queried_status)
+            .await
+        {
+            Err(e) => {
+                error!("Encountered error while trying to update status for run_report with run_id {} and report_id {} : {}", run_report.run_id, run_report.report_id, e);
+                Err(e)
+            }
+            Ok(_) => {
+                debug!(
+                    "Successfully checked/updated status for run_report with run_id {} and report_id {}",
+                    run_report.run_id,
+                    run_report.report_id
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks and updates the status of `run` (see `check_and_update_run_status`) using its own
+    /// connection from the DB pool and its active job's status from `workflow_statuses`, if it's
+    /// there, and logs any error as a run_error for `run`
+    async fn check_and_update_run_status_with_own_conn(
+        &self,
+        run: RunData,
+        workflow_statuses: &HashMap<String, Map<String, Value>>,
+    ) -> Result<(), UpdateStatusError> {
+        let conn = self.get_db_connection()?;
+        debug!("Checking status of run with id: {}", run.run_id);
+        let queried_status = StatusManager::get_active_cromwell_job_id(&run)
+            .and_then(|job_id| workflow_statuses.get(&job_id));
+        if let Err(e) = self
+            .check_and_update_run_status(&run, &conn, queried_status)
+            .await
+        {
+            let error_message = format!(
+                "Encountered error while trying to update status for run with id {}: {}",
+                run.run_id, e
+            );
+            run_error_logger::log_error_redacting_secrets(
+                &conn,
+                run.run_id,
+                error_message,
+                self.test_runner.secret_encryptor(),
+            );
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Attempts to start queued `run` (see `start_queued_run`) using its own connection from the DB
+    /// pool, and logs any error as a run_error for `run`
+    async fn start_queued_run_with_own_conn(&self, run: RunData) -> Result<(), UpdateStatusError> {
+        let conn = self.get_db_connection()?;
+        debug!("Attempting to start queued run with id: {}", run.run_id);
+        if let Err(e) = self.start_queued_run(&run, &conn).await {
+            let error_message = format!(
+                "Encountered error while trying to start queued run with id {}: {}",
+                run.run_id, e
+            );
+            run_error_logger::log_error_redacting_secrets(
+                &conn,
+                run.run_id,
+                error_message,
+                self.test_runner.secret_encryptor(),
+            );
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Makes the scheduled retry of starting a workflow for a run described by
+    /// `run_start_attempt` (see `TestRunner::retry_run_start`) using its own connection from the
+    /// DB pool, and logs any error as a run_error for the run and sends notifications that it
+    /// failed
+    async fn retry_run_start_with_own_conn(
+        &self,
+        run_start_attempt: RunStartAttemptData,
+    ) -> Result<(), UpdateStatusError> {
+        let conn = self.get_db_connection()?;
+        debug!(
+            "Retrying start of {} workflow for run with id: {}",
+            run_start_attempt.workflow, run_start_attempt.run_id
+        );
+        let run = RunData::find_by_id(&conn, run_start_attempt.run_id)
+            .map_err(|e| UpdateStatusError::DB(e.to_string()))?;
+        if let Err(e) = self
+            .test_runner
+            .retry_run_start(&conn, run, run_start_attempt.workflow)
+            .await
+        {
+            let error_message = format!(
+                "Encountered error while trying to retry start of {} workflow for run with id {}: {}",
+                run_start_attempt.workflow, run_start_attempt.run_id, e
+            );
+            run_error_logger::log_error_redacting_secrets(
+                &conn,
+                run_start_attempt.run_id,
+                error_message,
+                self.test_runner.secret_encryptor(),
+            );
+            // The test runner will have marked it as failed, so send notifications
+            self.notification_handler
+                .send_run_complete_notifications(&conn, run_start_attempt.run_id)
+                .await?;
+            return Err(UpdateStatusError::Run(e));
+        }
+        Ok(())
+    }
+
+    /// Checks and updates the status of `build` (see `check_and_update_build_status`) using its own
+    /// connection from the DB pool and its job's status from `workflow_statuses`, if it's there, and
+    /// logs the outcome
+    async fn check_and_update_build_status_with_own_conn(
+        &self,
+        build: SoftwareBuildData,
+        workflow_statuses: &HashMap<String, Map<String, Value>>,
+    ) -> Result<(), UpdateStatusError> {
+        let conn = self.get_db_connection()?;
+        debug!(
+            "Checking status of build with id: {}",
+            build.software_build_id
+        );
+        let queried_status = build
+            .build_job_id
+            .as_ref()
+            .and_then(|job_id| workflow_statuses.get(job_id));
+        match self
+            .check_and_update_build_status(&build, &conn, queried_status)
+            .await
+        {
+            Err(e) => {
+                error!(
+                    "Encountered error while trying to update status for build with id {}: {}",
+                    build.software_build_id, e
+                );
+                Err(e)
+            }
+            Ok(_) => {
+                debug!(
+                    "Successfully checked/updated status for build with id {}",
+                    build.software_build_id
+                );
+                Ok(())
+            }
+        }
+    }
+    /// Retrieves the statuses of the cromwell jobs specified by `cromwell_jobs` (pairs of the
+    /// server each job was submitted to and its id) in bulk (see
+    /// `query_workflow_statuses_from_cromwell`)
+    ///
+    /// If that fails, logs the error and counts it toward `consecutive_failures`, and returns an
+    /// empty map so the status of each job will be retrieved individually instead.  Returns an
+    /// error if there are too many consecutive failures
+    async fn get_workflow_statuses(
+        &self,
+        cromwell_jobs: Vec<(Option<String>, String)>,
+        consecutive_failures: &mut u32,
+    ) -> Result<HashMap<String, Map<String, Value>>, StatusManagerError> {
+        match self
+            .query_workflow_statuses_from_cromwell(cromwell_jobs)
+            .await
+        {
+            Ok(workflow_statuses) => Ok(workflow_statuses),
+            Err(e) => {
+                error!(
+                    "Failed to query cromwell job statuses in bulk, so they'll be checked individually, due to: {}",
+                    e
+                );
+                self.increment_consecutive_failures(consecutive_failures, e)?;
+                Ok(HashMap::new())
+            }
+        }
+    }
+
+    /// Finds runs and software builds that have been `Created` for longer than the configured
+    /// threshold, which means they were stranded while being started (e.g. because CARROT stopped
+    /// before submitting them to cromwell), and either resubmits them or marks them as failed,
+    /// depending on the configured policy
+    ///
+    /// Errors are logged and counted toward `consecutive_failures`.  Returns an error if there are
+    /// too many consecutive failures
+    async fn recover_stranded_created_runs_and_builds(
+        &self,
+        conn: &PgConnection,
+        consecutive_failures: &mut u32,
+    ) -> Result<(), StatusManagerError> {
+        let threshold = self.config.created_recovery_threshold_in_secs();
+        let cutoff: NaiveDateTime =
+            Utc::now().naive_utc() - chrono::Duration::seconds(threshold as i64);
+        // Recover runs
+        match RunData::find_created_before(conn, cutoff) {
+            Ok(runs) => {
+                for run in runs {
+                    if let Err(e) = self.recover_stranded_created_run(conn, &run).await {
+                        let error_message = format!(
+                            "Encountered error while trying to recover stranded created run with id {}: {}",
+                            run.run_id, e
+                        );
+                        run_error_logger::log_error_redacting_secrets(
+                            conn,
+                            run.run_id,
+                            error_message,
+                            self.test_runner.secret_encryptor(),
+                        );
+                        self.increment_consecutive_failures(consecutive_failures, e)?;
+                    }
+                }
+            }
+            Err(e) => {
+                error!("Failed to retrieve stranded created runs due to: {}", e);
+                self.increment_consecutive_failures(consecutive_failures, e)?;
+            }
+        }
+        // If we're resubmitting, created builds will be started when we check build statuses, so
+        // we only need to do something about stranded builds if we're failing them
+        if self.config.created_recovery_policy() == CreatedRecoveryPolicy::Fail {
+            match SoftwareBuildData::find_created_before(conn, cutoff) {
+                Ok(builds) => {
+                    for build in builds {
+                        error!(
+                            "Software build with id {} was stranded in created status for more than {} seconds, so it is being marked as failed",
+                            build.software_build_id, threshold
+                        );
+                        let changeset = SoftwareBuildChangeset {
+                            image_url: None,
+                            status: Some(BuildStatusEnum::Failed),
+                            build_job_id: None,
+                            finished_at: Some(Utc::now().naive_utc()),
+                            cromwell_server: None,
+                        };
+                        if let Err(e) =
+                            SoftwareBuildData::update(conn, build.software_build_id, changeset)
+                        {
+                            error!(
+                                "Failed to mark stranded software build with id {} as failed due to: {}",
+                                build.software_build_id, e
+                            );
+                            self.increment_consecutive_failures(consecutive_failures, e)?;
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to retrieve stranded created builds due to: {}", e);
+                    self.increment_consecutive_failures(consecutive_failures, e)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Resubmits or fails `run`, which was stranded in `Created` status, depending on the
+    /// configured policy, and logs a run_error explaining what happened
+    ///
+    /// Runs that are waiting for a failed start attempt to be retried aren't stranded, so they are
+    /// left alone.  Before resubmitting, cromwell is checked for a workflow that was already
+    /// submitted for `run` (see `TestRunner::adopt_submitted_workflow`), in which case `run` is
+    /// updated to track that workflow instead
+    async fn recover_stranded_created_run(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+    ) -> Result<(), UpdateStatusError> {
+        if StatusManager::has_pending_start_retry(conn, run, RunCallWorkflowEnum::Test)?
+            || StatusManager::has_pending_start_retry(conn, run, RunCallWorkflowEnum::Eval)?
+        {
+            return Ok(());
+        }
+        let threshold = self.config.created_recovery_threshold_in_secs();
+        match self.config.created_recovery_policy() {
+            CreatedRecoveryPolicy::Resubmit => {
+                if let Some(adopted_run) =
+                    self.test_runner.adopt_submitted_workflow(conn, run).await?
+                {
+                    let cromwell_job_id = adopted_run
+                        .eval_cromwell_job_id
+                        .or(adopted_run.test_cromwell_job_id)
+                        .unwrap_or_default();
+                    run_error_logger::log_error(
+                        conn,
+                        run.run_id,
+                        format!(
+                            "Run was stranded in created status for more than {} seconds (likely because CARROT stopped while starting it), but workflow {} had already been submitted for it, so that workflow is being tracked instead of resubmitting",
+                            threshold, cromwell_job_id
+                        ),
+                    );
+                    return Ok(());
+                }
+                run_error_logger::log_error(
+                    conn,
+                    run.run_id,
+                    format!(
+                        "Run was stranded in created status for more than {} seconds (likely because CARROT stopped while starting it), so it is being resubmitted",
+                        threshold
+                    ),
+                );
+                self.start_queued_run(run, conn).await
+            }
+            CreatedRecoveryPolicy::Fail => {
+                test_runner::update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                run_error_logger::log_error(
+                    conn,
+                    run.run_id,
+                    format!(
+                        "Run was stranded in created status for more than {} seconds (likely because CARROT stopped while starting it), so it was marked as failed",
+                        threshold
+                    ),
+                );
+                self.notification_handler
+                    .send_run_complete_notifications(conn, run.run_id)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the cromwell job id for the step `run` is currently in, if it is in the test or eval
+    /// step
+    fn get_active_cromwell_job_id(run: &RunData) -> Option<String> {
+        match run.status {
+            RunStatusEnum::TestSubmitted
+            | RunStatusEnum::TestAborting
+            | RunStatusEnum::TestQueuedInCromwell
+            | RunStatusEnum::TestRunning
+            | RunStatusEnum::TestStarting
+            | RunStatusEnum::TestWaitingForQueueSpace => run.test_cromwell_job_id.clone(),
+            RunStatusEnum::EvalSubmitted
+            | RunStatusEnum::EvalAborting
+            | RunStatusEnum::EvalQueuedInCromwell
+            | RunStatusEnum::EvalRunning
+            | RunStatusEnum::EvalStarting
+            | RunStatusEnum::EvalWaitingForQueueSpace => run.eval_cromwell_job_id.clone(),
+            _ => None,
+        }
+    }
+
     /// Increments `consecutive failures` by one, logs `e` and returns an error if
     /// `consecutive_failures` exceeds the allowed consecutive failures specified in `self.config`
     fn increment_consecutive_failures(
@@ -474,14 +1009,19 @@ impl StatusManager {
     /// accordingly if the status in cromwell is different.  If cromwell says the run has succeeded,
     /// fills results in the database and marks run as `Succeeded`.  If cromwell was run has failed,
     /// marks `EvalFailed`.
+    /// `queried_status` is the status of the run's active cromwell job from a bulk query, if there is
+    /// one, in which case full metadata is only retrieved from cromwell if the job has finished
     async fn check_and_update_run_status(
         &self,
         run: &RunData,
         conn: &PgConnection,
+        queried_status: Option<&Map<String, Value>>,
     ) -> Result<(), UpdateStatusError> {
         match run.status {
             // If this run has a status of 'Created', skip it, because it's still getting started
             RunStatusEnum::Created => Ok(()),
+            // If it's queued, skip it, because queued runs are started separately
+            RunStatusEnum::Queued => Ok(()),
             // If it's building, check if it's ready to run
             RunStatusEnum::Building => self.update_run_status_for_building(conn, run).await,
             // If it's in the testing phase, check and update its status based on that
@@ -491,7 +1031,8 @@ impl StatusManager {
             | RunStatusEnum::TestRunning
             | RunStatusEnum::TestStarting
             | RunStatusEnum::TestWaitingForQueueSpace => {
-                self.update_run_status_for_testing(conn, run).await
+                self.update_run_status_for_testing(conn, run, queried_status)
+                    .await
             }
             // If it's in the evaluating phase, check and update its status based on that
             RunStatusEnum::EvalSubmitted
@@ -500,7 +1041,8 @@ impl StatusManager {
             | RunStatusEnum::EvalRunning
             | RunStatusEnum::EvalStarting
             | RunStatusEnum::EvalWaitingForQueueSpace => {
-                self.update_run_status_for_evaluating(conn, run).await
+                self.update_run_status_for_evaluating(conn, run, queried_status)
+                    .await
             }
             // Any other statuses shouldn't be showing up here
             _ => {
@@ -510,6 +1052,26 @@ impl StatusManager {
         }
     }
 
+    /// Attempts to start `run`, which has a status of `Queued` (or is a stranded `Created` run), if
+    /// there is room for it within the limits on concurrent runs.  If starting it fails, sends
+    /// notifications that the run has failed
+    async fn start_queued_run(
+        &self,
+        run: &RunData,
+        conn: &PgConnection,
+    ) -> Result<(), UpdateStatusError> {
+        match self.test_runner.start_queued_run(conn, run).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                // The test runner will have marked it as failed, so send notifications
+                self.notification_handler
+                    .send_run_complete_notifications(conn, run.run_id)
+                    .await?;
+                Err(UpdateStatusError::Run(e))
+            }
+        }
+    }
+
     /// Checks status of builds for `run` and updates status accordingly (including starting run if
     /// builds have finished successfully)
     async fn update_run_status_for_building(
@@ -557,17 +1119,30 @@ impl StatusManager {
     /// Handles updating the run status of a run that is in the testing phase (i.e. the test wdl is
     /// running in cromwell)
     ///
-    /// Retrieves metadata for the cromwell job for the test wdl, updates the status of `run` if it
-    /// doesn't match the status retrieved from cromwell, and process outputs and starts the eval wdl
-    /// job if the test wdl job has succeeded
+    /// Retrieves metadata for the cromwell job for the test wdl (or uses `queried_status`, if it is
+    /// provided and the job hasn't finished), updates the status of `run` if it doesn't match the
+    /// status retrieved from cromwell, and process outputs and starts the eval wdl job if the test
+    /// wdl job has succeeded
     async fn update_run_status_for_testing(
         &self,
         conn: &PgConnection,
         run: &RunData,
+        queried_status: Option<&Map<String, Value>>,
     ) -> Result<(), UpdateStatusError> {
+        // If the test job already succeeded and starting the eval job is waiting to be retried,
+        // leave it for the retry
+        if run.status != RunStatusEnum::TestAborting
+            && StatusManager::has_pending_start_retry(conn, run, RunCallWorkflowEnum::Eval)?
+        {
+            return Ok(());
+        }
         // Get metadata
         let metadata = self
-            .get_status_metadata_from_cromwell(&run.test_cromwell_job_id.as_ref().unwrap())
+            .get_status_metadata(
+                run.cromwell_server.as_deref(),
+                run.test_cromwell_job_id.as_ref().unwrap(),
+                queried_status,
+            )
             .await?;
         // If the status is different from what's stored in the DB currently, update it
         let status = match metadata.get("status") {
@@ -591,7 +1166,40 @@ impl StatusManager {
                 )))
             }
         };
+        // If an abort has been requested for this run, cromwell may keep reporting the job as
+        // running until the abort goes through, so we leave it as aborting until it finishes.  If it
+        // managed to succeed before being aborted, we mark it aborted instead of starting the eval
+        let status = if run.status == RunStatusEnum::TestAborting {
+            match status {
+                RunStatusEnum::Succeeded | RunStatusEnum::TestAborted => RunStatusEnum::TestAborted,
+                RunStatusEnum::TestFailed => RunStatusEnum::TestFailed,
+                _ => RunStatusEnum::TestAborting,
+            }
+        } else {
+            status
+        };
+        // If the job is still going but has been running for too long, abort it
+        if StatusManager::is_cromwell_job_in_progress(&status)
+            && self
+                .abort_run_if_past_max_runtime(conn, run, &metadata, "test")
+                .await?
+        {
+            return Ok(());
+        }
         if status != run.status {
+            // If the test job has finished, store its calls
+            if status == RunStatusEnum::Succeeded
+                || status == RunStatusEnum::TestFailed
+                || status == RunStatusEnum::TestAborted
+            {
+                self.record_run_calls(
+                    conn,
+                    run,
+                    run.test_cromwell_job_id.as_ref().unwrap(),
+                    RunCallWorkflowEnum::Test,
+                )
+                .await;
+            }
             // If it succeeded, fill results in the DB and start the eval job
             if status == RunStatusEnum::Succeeded {
                 let outputs = match metadata.get("outputs") {
@@ -603,7 +1211,12 @@ impl StatusManager {
                     }
                 };
                 // If filling results errors out in some way, update run status to failed
-                if let Err(e) = StatusManager::fill_results(&outputs, run, conn) {
+                if let Err(e) = StatusManager::fill_results(
+                    &outputs,
+                    run,
+                    conn,
+                    self.test_runner.secret_encryptor(),
+                ) {
                     test_runner::update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
                     // Send notifications that the run failed
                     self.notification_handler
@@ -630,6 +1243,7 @@ impl StatusManager {
                         test_cromwell_job_id: None,
                         eval_cromwell_job_id: None,
                         finished_at: Some(StatusManager::get_end(&metadata)?),
+                        cromwell_server: None,
                     },
                     _ => RunChangeset {
                         name: None,
@@ -637,6 +1251,7 @@ impl StatusManager {
                         test_cromwell_job_id: None,
                         eval_cromwell_job_id: None,
                         finished_at: None,
+                        cromwell_server: None,
                     },
                 };
                 // Update
@@ -649,6 +1264,16 @@ impl StatusManager {
                     }
                     _ => {}
                 };
+                // If it failed, record why
+                if status == RunStatusEnum::TestFailed {
+                    StatusManager::log_cromwell_failures(
+                        conn,
+                        run,
+                        &metadata,
+                        "test",
+                        self.test_runner.secret_encryptor(),
+                    );
+                }
                 // If it ended unsuccessfully, send notifications
                 if status == RunStatusEnum::TestFailed || status == RunStatusEnum::TestAborted {
                     self.notification_handler
@@ -664,17 +1289,23 @@ impl StatusManager {
     /// Handles updating the run status of a run that is in the eval phase (i.e. the eval wdl is
     /// running in cromwell)
     ///
-    /// Retrieves metadata for the cromwell job for the eval wdl, updates the status of `run` if it
-    /// doesn't match the status retrieved from cromwell, and process outputs and sends notifications
-    /// if the eval wdl job has succeeded
+    /// Retrieves metadata for the cromwell job for the eval wdl (or uses `queried_status`, if it is
+    /// provided and the job hasn't finished), updates the status of `run` if it doesn't match the
+    /// status retrieved from cromwell, and process outputs and sends notifications if the eval wdl
+    /// job has succeeded
     async fn update_run_status_for_evaluating(
         &self,
         conn: &PgConnection,
         run: &RunData,
+        queried_status: Option<&Map<String, Value>>,
     ) -> Result<(), UpdateStatusError> {
         // Get metadata
         let metadata = self
-            .get_status_metadata_from_cromwell(&run.eval_cromwell_job_id.as_ref().unwrap())
+            .get_status_metadata(
+                run.cromwell_server.as_deref(),
+                run.eval_cromwell_job_id.as_ref().unwrap(),
+                queried_status,
+            )
             .await?;
         // If the status is different from what's stored in the DB currently, update it
         let status = match metadata.get("status") {
@@ -698,7 +1329,39 @@ impl StatusManager {
                 )))
             }
         };
+        // Same as for the test step, keep a run that's being aborted as aborting until it finishes
+        let status = if run.status == RunStatusEnum::EvalAborting {
+            match status {
+                RunStatusEnum::Succeeded
+                | RunStatusEnum::EvalFailed
+                | RunStatusEnum::EvalAborted => status,
+                _ => RunStatusEnum::EvalAborting,
+            }
+        } else {
+            status
+        };
+        // Same as for the test step, abort the job if it has been running for too long
+        if StatusManager::is_cromwell_job_in_progress(&status)
+            && self
+                .abort_run_if_past_max_runtime(conn, run, &metadata, "eval")
+                .await?
+        {
+            return Ok(());
+        }
         if status != run.status {
+            // If the eval job has finished, store its calls
+            if status == RunStatusEnum::Succeeded
+                || status == RunStatusEnum::EvalFailed
+                || status == RunStatusEnum::EvalAborted
+            {
+                self.record_run_calls(
+                    conn,
+                    run,
+                    run.eval_cromwell_job_id.as_ref().unwrap(),
+                    RunCallWorkflowEnum::Eval,
+                )
+                .await;
+            }
             // Set the changes based on the status
             let run_update: RunChangeset = match status {
                 RunStatusEnum::Succeeded
@@ -709,6 +1372,7 @@ impl StatusManager {
                     test_cromwell_job_id: None,
                     eval_cromwell_job_id: None,
                     finished_at: Some(StatusManager::get_end(&metadata)?),
+                    cromwell_server: None,
                 },
                 _ => RunChangeset {
                     name: None,
@@ -716,6 +1380,7 @@ impl StatusManager {
                     test_cromwell_job_id: None,
                     eval_cromwell_job_id: None,
                     finished_at: None,
+                    cromwell_server: None,
                 },
             };
             // Update
@@ -728,6 +1393,16 @@ impl StatusManager {
                 }
                 _ => {}
             };
+            // If it failed, record why
+            if status == RunStatusEnum::EvalFailed {
+                StatusManager::log_cromwell_failures(
+                    conn,
+                    run,
+                    &metadata,
+                    "eval",
+                    self.test_runner.secret_encryptor(),
+                );
+            }
 
             // If it succeeded, fill results in DB also, and start generating reports
             if status == RunStatusEnum::Succeeded {
@@ -740,7 +1415,12 @@ impl StatusManager {
                     }
                 };
                 // If filling results errors out in some way, update run status to failed
-                if let Err(e) = StatusManager::fill_results(&outputs, run, conn) {
+                if let Err(e) = StatusManager::fill_results(
+                    &outputs,
+                    run,
+                    conn,
+                    self.test_runner.secret_encryptor(),
+                ) {
                     test_runner::update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
                     // Send notifications that the run failed
                     self.notification_handler
@@ -780,18 +1460,287 @@ impl StatusManager {
         Ok(())
     }
 
-    /// Checks whether the count of run_result records for `run` matches the number expected, based on
-    /// the number of results mapped to its template.  Returns Ok(()) if so, or an error if not
-    fn check_result_counts(conn: &PgConnection, run: &RunData) -> Result<(), UpdateStatusError> {
-        // Get count of template_result mappings for the template corresponding to this run
-        let template_result_count = match TemplateResultData::find_count_for_test(conn, run.test_id)
+    /// Retrieves the calls for the cromwell job with id `cromwell_job_id` (the `workflow` workflow
+    /// for `run`) and stores them in the DB as run_call records, replacing any that were already
+    /// stored for that job.  The runtime attributes and docker image for each call can contain
+    /// secret values (e.g. from inputs), so those are redacted before the calls are stored
+    ///
+    /// Not being able to store the calls for a run shouldn't keep the run from progressing, so any
+    /// errors are logged instead of returned
+    async fn record_run_calls(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+        cromwell_job_id: &str,
+        workflow: RunCallWorkflowEnum,
+    ) {
+        let metadata = match self
+            .get_call_metadata_from_cromwell(run.cromwell_server.as_deref(), cromwell_job_id)
+            .await
         {
-            Ok(template_results) => template_results,
+            Ok(metadata) => metadata,
             Err(e) => {
-                return Err(UpdateStatusError::DB(format!(
-                    "Failed to load count of template result mappings from DB with error: {}",
-                    e
-                )));
+                error!(
+                    "Failed to retrieve call metadata for {} job {} for run {} with error: {}",
+                    workflow, cromwell_job_id, run.run_id, e
+                );
+                return;
+            }
+        };
+        let mut new_run_calls = StatusManager::get_run_calls_from_metadata(
+            run.run_id,
+            cromwell_job_id,
+            workflow,
+            &metadata,
+        );
+        // Redact any secret values, and don't store the calls at all if we can't
+        let secret_redactor = match SecretRedactor::load(conn, self.test_runner.secret_encryptor())
+        {
+            Ok(secret_redactor) => secret_redactor,
+            Err(e) => {
+                error!(
+                        "Failed to load secrets to redact calls for {} job {} for run {} with error: {}",
+                        workflow, cromwell_job_id, run.run_id, e
+                    );
+                return;
+            }
+        };
+        StatusManager::redact_secrets_from_run_calls(&mut new_run_calls, &secret_redactor);
+        // Clear out any calls we've already stored for this job so we don't end up with duplicates
+        if let Err(e) = RunCallData::delete_by_cromwell_job_id(conn, cromwell_job_id) {
+            error!(
+                "Failed to delete existing calls for {} job {} for run {} with error: {}",
+                workflow, cromwell_job_id, run.run_id, e
+            );
+            return;
+        }
+        if new_run_calls.is_empty() {
+            return;
+        }
+        if let Err(e) = RunCallData::batch_create(conn, new_run_calls) {
+            error!(
+                "Failed to store calls for {} job {} for run {} with error: {}",
+                workflow, cromwell_job_id, run.run_id, e
+            );
+        }
+    }
+
+    /// Replaces any secret values in the docker images and runtime attributes of `run_calls` with
+    /// REDACTED using `secret_redactor`
+    fn redact_secrets_from_run_calls(
+        run_calls: &mut [NewRunCall],
+        secret_redactor: &SecretRedactor,
+    ) {
+        for run_call in run_calls {
+            if let Some(docker_image) = &run_call.docker_image {
+                run_call.docker_image = Some(secret_redactor.redact_str(docker_image));
+            }
+            if let Some(runtime_attributes) = &mut run_call.runtime_attributes {
+                secret_redactor.redact_json(runtime_attributes);
+            }
+        }
+    }
+
+    /// Builds a NewRunCall for each attempt of each call in the `calls` section of `metadata`,
+    /// which should be the metadata for the `workflow` workflow of the run with id `run_id`, with
+    /// cromwell job id `cromwell_job_id`
+    fn get_run_calls_from_metadata(
+        run_id: Uuid,
+        cromwell_job_id: &str,
+        workflow: RunCallWorkflowEnum,
+        metadata: &Map<String, Value>,
+    ) -> Vec<NewRunCall> {
+        let mut new_run_calls: Vec<NewRunCall> = Vec::new();
+        let calls = match metadata.get("calls").and_then(|calls| calls.as_object()) {
+            Some(calls) => calls,
+            None => return new_run_calls,
+        };
+        // Calls are keyed by task name, with an entry for each shard and attempt
+        for (task_name, task_calls) in calls {
+            let task_calls = match task_calls.as_array() {
+                Some(task_calls) => task_calls,
+                None => continue,
+            };
+            for call in task_calls {
+                let get_str = |key: &str| -> Option<String> {
+                    call.get(key)
+                        .and_then(|value| value.as_str())
+                        .map(String::from)
+                };
+                let get_time = |key: &str| -> Option<NaiveDateTime> {
+                    call.get(key)
+                        .and_then(|value| value.as_str())
+                        .and_then(|value| {
+                            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.fZ").ok()
+                        })
+                };
+                new_run_calls.push(NewRunCall {
+                    run_id,
+                    workflow,
+                    cromwell_job_id: String::from(cromwell_job_id),
+                    task_name: task_name.clone(),
+                    // Cromwell uses -1 as the shard index for calls that aren't scattered
+                    shard_index: call
+                        .get("shardIndex")
+                        .and_then(|value| value.as_i64())
+                        .unwrap_or(-1) as i32,
+                    attempt: call
+                        .get("attempt")
+                        .and_then(|value| value.as_i64())
+                        .unwrap_or(1) as i32,
+                    execution_status: get_str("executionStatus"),
+                    backend_status: get_str("backendStatus"),
+                    return_code: call
+                        .get("returnCode")
+                        .and_then(|value| value.as_i64())
+                        .map(|value| value as i32),
+                    docker_image: get_str("dockerImageUsed"),
+                    runtime_attributes: call
+                        .get("runtimeAttributes")
+                        .filter(|value| !value.is_null())
+                        .cloned(),
+                    started_at: get_time("start"),
+                    finished_at: get_time("end"),
+                });
+            }
+        }
+        new_run_calls
+    }
+
+    /// Records each of the root causes of the failures in `metadata` (the metadata for the `step`
+    /// workflow of `run`) as a run_error for `run`, with any secret values redacted using
+    /// `secret_encryptor`
+    fn log_cromwell_failures(
+        conn: &PgConnection,
+        run: &RunData,
+        metadata: &Map<String, Value>,
+        step: &str,
+        secret_encryptor: Option<&SecretEncryptor>,
+    ) {
+        let failures = match metadata.get("failures").and_then(|value| value.as_array()) {
+            Some(failures) => failures,
+            None => return,
+        };
+        for message in log_retriever::get_failure_messages(failures) {
+            run_error_logger::log_error_redacting_secrets(
+                conn,
+                run.run_id,
+                format!("Cromwell {} workflow failure: {}", step, message),
+                secret_encryptor,
+            );
+        }
+    }
+
+    /// Returns true if `status` indicates that the test or eval job for a run is still in progress in
+    /// cromwell and has not been asked to abort
+    fn is_cromwell_job_in_progress(status: &RunStatusEnum) -> bool {
+        matches!(
+            status,
+            RunStatusEnum::TestSubmitted
+                | RunStatusEnum::TestQueuedInCromwell
+                | RunStatusEnum::TestStarting
+                | RunStatusEnum::TestRunning
+                | RunStatusEnum::TestWaitingForQueueSpace
+                | RunStatusEnum::EvalSubmitted
+                | RunStatusEnum::EvalQueuedInCromwell
+                | RunStatusEnum::EvalStarting
+                | RunStatusEnum::EvalRunning
+                | RunStatusEnum::EvalWaitingForQueueSpace
+        )
+    }
+
+    /// Aborts `run` if the cromwell job described by `metadata` was submitted longer ago than the
+    /// max runtime for `run`
+    ///
+    /// The max runtime is the `max_runtime_in_secs` of `run`'s test if it has one, or the
+    /// `default_max_runtime_in_secs` from the config otherwise.  If the job is past it, sends an
+    /// abort request through the test runner (which marks `run` as aborting) and records a run_error
+    /// explaining that the `step` job timed out.  Returns true if `run` was aborted, false if there
+    /// is no max runtime or the job is still within it, or an error if there is an issue retrieving
+    /// the test, parsing the submission time, or aborting the run
+    async fn abort_run_if_past_max_runtime(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+        metadata: &Map<String, Value>,
+        step: &str,
+    ) -> Result<bool, UpdateStatusError> {
+        // Get the max runtime from the test, or fall back to the default from the config
+        let test = match TestData::find_by_id(conn, run.test_id) {
+            Ok(test) => test,
+            Err(e) => {
+                return Err(UpdateStatusError::DB(format!(
+                    "Retrieving test for run in DB failed with error {}",
+                    e
+                )))
+            }
+        };
+        let max_runtime_in_secs: u64 = match test.max_runtime_in_secs {
+            Some(max_runtime) => match u64::try_from(max_runtime) {
+                Ok(max_runtime) => max_runtime,
+                // Tests can't be given a negative max runtime, so this shouldn't happen, but if it
+                // does, don't abort anything because of it
+                Err(_) => {
+                    error!(
+                        "Test {} for run {} has an invalid max runtime of {} seconds, so not checking it",
+                        test.test_id, run.run_id, max_runtime
+                    );
+                    return Ok(false);
+                }
+            },
+            None => match self.config.default_max_runtime_in_secs() {
+                Some(max_runtime) => max_runtime,
+                None => return Ok(false),
+            },
+        };
+        // Figure out how long it's been since the job was submitted (treating a submission time in
+        // the future as no time at all)
+        let submission = StatusManager::get_timestamp(metadata, "submission")?;
+        let runtime = Utc::now().naive_utc() - submission;
+        if u64::try_from(runtime.num_seconds())
+            .map_or(true, |runtime| runtime <= max_runtime_in_secs)
+        {
+            return Ok(false);
+        }
+        // If it's over, abort it and record why
+        self.test_runner.abort_run(conn, run).await?;
+        run_error_logger::log_error(
+            conn,
+            run.run_id,
+            format!(
+                "Timed out: {} workflow was aborted after exceeding the maximum runtime of {} seconds",
+                step, max_runtime_in_secs
+            ),
+        );
+        Ok(true)
+    }
+
+    /// Returns true if starting the `workflow` workflow for `run` failed and is waiting to be
+    /// retried (see `TestRunner::retry_run_start`), or an error if the check fails
+    fn has_pending_start_retry(
+        conn: &PgConnection,
+        run: &RunData,
+        workflow: RunCallWorkflowEnum,
+    ) -> Result<bool, UpdateStatusError> {
+        let run_start_attempts = RunStartAttemptData::find_by_run_id(conn, run.run_id)
+            .map_err(|e| UpdateStatusError::DB(e.to_string()))?;
+        Ok(run_start_attempts.iter().any(|run_start_attempt| {
+            run_start_attempt.workflow == workflow && run_start_attempt.next_attempt_at.is_some()
+        }))
+    }
+
+    /// Checks whether the count of run_result records for `run` matches the number expected, based on
+    /// the number of results mapped to its template.  Returns Ok(()) if so, or an error if not
+    fn check_result_counts(conn: &PgConnection, run: &RunData) -> Result<(), UpdateStatusError> {
+        // Get count of template_result mappings for the template corresponding to this run
+        let template_result_count = match TemplateResultData::find_count_for_test(conn, run.test_id)
+        {
+            Ok(template_results) => template_results,
+            Err(e) => {
+                return Err(UpdateStatusError::DB(format!(
+                    "Failed to load count of template result mappings from DB with error: {}",
+                    e
+                )));
             }
         };
         // Get count of run_results we've inserted for this run
@@ -850,11 +1799,13 @@ impl StatusManager {
         Ok(())
     }
 
-    /// Gets status for software build from cromwell and updates in DB if appropriate
+    /// Gets status for software build from cromwell (or uses `queried_status`, if it is provided and
+    /// the build job hasn't finished) and updates in DB if appropriate
     async fn check_and_update_build_status(
         &self,
         build: &SoftwareBuildData,
         conn: &PgConnection,
+        queried_status: Option<&Map<String, Value>>,
     ) -> Result<(), UpdateStatusError> {
         // If this build has a status of 'Created', start it
         if matches!(build.status, BuildStatusEnum::Created) {
@@ -879,6 +1830,7 @@ impl StatusManager {
                         status: Some(BuildStatusEnum::Failed),
                         build_job_id: None,
                         finished_at: Some(Utc::now().naive_utc()),
+                        cromwell_server: None,
                     };
                     // Update
                     match SoftwareBuildData::update(
@@ -899,7 +1851,11 @@ impl StatusManager {
         }
         // Get metadata
         let metadata = self
-            .get_status_metadata_from_cromwell(&build.build_job_id.as_ref().unwrap())
+            .get_status_metadata(
+                build.cromwell_server.as_deref(),
+                build.build_job_id.as_ref().unwrap(),
+                queried_status,
+            )
             .await?;
         // If the status is different from what's stored in the DB currently, update it
         let status = match metadata.get("status") {
@@ -957,6 +1913,7 @@ impl StatusManager {
                         status: Some(status.clone()),
                         build_job_id: None,
                         finished_at: Some(StatusManager::get_end(&metadata)?),
+                        cromwell_server: None,
                     }
                 }
                 BuildStatusEnum::Failed | BuildStatusEnum::Aborted => SoftwareBuildChangeset {
@@ -964,12 +1921,14 @@ impl StatusManager {
                     status: Some(status.clone()),
                     build_job_id: None,
                     finished_at: Some(StatusManager::get_end(&metadata)?),
+                    cromwell_server: None,
                 },
                 _ => SoftwareBuildChangeset {
                     image_url: None,
                     status: Some(status.clone()),
                     build_job_id: None,
                     finished_at: None,
+                    cromwell_server: None,
                 },
             };
             // Update
@@ -992,15 +1951,21 @@ impl StatusManager {
     /// Retrieves metadata information for the cromwell job tied to `run_report` via a request to
     /// cromwell's metadata API mapping, updates the status for `run_report` in the DB if the retreived
     /// status is different, and, in the case that it is a terminal status, sends notifications to
-    /// subscribed users and fills results
+    /// subscribed users and fills results.  If `queried_status` is provided, it is used instead of
+    /// retrieving metadata, unless the job has finished
     async fn check_and_update_run_report_status(
         &self,
         run_report: &RunReportData,
         conn: &PgConnection,
+        queried_status: Option<&Map<String, Value>>,
     ) -> Result<(), UpdateStatusError> {
         // Get metadata
         let metadata = self
-            .get_status_metadata_from_cromwell(&run_report.cromwell_job_id.as_ref().unwrap())
+            .get_status_metadata(
+                run_report.cromwell_server.as_deref(),
+                run_report.cromwell_job_id.as_ref().unwrap(),
+                queried_status,
+            )
             .await?;
         // If the status is different from what's stored in the DB currently, update it
         let status = match metadata.get("status") {
@@ -1036,12 +2001,14 @@ impl StatusManager {
                     cromwell_job_id: None,
                     finished_at: Some(StatusManager::get_end(&metadata)?),
                     results: None,
+                    cromwell_server: None,
                 },
                 _ => RunReportChangeset {
                     status: Some(status.clone()),
                     cromwell_job_id: None,
                     finished_at: None,
                     results: None,
+                    cromwell_server: None,
                 },
             };
             // Update
@@ -1122,35 +2089,114 @@ impl StatusManager {
             cromwell_job_id: None,
             results: Some(Value::Object(run_report_outputs_map)),
             finished_at: Some(StatusManager::get_end(metadata)?),
+            cromwell_server: None,
         })
     }
 
+    /// Gets the metadata we need for checking the status of `cromwell_job_id` on `cromwell_server`
+    /// (or the default server, if None)
+    ///
+    /// If `queried_status` (the job's id, status, submission, start, and end from a bulk query to
+    /// cromwell) is provided and the job hasn't finished, that is all we need, so it is returned.
+    /// Otherwise, we need the job's outputs and failures as well, so the full status metadata is
+    /// retrieved from cromwell (see `get_status_metadata_from_cromwell`)
+    async fn get_status_metadata(
+        &self,
+        cromwell_server: Option<&str>,
+        cromwell_job_id: &str,
+        queried_status: Option<&Map<String, Value>>,
+    ) -> Result<Map<String, Value>, UpdateStatusError> {
+        if let Some(queried_status) = queried_status {
+            if let Some(status) = queried_status
+                .get("status")
+                .and_then(|status| status.as_str())
+            {
+                let cromwell_status: CromwellStatus = (&*status.to_lowercase()).into();
+                if !matches!(
+                    cromwell_status,
+                    CromwellStatus::Succeeded | CromwellStatus::Failed | CromwellStatus::Aborted
+                ) {
+                    return Ok(queried_status.to_owned());
+                }
+            }
+        }
+        self.get_status_metadata_from_cromwell(cromwell_server, cromwell_job_id)
+            .await
+    }
+
+    /// Gets the statuses of the cromwell jobs specified by `cromwell_jobs` (pairs of the server
+    /// each job was submitted to, or None for the default server, and its id) from the execution
+    /// backend (via cromwell's query api, for cromwell).  Jobs are grouped by server, and each
+    /// server is queried in batches of up to WORKFLOW_STATUS_QUERY_BATCH_SIZE ids per request
+    ///
+    /// Returns a map of job ids to the results for those jobs, each containing the id, status,
+    /// and (where available) submission, start, and end for the job.  Jobs that the backend doesn't
+    /// return a result for will not be in the map
+    async fn query_workflow_statuses_from_cromwell(
+        &self,
+        cromwell_jobs: Vec<(Option<String>, String)>,
+    ) -> Result<HashMap<String, Map<String, Value>>, UpdateStatusError> {
+        // Group the job ids by the server they were submitted to
+        let mut job_ids_by_server: HashMap<Option<String>, Vec<String>> = HashMap::new();
+        for (cromwell_server, job_id) in cromwell_jobs {
+            job_ids_by_server
+                .entry(cromwell_server)
+                .or_default()
+                .push(job_id);
+        }
+        let mut workflow_statuses: HashMap<String, Map<String, Value>> = HashMap::new();
+        for (cromwell_server, job_ids) in job_ids_by_server {
+            let execution_client = self
+                .server_router
+                .execution_client(cromwell_server.as_deref())?;
+            for batch in job_ids.chunks(WORKFLOW_STATUS_QUERY_BATCH_SIZE) {
+                match execution_client.get_workflow_statuses(batch).await {
+                    Ok(batch_statuses) => workflow_statuses.extend(batch_statuses),
+                    Err(e) => return Err(UpdateStatusError::Cromwell(e.to_string())),
+                }
+            }
+        }
+        Ok(workflow_statuses)
+    }
+
     /// Gets the metadata from cromwell that we actually care about for `cromwell_job_id`
     ///
-    /// Gets the status, end, and outputs for the cromwell job specified by `cromwell_job_id` from the
-    /// cromwell metadata endpoint using `client` to connect
+    /// Gets the status, end, outputs, submission, and failures for the cromwell job specified by
+    /// `cromwell_job_id` from the execution backend for `cromwell_server` (or the default server,
+    /// if None)
     async fn get_status_metadata_from_cromwell(
         &self,
+        cromwell_server: Option<&str>,
         cromwell_job_id: &str,
     ) -> Result<Map<String, Value>, UpdateStatusError> {
-        // Get metadata
-        let params = cromwell_requests::MetadataParams {
-            exclude_key: None,
-            expand_sub_workflows: None,
-            // We only care about status, outputs, and end since we just want to know if the status has changed, and the end time and outputs if it finished
-            include_key: Some(vec![
-                String::from("status"),
-                String::from("end"),
-                String::from("outputs"),
-            ]),
-            metadata_source: None,
-        };
-        let metadata = self
-            .cromwell_client
-            .get_metadata(cromwell_job_id, &params)
-            .await;
-        match metadata {
-            Ok(value) => Ok(value.as_object().unwrap().to_owned()),
+        match self
+            .server_router
+            .execution_client(cromwell_server)?
+            .get_workflow_status_metadata(cromwell_job_id)
+            .await
+        {
+            Ok(metadata) => Ok(metadata),
+            Err(e) => Err(UpdateStatusError::Cromwell(e.to_string())),
+        }
+    }
+
+    /// Gets the metadata from cromwell for the calls for `cromwell_job_id`
+    ///
+    /// Gets the calls, with the fields we store in the run_call table, for the cromwell job
+    /// specified by `cromwell_job_id` from the execution backend for `cromwell_server` (or the
+    /// default server, if None)
+    async fn get_call_metadata_from_cromwell(
+        &self,
+        cromwell_server: Option<&str>,
+        cromwell_job_id: &str,
+    ) -> Result<Map<String, Value>, UpdateStatusError> {
+        match self
+            .server_router
+            .execution_client(cromwell_server)?
+            .get_workflow_call_metadata(cromwell_job_id)
+            .await
+        {
+            Ok(metadata) => Ok(metadata),
             Err(e) => Err(UpdateStatusError::Cromwell(e.to_string())),
         }
     }
@@ -1230,76 +2276,47 @@ impl StatusManager {
 
     /// Extracts value for `end` key from `metadata` and parses it into a NaiveDateTime
     fn get_end(metadata: &Map<String, Value>) -> Result<NaiveDateTime, UpdateStatusError> {
-        let end = match metadata.get("end") {
-            Some(end) => end.as_str().unwrap(),
+        StatusManager::get_timestamp(metadata, "end")
+    }
+
+    /// Extracts value for `key` from `metadata` and parses it into a NaiveDateTime
+    fn get_timestamp(
+        metadata: &Map<String, Value>,
+        key: &str,
+    ) -> Result<NaiveDateTime, UpdateStatusError> {
+        let timestamp = match metadata.get(key).and_then(|value| value.as_str()) {
+            Some(timestamp) => timestamp,
             None => {
-                return Err(UpdateStatusError::Cromwell(String::from(
-                    "Cromwell metadata request did not return end",
+                return Err(UpdateStatusError::Cromwell(format!(
+                    "Cromwell metadata request did not return {}",
+                    key
                 )))
             }
         };
-        match NaiveDateTime::parse_from_str(end, "%Y-%m-%dT%H:%M:%S%.fZ") {
-            Ok(end) => Ok(end),
+        match NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.fZ") {
+            Ok(timestamp) => Ok(timestamp),
             Err(_) => {
                 return Err(UpdateStatusError::Cromwell(format!(
-                    "Failed to parse end time from Cromwell metadata: {}",
-                    end
+                    "Failed to parse {} time from Cromwell metadata: {}",
+                    key, timestamp
                 )))
             }
         }
     }
     /// Writes records to the `run_result` table for each of the outputs in `outputs` for which there
     /// are mappings in the `template_result` table for the template from which `run` is derived and
-    /// which have a key matching the `template_result` record's `result_key` column
+    /// which have a key matching the `template_result` record's `result_key` column, with any
+    /// secret values redacted using `secret_encryptor`
     fn fill_results(
         outputs: &Map<String, Value>,
         run: &RunData,
         conn: &PgConnection,
+        secret_encryptor: Option<&SecretEncryptor>,
     ) -> Result<(), UpdateStatusError> {
-        // Get template_result mappings for the template corresponding to this run
-        let template_results = match TemplateResultData::find_for_test(conn, run.test_id) {
-            Ok(template_results) => template_results,
-            Err(e) => {
-                return Err(UpdateStatusError::DB(format!(
-                    "Failed to load result mappings from DB with error: {}",
-                    e
-                )));
-            }
-        };
-
-        // Keep a running list of results to write to the DB
-        let mut result_list: Vec<NewRunResult> = Vec::new();
-
-        // Loop through template_results, check for each of the keys in outputs, and add them to list to write
-        for template_result in template_results {
-            // Check outputs for this result
-            match outputs.get(&template_result.result_key) {
-                // If we found it, add it to the list of results to write to the DB
-                Some(output) => {
-                    // We have to parse some of the possible result so they're not enclosed in the
-                    // redundant quotes that would be there if we used output.to_string() for
-                    // everything
-                    let parsed_output: String = match output {
-                        Value::String(string_val) => string_val.to_string(),
-                        Value::Bool(bool_val) => bool_val.to_string(),
-                        Value::Number(number_val) => number_val.to_string(),
-                        _ => output.to_string(),
-                    };
-                    result_list.push(NewRunResult {
-                        run_id: run.run_id.clone(),
-                        result_id: template_result.result_id.clone(),
-                        value: parsed_output,
-                    });
-                }
-                None => {}
-            }
-        }
-
-        // Write result_list to the DB (return error if it fails)
-        if let Err(_) = RunResultData::batch_create(conn, result_list) {
+        if let Err(e) = test_runner::fill_results(conn, run, outputs, secret_encryptor) {
             return Err(UpdateStatusError::DB(format!(
-                "Failed to write results to DB for run {}",
-                run.run_id
+                "Failed to write results to DB for run {} with error: {}",
+                run.run_id, e
             )));
         }
 
@@ -1310,22 +2327,27 @@ impl StatusManager {
 #[cfg(test)]
 mod tests {
 
+    use crate::config::{CreatedRecoveryPolicy, StatusManagerConfig};
     use crate::custom_sql_types::{
-        BuildStatusEnum, ReportStatusEnum, ResultTypeEnum, RunStatusEnum,
+        BuildStatusEnum, ReportStatusEnum, ResultTypeEnum, RunCallWorkflowEnum, RunStatusEnum,
     };
     use crate::db::DbPool;
     use crate::manager::notification_handler::NotificationHandler;
     use crate::manager::report_builder::ReportBuilder;
+    use crate::manager::server_router::ServerRouter;
     use crate::manager::software_builder::SoftwareBuilder;
-    use crate::manager::status_manager::StatusManager;
+    use crate::manager::status_manager::{StatusManager, UpdateStatusError};
     use crate::manager::test_runner::TestRunner;
     use crate::models::pipeline::{NewPipeline, PipelineData};
     use crate::models::report::{NewReport, ReportData};
     use crate::models::result::{NewResult, ResultData};
     use crate::models::run::{NewRun, RunData, RunWithResultsAndErrorsData};
+    use crate::models::run_call::{NewRunCall, RunCallData, RunCallQuery};
     use crate::models::run_is_from_github::{NewRunIsFromGithub, RunIsFromGithubData};
     use crate::models::run_report::{NewRunReport, RunReportData};
     use crate::models::run_software_version::{NewRunSoftwareVersion, RunSoftwareVersionData};
+    use crate::models::run_start_attempt::RunStartAttemptData;
+    use crate::models::secret::{NewSecret, SecretData};
     use crate::models::software::{NewSoftware, SoftwareData};
     use crate::models::software_build::{NewSoftwareBuild, SoftwareBuildData};
     use crate::models::software_version::{NewSoftwareVersion, SoftwareVersionData};
@@ -1335,16 +2357,17 @@ mod tests {
     use crate::models::test::{NewTest, TestData};
     use crate::notifications::emailer::Emailer;
     use crate::notifications::github_commenter::GithubCommenter;
-    use crate::requests::cromwell_requests::CromwellClient;
     use crate::requests::github_requests::GithubClient;
     use crate::requests::test_resource_requests::TestResourceClient;
     use crate::storage::gcloud_storage::GCloudClient;
     use crate::unit_test_util::{get_test_db_pool, load_default_config};
+    use crate::util::secrets::{self, SecretRedactor};
     use actix_web::client::Client;
     use chrono::{NaiveDateTime, Utc};
     use diesel::PgConnection;
     use google_storage1::Object;
     use serde_json::{json, Value};
+    use std::cell::Cell;
     use std::fs::{read_to_string, File};
     use std::sync::mpsc;
     use tempfile::TempDir;
@@ -1439,6 +2462,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("test_send_email@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -1453,6 +2479,9 @@ mod tests {
             eval_wdl: format!("{}/eval.wdl", mockito::server_url()),
             eval_wdl_dependencies: None,
             created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(&conn, new_template).expect("Failed to insert test")
@@ -1470,6 +2499,35 @@ mod tests {
             ),
             eval_option_defaults: None,
             created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        TestData::create(&conn, new_test).expect("Failed to insert test")
+    }
+
+    fn insert_test_test_with_template_id_and_max_runtime(
+        conn: &PgConnection,
+        id: Uuid,
+        max_runtime_in_secs: i32,
+    ) -> TestData {
+        let new_test = NewTest {
+            name: String::from("Kevin's test test with a max runtime"),
+            template_id: id,
+            description: None,
+            test_input_defaults: Some(json!({"greeting_workflow.in_greeting": "Yo"})),
+            test_option_defaults: None,
+            eval_input_defaults: Some(
+                json!({"greeting_file_workflow.in_output_filename": "greeting.txt"}),
+            ),
+            eval_option_defaults: None,
+            created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: Some(max_runtime_in_secs),
+            cromwell_server: None,
         };
 
         TestData::create(&conn, new_test).expect("Failed to insert test")
@@ -1623,6 +2681,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("test_send_email@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -1637,6 +2698,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("test_send_email@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -1651,6 +2715,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("test_send_email@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let test = TestData::create(conn, new_test).expect("Failed inserting test test");
@@ -1685,6 +2753,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("test_send_email@example.com")),
             finished_at: None,
+            cromwell_server: None,
         };
 
         RunReportData::create(conn, new_run_report).expect("Failed inserting test run_report")
@@ -1747,8 +2816,15 @@ mod tests {
     }
 
     fn create_test_status_manager(db_pool: DbPool) -> StatusManager {
-        let carrot_config = load_default_config();
         let (_, channel_recv) = mpsc::channel();
+        create_test_status_manager_with_channel(db_pool, channel_recv)
+    }
+
+    fn create_test_status_manager_with_channel(
+        db_pool: DbPool,
+        channel_recv: mpsc::Receiver<()>,
+    ) -> StatusManager {
+        let carrot_config = load_default_config();
 
         // Make a client that'll be used for http requests
         let http_client: Client = Client::default();
@@ -1799,24 +2875,35 @@ mod tests {
         // Create a notification handler
         let notification_handler: NotificationHandler =
             NotificationHandler::new(emailer, github_commenter);
-        // Create a test resource client and cromwell client for the test runner
+        // Create a test resource client and server router for the test runner
         let test_resource_client: TestResourceClient =
             TestResourceClient::new(http_client.clone(), gcloud_client.clone());
-        let cromwell_client: CromwellClient =
-            CromwellClient::new(http_client.clone(), carrot_config.cromwell().address());
+        let server_router: ServerRouter = ServerRouter::new(http_client.clone(), &carrot_config);
         // Create a test runner and software builder
         let test_runner: TestRunner = match carrot_config.custom_image_build() {
             Some(image_build_config) => TestRunner::new(
-                cromwell_client.clone(),
+                server_router.clone(),
                 test_resource_client.clone(),
                 Some(image_build_config.image_registry_host()),
+                carrot_config.status_manager().max_concurrent_runs(),
+                carrot_config.retry().clone(),
+                carrot_config.cromwell().workflow_option_defaults().cloned(),
+                None,
+            ),
+            None => TestRunner::new(
+                server_router.clone(),
+                test_resource_client.clone(),
+                None,
+                carrot_config.status_manager().max_concurrent_runs(),
+                carrot_config.retry().clone(),
+                carrot_config.cromwell().workflow_option_defaults().cloned(),
+                None,
             ),
-            None => TestRunner::new(cromwell_client.clone(), test_resource_client.clone(), None),
         };
         // Create a software builder
         let software_builder: Option<SoftwareBuilder> = match carrot_config.custom_image_build() {
             Some(image_build_config) => Some(SoftwareBuilder::new(
-                cromwell_client.clone(),
+                server_router.clone(),
                 &image_build_config,
             )),
             None => None,
@@ -1825,7 +2912,7 @@ mod tests {
         let report_builder: Option<ReportBuilder> = match carrot_config.reporting() {
             Some(reporting_config) => {
                 // We can unwrap gcloud_client because reporting won't work without it
-                Some(ReportBuilder::new(cromwell_client.clone(), gcloud_client.expect("Failed to unwrap gcloud_client to create report builder.  This should not happen").clone(), reporting_config))
+                Some(ReportBuilder::new(server_router.clone(), gcloud_client.expect("Failed to unwrap gcloud_client to create report builder.  This should not happen").clone(), reporting_config))
             }
             None => None,
         };
@@ -1837,7 +2924,7 @@ mod tests {
             notification_handler,
             test_runner,
             software_builder,
-            cromwell_client,
+            server_router,
             report_builder,
         )
     }
@@ -1934,7 +3021,7 @@ mod tests {
         });
         let results_map = results_map.as_object().unwrap().to_owned();
         // Fill results
-        StatusManager::fill_results(&results_map, &test_run, &conn).unwrap();
+        StatusManager::fill_results(&results_map, &test_run, &conn, None).unwrap();
         // Query for run to make sure data was filled properly
         let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
         let results = result_run.results.unwrap().as_object().unwrap().to_owned();
@@ -1950,6 +3037,136 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_fill_results_redacts_secrets() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let secret_encryptor = secrets::get_secret_encryptor(&load_default_config()).unwrap();
+        SecretData::create(
+            &conn,
+            NewSecret {
+                name: String::from("kevins_token"),
+                description: None,
+                encrypted_value: secret_encryptor.encrypt("hunter2").unwrap(),
+                created_by: None,
+            },
+        )
+        .unwrap();
+        // Insert test, run, result, and template_result we'll use for testing
+        let template = insert_test_template(&conn);
+        let test_result = insert_test_result_with_name_and_type(
+            &conn,
+            String::from("Text Result"),
+            ResultTypeEnum::Text,
+        );
+        insert_test_template_result_with_template_id_and_result_id_and_result_key(
+            &conn,
+            template.template_id,
+            test_result.result_id,
+            String::from("greeting_workflow.TestKey"),
+        );
+        let test_test = insert_test_test_with_template_id(&conn, template.template_id);
+        let test_run =
+            insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+        // Create results map with a secret value in it
+        let results_map = json!({
+            "greeting_workflow.TestKey": "Logged in with hunter2",
+        });
+        let results_map = results_map.as_object().unwrap().to_owned();
+        // Fill results
+        StatusManager::fill_results(&results_map, &test_run, &conn, Some(&secret_encryptor))
+            .unwrap();
+        // Query for run to make sure the secret was redacted
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        let results = result_run.results.unwrap().as_object().unwrap().to_owned();
+        assert_eq!(
+            results.get("Text Result").unwrap(),
+            "Logged in with [REDACTED]"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_process_concurrently() {
+        let pool = get_test_db_pool();
+        let (_channel_send, channel_recv) = mpsc::channel();
+        let test_status_manager = create_test_status_manager_with_channel(pool, channel_recv);
+
+        let processed: Cell<u32> = Cell::new(0);
+        let mut consecutive_failures: u32 = 0;
+        // Process some items, with one failure
+        let terminated = test_status_manager
+            .process_concurrently(
+                vec![1, 2, 3, 4, 5, 6],
+                3,
+                &mut consecutive_failures,
+                |item: u32| {
+                    let processed = &processed;
+                    async move {
+                        processed.set(processed.get() + 1);
+                        if item == 3 {
+                            Err(UpdateStatusError::DB(format!("Failed on {}", item)))
+                        } else {
+                            Ok(())
+                        }
+                    }
+                },
+            )
+            .await
+            .unwrap();
+
+        assert!(!terminated);
+        assert_eq!(processed.get(), 6);
+        assert_eq!(consecutive_failures, 1);
+    }
+
+    #[actix_rt::test]
+    async fn test_process_concurrently_too_many_failures() {
+        let pool = get_test_db_pool();
+        let (_channel_send, channel_recv) = mpsc::channel();
+        let test_status_manager = create_test_status_manager_with_channel(pool, channel_recv);
+
+        let mut consecutive_failures: u32 = 0;
+        // Fail more times than the allowed consecutive failures in the test config (1)
+        let result = test_status_manager
+            .process_concurrently(
+                vec![1, 2, 3, 4, 5, 6, 7, 8],
+                4,
+                &mut consecutive_failures,
+                |item: u32| async move {
+                    Err(UpdateStatusError::DB(format!("Failed on {}", item)))
+                },
+            )
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(consecutive_failures, 2);
+    }
+
+    #[actix_rt::test]
+    async fn test_process_concurrently_terminated() {
+        let pool = get_test_db_pool();
+        let (channel_send, channel_recv) = mpsc::channel();
+        let test_status_manager = create_test_status_manager_with_channel(pool, channel_recv);
+        // Send a terminate message before we start
+        channel_send.send(()).unwrap();
+
+        let processed: Cell<u32> = Cell::new(0);
+        let mut consecutive_failures: u32 = 0;
+        let terminated = test_status_manager
+            .process_concurrently(vec![1, 2, 3], 2, &mut consecutive_failures, |_item: u32| {
+                let processed = &processed;
+                async move {
+                    processed.set(processed.get() + 1);
+                    Ok(())
+                }
+            })
+            .await
+            .unwrap();
+
+        assert!(terminated);
+        assert_eq!(processed.get(), 0);
+    }
+
     #[actix_rt::test]
     async fn test_check_and_update_run_status_test_succeeded() {
         let pool = get_test_db_pool();
@@ -2005,7 +3222,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2013,7 +3230,7 @@ mod tests {
         .create();
         // Check and update status
         test_status_manager
-            .check_and_update_run_status(&test_run, &conn)
+            .check_and_update_run_status(&test_run, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -2060,7 +3277,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/12345612-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/12345612-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2079,7 +3296,7 @@ mod tests {
         let github_mock = setup_github_mock();
         // Check and update status
         test_status_manager
-            .check_and_update_run_status(&test_run, &conn)
+            .check_and_update_run_status(&test_run, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -2141,7 +3358,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/12345612-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/12345612-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2161,7 +3378,7 @@ mod tests {
         let github_mock = setup_github_mock();
         // Check and update status
         let error = test_status_manager
-            .check_and_update_run_status(&test_run, &conn)
+            .check_and_update_run_status(&test_run, &conn, None)
             .await
             .unwrap_err();
         assert!(matches!(error, super::UpdateStatusError::Results(_)));
@@ -2199,23 +3416,62 @@ mod tests {
           "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
           "status": "Failed",
           "outputs": {},
-          "end": "2020-12-31T11:11:11.0000Z"
+          "end": "2020-12-31T11:11:11.0000Z",
+          "failures": [
+            {
+              "message": "Workflow failed",
+              "causedBy": [
+                {
+                  "message": "Job greeting_workflow.say_hello:NA:1 exited with return code 1",
+                  "causedBy": []
+                }
+              ]
+            }
+          ]
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
         .with_body(mock_response_body.to_string())
         .create();
+        // Define mockito mapping for the cromwell response with the calls for the job
+        let calls_mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "calls": {
+            "greeting_workflow.say_hello": [
+              {
+                "shardIndex": -1,
+                "attempt": 1,
+                "executionStatus": "Failed",
+                "backendStatus": "Done",
+                "returnCode": 1,
+                "dockerImageUsed": "ubuntu:latest",
+                "runtimeAttributes": {"cpu": "1"},
+                "start": "2020-12-31T10:11:11.0000Z",
+                "end": "2020-12-31T11:01:11.0000Z"
+              }
+            ]
+          }
+        });
+        let calls_mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=calls&includeKey=shardIndex&includeKey=attempt&includeKey=executionStatus&includeKey=backendStatus&includeKey=returnCode&includeKey=dockerImageUsed&includeKey=runtimeAttributes&includeKey=start&includeKey=end",
+        )
+        .with_status(201)
+        .with_header("content_type", "application/json")
+        .with_body(calls_mock_response_body.to_string())
+        .create();
         let github_mock = setup_github_mock();
         // Check and update status
         test_status_manager
-            .check_and_update_run_status(&test_run, &conn)
+            .check_and_update_run_status(&test_run, &conn, None)
             .await
             .unwrap();
         mock.assert();
+        calls_mock.assert();
         // Query for run to make sure data was filled properly
         let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
         assert_eq!(result_run.status, RunStatusEnum::TestFailed);
@@ -2224,20 +3480,205 @@ mod tests {
             NaiveDateTime::parse_from_str("2020-12-31T11:11:11.0000Z", "%Y-%m-%dT%H:%M:%S%.fZ")
                 .unwrap()
         );
+        // Make sure the call was stored
+        let result_calls = RunCallData::find(
+            &conn,
+            RunCallQuery {
+                run_id: Some(test_run.run_id),
+                workflow: None,
+                cromwell_job_id: None,
+                task_name: None,
+                execution_status: None,
+                backend_status: None,
+                return_code: None,
+                docker_image: None,
+                started_before: None,
+                started_after: None,
+                finished_before: None,
+                finished_after: None,
+                sort: None,
+                limit: None,
+                offset: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(result_calls.len(), 1);
+        assert_eq!(result_calls[0].workflow, RunCallWorkflowEnum::Test);
+        assert_eq!(result_calls[0].task_name, "greeting_workflow.say_hello");
+        assert_eq!(result_calls[0].execution_status.as_deref(), Some("Failed"));
+        assert_eq!(result_calls[0].return_code, Some(1));
+        // Make sure the failure from cromwell was recorded as an error
+        let errors = result_run.errors.unwrap().to_string();
+        assert!(errors.contains(
+            "Cromwell test workflow failure: Workflow failed: Job greeting_workflow.say_hello:NA:1 exited with return code 1"
+        ));
     }
 
-    #[actix_rt::test]
-    async fn test_check_and_update_run_status_test_running() {
-        let pool = get_test_db_pool();
-        let conn = pool.get().unwrap();
-        let test_status_manager = create_test_status_manager(pool);
-        // Insert test and run we'll use for testing
-        let template = insert_test_template(&conn);
-        let template_id = template.template_id;
-        let test_test = insert_test_test_with_template_id(&conn, template_id);
-        let test_run = insert_test_run_with_test_id_and_status_test_submitted(
+    #[test]
+    fn test_redact_secrets_from_run_calls() {
+        let conn = get_test_db_pool().get().unwrap();
+        let secret_encryptor = secrets::get_secret_encryptor(&load_default_config()).unwrap();
+        SecretData::create(
             &conn,
-            test_test.test_id.clone(),
+            NewSecret {
+                name: String::from("kevins_token"),
+                description: None,
+                encrypted_value: secret_encryptor.encrypt("hunter2").unwrap(),
+                created_by: None,
+            },
+        )
+        .unwrap();
+        let secret_redactor = SecretRedactor::load(&conn, Some(&secret_encryptor)).unwrap();
+        let run_id = Uuid::new_v4();
+        let mut run_calls = vec![
+            NewRunCall {
+                run_id,
+                workflow: RunCallWorkflowEnum::Test,
+                cromwell_job_id: String::from("53709600-d114-4194-a7f7-9e41211ca2ce"),
+                task_name: String::from("greeting_workflow.say_hello"),
+                shard_index: -1,
+                attempt: 1,
+                execution_status: Some(String::from("Done")),
+                backend_status: Some(String::from("Done")),
+                return_code: Some(0),
+                docker_image: Some(String::from("example.com/hunter2/ubuntu:latest")),
+                runtime_attributes: Some(json!({"cpu": "1", "env": ["TOKEN=hunter2"]})),
+                started_at: None,
+                finished_at: None,
+            },
+            NewRunCall {
+                run_id,
+                workflow: RunCallWorkflowEnum::Test,
+                cromwell_job_id: String::from("53709600-d114-4194-a7f7-9e41211ca2ce"),
+                task_name: String::from("greeting_workflow.say_goodbye"),
+                shard_index: -1,
+                attempt: 1,
+                execution_status: Some(String::from("Running")),
+                backend_status: None,
+                return_code: None,
+                docker_image: None,
+                runtime_attributes: None,
+                started_at: None,
+                finished_at: None,
+            },
+        ];
+
+        StatusManager::redact_secrets_from_run_calls(&mut run_calls, &secret_redactor);
+
+        assert_eq!(
+            run_calls[0].docker_image.as_deref(),
+            Some("example.com/[REDACTED]/ubuntu:latest")
+        );
+        assert_eq!(
+            run_calls[0].runtime_attributes,
+            Some(json!({"cpu": "1", "env": ["TOKEN=[REDACTED]"]}))
+        );
+        assert_eq!(run_calls[1].docker_image, None);
+        assert_eq!(run_calls[1].runtime_attributes, None);
+    }
+
+    #[test]
+    fn test_get_run_calls_from_metadata() {
+        let run_id = Uuid::new_v4();
+        let metadata = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "calls": {
+            "greeting_workflow.say_hello": [
+              {
+                "shardIndex": 0,
+                "attempt": 1,
+                "executionStatus": "RetryableFailure",
+                "backendStatus": "Preempted",
+                "dockerImageUsed": "ubuntu:latest",
+                "runtimeAttributes": {"cpu": "1", "preemptible": "1"},
+                "start": "2020-12-31T10:11:11.0000Z",
+                "end": "2020-12-31T10:31:11.0000Z"
+              },
+              {
+                "shardIndex": 0,
+                "attempt": 2,
+                "executionStatus": "Running",
+                "backendStatus": "Running",
+                "start": "2020-12-31T10:31:12.0000Z"
+              }
+            ]
+          }
+        });
+
+        let new_run_calls = StatusManager::get_run_calls_from_metadata(
+            run_id,
+            "53709600-d114-4194-a7f7-9e41211ca2ce",
+            RunCallWorkflowEnum::Eval,
+            metadata.as_object().unwrap(),
+        );
+
+        assert_eq!(
+            new_run_calls,
+            vec![
+                NewRunCall {
+                    run_id,
+                    workflow: RunCallWorkflowEnum::Eval,
+                    cromwell_job_id: String::from("53709600-d114-4194-a7f7-9e41211ca2ce"),
+                    task_name: String::from("greeting_workflow.say_hello"),
+                    shard_index: 0,
+                    attempt: 1,
+                    execution_status: Some(String::from("RetryableFailure")),
+                    backend_status: Some(String::from("Preempted")),
+                    return_code: None,
+                    docker_image: Some(String::from("ubuntu:latest")),
+                    runtime_attributes: Some(json!({"cpu": "1", "preemptible": "1"})),
+                    started_at: Some(
+                        NaiveDateTime::parse_from_str(
+                            "2020-12-31T10:11:11.0000Z",
+                            "%Y-%m-%dT%H:%M:%S%.fZ"
+                        )
+                        .unwrap()
+                    ),
+                    finished_at: Some(
+                        NaiveDateTime::parse_from_str(
+                            "2020-12-31T10:31:11.0000Z",
+                            "%Y-%m-%dT%H:%M:%S%.fZ"
+                        )
+                        .unwrap()
+                    ),
+                },
+                NewRunCall {
+                    run_id,
+                    workflow: RunCallWorkflowEnum::Eval,
+                    cromwell_job_id: String::from("53709600-d114-4194-a7f7-9e41211ca2ce"),
+                    task_name: String::from("greeting_workflow.say_hello"),
+                    shard_index: 0,
+                    attempt: 2,
+                    execution_status: Some(String::from("Running")),
+                    backend_status: Some(String::from("Running")),
+                    return_code: None,
+                    docker_image: None,
+                    runtime_attributes: None,
+                    started_at: Some(
+                        NaiveDateTime::parse_from_str(
+                            "2020-12-31T10:31:12.0000Z",
+                            "%Y-%m-%dT%H:%M:%S%.fZ"
+                        )
+                        .unwrap()
+                    ),
+                    finished_at: None,
+                },
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_check_and_update_run_status_test_running() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let test_status_manager = create_test_status_manager(pool);
+        // Insert test and run we'll use for testing
+        let template = insert_test_template(&conn);
+        let template_id = template.template_id;
+        let test_test = insert_test_test_with_template_id(&conn, template_id);
+        let test_run = insert_test_run_with_test_id_and_status_test_submitted(
+            &conn,
+            test_test.test_id.clone(),
         );
         // Define mockito mapping for cromwell response
         let mock_response_body = json!({
@@ -2248,7 +3689,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2256,7 +3697,99 @@ mod tests {
         .create();
         // Check and update status
         test_status_manager
-            .check_and_update_run_status(&test_run, &conn)
+            .check_and_update_run_status(&test_run, &conn, None)
+            .await
+            .unwrap();
+        mock.assert();
+        // Query for run to make sure data was filled properly
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::TestRunning);
+    }
+
+    #[actix_rt::test]
+    async fn test_check_and_update_run_status_eval_start_retry_pending() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let test_status_manager = create_test_status_manager(pool);
+        // Insert test and run we'll use for testing
+        let template = insert_test_template(&conn);
+        let template_id = template.template_id;
+        let test_test = insert_test_test_with_template_id(&conn, template_id);
+        let test_run = insert_test_run_with_test_id_and_status_test_submitted(
+            &conn,
+            test_test.test_id.clone(),
+        );
+        // Record a failed attempt at starting the eval workflow that is waiting to be retried
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Eval).unwrap();
+        RunStartAttemptData::schedule_retry(
+            &conn,
+            test_run.run_id,
+            RunCallWorkflowEnum::Eval,
+            Utc::now().naive_utc() + chrono::Duration::seconds(60),
+        )
+        .unwrap();
+        // Define mockito mappings to ensure nothing is requested from cromwell
+        let metadata_mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
+        )
+        .expect(0)
+        .create();
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .expect(0)
+            .create();
+        // Check and update status
+        test_status_manager
+            .check_and_update_run_status(&test_run, &conn, None)
+            .await
+            .unwrap();
+        metadata_mock.assert();
+        cromwell_mock.assert();
+        // Starting the eval job is left for the retry, so it should be unchanged
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::TestSubmitted);
+        assert!(result_run.eval_cromwell_job_id.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_check_and_update_run_status_test_running_queried_status() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let test_status_manager = create_test_status_manager(pool);
+        // Insert test and run we'll use for testing
+        let template = insert_test_template(&conn);
+        let template_id = template.template_id;
+        let test_test = insert_test_test_with_template_id(&conn, template_id);
+        let test_run = insert_test_run_with_test_id_and_status_test_submitted(
+            &conn,
+            test_test.test_id.clone(),
+        );
+        // Define mockito mapping for cromwell metadata, which we don't expect to be called since we
+        // already have the status
+        let mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
+        )
+        .with_status(201)
+        .with_header("content_type", "application/json")
+        .with_body("{}")
+        .expect(0)
+        .create();
+        // Status from a query to cromwell
+        let queried_status = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "name": "test_workflow",
+          "status": "Running",
+          "submission": Utc::now().naive_utc().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+          "start": Utc::now().naive_utc().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+        });
+        // Check and update status
+        test_status_manager
+            .check_and_update_run_status(
+                &test_run,
+                &conn,
+                Some(queried_status.as_object().unwrap()),
+            )
             .await
             .unwrap();
         mock.assert();
@@ -2287,21 +3820,228 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/12345612-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/12345612-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
+        )
+        .with_status(201)
+        .with_header("content_type", "application/json")
+        .with_body(mock_response_body.to_string())
+        .create();
+        // Check and update status
+        test_status_manager
+            .check_and_update_run_status(&test_run, &conn, None)
+            .await
+            .unwrap();
+        mock.assert();
+        // Query for run to make sure data was filled properly
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::EvalRunning);
+    }
+
+    #[actix_rt::test]
+    async fn test_check_and_update_run_status_test_past_max_runtime() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let test_status_manager = create_test_status_manager(pool);
+        // Insert test and run we'll use for testing
+        let template = insert_test_template(&conn);
+        let template_id = template.template_id;
+        let test_test = insert_test_test_with_template_id_and_max_runtime(&conn, template_id, 60);
+        let test_run = insert_test_run_with_test_id_and_status_test_submitted(
+            &conn,
+            test_test.test_id.clone(),
+        );
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Running",
+          "outputs": {},
+          "end": null,
+          "submission": "2020-12-31T11:11:11.0000Z"
+        });
+        let mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
+        )
+        .with_status(201)
+        .with_header("content_type", "application/json")
+        .with_body(mock_response_body.to_string())
+        .create();
+        // The job has been running for much longer than a minute, so it should be aborted
+        let abort_mock = mockito::mock(
+            "POST",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/abort",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(
+            json!({
+              "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+              "status": "Aborting"
+            })
+            .to_string(),
+        )
+        .create();
+        // Check and update status
+        test_status_manager
+            .check_and_update_run_status(&test_run, &conn, None)
+            .await
+            .unwrap();
+        mock.assert();
+        abort_mock.assert();
+        // Query for run to make sure it's aborting and the timeout was recorded
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::TestAborting);
+        assert!(result_run.finished_at.is_none());
+        let errors = result_run.errors.unwrap().to_string();
+        assert!(errors.contains(
+            "Timed out: test workflow was aborted after exceeding the maximum runtime of 60 seconds"
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn test_check_and_update_run_status_eval_within_max_runtime() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let test_status_manager = create_test_status_manager(pool);
+        // Insert test and run we'll use for testing
+        let template = insert_test_template(&conn);
+        let template_id = template.template_id;
+        let test_test = insert_test_test_with_template_id_and_max_runtime(&conn, template_id, 3600);
+        let test_run = insert_test_run_with_test_id_and_status_eval_submitted(
+            &conn,
+            test_test.test_id.clone(),
+        );
+        // Define mockito mapping for cromwell response with a job that was just submitted
+        let mock_response_body = json!({
+          "id": "12345612-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Running",
+          "outputs": {},
+          "end": null,
+          "submission": Utc::now().naive_utc().format("%Y-%m-%dT%H:%M:%S%.fZ").to_string()
+        });
+        let mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/12345612-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
         .with_body(mock_response_body.to_string())
         .create();
+        // It's still within its max runtime, so it shouldn't be aborted
+        let abort_mock = mockito::mock(
+            "POST",
+            "/api/workflows/v1/12345612-d114-4194-a7f7-9e41211ca2ce/abort",
+        )
+        .with_status(200)
+        .expect(0)
+        .create();
         // Check and update status
         test_status_manager
-            .check_and_update_run_status(&test_run, &conn)
+            .check_and_update_run_status(&test_run, &conn, None)
             .await
             .unwrap();
         mock.assert();
+        abort_mock.assert();
         // Query for run to make sure data was filled properly
         let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
         assert_eq!(result_run.status, RunStatusEnum::EvalRunning);
+        assert!(result_run.errors.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_check_and_update_run_status_test_aborting_still_running() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let test_status_manager = create_test_status_manager(pool);
+        // Insert test and run we'll use for testing
+        let template = insert_test_template(&conn);
+        let template_id = template.template_id;
+        let test_test = insert_test_test_with_template_id(&conn, template_id);
+        let test_run = insert_test_run_with_test_id_and_status(
+            &conn,
+            test_test.test_id,
+            RunStatusEnum::TestAborting,
+        );
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Running",
+          "outputs": {},
+          "end": null
+        });
+        let mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
+        )
+        .with_status(201)
+        .with_header("content_type", "application/json")
+        .with_body(mock_response_body.to_string())
+        .create();
+        // Check and update status
+        test_status_manager
+            .check_and_update_run_status(&test_run, &conn, None)
+            .await
+            .unwrap();
+        mock.assert();
+        // Query for run to make sure it's still aborting
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::TestAborting);
+        assert!(result_run.finished_at.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_check_and_update_run_status_test_aborting_succeeded() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let test_status_manager = create_test_status_manager(pool);
+        // Set up email dir for the notification email
+        let _email_dir = setup_test_email_dir("test_send_email");
+        // Insert test and run we'll use for testing
+        let template = insert_test_template(&conn);
+        let template_id = template.template_id;
+        let test_test = insert_test_test_with_template_id(&conn, template_id);
+        let test_run = insert_test_run_with_test_id_and_status(
+            &conn,
+            test_test.test_id,
+            RunStatusEnum::TestAborting,
+        );
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Succeeded",
+          "outputs": {
+            "greeting_workflow.TestKey": "TestVal"
+          },
+          "end": "2020-12-31T11:11:11.0000Z"
+        });
+        let mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
+        )
+        .with_status(201)
+        .with_header("content_type", "application/json")
+        .with_body(mock_response_body.to_string())
+        .create();
+        // The eval job should not be started
+        let cromwell_start_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .expect(0)
+            .create();
+        // Check and update status
+        test_status_manager
+            .check_and_update_run_status(&test_run, &conn, None)
+            .await
+            .unwrap();
+        mock.assert();
+        cromwell_start_mock.assert();
+        // Query for run to make sure it was marked aborted
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::TestAborted);
+        assert_eq!(
+            result_run.finished_at.unwrap(),
+            NaiveDateTime::parse_from_str("2020-12-31T11:11:11.0000Z", "%Y-%m-%dT%H:%M:%S%.fZ")
+                .unwrap()
+        );
     }
 
     #[actix_rt::test]
@@ -2343,7 +4083,7 @@ mod tests {
         .create();
         // Check and update status
         test_status_manager
-            .check_and_update_run_status(&test_run, &conn)
+            .check_and_update_run_status(&test_run, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -2398,7 +4138,7 @@ mod tests {
             .create();
         // Check and update status
         let error = test_status_manager
-            .check_and_update_run_status(&test_run, &conn)
+            .check_and_update_run_status(&test_run, &conn, None)
             .await
             .unwrap_err();
         assert!(matches!(
@@ -2411,6 +4151,77 @@ mod tests {
         assert_eq!(result_run.status, RunStatusEnum::CarrotFailed);
     }
 
+    #[actix_rt::test]
+    async fn test_check_and_update_run_status_queued() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let test_status_manager = create_test_status_manager(pool);
+        // Insert template, test, and run we'll use for testing
+        let test_template = insert_test_template(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status(
+            &conn,
+            test_test.test_id.clone(),
+            RunStatusEnum::Queued,
+        );
+
+        // Define mockito mappings to ensure nothing is retrieved or submitted
+        let wdl_mock = mockito::mock("GET", "/test.wdl").expect(0).create();
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .expect(0)
+            .create();
+        // Check and update status
+        test_status_manager
+            .check_and_update_run_status(&test_run, &conn, None)
+            .await
+            .unwrap();
+        wdl_mock.assert();
+        cromwell_mock.assert();
+        // Queued runs are started separately, so it should be unchanged
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::Queued);
+    }
+
+    #[actix_rt::test]
+    async fn test_start_queued_run_wdl_retrieval_failed() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let test_status_manager = create_test_status_manager(pool);
+        // Set up email dir for the notification email
+        let _email_dir = setup_test_email_dir("test_send_email");
+        // Insert template, test, and run we'll use for testing
+        let test_template = insert_test_template(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status(
+            &conn,
+            test_test.test_id.clone(),
+            RunStatusEnum::Queued,
+        );
+
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test.wdl")
+            .with_status(404)
+            .expect(1)
+            .create();
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .expect(0)
+            .create();
+        // Try to start the run
+        let error = test_status_manager
+            .start_queued_run(&test_run, &conn)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            super::UpdateStatusError::Run(crate::manager::test_runner::Error::ResourceRequest(_))
+        ));
+        wdl_mock.assert();
+        cromwell_mock.assert();
+        // Query for run to make sure it was marked as failed
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::CarrotFailed);
+    }
+
     #[actix_rt::test]
     async fn test_check_and_update_run_status_builds_finished() {
         let pool = get_test_db_pool();
@@ -2456,7 +4267,7 @@ mod tests {
 
         // Check and update status
         test_status_manager
-            .check_and_update_run_status(&test_run, &conn)
+            .check_and_update_run_status(&test_run, &conn, None)
             .await
             .unwrap();
         cromwell_mock.assert();
@@ -2469,6 +4280,249 @@ mod tests {
         );
     }
 
+    #[actix_rt::test]
+    async fn test_recover_stranded_created_runs_and_builds_fail() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let mut test_status_manager = create_test_status_manager(pool);
+        // Set the threshold to 0 so anything created is considered stranded
+        test_status_manager.config =
+            StatusManagerConfig::new(1, 1, None, false, None, 10, 0, CreatedRecoveryPolicy::Fail);
+        // Set up email dir for the notification email
+        let _email_dir = setup_test_email_dir("test_send_email");
+        // Insert template, test, run, and build we'll use for testing
+        let test_template = insert_test_template(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status(
+            &conn,
+            test_test.test_id.clone(),
+            RunStatusEnum::Created,
+        );
+        let test_software_version = insert_test_software_version(&conn);
+        let test_software_build = insert_test_software_build_for_version_with_status(
+            &conn,
+            test_software_version.software_version_id,
+            BuildStatusEnum::Created,
+        );
+
+        let mut consecutive_failures: u32 = 0;
+        test_status_manager
+            .recover_stranded_created_runs_and_builds(&conn, &mut consecutive_failures)
+            .await
+            .unwrap();
+        assert_eq!(consecutive_failures, 0);
+        // Make sure the run was failed and has an error explaining why
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::CarrotFailed);
+        let errors = result_run.errors.unwrap().to_string();
+        assert!(errors.contains("Run was stranded in created status for more than 0 seconds"));
+        // Make sure the build was failed
+        let result_build =
+            SoftwareBuildData::find_by_id(&conn, test_software_build.software_build_id).unwrap();
+        assert_eq!(result_build.status, BuildStatusEnum::Failed);
+        assert!(result_build.finished_at.is_some());
+    }
+
+    #[actix_rt::test]
+    async fn test_recover_stranded_created_runs_and_builds_resubmit() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let mut test_status_manager = create_test_status_manager(pool);
+        // Set the threshold to 0 so anything created is considered stranded
+        test_status_manager.config = StatusManagerConfig::new(
+            1,
+            1,
+            None,
+            false,
+            None,
+            10,
+            0,
+            CreatedRecoveryPolicy::Resubmit,
+        );
+        // Insert template, test, run, and build we'll use for testing
+        let test_template = insert_test_template(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status(
+            &conn,
+            test_test.test_id.clone(),
+            RunStatusEnum::Created,
+        );
+        let test_software_version = insert_test_software_version(&conn);
+        let test_software_build = insert_test_software_build_for_version_with_status(
+            &conn,
+            test_software_version.software_version_id,
+            BuildStatusEnum::Created,
+        );
+
+        // Define mockito mapping for the query for workflows already submitted for the run
+        let query_mock = mockito::mock("POST", "/api/workflows/v1/query")
+            .match_body(mockito::Matcher::Json(json!([
+                {"label": "carrot-instance:carrot"},
+                {"label": format!("carrot-run-id:{}", test_run.run_id)},
+                {"includeSubworkflows": "false"},
+                {"additionalQueryResultFields": "labels"}
+            ])))
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(json!({"results": [], "totalResultsCount": 0}).to_string())
+            .expect(1)
+            .create();
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test.wdl")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/run/test_wdl.wdl").unwrap())
+            .expect(1)
+            .create();
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .expect(1)
+            .create();
+
+        let mut consecutive_failures: u32 = 0;
+        test_status_manager
+            .recover_stranded_created_runs_and_builds(&conn, &mut consecutive_failures)
+            .await
+            .unwrap();
+        assert_eq!(consecutive_failures, 0);
+        query_mock.assert();
+        wdl_mock.assert();
+        cromwell_mock.assert();
+        // Make sure the run was resubmitted and has an error explaining why
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::TestSubmitted);
+        assert_eq!(
+            result_run.test_cromwell_job_id.unwrap(),
+            "53709600-d114-4194-a7f7-9e41211ca2ce"
+        );
+        let errors = result_run.errors.unwrap().to_string();
+        assert!(errors.contains("so it is being resubmitted"));
+        // The build should be left alone so it can be started by the build status check
+        let result_build =
+            SoftwareBuildData::find_by_id(&conn, test_software_build.software_build_id).unwrap();
+        assert_eq!(result_build.status, BuildStatusEnum::Created);
+    }
+
+    #[actix_rt::test]
+    async fn test_recover_stranded_created_runs_and_builds_resubmit_already_submitted() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let mut test_status_manager = create_test_status_manager(pool);
+        // Set the threshold to 0 so anything created is considered stranded
+        test_status_manager.config = StatusManagerConfig::new(
+            1,
+            1,
+            None,
+            false,
+            None,
+            10,
+            0,
+            CreatedRecoveryPolicy::Resubmit,
+        );
+        // Insert template, test, and run we'll use for testing
+        let test_template = insert_test_template(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status(
+            &conn,
+            test_test.test_id.clone(),
+            RunStatusEnum::Created,
+        );
+
+        // Define mockito mapping for the query, which finds the test workflow that was submitted
+        // before CARROT could record it
+        let query_mock = mockito::mock("POST", "/api/workflows/v1/query")
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(
+                json!({
+                    "results": [
+                        {
+                            "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+                            "status": "Running",
+                            "labels": {
+                                "carrot-instance": "carrot",
+                                "carrot-stage": "test",
+                                "carrot-run-id": test_run.run_id.to_string()
+                            }
+                        }
+                    ],
+                    "totalResultsCount": 1
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+        // Define mockito mappings to ensure nothing is resubmitted
+        let wdl_mock = mockito::mock("GET", "/test.wdl").expect(0).create();
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .expect(0)
+            .create();
+
+        let mut consecutive_failures: u32 = 0;
+        test_status_manager
+            .recover_stranded_created_runs_and_builds(&conn, &mut consecutive_failures)
+            .await
+            .unwrap();
+        assert_eq!(consecutive_failures, 0);
+        query_mock.assert();
+        wdl_mock.assert();
+        cromwell_mock.assert();
+        // Make sure the run is tracking the existing workflow and has an error explaining why
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::TestSubmitted);
+        assert_eq!(
+            result_run.test_cromwell_job_id.unwrap(),
+            "53709600-d114-4194-a7f7-9e41211ca2ce"
+        );
+        assert_eq!(result_run.cromwell_server.unwrap(), "default");
+        let errors = result_run.errors.unwrap().to_string();
+        assert!(errors.contains("so that workflow is being tracked instead of resubmitting"));
+    }
+
+    #[actix_rt::test]
+    async fn test_recover_stranded_created_runs_and_builds_start_retry_pending() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let mut test_status_manager = create_test_status_manager(pool);
+        // Set the threshold to 0 so anything created is considered stranded
+        test_status_manager.config =
+            StatusManagerConfig::new(1, 1, None, false, None, 10, 0, CreatedRecoveryPolicy::Fail);
+        // Insert template, test, and run we'll use for testing
+        let test_template = insert_test_template(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status(
+            &conn,
+            test_test.test_id.clone(),
+            RunStatusEnum::Created,
+        );
+        // Record a failed attempt at starting the test workflow that is waiting to be retried
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Test).unwrap();
+        RunStartAttemptData::schedule_retry(
+            &conn,
+            test_run.run_id,
+            RunCallWorkflowEnum::Test,
+            Utc::now().naive_utc() + chrono::Duration::seconds(60),
+        )
+        .unwrap();
+
+        let mut consecutive_failures: u32 = 0;
+        test_status_manager
+            .recover_stranded_created_runs_and_builds(&conn, &mut consecutive_failures)
+            .await
+            .unwrap();
+        assert_eq!(consecutive_failures, 0);
+        // The run isn't stranded, so it should be left for the retry
+        let result_run = RunWithResultsAndErrorsData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(result_run.status, RunStatusEnum::Created);
+        assert!(result_run.errors.is_none());
+    }
+
     #[actix_rt::test]
     async fn test_check_and_update_build_status_succeeded() {
         let pool = get_test_db_pool();
@@ -2489,7 +4543,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2497,7 +4551,7 @@ mod tests {
         .create();
         // Check and update status
         test_status_manager
-            .check_and_update_build_status(&test_build, &conn)
+            .check_and_update_build_status(&test_build, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -2516,6 +4570,144 @@ mod tests {
         );
     }
 
+    #[actix_rt::test]
+    async fn test_check_and_update_build_status_succeeded_queried_status() {
+        let pool = get_test_db_pool();
+        let conn = pool.get().unwrap();
+        let test_status_manager = create_test_status_manager(pool);
+        let test_build = insert_test_software_build(&conn);
+        // Define mockito mapping for cromwell response, which should be retrieved since the query
+        // results don't include outputs
+        let mock_response_body = json!({
+          "id": "ca92ed46-cb1e-4486-b8ff-fc48d7771e67",
+          "status": "Succeeded",
+          "outputs": {
+            "docker_build.image_url": "test.gcr.io/test_project/test_image:test",
+          },
+          "end": "2020-12-31T11:11:11.0000Z"
+        });
+        let mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
+        )
+        .with_status(201)
+        .with_header("content_type", "application/json")
+        .with_body(mock_response_body.to_string())
+        .expect(1)
+        .create();
+        // Status from a query to cromwell
+        let queried_status = json!({
+          "id": "ca92ed46-cb1e-4486-b8ff-fc48d7771e67",
+          "name": "docker_build",
+          "status": "Succeeded",
+          "submission": "2020-12-31T10:11:11.000Z",
+          "start": "2020-12-31T10:11:12.000Z",
+          "end": "2020-12-31T11:11:11.000Z"
+        });
+        // Check and update status
+        test_status_manager
+            .check_and_update_build_status(
+                &test_build,
+                &conn,
+                Some(queried_status.as_object().unwrap()),
+            )
+            .await
+            .unwrap();
+        mock.assert();
+        // Query for build to make sure data was filled properly
+        let result_build =
+            SoftwareBuildData::find_by_id(&conn, test_build.software_build_id).unwrap();
+        assert_eq!(result_build.status, BuildStatusEnum::Succeeded);
+        assert_eq!(
+            result_build.image_url.unwrap(),
+            "test.gcr.io/test_project/test_image:test"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_query_workflow_statuses_from_cromwell() {
+        let pool = get_test_db_pool();
+        let test_status_manager = create_test_status_manager(pool);
+        // Define mockito mapping for cromwell query response
+        let mock_response_body = json!({
+          "results": [
+            {
+              "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+              "name": "test_workflow",
+              "status": "Running",
+              "submission": "2020-12-31T10:11:11.000Z",
+              "start": "2020-12-31T10:11:12.000Z"
+            },
+            {
+              "id": "12345612-d114-4194-a7f7-9e41211ca2ce",
+              "name": "eval_workflow",
+              "status": "Failed",
+              "submission": "2020-12-31T10:11:11.000Z",
+              "start": "2020-12-31T10:11:12.000Z",
+              "end": "2020-12-31T11:11:11.000Z"
+            }
+          ],
+          "totalResultsCount": 2
+        });
+        let mock = mockito::mock("POST", "/api/workflows/v1/query")
+            .match_body(mockito::Matcher::Json(json!([
+                {"id": "53709600-d114-4194-a7f7-9e41211ca2ce"},
+                {"id": "12345612-d114-4194-a7f7-9e41211ca2ce"},
+                {"id": "ca92ed46-cb1e-4486-b8ff-fc48d7771e67"}
+            ])))
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .expect(1)
+            .create();
+
+        let workflow_statuses = test_status_manager
+            .query_workflow_statuses_from_cromwell(vec![
+                (None, String::from("53709600-d114-4194-a7f7-9e41211ca2ce")),
+                (None, String::from("12345612-d114-4194-a7f7-9e41211ca2ce")),
+                (None, String::from("ca92ed46-cb1e-4486-b8ff-fc48d7771e67")),
+            ])
+            .await
+            .unwrap();
+        mock.assert();
+
+        assert_eq!(workflow_statuses.len(), 2);
+        assert_eq!(
+            workflow_statuses
+                .get("53709600-d114-4194-a7f7-9e41211ca2ce")
+                .unwrap()
+                .get("status")
+                .unwrap(),
+            "Running"
+        );
+        assert_eq!(
+            workflow_statuses
+                .get("12345612-d114-4194-a7f7-9e41211ca2ce")
+                .unwrap()
+                .get("status")
+                .unwrap(),
+            "Failed"
+        );
+        assert!(workflow_statuses
+            .get("ca92ed46-cb1e-4486-b8ff-fc48d7771e67")
+            .is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_query_workflow_statuses_from_cromwell_unknown_server() {
+        let pool = get_test_db_pool();
+        let test_status_manager = create_test_status_manager(pool);
+
+        let result = test_status_manager
+            .query_workflow_statuses_from_cromwell(vec![(
+                Some(String::from("not_a_server")),
+                String::from("53709600-d114-4194-a7f7-9e41211ca2ce"),
+            )])
+            .await;
+
+        assert!(matches!(result, Err(UpdateStatusError::Server(_))));
+    }
+
     #[actix_rt::test]
     async fn test_check_and_update_build_status_failed() {
         let pool = get_test_db_pool();
@@ -2534,7 +4726,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2542,7 +4734,7 @@ mod tests {
         .create();
         // Check and update status
         test_status_manager
-            .check_and_update_build_status(&test_build, &conn)
+            .check_and_update_build_status(&test_build, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -2576,7 +4768,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2584,7 +4776,7 @@ mod tests {
         .create();
         // Check and update status
         test_status_manager
-            .check_and_update_build_status(&test_build, &conn)
+            .check_and_update_build_status(&test_build, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -2625,7 +4817,7 @@ mod tests {
             .create();
         // Check and update status
         test_status_manager
-            .check_and_update_build_status(&test_build, &conn)
+            .check_and_update_build_status(&test_build, &conn, None)
             .await
             .unwrap();
         cromwell_mock.assert();
@@ -2657,7 +4849,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2665,7 +4857,7 @@ mod tests {
         .create();
         // Check and update status
         test_status_manager
-            .check_and_update_build_status(&test_build, &conn)
+            .check_and_update_build_status(&test_build, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -2701,7 +4893,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(200)
         .with_header("content_type", "application/json")
@@ -2710,7 +4902,7 @@ mod tests {
         let github_mock = setup_github_mock();
         // Check and update status
         test_status_manager
-            .check_and_update_run_report_status(&run_report, &conn)
+            .check_and_update_run_report_status(&run_report, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -2753,7 +4945,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2761,7 +4953,7 @@ mod tests {
         .create();
         // Check and update status
         test_status_manager
-            .check_and_update_run_report_status(&run_report, &conn)
+            .check_and_update_run_report_status(&run_report, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -2795,7 +4987,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2803,7 +4995,7 @@ mod tests {
         .create();
         // Check and update status
         test_status_manager
-            .check_and_update_run_report_status(&run_report, &conn)
+            .check_and_update_run_report_status(&run_report, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -2835,7 +5027,7 @@ mod tests {
         });
         let mock = mockito::mock(
             "GET",
-            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs",
+            "/api/workflows/v1/ca92ed46-cb1e-4486-b8ff-fc48d7771e67/metadata?includeKey=status&includeKey=end&includeKey=outputs&includeKey=submission&includeKey=failures",
         )
         .with_status(201)
         .with_header("content_type", "application/json")
@@ -2843,7 +5035,7 @@ mod tests {
         .create();
         // Check and update status
         test_status_manager
-            .check_and_update_run_report_status(&run_report, &conn)
+            .check_and_update_run_report_status(&run_report, &conn, None)
             .await
             .unwrap();
         mock.assert();
@@ -3,20 +3,40 @@
 //! The processing of running a test, once it has been defined and a request has been made to the
 //! test run mapping, is divided into multiple steps defined here
 
-use crate::custom_sql_types::{BuildStatusEnum, RunStatusEnum};
+use crate::config::{RetriableError, RetryConfig};
+use crate::custom_sql_types::{
+    BuildStatusEnum, RerunModeEnum, ResultTypeEnum, RunCallWorkflowEnum, RunStatusEnum,
+    WorkflowLanguageEnum,
+};
+use crate::manager::server_router::{self, ServerRouter};
+use crate::manager::util::{
+    WorkflowLabels, WorkflowStage, INSTANCE_LABEL, RUN_ID_LABEL, STAGE_LABEL,
+};
 use crate::manager::{software_builder, util};
+use crate::models::pipeline::PipelineData;
+use crate::models::result::ResultData;
 use crate::models::run::{NewRun, RunChangeset, RunData, RunQuery};
+use crate::models::run_in_group::{NewRunInGroup, RunInGroupData};
+use crate::models::run_input_source::{NewRunInputSource, RunInputSourceData};
+use crate::models::run_is_rerun::{NewRunIsRerun, RunIsRerunData};
+use crate::models::run_result::{NewRunResult, RunResultData};
 use crate::models::run_software_version::{NewRunSoftwareVersion, RunSoftwareVersionData};
+use crate::models::run_start_attempt::RunStartAttemptData;
 use crate::models::software::SoftwareData;
 use crate::models::software_build::SoftwareBuildData;
 use crate::models::software_version::SoftwareVersionData;
 use crate::models::template::TemplateData;
+use crate::models::template_result::TemplateResultData;
 use crate::models::test::TestData;
 use crate::requests::cromwell_requests::{
-    CromwellClient, CromwellRequestError, WorkflowIdAndStatus,
+    CromwellRequestError, WorkflowIdAndStatus, WorkflowQueryParams,
 };
 use crate::requests::test_resource_requests;
+use crate::run_error_logger;
+use crate::util::secrets::{self, SecretEncryptor, SecretRedactor};
 use crate::util::temp_storage;
+use crate::validation::wdl_inputs;
+use crate::validation::womtool::WomtoolRunner;
 use chrono::Utc;
 use diesel::PgConnection;
 use log::error;
@@ -30,12 +50,25 @@ use uuid::Uuid;
 
 lazy_static! {
     // Build regex for matching values specifying custom builds
-    static ref IMAGE_BUILD_REGEX: Regex =
+    pub(crate) static ref IMAGE_BUILD_REGEX: Regex =
         Regex::new(r"image_build:\w[^\|]*\|.*").unwrap();
 
     // Build regex for matching values specifying test outputs
-    static ref TEST_OUTPUT_REGEX: Regex =
+    pub(crate) static ref TEST_OUTPUT_REGEX: Regex =
         Regex::new(r"test_output:[a-zA-Z][a-zA-Z0-9_]+\.[a-zA-Z][a-zA-Z0-9_]+").unwrap();
+
+    // Build regex for matching values specifying test outputs from CWL workflows, which can be
+    // referred to by just their id or qualified with a workflow name (e.g. main.out, #main/out)
+    pub(crate) static ref CWL_TEST_OUTPUT_REGEX: Regex =
+        Regex::new(r"^test_output:#?[a-zA-Z_][\w\-]*([.#/][a-zA-Z_][\w\-]*)*$").unwrap();
+
+    // Build regexes for matching values referencing the outputs of other runs, either by run name
+    // or id (run_output:{run}.{output}) or by the most recent succeeded run of a test
+    // (latest_successful_output:{test_name}.{output})
+    static ref RUN_OUTPUT_REGEX: Regex =
+        Regex::new(r"^run_output:.+\..+$").unwrap();
+    static ref LATEST_SUCCESSFUL_OUTPUT_REGEX: Regex =
+        Regex::new(r"^latest_successful_output:.+\..+$").unwrap();
 }
 
 /// Enum for denoting whether a run is still building, has finished, or has failed builds
@@ -46,6 +79,17 @@ pub enum RunBuildStatus {
     Failed,
 }
 
+/// An input for a run whose value was resolved from the outputs of another run, which is recorded
+/// in the RUN_INPUT_SOURCE table once the run has been created
+#[derive(Debug, PartialEq)]
+struct ResolvedInput {
+    workflow: RunCallWorkflowEnum,
+    input_key: String,
+    reference: String,
+    source_run_id: Uuid,
+    value: Value,
+}
+
 /// Error type for possible errors returned by running a test
 #[derive(Debug)]
 pub enum Error {
@@ -59,6 +103,12 @@ pub enum Error {
     Build(software_builder::Error),
     MissingOutputKey(String),
     ResourceRequest(test_resource_requests::Error),
+    AlreadyFinished,
+    RerunNotAllowed(String),
+    Server(server_router::Error),
+    Inputs(wdl_inputs::Error),
+    Secrets(secrets::Error),
+    InputReference(String),
 }
 
 impl std::error::Error for Error {}
@@ -80,6 +130,12 @@ impl fmt::Display for Error {
                 k
             ),
             Error::ResourceRequest(e) => write!(f, "Error ResourceRequest: {}", e),
+            Error::AlreadyFinished => write!(f, "Error AlreadyFinished"),
+            Error::RerunNotAllowed(msg) => write!(f, "Error RerunNotAllowed: {}", msg),
+            Error::Server(e) => write!(f, "Error Server {}", e),
+            Error::Inputs(e) => write!(f, "Error Inputs {}", e),
+            Error::Secrets(e) => write!(f, "Error Secrets {}", e),
+            Error::InputReference(msg) => write!(f, "Error InputReference: {}", msg),
         }
     }
 }
@@ -109,39 +165,87 @@ impl From<test_resource_requests::Error> for Error {
         Error::ResourceRequest(e)
     }
 }
+impl From<server_router::Error> for Error {
+    fn from(e: server_router::Error) -> Error {
+        Error::Server(e)
+    }
+}
+impl From<wdl_inputs::Error> for Error {
+    fn from(e: wdl_inputs::Error) -> Error {
+        Error::Inputs(e)
+    }
+}
+impl From<secrets::Error> for Error {
+    fn from(e: secrets::Error) -> Error {
+        Error::Secrets(e)
+    }
+}
 
 /// Struct for operations related to running tests.
 #[derive(Clone)]
 pub struct TestRunner {
-    cromwell_client: CromwellClient,
+    server_router: ServerRouter,
     test_resource_client: test_resource_requests::TestResourceClient,
     image_registry_host: Option<String>,
+    max_concurrent_runs: Option<u32>,
+    retry_config: RetryConfig,
+    workflow_option_defaults: Option<Value>,
+    secret_encryptor: Option<SecretEncryptor>,
 }
 
 impl TestRunner {
-    /// Creates a new TestRunner that will use `cromwell_client` for handling cromwell requests, and
+    /// Creates a new TestRunner that will use `server_router` for running workflows, and
     /// `test_resource_client` for retrieving wdls.  If custom image building is enabled,
-    /// `image_registry_host` is the location where the images will be hosted
+    /// `image_registry_host` is the location where the images will be hosted.  If
+    /// `max_concurrent_runs` is specified, runs will be queued instead of started if that many runs
+    /// are already active.  `retry_config` determines which failures to start the test or eval
+    /// workflow for a run are retried, and how many times.  `workflow_option_defaults`, if
+    /// specified, are the workflow options that the options for every run are merged over.  If
+    /// secrets are enabled, `secret_encryptor` is used to decrypt the values of secrets referenced
+    /// in inputs when they're submitted to cromwell
     pub fn new(
-        cromwell_client: CromwellClient,
+        server_router: ServerRouter,
         test_resource_client: test_resource_requests::TestResourceClient,
         image_registry_host: Option<&str>,
+        max_concurrent_runs: Option<u32>,
+        retry_config: RetryConfig,
+        workflow_option_defaults: Option<Value>,
+        secret_encryptor: Option<SecretEncryptor>,
     ) -> TestRunner {
         TestRunner {
-            cromwell_client,
+            server_router,
             test_resource_client,
             image_registry_host: image_registry_host.map(String::from),
+            max_concurrent_runs,
+            retry_config,
+            workflow_option_defaults,
+            secret_encryptor,
         }
     }
+    /// Returns the encryptor for secrets if secrets are enabled, so other components can redact
+    /// secret values
+    pub fn secret_encryptor(&self) -> Option<&SecretEncryptor> {
+        self.secret_encryptor.as_ref()
+    }
     /// Creates a new run and inserts it into the DB
     ///
     /// Creates a new run based on `name`, `test_input`, `eval_input`, and `created_by`, with
     /// `test_id`, and inserts it into the DB with status `Created`.  If any of the parameters for
     /// this run match the format for specifying a software build, it marks the run as `Building`
     /// (after creating the records for the builds, if necessary).
-    /// If none of the parameters specify a software build, it starts the run.  Returns created run or
-    /// an error if: parsing `test_id` fails, or a run already exists with the name specified in
-    /// `new_run.name`, or there is an error querying or inserting to the DB.
+    /// Any `run_output:` or `latest_successful_output:` inputs are replaced with the outputs of the
+    /// runs they reference (see [`TestRunner::resolve_run_output_references`]), and the values
+    /// they resolved to are recorded in the RUN_INPUT_SOURCE table.
+    /// If `run_group_id` is provided, the run is added to that run group before it is started, so
+    /// it is a member of the group for its whole lifecycle.
+    /// If `womtool_runner` is provided, the merged test and eval inputs are checked against the
+    /// inputs of the template's WDLs first, and an `Inputs` error is returned if they don't match.
+    /// If none of the parameters specify a software build, it starts the run.  If starting the run
+    /// would exceed the global limit on concurrent runs or the limit for its test or pipeline, it
+    /// marks the run as `Queued` instead, and the `status_manager` will start it once there is
+    /// room.  Returns created run or an error if: parsing `test_id` fails, or a run already exists
+    /// with the name specified in `new_run.name`, or there is an error querying or inserting to the
+    /// DB.
     ///
     /// Note: In the case that a docker image needs to be built for a run, it does not actually start
     /// the build (i.e. it doesn't submit the build job to Cromwell).  Instead, it marks the build as
@@ -157,6 +261,8 @@ impl TestRunner {
         eval_input: Option<Value>,
         eval_options: Option<Value>,
         created_by: Option<String>,
+        run_group_id: Option<Uuid>,
+        womtool_runner: Option<&WomtoolRunner>,
     ) -> Result<RunData, Error> {
         // Parse test id into UUID
         let test_id = TestRunner::parse_test_id(test_id)?;
@@ -171,20 +277,17 @@ impl TestRunner {
         if let Some(inputs) = &test_input {
             json_patch::merge(&mut test_json, inputs);
         }
-        let test_options_json: Option<Value> = {
-            if test.test_option_defaults.is_some() || test_options.is_some() {
-                let mut test_options_json = json!({});
-                if let Some(defaults) = &test.test_option_defaults {
-                    json_patch::merge(&mut test_options_json, defaults);
-                }
-                if let Some(inputs) = &test_options {
-                    json_patch::merge(&mut test_options_json, inputs);
-                }
-                Some(test_options_json)
-            } else {
-                None
-            }
-        };
+        // Options are merged from the most general defaults (from the config) to the options
+        // specified for this run, so the effective options are stored with the run
+        let template = TestRunner::get_template(conn, test.template_id)?;
+        let pipeline = PipelineData::find_by_id(conn, template.pipeline_id)?;
+        let test_options_json: Option<Value> = TestRunner::merge_options(&[
+            self.workflow_option_defaults.as_ref(),
+            pipeline.workflow_option_defaults.as_ref(),
+            template.workflow_option_defaults.as_ref(),
+            test.test_option_defaults.as_ref(),
+            test_options.as_ref(),
+        ]);
         let mut eval_json = json!({});
         if let Some(defaults) = &test.eval_input_defaults {
             json_patch::merge(&mut eval_json, defaults);
@@ -192,20 +295,35 @@ impl TestRunner {
         if let Some(inputs) = &eval_input {
             json_patch::merge(&mut eval_json, inputs);
         }
-        let eval_options_json: Option<Value> = {
-            if test.eval_option_defaults.is_some() || eval_options.is_some() {
-                let mut eval_options_json = json!({});
-                if let Some(defaults) = &test.eval_option_defaults {
-                    json_patch::merge(&mut eval_options_json, defaults);
-                }
-                if let Some(inputs) = &eval_options {
-                    json_patch::merge(&mut eval_options_json, inputs);
-                }
-                Some(eval_options_json)
-            } else {
-                None
-            }
-        };
+        let eval_options_json: Option<Value> = TestRunner::merge_options(&[
+            self.workflow_option_defaults.as_ref(),
+            pipeline.workflow_option_defaults.as_ref(),
+            template.workflow_option_defaults.as_ref(),
+            test.eval_option_defaults.as_ref(),
+            eval_options.as_ref(),
+        ]);
+
+        // Fill in the values of any inputs that reference the outputs of other runs
+        let mut resolved_inputs = self
+            .resolve_run_output_references(conn, &mut test_json, RunCallWorkflowEnum::Test)
+            .await?;
+        resolved_inputs.extend(
+            self.resolve_run_output_references(conn, &mut eval_json, RunCallWorkflowEnum::Eval)
+                .await?,
+        );
+
+        // Check the inputs against the WDLs, if we've been asked to
+        if let Some(womtool_runner) = womtool_runner {
+            wdl_inputs::validate_inputs_for_template(
+                womtool_runner,
+                &self.test_resource_client,
+                &template,
+                Some(&test_json),
+                Some(&eval_json),
+                true,
+            )
+            .await?;
+        }
 
         // Make a name if one has not been specified
         let run_name = match name {
@@ -225,6 +343,276 @@ impl TestRunner {
             created_by,
         )?;
 
+        // Record where the values for any inputs that referenced other runs came from
+        for resolved_input in resolved_inputs {
+            let new_run_input_source = NewRunInputSource {
+                run_id: run.run_id,
+                workflow: resolved_input.workflow,
+                input_key: resolved_input.input_key,
+                reference: resolved_input.reference,
+                source_run_id: resolved_input.source_run_id,
+                value: resolved_input.value,
+            };
+            if let Err(e) = RunInputSourceData::create(conn, new_run_input_source) {
+                update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                return Err(Error::DB(e));
+            }
+        }
+
+        // Add it to its run group, if it has one
+        if let Some(run_group_id) = run_group_id {
+            let new_run_in_group = NewRunInGroup {
+                run_id: run.run_id,
+                run_group_id,
+            };
+            if let Err(e) = RunInGroupData::create(conn, new_run_in_group) {
+                update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                return Err(Error::DB(e));
+            }
+        }
+
+        self.start_created_run(conn, &run, test.template_id).await
+    }
+
+    /// Creates a new run by rerunning `source_run`
+    ///
+    /// If `mode` is `Full`, creates a new run with the same inputs and options as `source_run` and
+    /// starts it the same way `create_run` would.  If `mode` is `EvalOnly`, creates a new run with
+    /// the same inputs and options, retrieves the outputs of the test wdl job for `source_run` from
+    /// cromwell, and submits only the eval wdl using those outputs.  In either case, the new run is
+    /// linked to `source_run` with a record in the RUN_IS_RERUN table.  The new run will be named
+    /// `name` if specified, or will be given a default name based on the name of `source_run`
+    /// otherwise.  Returns the created run, or an error if `source_run` has not finished, if
+    /// `mode` is `EvalOnly` and the test wdl job for `source_run` did not succeed, or if there is
+    /// an issue with creating or starting the run
+    pub async fn rerun_run(
+        &self,
+        conn: &PgConnection,
+        source_run: &RunData,
+        mode: RerunModeEnum,
+        name: Option<String>,
+        created_by: Option<String>,
+    ) -> Result<RunData, Error> {
+        // We only allow rerunning runs that have finished
+        if source_run.finished_at.is_none() {
+            return Err(Error::RerunNotAllowed(format!(
+                "Run has not finished (status: {})",
+                source_run.status
+            )));
+        }
+        // If we're only rerunning the eval step, we need the test outputs from the source run, so
+        // make sure its test wdl job succeeded (we know it did if it has an eval job) and retrieve
+        // them before creating anything
+        let test_outputs: Option<Map<String, Value>> = match mode {
+            RerunModeEnum::Full => None,
+            RerunModeEnum::EvalOnly => {
+                if source_run.eval_cromwell_job_id.is_none() {
+                    return Err(Error::RerunNotAllowed(String::from(
+                        "Cannot rerun only the eval step for a run whose test step did not succeed",
+                    )));
+                }
+                Some(self.get_source_test_outputs(conn, source_run).await?)
+            }
+        };
+
+        let test = TestRunner::get_test(conn, source_run.test_id)?;
+
+        // Make a name if one has not been specified
+        let run_name = match name {
+            Some(run_name) => run_name,
+            None => format!("{}_rerun_{}", source_run.name, Utc::now()),
+        };
+
+        // Write run to db
+        let run = TestRunner::create_run_in_db(
+            conn,
+            source_run.test_id,
+            run_name,
+            source_run.test_input.clone(),
+            source_run.test_options.clone(),
+            source_run.eval_input.clone(),
+            source_run.eval_options.clone(),
+            created_by,
+        )?;
+
+        // Link it to the run it's a rerun of
+        let new_run_is_rerun = NewRunIsRerun {
+            run_id: run.run_id,
+            source_run_id: source_run.run_id,
+            mode,
+        };
+        if let Err(e) = RunIsRerunData::create(conn, new_run_is_rerun) {
+            update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+            return Err(Error::DB(e));
+        }
+
+        match test_outputs {
+            None => self.start_created_run(conn, &run, test.template_id).await,
+            Some(test_outputs) => {
+                self.start_created_run_eval_only(conn, &run, &test_outputs)
+                    .await
+            }
+        }
+    }
+
+    /// Starts `run`, which has been waiting with status `Queued` (or was stranded with status
+    /// `Created`), if there is room for it
+    ///
+    /// If starting `run` would still exceed a limit on concurrent runs, it is left (or marked)
+    /// `Queued`.
+    /// Otherwise, it is started the same way it would have been if there had been room when it was
+    /// created.  For eval-only reruns, this means retrieving the outputs of the test wdl job for the
+    /// source run again.  Returns the updated run, or an error if there is an issue starting it
+    /// (in which case it is marked as `CarrotFailed`)
+    pub async fn start_queued_run(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+    ) -> Result<RunData, Error> {
+        // Check if there's room first so we don't retrieve anything we don't need yet
+        if let Some(queued_run) = self.queue_run_if_at_capacity(conn, run)? {
+            return Ok(queued_run);
+        }
+        // If it's an eval-only rerun, we'll need the test outputs from the source run
+        let rerun = match RunIsRerunData::find_by_run_id(conn, run.run_id) {
+            Ok(rerun) => Some(rerun),
+            Err(diesel::result::Error::NotFound) => None,
+            Err(e) => {
+                update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                return Err(Error::DB(e));
+            }
+        };
+        match rerun {
+            Some(rerun) if rerun.mode == RerunModeEnum::EvalOnly => {
+                let source_run = match RunData::find_by_id(conn, rerun.source_run_id) {
+                    Ok(source_run) => source_run,
+                    Err(e) => {
+                        update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                        return Err(Error::DB(e));
+                    }
+                };
+                let test_outputs = match self.get_source_test_outputs(conn, &source_run).await {
+                    Ok(test_outputs) => test_outputs,
+                    Err(e) => {
+                        update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                        return Err(e);
+                    }
+                };
+                self.start_created_run_eval_only(conn, run, &test_outputs)
+                    .await
+            }
+            _ => {
+                let test = match TestRunner::get_test(conn, run.test_id) {
+                    Ok(test) => test,
+                    Err(e) => {
+                        update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                        return Err(e);
+                    }
+                };
+                self.start_created_run(conn, run, test.template_id).await
+            }
+        }
+    }
+
+    /// Marks `run` as `Queued` if starting it would exceed the global limit on concurrent runs or
+    /// the limit for its test or pipeline
+    ///
+    /// Returns the queued run if it was queued, or None if there is room to start it.  If checking
+    /// the limits fails, marks `run` as `CarrotFailed` and returns an error
+    fn queue_run_if_at_capacity(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+    ) -> Result<Option<RunData>, Error> {
+        match self.has_capacity_for_run(conn, run) {
+            Ok(true) => Ok(None),
+            Ok(false) => {
+                // If it's already queued, there's no need to update it
+                if run.status == RunStatusEnum::Queued {
+                    Ok(Some(RunData::find_by_id(conn, run.run_id)?))
+                } else {
+                    Ok(Some(update_run_status(
+                        conn,
+                        run.run_id,
+                        RunStatusEnum::Queued,
+                    )?))
+                }
+            }
+            Err(e) => {
+                update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Checks whether starting `run` would keep the number of active runs within the global limit
+    /// on concurrent runs and the limits for the test and pipeline `run` belongs to, if there are
+    /// any
+    ///
+    /// Runs that are `Created` count as active, so runs being started at the same time can't
+    /// exceed a limit together.  Queued runs are started in order by the status manager, so if
+    /// `run` is a new run (i.e. it isn't `Queued` already), the runs queued within each limit's
+    /// scope also count against that limit, so it doesn't take a spot one of them is waiting for.
+    /// Runs queued because of some other limit (e.g. the limit for a different test) don't keep
+    /// `run` from starting.  Returns true if there is room to start `run`, false if not, or an
+    /// error if there is an issue querying the DB
+    fn has_capacity_for_run(&self, conn: &PgConnection, run: &RunData) -> Result<bool, Error> {
+        let is_new_run = run.status != RunStatusEnum::Queued;
+        // Check the global limit
+        if let Some(limit) = self.max_concurrent_runs {
+            let mut count = RunData::count_active(conn, run.run_id)?;
+            if is_new_run {
+                count += RunData::count_queued(conn)?;
+            }
+            if count >= i64::from(limit) {
+                return Ok(false);
+            }
+        }
+        // Check the limit for the test
+        let test = TestRunner::get_test(conn, run.test_id)?;
+        if let Some(limit) = test.max_concurrent_runs {
+            let mut count = RunData::count_active_for_test(conn, test.test_id, run.run_id)?;
+            if is_new_run {
+                count += RunData::count_queued_for_test(conn, test.test_id)?;
+            }
+            if count >= i64::from(limit) {
+                return Ok(false);
+            }
+        }
+        // Check the limit for the pipeline
+        let template = TestRunner::get_template(conn, test.template_id)?;
+        let pipeline = PipelineData::find_by_id(conn, template.pipeline_id)?;
+        if let Some(limit) = pipeline.max_concurrent_runs {
+            let mut count =
+                RunData::count_active_for_pipeline(conn, pipeline.pipeline_id, run.run_id)?;
+            if is_new_run {
+                count += RunData::count_queued_for_pipeline(conn, pipeline.pipeline_id)?;
+            }
+            if count >= i64::from(limit) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Starts `run`, which has just been created with status `Created` (or has been `Queued`)
+    ///
+    /// If there isn't room to start `run` within the limits on concurrent runs, it marks the run as
+    /// `Queued`.  If any of the parameters for `run` match the format for specifying a software
+    /// build, it marks the run as `Building` (after creating the records for the builds, if
+    /// necessary).  Otherwise, it submits the test wdl for `run` to cromwell.  If anything fails,
+    /// marks `run` as `CarrotFailed` and returns an error
+    async fn start_created_run(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+        template_id: Uuid,
+    ) -> Result<RunData, Error> {
+        // If we're at capacity, queue the run so the status manager can start it later
+        if let Some(queued_run) = self.queue_run_if_at_capacity(conn, run)? {
+            return Ok(queued_run);
+        }
         // Process software image build parameters in the run's input if software building is enabled
         let mut version_map: HashMap<String, SoftwareVersionData> = HashMap::new();
         if self.image_registry_host.is_some() {
@@ -287,7 +675,7 @@ impl TestRunner {
         // Otherwise, start the run
         else {
             match self
-                .start_run_test_with_template_id(conn, &run, test.template_id)
+                .start_run_test_with_template_id(conn, run, template_id)
                 .await
             {
                 Ok(run) => Ok(run),
@@ -299,6 +687,344 @@ impl TestRunner {
         }
     }
 
+    /// Starts `run`, which is an eval-only rerun that has just been created with status `Created`
+    /// (or has been `Queued`), by submitting its eval wdl to cromwell with `test_outputs`, the
+    /// outputs of the test wdl job for the run it is a rerun of
+    ///
+    /// If there isn't room to start `run` within the limits on concurrent runs, it marks the run as
+    /// `Queued`.  Since the test step is not being rerun, `run` does not get a test wdl job id of
+    /// its own (the source run can be found through its RUN_IS_RERUN record), and results are
+    /// filled from `test_outputs` before the eval wdl is submitted.  Any software builds specified
+    /// in the parameters for `run` must have already been built (which they should have been for
+    /// the source run to reach its eval step).  If anything fails, marks `run` as `CarrotFailed`
+    /// and returns an error
+    async fn start_created_run_eval_only(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+        test_outputs: &Map<String, Value>,
+    ) -> Result<RunData, Error> {
+        // If we're at capacity, queue the run so the status manager can start it later
+        if let Some(queued_run) = self.queue_run_if_at_capacity(conn, run)? {
+            return Ok(queued_run);
+        }
+        // Map the run to any software versions it uses and make sure they're all built
+        if self.image_registry_host.is_some() {
+            match TestRunner::map_run_to_existing_builds(conn, run) {
+                Ok(RunBuildStatus::Finished) => {}
+                Ok(_) => {
+                    update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                    return Err(Error::RerunNotAllowed(String::from(
+                        "Software builds used by the source run are not available",
+                    )));
+                }
+                Err(e) => {
+                    update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                    return Err(e);
+                }
+            }
+        }
+
+        // Fill results from the test outputs, same as we would if the test wdl had just finished
+        if let Err(e) = fill_results(conn, run, test_outputs, self.secret_encryptor.as_ref()) {
+            update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+            return Err(e);
+        }
+
+        self.start_run_eval(conn, run, test_outputs).await
+    }
+
+    /// Creates RUN_SOFTWARE_VERSION rows mapping `run` to the software versions specified in its
+    /// inputs, without creating builds for them, and returns the status of the most recent builds
+    /// for those versions
+    fn map_run_to_existing_builds(
+        conn: &PgConnection,
+        run: &RunData,
+    ) -> Result<RunBuildStatus, Error> {
+        TestRunner::process_software_version_mappings(conn, run.run_id, &run.test_input)?;
+        TestRunner::process_software_version_mappings(conn, run.run_id, &run.eval_input)?;
+        run_finished_building(conn, run.run_id)
+    }
+
+    /// Returns the run that actually ran the test wdl whose outputs `run` used
+    ///
+    /// Eval-only reruns don't run the test wdl, so this follows RUN_IS_RERUN records for eval-only
+    /// reruns back to their source runs until it finds a run that isn't one.  If `run` is not an
+    /// eval-only rerun, this is `run` itself
+    fn find_test_workflow_run(conn: &PgConnection, run: &RunData) -> Result<RunData, Error> {
+        let mut test_workflow_run_id = run.run_id;
+        loop {
+            match RunIsRerunData::find_by_run_id(conn, test_workflow_run_id) {
+                Ok(rerun) if rerun.mode == RerunModeEnum::EvalOnly => {
+                    test_workflow_run_id = rerun.source_run_id;
+                }
+                Ok(_) | Err(diesel::result::Error::NotFound) => break,
+                Err(e) => return Err(Error::DB(e)),
+            }
+        }
+        Ok(RunData::find_by_id(conn, test_workflow_run_id)?)
+    }
+
+    /// Retrieves the outputs of the test wdl job used by `source_run` (see
+    /// `find_test_workflow_run`), for doing an eval-only rerun of it
+    async fn get_source_test_outputs(
+        &self,
+        conn: &PgConnection,
+        source_run: &RunData,
+    ) -> Result<Map<String, Value>, Error> {
+        let test_workflow_run = TestRunner::find_test_workflow_run(conn, source_run)?;
+        match &test_workflow_run.test_cromwell_job_id {
+            Some(test_cromwell_job_id) => {
+                self.get_test_outputs(
+                    test_workflow_run.cromwell_server.as_deref(),
+                    test_cromwell_job_id,
+                )
+                .await
+            }
+            None => Err(Error::RerunNotAllowed(String::from(
+                "Cannot rerun only the eval step for a run whose test step did not succeed",
+            ))),
+        }
+    }
+
+    /// Retrieves the outputs of the cromwell job with id `test_cromwell_job_id` from the cromwell
+    /// server named `cromwell_server` (or the default server, if None)
+    async fn get_test_outputs(
+        &self,
+        cromwell_server: Option<&str>,
+        test_cromwell_job_id: &str,
+    ) -> Result<Map<String, Value>, Error> {
+        let metadata = match self
+            .server_router
+            .execution_client(cromwell_server)?
+            .get_workflow_output_metadata(test_cromwell_job_id)
+            .await
+        {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                error!(
+                    "Encountered an error while attempting to retrieve outputs for job {} from cromwell: {}",
+                    test_cromwell_job_id, e
+                );
+                return Err(Error::Cromwell(e));
+            }
+        };
+        match metadata
+            .get("outputs")
+            .and_then(|outputs| outputs.as_object())
+        {
+            Some(outputs) => Ok(outputs.to_owned()),
+            None => {
+                error!(
+                    "Cromwell metadata request for job {} did not return outputs",
+                    test_cromwell_job_id
+                );
+                Err(Error::Json)
+            }
+        }
+    }
+
+    /// Replaces any values in `inputs` that reference the outputs of other runs with those outputs
+    ///
+    /// References can be either `run_output:{run_name_or_id}.{output_key}`, for an output of a
+    /// specific run, or `latest_successful_output:{test_name}.{output_key}`, for an output of the
+    /// most recent `Succeeded` run of a test (see [`TestRunner::get_run_output`] for how outputs are
+    /// found).  Returns a record of each input that was resolved (labeled with `workflow`), or an
+    /// `InputReference` error if a reference can't be resolved
+    async fn resolve_run_output_references(
+        &self,
+        conn: &PgConnection,
+        inputs: &mut Value,
+        workflow: RunCallWorkflowEnum,
+    ) -> Result<Vec<ResolvedInput>, Error> {
+        let mut resolved_inputs: Vec<ResolvedInput> = Vec::new();
+
+        let object_map = match inputs.as_object_mut() {
+            Some(map) => map,
+            // If the inputs aren't an object, there's nothing to resolve
+            None => return Ok(resolved_inputs),
+        };
+
+        for (key, value) in object_map.iter_mut() {
+            let reference = match value.as_str() {
+                Some(val)
+                    if RUN_OUTPUT_REGEX.is_match(val)
+                        || LATEST_SUCCESSFUL_OUTPUT_REGEX.is_match(val) =>
+                {
+                    String::from(val)
+                }
+                _ => continue,
+            };
+            let (source_run, output_key) = TestRunner::find_referenced_run(conn, &reference)?;
+            let output = self.get_run_output(conn, &source_run, output_key).await?;
+            *value = output.clone();
+            resolved_inputs.push(ResolvedInput {
+                workflow,
+                input_key: key.clone(),
+                reference,
+                source_run_id: source_run.run_id,
+                value: output,
+            });
+        }
+
+        Ok(resolved_inputs)
+    }
+
+    /// Finds the run that `reference` (a `run_output:` or `latest_successful_output:` reference)
+    /// refers to and returns it along with the output key from `reference`
+    ///
+    /// Run names, test names, and output keys can all contain `.`, so each `.` in the reference is
+    /// tried in turn as the separator between the run or test and the output key, starting from the
+    /// last one so the longest run or test name that matches is used (e.g. with runs named `a` and
+    /// `a.b`, `run_output:a.b.c` refers to output `c` of run `a.b`).  Returns an `InputReference`
+    /// error if none of them identify a run
+    fn find_referenced_run<'a>(
+        conn: &PgConnection,
+        reference: &'a str,
+    ) -> Result<(RunData, &'a str), Error> {
+        let (is_latest_successful, run_and_output) =
+            if let Some(run_and_output) = reference.strip_prefix("latest_successful_output:") {
+                (true, run_and_output)
+            } else {
+                (false, reference.trim_start_matches("run_output:"))
+            };
+        for (index, _) in run_and_output.rmatch_indices('.') {
+            let run_or_test = &run_and_output[..index];
+            let output_key = &run_and_output[index + 1..];
+            if output_key.is_empty() {
+                continue;
+            }
+            let source_run = if is_latest_successful {
+                RunData::find_most_recent_succeeded_for_test_name(conn, run_or_test)
+            } else {
+                match Uuid::parse_str(run_or_test) {
+                    Ok(id) => RunData::find_by_id(conn, id),
+                    Err(_) => RunData::find_by_name(conn, run_or_test),
+                }
+            };
+            match source_run {
+                Ok(source_run) => return Ok((source_run, output_key)),
+                Err(diesel::result::Error::NotFound) => continue,
+                Err(e) => {
+                    error!(
+                        "Encountered error while attempting to retrieve run for reference {}: {}",
+                        reference, e
+                    );
+                    return Err(Error::DB(e));
+                }
+            }
+        }
+        let msg = if is_latest_successful {
+            format!(
+                "No succeeded run found for a test referenced by {}",
+                reference
+            )
+        } else {
+            format!("No run found matching {}", reference)
+        };
+        error!("{}", msg);
+        Err(Error::InputReference(msg))
+    }
+
+    /// Returns the value of the output with key `output_key` from `source_run`
+    ///
+    /// The run's stored results are checked first, for a result mapped to `output_key` for its
+    /// template, and converted back to JSON according to the result's type.  If there isn't one,
+    /// the outputs of its test and eval workflows are retrieved from cromwell and checked (for an
+    /// eval-only rerun, the test workflow is the one from the run it reran).  Outputs from cromwell
+    /// that contain the value of a secret can't be used, since that would write the secret's value
+    /// to the new run's inputs.  Returns an `InputReference` error if the output can't be found or
+    /// can't be used
+    async fn get_run_output(
+        &self,
+        conn: &PgConnection,
+        source_run: &RunData,
+        output_key: &str,
+    ) -> Result<Value, Error> {
+        // Check the run's results first, since they don't require a trip to cromwell
+        let template_results = TemplateResultData::find_for_test(conn, source_run.test_id)?;
+        if let Some(template_result) = template_results
+            .iter()
+            .find(|template_result| template_result.result_key == output_key)
+        {
+            match RunResultData::find_by_run_and_result(
+                conn,
+                source_run.run_id,
+                template_result.result_id,
+            ) {
+                Ok(run_result) => {
+                    let result = ResultData::find_by_id(conn, template_result.result_id)?;
+                    return Ok(TestRunner::parse_result_value(
+                        &run_result.value,
+                        result.result_type,
+                    ));
+                }
+                Err(diesel::result::Error::NotFound) => {}
+                Err(e) => return Err(Error::DB(e)),
+            }
+        }
+        // If it's not in the results, check the outputs from cromwell (eval-only reruns don't have
+        // their own test job, so we get that from the run they reran)
+        let test_workflow_run = TestRunner::find_test_workflow_run(conn, source_run)?;
+        let cromwell_jobs = [
+            (
+                &test_workflow_run.cromwell_server,
+                &test_workflow_run.test_cromwell_job_id,
+            ),
+            (
+                &source_run.cromwell_server,
+                &source_run.eval_cromwell_job_id,
+            ),
+        ];
+        for (cromwell_server, cromwell_job_id) in cromwell_jobs.iter().copied() {
+            let cromwell_job_id = match cromwell_job_id {
+                Some(cromwell_job_id) => cromwell_job_id,
+                None => continue,
+            };
+            let outputs = self
+                .get_test_outputs(cromwell_server.as_deref(), cromwell_job_id)
+                .await?;
+            if let Some(output) = outputs.get(output_key) {
+                // Make sure we're not copying the value of a secret into the inputs
+                let secret_redactor = SecretRedactor::load(conn, self.secret_encryptor.as_ref())?;
+                let mut redacted_output = output.clone();
+                secret_redactor.redact_json(&mut redacted_output);
+                if &redacted_output != output {
+                    let msg = format!(
+                        "Output {} of run {} contains the value of a secret, so it can't be used as an input",
+                        output_key, source_run.name
+                    );
+                    error!("{}", msg);
+                    return Err(Error::InputReference(msg));
+                }
+                return Ok(output.to_owned());
+            }
+        }
+        let msg = format!("No output {} found for run {}", output_key, source_run.name);
+        error!("{}", msg);
+        Err(Error::InputReference(msg))
+    }
+
+    /// Converts `value`, a result value as it's stored in the RUN_RESULT table, back to JSON
+    /// according to `result_type`
+    ///
+    /// Results are stored as text, so `Numeric` results are parsed back into numbers (so they can
+    /// be used for `Int` and `Float` inputs).  Everything else, and any numeric value that can't be
+    /// parsed, is returned as a string
+    fn parse_result_value(value: &str, result_type: ResultTypeEnum) -> Value {
+        if result_type == ResultTypeEnum::Numeric {
+            if let Ok(int_value) = value.parse::<i64>() {
+                return json!(int_value);
+            }
+            if let Ok(float_value) = value.parse::<f64>() {
+                if let Some(number_value) = serde_json::Number::from_f64(float_value) {
+                    return Value::Number(number_value);
+                }
+            }
+        }
+        json!(value)
+    }
+
     /// Starts a run by submitting it to cromwell
     ///
     /// Assembles the input json and test wdl for `run` (using `conn` to retrieve necessary data
@@ -322,19 +1048,39 @@ impl TestRunner {
     ///
     /// Assembles the input json and test wdl for `run` (using `conn` to retrieve necessary data
     /// from the TEMPLATE table) and submits it to cromwell using `self.client`, then updates the
-    /// row in the database with the status and the test cromwell job id
+    /// row in the database with the status and the test cromwell job id.  If the attempt fails
+    /// with an error that is retriable according to `self.retry_config` and there are attempts
+    /// remaining, a retry is scheduled for the status manager (see `record_failed_start_attempt`)
+    /// and the run is returned with status `Created`
     pub async fn start_run_test_with_template_id(
         &self,
         conn: &PgConnection,
         run: &RunData,
         template_id: Uuid,
     ) -> Result<RunData, Error> {
-        // Retrieve template to get WDLs or return error
-        let template_id = template_id.clone();
-        let template = TestRunner::get_template(&conn, template_id)?;
+        match self.submit_run_test(conn, run, template_id).await {
+            Ok(run) => Ok(run),
+            Err(e) => self.record_failed_start_attempt(conn, run, RunCallWorkflowEnum::Test, e),
+        }
+    }
 
-        // Format json so it's ready to submit
-        let input_json_to_submit = self.format_test_json_for_cromwell(&run.test_input)?;
+    /// Makes a single attempt at submitting the test wdl for `run` to cromwell (see
+    /// `start_run_test_with_template_id`)
+    async fn submit_run_test(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+        template_id: Uuid,
+    ) -> Result<RunData, Error> {
+        // Retrieve template to get WDLs or return error
+        let template_id = template_id.clone();
+        let template = TestRunner::get_template(&conn, template_id)?;
+
+        // Figure out which cromwell server to run it on
+        let cromwell_server = self.get_cromwell_server_for_run(conn, run)?;
+
+        // Format json so it's ready to submit
+        let input_json_to_submit = self.format_test_json_for_cromwell(conn, &run.test_input)?;
 
         // Write json to temp file so it can be submitted to cromwell
         let input_json_file =
@@ -379,11 +1125,20 @@ impl TestRunner {
         // Send job request to cromwell
         let start_job_result: Result<WorkflowIdAndStatus, CromwellRequestError> =
             util::start_job_from_file(
-                &self.cromwell_client,
+                self.server_router
+                    .execution_client(Some(&cromwell_server))?,
                 &test_wdl_as_file.path(),
                 test_wdl_deps_file_path.as_deref(),
                 &input_json_file.path(),
                 options_json_file_path.as_deref(),
+                template.workflow_language,
+                &WorkflowLabels {
+                    stage: WorkflowStage::Test,
+                    run_id: Some(run.run_id),
+                    test_id: Some(run.test_id),
+                    software_build_id: None,
+                    report_id: None,
+                },
             )
             .await;
 
@@ -406,6 +1161,7 @@ impl TestRunner {
             test_cromwell_job_id: Some(start_job_response.id),
             eval_cromwell_job_id: None,
             finished_at: None,
+            cromwell_server: Some(cromwell_server),
         };
 
         Ok(RunData::update(conn, run.run_id, run_update)?)
@@ -445,21 +1201,49 @@ impl TestRunner {
     /// Assembles the input json (including pulling relevant outputs from `test_outputs` to supply as
     /// inputs to the eval wdl) and eval wdl for `run` (using `conn` to retrieve necessary data
     /// from the TEMPLATE table) and submits it to cromwell using `client`, then updates the
-    /// row in the database with the status and the eval cromwell job id
+    /// row in the database with the status and the eval cromwell job id.  If the attempt fails
+    /// with an error that is retriable according to `self.retry_config` and there are attempts
+    /// remaining, a retry is scheduled for the status manager (see `record_failed_start_attempt`)
+    /// and the run is returned unchanged
     pub async fn start_run_eval_with_template_id(
         &self,
         conn: &PgConnection,
         run: &RunData,
         template_id: Uuid,
         test_outputs: &Map<String, Value>,
+    ) -> Result<RunData, Error> {
+        match self
+            .submit_run_eval(conn, run, template_id, test_outputs)
+            .await
+        {
+            Ok(run) => Ok(run),
+            Err(e) => self.record_failed_start_attempt(conn, run, RunCallWorkflowEnum::Eval, e),
+        }
+    }
+
+    /// Makes a single attempt at submitting the eval wdl for `run` to cromwell (see
+    /// `start_run_eval_with_template_id`)
+    async fn submit_run_eval(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+        template_id: Uuid,
+        test_outputs: &Map<String, Value>,
     ) -> Result<RunData, Error> {
         // Retrieve template to get WDLs or return error
         let template_id = template_id.clone();
         let template = TestRunner::get_template(&conn, template_id)?;
 
+        // Figure out which cromwell server to run it on
+        let cromwell_server = self.get_cromwell_server_for_run(conn, run)?;
+
         // Format json so it's ready to submit
-        let input_json_to_submit =
-            self.format_eval_json_for_cromwell(&run.eval_input, test_outputs)?;
+        let input_json_to_submit = self.format_eval_json_for_cromwell(
+            conn,
+            &run.eval_input,
+            test_outputs,
+            template.workflow_language,
+        )?;
 
         // Write json to temp file so it can be submitted to cromwell
         let input_json_file =
@@ -504,11 +1288,20 @@ impl TestRunner {
         // Send job request to cromwell
         let start_job_result: Result<WorkflowIdAndStatus, CromwellRequestError> =
             util::start_job_from_file(
-                &self.cromwell_client,
+                self.server_router
+                    .execution_client(Some(&cromwell_server))?,
                 &eval_wdl_as_file.path(),
                 eval_wdl_deps_file_path.as_deref(),
                 &input_json_file.path(),
                 options_json_file_path.as_deref(),
+                template.workflow_language,
+                &WorkflowLabels {
+                    stage: WorkflowStage::Eval,
+                    run_id: Some(run.run_id),
+                    test_id: Some(run.test_id),
+                    software_build_id: None,
+                    report_id: None,
+                },
             )
             .await;
         // Process result
@@ -530,11 +1323,327 @@ impl TestRunner {
             test_cromwell_job_id: None,
             eval_cromwell_job_id: Some(start_job_response.id),
             finished_at: None,
+            cromwell_server: Some(cromwell_server),
         };
 
         Ok(RunData::update(conn, run.run_id, run_update)?)
     }
 
+    /// Returns the name of the cromwell server to run the workflows for `run` on: the one it has
+    /// already been assigned to, if it has been, or the one chosen for its test (see
+    /// `ServerRouter::choose_server_for_test`) otherwise
+    fn get_cromwell_server_for_run(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+    ) -> Result<String, Error> {
+        match &run.cromwell_server {
+            Some(cromwell_server) => Ok(cromwell_server.clone()),
+            None => {
+                let test = TestRunner::get_test(conn, run.test_id)?;
+                Ok(self.server_router.choose_server_for_test(conn, &test)?)
+            }
+        }
+    }
+
+    /// Records a failed attempt, with error `e`, at starting the `workflow` workflow for `run`
+    ///
+    /// If `e` is retriable according to `self.retry_config`, increments the attempt count for
+    /// `run` and `workflow` and logs the failed attempt as a run_error.  Then, if there are attempts
+    /// remaining, schedules the next attempt for after the backoff time so the status manager can
+    /// make it (see `retry_run_start`) and returns the run: with status `Created` for the test
+    /// workflow, so it isn't picked up again as queued or building in the meantime, or unchanged
+    /// for the eval workflow.  Otherwise, returns `e`
+    fn record_failed_start_attempt(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+        workflow: RunCallWorkflowEnum,
+        e: Error,
+    ) -> Result<RunData, Error> {
+        let retriable = match get_retriable_error_type(&e) {
+            Some(error_type) => self.retry_config.retriable_errors().contains(&error_type),
+            None => false,
+        };
+        let max_attempts = self.retry_config.max_attempts();
+        if !retriable || max_attempts <= 1 {
+            return Err(e);
+        }
+        let attempt: u32 = match RunStartAttemptData::increment(conn, run.run_id, workflow) {
+            Ok(run_start_attempt) => run_start_attempt.attempts as u32,
+            Err(db_error) => {
+                error!(
+                    "Failed to record attempt to start {} workflow for run {} due to: {}",
+                    workflow, run.run_id, db_error
+                );
+                return Err(e);
+            }
+        };
+        if attempt < max_attempts {
+            let backoff = self.retry_config.backoff_in_secs(attempt);
+            run_error_logger::log_error_redacting_secrets(
+                conn,
+                run.run_id,
+                format!(
+                    "Attempt {} of {} to start {} workflow failed with error: {}.  Retrying in {} seconds",
+                    attempt, max_attempts, workflow, e, backoff
+                ),
+                self.secret_encryptor.as_ref(),
+            );
+            let retry_at = Utc::now().naive_utc() + chrono::Duration::seconds(backoff as i64);
+            if let Err(db_error) =
+                RunStartAttemptData::schedule_retry(conn, run.run_id, workflow, retry_at)
+            {
+                error!(
+                    "Failed to schedule retry of {} workflow for run {} due to: {}",
+                    workflow, run.run_id, db_error
+                );
+                return Err(e);
+            }
+            match workflow {
+                RunCallWorkflowEnum::Test if run.status != RunStatusEnum::Created => {
+                    update_run_status(conn, run.run_id, RunStatusEnum::Created)
+                }
+                _ => Ok(RunData::find_by_id(conn, run.run_id)?),
+            }
+        } else {
+            run_error_logger::log_error_redacting_secrets(
+                conn,
+                run.run_id,
+                format!(
+                    "Attempt {} of {} to start {} workflow failed with error: {}.  No attempts remaining",
+                    attempt, max_attempts, workflow, e
+                ),
+                self.secret_encryptor.as_ref(),
+            );
+            Err(e)
+        }
+    }
+
+    /// Makes the scheduled retry (see `record_failed_start_attempt`) of starting the `workflow`
+    /// workflow for `run`
+    ///
+    /// Claims the retry first so it is only made once, and does nothing if there was no retry to
+    /// claim or `run` has since finished, been aborted, or been started some other way.  For the
+    /// test workflow, this starts `run` as normal (see `start_run_test`).  For the eval workflow,
+    /// the outputs of the test wdl job are retrieved from cromwell again to start the eval wdl (see
+    /// `start_run_eval`).  Returns the updated run, or an error (having marked `run` as
+    /// `CarrotFailed`) if the attempt fails and there are no attempts remaining
+    pub async fn retry_run_start(
+        &self,
+        conn: &PgConnection,
+        run: RunData,
+        workflow: RunCallWorkflowEnum,
+    ) -> Result<RunData, Error> {
+        if !RunStartAttemptData::claim_retry(conn, run.run_id, workflow)? {
+            return Ok(run);
+        }
+        match workflow {
+            RunCallWorkflowEnum::Test => {
+                if run.status != RunStatusEnum::Created || run.test_cromwell_job_id.is_some() {
+                    return Ok(run);
+                }
+                match self.start_run_test(conn, &run).await {
+                    Ok(run) => Ok(run),
+                    Err(e) => {
+                        update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                        Err(e)
+                    }
+                }
+            }
+            RunCallWorkflowEnum::Eval => {
+                if run.finished_at.is_some()
+                    || run.status == RunStatusEnum::TestAborting
+                    || run.eval_cromwell_job_id.is_some()
+                {
+                    return Ok(run);
+                }
+                let test_outputs = match self.get_source_test_outputs(conn, &run).await {
+                    Ok(test_outputs) => test_outputs,
+                    Err(e) => {
+                        update_run_status(conn, run.run_id, RunStatusEnum::CarrotFailed)?;
+                        return Err(e);
+                    }
+                };
+                self.start_run_eval(conn, &run, &test_outputs).await
+            }
+        }
+    }
+
+    /// Looks for a workflow that has already been submitted to cromwell for `run`, which is still
+    /// `Created`, and, if there is one, updates `run` to track it instead of submitting it again
+    ///
+    /// Queries each of the cromwell servers that run workflows for workflows labeled with this
+    /// CARROT instance's name and the id of `run`.  This catches the case where CARROT stopped
+    /// after submitting a workflow but before recording its id.  If an eval workflow is found
+    /// (which is only submitted after the test workflow, if there is one), `run` is updated to
+    /// `EvalSubmitted` with its id; otherwise, if a test workflow is found, `run` is updated to
+    /// `TestSubmitted` with its id.  The status manager will then update it from the status of the
+    /// workflow.  Returns the updated run, None if no workflow was found, or an error if querying
+    /// cromwell or updating the run fails
+    pub async fn adopt_submitted_workflow(
+        &self,
+        conn: &PgConnection,
+        run: &RunData,
+    ) -> Result<Option<RunData>, Error> {
+        let mut test_workflow: Option<(String, String)> = None;
+        for cromwell_server in self.server_router.server_names() {
+            // Only servers used for running workflows through cromwell could have them
+            let cromwell_client = match self
+                .server_router
+                .execution_client(Some(cromwell_server))
+                .ok()
+                .and_then(|execution_client| execution_client.as_cromwell())
+            {
+                Some(cromwell_client) => cromwell_client,
+                None => continue,
+            };
+            let params = WorkflowQueryParams {
+                label: Some(vec![
+                    format!("{}:{}", INSTANCE_LABEL, cromwell_client.instance_name()),
+                    format!("{}:{}", RUN_ID_LABEL, run.run_id),
+                ]),
+                include_subworkflows: Some(false),
+                additional_query_result_fields: Some(vec![String::from("labels")]),
+                ..Default::default()
+            };
+            let response = cromwell_client
+                .query_workflows(&params)
+                .await
+                .map_err(Error::Cromwell)?;
+            let results = match response.get("results").and_then(Value::as_array) {
+                Some(results) => results,
+                None => return Err(Error::Json),
+            };
+            for result in results {
+                let cromwell_job_id = match result.get("id").and_then(Value::as_str) {
+                    Some(id) => String::from(id),
+                    None => return Err(Error::Json),
+                };
+                let stage = result
+                    .get("labels")
+                    .and_then(|labels| labels.get(STAGE_LABEL))
+                    .and_then(Value::as_str)
+                    .and_then(|stage| stage.parse::<WorkflowStage>().ok());
+                match stage {
+                    Some(WorkflowStage::Eval) => {
+                        let run_update = RunChangeset {
+                            name: None,
+                            status: Some(RunStatusEnum::EvalSubmitted),
+                            test_cromwell_job_id: None,
+                            eval_cromwell_job_id: Some(cromwell_job_id),
+                            finished_at: None,
+                            cromwell_server: Some(cromwell_server.clone()),
+                        };
+                        return Ok(Some(RunData::update(conn, run.run_id, run_update)?));
+                    }
+                    Some(WorkflowStage::Test) if test_workflow.is_none() => {
+                        test_workflow = Some((cromwell_server.clone(), cromwell_job_id));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        match test_workflow {
+            Some((cromwell_server, cromwell_job_id)) => {
+                let run_update = RunChangeset {
+                    name: None,
+                    status: Some(RunStatusEnum::TestSubmitted),
+                    test_cromwell_job_id: Some(cromwell_job_id),
+                    eval_cromwell_job_id: None,
+                    finished_at: None,
+                    cromwell_server: Some(cromwell_server),
+                };
+                Ok(Some(RunData::update(conn, run.run_id, run_update)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Aborts `run` if it has not already finished
+    ///
+    /// If `run` is still `Created`, `Queued`, or `Building`, nothing has been submitted to cromwell
+    /// yet, so it is simply marked as `TestAborted`.  If it is in the testing or evaluating phase, sends a request
+    /// to cromwell to abort the corresponding job and marks the run as `TestAborting` or
+    /// `EvalAborting`, respectively.  The `status_manager` will then mark it as aborted once cromwell
+    /// reports that the job has been aborted.  Runs that are already aborting are returned
+    /// unchanged.  Returns the updated run, or an error if `run` has already finished, or there is
+    /// an issue sending the abort request to cromwell or updating the run in the DB
+    pub async fn abort_run(&self, conn: &PgConnection, run: &RunData) -> Result<RunData, Error> {
+        match run.status {
+            // If it hasn't been submitted to cromwell yet, we can just mark it as aborted
+            RunStatusEnum::Created | RunStatusEnum::Queued | RunStatusEnum::Building => {
+                update_run_status(conn, run.run_id, RunStatusEnum::TestAborted)
+            }
+            // If the test wdl is running, abort the test job
+            RunStatusEnum::TestSubmitted
+            | RunStatusEnum::TestQueuedInCromwell
+            | RunStatusEnum::TestRunning
+            | RunStatusEnum::TestStarting
+            | RunStatusEnum::TestWaitingForQueueSpace => {
+                self.abort_cromwell_job(
+                    run.cromwell_server.as_deref(),
+                    run.test_cromwell_job_id.as_deref(),
+                )
+                .await?;
+                update_run_status(conn, run.run_id, RunStatusEnum::TestAborting)
+            }
+            // If the eval wdl is running, abort the eval job
+            RunStatusEnum::EvalSubmitted
+            | RunStatusEnum::EvalQueuedInCromwell
+            | RunStatusEnum::EvalRunning
+            | RunStatusEnum::EvalStarting
+            | RunStatusEnum::EvalWaitingForQueueSpace => {
+                self.abort_cromwell_job(
+                    run.cromwell_server.as_deref(),
+                    run.eval_cromwell_job_id.as_deref(),
+                )
+                .await?;
+                update_run_status(conn, run.run_id, RunStatusEnum::EvalAborting)
+            }
+            // If we're already aborting, there's nothing more to do
+            RunStatusEnum::TestAborting | RunStatusEnum::EvalAborting => {
+                Ok(RunData::find_by_id(conn, run.run_id)?)
+            }
+            // Anything else has already finished, so it can't be aborted
+            _ => Err(Error::AlreadyFinished),
+        }
+    }
+
+    /// Sends a request to the cromwell server named `cromwell_server` (or the default server, if
+    /// None) to abort the job with id `cromwell_job_id`, or returns an error if `cromwell_job_id` is
+    /// None or the request fails
+    async fn abort_cromwell_job(
+        &self,
+        cromwell_server: Option<&str>,
+        cromwell_job_id: Option<&str>,
+    ) -> Result<(), Error> {
+        let cromwell_job_id = match cromwell_job_id {
+            Some(job_id) => job_id,
+            None => {
+                error!("Attempted to abort cromwell job for run without a cromwell job id");
+                return Err(Error::Cromwell(CromwellRequestError::Failed(String::from(
+                    "Run does not have a cromwell job id to abort",
+                ))));
+            }
+        };
+        match self
+            .server_router
+            .execution_client(cromwell_server)?
+            .abort_workflow(cromwell_job_id)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                error!(
+                    "Encountered an error while attempting to abort job {} in cromwell: {}",
+                    cromwell_job_id, e
+                );
+                Err(Error::Cromwell(e))
+            }
+        }
+    }
+
     /// Returns a map of keys from the `inputs_json` that contain values formatted to indicate that they
     /// should be filled with a custom docker image, to a SoftwareVersionData object for that version of
     /// the specified software
@@ -685,6 +1794,7 @@ impl TestRunner {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: Some(String::from(name)),
             status: None,
             test_input: None,
@@ -752,6 +1862,16 @@ impl TestRunner {
         }
     }
 
+    /// Deep merges `layers` of workflow options in order, so values in later layers override those
+    /// in earlier ones.  Returns None if none of the layers have a value
+    fn merge_options(layers: &[Option<&Value>]) -> Option<Value> {
+        let mut merged: Option<Value> = None;
+        for layer in layers.iter().flatten() {
+            json_patch::merge(merged.get_or_insert_with(|| json!({})), layer);
+        }
+        merged
+    }
+
     /// Retrieves template from DB with id `template_id` or returns error if query fails or template
     /// does not exist
     fn get_template(conn: &PgConnection, test_id: Uuid) -> Result<TemplateData, Error> {
@@ -773,7 +1893,14 @@ impl TestRunner {
     /// Necessary changes for test input:
     ///  1. Convert `image_build:` inputs to their corresponding `gs://` uris where the docker images
     ///     will be
-    fn format_test_json_for_cromwell(&self, inputs: &Value) -> Result<Value, Error> {
+    ///  2. Replace `secret:` inputs with the decrypted values of the secrets they reference, using
+    ///     `conn` to retrieve them.  This is the only place secret values are filled in, so they
+    ///     only ever end up in the input json that is submitted to cromwell
+    fn format_test_json_for_cromwell(
+        &self,
+        conn: &PgConnection,
+        inputs: &Value,
+    ) -> Result<Value, Error> {
         // Get inputs as map
         let object_map = match inputs.as_object() {
             Some(map) => map,
@@ -808,6 +1935,14 @@ impl TestRunner {
                         ));
                     }
                 }
+                // If it's referencing a secret, fill in the secret's value
+                else if let Some(secret_name) = secrets::get_secret_name(val) {
+                    new_val = json!(secrets::get_secret_value(
+                        conn,
+                        self.secret_encryptor.as_ref(),
+                        secret_name
+                    )?);
+                }
             };
 
             formatted_json.insert(String::from(key), new_val.to_owned());
@@ -823,11 +1958,16 @@ impl TestRunner {
     ///  1. Convert `image_build:` inputs to their corresponding `gs://` uris where the docker images
     ///     will be
     ///  2. Extract the values for `test_output:` inputs from `test_outputs` and fill them in for those
-    ///     inputs in `inputs`
+    ///     inputs in `inputs`.  If `workflow_language` is CWL, outputs can also be referred to by
+    ///     their CWL ids (see [`TestRunner::find_test_output`])
+    ///  3. Replace `secret:` inputs with the decrypted values of the secrets they reference, using
+    ///     `conn` to retrieve them
     fn format_eval_json_for_cromwell(
         &self,
+        conn: &PgConnection,
         inputs: &Value,
         test_outputs: &Map<String, Value>,
+        workflow_language: WorkflowLanguageEnum,
     ) -> Result<Value, Error> {
         // Get inputs as map
         let object_map = match inputs.as_object() {
@@ -864,11 +2004,15 @@ impl TestRunner {
                     }
                 }
                 // If it's a test_output input, fill it with the corresponding output
-                else if TEST_OUTPUT_REGEX.is_match(val) {
+                else if TEST_OUTPUT_REGEX.is_match(val)
+                    || (workflow_language == WorkflowLanguageEnum::Cwl
+                        && CWL_TEST_OUTPUT_REGEX.is_match(val))
+                {
                     // Get the key that we need to look for in the outputs
                     let output_key = val.trim_start_matches("test_output:");
                     // Find it in the outputs
-                    match test_outputs.get(output_key) {
+                    match TestRunner::find_test_output(test_outputs, output_key, workflow_language)
+                    {
                         Some(val) => {
                             new_val = val.to_owned();
                         }
@@ -879,6 +2023,14 @@ impl TestRunner {
                         }
                     }
                 }
+                // If it's referencing a secret, fill in the secret's value
+                else if let Some(secret_name) = secrets::get_secret_name(val) {
+                    new_val = json!(secrets::get_secret_value(
+                        conn,
+                        self.secret_encryptor.as_ref(),
+                        secret_name
+                    )?);
+                }
             };
 
             formatted_json.insert(String::from(key), new_val.to_owned());
@@ -887,6 +2039,46 @@ impl TestRunner {
         Ok(formatted_json.into())
     }
 
+    /// Returns the value in `test_outputs` for `output_key`, or None if there isn't one
+    ///
+    /// For WDL, `output_key` has to match exactly.  The output names cromwell reports for CWL
+    /// workflows are qualified with a workflow name we don't control (e.g. `main.out_file`), so for
+    /// CWL, if there isn't an exact match, `output_key` will also match an output with the same id
+    /// (the part after the last `.`, `#`, or `/`), as long as only one output has that id
+    fn find_test_output<'a>(
+        test_outputs: &'a Map<String, Value>,
+        output_key: &str,
+        workflow_language: WorkflowLanguageEnum,
+    ) -> Option<&'a Value> {
+        if let Some(output) = test_outputs.get(output_key) {
+            return Some(output);
+        }
+        if workflow_language != WorkflowLanguageEnum::Cwl {
+            return None;
+        }
+        fn get_output_id(key: &str) -> &str {
+            match key.rfind(|c| c == '.' || c == '#' || c == '/') {
+                Some(index) => &key[index + 1..],
+                None => key,
+            }
+        }
+        let output_id = get_output_id(output_key);
+        let mut matching_outputs = test_outputs
+            .iter()
+            .filter(|(key, _)| get_output_id(key) == output_id);
+        match (matching_outputs.next(), matching_outputs.next()) {
+            (Some((_, output)), None) => Some(output),
+            (Some(_), Some(_)) => {
+                error!(
+                    "Output key {} matches more than one CWL output, so it is ambiguous",
+                    output_key
+                );
+                None
+            }
+            _ => None,
+        }
+    }
+
     /// Generates a default name for a run based on `test_name` and the current datetime
     fn get_run_default_name(test_name: &str) -> String {
         format!("{}_run_{}", test_name, Utc::now())
@@ -956,6 +2148,22 @@ impl TestRunner {
     }
 }
 
+/// Returns the type of retriable error `e` is, if it is one of the types of errors that can be
+/// configured as retriable
+fn get_retriable_error_type(e: &Error) -> Option<RetriableError> {
+    match e {
+        Error::Cromwell(CromwellRequestError::Request(_)) => Some(RetriableError::CromwellRequest),
+        Error::Cromwell(CromwellRequestError::Payload(_)) => Some(RetriableError::CromwellPayload),
+        Error::Cromwell(CromwellRequestError::ServerError(_)) => {
+            Some(RetriableError::CromwellFailed)
+        }
+        Error::Cromwell(CromwellRequestError::Io(_)) => Some(RetriableError::CromwellIo),
+        Error::ResourceRequest(_) => Some(RetriableError::ResourceRequest),
+        Error::DB(_) => Some(RetriableError::DB),
+        _ => None,
+    }
+}
+
 /// Updates the run with the specified `run_id` to have the specified status
 ///
 /// Returns the updated run if successful or an error if it fails
@@ -978,6 +2186,7 @@ pub fn update_run_status(
             test_cromwell_job_id: None,
             eval_cromwell_job_id: None,
             finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
         },
         _ => RunChangeset {
             name: None,
@@ -985,6 +2194,7 @@ pub fn update_run_status(
             test_cromwell_job_id: None,
             eval_cromwell_job_id: None,
             finished_at: None,
+            cromwell_server: None,
         },
     };
 
@@ -1025,24 +2235,84 @@ pub fn run_finished_building(conn: &PgConnection, run_id: Uuid) -> Result<RunBui
     Ok(status)
 }
 
+/// Writes records to the `run_result` table for each of the outputs in `outputs` for which there
+/// are mappings in the `template_result` table for the template from which `run` is derived and
+/// which have a key matching the `template_result` record's `result_key` column.  The values of any
+/// secrets (decrypted using `secret_encryptor`) in the outputs are redacted before they're written
+pub fn fill_results(
+    conn: &PgConnection,
+    run: &RunData,
+    outputs: &Map<String, Value>,
+    secret_encryptor: Option<&SecretEncryptor>,
+) -> Result<(), Error> {
+    // Get template_result mappings for the template corresponding to this run
+    let template_results = TemplateResultData::find_for_test(conn, run.test_id)?;
+
+    // Load secrets so we can redact them from the results
+    let secret_redactor = SecretRedactor::load(conn, secret_encryptor)?;
+
+    // Keep a running list of results to write to the DB
+    let mut result_list: Vec<NewRunResult> = Vec::new();
+
+    // Loop through template_results, check for each of the keys in outputs, and add them to list to write
+    for template_result in template_results {
+        // Check outputs for this result
+        if let Some(output) = outputs.get(&template_result.result_key) {
+            // We have to parse some of the possible result so they're not enclosed in the
+            // redundant quotes that would be there if we used output.to_string() for
+            // everything
+            let parsed_output: String = match output {
+                Value::String(string_val) => string_val.to_string(),
+                Value::Bool(bool_val) => bool_val.to_string(),
+                Value::Number(number_val) => number_val.to_string(),
+                _ => output.to_string(),
+            };
+            result_list.push(NewRunResult {
+                run_id: run.run_id,
+                result_id: template_result.result_id,
+                value: secret_redactor.redact_str(&parsed_output),
+            });
+        }
+    }
+
+    // Write result_list to the DB
+    RunResultData::batch_create(conn, result_list)?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::custom_sql_types::{BuildStatusEnum, RunStatusEnum};
+    use crate::config::{RetriableError, RetryConfig};
+    use crate::custom_sql_types::{
+        BuildStatusEnum, RerunModeEnum, ResultTypeEnum, RunCallWorkflowEnum, RunStatusEnum,
+        WorkflowLanguageEnum,
+    };
+    use crate::manager::server_router::ServerRouter;
     use crate::manager::test_runner::{run_finished_building, Error, RunBuildStatus, TestRunner};
-    use crate::models::pipeline::{NewPipeline, PipelineData};
-    use crate::models::run::{NewRun, RunData};
+    use crate::models::pipeline::{NewPipeline, PipelineChangeset, PipelineData};
+    use crate::models::result::{NewResult, ResultData};
+    use crate::models::run::{NewRun, RunChangeset, RunData};
+    use crate::models::run_error::{RunErrorData, RunErrorQuery};
+    use crate::models::run_input_source::RunInputSourceData;
+    use crate::models::run_is_rerun::{NewRunIsRerun, RunIsRerunData};
+    use crate::models::run_result::{NewRunResult, RunResultData};
     use crate::models::run_software_version::{NewRunSoftwareVersion, RunSoftwareVersionData};
+    use crate::models::run_start_attempt::RunStartAttemptData;
+    use crate::models::secret::{NewSecret, SecretData};
     use crate::models::software::{NewSoftware, SoftwareData};
     use crate::models::software_build::{NewSoftwareBuild, SoftwareBuildData, SoftwareBuildQuery};
     use crate::models::software_version::{
         NewSoftwareVersion, SoftwareVersionData, SoftwareVersionQuery,
     };
-    use crate::models::template::{NewTemplate, TemplateData};
-    use crate::models::test::{NewTest, TestData};
-    use crate::requests::cromwell_requests::CromwellClient;
+    use crate::models::template::{NewTemplate, TemplateChangeset, TemplateData};
+    use crate::models::template_result::{NewTemplateResult, TemplateResultData};
+    use crate::models::test::{NewTest, TestChangeset, TestData};
+    use crate::requests::cromwell_requests::CromwellRequestError;
     use crate::requests::test_resource_requests::TestResourceClient;
     use crate::storage::gcloud_storage::GCloudClient;
-    use crate::unit_test_util::get_test_db_connection;
+    use crate::unit_test_util::{get_test_db_connection, load_default_config};
+    use crate::util::secrets;
     use actix_web::client::Client;
     use chrono::Utc;
     use diesel::PgConnection;
@@ -1055,6 +2325,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -1069,6 +2342,9 @@ mod tests {
             eval_wdl: format!("{}/eval_no_software_params", mockito::server_url()),
             eval_wdl_dependencies: None,
             created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(&conn, new_template).expect("Failed to insert test")
@@ -1079,6 +2355,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -1093,6 +2372,9 @@ mod tests {
             eval_wdl: format!("{}/eval_software_params", mockito::server_url()),
             eval_wdl_dependencies: None,
             created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(&conn, new_template).expect("Failed to insert test")
@@ -1108,6 +2390,10 @@ mod tests {
             eval_input_defaults: Some(json!({"test_test.in_verb":"yelled"})),
             eval_option_defaults: Some(json!({"option": false})),
             created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(&conn, new_test).expect("Failed to insert test")
@@ -1184,6 +2470,54 @@ mod tests {
         RunData::create(conn, new_run).expect("Failed inserting test run")
     }
 
+    fn insert_test_run_with_test_id_and_status_queued(conn: &PgConnection, id: Uuid) -> RunData {
+        let new_run = NewRun {
+            name: String::from("Kevin's Queued Run"),
+            test_id: id,
+            status: RunStatusEnum::Queued,
+            test_input: json!({"test_test.in_pleasantry":"Yo", "in_user_name":"Kevin"}),
+            test_options: None,
+            eval_input: json!({"test_test.in_verb":"yelled"}),
+            eval_options: None,
+            test_cromwell_job_id: None,
+            eval_cromwell_job_id: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: None,
+        };
+
+        RunData::create(conn, new_run).expect("Failed inserting test run")
+    }
+
+    fn set_test_max_concurrent_runs(conn: &PgConnection, id: Uuid, limit: i32) {
+        let changes = TestChangeset {
+            name: None,
+            description: None,
+            test_input_defaults: None,
+            test_option_defaults: None,
+            eval_input_defaults: None,
+            eval_option_defaults: None,
+            max_concurrent_runs: Some(limit),
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        TestData::update(conn, id, changes).expect("Failed to update test max_concurrent_runs");
+    }
+
+    fn set_pipeline_max_concurrent_runs(conn: &PgConnection, id: Uuid, limit: i32) {
+        let changes = PipelineChangeset {
+            name: None,
+            description: None,
+            max_concurrent_runs: Some(limit),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        PipelineData::update(conn, id, changes)
+            .expect("Failed to update pipeline max_concurrent_runs");
+    }
+
     fn insert_test_software_version(conn: &PgConnection) -> SoftwareVersionData {
         let new_software = NewSoftware {
             name: String::from("Kevin's Software"),
@@ -1235,18 +2569,55 @@ mod tests {
     }
 
     fn initialize_test_runner_without_registry_host() -> TestRunner {
-        let cromwell_client = CromwellClient::new(Client::default(), &mockito::server_url());
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
         let test_resource_client = TestResourceClient::new(Client::default(), None);
-        TestRunner::new(cromwell_client, test_resource_client, None)
+        TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        )
     }
 
     fn initialize_test_runner_with_registry_host() -> TestRunner {
-        let cromwell_client = CromwellClient::new(Client::default(), &mockito::server_url());
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
         let test_resource_client = TestResourceClient::new(Client::default(), None);
         TestRunner::new(
-            cromwell_client,
+            server_router,
             test_resource_client,
             Some("https://example.com"),
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        )
+    }
+
+    fn initialize_test_runner_with_secrets(conn: &PgConnection) -> TestRunner {
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let secret_encryptor = secrets::get_secret_encryptor(&load_default_config()).unwrap();
+        SecretData::create(
+            conn,
+            NewSecret {
+                name: String::from("kevins_token"),
+                description: None,
+                encrypted_value: secret_encryptor.encrypt("hunter2").unwrap(),
+                created_by: None,
+            },
+        )
+        .expect("Failed to insert test secret");
+        TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            Some(secret_encryptor),
         )
     }
 
@@ -1301,6 +2672,8 @@ mod tests {
                 Some(eval_params.clone()),
                 eval_options,
                 Some(String::from("Kevin@example.com")),
+                None,
+                None,
             )
             .await
             .unwrap();
@@ -1331,19 +2704,69 @@ mod tests {
     }
 
     #[actix_rt::test]
-    async fn test_create_run_software_params() {
+    async fn test_create_run_workflow_option_defaults() {
         let conn = get_test_db_connection();
-        let test_test_runner: TestRunner = initialize_test_runner_with_registry_host();
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let test_test_runner = TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            None,
+            RetryConfig::default(),
+            Some(json!({
+                "read_from_cache": false,
+                "default_runtime_attributes": {"zones": "us-central1-a", "maxRetries": 1}
+            })),
+            None,
+        );
 
-        let test_template = insert_test_template_software_params(&conn);
+        // Set workflow option defaults for the pipeline and template
+        let test_template = insert_test_template_no_software_params(&conn);
+        PipelineData::update(
+            &conn,
+            test_template.pipeline_id,
+            PipelineChangeset {
+                name: None,
+                description: None,
+                max_concurrent_runs: None,
+                cromwell_server: None,
+                workflow_option_defaults: Some(json!({
+                    "final_workflow_outputs_dir": "gs://pipeline/outputs",
+                    "default_runtime_attributes": {"maxRetries": 2}
+                })),
+            },
+        )
+        .unwrap();
+        TemplateData::update(
+            &conn,
+            test_template.template_id,
+            TemplateChangeset {
+                name: None,
+                description: None,
+                test_wdl: None,
+                test_wdl_dependencies: None,
+                eval_wdl: None,
+                eval_wdl_dependencies: None,
+                cromwell_server: None,
+                workflow_option_defaults: Some(json!({
+                    "final_workflow_outputs_dir": "gs://template/outputs"
+                })),
+                workflow_language: None,
+            },
+        )
+        .unwrap();
         let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
 
-        let test_software = insert_test_software(&conn);
-
-        let test_params = json!({"in_user_name":"Kevin", "in_test_image":"image_build:TestSoftware|764a00442ddb412eed331655cfd90e151f580518"});
-        let test_options = None;
-        let eval_params = json!({"in_user":"Jonn", "in_eval_image":"image_build:TestSoftware|764a00442ddb412eed331655cfd90e151f580518"});
-        let eval_options = Some(json!({"option": true}));
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test_no_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/test_wdl_no_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(1)
+            .create();
         // Define mockito mapping for cromwell response
         let mock_response_body = json!({
           "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
@@ -1352,7 +2775,7 @@ mod tests {
         let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
             .with_status(201)
             .with_header("content_type", "application/json")
-            .expect(0)
+            .with_body(mock_response_body.to_string())
             .create();
 
         let test_run = test_test_runner
@@ -1360,25 +2783,92 @@ mod tests {
                 &conn,
                 &test_test.test_id.to_string(),
                 Some(String::from("Test run")),
-                Some(test_params.clone()),
-                test_options,
-                Some(eval_params.clone()),
-                eval_options,
+                Some(json!({"in_user_name":"Kevin"})),
+                Some(json!({"read_from_cache": true})),
+                Some(json!({})),
+                None,
                 Some(String::from("Kevin@example.com")),
+                None,
+                None,
             )
             .await
             .unwrap();
 
+        wdl_mock.assert();
         cromwell_mock.assert();
 
-        assert_eq!(test_run.test_id, test_test.test_id);
-        assert_eq!(test_run.status, RunStatusEnum::Building);
-        let mut test_input_to_compare = json!({});
-        json_patch::merge(
-            &mut test_input_to_compare,
-            &test_test.test_input_defaults.unwrap(),
+        // Each level should override the ones before it, with nested objects merged
+        assert_eq!(
+            test_run.test_options,
+            Some(json!({
+                "read_from_cache": true,
+                "default_runtime_attributes": {"zones": "us-central1-a", "maxRetries": 2},
+                "final_workflow_outputs_dir": "gs://template/outputs",
+                "option": true
+            }))
         );
-        json_patch::merge(&mut test_input_to_compare, &test_params);
+        assert_eq!(
+            test_run.eval_options,
+            Some(json!({
+                "read_from_cache": false,
+                "default_runtime_attributes": {"zones": "us-central1-a", "maxRetries": 2},
+                "final_workflow_outputs_dir": "gs://template/outputs",
+                "option": false
+            }))
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_create_run_software_params() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_with_registry_host();
+
+        let test_template = insert_test_template_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+
+        let test_software = insert_test_software(&conn);
+
+        let test_params = json!({"in_user_name":"Kevin", "in_test_image":"image_build:TestSoftware|764a00442ddb412eed331655cfd90e151f580518"});
+        let test_options = None;
+        let eval_params = json!({"in_user":"Jonn", "in_eval_image":"image_build:TestSoftware|764a00442ddb412eed331655cfd90e151f580518"});
+        let eval_options = Some(json!({"option": true}));
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .expect(0)
+            .create();
+
+        let test_run = test_test_runner
+            .create_run(
+                &conn,
+                &test_test.test_id.to_string(),
+                Some(String::from("Test run")),
+                Some(test_params.clone()),
+                test_options,
+                Some(eval_params.clone()),
+                eval_options,
+                Some(String::from("Kevin@example.com")),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        cromwell_mock.assert();
+
+        assert_eq!(test_run.test_id, test_test.test_id);
+        assert_eq!(test_run.status, RunStatusEnum::Building);
+        let mut test_input_to_compare = json!({});
+        json_patch::merge(
+            &mut test_input_to_compare,
+            &test_test.test_input_defaults.unwrap(),
+        );
+        json_patch::merge(&mut test_input_to_compare, &test_params);
         let mut eval_input_to_compare = json!({});
         json_patch::merge(
             &mut eval_input_to_compare,
@@ -1420,35 +2910,1262 @@ mod tests {
         let created_software_build = SoftwareBuildData::find(&conn, software_build_q).unwrap();
         assert_eq!(created_software_build.len(), 1);
 
-        let created_run_software_version =
-            RunSoftwareVersionData::find_by_run_and_software_version(
-                &conn,
-                test_run.run_id,
-                created_software_version[0].software_version_id,
-            )
+        let created_run_software_version =
+            RunSoftwareVersionData::find_by_run_and_software_version(
+                &conn,
+                test_run.run_id,
+                created_software_version[0].software_version_id,
+            )
+            .unwrap();
+    }
+
+    fn insert_test_run_result_for_run(
+        conn: &PgConnection,
+        run: &RunData,
+        template_id: Uuid,
+        result_key: &str,
+        result_type: ResultTypeEnum,
+        value: &str,
+    ) {
+        let new_result = NewResult {
+            name: String::from("Kevin's result"),
+            result_type,
+            description: None,
+            created_by: None,
+        };
+        let new_result = ResultData::create(conn, new_result).expect("Failed inserting result");
+        let new_template_result = NewTemplateResult {
+            template_id,
+            result_id: new_result.result_id,
+            result_key: String::from(result_key),
+            created_by: None,
+        };
+        TemplateResultData::create(conn, new_template_result)
+            .expect("Failed inserting template result");
+        let new_run_result = NewRunResult {
+            run_id: run.run_id,
+            result_id: new_result.result_id,
+            value: String::from(value),
+        };
+        RunResultData::create(conn, new_run_result).expect("Failed inserting run result");
+    }
+
+    #[actix_rt::test]
+    async fn test_create_run_run_output_references() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let source_run = insert_test_run(&conn);
+        let source_test = TestData::find_by_id(&conn, source_run.test_id).unwrap();
+        insert_test_run_result_for_run(
+            &conn,
+            &source_run,
+            source_test.template_id,
+            "test_test.out_file",
+            ResultTypeEnum::File,
+            "gs://example/out_file.txt",
+        );
+
+        let test_params = json!({
+            "test_test.in_greeting": "run_output:Kevin's test run.test_test.out_greeting",
+            "test_test.in_greeting_by_id": format!("run_output:{}.test_test.out_greeting", source_run.run_id)
+        });
+        let eval_params = json!({
+            "test_test.in_file": "latest_successful_output:Kevin's test test.test_test.out_file"
+        });
+        // Define mockito mapping for cromwell metadata response for the source run's test job
+        let metadata_mock_body = json!({
+          "outputs": {
+            "test_test.out_greeting": "Yo"
+          }
+        });
+        let metadata_mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/123456789/metadata?includeKey=outputs",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(metadata_mock_body.to_string())
+        .expect(2)
+        .create();
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test_no_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/test_wdl_no_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(1)
+            .create();
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .create();
+
+        let test_run = test_test_runner
+            .create_run(
+                &conn,
+                &source_run.test_id.to_string(),
+                Some(String::from("Test run with references")),
+                Some(test_params),
+                None,
+                Some(eval_params),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        metadata_mock.assert();
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert_eq!(test_run.status, RunStatusEnum::TestSubmitted);
+        assert_eq!(
+            test_run.test_input,
+            json!({
+                "test_test.in_pleasantry": "Yo",
+                "test_test.in_greeting": "Yo",
+                "test_test.in_greeting_by_id": "Yo"
+            })
+        );
+        assert_eq!(
+            test_run.eval_input,
+            json!({
+                "test_test.in_verb": "yelled",
+                "test_test.in_file": "gs://example/out_file.txt"
+            })
+        );
+
+        let input_sources = RunInputSourceData::find_by_run_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(input_sources.len(), 3);
+        assert_eq!(input_sources[0].workflow, RunCallWorkflowEnum::Test);
+        assert_eq!(input_sources[0].input_key, "test_test.in_greeting");
+        assert_eq!(
+            input_sources[0].reference,
+            "run_output:Kevin's test run.test_test.out_greeting"
+        );
+        assert_eq!(input_sources[0].source_run_id, source_run.run_id);
+        assert_eq!(input_sources[0].value, json!("Yo"));
+        assert_eq!(input_sources[1].input_key, "test_test.in_greeting_by_id");
+        assert_eq!(input_sources[1].source_run_id, source_run.run_id);
+        assert_eq!(input_sources[2].workflow, RunCallWorkflowEnum::Eval);
+        assert_eq!(input_sources[2].input_key, "test_test.in_file");
+        assert_eq!(input_sources[2].source_run_id, source_run.run_id);
+        assert_eq!(input_sources[2].value, json!("gs://example/out_file.txt"));
+    }
+
+    #[actix_rt::test]
+    async fn test_find_referenced_run_prefers_longest_run_name() {
+        let conn = get_test_db_connection();
+
+        let short_name_run = insert_test_run(&conn);
+        let new_run = NewRun {
+            test_id: short_name_run.test_id,
+            name: String::from("Kevin's test run.test_test"),
+            status: RunStatusEnum::Succeeded,
+            test_input: json!({}),
+            test_options: None,
+            eval_input: json!({}),
+            eval_options: None,
+            test_cromwell_job_id: Some(String::from("987654321")),
+            eval_cromwell_job_id: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: Some(Utc::now().naive_utc()),
+        };
+        let long_name_run = RunData::create(&conn, new_run).expect("Failed to insert run");
+
+        let (found_run, output_key) = TestRunner::find_referenced_run(
+            &conn,
+            "run_output:Kevin's test run.test_test.out_greeting",
+        )
+        .unwrap();
+
+        assert_eq!(found_run.run_id, long_name_run.run_id);
+        assert_eq!(output_key, "out_greeting");
+    }
+
+    #[actix_rt::test]
+    async fn test_get_run_output_numeric_result() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let source_run = insert_test_run(&conn);
+        let source_test = TestData::find_by_id(&conn, source_run.test_id).unwrap();
+        insert_test_run_result_for_run(
+            &conn,
+            &source_run,
+            source_test.template_id,
+            "test_test.out_count",
+            ResultTypeEnum::Numeric,
+            "42",
+        );
+
+        let output = test_test_runner
+            .get_run_output(&conn, &source_run, "test_test.out_count")
+            .await
+            .unwrap();
+
+        assert_eq!(output, json!(42));
+    }
+
+    #[test]
+    fn test_parse_result_value() {
+        assert_eq!(
+            TestRunner::parse_result_value("42", ResultTypeEnum::Numeric),
+            json!(42)
+        );
+        assert_eq!(
+            TestRunner::parse_result_value("4.5", ResultTypeEnum::Numeric),
+            json!(4.5)
+        );
+        assert_eq!(
+            TestRunner::parse_result_value("not a number", ResultTypeEnum::Numeric),
+            json!("not a number")
+        );
+        assert_eq!(
+            TestRunner::parse_result_value("42", ResultTypeEnum::Text),
+            json!("42")
+        );
+        assert_eq!(
+            TestRunner::parse_result_value("gs://example/42", ResultTypeEnum::File),
+            json!("gs://example/42")
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_create_run_run_output_reference_run_not_found() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let source_run = insert_test_run(&conn);
+
+        let result = test_test_runner
+            .create_run(
+                &conn,
+                &source_run.test_id.to_string(),
+                Some(String::from("Test run with references")),
+                Some(json!({"test_test.in_greeting": "run_output:Kevin's other run.test_test.out_greeting"})),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await;
+
+        assert!(matches!(result, Err(Error::InputReference(_))));
+        assert!(matches!(
+            RunData::find_by_name(&conn, "Test run with references"),
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn test_create_run_latest_successful_output_reference_output_not_found() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let source_run = insert_test_run(&conn);
+
+        // Define mockito mappings for cromwell metadata responses for the source run's jobs
+        let test_metadata_mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/123456789/metadata?includeKey=outputs",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(json!({"outputs": {"test_test.out_greeting": "Yo"}}).to_string())
+        .expect(1)
+        .create();
+        let eval_metadata_mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/12345678901/metadata?includeKey=outputs",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(json!({"outputs": {"test_test.out_verdict": "Good"}}).to_string())
+        .expect(1)
+        .create();
+
+        let result = test_test_runner
+            .create_run(
+                &conn,
+                &source_run.test_id.to_string(),
+                Some(String::from("Test run with references")),
+                None,
+                None,
+                Some(json!({"test_test.in_file": "latest_successful_output:Kevin's test test.test_test.out_missing"})),
+                None,
+                None,
+                None,
+                None,
+            )
+            .await;
+
+        test_metadata_mock.assert();
+        eval_metadata_mock.assert();
+
+        assert!(matches!(result, Err(Error::InputReference(_))));
+    }
+
+    #[actix_rt::test]
+    async fn test_create_run_run_output_reference_secret() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_with_secrets(&conn);
+
+        let source_run = insert_test_run(&conn);
+
+        // Define mockito mapping for cromwell metadata response with an output containing a secret
+        let metadata_mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/123456789/metadata?includeKey=outputs",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(json!({"outputs": {"test_test.out_token": "token=hunter2"}}).to_string())
+        .expect(1)
+        .create();
+
+        let result = test_test_runner
+            .create_run(
+                &conn,
+                &source_run.test_id.to_string(),
+                Some(String::from("Test run with references")),
+                Some(json!({"test_test.in_token": "run_output:Kevin's test run.test_test.out_token"})),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .await;
+
+        metadata_mock.assert();
+
+        assert!(matches!(result, Err(Error::InputReference(_))));
+    }
+
+    #[actix_rt::test]
+    async fn test_start_run_test() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status_building(&conn, test_test.test_id);
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/test_wdl_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(1)
+            .create();
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "34958601-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .create();
+
+        let result = test_test_runner
+            .start_run_test(&conn, &test_run)
+            .await
+            .unwrap();
+
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert_eq!(
+            result.test_cromwell_job_id.unwrap(),
+            "34958601-d114-4194-a7f7-9e41211ca2ce"
+        );
+        assert_eq!(result.cromwell_server.unwrap(), "default");
+    }
+
+    #[actix_rt::test]
+    async fn test_start_run_test_retries_exhausted() {
+        let conn = get_test_db_connection();
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let test_test_runner = TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            None,
+            RetryConfig::new(2, 0, 2, 0, vec![RetriableError::CromwellFailed]),
+            None,
+            None,
+        );
+
+        let test_template = insert_test_template_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status_building(&conn, test_test.test_id);
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/test_wdl_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(2)
+            .create();
+        // Define mockito mapping for cromwell response
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(503)
+            .expect(2)
+            .create();
+
+        // The first attempt should fail and schedule a retry
+        let retrying_run = test_test_runner
+            .start_run_test(&conn, &test_run)
+            .await
+            .unwrap();
+
+        assert_eq!(retrying_run.status, RunStatusEnum::Created);
+        let run_start_attempts =
+            RunStartAttemptData::find_by_run_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(run_start_attempts[0].attempts, 1);
+        assert!(run_start_attempts[0].next_attempt_at.is_some());
+
+        // Then the retry should fail with no attempts remaining
+        let error = test_test_runner
+            .retry_run_start(&conn, retrying_run, RunCallWorkflowEnum::Test)
+            .await
+            .unwrap_err();
+
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert!(matches!(
+            error,
+            Error::Cromwell(CromwellRequestError::ServerError(_))
+        ));
+        let failed_run = RunData::find_by_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(failed_run.status, RunStatusEnum::CarrotFailed);
+        // Make sure both attempts were counted and logged
+        let run_start_attempts =
+            RunStartAttemptData::find_by_run_id(&conn, test_run.run_id).unwrap();
+        assert_eq!(run_start_attempts.len(), 1);
+        assert_eq!(run_start_attempts[0].workflow, RunCallWorkflowEnum::Test);
+        assert_eq!(run_start_attempts[0].attempts, 2);
+        let run_errors = RunErrorData::find(
+            &conn,
+            RunErrorQuery {
+                run_error_id: None,
+                run_id: Some(test_run.run_id),
+                error: None,
+                created_before: None,
+                created_after: None,
+                sort: None,
+                limit: None,
+                offset: None,
+            },
+        )
+        .unwrap();
+        assert_eq!(run_errors.len(), 2);
+        assert!(run_errors.iter().any(|run_error| run_error
+            .error
+            .starts_with("Attempt 1 of 2 to start test workflow failed")
+            && run_error.error.ends_with("Retrying in 0 seconds")));
+        assert!(run_errors.iter().any(|run_error| run_error
+            .error
+            .starts_with("Attempt 2 of 2 to start test workflow failed")
+            && run_error.error.ends_with("No attempts remaining")));
+    }
+
+    #[actix_rt::test]
+    async fn test_retry_run_start_test() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status_building(&conn, test_test.test_id);
+        let test_run = RunData::update(
+            &conn,
+            test_run.run_id,
+            RunChangeset {
+                name: None,
+                status: Some(RunStatusEnum::Created),
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                finished_at: None,
+                cromwell_server: None,
+            },
+        )
+        .unwrap();
+        // Record a failed attempt at starting the test workflow that is due to be retried
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Test).unwrap();
+        RunStartAttemptData::schedule_retry(
+            &conn,
+            test_run.run_id,
+            RunCallWorkflowEnum::Test,
+            Utc::now().naive_utc(),
+        )
+        .unwrap();
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/test_wdl_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(1)
+            .create();
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "34958601-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .expect(1)
+            .create();
+
+        let result = test_test_runner
+            .retry_run_start(&conn, test_run, RunCallWorkflowEnum::Test)
+            .await
+            .unwrap();
+
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert_eq!(result.status, RunStatusEnum::TestSubmitted);
+        assert_eq!(
+            result.test_cromwell_job_id.unwrap(),
+            "34958601-d114-4194-a7f7-9e41211ca2ce"
+        );
+        // The retry should have been claimed so it isn't made again
+        let run_start_attempts = RunStartAttemptData::find_by_run_id(&conn, result.run_id).unwrap();
+        assert!(run_start_attempts[0].next_attempt_at.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_adopt_submitted_workflow_eval() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_run = insert_test_run(&conn);
+        // Define mockito mapping for the query, which finds both workflows for the run
+        let query_mock = mockito::mock("POST", "/api/workflows/v1/query")
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(
+                json!({
+                    "results": [
+                        {
+                            "id": "34958601-d114-4194-a7f7-9e41211ca2ce",
+                            "status": "Succeeded",
+                            "labels": {
+                                "carrot-instance": "carrot",
+                                "carrot-stage": "test",
+                                "carrot-run-id": test_run.run_id.to_string()
+                            }
+                        },
+                        {
+                            "id": "45069712-d114-4194-a7f7-9e41211ca2ce",
+                            "status": "Running",
+                            "labels": {
+                                "carrot-instance": "carrot",
+                                "carrot-stage": "eval",
+                                "carrot-run-id": test_run.run_id.to_string()
+                            }
+                        }
+                    ],
+                    "totalResultsCount": 2
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let result = test_test_runner
+            .adopt_submitted_workflow(&conn, &test_run)
+            .await
+            .unwrap()
+            .unwrap();
+
+        query_mock.assert();
+
+        // The eval workflow is the later one, so that's the one the run should track
+        assert_eq!(result.status, RunStatusEnum::EvalSubmitted);
+        assert_eq!(
+            result.eval_cromwell_job_id.unwrap(),
+            "45069712-d114-4194-a7f7-9e41211ca2ce"
+        );
+        assert_eq!(result.cromwell_server.unwrap(), "default");
+    }
+
+    #[actix_rt::test]
+    async fn test_retry_run_start_not_scheduled() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status_building(&conn, test_test.test_id);
+        // Nothing should be requested from cromwell since there's no retry to make
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .expect(0)
+            .create();
+
+        let result = test_test_runner
+            .retry_run_start(&conn, test_run, RunCallWorkflowEnum::Test)
+            .await
+            .unwrap();
+
+        cromwell_mock.assert();
+
+        assert_eq!(result.status, RunStatusEnum::Building);
+        assert!(result.test_cromwell_job_id.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_start_run_test_not_retriable() {
+        let conn = get_test_db_connection();
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let test_test_runner = TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            None,
+            RetryConfig::new(3, 0, 2, 0, vec![RetriableError::CromwellRequest]),
+            None,
+            None,
+        );
+
+        let test_template = insert_test_template_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status_building(&conn, test_test.test_id);
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/test_wdl_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(1)
+            .create();
+        // Define mockito mapping for cromwell response
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(503)
+            .expect(1)
+            .create();
+
+        let error = test_test_runner
+            .start_run_test(&conn, &test_run)
+            .await
+            .unwrap_err();
+
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert!(matches!(
+            error,
+            Error::Cromwell(CromwellRequestError::ServerError(_))
+        ));
+        // It shouldn't have been retried, so there should be no attempts recorded
+        let run_start_attempts =
+            RunStartAttemptData::find_by_run_id(&conn, test_run.run_id).unwrap();
+        assert!(run_start_attempts.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn test_start_run_test_client_error_not_retriable() {
+        let conn = get_test_db_connection();
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let test_test_runner = TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            None,
+            RetryConfig::new(3, 0, 2, 0, vec![RetriableError::CromwellFailed]),
+            None,
+            None,
+        );
+
+        let test_template = insert_test_template_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status_building(&conn, test_test.test_id);
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/test_wdl_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(1)
+            .create();
+        // Define mockito mapping for cromwell response rejecting the request
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(400)
+            .expect(1)
+            .create();
+
+        let error = test_test_runner
+            .start_run_test(&conn, &test_run)
+            .await
+            .unwrap_err();
+
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert!(matches!(
+            error,
+            Error::Cromwell(CromwellRequestError::Failed(_))
+        ));
+        // A 4xx response means the request was rejected, so it shouldn't have been retried
+        let run_start_attempts =
+            RunStartAttemptData::find_by_run_id(&conn, test_run.run_id).unwrap();
+        assert!(run_start_attempts.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn test_start_run_eval() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run =
+            insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+
+        let test_results = json!({
+            "test_test.TestKey": "TestVal",
+            "test_test.UnimportantKey": "Who Cares?"
+        });
+
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/eval_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/eval_wdl_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(1)
+            .create();
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "34958601-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .create();
+
+        let result = test_test_runner
+            .start_run_eval(&conn, &test_run, test_results.as_object().unwrap())
+            .await
+            .unwrap();
+
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert_eq!(
+            result.eval_cromwell_job_id.unwrap(),
+            "34958601-d114-4194-a7f7-9e41211ca2ce"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_start_run_eval_missing_test_output() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run =
+            insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+
+        let test_results = json!({
+            "test_test.UnimportantKey": "Who Cares?"
+        });
+
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .expect(0)
+            .create();
+
+        let result = test_test_runner
+            .start_run_eval(&conn, &test_run, test_results.as_object().unwrap())
+            .await;
+
+        cromwell_mock.assert();
+
+        assert!(matches!(result, Err(Error::MissingOutputKey(_))));
+    }
+
+    #[actix_rt::test]
+    async fn test_create_run_queued_global_limit() {
+        let conn = get_test_db_connection();
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let test_test_runner = TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            Some(1),
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let test_template = insert_test_template_no_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+
+        // Nothing should be retrieved or submitted
+        let wdl_mock = mockito::mock("GET", "/test_no_software_params")
+            .with_status(200)
+            .expect(0)
+            .create();
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .expect(0)
+            .create();
+
+        let test_run = test_test_runner
+            .create_run(
+                &conn,
+                &test_test.test_id.to_string(),
+                Some(String::from("Test run")),
+                Some(json!({"in_user_name":"Kevin"})),
+                None,
+                None,
+                None,
+                Some(String::from("Kevin@example.com")),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert_eq!(test_run.status, RunStatusEnum::Queued);
+        assert!(test_run.test_cromwell_job_id.is_none());
+        assert!(test_run.finished_at.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_create_run_queued_test_limit() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_no_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        set_test_max_concurrent_runs(&conn, test_test.test_id, 1);
+        insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .expect(0)
+            .create();
+
+        let test_run = test_test_runner
+            .create_run(
+                &conn,
+                &test_test.test_id.to_string(),
+                Some(String::from("Test run")),
+                Some(json!({"in_user_name":"Kevin"})),
+                None,
+                None,
+                None,
+                Some(String::from("Kevin@example.com")),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        cromwell_mock.assert();
+
+        assert_eq!(test_run.status, RunStatusEnum::Queued);
+    }
+
+    #[actix_rt::test]
+    async fn test_create_run_queued_behind_queued_run() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_no_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        set_test_max_concurrent_runs(&conn, test_test.test_id, 2);
+        insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+        // There's room for one more run, but this one was queued first, so the new run should
+        // wait behind it
+        insert_test_run_with_test_id_and_status_queued(&conn, test_test.test_id);
+
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .expect(0)
+            .create();
+
+        let test_run = test_test_runner
+            .create_run(
+                &conn,
+                &test_test.test_id.to_string(),
+                Some(String::from("Test run")),
+                Some(json!({"in_user_name":"Kevin"})),
+                None,
+                None,
+                None,
+                Some(String::from("Kevin@example.com")),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        cromwell_mock.assert();
+
+        assert_eq!(test_run.status, RunStatusEnum::Queued);
+    }
+
+    #[actix_rt::test]
+    async fn test_create_run_not_queued_behind_run_queued_for_other_limit() {
+        let conn = get_test_db_connection();
+        let server_router = ServerRouter::new(Client::default(), &load_default_config());
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let test_test_runner = TestRunner::new(
+            server_router,
+            test_resource_client,
+            None,
+            Some(10),
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let test_template = insert_test_template_no_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        // Another test is at its own limit and has a run queued, which shouldn't keep runs for
+        // this test from starting, since there's plenty of room within the global limit
+        let other_test = TestData::create(
+            &conn,
+            NewTest {
+                name: String::from("Kevin's other test test"),
+                template_id: test_template.template_id,
+                description: None,
+                test_input_defaults: None,
+                test_option_defaults: None,
+                eval_input_defaults: None,
+                eval_option_defaults: None,
+                created_by: None,
+                max_concurrent_runs: Some(1),
+                priority: None,
+                max_runtime_in_secs: None,
+                cromwell_server: None,
+            },
+        )
+        .expect("Failed to insert test");
+        insert_test_run_with_test_id_and_status_test_submitted(&conn, other_test.test_id);
+        insert_test_run_with_test_id_and_status_queued(&conn, other_test.test_id);
+
+        let wdl_mock = mockito::mock("GET", "/test_no_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/test_wdl_no_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(1)
+            .create();
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(
+                json!({
+                  "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+                  "status": "Submitted"
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let test_run = test_test_runner
+            .create_run(
+                &conn,
+                &test_test.test_id.to_string(),
+                Some(String::from("Test run")),
+                Some(json!({"in_user_name":"Kevin"})),
+                None,
+                None,
+                None,
+                Some(String::from("Kevin@example.com")),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert_eq!(test_run.status, RunStatusEnum::TestSubmitted);
+    }
+
+    #[actix_rt::test]
+    async fn test_create_run_queued_created_run_counts_toward_limit() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_no_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        set_test_max_concurrent_runs(&conn, test_test.test_id, 1);
+        // Another run that's in the process of being started takes up the only slot
+        let other_run = insert_test_run_with_test_id_and_status_queued(&conn, test_test.test_id);
+        RunData::update(
+            &conn,
+            other_run.run_id,
+            RunChangeset {
+                name: None,
+                status: Some(RunStatusEnum::Created),
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                finished_at: None,
+                cromwell_server: None,
+            },
+        )
+        .unwrap();
+
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .expect(0)
+            .create();
+
+        let test_run = test_test_runner
+            .create_run(
+                &conn,
+                &test_test.test_id.to_string(),
+                Some(String::from("Test run")),
+                Some(json!({"in_user_name":"Kevin"})),
+                None,
+                None,
+                None,
+                Some(String::from("Kevin@example.com")),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        cromwell_mock.assert();
+
+        assert_eq!(test_run.status, RunStatusEnum::Queued);
+    }
+
+    #[actix_rt::test]
+    async fn test_start_queued_run() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_no_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        set_test_max_concurrent_runs(&conn, test_test.test_id, 1);
+        let test_run = insert_test_run_with_test_id_and_status_queued(&conn, test_test.test_id);
+
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test_no_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/test_wdl_no_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(1)
+            .create();
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .create();
+
+        let result = test_test_runner
+            .start_queued_run(&conn, &test_run)
+            .await
+            .unwrap();
+
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert_eq!(result.status, RunStatusEnum::TestSubmitted);
+        assert_eq!(
+            result.test_cromwell_job_id,
+            Some("53709600-d114-4194-a7f7-9e41211ca2ce".to_string())
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_start_queued_run_still_at_capacity() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_no_software_params(&conn);
+        set_pipeline_max_concurrent_runs(&conn, test_template.pipeline_id, 1);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+        let test_run = insert_test_run_with_test_id_and_status_queued(&conn, test_test.test_id);
+
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .expect(0)
+            .create();
+
+        let result = test_test_runner
+            .start_queued_run(&conn, &test_run)
+            .await
             .unwrap();
+
+        cromwell_mock.assert();
+
+        assert_eq!(result.status, RunStatusEnum::Queued);
+        assert_eq!(result.run_id, test_run.run_id);
     }
 
     #[actix_rt::test]
-    async fn test_start_run_test() {
+    async fn test_abort_run_queued() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_no_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run = insert_test_run_with_test_id_and_status_queued(&conn, test_test.test_id);
+
+        let cromwell_mock = mockito::mock("POST", mockito::Matcher::Any)
+            .with_status(200)
+            .expect(0)
+            .create();
+
+        let result = test_test_runner.abort_run(&conn, &test_run).await.unwrap();
+
+        cromwell_mock.assert();
+
+        assert_eq!(result.status, RunStatusEnum::TestAborted);
+        assert!(result.finished_at.is_some());
+    }
+
+    #[actix_rt::test]
+    async fn test_abort_run_building() {
         let conn = get_test_db_connection();
         let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
 
         let test_template = insert_test_template_software_params(&conn);
         let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
         let test_run = insert_test_run_with_test_id_and_status_building(&conn, test_test.test_id);
+
+        let cromwell_mock = mockito::mock("POST", mockito::Matcher::Any)
+            .with_status(200)
+            .expect(0)
+            .create();
+
+        let result = test_test_runner.abort_run(&conn, &test_run).await.unwrap();
+
+        cromwell_mock.assert();
+
+        assert_eq!(result.status, RunStatusEnum::TestAborted);
+        assert!(result.finished_at.is_some());
+    }
+
+    #[actix_rt::test]
+    async fn test_abort_run_test_submitted() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let test_run =
+            insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Aborting"
+        });
+        let cromwell_mock = mockito::mock(
+            "POST",
+            "/api/workflows/v1/53709600-d114-4194-a7f7-9e41211ca2ce/abort",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(mock_response_body.to_string())
+        .create();
+
+        let result = test_test_runner.abort_run(&conn, &test_run).await.unwrap();
+
+        cromwell_mock.assert();
+
+        assert_eq!(result.status, RunStatusEnum::TestAborting);
+        assert!(result.finished_at.is_none());
+    }
+
+    #[actix_rt::test]
+    async fn test_abort_run_already_finished() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_run = insert_test_run(&conn);
+
+        let cromwell_mock = mockito::mock("POST", mockito::Matcher::Any)
+            .with_status(200)
+            .expect(0)
+            .create();
+
+        let result = test_test_runner.abort_run(&conn, &test_run).await;
+
+        cromwell_mock.assert();
+
+        assert!(matches!(result, Err(Error::AlreadyFinished)));
+    }
+
+    #[actix_rt::test]
+    async fn test_rerun_run_full() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let source_run = insert_test_run(&conn);
+
         // Define mockito mapping for wdl
-        let wdl_mock = mockito::mock("GET", "/test_software_params")
+        let wdl_mock = mockito::mock("GET", "/test_no_software_params")
             .with_status(200)
             .with_body(
-                read_to_string("testdata/manager/test_runner/test_wdl_software_params.wdl")
+                read_to_string("testdata/manager/test_runner/test_wdl_no_software_params.wdl")
                     .unwrap(),
             )
             .expect(1)
             .create();
         // Define mockito mapping for cromwell response
         let mock_response_body = json!({
-          "id": "34958601-d114-4194-a7f7-9e41211ca2ce",
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
           "status": "Submitted"
         });
         let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
@@ -1458,39 +4175,61 @@ mod tests {
             .create();
 
         let result = test_test_runner
-            .start_run_test(&conn, &test_run)
+            .rerun_run(
+                &conn,
+                &source_run,
+                RerunModeEnum::Full,
+                Some(String::from("Test rerun")),
+                Some(String::from("Kevin@example.com")),
+            )
             .await
             .unwrap();
 
         wdl_mock.assert();
         cromwell_mock.assert();
 
+        assert_eq!(result.name, "Test rerun");
+        assert_eq!(result.status, RunStatusEnum::TestSubmitted);
         assert_eq!(
-            result.test_cromwell_job_id.unwrap(),
-            "34958601-d114-4194-a7f7-9e41211ca2ce"
+            result.test_cromwell_job_id,
+            Some("53709600-d114-4194-a7f7-9e41211ca2ce".to_string())
         );
+        assert_eq!(result.test_input, source_run.test_input);
+        assert_eq!(result.eval_input, source_run.eval_input);
+        assert_eq!(result.eval_options, source_run.eval_options);
+
+        let run_is_rerun = RunIsRerunData::find_by_run_id(&conn, result.run_id).unwrap();
+        assert_eq!(run_is_rerun.source_run_id, source_run.run_id);
+        assert_eq!(run_is_rerun.mode, RerunModeEnum::Full);
     }
 
     #[actix_rt::test]
-    async fn test_start_run_eval() {
+    async fn test_rerun_run_eval_only() {
         let conn = get_test_db_connection();
         let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
 
-        let test_template = insert_test_template_software_params(&conn);
-        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
-        let test_run =
-            insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+        let source_run = insert_test_run(&conn);
 
-        let test_results = json!({
-            "test_test.TestKey": "TestVal",
-            "test_test.UnimportantKey": "Who Cares?"
+        // Define mockito mapping for cromwell metadata response
+        let metadata_mock_body = json!({
+          "outputs": {
+            "test_test.TestKey": "TestVal"
+          }
         });
-
+        let metadata_mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/123456789/metadata?includeKey=outputs",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(metadata_mock_body.to_string())
+        .expect(1)
+        .create();
         // Define mockito mapping for wdl
-        let wdl_mock = mockito::mock("GET", "/eval_software_params")
+        let wdl_mock = mockito::mock("GET", "/eval_no_software_params")
             .with_status(200)
             .with_body(
-                read_to_string("testdata/manager/test_runner/eval_wdl_software_params.wdl")
+                read_to_string("testdata/manager/test_runner/eval_wdl_no_software_params.wdl")
                     .unwrap(),
             )
             .expect(1)
@@ -1504,49 +4243,164 @@ mod tests {
             .with_status(201)
             .with_header("content_type", "application/json")
             .with_body(mock_response_body.to_string())
+            .expect(1)
             .create();
 
         let result = test_test_runner
-            .start_run_eval(&conn, &test_run, test_results.as_object().unwrap())
+            .rerun_run(&conn, &source_run, RerunModeEnum::EvalOnly, None, None)
             .await
             .unwrap();
 
+        metadata_mock.assert();
         wdl_mock.assert();
         cromwell_mock.assert();
 
+        assert_eq!(result.status, RunStatusEnum::EvalSubmitted);
+        assert!(result.test_cromwell_job_id.is_none());
         assert_eq!(
-            result.eval_cromwell_job_id.unwrap(),
-            "34958601-d114-4194-a7f7-9e41211ca2ce"
+            result.eval_cromwell_job_id,
+            Some("34958601-d114-4194-a7f7-9e41211ca2ce".to_string())
         );
+
+        let run_is_rerun = RunIsRerunData::find_by_run_id(&conn, result.run_id).unwrap();
+        assert_eq!(run_is_rerun.source_run_id, source_run.run_id);
+        assert_eq!(run_is_rerun.mode, RerunModeEnum::EvalOnly);
     }
 
     #[actix_rt::test]
-    async fn test_start_run_eval_missing_test_output() {
+    async fn test_rerun_run_eval_only_of_eval_only_rerun() {
         let conn = get_test_db_connection();
         let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
 
-        let test_template = insert_test_template_software_params(&conn);
-        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
-        let test_run =
-            insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+        let original_run = insert_test_run(&conn);
+        // An eval-only rerun of the original run, which doesn't have a test job of its own
+        let source_run = RunData::create(
+            &conn,
+            NewRun {
+                test_id: original_run.test_id,
+                name: String::from("Kevin's test run rerun"),
+                status: RunStatusEnum::Succeeded,
+                test_input: original_run.test_input.clone(),
+                test_options: original_run.test_options.clone(),
+                eval_input: original_run.eval_input.clone(),
+                eval_options: original_run.eval_options.clone(),
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: Some(String::from("10987654321")),
+                created_by: Some(String::from("Kevin@example.com")),
+                finished_at: Some(Utc::now().naive_utc()),
+            },
+        )
+        .unwrap();
+        RunIsRerunData::create(
+            &conn,
+            NewRunIsRerun {
+                run_id: source_run.run_id,
+                source_run_id: original_run.run_id,
+                mode: RerunModeEnum::EvalOnly,
+            },
+        )
+        .unwrap();
 
-        let test_results = json!({
-            "test_test.UnimportantKey": "Who Cares?"
+        // Define mockito mapping for cromwell metadata response for the original run's test job
+        let metadata_mock_body = json!({
+          "outputs": {
+            "test_test.TestKey": "TestVal"
+          }
+        });
+        let metadata_mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/123456789/metadata?includeKey=outputs",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(metadata_mock_body.to_string())
+        .expect(1)
+        .create();
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/eval_no_software_params")
+            .with_status(200)
+            .with_body(
+                read_to_string("testdata/manager/test_runner/eval_wdl_no_software_params.wdl")
+                    .unwrap(),
+            )
+            .expect(1)
+            .create();
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "34958601-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
         });
-
         let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
             .with_status(201)
             .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .expect(1)
+            .create();
+
+        let result = test_test_runner
+            .rerun_run(&conn, &source_run, RerunModeEnum::EvalOnly, None, None)
+            .await
+            .unwrap();
+
+        metadata_mock.assert();
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        assert_eq!(result.status, RunStatusEnum::EvalSubmitted);
+        assert!(result.test_cromwell_job_id.is_none());
+
+        let test_workflow_run = TestRunner::find_test_workflow_run(&conn, &result).unwrap();
+        assert_eq!(test_workflow_run.run_id, original_run.run_id);
+    }
+
+    #[actix_rt::test]
+    async fn test_rerun_run_not_finished() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        let test_template = insert_test_template_no_software_params(&conn);
+        let test_test = insert_test_test_with_template_id(&conn, test_template.template_id);
+        let source_run =
+            insert_test_run_with_test_id_and_status_test_submitted(&conn, test_test.test_id);
+
+        let cromwell_mock = mockito::mock("POST", mockito::Matcher::Any)
+            .with_status(201)
             .expect(0)
             .create();
 
         let result = test_test_runner
-            .start_run_eval(&conn, &test_run, test_results.as_object().unwrap())
+            .rerun_run(&conn, &source_run, RerunModeEnum::Full, None, None)
             .await;
 
         cromwell_mock.assert();
 
-        assert!(matches!(result, Err(Error::MissingOutputKey(_))));
+        assert!(matches!(result, Err(Error::RerunNotAllowed(_))));
+    }
+
+    #[actix_rt::test]
+    async fn test_rerun_run_eval_only_test_failed() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+
+        // A run whose test step failed won't have an eval job
+        let source_run = RunData {
+            status: RunStatusEnum::TestFailed,
+            eval_cromwell_job_id: None,
+            ..insert_test_run(&conn)
+        };
+
+        let cromwell_mock = mockito::mock("GET", mockito::Matcher::Any)
+            .with_status(200)
+            .expect(0)
+            .create();
+
+        let result = test_test_runner
+            .rerun_run(&conn, &source_run, RerunModeEnum::EvalOnly, None, None)
+            .await;
+
+        cromwell_mock.assert();
+
+        assert!(matches!(result, Err(Error::RerunNotAllowed(_))));
     }
 
     #[test]
@@ -1572,12 +4426,13 @@ mod tests {
 
     #[test]
     fn test_format_test_json_for_cromwell_success() {
+        let conn = get_test_db_connection();
         let test_test_runner: TestRunner = initialize_test_runner_with_registry_host();
 
         let test_json = json!({"test_workflow.test":"1","test_workflow.image":"image_build:example_project|1a4c5eb5fc4921b2642b6ded863894b3745a5dc7"});
 
         let formatted_json = test_test_runner
-            .format_test_json_for_cromwell(&test_json)
+            .format_test_json_for_cromwell(&conn, &test_json)
             .expect("Failed to format test json");
 
         let expected_json = json!({"test_workflow.test":"1","test_workflow.image":"https://example.com/example_project:1a4c5eb5fc4921b2642b6ded863894b3745a5dc7"});
@@ -1587,12 +4442,18 @@ mod tests {
 
     #[test]
     fn test_format_eval_json_for_cromwell_success() {
+        let conn = get_test_db_connection();
         let test_test_runner: TestRunner = initialize_test_runner_with_registry_host();
         let test_json = json!({"eval_workflow.test":"test_output:test_workflow.test","eval_workflow.image":"image_build:example_project|1a4c5eb5fc4921b2642b6ded863894b3745a5dc7"});
         let test_output = json!({"test_workflow.test":"2"});
 
         let formatted_json = test_test_runner
-            .format_eval_json_for_cromwell(&test_json, test_output.as_object().unwrap())
+            .format_eval_json_for_cromwell(
+                &conn,
+                &test_json,
+                test_output.as_object().unwrap(),
+                WorkflowLanguageEnum::Wdl,
+            )
             .expect("Failed to format test json");
 
         let expected_json = json!({"eval_workflow.test":"2","eval_workflow.image":"https://example.com/example_project:1a4c5eb5fc4921b2642b6ded863894b3745a5dc7"});
@@ -1600,6 +4461,126 @@ mod tests {
         assert_eq!(formatted_json, expected_json);
     }
 
+    #[actix_rt::test]
+    async fn test_format_eval_json_for_cromwell_cwl() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_with_registry_host();
+        let test_json = json!({
+            "in_file":"test_output:out_file",
+            "in_count":"test_output:#main/out-count",
+            "in_name":"test_output:main.out_name",
+            "in_literal":"not a test_output"
+        });
+        let test_output = json!({
+            "main.out_file":"gs://example/out_file.txt",
+            "main.out-count":3,
+            "main.out_name":"kevin"
+        });
+
+        let formatted_json = test_test_runner
+            .format_eval_json_for_cromwell(
+                &conn,
+                &test_json,
+                test_output.as_object().unwrap(),
+                WorkflowLanguageEnum::Cwl,
+            )
+            .expect("Failed to format test json");
+
+        let expected_json = json!({
+            "in_file":"gs://example/out_file.txt",
+            "in_count":3,
+            "in_name":"kevin",
+            "in_literal":"not a test_output"
+        });
+
+        assert_eq!(formatted_json, expected_json);
+    }
+
+    #[actix_rt::test]
+    async fn test_format_eval_json_for_cromwell_cwl_ambiguous_output() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_with_registry_host();
+        let test_json = json!({"in_file":"test_output:out_file"});
+        let test_output = json!({
+            "main.out_file":"gs://example/out_file.txt",
+            "subworkflow.out_file":"gs://example/other_out_file.txt"
+        });
+
+        let result = test_test_runner.format_eval_json_for_cromwell(
+            &conn,
+            &test_json,
+            test_output.as_object().unwrap(),
+            WorkflowLanguageEnum::Cwl,
+        );
+
+        assert!(matches!(result, Err(Error::MissingOutputKey(_))));
+    }
+
+    #[actix_rt::test]
+    async fn test_format_test_json_for_cromwell_secret() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_with_secrets(&conn);
+        let test_json =
+            json!({"test_workflow.test":"1","test_workflow.token":"secret:kevins_token"});
+
+        let formatted_json = test_test_runner
+            .format_test_json_for_cromwell(&conn, &test_json)
+            .expect("Failed to format test json");
+
+        let expected_json = json!({"test_workflow.test":"1","test_workflow.token":"hunter2"});
+
+        assert_eq!(formatted_json, expected_json);
+    }
+
+    #[actix_rt::test]
+    async fn test_format_eval_json_for_cromwell_secret() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_with_secrets(&conn);
+        let test_json = json!({"eval_workflow.test":"test_output:test_workflow.test","eval_workflow.token":"secret:kevins_token"});
+        let test_output = json!({"test_workflow.test":"2"});
+
+        let formatted_json = test_test_runner
+            .format_eval_json_for_cromwell(
+                &conn,
+                &test_json,
+                test_output.as_object().unwrap(),
+                WorkflowLanguageEnum::Wdl,
+            )
+            .expect("Failed to format test json");
+
+        let expected_json = json!({"eval_workflow.test":"2","eval_workflow.token":"hunter2"});
+
+        assert_eq!(formatted_json, expected_json);
+    }
+
+    #[actix_rt::test]
+    async fn test_format_test_json_for_cromwell_secret_not_found() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_with_secrets(&conn);
+        let test_json = json!({"test_workflow.token":"secret:not_kevins_token"});
+
+        let result = test_test_runner.format_test_json_for_cromwell(&conn, &test_json);
+
+        assert!(matches!(
+            result,
+            Err(Error::Secrets(secrets::Error::NotFound(_)))
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn test_format_test_json_for_cromwell_secrets_disabled() {
+        let conn = get_test_db_connection();
+        let test_test_runner: TestRunner = initialize_test_runner_without_registry_host();
+        let test_json = json!({"test_workflow.token":"secret:kevins_token"});
+
+        let result = test_test_runner.format_test_json_for_cromwell(&conn, &test_json);
+
+        assert!(matches!(
+            result,
+            Err(Error::Secrets(secrets::Error::NotConfigured(_)))
+        ));
+    }
+
     #[test]
     fn test_check_if_run_with_name_exists_true() {
         let conn = get_test_db_connection();
@@ -1624,11 +4605,12 @@ mod tests {
 
     #[test]
     fn test_format_test_json_for_cromwell_failure() {
+        let conn = get_test_db_connection();
         let test_test_runner: TestRunner = initialize_test_runner_with_registry_host();
 
         let test_json = json!(["test", "1"]);
 
-        let formatted_json = test_test_runner.format_test_json_for_cromwell(&test_json);
+        let formatted_json = test_test_runner.format_test_json_for_cromwell(&conn, &test_json);
 
         assert!(matches!(formatted_json, Err(Error::Json)));
     }
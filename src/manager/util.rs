@@ -1,52 +1,122 @@
 //! Contains utility functions shared by multiple of the modules within the `manager` module
 
-use crate::requests::cromwell_requests;
-use crate::requests::cromwell_requests::{
-    CromwellClient, CromwellRequestError, WorkflowIdAndStatus, WorkflowTypeEnum,
-};
-use std::path::{Path, PathBuf};
+use crate::custom_sql_types::WorkflowLanguageEnum;
+use crate::requests::cromwell_requests::{CromwellRequestError, WorkflowIdAndStatus};
+use crate::requests::execution_backend::{ExecutionBackend, WorkflowSubmission};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::path::Path;
 use std::sync::mpsc;
 use std::time::Duration;
+use uuid::Uuid;
 
-/// Sends a request to cromwell to start a job from a WDL file
+/// Keys for the labels CARROT attaches to the workflows it submits to cromwell
+pub const INSTANCE_LABEL: &str = "carrot-instance";
+pub const STAGE_LABEL: &str = "carrot-stage";
+pub const RUN_ID_LABEL: &str = "carrot-run-id";
+pub const TEST_ID_LABEL: &str = "carrot-test-id";
+pub const SOFTWARE_BUILD_ID_LABEL: &str = "carrot-software-build-id";
+pub const REPORT_ID_LABEL: &str = "carrot-report-id";
+
+/// The stages of processing for which CARROT submits workflows to cromwell
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WorkflowStage {
+    Test,
+    Eval,
+    Build,
+    Report,
+}
+
+impl fmt::Display for WorkflowStage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WorkflowStage::Test => write!(f, "test"),
+            WorkflowStage::Eval => write!(f, "eval"),
+            WorkflowStage::Build => write!(f, "build"),
+            WorkflowStage::Report => write!(f, "report"),
+        }
+    }
+}
+
+impl std::str::FromStr for WorkflowStage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "test" => Ok(WorkflowStage::Test),
+            "eval" => Ok(WorkflowStage::Eval),
+            "build" => Ok(WorkflowStage::Build),
+            "report" => Ok(WorkflowStage::Report),
+            _ => Err(format!("Invalid workflow stage: {}", s)),
+        }
+    }
+}
+
+/// The identifiers of the CARROT records a workflow is being submitted for, to be attached to the
+/// workflow as labels so it can be traced back to them
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkflowLabels {
+    pub stage: WorkflowStage,
+    pub run_id: Option<Uuid>,
+    pub test_id: Option<Uuid>,
+    pub software_build_id: Option<Uuid>,
+    pub report_id: Option<Uuid>,
+}
+
+impl WorkflowLabels {
+    /// Returns these labels, along with a label identifying the CARROT instance as
+    /// `instance_name`, as a json object in the format cromwell expects
+    pub fn to_json(&self, instance_name: &str) -> Value {
+        let mut labels = Map::new();
+        labels.insert(String::from(INSTANCE_LABEL), Value::from(instance_name));
+        labels.insert(
+            String::from(STAGE_LABEL),
+            Value::from(self.stage.to_string()),
+        );
+        let id_labels = [
+            (RUN_ID_LABEL, self.run_id),
+            (TEST_ID_LABEL, self.test_id),
+            (SOFTWARE_BUILD_ID_LABEL, self.software_build_id),
+            (REPORT_ID_LABEL, self.report_id),
+        ];
+        for (key, id) in id_labels.iter() {
+            if let Some(id) = id {
+                labels.insert(String::from(*key), Value::from(id.to_string()));
+            }
+        }
+        Value::Object(labels)
+    }
+}
+
+/// Sends a request to the execution backend to start a job from a WDL file
 ///
-/// Sends a request to Cromwell specifying the WDL at `wdl_file_path` for the workflow and the
-/// json at `json_file_path` for the inputs.  Returns the response as a WorkflowIdAndType or an
-/// error if there is some issue starting the job
+/// Sends a request to the backend specified by `execution_client` with the WDL at `wdl_file_path`
+/// for the workflow and the json at `json_file_path` for the inputs.  The workflow is submitted as
+/// `workflow_language` (i.e. the WDL file may actually be a CWL file).  The workflow is labeled with
+/// the identifiers in `labels` and the name of the CARROT instance configured for
+/// `execution_client`.  Returns the response as a WorkflowIdAndType or an error if there is some
+/// issue starting the job
 pub async fn start_job_from_file(
-    cromwell_client: &CromwellClient,
+    execution_client: &dyn ExecutionBackend,
     wdl_file_path: &Path,
     wdl_deps_file_path: Option<&Path>,
     inputs_file_path: &Path,
     options_file_path: Option<&Path>,
+    workflow_language: WorkflowLanguageEnum,
+    labels: &WorkflowLabels,
 ) -> Result<WorkflowIdAndStatus, CromwellRequestError> {
-    let options_file_path = match options_file_path {
-        Some(file_path) => Some(PathBuf::from(file_path)),
-        None => None,
-    };
-    let wdl_deps_file_path: Option<PathBuf> = match wdl_deps_file_path {
-        Some(file_path) => Some(PathBuf::from(file_path)),
-        None => None,
-    };
-    // Build request parameters
-    let cromwell_params = cromwell_requests::StartJobParams {
-        labels: None,
-        workflow_dependencies: wdl_deps_file_path,
-        workflow_inputs: Some(PathBuf::from(inputs_file_path)),
-        workflow_inputs_2: None,
-        workflow_inputs_3: None,
-        workflow_inputs_4: None,
-        workflow_inputs_5: None,
-        workflow_on_hold: None,
-        workflow_options: options_file_path,
-        workflow_root: None,
-        workflow_source: Some(PathBuf::from(wdl_file_path)),
-        workflow_type: Some(WorkflowTypeEnum::WDL),
-        workflow_type_version: None,
-        workflow_url: None,
-    };
+    let labels = labels.to_json(execution_client.instance_name());
     // Submit request to start job
-    cromwell_client.start_job(cromwell_params).await
+    execution_client
+        .submit_workflow(WorkflowSubmission {
+            wdl_file_path,
+            wdl_deps_file_path,
+            inputs_file_path,
+            options_file_path,
+            workflow_language,
+            labels: &labels,
+        })
+        .await
 }
 
 /// Returns an image URL generated from `IMAGE_REGISTRY_HOST`, `software_name`, and `commit_hash`
@@ -84,19 +154,23 @@ pub fn check_for_terminate_message_with_timeout(
 
 #[cfg(test)]
 mod tests {
-    use crate::manager::util::start_job_from_file;
+    use crate::custom_sql_types::WorkflowLanguageEnum;
+    use crate::manager::util::{start_job_from_file, WorkflowLabels, WorkflowStage};
     use crate::requests::cromwell_requests::CromwellClient;
     use crate::util::temp_storage;
     use actix_web::client::Client;
+    use mockito::Matcher;
     use serde_json::json;
     use serde_json::Value;
     use std::path::PathBuf;
+    use std::str::FromStr;
+    use uuid::Uuid;
 
     #[actix_rt::test]
     async fn test_start_job() {
         // Get client
-        let cromwell_client: CromwellClient =
-            CromwellClient::new(Client::default(), &mockito::server_url());
+        let execution_client =
+            CromwellClient::new(Client::default(), &mockito::server_url(), "carrot", None);
         // Create job data with simple test workflow
         let test_path = PathBuf::from("testdata/routes/template/valid_wdl_with_deps.wdl");
         let test_deps_path = Some(PathBuf::from("testdata/routes/template/valid_wdl_deps.zip"));
@@ -117,18 +191,34 @@ mod tests {
           "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
           "status": "Submitted"
         });
+        // Make labels for the workflow
+        let test_run_id = Uuid::new_v4();
+        let test_labels = WorkflowLabels {
+            stage: WorkflowStage::Test,
+            run_id: Some(test_run_id),
+            test_id: Some(Uuid::new_v4()),
+            software_build_id: None,
+            report_id: None,
+        };
         let mock = mockito::mock("POST", "/api/workflows/v1")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(String::from("\"carrot-instance\":\"carrot\"")),
+                Matcher::Regex(format!("\"carrot-run-id\":\"{}\"", test_run_id)),
+                Matcher::Regex(String::from("\"carrot-stage\":\"test\"")),
+            ]))
             .with_status(201)
             .with_header("content_type", "application/json")
             .with_body(mock_response_body.to_string())
             .create();
 
         let response = start_job_from_file(
-            &cromwell_client,
+            &execution_client,
             test_path.as_path(),
             test_deps_path.as_deref(),
             test_json_file.path(),
             Some(test_json_file.path()),
+            WorkflowLanguageEnum::Wdl,
+            &test_labels,
         )
         .await
         .unwrap();
@@ -138,4 +228,42 @@ mod tests {
         assert_eq!(response.status, String::from("Submitted"));
         assert_eq!(response.id, "53709600-d114-4194-a7f7-9e41211ca2ce");
     }
+
+    #[test]
+    fn test_workflow_labels_to_json() {
+        let test_build_id = Uuid::new_v4();
+        let test_labels = WorkflowLabels {
+            stage: WorkflowStage::Build,
+            run_id: None,
+            test_id: None,
+            software_build_id: Some(test_build_id),
+            report_id: None,
+        };
+
+        let labels_json = test_labels.to_json("test-instance");
+
+        assert_eq!(
+            labels_json,
+            json!({
+                "carrot-instance": "test-instance",
+                "carrot-stage": "build",
+                "carrot-software-build-id": test_build_id.to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_workflow_stage_from_str() {
+        for stage in [
+            WorkflowStage::Test,
+            WorkflowStage::Eval,
+            WorkflowStage::Build,
+            WorkflowStage::Report,
+        ]
+        .iter()
+        {
+            assert_eq!(WorkflowStage::from_str(&stage.to_string()).unwrap(), *stage);
+        }
+        assert!(WorkflowStage::from_str("invalid").is_err());
+    }
 }
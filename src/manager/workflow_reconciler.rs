@@ -0,0 +1,673 @@
+//! Defines functionality for finding (and optionally aborting) orphaned cromwell workflows
+//!
+//! CARROT labels every workflow it submits to cromwell with its instance name, the stage of
+//! processing the workflow is for (test, eval, build, or report), and the ids of the records it is
+//! being run for.  If CARROT fails to record a workflow's id after submitting it, or a record is
+//! marked as finished while its workflow is still going, that workflow will keep running (and
+//! costing money) without CARROT ever checking on it.  The reconciler queries each of the
+//! configured cromwell servers for the active workflows labeled with this instance's name and
+//! reports the ones that don't have a matching record in the DB or whose record is already in a
+//! terminal state
+
+use crate::custom_sql_types::{
+    BuildStatusEnum, ReportStatusEnum, RunStatusEnum, RUN_FAILURE_STATUSES,
+};
+use crate::manager::server_router::{self, ServerRouter};
+use crate::manager::util::{
+    WorkflowStage, INSTANCE_LABEL, REPORT_ID_LABEL, RUN_ID_LABEL, SOFTWARE_BUILD_ID_LABEL,
+    STAGE_LABEL,
+};
+use crate::models::run::RunData;
+use crate::models::run_report::RunReportData;
+use crate::models::software_build::SoftwareBuildData;
+use crate::requests::cromwell_requests::{
+    CromwellClient, CromwellRequestError, WorkflowQueryParams,
+};
+use diesel::PgConnection;
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+/// The number of workflows to request from cromwell per page when querying for active workflows
+const QUERY_PAGE_SIZE: u32 = 100;
+
+/// The cromwell statuses for workflows that have not finished yet
+const ACTIVE_WORKFLOW_STATUSES: [&str; 3] = ["Submitted", "Running", "On Hold"];
+
+/// Struct for reconciling the workflows running in cromwell with the records in the DB
+#[derive(Clone)]
+pub struct WorkflowReconciler {
+    server_router: ServerRouter,
+}
+
+/// The reason a workflow was determined to be orphaned
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanReason {
+    /// There is no record in the DB that the workflow's labels match to, or the record that they
+    /// match to has a different workflow id
+    NoMatchingRecord,
+    /// The record the workflow belongs to has already finished
+    RecordAlreadyTerminal,
+}
+
+/// An active workflow in cromwell that is not being tracked by an unfinished record in the DB
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct OrphanedWorkflow {
+    pub cromwell_server: String,
+    pub cromwell_job_id: String,
+    pub stage: Option<String>,
+    pub labels: Map<String, Value>,
+    pub reason: OrphanReason,
+    pub aborted: bool,
+}
+
+/// The results of a reconciliation: the number of active workflows that were checked and the ones
+/// among them that were found to be orphaned
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct ReconciliationReport {
+    pub checked: usize,
+    pub orphaned: Vec<OrphanedWorkflow>,
+}
+
+/// Error type for possible errors returned by reconciling workflows
+#[derive(Debug)]
+pub enum Error {
+    Cromwell(CromwellRequestError),
+    DB(diesel::result::Error),
+    Parse(String),
+    Server(server_router::Error),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Cromwell(e) => write!(f, "Error Cromwell {}", e),
+            Error::DB(e) => write!(f, "Error DB {}", e),
+            Error::Parse(e) => write!(f, "Error Parse {}", e),
+            Error::Server(e) => write!(f, "Error Server {}", e),
+        }
+    }
+}
+
+impl From<CromwellRequestError> for Error {
+    fn from(e: CromwellRequestError) -> Error {
+        Error::Cromwell(e)
+    }
+}
+
+impl From<diesel::result::Error> for Error {
+    fn from(e: diesel::result::Error) -> Error {
+        Error::DB(e)
+    }
+}
+
+impl From<server_router::Error> for Error {
+    fn from(e: server_router::Error) -> Error {
+        Error::Server(e)
+    }
+}
+
+impl WorkflowReconciler {
+    /// Creates a new WorkflowReconciler that will use `server_router` for querying and aborting
+    /// workflows on each of the configured cromwell servers
+    pub fn new(server_router: ServerRouter) -> WorkflowReconciler {
+        WorkflowReconciler { server_router }
+    }
+
+    /// Queries each cromwell server for the active workflows labeled with this CARROT instance's
+    /// name and checks each against the records in the DB (using `conn`).  Workflows that do not
+    /// have a matching record, or whose record is already in a terminal state, are included in the
+    /// returned report and, if `abort` is true, aborted.  Failures to abort are logged and
+    /// reflected in the `aborted` field for the workflow.  Returns an error if querying cromwell or
+    /// the DB fails
+    pub async fn reconcile(
+        &self,
+        conn: &PgConnection,
+        abort: bool,
+    ) -> Result<ReconciliationReport, Error> {
+        let mut checked: usize = 0;
+        let mut orphaned: Vec<OrphanedWorkflow> = Vec::new();
+        for cromwell_server in self.server_router.server_names() {
+            let cromwell_client = self.server_router.cromwell_client(Some(cromwell_server))?;
+            // Get all the active workflows first so aborting them doesn't mess up the paging
+            let workflows = WorkflowReconciler::get_active_workflows(cromwell_client).await?;
+            checked += workflows.len();
+            for (cromwell_job_id, labels) in &workflows {
+                let reason =
+                    match WorkflowReconciler::check_workflow(conn, cromwell_job_id, labels)? {
+                        Some(reason) => reason,
+                        None => continue,
+                    };
+                let aborted = if abort {
+                    match cromwell_client.abort_job(cromwell_job_id).await {
+                        Ok(_) => {
+                            info!(
+                                "Aborted orphaned workflow {} on {}",
+                                cromwell_job_id, cromwell_server
+                            );
+                            true
+                        }
+                        Err(e) => {
+                            error!(
+                                "Failed to abort orphaned workflow {} on {} due to: {}",
+                                cromwell_job_id, cromwell_server, e
+                            );
+                            false
+                        }
+                    }
+                } else {
+                    false
+                };
+                orphaned.push(OrphanedWorkflow {
+                    cromwell_server: cromwell_server.clone(),
+                    cromwell_job_id: cromwell_job_id.clone(),
+                    stage: labels
+                        .get(STAGE_LABEL)
+                        .and_then(Value::as_str)
+                        .map(String::from),
+                    labels: labels.clone(),
+                    reason,
+                    aborted,
+                });
+            }
+        }
+        Ok(ReconciliationReport { checked, orphaned })
+    }
+
+    /// Pages through the results of querying cromwell with `cromwell_client` for the active
+    /// workflows labeled with this CARROT instance's name and returns the id and labels for each
+    async fn get_active_workflows(
+        cromwell_client: &CromwellClient,
+    ) -> Result<Vec<(String, Map<String, Value>)>, Error> {
+        let mut workflows: Vec<(String, Map<String, Value>)> = Vec::new();
+        let mut page: u32 = 1;
+        loop {
+            let params = WorkflowQueryParams {
+                status: Some(
+                    ACTIVE_WORKFLOW_STATUSES
+                        .iter()
+                        .map(|status| String::from(*status))
+                        .collect(),
+                ),
+                label: Some(vec![format!(
+                    "{}:{}",
+                    INSTANCE_LABEL,
+                    cromwell_client.instance_name()
+                )]),
+                include_subworkflows: Some(false),
+                additional_query_result_fields: Some(vec![String::from("labels")]),
+                page: Some(page),
+                page_size: Some(QUERY_PAGE_SIZE),
+                ..Default::default()
+            };
+            let response = cromwell_client.query_workflows(&params).await?;
+            let results = match response.get("results").and_then(Value::as_array) {
+                Some(results) => results,
+                None => {
+                    return Err(Error::Parse(format!(
+                        "Cromwell query response missing results array: {}",
+                        response
+                    )))
+                }
+            };
+            for result in results {
+                let id = match result.get("id").and_then(Value::as_str) {
+                    Some(id) => String::from(id),
+                    None => {
+                        return Err(Error::Parse(format!(
+                            "Cromwell query result missing id: {}",
+                            result
+                        )))
+                    }
+                };
+                let labels = match result.get("labels").and_then(Value::as_object) {
+                    Some(labels) => labels.clone(),
+                    None => Map::new(),
+                };
+                workflows.push((id, labels));
+            }
+            // Stop if this was the last page
+            let total_results_count = response
+                .get("totalResultsCount")
+                .and_then(Value::as_u64)
+                .unwrap_or(0);
+            if results.is_empty() || u64::from(page * QUERY_PAGE_SIZE) >= total_results_count {
+                break;
+            }
+            page += 1;
+        }
+        Ok(workflows)
+    }
+
+    /// Checks the record identified by `labels` for the workflow with id `cromwell_job_id`, using
+    /// `conn`.  Returns the reason the workflow is orphaned, or None if it belongs to a record that
+    /// is still unfinished.  An unfinished record that doesn't have a job id for the workflow's
+    /// stage yet counts as the workflow's record, since CARROT may have submitted the workflow and
+    /// not recorded its id yet (or may have stopped before it could, in which case the status
+    /// manager will pick the workflow up when it recovers the stranded record)
+    fn check_workflow(
+        conn: &PgConnection,
+        cromwell_job_id: &str,
+        labels: &Map<String, Value>,
+    ) -> Result<Option<OrphanReason>, Error> {
+        // If we can't tell what the workflow is for, there's no record we can match it to
+        let stage = match labels
+            .get(STAGE_LABEL)
+            .and_then(Value::as_str)
+            .map(WorkflowStage::from_str)
+        {
+            Some(Ok(stage)) => stage,
+            _ => return Ok(Some(OrphanReason::NoMatchingRecord)),
+        };
+        let result = match stage {
+            WorkflowStage::Test | WorkflowStage::Eval => {
+                let run_id = match get_id_label(labels, RUN_ID_LABEL) {
+                    Some(run_id) => run_id,
+                    None => return Ok(Some(OrphanReason::NoMatchingRecord)),
+                };
+                RunData::find_by_id(conn, run_id).map(|run| {
+                    let job_id = match stage {
+                        WorkflowStage::Test => run.test_cromwell_job_id,
+                        _ => run.eval_cromwell_job_id,
+                    };
+                    let is_terminal = run.status == RunStatusEnum::Succeeded
+                        || RUN_FAILURE_STATUSES.contains(&run.status);
+                    (job_id, is_terminal)
+                })
+            }
+            WorkflowStage::Build => {
+                let software_build_id = match get_id_label(labels, SOFTWARE_BUILD_ID_LABEL) {
+                    Some(software_build_id) => software_build_id,
+                    None => return Ok(Some(OrphanReason::NoMatchingRecord)),
+                };
+                SoftwareBuildData::find_by_id(conn, software_build_id).map(|build| {
+                    let is_terminal = matches!(
+                        build.status,
+                        BuildStatusEnum::Succeeded
+                            | BuildStatusEnum::Failed
+                            | BuildStatusEnum::Aborted
+                            | BuildStatusEnum::Expired
+                    );
+                    (build.build_job_id, is_terminal)
+                })
+            }
+            WorkflowStage::Report => {
+                let (run_id, report_id) = match (
+                    get_id_label(labels, RUN_ID_LABEL),
+                    get_id_label(labels, REPORT_ID_LABEL),
+                ) {
+                    (Some(run_id), Some(report_id)) => (run_id, report_id),
+                    _ => return Ok(Some(OrphanReason::NoMatchingRecord)),
+                };
+                RunReportData::find_by_run_and_report(conn, run_id, report_id).map(|run_report| {
+                    let is_terminal = matches!(
+                        run_report.status,
+                        ReportStatusEnum::Succeeded
+                            | ReportStatusEnum::Failed
+                            | ReportStatusEnum::Aborted
+                            | ReportStatusEnum::Expired
+                    );
+                    (run_report.cromwell_job_id, is_terminal)
+                })
+            }
+        };
+        match result {
+            // If the record is tracking a different workflow, then this one is orphaned
+            Ok((Some(job_id), _)) if job_id != cromwell_job_id => {
+                Ok(Some(OrphanReason::NoMatchingRecord))
+            }
+            Ok((_, true)) => Ok(Some(OrphanReason::RecordAlreadyTerminal)),
+            Ok((_, false)) => Ok(None),
+            Err(diesel::NotFound) => Ok(Some(OrphanReason::NoMatchingRecord)),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Returns the value of the label with key `key` in `labels` parsed as a Uuid, or None if it is
+/// missing or not a valid Uuid
+fn get_id_label(labels: &Map<String, Value>, key: &str) -> Option<Uuid> {
+    labels
+        .get(key)
+        .and_then(Value::as_str)
+        .and_then(|id| Uuid::parse_str(id).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::manager::server_router::ServerRouter;
+    use crate::manager::workflow_reconciler::{Error, OrphanReason, WorkflowReconciler};
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{NewRun, RunData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::unit_test_util::{get_test_db_connection, load_default_config};
+    use actix_web::client::Client;
+    use diesel::PgConnection;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn create_test_workflow_reconciler() -> WorkflowReconciler {
+        WorkflowReconciler::new(ServerRouter::new(Client::default(), &load_default_config()))
+    }
+
+    fn insert_test_run(
+        conn: &PgConnection,
+        status: RunStatusEnum,
+        test_cromwell_job_id: Option<String>,
+        eval_cromwell_job_id: Option<String>,
+    ) -> RunData {
+        let new_pipeline = NewPipeline {
+            name: format!("Kevin's Pipeline {}", Uuid::new_v4()),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: format!("Kevin's Template {}", Uuid::new_v4()),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing")),
+            test_wdl: String::from("testtest"),
+            test_wdl_dependencies: None,
+            eval_wdl: String::from("evaltest"),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: format!("Kevin's Test {}", Uuid::new_v4()),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: None,
+            test_option_defaults: None,
+            eval_input_defaults: None,
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        let new_run = NewRun {
+            test_id: test.test_id,
+            name: format!("Kevin's test run {}", Uuid::new_v4()),
+            status,
+            test_input: json!({}),
+            test_options: None,
+            eval_input: json!({}),
+            eval_options: None,
+            test_cromwell_job_id,
+            eval_cromwell_job_id,
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: None,
+        };
+
+        RunData::create(conn, new_run).expect("Failed inserting test run")
+    }
+
+    #[actix_rt::test]
+    async fn test_reconcile_report_only() {
+        let conn = get_test_db_connection();
+        let test_reconciler = create_test_workflow_reconciler();
+
+        let running_run = insert_test_run(
+            &conn,
+            RunStatusEnum::TestRunning,
+            Some(String::from("11111111-1111-1111-1111-111111111111")),
+            None,
+        );
+        let finished_run = insert_test_run(
+            &conn,
+            RunStatusEnum::Succeeded,
+            Some(String::from("22222222-2222-2222-2222-222222222221")),
+            Some(String::from("22222222-2222-2222-2222-222222222222")),
+        );
+        let other_job_run = insert_test_run(
+            &conn,
+            RunStatusEnum::TestRunning,
+            Some(String::from("33333333-3333-3333-3333-333333333330")),
+            None,
+        );
+
+        let mock_response_body = json!({
+            "results": [
+                {
+                    "id": "11111111-1111-1111-1111-111111111111",
+                    "status": "Running",
+                    "labels": {
+                        "carrot-instance": "carrot",
+                        "carrot-stage": "test",
+                        "carrot-run-id": running_run.run_id.to_string()
+                    }
+                },
+                {
+                    "id": "22222222-2222-2222-2222-222222222222",
+                    "status": "Running",
+                    "labels": {
+                        "carrot-instance": "carrot",
+                        "carrot-stage": "eval",
+                        "carrot-run-id": finished_run.run_id.to_string()
+                    }
+                },
+                {
+                    "id": "33333333-3333-3333-3333-333333333333",
+                    "status": "Running",
+                    "labels": {
+                        "carrot-instance": "carrot",
+                        "carrot-stage": "test",
+                        "carrot-run-id": other_job_run.run_id.to_string()
+                    }
+                },
+                {
+                    "id": "44444444-4444-4444-4444-444444444444",
+                    "status": "Submitted",
+                    "labels": {
+                        "carrot-instance": "carrot",
+                        "carrot-stage": "build",
+                        "carrot-software-build-id": Uuid::new_v4().to_string()
+                    }
+                },
+                {
+                    "id": "55555555-5555-5555-5555-555555555555",
+                    "status": "On Hold",
+                    "labels": {
+                        "carrot-instance": "carrot"
+                    }
+                }
+            ],
+            "totalResultsCount": 5
+        });
+        let query_mock = mockito::mock("POST", "/api/workflows/v1/query")
+            .match_body(mockito::Matcher::Json(json!([
+                {"status": "Submitted"},
+                {"status": "Running"},
+                {"status": "On Hold"},
+                {"label": "carrot-instance:carrot"},
+                {"includeSubworkflows": "false"},
+                {"additionalQueryResultFields": "labels"},
+                {"page": "1"},
+                {"pageSize": "100"}
+            ])))
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .create();
+        let abort_mock = mockito::mock(
+            "POST",
+            mockito::Matcher::Regex(String::from("/api/workflows/v1/.*/abort")),
+        )
+        .expect(0)
+        .create();
+
+        let report = test_reconciler.reconcile(&conn, false).await.unwrap();
+
+        query_mock.assert();
+        abort_mock.assert();
+
+        assert_eq!(report.checked, 5);
+        let orphans: Vec<(&str, OrphanReason, bool)> = report
+            .orphaned
+            .iter()
+            .map(|orphan| {
+                (
+                    orphan.cromwell_job_id.as_str(),
+                    orphan.reason,
+                    orphan.aborted,
+                )
+            })
+            .collect();
+        assert_eq!(
+            orphans,
+            vec![
+                (
+                    "22222222-2222-2222-2222-222222222222",
+                    OrphanReason::RecordAlreadyTerminal,
+                    false
+                ),
+                (
+                    "33333333-3333-3333-3333-333333333333",
+                    OrphanReason::NoMatchingRecord,
+                    false
+                ),
+                (
+                    "44444444-4444-4444-4444-444444444444",
+                    OrphanReason::NoMatchingRecord,
+                    false
+                ),
+                (
+                    "55555555-5555-5555-5555-555555555555",
+                    OrphanReason::NoMatchingRecord,
+                    false
+                ),
+            ]
+        );
+        assert_eq!(report.orphaned[0].stage, Some(String::from("eval")));
+        assert_eq!(report.orphaned[3].stage, None);
+    }
+
+    #[test]
+    fn test_check_workflow_job_id_not_recorded() {
+        let conn = get_test_db_connection();
+
+        let created_run = insert_test_run(&conn, RunStatusEnum::Created, None, None);
+        let failed_run = insert_test_run(&conn, RunStatusEnum::CarrotFailed, None, None);
+        let labels_for_run = |run: &RunData| {
+            json!({
+                "carrot-instance": "carrot",
+                "carrot-stage": "test",
+                "carrot-run-id": run.run_id.to_string()
+            })
+            .as_object()
+            .unwrap()
+            .to_owned()
+        };
+
+        // The run may have been submitted without its job id having been recorded yet, so the
+        // workflow isn't orphaned
+        let created_result = WorkflowReconciler::check_workflow(
+            &conn,
+            "11111111-1111-1111-1111-111111111111",
+            &labels_for_run(&created_run),
+        )
+        .unwrap();
+        assert_eq!(created_result, None);
+        // But if the run has already finished, it is
+        let failed_result = WorkflowReconciler::check_workflow(
+            &conn,
+            "22222222-2222-2222-2222-222222222222",
+            &labels_for_run(&failed_run),
+        )
+        .unwrap();
+        assert_eq!(failed_result, Some(OrphanReason::RecordAlreadyTerminal));
+    }
+
+    #[actix_rt::test]
+    async fn test_reconcile_abort() {
+        let conn = get_test_db_connection();
+        let test_reconciler = create_test_workflow_reconciler();
+
+        let mock_response_body = json!({
+            "results": [
+                {
+                    "id": "66666666-6666-6666-6666-666666666666",
+                    "status": "Running",
+                    "labels": {
+                        "carrot-instance": "carrot",
+                        "carrot-stage": "test",
+                        "carrot-run-id": Uuid::new_v4().to_string()
+                    }
+                }
+            ],
+            "totalResultsCount": 1
+        });
+        let query_mock = mockito::mock("POST", "/api/workflows/v1/query")
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .create();
+        let abort_mock = mockito::mock(
+            "POST",
+            "/api/workflows/v1/66666666-6666-6666-6666-666666666666/abort",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(
+            json!({
+                "id": "66666666-6666-6666-6666-666666666666",
+                "status": "Aborting"
+            })
+            .to_string(),
+        )
+        .create();
+
+        let report = test_reconciler.reconcile(&conn, true).await.unwrap();
+
+        query_mock.assert();
+        abort_mock.assert();
+
+        assert_eq!(report.checked, 1);
+        assert_eq!(report.orphaned.len(), 1);
+        assert_eq!(report.orphaned[0].reason, OrphanReason::NoMatchingRecord);
+        assert!(report.orphaned[0].aborted);
+    }
+
+    #[actix_rt::test]
+    async fn test_reconcile_cromwell_failure() {
+        let conn = get_test_db_connection();
+        let test_reconciler = create_test_workflow_reconciler();
+
+        let query_mock = mockito::mock("POST", "/api/workflows/v1/query")
+            .with_status(500)
+            .with_header("content_type", "application/json")
+            .with_body(json!({"status": "error", "message": "Oh no"}).to_string())
+            .create();
+
+        let result = test_reconciler.reconcile(&conn, false).await;
+
+        query_mock.assert();
+
+        assert!(matches!(result, Err(Error::Cromwell(_))));
+    }
+}
@@ -5,11 +5,19 @@ pub mod pipeline;
 pub mod report;
 pub mod result;
 pub mod run;
+pub mod run_call;
 pub mod run_error;
+pub mod run_group;
+pub mod run_in_group;
+pub mod run_input_source;
 pub mod run_is_from_github;
+pub mod run_is_from_schedule;
+pub mod run_is_rerun;
 pub mod run_report;
 pub mod run_result;
 pub mod run_software_version;
+pub mod run_start_attempt;
+pub mod secret;
 pub mod software;
 pub mod software_build;
 pub mod software_version;
@@ -18,6 +26,7 @@ pub mod template;
 pub mod template_report;
 pub mod template_result;
 pub mod test;
+pub mod test_schedule;
 pub mod wdl_hash;
 
 // Utility modules only meant to be used within this module
@@ -9,6 +9,7 @@ use crate::util;
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 
 /// Mapping to a pipeline as it exists in the PIPELINE table in the database.
@@ -21,6 +22,9 @@ pub struct PipelineData {
     pub description: Option<String>,
     pub created_at: NaiveDateTime,
     pub created_by: Option<String>,
+    pub max_concurrent_runs: Option<i32>,
+    pub cromwell_server: Option<String>,
+    pub workflow_option_defaults: Option<Value>,
 }
 
 /// Represents all possible parameters for a query of the PIPELINE table
@@ -43,7 +47,8 @@ pub struct PipelineQuery {
 
 /// A new pipeline to be inserted into the DB
 ///
-/// name is a required field, but description and created_by are not, so can be filled with `None`
+/// name is a required field, but description, created_by, max_concurrent_runs, cromwell_server,
+/// and workflow_option_defaults are not, so can be filled with `None`
 /// pipeline_id and created_at are populated automatically by the DB
 #[derive(Deserialize, Insertable, Serialize)]
 #[table_name = "pipeline"]
@@ -51,16 +56,23 @@ pub struct NewPipeline {
     pub name: String,
     pub description: Option<String>,
     pub created_by: Option<String>,
+    pub max_concurrent_runs: Option<i32>,
+    pub cromwell_server: Option<String>,
+    pub workflow_option_defaults: Option<Value>,
 }
 
 /// Represents fields to change when updating a pipeline
 ///
-/// Only name and description can be modified after the pipeline has been created
+/// Only name, description, max_concurrent_runs, cromwell_server, and workflow_option_defaults can
+/// be modified after the pipeline has been created
 #[derive(Deserialize, Serialize, AsChangeset, Debug)]
 #[table_name = "pipeline"]
 pub struct PipelineChangeset {
     pub name: Option<String>,
     pub description: Option<String>,
+    pub max_concurrent_runs: Option<i32>,
+    pub cromwell_server: Option<String>,
+    pub workflow_option_defaults: Option<Value>,
 }
 
 impl PipelineData {
@@ -213,6 +225,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline")
@@ -228,6 +243,9 @@ mod tests {
             eval_wdl: String::from("evalevaleval"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(conn, new_template).expect("Failed inserting test template")
@@ -240,6 +258,9 @@ mod tests {
             name: String::from("Name1"),
             description: Some(String::from("Description4")),
             created_by: Some(String::from("Test@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         pipelines.push(
@@ -250,6 +271,9 @@ mod tests {
             name: String::from("Name2"),
             description: Some(String::from("Description3")),
             created_by: Some(String::from("Test@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         pipelines.push(
@@ -260,6 +284,9 @@ mod tests {
             name: String::from("Name4"),
             description: Some(String::from("Description3")),
             created_by: Some(String::from("Test@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         pipelines.push(
@@ -484,6 +511,9 @@ mod tests {
             name: test_pipeline.name,
             description: test_pipeline.description,
             created_by: test_pipeline.created_by,
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let new_pipeline = PipelineData::create(&conn, copy_pipeline);
@@ -506,6 +536,9 @@ mod tests {
         let changes = PipelineChangeset {
             name: Some(String::from("TestTestTestTest")),
             description: Some(String::from("TESTTESTTESTTEST")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let updated_pipeline = PipelineData::update(&conn, test_pipeline.pipeline_id, changes)
@@ -527,6 +560,9 @@ mod tests {
         let changes = PipelineChangeset {
             name: Some(test_pipelines[0].name.clone()),
             description: None,
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let updated_pipeline = PipelineData::update(&conn, test_pipelines[1].pipeline_id, changes);
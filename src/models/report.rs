@@ -333,6 +333,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -347,6 +350,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -361,6 +367,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let test = TestData::create(conn, new_test).expect("Failed inserting test test");
@@ -452,6 +462,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
         };
 
         RunReportData::create(conn, new_run_report).expect("Failed inserting test run_report")
@@ -479,6 +490,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: None,
+            cromwell_server: None,
         };
 
         RunReportData::create(conn, new_run_report).expect("Failed inserting test run_report")
@@ -80,6 +80,19 @@ impl ResultData {
         result.filter(result_id.eq(id)).first::<Self>(conn)
     }
 
+    /// Queries the DB for a result with the specified name
+    ///
+    /// Queries the DB using `conn` to retrieve the first row with a name value of `result_name`
+    /// Returns a result containing either the retrieved result as a ResultData instance
+    /// or an error if the query fails for some reason or if no result is found matching the
+    /// criteria
+    pub fn find_by_name(
+        conn: &PgConnection,
+        result_name: &str,
+    ) -> Result<Self, diesel::result::Error> {
+        result.filter(name.eq(result_name)).first::<Self>(conn)
+    }
+
     /// Queries the DB for result matching the specified query criteria
     ///
     /// Queries the DB using `conn` to retrieve results matching the crieria in `params`
@@ -300,6 +313,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -314,6 +330,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -354,6 +373,30 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn find_by_name_exists() {
+        let conn = get_test_db_connection();
+
+        let test_result = insert_test_result(&conn);
+
+        let found_result = ResultData::find_by_name(&conn, &test_result.name)
+            .expect("Failed to retrieve test result by name.");
+
+        assert_eq!(found_result, test_result);
+    }
+
+    #[test]
+    fn find_by_name_not_exists() {
+        let conn = get_test_db_connection();
+
+        let nonexistent_result = ResultData::find_by_name(&conn, "Nonexistent result name");
+
+        assert!(matches!(
+            nonexistent_result,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
     #[test]
     fn find_with_result_id() {
         let conn = get_test_db_connection();
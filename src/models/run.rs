@@ -3,12 +3,19 @@
 //! A run represents a specific run of a test.  Represented in the database by the RUN table.
 
 use crate::custom_sql_types::{RunStatusEnum, RUN_FAILURE_STATUSES};
+use crate::models::run_call::RunCallData;
 use crate::models::run_error::RunErrorData;
+use crate::models::run_in_group::RunInGroupData;
+use crate::models::run_input_source::RunInputSourceData;
 use crate::models::run_is_from_github::RunIsFromGithubData;
+use crate::models::run_is_from_schedule::RunIsFromScheduleData;
+use crate::models::run_is_rerun::RunIsRerunData;
 use crate::models::run_result::RunResultData;
 use crate::models::run_software_version::RunSoftwareVersionData;
+use crate::models::run_start_attempt::RunStartAttemptData;
 use crate::schema::run;
 use crate::schema::run::dsl::*;
+use crate::schema::run_in_group;
 use crate::schema::run_with_results_and_errors;
 use crate::schema::template;
 use crate::schema::test;
@@ -39,6 +46,7 @@ pub struct RunData {
     pub created_at: NaiveDateTime,
     pub created_by: Option<String>,
     pub finished_at: Option<NaiveDateTime>,
+    pub cromwell_server: Option<String>,
 }
 
 /// Mapping to a run as viewed through the RUN_WITH_RESULTS_AND_ERRORS view, which assembles data
@@ -61,6 +69,7 @@ pub struct RunWithResultsAndErrorsData {
     pub created_at: NaiveDateTime,
     pub created_by: Option<String>,
     pub finished_at: Option<NaiveDateTime>,
+    pub cromwell_server: Option<String>,
     pub results: Option<Value>,
     pub errors: Option<Value>,
 }
@@ -75,6 +84,7 @@ pub struct RunQuery {
     pub pipeline_id: Option<Uuid>,
     pub template_id: Option<Uuid>,
     pub test_id: Option<Uuid>,
+    pub run_group_id: Option<Uuid>,
     pub name: Option<String>,
     pub status: Option<RunStatusEnum>,
     pub test_input: Option<Value>,
@@ -116,7 +126,8 @@ pub struct NewRun {
 
 /// Represents fields to change when updating a run
 ///
-/// Only name, status, and finished_at can be modified after the run has been created
+/// Only name, status, test_cromwell_job_id, eval_cromwell_job_id, finished_at, and cromwell_server
+/// can be modified after the run has been created
 #[derive(Deserialize, Serialize, AsChangeset, Debug)]
 #[table_name = "run"]
 pub struct RunChangeset {
@@ -125,6 +136,7 @@ pub struct RunChangeset {
     pub test_cromwell_job_id: Option<String>,
     pub eval_cromwell_job_id: Option<String>,
     pub finished_at: Option<NaiveDateTime>,
+    pub cromwell_server: Option<String>,
 }
 
 /// Represents an error generated by an attempt at deleting a row in the RUN table
@@ -164,6 +176,34 @@ impl RunData {
         run.filter(run_id.eq(id)).first::<Self>(conn)
     }
 
+    /// Queries the DB for a run with the specified name
+    ///
+    /// Queries the DB using `conn` to retrieve the run with a name value of `run_name`
+    /// Returns a result containing either the retrieved run as a RunData instance or an error if
+    /// the query fails for some reason or if no run is found matching the criteria
+    pub fn find_by_name(
+        conn: &PgConnection,
+        run_name: &str,
+    ) -> Result<Self, diesel::result::Error> {
+        run.filter(name.eq(run_name)).first::<Self>(conn)
+    }
+
+    /// Queries the DB for the most recently finished `Succeeded` run of the test named `test_name`
+    ///
+    /// Returns a result containing either the retrieved run as a RunData instance or an error if
+    /// the query fails for some reason or if the test has no succeeded runs
+    pub fn find_most_recent_succeeded_for_test_name(
+        conn: &PgConnection,
+        test_name: &str,
+    ) -> Result<Self, diesel::result::Error> {
+        run.inner_join(test::table)
+            .filter(test::dsl::name.eq(test_name))
+            .filter(status.eq(RunStatusEnum::Succeeded))
+            .order_by((run::dsl::finished_at.desc(), run::dsl::created_at.desc()))
+            .select(run::all_columns)
+            .first::<Self>(conn)
+    }
+
     /// Queries the DB for runs matching the specified query criteria
     ///
     /// Queries the DB using `conn` to retrieve runs matching the crieria in `params`
@@ -196,6 +236,16 @@ impl RunData {
             query = query.filter(test_id.eq_any(template_subquery));
         }
 
+        // Filtering by run_group_id also requires a subquery
+        if let Some(param) = params.run_group_id {
+            // Subquery for getting all run_ids for runs belonging to the specified run group
+            let run_group_subquery = run_in_group::dsl::run_in_group
+                .filter(run_in_group::dsl::run_group_id.eq(param))
+                .select(run_in_group::dsl::run_id);
+            // Filter by the results of the run group subquery
+            query = query.filter(run_id.eq_any(run_group_subquery));
+        }
+
         // Add filters for each of the other params if they have values
         if let Some(param) = params.test_id {
             query = query.filter(test_id.eq(param));
@@ -362,6 +412,180 @@ impl RunData {
         run.filter(finished_at.is_null()).load::<Self>(conn)
     }
 
+    /// Queries the DB for runs that are waiting for a slot to start
+    ///
+    /// Returns result containing either a vector of the retrieved runs (which have a status of
+    /// `Queued`) or a diesel error if retrieving the runs fails for some reason.  The runs are
+    /// sorted in the order they were created, unless `prioritize` is true, in which case they are
+    /// sorted first by the priority of their tests (highest first), and then the order they were
+    /// created
+    pub fn find_queued(
+        conn: &PgConnection,
+        prioritize: bool,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        if prioritize {
+            run.inner_join(test::table)
+                .filter(status.eq(RunStatusEnum::Queued))
+                .order_by((test::dsl::priority.desc(), run::dsl::created_at.asc()))
+                .select(run::all_columns)
+                .load::<Self>(conn)
+        } else {
+            run.filter(status.eq(RunStatusEnum::Queued))
+                .order_by(run::dsl::created_at.asc())
+                .load::<Self>(conn)
+        }
+    }
+
+    /// Queries the DB for runs that have been `Created` since before `cutoff`
+    ///
+    /// Runs are normally only `Created` while they are being started, so these are runs that were
+    /// stranded (e.g. by CARROT stopping before they were submitted to cromwell).  Returns result
+    /// containing either a vector of the retrieved runs, sorted in the order they were created, or
+    /// a diesel error if retrieving the runs fails for some reason
+    pub fn find_created_before(
+        conn: &PgConnection,
+        cutoff: NaiveDateTime,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        run.filter(status.eq(RunStatusEnum::Created))
+            .filter(created_at.lt(cutoff))
+            .order_by(run::dsl::created_at.asc())
+            .load::<Self>(conn)
+    }
+
+    /// Queries the DB for the number of runs, other than the run specified by `excluded_id`, that
+    /// are currently active
+    ///
+    /// A run is considered active if it has not finished and it is not `Queued`.  `Created` runs
+    /// are counted, since they are in the process of being started and will be active soon.
+    /// `excluded_id` is for excluding the run that is being started from the count.  Returns result
+    /// containing either the number of active runs or a diesel error if the query fails for some
+    /// reason
+    pub fn count_active(
+        conn: &PgConnection,
+        excluded_id: Uuid,
+    ) -> Result<i64, diesel::result::Error> {
+        run.filter(finished_at.is_null())
+            .filter(status.ne(RunStatusEnum::Queued))
+            .filter(run_id.ne(excluded_id))
+            .count()
+            .get_result(conn)
+    }
+
+    /// Queries the DB for the number of runs, other than the run specified by `excluded_id`, that
+    /// are currently active for the test specified by `id`
+    ///
+    /// A run is considered active if it has not finished and it is not `Queued`.  `Created` runs
+    /// are counted, since they are in the process of being started and will be active soon.
+    /// Returns result containing either the number of active runs or a diesel error if the query
+    /// fails for some reason
+    pub fn count_active_for_test(
+        conn: &PgConnection,
+        id: Uuid,
+        excluded_id: Uuid,
+    ) -> Result<i64, diesel::result::Error> {
+        run.filter(test_id.eq(id))
+            .filter(finished_at.is_null())
+            .filter(status.ne(RunStatusEnum::Queued))
+            .filter(run_id.ne(excluded_id))
+            .count()
+            .get_result(conn)
+    }
+
+    /// Queries the DB for the number of runs, other than the run specified by `excluded_id`, that
+    /// are currently active for the pipeline specified by `id`
+    ///
+    /// A run is considered active if it has not finished and it is not `Queued`.  `Created` runs
+    /// are counted, since they are in the process of being started and will be active soon.
+    /// Returns result containing either the number of active runs or a diesel error if the query
+    /// fails for some reason
+    pub fn count_active_for_pipeline(
+        conn: &PgConnection,
+        id: Uuid,
+        excluded_id: Uuid,
+    ) -> Result<i64, diesel::result::Error> {
+        let pipeline_subquery = template::dsl::template
+            .filter(template::dsl::pipeline_id.eq(id))
+            .select(template::dsl::template_id);
+        let template_subquery = test::dsl::test
+            .filter(test::dsl::template_id.eq_any(pipeline_subquery))
+            .select(test::dsl::test_id);
+        run.filter(test_id.eq_any(template_subquery))
+            .filter(finished_at.is_null())
+            .filter(status.ne(RunStatusEnum::Queued))
+            .filter(run_id.ne(excluded_id))
+            .count()
+            .get_result(conn)
+    }
+
+    /// Queries the DB for the number of runs that are `Queued`
+    ///
+    /// Returns result containing either the number of queued runs or a diesel error if the query
+    /// fails for some reason
+    pub fn count_queued(conn: &PgConnection) -> Result<i64, diesel::result::Error> {
+        run.filter(status.eq(RunStatusEnum::Queued))
+            .count()
+            .get_result(conn)
+    }
+
+    /// Queries the DB for the number of runs that are `Queued` for the test specified by `id`
+    ///
+    /// Returns result containing either the number of queued runs or a diesel error if the query
+    /// fails for some reason
+    pub fn count_queued_for_test(
+        conn: &PgConnection,
+        id: Uuid,
+    ) -> Result<i64, diesel::result::Error> {
+        run.filter(test_id.eq(id))
+            .filter(status.eq(RunStatusEnum::Queued))
+            .count()
+            .get_result(conn)
+    }
+
+    /// Queries the DB for the number of runs that are `Queued` for the pipeline specified by `id`
+    ///
+    /// Returns result containing either the number of queued runs or a diesel error if the query
+    /// fails for some reason
+    pub fn count_queued_for_pipeline(
+        conn: &PgConnection,
+        id: Uuid,
+    ) -> Result<i64, diesel::result::Error> {
+        let pipeline_subquery = template::dsl::template
+            .filter(template::dsl::pipeline_id.eq(id))
+            .select(template::dsl::template_id);
+        let template_subquery = test::dsl::test
+            .filter(test::dsl::template_id.eq_any(pipeline_subquery))
+            .select(test::dsl::test_id);
+        run.filter(test_id.eq_any(template_subquery))
+            .filter(status.eq(RunStatusEnum::Queued))
+            .count()
+            .get_result(conn)
+    }
+
+    /// Queries the DB for the number of runs that are currently active on the cromwell server
+    /// named `server`
+    ///
+    /// A run is considered active if it has not finished and it is not `Created` (i.e. still being
+    /// started) or `Queued`.  Runs that don't have a cromwell server recorded were run on the
+    /// default server, so they are also counted if `is_default_server` is true.  Returns result
+    /// containing either the number of active runs or a diesel error if the query fails for some
+    /// reason
+    pub fn count_active_for_server(
+        conn: &PgConnection,
+        server: &str,
+        is_default_server: bool,
+    ) -> Result<i64, diesel::result::Error> {
+        let mut query = run
+            .filter(finished_at.is_null())
+            .filter(status.ne_all(vec![RunStatusEnum::Created, RunStatusEnum::Queued]))
+            .into_boxed();
+        if is_default_server {
+            query = query.filter(cromwell_server.eq(server).or(cromwell_server.is_null()));
+        } else {
+            query = query.filter(cromwell_server.eq(server));
+        }
+        query.count().get_result(conn)
+    }
+
     /// Inserts a new run into the DB
     ///
     /// Creates a new run row in the DB using `conn` with the values specified in `params`
@@ -425,12 +649,18 @@ impl RunData {
         }
         // Do all the actual deleting in a closure so we can run it in a transaction
         let delete_closure = || {
-            // Delete run_software_version, run_result, run_error, and run_is_from_github rows tied
-            // to this run
+            // Delete run_software_version, run_result, run_error, run_call, run_start_attempt,
+            // run_is_from_github, run_is_rerun, and run_input_source rows tied to this run
             RunSoftwareVersionData::delete_by_run_id(conn, id)?;
             RunResultData::delete_by_run_id(conn, id)?;
             RunIsFromGithubData::delete_by_run_id(conn, id)?;
             RunErrorData::delete_by_run_id(conn, id)?;
+            RunCallData::delete_by_run_id(conn, id)?;
+            RunStartAttemptData::delete_by_run_id(conn, id)?;
+            RunIsRerunData::delete_by_run_id(conn, id)?;
+            RunInputSourceData::delete_by_run_id(conn, id)?;
+            RunIsFromScheduleData::delete_by_run_id(conn, id)?;
+            RunInGroupData::delete_by_run_id(conn, id)?;
 
             // Delete and return result
             Ok(diesel::delete(run.filter(run_id.eq(id))).execute(conn)?)
@@ -471,6 +701,7 @@ impl RunWithResultsAndErrorsData {
                 run_with_results_and_errors::dsl::created_at,
                 run_with_results_and_errors::dsl::created_by,
                 run_with_results_and_errors::dsl::finished_at,
+                run_with_results_and_errors::dsl::cromwell_server,
                 run_with_results_and_errors::dsl::results,
                 run_with_results_and_errors::dsl::errors,
             ))
@@ -510,6 +741,17 @@ impl RunWithResultsAndErrorsData {
                 query.filter(run_with_results_and_errors::dsl::test_id.eq_any(template_subquery));
         }
 
+        // Filtering by run_group_id also requires a subquery
+        if let Some(param) = params.run_group_id {
+            // Subquery for getting all run_ids for runs belonging to the specified run group
+            let run_group_subquery = run_in_group::dsl::run_in_group
+                .filter(run_in_group::dsl::run_group_id.eq(param))
+                .select(run_in_group::dsl::run_id);
+            // Filter by the results of the run group subquery
+            query =
+                query.filter(run_with_results_and_errors::dsl::run_id.eq_any(run_group_subquery));
+        }
+
         // Add filters for each of the other params if they have values
         if let Some(param) = params.test_id {
             query = query.filter(run_with_results_and_errors::dsl::test_id.eq(param));
@@ -714,6 +956,7 @@ impl RunWithResultsAndErrorsData {
                 run_with_results_and_errors::dsl::created_at,
                 run_with_results_and_errors::dsl::created_by,
                 run_with_results_and_errors::dsl::finished_at,
+                run_with_results_and_errors::dsl::cromwell_server,
                 run_with_results_and_errors::dsl::results,
                 run_with_results_and_errors::dsl::errors,
             ))
@@ -728,6 +971,8 @@ mod tests {
     use crate::models::pipeline::{NewPipeline, PipelineData};
     use crate::models::result::{NewResult, ResultData};
     use crate::models::run_error::{NewRunError, RunErrorQuery};
+    use crate::models::run_group::{NewRunGroup, RunGroupData};
+    use crate::models::run_in_group::NewRunInGroup;
     use crate::models::run_is_from_github::{
         NewRunIsFromGithub, RunIsFromGithubData, RunIsFromGithubQuery,
     };
@@ -742,6 +987,7 @@ mod tests {
     use crate::unit_test_util::*;
     use chrono::format::StrftimeItems;
     use chrono::offset::Utc;
+    use diesel::dsl::IntervalDsl;
     use rand::distributions::Alphanumeric;
     use rand::prelude::*;
     use serde_json::json;
@@ -770,6 +1016,7 @@ mod tests {
             finished_at: test_run.finished_at,
             results: Some(test_results),
             errors: Some(test_errors),
+            cromwell_server: None,
         }
     }
 
@@ -868,6 +1115,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -882,6 +1132,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -896,6 +1149,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: Some(serde_json::from_str("{\"eval_option\":\"test\"}").unwrap()),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let test = TestData::create(conn, new_test).expect("Failed inserting test test");
@@ -922,6 +1179,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -936,6 +1196,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -950,6 +1213,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: Some(serde_json::from_str("{\"eval_option\":\"test\"}").unwrap()),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let test = TestData::create(conn, new_test).expect("Failed inserting test test");
@@ -986,6 +1253,9 @@ mod tests {
             name: String::from("Kevin's Pipeline3"),
             description: Some(String::from("Kevin made this pipeline for testing3")),
             created_by: Some(String::from("Kevin3@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -1000,6 +1270,9 @@ mod tests {
             eval_wdl: String::from(""),
             eval_wdl_dependencies: None,
             created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(&conn, new_template).expect("Failed to insert test")
@@ -1015,6 +1288,10 @@ mod tests {
             eval_input_defaults: None,
             eval_option_defaults: None,
             created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(&conn, new_test).expect("Failed to insert test")
@@ -1025,6 +1302,9 @@ mod tests {
             name: String::from("Kevin's Pipeline3"),
             description: Some(String::from("Kevin made this pipeline for testing3")),
             created_by: Some(String::from("Kevin3@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -1039,6 +1319,9 @@ mod tests {
             eval_wdl: String::from(""),
             eval_wdl_dependencies: None,
             created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template = TemplateData::create(&conn, new_template).expect("Failed to insert test");
@@ -1052,6 +1335,10 @@ mod tests {
             eval_input_defaults: None,
             eval_option_defaults: None,
             created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(&conn, new_test).expect("Failed to insert test")
@@ -1237,6 +1524,7 @@ mod tests {
             pipeline_id: Some(test_template.pipeline_id),
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1274,6 +1562,7 @@ mod tests {
             pipeline_id: None,
             template_id: Some(test_template.template_id),
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1312,6 +1601,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: Some(test_run.test_id),
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1336,6 +1626,64 @@ mod tests {
         assert_eq!(found_runs[0], test_run);
     }
 
+    #[test]
+    fn find_with_run_group_id() {
+        let conn = get_test_db_connection();
+
+        let test = insert_test_test(&conn);
+        let test_runs = insert_test_runs_with_test_id(&conn, test.test_id);
+        insert_test_run(&conn);
+
+        let run_group = RunGroupData::create(
+            &conn,
+            NewRunGroup {
+                name: String::from("Kevin's Run Group"),
+                description: None,
+                created_by: None,
+            },
+        )
+        .expect("Failed inserting test run group");
+        for test_run in &test_runs[0..2] {
+            RunInGroupData::create(
+                &conn,
+                NewRunInGroup {
+                    run_id: test_run.run_id,
+                    run_group_id: run_group.run_group_id,
+                },
+            )
+            .expect("Failed inserting test run_in_group");
+        }
+
+        let test_query = RunQuery {
+            pipeline_id: None,
+            template_id: None,
+            test_id: None,
+            run_group_id: Some(run_group.run_group_id),
+            name: None,
+            status: None,
+            test_input: None,
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            test_cromwell_job_id: None,
+            eval_cromwell_job_id: None,
+            created_before: None,
+            created_after: None,
+            created_by: None,
+            finished_before: None,
+            finished_after: None,
+            sort: Some(String::from("name")),
+            limit: None,
+            offset: None,
+        };
+
+        let found_runs = RunData::find(&conn, test_query).expect("Failed to find runs");
+
+        assert_eq!(found_runs.len(), 2);
+        assert_eq!(found_runs[0], test_runs[0]);
+        assert_eq!(found_runs[1], test_runs[1]);
+    }
+
     #[test]
     fn find_with_name() {
         let conn = get_test_db_connection();
@@ -1347,6 +1695,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: Some(test_runs[1].name.clone()),
             status: None,
             test_input: None,
@@ -1382,6 +1731,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: Some(RunStatusEnum::TestSubmitted),
             test_input: None,
@@ -1418,6 +1768,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: Some(test_run.test_input.clone()),
@@ -1454,6 +1805,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1489,6 +1841,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1524,6 +1877,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1560,6 +1914,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1596,6 +1951,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1632,6 +1988,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: Some(test_runs[0].test_id),
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1660,6 +2017,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: Some(test_runs[0].test_id),
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1695,6 +2053,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1721,6 +2080,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1755,6 +2115,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1781,6 +2142,7 @@ mod tests {
             pipeline_id: None,
             template_id: None,
             test_id: None,
+            run_group_id: None,
             name: None,
             status: None,
             test_input: None,
@@ -1804,6 +2166,368 @@ mod tests {
         assert_eq!(found_runs.len(), 2);
     }
 
+    fn insert_test_runs_with_test_id_and_statuses(
+        conn: &PgConnection,
+        id: Uuid,
+        runs_to_insert: &[(&str, RunStatusEnum)],
+    ) -> Vec<RunData> {
+        let mut runs = Vec::new();
+
+        for (index, (run_name, run_status)) in runs_to_insert.iter().enumerate() {
+            let new_run = NewRun {
+                test_id: id,
+                name: String::from(*run_name),
+                status: run_status.clone(),
+                test_input: serde_json::from_str("{}").unwrap(),
+                test_options: None,
+                eval_input: serde_json::from_str("{}").unwrap(),
+                eval_options: None,
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                created_by: None,
+                finished_at: None,
+            };
+
+            let new_run = RunData::create(conn, new_run).expect("Failed inserting test run");
+            // Space out the created_at values so we can check ordering, since everything in a test
+            // happens in the same transaction
+            let new_run = diesel::update(run.filter(run_id.eq(new_run.run_id)))
+                .set(created_at.eq(created_at + (index as i32).days()))
+                .get_result::<RunData>(conn)
+                .expect("Failed updating test run created_at");
+
+            runs.push(new_run);
+        }
+
+        runs
+    }
+
+    #[test]
+    fn find_queued_success() {
+        let conn = get_test_db_connection();
+
+        let test = insert_test_test(&conn);
+        let test_runs = insert_test_runs_with_test_id_and_statuses(
+            &conn,
+            test.test_id,
+            &[
+                ("queued1", RunStatusEnum::Queued),
+                ("running", RunStatusEnum::TestRunning),
+                ("queued2", RunStatusEnum::Queued),
+            ],
+        );
+
+        let found_runs = RunData::find_queued(&conn, false).unwrap();
+
+        assert_eq!(found_runs.len(), 2);
+        assert_eq!(found_runs[0], test_runs[0]);
+        assert_eq!(found_runs[1], test_runs[2]);
+    }
+
+    #[test]
+    fn find_queued_prioritized() {
+        let conn = get_test_db_connection();
+
+        let template = insert_test_template(&conn);
+        let low_priority_test = insert_test_test_with_template_id(&conn, template.template_id);
+        let high_priority_test = TestData::create(
+            &conn,
+            NewTest {
+                name: String::from("Kevin's important test"),
+                template_id: template.template_id,
+                description: None,
+                test_input_defaults: None,
+                test_option_defaults: None,
+                eval_input_defaults: None,
+                eval_option_defaults: None,
+                created_by: None,
+                max_concurrent_runs: None,
+                priority: Some(5),
+                max_runtime_in_secs: None,
+                cromwell_server: None,
+            },
+        )
+        .expect("Failed to insert test");
+
+        let low_priority_runs = insert_test_runs_with_test_id_and_statuses(
+            &conn,
+            low_priority_test.test_id,
+            &[("queued_low", RunStatusEnum::Queued)],
+        );
+        // Insert a couple runs so the high priority run is created after the low priority one
+        let high_priority_runs = insert_test_runs_with_test_id_and_statuses(
+            &conn,
+            high_priority_test.test_id,
+            &[
+                ("running_high", RunStatusEnum::TestRunning),
+                ("queued_high", RunStatusEnum::Queued),
+            ],
+        );
+
+        let found_runs = RunData::find_queued(&conn, true).unwrap();
+
+        assert_eq!(found_runs.len(), 2);
+        assert_eq!(found_runs[0], high_priority_runs[1]);
+        assert_eq!(found_runs[1], low_priority_runs[0]);
+    }
+
+    #[test]
+    fn find_by_name_exists() {
+        let conn = get_test_db_connection();
+
+        let test = insert_test_test(&conn);
+        let test_runs = insert_test_runs_with_test_id(&conn, test.test_id);
+
+        let found_run =
+            RunData::find_by_name(&conn, "name2").expect("Failed to retrieve test run by name.");
+
+        assert_eq!(found_run, test_runs[1]);
+    }
+
+    #[test]
+    fn find_by_name_not_exists() {
+        let conn = get_test_db_connection();
+
+        let nonexistent_run = RunData::find_by_name(&conn, "nonexistent run");
+
+        assert!(matches!(
+            nonexistent_run,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn find_most_recent_succeeded_for_test_name_success() {
+        let conn = get_test_db_connection();
+
+        let test = insert_test_test(&conn);
+        let test_runs = insert_test_runs_with_test_id_and_statuses(
+            &conn,
+            test.test_id,
+            &[
+                ("succeeded1", RunStatusEnum::Succeeded),
+                ("succeeded2", RunStatusEnum::Succeeded),
+                ("failed", RunStatusEnum::EvalFailed),
+            ],
+        );
+        // Set finished_at values so the second succeeded run finished most recently
+        for (index, test_run) in test_runs.iter().enumerate() {
+            diesel::update(run.filter(run_id.eq(test_run.run_id)))
+                .set(finished_at.eq((created_at + (index as i32).hours()).nullable()))
+                .execute(&conn)
+                .expect("Failed updating test run finished_at");
+        }
+
+        let found_run = RunData::find_most_recent_succeeded_for_test_name(&conn, &test.name)
+            .expect("Failed to retrieve most recent succeeded run for test.");
+
+        assert_eq!(found_run.run_id, test_runs[1].run_id);
+    }
+
+    #[test]
+    fn find_most_recent_succeeded_for_test_name_none() {
+        let conn = get_test_db_connection();
+
+        let test = insert_test_test(&conn);
+        insert_test_runs_with_test_id_and_statuses(
+            &conn,
+            test.test_id,
+            &[("failed", RunStatusEnum::EvalFailed)],
+        );
+
+        let found_run = RunData::find_most_recent_succeeded_for_test_name(&conn, &test.name);
+
+        assert!(matches!(found_run, Err(diesel::result::Error::NotFound)));
+    }
+
+    #[test]
+    fn find_created_before_success() {
+        let conn = get_test_db_connection();
+
+        let test = insert_test_test(&conn);
+        let test_runs = insert_test_runs_with_test_id_and_statuses(
+            &conn,
+            test.test_id,
+            &[
+                ("created", RunStatusEnum::Created),
+                ("running", RunStatusEnum::TestRunning),
+                ("queued", RunStatusEnum::Queued),
+            ],
+        );
+
+        // created_at is set to the start of the test transaction, so use a cutoff after that
+        let found_runs = RunData::find_created_before(
+            &conn,
+            Utc::now().naive_utc() + chrono::Duration::minutes(1),
+        )
+        .unwrap();
+
+        assert_eq!(found_runs.len(), 1);
+        assert_eq!(found_runs[0], test_runs[0]);
+
+        // And make sure none are returned for a cutoff before that
+        let found_runs = RunData::find_created_before(
+            &conn,
+            Utc::now().naive_utc() - chrono::Duration::minutes(1),
+        )
+        .unwrap();
+
+        assert!(found_runs.is_empty());
+    }
+
+    #[test]
+    fn count_active_success() {
+        let conn = get_test_db_connection();
+
+        let template = insert_test_template(&conn);
+        let first_test = insert_test_test_with_template_id(&conn, template.template_id);
+        let second_test = TestData::create(
+            &conn,
+            NewTest {
+                name: String::from("Kevin's other test"),
+                template_id: template.template_id,
+                description: None,
+                test_input_defaults: None,
+                test_option_defaults: None,
+                eval_input_defaults: None,
+                eval_option_defaults: None,
+                created_by: None,
+                max_concurrent_runs: None,
+                priority: None,
+                max_runtime_in_secs: None,
+                cromwell_server: None,
+            },
+        )
+        .expect("Failed to insert test");
+
+        let first_test_runs = insert_test_runs_with_test_id_and_statuses(
+            &conn,
+            first_test.test_id,
+            &[
+                ("created", RunStatusEnum::Created),
+                ("queued", RunStatusEnum::Queued),
+                ("building", RunStatusEnum::Building),
+                ("eval_running", RunStatusEnum::EvalRunning),
+            ],
+        );
+        insert_test_runs_with_test_id_and_statuses(
+            &conn,
+            second_test.test_id,
+            &[("test_running", RunStatusEnum::TestRunning)],
+        );
+        // Add a few more runs, only one of which hasn't finished
+        insert_test_runs_with_test_id(&conn, second_test.test_id);
+
+        // The created run counts as active, since it's being started, unless it's excluded
+        let created_run_id = first_test_runs[0].run_id;
+        let other_run_id = Uuid::new_v4();
+
+        assert_eq!(RunData::count_active(&conn, other_run_id).unwrap(), 5);
+        assert_eq!(RunData::count_active(&conn, created_run_id).unwrap(), 4);
+        assert_eq!(
+            RunData::count_active_for_test(&conn, first_test.test_id, other_run_id).unwrap(),
+            3
+        );
+        assert_eq!(
+            RunData::count_active_for_test(&conn, first_test.test_id, created_run_id).unwrap(),
+            2
+        );
+        assert_eq!(
+            RunData::count_active_for_pipeline(&conn, template.pipeline_id, other_run_id).unwrap(),
+            5
+        );
+        assert_eq!(
+            RunData::count_active_for_pipeline(&conn, template.pipeline_id, created_run_id)
+                .unwrap(),
+            4
+        );
+        assert_eq!(
+            RunData::count_active_for_pipeline(&conn, Uuid::new_v4(), other_run_id).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn count_queued_success() {
+        let conn = get_test_db_connection();
+
+        let template = insert_test_template(&conn);
+        let test = insert_test_test_with_template_id(&conn, template.template_id);
+        insert_test_runs_with_test_id_and_statuses(
+            &conn,
+            test.test_id,
+            &[
+                ("created", RunStatusEnum::Created),
+                ("queued1", RunStatusEnum::Queued),
+                ("queued2", RunStatusEnum::Queued),
+                ("test_running", RunStatusEnum::TestRunning),
+            ],
+        );
+
+        assert_eq!(RunData::count_queued(&conn).unwrap(), 2);
+        assert_eq!(
+            RunData::count_queued_for_test(&conn, test.test_id).unwrap(),
+            2
+        );
+        assert_eq!(
+            RunData::count_queued_for_test(&conn, Uuid::new_v4()).unwrap(),
+            0
+        );
+        assert_eq!(
+            RunData::count_queued_for_pipeline(&conn, template.pipeline_id).unwrap(),
+            2
+        );
+        assert_eq!(
+            RunData::count_queued_for_pipeline(&conn, Uuid::new_v4()).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn count_active_for_server_success() {
+        let conn = get_test_db_connection();
+
+        let template = insert_test_template(&conn);
+        let test = insert_test_test_with_template_id(&conn, template.template_id);
+        let runs = insert_test_runs_with_test_id_and_statuses(
+            &conn,
+            test.test_id,
+            &[
+                ("default_running", RunStatusEnum::TestRunning),
+                ("hpc_running", RunStatusEnum::EvalRunning),
+                ("hpc_queued", RunStatusEnum::Queued),
+            ],
+        );
+        for run_data in &runs[1..] {
+            RunData::update(
+                &conn,
+                run_data.run_id,
+                RunChangeset {
+                    name: None,
+                    status: None,
+                    test_cromwell_job_id: None,
+                    eval_cromwell_job_id: None,
+                    finished_at: None,
+                    cromwell_server: Some(String::from("hpc")),
+                },
+            )
+            .expect("Failed to update run cromwell_server");
+        }
+
+        assert_eq!(
+            RunData::count_active_for_server(&conn, "hpc", false).unwrap(),
+            1
+        );
+        assert_eq!(
+            RunData::count_active_for_server(&conn, "default", true).unwrap(),
+            1
+        );
+        assert_eq!(
+            RunData::count_active_for_server(&conn, "default", false).unwrap(),
+            0
+        );
+    }
+
     #[test]
     fn find_unfinished_success() {
         let conn = get_test_db_connection();
@@ -1829,6 +2553,7 @@ mod tests {
             test_cromwell_job_id: None,
             eval_cromwell_job_id: None,
             finished_at: Some("2099-01-01T00:00:00".parse::<NaiveDateTime>().unwrap()),
+            cromwell_server: None,
         };
 
         let updated_run =
@@ -1855,6 +2580,7 @@ mod tests {
             test_cromwell_job_id: None,
             eval_cromwell_job_id: None,
             finished_at: None,
+            cromwell_server: None,
         };
 
         let updated_run = RunData::update(&conn, test_runs[1].run_id, changes);
@@ -0,0 +1,573 @@
+//! Contains structs and functions for doing operations on RUN_CALL records.
+//!
+//! A run_call record represents a single call (an attempt at running a shard of a task) in the
+//! test or eval workflow of a run, as reported in the metadata from cromwell.  These are stored
+//! when the corresponding workflow finishes so it is possible to see which task failed or ran
+//! slowly without going to cromwell.  Represented in the database by the RUN_CALL table.
+
+use crate::custom_sql_types::RunCallWorkflowEnum;
+use crate::schema::run_call;
+use crate::schema::run_call::dsl::*;
+use crate::util;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Mapping to a run_call as it exists in the RUN_CALL table in the database.
+///
+/// An instance of this struct will be returned by any queries for run_calls.
+#[derive(Queryable, Deserialize, Serialize, PartialEq, Debug)]
+pub struct RunCallData {
+    pub run_call_id: Uuid,
+    pub run_id: Uuid,
+    pub workflow: RunCallWorkflowEnum,
+    pub cromwell_job_id: String,
+    pub task_name: String,
+    pub shard_index: i32,
+    pub attempt: i32,
+    pub execution_status: Option<String>,
+    pub backend_status: Option<String>,
+    pub return_code: Option<i32>,
+    pub docker_image: Option<String>,
+    pub runtime_attributes: Option<Value>,
+    pub started_at: Option<NaiveDateTime>,
+    pub finished_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Represents all possible parameters for a query of the RUN_CALL table
+///
+/// All values are optional, so any combination can be used during a query.  Limit and offset are
+/// used for pagination.  Sort expects a comma-separated list of sort keys, optionally enclosed
+/// with either asc() or desc().  For example: asc(task_name)
+#[derive(Deserialize)]
+pub struct RunCallQuery {
+    pub run_id: Option<Uuid>,
+    pub workflow: Option<RunCallWorkflowEnum>,
+    pub cromwell_job_id: Option<String>,
+    pub task_name: Option<String>,
+    pub execution_status: Option<String>,
+    pub backend_status: Option<String>,
+    pub return_code: Option<i32>,
+    pub docker_image: Option<String>,
+    pub started_before: Option<NaiveDateTime>,
+    pub started_after: Option<NaiveDateTime>,
+    pub finished_before: Option<NaiveDateTime>,
+    pub finished_after: Option<NaiveDateTime>,
+    pub sort: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// A new run_call to be inserted into the DB
+///
+/// run_id, workflow, cromwell_job_id, task_name, shard_index, and attempt are required fields, but
+/// the rest are not, so can be filled with `None`; created_at and run_call_id are populated
+/// automatically by the DB
+#[derive(Deserialize, Serialize, Insertable, Debug, PartialEq)]
+#[table_name = "run_call"]
+pub struct NewRunCall {
+    pub run_id: Uuid,
+    pub workflow: RunCallWorkflowEnum,
+    pub cromwell_job_id: String,
+    pub task_name: String,
+    pub shard_index: i32,
+    pub attempt: i32,
+    pub execution_status: Option<String>,
+    pub backend_status: Option<String>,
+    pub return_code: Option<i32>,
+    pub docker_image: Option<String>,
+    pub runtime_attributes: Option<Value>,
+    pub started_at: Option<NaiveDateTime>,
+    pub finished_at: Option<NaiveDateTime>,
+}
+
+impl RunCallData {
+    /// Queries the DB for a run_call with the specified run_call_id
+    ///
+    /// Queries the DB using `conn` to retrieve the first row with a run_call_id value of `id`
+    /// Returns a result containing either the retrieved run_call as a RunCallData instance or an
+    /// error if the query fails for some reason or if no run_call is found matching the criteria
+    ///
+    /// This is basically just here for api completeness
+    #[allow(dead_code)]
+    pub fn find_by_id(conn: &PgConnection, id: Uuid) -> Result<Self, diesel::result::Error> {
+        run_call.filter(run_call_id.eq(id)).first::<Self>(conn)
+    }
+
+    /// Queries the DB for run_calls matching the specified query criteria
+    ///
+    /// Queries the DB using `conn` to retrieve run_calls matching the criteria in `params`
+    /// Returns a result containing either a vector of the retrieved run_calls as RunCallData
+    /// instances or an error if the query fails for some reason
+    pub fn find(
+        conn: &PgConnection,
+        params: RunCallQuery,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        // Put the query into a box (pointer) so it can be built dynamically
+        let mut query = run_call.into_boxed();
+
+        // Add filters for each of the params if they have values
+        if let Some(param) = params.run_id {
+            query = query.filter(run_id.eq(param));
+        }
+        if let Some(param) = params.workflow {
+            query = query.filter(workflow.eq(param));
+        }
+        if let Some(param) = params.cromwell_job_id {
+            query = query.filter(cromwell_job_id.eq(param));
+        }
+        if let Some(param) = params.task_name {
+            query = query.filter(task_name.eq(param));
+        }
+        if let Some(param) = params.execution_status {
+            query = query.filter(execution_status.eq(param));
+        }
+        if let Some(param) = params.backend_status {
+            query = query.filter(backend_status.eq(param));
+        }
+        if let Some(param) = params.return_code {
+            query = query.filter(return_code.eq(param));
+        }
+        if let Some(param) = params.docker_image {
+            query = query.filter(docker_image.eq(param));
+        }
+        if let Some(param) = params.started_before {
+            query = query.filter(started_at.lt(param));
+        }
+        if let Some(param) = params.started_after {
+            query = query.filter(started_at.gt(param));
+        }
+        if let Some(param) = params.finished_before {
+            query = query.filter(finished_at.lt(param));
+        }
+        if let Some(param) = params.finished_after {
+            query = query.filter(finished_at.gt(param));
+        }
+
+        // If there is a sort param, parse it and add to the order by clause accordingly
+        if let Some(sort) = params.sort {
+            let sort = util::sort_string::parse_sort_string(&sort);
+            for sort_clause in sort {
+                match &sort_clause.key[..] {
+                    "workflow" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(workflow.asc());
+                        } else {
+                            query = query.then_order_by(workflow.desc());
+                        }
+                    }
+                    "task_name" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(task_name.asc());
+                        } else {
+                            query = query.then_order_by(task_name.desc());
+                        }
+                    }
+                    "shard_index" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(shard_index.asc());
+                        } else {
+                            query = query.then_order_by(shard_index.desc());
+                        }
+                    }
+                    "attempt" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(attempt.asc());
+                        } else {
+                            query = query.then_order_by(attempt.desc());
+                        }
+                    }
+                    "execution_status" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(execution_status.asc());
+                        } else {
+                            query = query.then_order_by(execution_status.desc());
+                        }
+                    }
+                    "backend_status" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(backend_status.asc());
+                        } else {
+                            query = query.then_order_by(backend_status.desc());
+                        }
+                    }
+                    "return_code" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(return_code.asc());
+                        } else {
+                            query = query.then_order_by(return_code.desc());
+                        }
+                    }
+                    "started_at" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(started_at.asc());
+                        } else {
+                            query = query.then_order_by(started_at.desc());
+                        }
+                    }
+                    "finished_at" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(finished_at.asc());
+                        } else {
+                            query = query.then_order_by(finished_at.desc());
+                        }
+                    }
+                    // Don't add to the order by clause if the sort key isn't recognized
+                    &_ => {}
+                }
+            }
+        }
+
+        if let Some(param) = params.limit {
+            query = query.limit(param);
+        }
+        if let Some(param) = params.offset {
+            query = query.offset(param);
+        }
+
+        // Perform the query
+        query.load::<Self>(conn)
+    }
+
+    /// Inserts multiple new run_calls into the DB
+    ///
+    /// Creates a new run_call row in the DB using `conn` for each insert record specified in
+    /// `params`
+    /// Returns a result containing either the new run_call records that were created or an
+    /// error if the insert fails for some reason
+    pub fn batch_create(
+        conn: &PgConnection,
+        params: Vec<NewRunCall>,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        diesel::insert_into(run_call)
+            .values(&params)
+            .get_results(conn)
+    }
+
+    /// Deletes run_call rows from the DB that are mapped to the run specified by `id`
+    ///
+    /// Returns either the number of run_call rows deleted, or an error if something goes
+    /// wrong during the delete
+    pub fn delete_by_run_id(conn: &PgConnection, id: Uuid) -> Result<usize, diesel::result::Error> {
+        diesel::delete(run_call).filter(run_id.eq(id)).execute(conn)
+    }
+
+    /// Deletes run_call rows from the DB that came from the cromwell job specified by `job_id`
+    ///
+    /// Returns either the number of run_call rows deleted, or an error if something goes
+    /// wrong during the delete
+    pub fn delete_by_cromwell_job_id(
+        conn: &PgConnection,
+        job_id: &str,
+    ) -> Result<usize, diesel::result::Error> {
+        diesel::delete(run_call)
+            .filter(cromwell_job_id.eq(job_id))
+            .execute(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{NewRun, RunData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::unit_test_util::*;
+    use chrono::Utc;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn insert_test_run(conn: &PgConnection) -> RunData {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline 2"),
+            description: Some(String::from("Kevin made this pipeline for testing 2")),
+            created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's Template2"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing2")),
+            test_wdl: String::from("testtest"),
+            test_wdl_dependencies: None,
+            eval_wdl: String::from("evaltest"),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test2"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: Some(serde_json::from_str("{\"test\":\"test\"}").unwrap()),
+            test_option_defaults: None,
+            eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        let new_run = NewRun {
+            test_id: test.test_id,
+            name: String::from("Kevin's test run"),
+            status: RunStatusEnum::Succeeded,
+            test_input: serde_json::from_str("{\"test\":\"1\"}").unwrap(),
+            test_options: None,
+            eval_input: serde_json::from_str("{}").unwrap(),
+            eval_options: None,
+            test_cromwell_job_id: Some(String::from("123456789")),
+            eval_cromwell_job_id: Some(String::from("12345678901")),
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: Some(Utc::now().naive_utc()),
+        };
+
+        RunData::create(conn, new_run).expect("Failed inserting test run")
+    }
+
+    fn insert_test_run_calls(conn: &PgConnection) -> Vec<RunCallData> {
+        let run = insert_test_run(conn);
+
+        let new_run_calls = vec![
+            NewRunCall {
+                run_id: run.run_id,
+                workflow: RunCallWorkflowEnum::Test,
+                cromwell_job_id: String::from("123456789"),
+                task_name: String::from("test_workflow.hello"),
+                shard_index: -1,
+                attempt: 1,
+                execution_status: Some(String::from("Done")),
+                backend_status: Some(String::from("Done")),
+                return_code: Some(0),
+                docker_image: Some(String::from("ubuntu:latest")),
+                runtime_attributes: Some(json!({"cpu": "1", "memory": "2 GB"})),
+                started_at: Some(Utc::now().naive_utc()),
+                finished_at: Some(Utc::now().naive_utc()),
+            },
+            NewRunCall {
+                run_id: run.run_id,
+                workflow: RunCallWorkflowEnum::Eval,
+                cromwell_job_id: String::from("12345678901"),
+                task_name: String::from("eval_workflow.compare"),
+                shard_index: 0,
+                attempt: 1,
+                execution_status: Some(String::from("Failed")),
+                backend_status: Some(String::from("Failed")),
+                return_code: Some(1),
+                docker_image: Some(String::from("ubuntu:latest")),
+                runtime_attributes: None,
+                started_at: Some(Utc::now().naive_utc()),
+                finished_at: None,
+            },
+            NewRunCall {
+                run_id: run.run_id,
+                workflow: RunCallWorkflowEnum::Eval,
+                cromwell_job_id: String::from("12345678901"),
+                task_name: String::from("eval_workflow.compare"),
+                shard_index: 0,
+                attempt: 2,
+                execution_status: Some(String::from("Done")),
+                backend_status: Some(String::from("Done")),
+                return_code: Some(0),
+                docker_image: Some(String::from("ubuntu:latest")),
+                runtime_attributes: None,
+                started_at: Some(Utc::now().naive_utc()),
+                finished_at: Some(Utc::now().naive_utc()),
+            },
+        ];
+
+        RunCallData::batch_create(conn, new_run_calls).expect("Failed inserting test run_calls")
+    }
+
+    #[test]
+    fn find_by_id_exists() {
+        let conn = get_test_db_connection();
+
+        let test_run_calls = insert_test_run_calls(&conn);
+
+        let found_run_call = RunCallData::find_by_id(&conn, test_run_calls[0].run_call_id)
+            .expect("Failed to retrieve test run_call by id.");
+
+        assert_eq!(found_run_call, test_run_calls[0]);
+    }
+
+    #[test]
+    fn find_by_id_not_exists() {
+        let conn = get_test_db_connection();
+
+        let nonexistent_run_call = RunCallData::find_by_id(&conn, Uuid::new_v4());
+
+        assert!(matches!(
+            nonexistent_run_call,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn find_with_run_id_and_workflow() {
+        let conn = get_test_db_connection();
+
+        let test_run_calls = insert_test_run_calls(&conn);
+
+        let test_query = RunCallQuery {
+            run_id: Some(test_run_calls[0].run_id),
+            workflow: Some(RunCallWorkflowEnum::Eval),
+            cromwell_job_id: None,
+            task_name: None,
+            execution_status: None,
+            backend_status: None,
+            return_code: None,
+            docker_image: None,
+            started_before: None,
+            started_after: None,
+            finished_before: None,
+            finished_after: None,
+            sort: Some(String::from("desc(attempt)")),
+            limit: None,
+            offset: None,
+        };
+
+        let found_run_calls =
+            RunCallData::find(&conn, test_query).expect("Failed to find run_calls");
+
+        assert_eq!(found_run_calls.len(), 2);
+        assert_eq!(found_run_calls[0], test_run_calls[2]);
+        assert_eq!(found_run_calls[1], test_run_calls[1]);
+    }
+
+    #[test]
+    fn find_with_return_code() {
+        let conn = get_test_db_connection();
+
+        let test_run_calls = insert_test_run_calls(&conn);
+
+        let test_query = RunCallQuery {
+            run_id: None,
+            workflow: None,
+            cromwell_job_id: None,
+            task_name: None,
+            execution_status: None,
+            backend_status: None,
+            return_code: Some(1),
+            docker_image: None,
+            started_before: None,
+            started_after: None,
+            finished_before: None,
+            finished_after: None,
+            sort: None,
+            limit: None,
+            offset: None,
+        };
+
+        let found_run_calls =
+            RunCallData::find(&conn, test_query).expect("Failed to find run_calls");
+
+        assert_eq!(found_run_calls.len(), 1);
+        assert_eq!(found_run_calls[0], test_run_calls[1]);
+    }
+
+    #[test]
+    fn find_with_sort_and_limit_and_offset() {
+        let conn = get_test_db_connection();
+
+        let test_run_calls = insert_test_run_calls(&conn);
+
+        let test_query = RunCallQuery {
+            run_id: Some(test_run_calls[0].run_id),
+            workflow: None,
+            cromwell_job_id: None,
+            task_name: None,
+            execution_status: None,
+            backend_status: None,
+            return_code: None,
+            docker_image: None,
+            started_before: None,
+            started_after: None,
+            finished_before: None,
+            finished_after: None,
+            sort: Some(String::from("workflow,attempt")),
+            limit: Some(2),
+            offset: Some(1),
+        };
+
+        let found_run_calls =
+            RunCallData::find(&conn, test_query).expect("Failed to find run_calls");
+
+        assert_eq!(found_run_calls.len(), 2);
+        assert_eq!(found_run_calls[0], test_run_calls[1]);
+        assert_eq!(found_run_calls[1], test_run_calls[2]);
+    }
+
+    #[test]
+    fn batch_create_success() {
+        let conn = get_test_db_connection();
+
+        let test_run_calls = insert_test_run_calls(&conn);
+
+        assert_eq!(test_run_calls.len(), 3);
+        assert_eq!(test_run_calls[0].task_name, "test_workflow.hello");
+        assert_eq!(test_run_calls[0].shard_index, -1);
+        assert_eq!(
+            test_run_calls[0].runtime_attributes,
+            Some(json!({"cpu": "1", "memory": "2 GB"}))
+        );
+        assert_eq!(test_run_calls[1].return_code, Some(1));
+        assert_eq!(test_run_calls[2].attempt, 2);
+    }
+
+    #[test]
+    fn delete_by_run_id_success() {
+        let conn = get_test_db_connection();
+
+        let test_run_calls = insert_test_run_calls(&conn);
+
+        let delete_result = RunCallData::delete_by_run_id(&conn, test_run_calls[0].run_id).unwrap();
+
+        assert_eq!(delete_result, 3);
+
+        let deleted_run_call = RunCallData::find_by_id(&conn, test_run_calls[0].run_call_id);
+
+        assert!(matches!(
+            deleted_run_call,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn delete_by_cromwell_job_id_success() {
+        let conn = get_test_db_connection();
+
+        let test_run_calls = insert_test_run_calls(&conn);
+
+        let delete_result = RunCallData::delete_by_cromwell_job_id(&conn, "12345678901").unwrap();
+
+        assert_eq!(delete_result, 2);
+
+        let remaining_run_call = RunCallData::find_by_id(&conn, test_run_calls[0].run_call_id)
+            .expect("Failed to retrieve remaining run_call");
+
+        assert_eq!(remaining_run_call, test_run_calls[0]);
+    }
+}
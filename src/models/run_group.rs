@@ -0,0 +1,838 @@
+//! Contains structs and functions for doing operations on run groups.
+//!
+//! A run group is a named collection of runs that were started together (e.g. as part of a batch
+//! submission) so they can be tracked as a unit.  Runs are mapped to groups via the RUN_IN_GROUP
+//! table.  Once all of a group's runs have finished, a notification is sent (once) to its creator
+//! and subscribers.  Adding a run to the group resets this, so the notification is sent again once
+//! the new run finishes.  Represented in the database by the RUN_GROUP table.
+
+use crate::custom_sql_types::{RunStatusEnum, RUN_FAILURE_STATUSES};
+use crate::models::run_in_group::RunInGroupData;
+use crate::schema::run;
+use crate::schema::run_group;
+use crate::schema::run_group::dsl::*;
+use crate::schema::run_in_group;
+use crate::util;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Mapping to a run group as it exists in the RUN_GROUP table in the database.
+///
+/// An instance of this struct will be returned by any queries for run groups.
+#[derive(Queryable, Deserialize, Serialize, PartialEq, Debug)]
+pub struct RunGroupData {
+    pub run_group_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub created_by: Option<String>,
+    pub notified_at: Option<NaiveDateTime>,
+}
+
+/// Represents the aggregated status of the runs in a run group
+///
+/// `succeeded` and `failed` count runs that have finished successfully or unsuccessfully
+/// (including aborted runs), respectively, and `in_progress` counts runs that have not finished.
+/// `all_finished` is true if the group contains at least one run and all of its runs have finished
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct RunGroupStatusData {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub in_progress: usize,
+    pub all_finished: bool,
+}
+
+/// Represents a run group along with the aggregated status of its runs
+///
+/// An instance of this struct will be returned by any queries for run groups with statuses
+#[derive(Deserialize, Serialize, PartialEq, Debug)]
+pub struct RunGroupWithStatusData {
+    pub run_group_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub created_by: Option<String>,
+    pub notified_at: Option<NaiveDateTime>,
+    pub status: RunGroupStatusData,
+}
+
+/// Represents all possible parameters for a query of the RUN_GROUP table
+///
+/// All values are optional, so any combination can be used during a query.  Limit and offset are
+/// used for pagination.  Sort expects a comma-separated list of sort keys, optionally enclosed
+/// with either asc() or desc().  For example: asc(name),desc(description),run_group_id
+#[derive(Deserialize, Serialize)]
+pub struct RunGroupQuery {
+    pub run_group_id: Option<Uuid>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub created_before: Option<NaiveDateTime>,
+    pub created_after: Option<NaiveDateTime>,
+    pub created_by: Option<String>,
+    pub sort: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// A new run group to be inserted into the DB
+///
+/// name is a required field, but description and created_by are not, so can be filled with `None`
+/// run_group_id and created_at are populated automatically by the DB
+#[derive(Deserialize, Serialize, Insertable)]
+#[table_name = "run_group"]
+pub struct NewRunGroup {
+    pub name: String,
+    pub description: Option<String>,
+    pub created_by: Option<String>,
+}
+
+/// Represents fields to change when updating a run group
+///
+/// Only name and description can be modified after the run group has been created
+#[derive(Deserialize, Serialize, AsChangeset, Debug)]
+#[table_name = "run_group"]
+pub struct RunGroupChangeset {
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+impl RunGroupData {
+    /// Queries the DB for a run group with the specified id
+    ///
+    /// Queries the DB using `conn` to retrieve the first row with a run_group_id value of `id`
+    /// Returns a result containing either the retrieved run group as a RunGroupData instance
+    /// or an error if the query fails for some reason or if no run group is found matching the
+    /// criteria
+    pub fn find_by_id(conn: &PgConnection, id: Uuid) -> Result<Self, diesel::result::Error> {
+        run_group.filter(run_group_id.eq(id)).first::<Self>(conn)
+    }
+
+    /// Queries the DB for run groups matching the specified query criteria
+    ///
+    /// Queries the DB using `conn` to retrieve run groups matching the criteria in `params`
+    /// Returns a result containing either a vector of the retrieved run groups as RunGroupData
+    /// instances or an error if the query fails for some reason
+    pub fn find(
+        conn: &PgConnection,
+        params: RunGroupQuery,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        // Put the query into a box (pointer) so it can be built dynamically
+        let mut query = run_group.into_boxed();
+
+        // Add filters for each of the params if they have values
+        if let Some(param) = params.run_group_id {
+            query = query.filter(run_group_id.eq(param));
+        }
+        if let Some(param) = params.name {
+            query = query.filter(name.eq(param));
+        }
+        if let Some(param) = params.description {
+            query = query.filter(description.eq(param));
+        }
+        if let Some(param) = params.created_before {
+            query = query.filter(created_at.lt(param));
+        }
+        if let Some(param) = params.created_after {
+            query = query.filter(created_at.gt(param));
+        }
+        if let Some(param) = params.created_by {
+            query = query.filter(created_by.eq(param));
+        }
+
+        // If there is a sort param, parse it and add to the order by clause accordingly
+        if let Some(sort) = params.sort {
+            let sort = util::sort_string::parse_sort_string(&sort);
+            for sort_clause in sort {
+                match &*sort_clause.key {
+                    "run_group_id" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(run_group_id.asc());
+                        } else {
+                            query = query.then_order_by(run_group_id.desc());
+                        }
+                    }
+                    "name" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(name.asc());
+                        } else {
+                            query = query.then_order_by(name.desc());
+                        }
+                    }
+                    "description" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(description.asc());
+                        } else {
+                            query = query.then_order_by(description.desc());
+                        }
+                    }
+                    "created_at" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(created_at.asc());
+                        } else {
+                            query = query.then_order_by(created_at.desc());
+                        }
+                    }
+                    "created_by" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(created_by.asc());
+                        } else {
+                            query = query.then_order_by(created_by.desc());
+                        }
+                    }
+                    // Don't add to the order by clause if the sort key isn't recognized
+                    &_ => {}
+                }
+            }
+        }
+
+        if let Some(param) = params.limit {
+            query = query.limit(param);
+        }
+        if let Some(param) = params.offset {
+            query = query.offset(param);
+        }
+
+        // Perform the query
+        query.load::<Self>(conn)
+    }
+
+    /// Queries the DB for run groups that contain the run specified by `id`
+    ///
+    /// Returns a result containing either a vector of the retrieved run groups as RunGroupData
+    /// instances or an error if the query fails for some reason
+    pub fn find_for_run(conn: &PgConnection, id: Uuid) -> Result<Vec<Self>, diesel::result::Error> {
+        let group_subquery = run_in_group::dsl::run_in_group
+            .filter(run_in_group::dsl::run_id.eq(id))
+            .select(run_in_group::dsl::run_group_id);
+
+        run_group
+            .filter(run_group_id.eq_any(group_subquery))
+            .load::<Self>(conn)
+    }
+
+    /// Inserts a new run group into the DB
+    ///
+    /// Creates a new run group row in the DB using `conn` with the values specified in `params`
+    /// Returns a result containing either the new run group that was created or an error if the
+    /// insert fails for some reason
+    pub fn create(conn: &PgConnection, params: NewRunGroup) -> Result<Self, diesel::result::Error> {
+        diesel::insert_into(run_group)
+            .values(&params)
+            .get_result(conn)
+    }
+
+    /// Updates a specified run group in the DB
+    ///
+    /// Updates the run group row in the DB using `conn` specified by `id` with the values in
+    /// `params`
+    /// Returns a result containing either the newly updated run group or an error if the update
+    /// fails for some reason
+    pub fn update(
+        conn: &PgConnection,
+        id: Uuid,
+        params: RunGroupChangeset,
+    ) -> Result<Self, diesel::result::Error> {
+        diesel::update(run_group.filter(run_group_id.eq(id)))
+            .set(params)
+            .get_result(conn)
+    }
+
+    /// Claims the completion notification for the run group specified by `id`
+    ///
+    /// Sets notified_at for the run group in the DB using `conn` to the current time, but only if
+    /// it hasn't already been set, so only one caller can claim the notification even if multiple
+    /// runs in the group finish at the same time.  Returns a result containing either the updated
+    /// run group if the claim succeeded, None if the notification was already claimed (or the run
+    /// group doesn't exist), or an error if the update fails for some reason
+    pub fn claim_completion_notification(
+        conn: &PgConnection,
+        id: Uuid,
+    ) -> Result<Option<Self>, diesel::result::Error> {
+        diesel::update(
+            run_group
+                .filter(run_group_id.eq(id))
+                .filter(notified_at.is_null()),
+        )
+        .set(notified_at.eq(diesel::dsl::now))
+        .get_result(conn)
+        .optional()
+    }
+
+    /// Clears the completion notification for the run group specified by `id`
+    ///
+    /// Sets notified_at for the run group in the DB using `conn` back to null, so a notification
+    /// will be sent again once all of its runs have finished.  This should be done whenever a run
+    /// is added to the group.  Returns a result containing either the number of run groups updated
+    /// or an error if the update fails for some reason
+    pub fn clear_completion_notification(
+        conn: &PgConnection,
+        id: Uuid,
+    ) -> Result<usize, diesel::result::Error> {
+        diesel::update(run_group.filter(run_group_id.eq(id)))
+            .set(notified_at.eq(None::<NaiveDateTime>))
+            .execute(conn)
+    }
+
+    /// Deletes a specific run group in the DB
+    ///
+    /// Deletes the run group row in the DB using `conn` specified by `id`, along with the rows
+    /// mapping runs to it.  The runs themselves are not deleted.  Returns a result containing either
+    /// the number of run group rows deleted or an error if the delete fails for some reason
+    pub fn delete(conn: &PgConnection, id: Uuid) -> Result<usize, diesel::result::Error> {
+        // Do all the actual deleting in a closure so we can run it in a transaction
+        let delete_closure = || {
+            // Delete the run_in_group rows mapping runs to this group
+            RunInGroupData::delete_by_run_group_id(conn, id)?;
+            // Delete and return result
+            diesel::delete(run_group.filter(run_group_id.eq(id))).execute(conn)
+        };
+        // Do the delete in a transaction
+        #[cfg(not(test))]
+        return conn.build_transaction().run(|| delete_closure());
+
+        // Tests do all database stuff in transactions that are not committed, and you can't have a
+        // transaction within a transaction, so, for tests, we don't run this in a transaction
+        #[cfg(test)]
+        return delete_closure();
+    }
+}
+
+impl RunGroupStatusData {
+    /// Aggregates the statuses of the runs in the run group specified by `id`
+    ///
+    /// Queries the DB using `conn` to retrieve the statuses of the runs in the run group and counts
+    /// how many have succeeded, failed, or are still in progress.  Returns a result containing
+    /// either the aggregated status or an error if the query fails for some reason
+    pub fn find_for_run_group(
+        conn: &PgConnection,
+        id: Uuid,
+    ) -> Result<Self, diesel::result::Error> {
+        let run_statuses: Vec<RunStatusEnum> = run_in_group::dsl::run_in_group
+            .inner_join(run::dsl::run)
+            .filter(run_in_group::dsl::run_group_id.eq(id))
+            .select(run::dsl::status)
+            .load::<RunStatusEnum>(conn)?;
+
+        let total = run_statuses.len();
+        let succeeded = run_statuses
+            .iter()
+            .filter(|run_status| **run_status == RunStatusEnum::Succeeded)
+            .count();
+        let failed = run_statuses
+            .iter()
+            .filter(|run_status| RUN_FAILURE_STATUSES.contains(run_status))
+            .count();
+        let in_progress = total - succeeded - failed;
+
+        Ok(RunGroupStatusData {
+            total,
+            succeeded,
+            failed,
+            in_progress,
+            all_finished: total > 0 && in_progress == 0,
+        })
+    }
+}
+
+impl RunGroupWithStatusData {
+    /// Queries the DB for a run group with the specified id, along with the aggregated status of
+    /// its runs
+    ///
+    /// Returns a result containing either the retrieved run group as a RunGroupWithStatusData
+    /// instance or an error if the query fails for some reason or if no run group is found
+    /// matching the criteria
+    pub fn find_by_id(conn: &PgConnection, id: Uuid) -> Result<Self, diesel::result::Error> {
+        let group = RunGroupData::find_by_id(conn, id)?;
+        RunGroupWithStatusData::from_run_group(conn, group)
+    }
+
+    /// Queries the DB for run groups matching the specified query criteria, along with the
+    /// aggregated status of each group's runs
+    ///
+    /// Returns a result containing either a vector of the retrieved run groups as
+    /// RunGroupWithStatusData instances or an error if the query fails for some reason
+    pub fn find(
+        conn: &PgConnection,
+        params: RunGroupQuery,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        RunGroupData::find(conn, params)?
+            .into_iter()
+            .map(|group| RunGroupWithStatusData::from_run_group(conn, group))
+            .collect()
+    }
+
+    /// Builds a RunGroupWithStatusData from `group` by querying the DB using `conn` for the
+    /// aggregated status of its runs
+    pub fn from_run_group(
+        conn: &PgConnection,
+        group: RunGroupData,
+    ) -> Result<Self, diesel::result::Error> {
+        let group_status = RunGroupStatusData::find_for_run_group(conn, group.run_group_id)?;
+
+        Ok(RunGroupWithStatusData {
+            run_group_id: group.run_group_id,
+            name: group.name,
+            description: group.description,
+            created_at: group.created_at,
+            created_by: group.created_by,
+            notified_at: group.notified_at,
+            status: group_status,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{NewRun, RunData};
+    use crate::models::run_in_group::NewRunInGroup;
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::unit_test_util::*;
+    use uuid::Uuid;
+
+    fn insert_test_run_groups(conn: &PgConnection) -> Vec<RunGroupData> {
+        let mut run_groups = Vec::new();
+
+        for (group_name, group_description) in [
+            ("Group A", Some("First test group")),
+            ("Group B", None),
+            ("Group C", Some("Third test group")),
+        ] {
+            let new_run_group = NewRunGroup {
+                name: String::from(group_name),
+                description: group_description.map(String::from),
+                created_by: Some(String::from("Kevin@example.com")),
+            };
+
+            run_groups.push(
+                RunGroupData::create(conn, new_run_group).expect("Failed inserting test run_group"),
+            );
+        }
+
+        run_groups
+    }
+
+    fn insert_test_runs_in_group_with_statuses(
+        conn: &PgConnection,
+        id: Uuid,
+        statuses: &[RunStatusEnum],
+    ) -> Vec<RunData> {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's Template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing")),
+            test_wdl: String::from("testtest"),
+            test_wdl_dependencies: None,
+            eval_wdl: String::from("evaltest"),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: None,
+            test_option_defaults: None,
+            eval_input_defaults: None,
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        let mut runs = Vec::new();
+
+        for (index, run_status) in statuses.iter().enumerate() {
+            let new_run = NewRun {
+                test_id: test.test_id,
+                name: format!("Kevin's run {}", index),
+                status: run_status.clone(),
+                test_input: serde_json::from_str("{}").unwrap(),
+                test_options: None,
+                eval_input: serde_json::from_str("{}").unwrap(),
+                eval_options: None,
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                created_by: Some(String::from("Kevin@example.com")),
+                finished_at: None,
+            };
+
+            let run = RunData::create(conn, new_run).expect("Failed inserting test run");
+
+            RunInGroupData::create(
+                conn,
+                NewRunInGroup {
+                    run_id: run.run_id,
+                    run_group_id: id,
+                },
+            )
+            .expect("Failed inserting test run_in_group");
+
+            runs.push(run);
+        }
+
+        runs
+    }
+
+    fn insert_test_run_group(conn: &PgConnection) -> RunGroupData {
+        let new_run_group = NewRunGroup {
+            name: String::from("Kevin's Run Group"),
+            description: Some(String::from("Kevin made this run group for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        RunGroupData::create(conn, new_run_group).expect("Failed inserting test run_group")
+    }
+
+    #[test]
+    fn find_by_id_exists() {
+        let conn = get_test_db_connection();
+
+        let test_run_group = insert_test_run_group(&conn);
+
+        let found_run_group = RunGroupData::find_by_id(&conn, test_run_group.run_group_id)
+            .expect("Failed to retrieve test run_group by id.");
+
+        assert_eq!(found_run_group, test_run_group);
+    }
+
+    #[test]
+    fn find_by_id_not_exists() {
+        let conn = get_test_db_connection();
+
+        let nonexistent_run_group = RunGroupData::find_by_id(&conn, Uuid::new_v4());
+
+        assert!(matches!(
+            nonexistent_run_group,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn create_success() {
+        let conn = get_test_db_connection();
+
+        let test_run_group = insert_test_run_group(&conn);
+
+        assert_eq!(test_run_group.name, "Kevin's Run Group");
+        assert_eq!(
+            test_run_group
+                .description
+                .expect("Inserted run_group missing description"),
+            "Kevin made this run group for testing"
+        );
+        assert_eq!(
+            test_run_group
+                .created_by
+                .expect("Inserted run_group missing created_by"),
+            "Kevin@example.com"
+        );
+    }
+
+    #[test]
+    fn create_failure_same_name() {
+        let conn = get_test_db_connection();
+
+        insert_test_run_group(&conn);
+
+        let copy_run_group = NewRunGroup {
+            name: String::from("Kevin's Run Group"),
+            description: None,
+            created_by: None,
+        };
+
+        let new_run_group = RunGroupData::create(&conn, copy_run_group);
+
+        assert!(matches!(
+            new_run_group,
+            Err(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            ),)
+        ));
+    }
+
+    #[test]
+    fn find_with_name() {
+        let conn = get_test_db_connection();
+
+        let test_run_groups = insert_test_run_groups(&conn);
+
+        let test_query = RunGroupQuery {
+            run_group_id: None,
+            name: Some(String::from("Group B")),
+            description: None,
+            created_before: None,
+            created_after: None,
+            created_by: None,
+            sort: None,
+            limit: None,
+            offset: None,
+        };
+
+        let found_run_groups =
+            RunGroupData::find(&conn, test_query).expect("Failed to find run_groups");
+
+        assert_eq!(found_run_groups.len(), 1);
+        assert_eq!(found_run_groups[0], test_run_groups[1]);
+    }
+
+    #[test]
+    fn find_with_sort_and_limit_and_offset() {
+        let conn = get_test_db_connection();
+
+        let test_run_groups = insert_test_run_groups(&conn);
+
+        let test_query = RunGroupQuery {
+            run_group_id: None,
+            name: None,
+            description: None,
+            created_before: None,
+            created_after: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            sort: Some(String::from("desc(name)")),
+            limit: Some(2),
+            offset: Some(1),
+        };
+
+        let found_run_groups =
+            RunGroupData::find(&conn, test_query).expect("Failed to find run_groups");
+
+        assert_eq!(found_run_groups.len(), 2);
+        assert_eq!(found_run_groups[0], test_run_groups[1]);
+        assert_eq!(found_run_groups[1], test_run_groups[0]);
+    }
+
+    #[test]
+    fn find_for_run_success() {
+        let conn = get_test_db_connection();
+
+        let test_run_groups = insert_test_run_groups(&conn);
+        let test_runs = insert_test_runs_in_group_with_statuses(
+            &conn,
+            test_run_groups[0].run_group_id,
+            &[RunStatusEnum::Succeeded],
+        );
+        RunInGroupData::create(
+            &conn,
+            NewRunInGroup {
+                run_id: test_runs[0].run_id,
+                run_group_id: test_run_groups[2].run_group_id,
+            },
+        )
+        .expect("Failed inserting test run_in_group");
+
+        let mut found_run_groups = RunGroupData::find_for_run(&conn, test_runs[0].run_id)
+            .expect("Failed to find run_groups for run");
+        found_run_groups.sort_by(|a, b| a.name.cmp(&b.name));
+
+        assert_eq!(found_run_groups.len(), 2);
+        assert_eq!(found_run_groups[0], test_run_groups[0]);
+        assert_eq!(found_run_groups[1], test_run_groups[2]);
+    }
+
+    #[test]
+    fn update_success() {
+        let conn = get_test_db_connection();
+
+        let test_run_group = insert_test_run_group(&conn);
+
+        let changes = RunGroupChangeset {
+            name: Some(String::from("TestTestTestTest")),
+            description: Some(String::from("TESTTESTTESTTEST")),
+        };
+
+        let updated_run_group = RunGroupData::update(&conn, test_run_group.run_group_id, changes)
+            .expect("Failed to update run_group");
+
+        assert_eq!(updated_run_group.name, String::from("TestTestTestTest"));
+        assert_eq!(
+            updated_run_group.description.unwrap(),
+            String::from("TESTTESTTESTTEST")
+        );
+    }
+
+    #[test]
+    fn claim_completion_notification_success() {
+        let conn = get_test_db_connection();
+
+        let test_run_group = insert_test_run_group(&conn);
+        assert!(test_run_group.notified_at.is_none());
+
+        let claimed_run_group =
+            RunGroupData::claim_completion_notification(&conn, test_run_group.run_group_id)
+                .expect("Failed to claim run group notification")
+                .expect("Run group notification not claimed");
+
+        assert_eq!(claimed_run_group.run_group_id, test_run_group.run_group_id);
+        assert!(claimed_run_group.notified_at.is_some());
+
+        // It can only be claimed once
+        let second_claim =
+            RunGroupData::claim_completion_notification(&conn, test_run_group.run_group_id)
+                .expect("Failed to claim run group notification");
+
+        assert!(second_claim.is_none());
+    }
+
+    #[test]
+    fn delete_success() {
+        let conn = get_test_db_connection();
+
+        let test_run_group = insert_test_run_group(&conn);
+        let test_runs = insert_test_runs_in_group_with_statuses(
+            &conn,
+            test_run_group.run_group_id,
+            &[RunStatusEnum::Succeeded, RunStatusEnum::TestRunning],
+        );
+
+        let delete_result = RunGroupData::delete(&conn, test_run_group.run_group_id).unwrap();
+
+        assert_eq!(delete_result, 1);
+
+        let deleted_run_group = RunGroupData::find_by_id(&conn, test_run_group.run_group_id);
+
+        assert!(matches!(
+            deleted_run_group,
+            Err(diesel::result::Error::NotFound)
+        ));
+
+        // The runs themselves should still exist
+        for test_run in test_runs {
+            RunData::find_by_id(&conn, test_run.run_id).expect("Run deleted with its group");
+        }
+    }
+
+    #[test]
+    fn find_status_for_run_group_success() {
+        let conn = get_test_db_connection();
+
+        let test_run_group = insert_test_run_group(&conn);
+        insert_test_runs_in_group_with_statuses(
+            &conn,
+            test_run_group.run_group_id,
+            &[
+                RunStatusEnum::Succeeded,
+                RunStatusEnum::Succeeded,
+                RunStatusEnum::TestFailed,
+                RunStatusEnum::EvalAborted,
+                RunStatusEnum::TestRunning,
+            ],
+        );
+
+        let group_status =
+            RunGroupStatusData::find_for_run_group(&conn, test_run_group.run_group_id)
+                .expect("Failed to retrieve run group status");
+
+        assert_eq!(
+            group_status,
+            RunGroupStatusData {
+                total: 5,
+                succeeded: 2,
+                failed: 2,
+                in_progress: 1,
+                all_finished: false,
+            }
+        );
+    }
+
+    #[test]
+    fn find_status_for_run_group_all_finished() {
+        let conn = get_test_db_connection();
+
+        let test_run_group = insert_test_run_group(&conn);
+        insert_test_runs_in_group_with_statuses(
+            &conn,
+            test_run_group.run_group_id,
+            &[RunStatusEnum::Succeeded, RunStatusEnum::CarrotFailed],
+        );
+
+        let group_status =
+            RunGroupStatusData::find_for_run_group(&conn, test_run_group.run_group_id)
+                .expect("Failed to retrieve run group status");
+
+        assert_eq!(
+            group_status,
+            RunGroupStatusData {
+                total: 2,
+                succeeded: 1,
+                failed: 1,
+                in_progress: 0,
+                all_finished: true,
+            }
+        );
+    }
+
+    #[test]
+    fn find_status_for_run_group_empty() {
+        let conn = get_test_db_connection();
+
+        let test_run_group = insert_test_run_group(&conn);
+
+        let group_status =
+            RunGroupStatusData::find_for_run_group(&conn, test_run_group.run_group_id)
+                .expect("Failed to retrieve run group status");
+
+        assert_eq!(
+            group_status,
+            RunGroupStatusData {
+                total: 0,
+                succeeded: 0,
+                failed: 0,
+                in_progress: 0,
+                all_finished: false,
+            }
+        );
+    }
+
+    #[test]
+    fn find_with_status_by_id_exists() {
+        let conn = get_test_db_connection();
+
+        let test_run_group = insert_test_run_group(&conn);
+        insert_test_runs_in_group_with_statuses(
+            &conn,
+            test_run_group.run_group_id,
+            &[RunStatusEnum::Succeeded, RunStatusEnum::TestSubmitted],
+        );
+
+        let found_run_group =
+            RunGroupWithStatusData::find_by_id(&conn, test_run_group.run_group_id)
+                .expect("Failed to retrieve test run_group with status by id.");
+
+        assert_eq!(found_run_group.run_group_id, test_run_group.run_group_id);
+        assert_eq!(found_run_group.name, test_run_group.name);
+        assert_eq!(found_run_group.status.total, 2);
+        assert_eq!(found_run_group.status.succeeded, 1);
+        assert_eq!(found_run_group.status.in_progress, 1);
+    }
+}
@@ -0,0 +1,421 @@
+//! Contains structs and functions for doing operations on RUN_IN_GROUP records.
+//!
+//! A run_in_group record represents that a specific run is a member of a specific run group.
+//! Represented in the database by the RUN_IN_GROUP table.
+
+use crate::models::run_group::RunGroupData;
+use crate::schema::run_in_group;
+use crate::schema::run_in_group::dsl::*;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Mapping to a run_in_group as it exists in the RUN_IN_GROUP table in the database.
+///
+/// An instance of this struct will be returned by any queries for run_in_groups.
+#[derive(Queryable, Deserialize, Serialize, PartialEq, Debug)]
+pub struct RunInGroupData {
+    pub run_id: Uuid,
+    pub run_group_id: Uuid,
+    pub created_at: NaiveDateTime,
+}
+
+/// A new run_in_group to be inserted into the DB
+///
+/// run_id and run_group_id are both required fields; created_at is populated automatically by the
+/// DB
+#[derive(Deserialize, Serialize, Insertable)]
+#[table_name = "run_in_group"]
+pub struct NewRunInGroup {
+    pub run_id: Uuid,
+    pub run_group_id: Uuid,
+}
+
+impl RunInGroupData {
+    /// Queries the DB for a run_in_group with the specified run_id and run_group_id
+    ///
+    /// Queries the DB using `conn` to retrieve the first row with a run_id value of
+    /// `query_run_id` and a run_group_id value of `query_run_group_id`.  Returns a result
+    /// containing either the retrieved run_in_group as a RunInGroupData instance or an error if the
+    /// query fails for some reason or if no run_in_group is found matching the criteria
+    ///
+    /// This is function is currently not in use, but it's functionality will likely be necessary in
+    /// the future, so it is included
+    #[allow(dead_code)]
+    pub fn find_by_run_and_run_group(
+        conn: &PgConnection,
+        query_run_id: Uuid,
+        query_run_group_id: Uuid,
+    ) -> Result<Self, diesel::result::Error> {
+        run_in_group
+            .filter(run_id.eq(query_run_id))
+            .filter(run_group_id.eq(query_run_group_id))
+            .first::<Self>(conn)
+    }
+
+    /// Queries the DB for run_in_groups for the run group specified by `id`
+    ///
+    /// Queries the DB using `conn` to retrieve rows with a run_group_id value of `id`, sorted by
+    /// created_at.  Returns a result containing either a vector of the retrieved run_in_groups as
+    /// RunInGroupData instances or an error if the query fails for some reason
+    pub fn find_by_run_group_id(
+        conn: &PgConnection,
+        id: Uuid,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        run_in_group
+            .filter(run_group_id.eq(id))
+            .order_by(created_at.asc())
+            .load::<Self>(conn)
+    }
+
+    /// Inserts a new run_in_group into the DB
+    ///
+    /// Creates a new run_in_group row in the DB using `conn` with the values specified in `params`
+    /// and clears the completion notification for the run group (see
+    /// `RunGroupData::clear_completion_notification`), since it has a new run that hasn't finished
+    /// yet.  Returns a result containing either the new run_in_group that was created or an error
+    /// if the insert fails for some reason
+    pub fn create(
+        conn: &PgConnection,
+        params: NewRunInGroup,
+    ) -> Result<Self, diesel::result::Error> {
+        let new_run_in_group = diesel::insert_into(run_in_group)
+            .values(&params)
+            .get_result::<Self>(conn)?;
+        RunGroupData::clear_completion_notification(conn, new_run_in_group.run_group_id)?;
+        Ok(new_run_in_group)
+    }
+
+    /// Deletes the run_in_group row from the DB mapping the run specified by `delete_run_id` to the
+    /// run group specified by `delete_run_group_id`
+    ///
+    /// Returns either the number of run_in_group rows deleted, or an error if something goes wrong
+    /// during the delete
+    pub fn delete(
+        conn: &PgConnection,
+        delete_run_id: Uuid,
+        delete_run_group_id: Uuid,
+    ) -> Result<usize, diesel::result::Error> {
+        diesel::delete(run_in_group)
+            .filter(run_id.eq(delete_run_id))
+            .filter(run_group_id.eq(delete_run_group_id))
+            .execute(conn)
+    }
+
+    /// Deletes run_in_group rows from the DB that are mapped to the run group specified by `id`
+    ///
+    /// Returns either the number of run_in_group rows deleted, or an error if something goes wrong
+    /// during the delete
+    pub fn delete_by_run_group_id(
+        conn: &PgConnection,
+        id: Uuid,
+    ) -> Result<usize, diesel::result::Error> {
+        diesel::delete(run_in_group)
+            .filter(run_group_id.eq(id))
+            .execute(conn)
+    }
+
+    /// Deletes run_in_group rows from the DB that are mapped to the run specified by `id`
+    ///
+    /// Returns either the number of run_in_group rows deleted, or an error if something goes wrong
+    /// during the delete
+    pub fn delete_by_run_id(conn: &PgConnection, id: Uuid) -> Result<usize, diesel::result::Error> {
+        diesel::delete(run_in_group)
+            .filter(run_id.eq(id))
+            .execute(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{NewRun, RunData};
+    use crate::models::run_group::{NewRunGroup, RunGroupData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::unit_test_util::*;
+    use uuid::Uuid;
+
+    fn insert_test_runs_and_group(conn: &PgConnection) -> (Vec<RunData>, RunGroupData) {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's Template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing")),
+            test_wdl: String::from("testtest"),
+            test_wdl_dependencies: None,
+            eval_wdl: String::from("evaltest"),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: Some(serde_json::from_str("{\"test\":\"test\"}").unwrap()),
+            test_option_defaults: None,
+            eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        let mut runs = Vec::new();
+
+        for run_name in ["name1", "name2"] {
+            let new_run = NewRun {
+                test_id: test.test_id,
+                name: String::from(run_name),
+                status: RunStatusEnum::TestSubmitted,
+                test_input: serde_json::from_str("{}").unwrap(),
+                test_options: None,
+                eval_input: serde_json::from_str("{\"test\":\"2\"}").unwrap(),
+                eval_options: None,
+                test_cromwell_job_id: Some(String::from("1234567890")),
+                eval_cromwell_job_id: None,
+                created_by: Some(String::from("Kevin@example.com")),
+                finished_at: None,
+            };
+
+            runs.push(RunData::create(conn, new_run).expect("Failed inserting test run"));
+        }
+
+        let new_run_group = NewRunGroup {
+            name: String::from("Kevin's Run Group"),
+            description: None,
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        let run_group =
+            RunGroupData::create(conn, new_run_group).expect("Failed inserting test run_group");
+
+        (runs, run_group)
+    }
+
+    fn insert_test_run_in_groups(conn: &PgConnection) -> (RunGroupData, Vec<RunInGroupData>) {
+        let (runs, run_group) = insert_test_runs_and_group(conn);
+
+        let mut run_in_groups = Vec::new();
+
+        for run in runs {
+            let new_run_in_group = NewRunInGroup {
+                run_id: run.run_id,
+                run_group_id: run_group.run_group_id,
+            };
+
+            run_in_groups.push(
+                RunInGroupData::create(conn, new_run_in_group)
+                    .expect("Failed inserting test run_in_group"),
+            );
+        }
+
+        (run_group, run_in_groups)
+    }
+
+    #[test]
+    fn find_by_run_group_id_success() {
+        let conn = get_test_db_connection();
+
+        let (test_run_group, test_run_in_groups) = insert_test_run_in_groups(&conn);
+
+        let found_run_in_groups =
+            RunInGroupData::find_by_run_group_id(&conn, test_run_group.run_group_id)
+                .expect("Failed to retrieve test run_in_groups by run_group_id.");
+
+        assert_eq!(found_run_in_groups, test_run_in_groups);
+    }
+
+    #[test]
+    fn find_by_run_group_id_none() {
+        let conn = get_test_db_connection();
+
+        insert_test_run_in_groups(&conn);
+
+        let found_run_in_groups = RunInGroupData::find_by_run_group_id(&conn, Uuid::new_v4())
+            .expect("Failed to retrieve test run_in_groups by run_group_id.");
+
+        assert!(found_run_in_groups.is_empty());
+    }
+
+    #[test]
+    fn create_success() {
+        let conn = get_test_db_connection();
+
+        let (test_runs, test_run_group) = insert_test_runs_and_group(&conn);
+
+        let new_run_in_group = NewRunInGroup {
+            run_id: test_runs[0].run_id,
+            run_group_id: test_run_group.run_group_id,
+        };
+
+        let test_run_in_group = RunInGroupData::create(&conn, new_run_in_group)
+            .expect("Failed inserting test run_in_group");
+
+        assert_eq!(test_run_in_group.run_id, test_runs[0].run_id);
+        assert_eq!(test_run_in_group.run_group_id, test_run_group.run_group_id);
+    }
+
+    #[test]
+    fn create_clears_completion_notification() {
+        let conn = get_test_db_connection();
+
+        let (test_runs, test_run_group) = insert_test_runs_and_group(&conn);
+        RunGroupData::claim_completion_notification(&conn, test_run_group.run_group_id)
+            .unwrap()
+            .expect("Run group notification not claimed");
+
+        let new_run_in_group = NewRunInGroup {
+            run_id: test_runs[0].run_id,
+            run_group_id: test_run_group.run_group_id,
+        };
+        RunInGroupData::create(&conn, new_run_in_group)
+            .expect("Failed inserting test run_in_group");
+
+        // The group has a new run, so it should be notified again once that run finishes
+        let found_run_group = RunGroupData::find_by_id(&conn, test_run_group.run_group_id).unwrap();
+        assert!(found_run_group.notified_at.is_none());
+    }
+
+    #[test]
+    fn delete_by_run_id_success() {
+        let conn = get_test_db_connection();
+
+        let (test_run_group, test_run_in_groups) = insert_test_run_in_groups(&conn);
+
+        let delete_result =
+            RunInGroupData::delete_by_run_id(&conn, test_run_in_groups[0].run_id).unwrap();
+
+        assert_eq!(delete_result, 1);
+
+        let found_run_in_groups =
+            RunInGroupData::find_by_run_group_id(&conn, test_run_group.run_group_id)
+                .expect("Failed to retrieve test run_in_groups by run_group_id.");
+
+        assert_eq!(found_run_in_groups.len(), 1);
+        assert_eq!(found_run_in_groups[0], test_run_in_groups[1]);
+    }
+
+    #[test]
+    fn find_by_run_and_run_group_exists() {
+        let conn = get_test_db_connection();
+
+        let (test_run_group, test_run_in_groups) = insert_test_run_in_groups(&conn);
+
+        let found_run_in_group = RunInGroupData::find_by_run_and_run_group(
+            &conn,
+            test_run_in_groups[1].run_id,
+            test_run_group.run_group_id,
+        )
+        .expect("Failed to retrieve test run_in_group by run_id and run_group_id.");
+
+        assert_eq!(found_run_in_group, test_run_in_groups[1]);
+    }
+
+    #[test]
+    fn find_by_run_and_run_group_not_exists() {
+        let conn = get_test_db_connection();
+
+        let (_, test_run_in_groups) = insert_test_run_in_groups(&conn);
+
+        let nonexistent_run_in_group = RunInGroupData::find_by_run_and_run_group(
+            &conn,
+            test_run_in_groups[1].run_id,
+            Uuid::new_v4(),
+        );
+
+        assert!(matches!(
+            nonexistent_run_in_group,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn create_failure_no_run_group() {
+        let conn = get_test_db_connection();
+
+        let (test_runs, _) = insert_test_runs_and_group(&conn);
+
+        let new_run_in_group = NewRunInGroup {
+            run_id: test_runs[0].run_id,
+            run_group_id: Uuid::new_v4(),
+        };
+
+        let test_run_in_group = RunInGroupData::create(&conn, new_run_in_group);
+
+        assert!(matches!(
+            test_run_in_group,
+            Err(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::ForeignKeyViolation,
+                _,
+            ),)
+        ));
+    }
+
+    #[test]
+    fn delete_success() {
+        let conn = get_test_db_connection();
+
+        let (test_run_group, test_run_in_groups) = insert_test_run_in_groups(&conn);
+
+        let delete_result = RunInGroupData::delete(
+            &conn,
+            test_run_in_groups[1].run_id,
+            test_run_group.run_group_id,
+        )
+        .unwrap();
+
+        assert_eq!(delete_result, 1);
+
+        let found_run_in_groups =
+            RunInGroupData::find_by_run_group_id(&conn, test_run_group.run_group_id)
+                .expect("Failed to retrieve test run_in_groups by run_group_id.");
+
+        assert_eq!(found_run_in_groups.len(), 1);
+        assert_eq!(found_run_in_groups[0], test_run_in_groups[0]);
+    }
+
+    #[test]
+    fn delete_by_run_group_id_success() {
+        let conn = get_test_db_connection();
+
+        let (test_run_group, _) = insert_test_run_in_groups(&conn);
+
+        let delete_result =
+            RunInGroupData::delete_by_run_group_id(&conn, test_run_group.run_group_id).unwrap();
+
+        assert_eq!(delete_result, 2);
+
+        let found_run_in_groups =
+            RunInGroupData::find_by_run_group_id(&conn, test_run_group.run_group_id)
+                .expect("Failed to retrieve test run_in_groups by run_group_id.");
+
+        assert!(found_run_in_groups.is_empty());
+    }
+}
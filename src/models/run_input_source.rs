@@ -0,0 +1,281 @@
+//! Contains structs and functions for doing operations on RUN_INPUT_SOURCE records.
+//!
+//! A run_input_source record represents that an input to a run was resolved from the outputs of
+//! another run, via a `run_output:` or `latest_successful_output:` reference.  This is tracked so
+//! it's possible to see where the value for that input came from.  Represented in the database by
+//! the RUN_INPUT_SOURCE table.
+
+use crate::custom_sql_types::RunCallWorkflowEnum;
+use crate::schema::run_input_source;
+use crate::schema::run_input_source::dsl::*;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Mapping to a run_input_source as it exists in the RUN_INPUT_SOURCE table in the database.
+///
+/// An instance of this struct will be returned by any queries for run_input_sources.
+#[derive(Queryable, Deserialize, Serialize, PartialEq, Debug)]
+pub struct RunInputSourceData {
+    pub run_id: Uuid,
+    pub workflow: RunCallWorkflowEnum,
+    pub input_key: String,
+    pub reference: String,
+    pub source_run_id: Uuid,
+    pub value: Value,
+    pub created_at: NaiveDateTime,
+}
+
+/// A new run_input_source to be inserted into the DB
+///
+/// run_id, workflow, input_key, reference, source_run_id, and value are all required fields;
+/// created_at is populated automatically by the DB
+#[derive(Deserialize, Serialize, Insertable, Clone, Debug, PartialEq)]
+#[table_name = "run_input_source"]
+pub struct NewRunInputSource {
+    pub run_id: Uuid,
+    pub workflow: RunCallWorkflowEnum,
+    pub input_key: String,
+    pub reference: String,
+    pub source_run_id: Uuid,
+    pub value: Value,
+}
+
+impl RunInputSourceData {
+    /// Queries the DB for run_input_sources for the run specified by `id`
+    ///
+    /// Queries the DB using `conn` to retrieve the rows with a run_id value of `id`, sorted by
+    /// workflow (so test comes before eval) and then input_key.  Returns a result containing either
+    /// a vector of the retrieved run_input_sources as RunInputSourceData instances or an error if
+    /// the query fails for some reason
+    pub fn find_by_run_id(
+        conn: &PgConnection,
+        id: Uuid,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        run_input_source
+            .filter(run_id.eq(id))
+            .order_by(workflow.asc())
+            .then_order_by(input_key.asc())
+            .load::<Self>(conn)
+    }
+
+    /// Inserts a new run_input_source into the DB
+    ///
+    /// Creates a new run_input_source row in the DB using `conn` with the values specified in
+    /// `params`.  Returns a result containing either the new run_input_source that was created or
+    /// an error if the insert fails for some reason
+    pub fn create(
+        conn: &PgConnection,
+        params: NewRunInputSource,
+    ) -> Result<Self, diesel::result::Error> {
+        diesel::insert_into(run_input_source)
+            .values(&params)
+            .get_result(conn)
+    }
+
+    /// Deletes run_input_source rows from the DB that are mapped to the run specified by `id`,
+    /// either as the run the input belongs to or as the run the value came from
+    ///
+    /// Returns either the number of run_input_source rows deleted, or an error if something goes
+    /// wrong during the delete
+    pub fn delete_by_run_id(conn: &PgConnection, id: Uuid) -> Result<usize, diesel::result::Error> {
+        diesel::delete(run_input_source)
+            .filter(run_id.eq(id).or(source_run_id.eq(id)))
+            .execute(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{NewRun, RunData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::unit_test_util::*;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    fn insert_test_runs(conn: &PgConnection) -> Vec<RunData> {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline 2"),
+            description: Some(String::from("Kevin made this pipeline for testing 2")),
+            created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's Template2"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing2")),
+            test_wdl: String::from("testtest"),
+            test_wdl_dependencies: None,
+            eval_wdl: String::from("evaltest"),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test2"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: Some(serde_json::from_str("{\"test\":\"test\"}").unwrap()),
+            test_option_defaults: None,
+            eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        let mut runs = Vec::new();
+
+        for run_name in &["Kevin's source run", "Kevin's test run"] {
+            let new_run = NewRun {
+                test_id: test.test_id,
+                name: String::from(*run_name),
+                status: RunStatusEnum::Succeeded,
+                test_input: serde_json::from_str("{\"test\":\"1\"}").unwrap(),
+                test_options: None,
+                eval_input: serde_json::from_str("{}").unwrap(),
+                eval_options: None,
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                created_by: Some(String::from("Kevin@example.com")),
+                finished_at: None,
+            };
+
+            runs.push(RunData::create(conn, new_run).expect("Failed inserting test run"));
+        }
+
+        runs
+    }
+
+    fn insert_test_run_input_sources(
+        conn: &PgConnection,
+    ) -> (Vec<RunData>, Vec<RunInputSourceData>) {
+        let runs = insert_test_runs(conn);
+
+        let mut run_input_sources = Vec::new();
+
+        let new_run_input_source = NewRunInputSource {
+            run_id: runs[1].run_id,
+            workflow: RunCallWorkflowEnum::Test,
+            input_key: String::from("test_workflow.in_bam"),
+            reference: String::from("run_output:Kevin's source run.test_workflow.out_bam"),
+            source_run_id: runs[0].run_id,
+            value: json!("gs://example/out.bam"),
+        };
+
+        run_input_sources.push(
+            RunInputSourceData::create(conn, new_run_input_source)
+                .expect("Failed inserting test run_input_source"),
+        );
+
+        let new_run_input_source = NewRunInputSource {
+            run_id: runs[1].run_id,
+            workflow: RunCallWorkflowEnum::Eval,
+            input_key: String::from("eval_workflow.truth_vcf"),
+            reference: String::from("latest_successful_output:Kevin's Test2.out_vcf"),
+            source_run_id: runs[0].run_id,
+            value: json!("gs://example/out.vcf"),
+        };
+
+        run_input_sources.push(
+            RunInputSourceData::create(conn, new_run_input_source)
+                .expect("Failed inserting test run_input_source"),
+        );
+
+        (runs, run_input_sources)
+    }
+
+    #[test]
+    fn find_by_run_id_success() {
+        let conn = get_test_db_connection();
+
+        let (test_runs, test_run_input_sources) = insert_test_run_input_sources(&conn);
+
+        let found_run_input_sources =
+            RunInputSourceData::find_by_run_id(&conn, test_runs[1].run_id)
+                .expect("Failed to retrieve test run_input_sources by run_id.");
+
+        assert_eq!(found_run_input_sources, test_run_input_sources);
+    }
+
+    #[test]
+    fn find_by_run_id_not_exists() {
+        let conn = get_test_db_connection();
+
+        insert_test_run_input_sources(&conn);
+
+        let found_run_input_sources =
+            RunInputSourceData::find_by_run_id(&conn, Uuid::new_v4()).unwrap();
+
+        assert!(found_run_input_sources.is_empty());
+    }
+
+    #[test]
+    fn create_success() {
+        let conn = get_test_db_connection();
+
+        let test_runs = insert_test_runs(&conn);
+
+        let new_run_input_source = NewRunInputSource {
+            run_id: test_runs[1].run_id,
+            workflow: RunCallWorkflowEnum::Test,
+            input_key: String::from("test_workflow.in_number"),
+            reference: String::from("run_output:Kevin's source run.test_workflow.out_number"),
+            source_run_id: test_runs[0].run_id,
+            value: json!(4),
+        };
+
+        let test_run_input_source = RunInputSourceData::create(&conn, new_run_input_source)
+            .expect("Failed inserting test run_input_source");
+
+        assert_eq!(test_run_input_source.run_id, test_runs[1].run_id);
+        assert_eq!(test_run_input_source.workflow, RunCallWorkflowEnum::Test);
+        assert_eq!(test_run_input_source.input_key, "test_workflow.in_number");
+        assert_eq!(
+            test_run_input_source.reference,
+            "run_output:Kevin's source run.test_workflow.out_number"
+        );
+        assert_eq!(test_run_input_source.source_run_id, test_runs[0].run_id);
+        assert_eq!(test_run_input_source.value, json!(4));
+    }
+
+    #[test]
+    fn delete_by_run_id_source_success() {
+        let conn = get_test_db_connection();
+
+        let (test_runs, _) = insert_test_run_input_sources(&conn);
+
+        let delete_result =
+            RunInputSourceData::delete_by_run_id(&conn, test_runs[0].run_id).unwrap();
+
+        assert_eq!(delete_result, 2);
+
+        let found_run_input_sources =
+            RunInputSourceData::find_by_run_id(&conn, test_runs[1].run_id).unwrap();
+
+        assert!(found_run_input_sources.is_empty());
+    }
+}
@@ -0,0 +1,262 @@
+//! Contains structs and functions for doing operations on RUN_IS_FROM_SCHEDULE records.
+//!
+//! A run_is_from_schedule record represents that a specific run was started by a test schedule,
+//! along with the time the schedule specified the run for.  This is tracked so runs can be traced
+//! back to the schedule that created them.  Represented in the database by the
+//! RUN_IS_FROM_SCHEDULE table.
+
+use crate::schema::run_is_from_schedule;
+use crate::schema::run_is_from_schedule::dsl::*;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Mapping to a run_is_from_schedule as it exists in the RUN_IS_FROM_SCHEDULE table in the
+/// database.
+///
+/// An instance of this struct will be returned by any queries for run_is_from_schedules.
+#[derive(Queryable, Deserialize, Serialize, PartialEq, Debug)]
+pub struct RunIsFromScheduleData {
+    pub run_id: Uuid,
+    pub test_schedule_id: Uuid,
+    pub scheduled_for: NaiveDateTime,
+    pub created_at: NaiveDateTime,
+}
+
+/// A new run_is_from_schedule to be inserted into the DB
+///
+/// run_id, test_schedule_id, and scheduled_for are all required fields; created_at is populated
+/// automatically by the DB
+#[derive(Deserialize, Serialize, Insertable)]
+#[table_name = "run_is_from_schedule"]
+pub struct NewRunIsFromSchedule {
+    pub run_id: Uuid,
+    pub test_schedule_id: Uuid,
+    pub scheduled_for: NaiveDateTime,
+}
+
+impl RunIsFromScheduleData {
+    /// Queries the DB for a run_is_from_schedule with the specified run_id
+    ///
+    /// Queries the DB using `conn` to retrieve the first row with a run_id value of `id`
+    /// Returns a result containing either the retrieved run_is_from_schedule as a
+    /// RunIsFromScheduleData instance or an error if the query fails for some reason or if no
+    /// run_is_from_schedule is found matching the criteria
+    ///
+    /// This is function is currently not in use, but it's functionality will likely be necessary in
+    /// the future, so it is included
+    #[allow(dead_code)]
+    pub fn find_by_run_id(conn: &PgConnection, id: Uuid) -> Result<Self, diesel::result::Error> {
+        run_is_from_schedule
+            .filter(run_id.eq(id))
+            .first::<Self>(conn)
+    }
+
+    /// Inserts a new run_is_from_schedule into the DB
+    ///
+    /// Creates a new run_is_from_schedule row in the DB using `conn` with the values specified in
+    /// `params`
+    /// Returns a result containing either the new run_is_from_schedule that was created or an
+    /// error if the insert fails for some reason
+    pub fn create(
+        conn: &PgConnection,
+        params: NewRunIsFromSchedule,
+    ) -> Result<Self, diesel::result::Error> {
+        diesel::insert_into(run_is_from_schedule)
+            .values(&params)
+            .get_result(conn)
+    }
+
+    /// Deletes a run_is_from_schedule row from the DB that is mapped to the run specified by `id`
+    ///
+    /// Returns either the number of run_is_from_schedule rows deleted, or an error if something
+    /// goes wrong during the delete
+    pub fn delete_by_run_id(conn: &PgConnection, id: Uuid) -> Result<usize, diesel::result::Error> {
+        diesel::delete(run_is_from_schedule)
+            .filter(run_id.eq(id))
+            .execute(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{NewRun, RunData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::models::test_schedule::{NewTestSchedule, TestScheduleData};
+    use crate::unit_test_util::*;
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    fn insert_test_run_and_schedule(conn: &PgConnection) -> (RunData, TestScheduleData) {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's Template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing")),
+            test_wdl: String::from("testtest"),
+            test_wdl_dependencies: None,
+            eval_wdl: String::from("evaltest"),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: Some(serde_json::from_str("{\"test\":\"test\"}").unwrap()),
+            test_option_defaults: None,
+            eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        let new_test_schedule = NewTestSchedule {
+            test_id: test.test_id,
+            cron_expression: String::from("0 2 * * *"),
+            test_input: None,
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: true,
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        let test_schedule = TestScheduleData::create(conn, new_test_schedule)
+            .expect("Failed inserting test test_schedule");
+
+        let new_run = NewRun {
+            test_id: test.test_id,
+            name: String::from("name1"),
+            status: RunStatusEnum::TestSubmitted,
+            test_input: serde_json::from_str("{}").unwrap(),
+            test_options: None,
+            eval_input: serde_json::from_str("{\"test\":\"2\"}").unwrap(),
+            eval_options: None,
+            test_cromwell_job_id: Some(String::from("1234567890")),
+            eval_cromwell_job_id: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: None,
+        };
+
+        let run = RunData::create(conn, new_run).expect("Failed inserting test run");
+
+        (run, test_schedule)
+    }
+
+    fn insert_test_run_is_from_schedule(conn: &PgConnection) -> RunIsFromScheduleData {
+        let (run, test_schedule) = insert_test_run_and_schedule(conn);
+
+        let new_run_is_from_schedule = NewRunIsFromSchedule {
+            run_id: run.run_id,
+            test_schedule_id: test_schedule.test_schedule_id,
+            scheduled_for: NaiveDate::from_ymd(2022, 4, 12).and_hms(2, 0, 0),
+        };
+
+        RunIsFromScheduleData::create(conn, new_run_is_from_schedule)
+            .expect("Failed inserting test run_is_from_schedule")
+    }
+
+    #[test]
+    fn find_by_run_id_exists() {
+        let conn = get_test_db_connection();
+
+        let test_run_is_from_schedule = insert_test_run_is_from_schedule(&conn);
+
+        let found_run_is_from_schedule =
+            RunIsFromScheduleData::find_by_run_id(&conn, test_run_is_from_schedule.run_id)
+                .expect("Failed to retrieve test run_is_from_schedule by id.");
+
+        assert_eq!(found_run_is_from_schedule, test_run_is_from_schedule);
+    }
+
+    #[test]
+    fn find_by_run_id_not_exists() {
+        let conn = get_test_db_connection();
+
+        let nonexistent_run_is_from_schedule =
+            RunIsFromScheduleData::find_by_run_id(&conn, Uuid::new_v4());
+
+        assert!(matches!(
+            nonexistent_run_is_from_schedule,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn create_success() {
+        let conn = get_test_db_connection();
+
+        let (run, test_schedule) = insert_test_run_and_schedule(&conn);
+
+        let new_run_is_from_schedule = NewRunIsFromSchedule {
+            run_id: run.run_id,
+            test_schedule_id: test_schedule.test_schedule_id,
+            scheduled_for: NaiveDate::from_ymd(2022, 4, 12).and_hms(2, 0, 0),
+        };
+
+        let test_run_is_from_schedule =
+            RunIsFromScheduleData::create(&conn, new_run_is_from_schedule)
+                .expect("Failed inserting test run_is_from_schedule");
+
+        assert_eq!(test_run_is_from_schedule.run_id, run.run_id);
+        assert_eq!(
+            test_run_is_from_schedule.test_schedule_id,
+            test_schedule.test_schedule_id
+        );
+        assert_eq!(
+            test_run_is_from_schedule.scheduled_for,
+            NaiveDate::from_ymd(2022, 4, 12).and_hms(2, 0, 0)
+        );
+    }
+
+    #[test]
+    fn delete_by_run_id_success() {
+        let conn = get_test_db_connection();
+
+        let test_run_is_from_schedule = insert_test_run_is_from_schedule(&conn);
+
+        let delete_result =
+            RunIsFromScheduleData::delete_by_run_id(&conn, test_run_is_from_schedule.run_id)
+                .unwrap();
+
+        assert_eq!(delete_result, 1);
+
+        let deleted_run_is_from_schedule =
+            RunIsFromScheduleData::find_by_run_id(&conn, test_run_is_from_schedule.run_id);
+
+        assert!(matches!(
+            deleted_run_is_from_schedule,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+}
@@ -0,0 +1,317 @@
+//! Contains structs and functions for doing operations on RUN_IS_RERUN records.
+//!
+//! A run_is_rerun record represents that a specific run was created by rerunning another run,
+//! either in full or by re-executing only its eval step.  This is tracked so the history of a run
+//! can be followed back to the run it was rerun from.  Represented in the database by the
+//! RUN_IS_RERUN table.
+
+use crate::custom_sql_types::RerunModeEnum;
+use crate::schema::run_is_rerun;
+use crate::schema::run_is_rerun::dsl::*;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Mapping to a run_is_rerun as it exists in the RUN_IS_RERUN table in the database.
+///
+/// An instance of this struct will be returned by any queries for run_is_reruns.
+#[derive(Queryable, Deserialize, Serialize, PartialEq, Debug)]
+pub struct RunIsRerunData {
+    pub run_id: Uuid,
+    pub source_run_id: Uuid,
+    pub mode: RerunModeEnum,
+    pub created_at: NaiveDateTime,
+}
+
+/// A new run_is_rerun to be inserted into the DB
+///
+/// run_id, source_run_id, and mode are all required fields; created_at is populated automatically
+/// by the DB
+#[derive(Deserialize, Serialize, Insertable)]
+#[table_name = "run_is_rerun"]
+pub struct NewRunIsRerun {
+    pub run_id: Uuid,
+    pub source_run_id: Uuid,
+    pub mode: RerunModeEnum,
+}
+
+impl RunIsRerunData {
+    /// Queries the DB for a run_is_rerun with the specified run_id
+    ///
+    /// Queries the DB using `conn` to retrieve the first row with a run_id value of `id`
+    /// Returns a result containing either the retrieved run_is_rerun as a RunIsRerunData instance
+    /// or an error if the query fails for some reason or if no run_is_rerun is found matching the
+    /// criteria
+    pub fn find_by_run_id(conn: &PgConnection, id: Uuid) -> Result<Self, diesel::result::Error> {
+        run_is_rerun.filter(run_id.eq(id)).first::<Self>(conn)
+    }
+
+    /// Queries the DB for run_is_reruns involving the run specified by `id`
+    ///
+    /// Queries the DB using `conn` to retrieve rows with either a run_id or a source_run_id value
+    /// of `id` (i.e. the record of which run `id` was rerun from, if it is a rerun, and records for
+    /// any reruns of `id`), sorted by created_at.  Returns a result containing either a vector of the
+    /// retrieved run_is_reruns as RunIsRerunData instances or an error if the query fails for some
+    /// reason
+    pub fn find_for_run(conn: &PgConnection, id: Uuid) -> Result<Vec<Self>, diesel::result::Error> {
+        run_is_rerun
+            .filter(run_id.eq(id).or(source_run_id.eq(id)))
+            .order_by(created_at.asc())
+            .load::<Self>(conn)
+    }
+
+    /// Inserts a new run_is_rerun into the DB
+    ///
+    /// Creates a new run_is_rerun row in the DB using `conn` with the values specified in `params`
+    /// Returns a result containing either the new run_is_rerun that was created or an error if the
+    /// insert fails for some reason
+    pub fn create(
+        conn: &PgConnection,
+        params: NewRunIsRerun,
+    ) -> Result<Self, diesel::result::Error> {
+        diesel::insert_into(run_is_rerun)
+            .values(&params)
+            .get_result(conn)
+    }
+
+    /// Deletes run_is_rerun rows from the DB that are mapped to the run specified by `id`, either as
+    /// the rerun or as the source run
+    ///
+    /// Returns either the number of run_is_rerun rows deleted, or an error if something goes wrong
+    /// during the delete
+    pub fn delete_by_run_id(conn: &PgConnection, id: Uuid) -> Result<usize, diesel::result::Error> {
+        diesel::delete(run_is_rerun)
+            .filter(run_id.eq(id).or(source_run_id.eq(id)))
+            .execute(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{NewRun, RunData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::unit_test_util::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn insert_test_runs(conn: &PgConnection) -> Vec<RunData> {
+        let mut runs = Vec::new();
+
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing 2")),
+            created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's Template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing2")),
+            test_wdl: String::from("testtest"),
+            test_wdl_dependencies: None,
+            eval_wdl: String::from("evaltest"),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: Some(serde_json::from_str("{\"test\":\"test\"}").unwrap()),
+            test_option_defaults: None,
+            eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        let new_run = NewRun {
+            test_id: test.test_id,
+            name: String::from("name1"),
+            status: RunStatusEnum::EvalFailed,
+            test_input: serde_json::from_str("{}").unwrap(),
+            test_options: None,
+            eval_input: serde_json::from_str("{\"test\":\"2\"}").unwrap(),
+            eval_options: None,
+            test_cromwell_job_id: Some(String::from("1234567890")),
+            eval_cromwell_job_id: Some(String::from("12345678902")),
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: Some(Utc::now().naive_utc()),
+        };
+
+        runs.push(RunData::create(conn, new_run).expect("Failed inserting test run"));
+
+        let new_run = NewRun {
+            test_id: test.test_id,
+            name: String::from("name2"),
+            status: RunStatusEnum::EvalSubmitted,
+            test_input: serde_json::from_str("{}").unwrap(),
+            test_options: None,
+            eval_input: serde_json::from_str("{\"test\":\"2\"}").unwrap(),
+            eval_options: None,
+            test_cromwell_job_id: Some(String::from("1234567890")),
+            eval_cromwell_job_id: Some(String::from("12345678903")),
+            created_by: None,
+            finished_at: None,
+        };
+
+        runs.push(RunData::create(conn, new_run).expect("Failed inserting test run"));
+
+        let new_run = NewRun {
+            test_id: test.test_id,
+            name: String::from("name3"),
+            status: RunStatusEnum::TestSubmitted,
+            test_input: serde_json::from_str("{}").unwrap(),
+            test_options: None,
+            eval_input: serde_json::from_str("{\"test\":\"2\"}").unwrap(),
+            eval_options: None,
+            test_cromwell_job_id: Some(String::from("123456789012")),
+            eval_cromwell_job_id: None,
+            created_by: None,
+            finished_at: None,
+        };
+
+        runs.push(RunData::create(conn, new_run).expect("Failed inserting test run"));
+
+        runs
+    }
+
+    fn insert_test_run_is_reruns(conn: &PgConnection) -> (Vec<RunData>, Vec<RunIsRerunData>) {
+        let runs = insert_test_runs(conn);
+
+        let mut run_is_reruns = Vec::new();
+
+        let new_run_is_rerun = NewRunIsRerun {
+            run_id: runs[1].run_id,
+            source_run_id: runs[0].run_id,
+            mode: RerunModeEnum::EvalOnly,
+        };
+
+        run_is_reruns.push(
+            RunIsRerunData::create(conn, new_run_is_rerun)
+                .expect("Failed inserting test run_is_rerun"),
+        );
+
+        let new_run_is_rerun = NewRunIsRerun {
+            run_id: runs[2].run_id,
+            source_run_id: runs[1].run_id,
+            mode: RerunModeEnum::Full,
+        };
+
+        run_is_reruns.push(
+            RunIsRerunData::create(conn, new_run_is_rerun)
+                .expect("Failed inserting test run_is_rerun"),
+        );
+
+        (runs, run_is_reruns)
+    }
+
+    #[test]
+    fn find_by_run_id_exists() {
+        let conn = get_test_db_connection();
+
+        let (_, test_run_is_reruns) = insert_test_run_is_reruns(&conn);
+
+        let found_run_is_rerun =
+            RunIsRerunData::find_by_run_id(&conn, test_run_is_reruns[0].run_id)
+                .expect("Failed to retrieve test run_is_rerun by id.");
+
+        assert_eq!(found_run_is_rerun, test_run_is_reruns[0]);
+    }
+
+    #[test]
+    fn find_by_run_id_not_exists() {
+        let conn = get_test_db_connection();
+
+        let nonexistent_run_is_rerun = RunIsRerunData::find_by_run_id(&conn, Uuid::new_v4());
+
+        assert!(matches!(
+            nonexistent_run_is_rerun,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn find_for_run_success() {
+        let conn = get_test_db_connection();
+
+        let (test_runs, test_run_is_reruns) = insert_test_run_is_reruns(&conn);
+
+        let found_run_is_reruns = RunIsRerunData::find_for_run(&conn, test_runs[1].run_id)
+            .expect("Failed to retrieve test run_is_reruns for run.");
+
+        assert_eq!(found_run_is_reruns, test_run_is_reruns);
+    }
+
+    #[test]
+    fn find_for_run_none() {
+        let conn = get_test_db_connection();
+
+        insert_test_run_is_reruns(&conn);
+
+        let found_run_is_reruns = RunIsRerunData::find_for_run(&conn, Uuid::new_v4())
+            .expect("Failed to retrieve test run_is_reruns for run.");
+
+        assert!(found_run_is_reruns.is_empty());
+    }
+
+    #[test]
+    fn create_success() {
+        let conn = get_test_db_connection();
+
+        let test_runs = insert_test_runs(&conn);
+
+        let new_run_is_rerun = NewRunIsRerun {
+            run_id: test_runs[1].run_id,
+            source_run_id: test_runs[0].run_id,
+            mode: RerunModeEnum::Full,
+        };
+
+        let test_run_is_rerun = RunIsRerunData::create(&conn, new_run_is_rerun)
+            .expect("Failed inserting test run_is_rerun");
+
+        assert_eq!(test_run_is_rerun.run_id, test_runs[1].run_id);
+        assert_eq!(test_run_is_rerun.source_run_id, test_runs[0].run_id);
+        assert_eq!(test_run_is_rerun.mode, RerunModeEnum::Full);
+    }
+
+    #[test]
+    fn delete_by_run_id_success() {
+        let conn = get_test_db_connection();
+
+        let (test_runs, _) = insert_test_run_is_reruns(&conn);
+
+        let delete_result = RunIsRerunData::delete_by_run_id(&conn, test_runs[1].run_id).unwrap();
+
+        assert_eq!(delete_result, 2);
+
+        let found_run_is_reruns = RunIsRerunData::find_for_run(&conn, test_runs[1].run_id)
+            .expect("Failed to retrieve test run_is_reruns for run.");
+
+        assert!(found_run_is_reruns.is_empty());
+    }
+}
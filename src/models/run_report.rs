@@ -27,6 +27,7 @@ pub struct RunReportData {
     pub created_at: NaiveDateTime,
     pub created_by: Option<String>,
     pub finished_at: Option<NaiveDateTime>,
+    pub cromwell_server: Option<String>,
 }
 
 /// Represents all possible parameters for a query of the RUN_REPORT table
@@ -53,8 +54,8 @@ pub struct RunReportQuery {
 
 /// A new run_report mapping to be inserted into the DB
 ///
-/// run_id, report_id, and report_key are all required fields, but created_by is not
-/// created_at is populated automatically by the DB
+/// run_id, report_id, and report_key are all required fields, but created_by and cromwell_server
+/// are not; created_at is populated automatically by the DB
 #[derive(Deserialize, Serialize, Insertable)]
 #[table_name = "run_report"]
 pub struct NewRunReport {
@@ -65,11 +66,12 @@ pub struct NewRunReport {
     pub results: Option<Value>,
     pub created_by: Option<String>,
     pub finished_at: Option<NaiveDateTime>,
+    pub cromwell_server: Option<String>,
 }
 
 /// Represents fields to change when updating a run_report
 ///
-/// Only status, cromwell_job_id, results, and finished_at can be updated
+/// Only status, cromwell_job_id, results, finished_at, and cromwell_server can be updated
 #[derive(Deserialize, Serialize, AsChangeset, Debug)]
 #[table_name = "run_report"]
 pub struct RunReportChangeset {
@@ -77,6 +79,7 @@ pub struct RunReportChangeset {
     pub cromwell_job_id: Option<String>,
     pub results: Option<Value>,
     pub finished_at: Option<NaiveDateTime>,
+    pub cromwell_server: Option<String>,
 }
 
 impl RunReportData {
@@ -306,6 +309,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -320,6 +326,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -334,6 +343,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let test = TestData::create(conn, new_test).expect("Failed inserting test test");
@@ -360,6 +373,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 5"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -374,6 +390,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -388,6 +407,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let test = TestData::create(conn, new_test).expect("Failed inserting test test");
@@ -430,6 +453,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
         };
 
         RunReportData::create(conn, new_run_report).expect("Failed inserting test run_report")
@@ -460,6 +484,7 @@ mod tests {
             ),
             created_by: Some(String::from("Kelvin@example.com")),
             finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
         };
 
         run_reports.push(
@@ -486,6 +511,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: None,
+            cromwell_server: None,
         };
 
         run_reports.push(
@@ -510,6 +536,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: None,
+            cromwell_server: None,
         };
 
         run_reports.push(
@@ -901,6 +928,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: None,
+            cromwell_server: None,
         };
 
         let new_run_report = RunReportData::create(&conn, copy_run_report);
@@ -925,6 +953,7 @@ mod tests {
             cromwell_job_id: Some(String::from("123456asdsdfes")),
             results: Some(json!({"test":"test"})),
             finished_at: Some("2099-01-01T00:00:00".parse::<NaiveDateTime>().unwrap()),
+            cromwell_server: None,
         };
 
         let updated_run_report = RunReportData::update(
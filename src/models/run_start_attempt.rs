@@ -0,0 +1,395 @@
+//! Contains structs and functions for doing operations on RUN_START_ATTEMPT records.
+//!
+//! A run_start_attempt record represents the number of attempts that have been made at starting
+//! (i.e. submitting to cromwell) the test or eval workflow for a run.  This is tracked so attempts
+//! that fail for transient reasons can be retried up to the configured limit before the run is
+//! marked as failed.  When a retry is waiting out its backoff, next_attempt_at holds the time at
+//! which the status manager should make the next attempt.  Represented in the database by the
+//! RUN_START_ATTEMPT table.
+
+use crate::custom_sql_types::RunCallWorkflowEnum;
+use crate::schema::run_start_attempt;
+use crate::schema::run_start_attempt::dsl::*;
+use chrono::{NaiveDateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Mapping to a run_start_attempt as it exists in the RUN_START_ATTEMPT table in the database.
+///
+/// An instance of this struct will be returned by any queries for run_start_attempts.
+#[derive(Queryable, Deserialize, Serialize, PartialEq, Debug)]
+pub struct RunStartAttemptData {
+    pub run_id: Uuid,
+    pub workflow: RunCallWorkflowEnum,
+    pub attempts: i32,
+    pub last_attempted_at: NaiveDateTime,
+    pub next_attempt_at: Option<NaiveDateTime>,
+}
+
+/// A new run_start_attempt to be inserted into the DB
+///
+/// run_id, workflow, and attempts are all required fields; last_attempted_at is populated
+/// automatically by the DB, and next_attempt_at is only set when a retry is scheduled
+#[derive(Deserialize, Serialize, Insertable)]
+#[table_name = "run_start_attempt"]
+struct NewRunStartAttempt {
+    run_id: Uuid,
+    workflow: RunCallWorkflowEnum,
+    attempts: i32,
+}
+
+impl RunStartAttemptData {
+    /// Queries the DB for run_start_attempts for the run specified by `id`
+    ///
+    /// Queries the DB using `conn` to retrieve the rows with a run_id value of `id`, sorted by
+    /// workflow (so test comes before eval).  Returns a result containing either a vector of the
+    /// retrieved run_start_attempts as RunStartAttemptData instances or an error if the query fails
+    /// for some reason
+    pub fn find_by_run_id(
+        conn: &PgConnection,
+        id: Uuid,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        run_start_attempt
+            .filter(run_id.eq(id))
+            .order_by(workflow.asc())
+            .load::<Self>(conn)
+    }
+
+    /// Records an attempt at starting the `workflow_type` workflow for the run specified by `id`
+    ///
+    /// Inserts a run_start_attempt row with an attempt count of 1 using `conn` if there isn't one
+    /// yet for `id` and `workflow_type`, or increments the count and updates last_attempted_at for
+    /// the existing row if there is.  Returns a result containing either the new or updated
+    /// run_start_attempt or an error if the upsert fails for some reason
+    pub fn increment(
+        conn: &PgConnection,
+        id: Uuid,
+        workflow_type: RunCallWorkflowEnum,
+    ) -> Result<Self, diesel::result::Error> {
+        let new_run_start_attempt = NewRunStartAttempt {
+            run_id: id,
+            workflow: workflow_type,
+            attempts: 1,
+        };
+        diesel::insert_into(run_start_attempt)
+            .values(&new_run_start_attempt)
+            .on_conflict((run_id, workflow))
+            .do_update()
+            .set((
+                attempts.eq(attempts + 1),
+                last_attempted_at.eq(Utc::now().naive_utc()),
+            ))
+            .get_result(conn)
+    }
+
+    /// Queries the DB for run_start_attempts with a retry that is due as of `time`
+    ///
+    /// Queries the DB using `conn` to retrieve the rows with a next_attempt_at value that is not
+    /// later than `time`, sorted by next_attempt_at so the retries that have been waiting longest
+    /// come first.  Returns a result containing either a vector of the retrieved
+    /// run_start_attempts or an error if the query fails for some reason
+    pub fn find_due_retries(
+        conn: &PgConnection,
+        time: NaiveDateTime,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        run_start_attempt
+            .filter(next_attempt_at.le(time))
+            .order_by(next_attempt_at.asc())
+            .load::<Self>(conn)
+    }
+
+    /// Schedules the next attempt at starting the `workflow_type` workflow for the run specified
+    /// by `id` for `retry_at`
+    ///
+    /// Sets next_attempt_at to `retry_at` for the run_start_attempt row for `id` and
+    /// `workflow_type` using `conn`.  Returns a result containing either the updated
+    /// run_start_attempt or an error if there is no such row or the update fails for some reason
+    pub fn schedule_retry(
+        conn: &PgConnection,
+        id: Uuid,
+        workflow_type: RunCallWorkflowEnum,
+        retry_at: NaiveDateTime,
+    ) -> Result<Self, diesel::result::Error> {
+        diesel::update(
+            run_start_attempt
+                .filter(run_id.eq(id))
+                .filter(workflow.eq(workflow_type)),
+        )
+        .set(next_attempt_at.eq(retry_at))
+        .get_result(conn)
+    }
+
+    /// Claims the scheduled retry for starting the `workflow_type` workflow for the run specified
+    /// by `id`
+    ///
+    /// Clears next_attempt_at for the run_start_attempt row for `id` and `workflow_type` using
+    /// `conn`, but only if it is currently set, so only one caller can claim a given retry.
+    /// Returns a result containing true if the retry was claimed, false if there was no retry
+    /// scheduled, or an error if the update fails for some reason
+    pub fn claim_retry(
+        conn: &PgConnection,
+        id: Uuid,
+        workflow_type: RunCallWorkflowEnum,
+    ) -> Result<bool, diesel::result::Error> {
+        let updated_count = diesel::update(
+            run_start_attempt
+                .filter(run_id.eq(id))
+                .filter(workflow.eq(workflow_type))
+                .filter(next_attempt_at.is_not_null()),
+        )
+        .set(next_attempt_at.eq(None::<NaiveDateTime>))
+        .execute(conn)?;
+        Ok(updated_count > 0)
+    }
+
+    /// Deletes run_start_attempt rows from the DB that are mapped to the run specified by `id`
+    ///
+    /// Returns either the number of run_start_attempt rows deleted, or an error if something goes
+    /// wrong during the delete
+    pub fn delete_by_run_id(conn: &PgConnection, id: Uuid) -> Result<usize, diesel::result::Error> {
+        diesel::delete(run_start_attempt)
+            .filter(run_id.eq(id))
+            .execute(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{NewRun, RunData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::unit_test_util::*;
+    use uuid::Uuid;
+
+    fn insert_test_run(conn: &PgConnection) -> RunData {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline 2"),
+            description: Some(String::from("Kevin made this pipeline for testing 2")),
+            created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's Template2"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing2")),
+            test_wdl: String::from("testtest"),
+            test_wdl_dependencies: None,
+            eval_wdl: String::from("evaltest"),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test2"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: Some(serde_json::from_str("{\"test\":\"test\"}").unwrap()),
+            test_option_defaults: None,
+            eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        let new_run = NewRun {
+            test_id: test.test_id,
+            name: String::from("Kevin's test run"),
+            status: RunStatusEnum::Created,
+            test_input: serde_json::from_str("{\"test\":\"1\"}").unwrap(),
+            test_options: None,
+            eval_input: serde_json::from_str("{}").unwrap(),
+            eval_options: None,
+            test_cromwell_job_id: None,
+            eval_cromwell_job_id: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: None,
+        };
+
+        RunData::create(conn, new_run).expect("Failed inserting test run")
+    }
+
+    #[test]
+    fn increment_new() {
+        let conn = get_test_db_connection();
+
+        let test_run = insert_test_run(&conn);
+
+        let test_run_start_attempt =
+            RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Test)
+                .expect("Failed incrementing run_start_attempt");
+
+        assert_eq!(test_run_start_attempt.run_id, test_run.run_id);
+        assert_eq!(test_run_start_attempt.workflow, RunCallWorkflowEnum::Test);
+        assert_eq!(test_run_start_attempt.attempts, 1);
+    }
+
+    #[test]
+    fn increment_existing() {
+        let conn = get_test_db_connection();
+
+        let test_run = insert_test_run(&conn);
+
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Test).unwrap();
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Eval).unwrap();
+        let test_run_start_attempt =
+            RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Test)
+                .expect("Failed incrementing run_start_attempt");
+
+        assert_eq!(test_run_start_attempt.workflow, RunCallWorkflowEnum::Test);
+        assert_eq!(test_run_start_attempt.attempts, 2);
+    }
+
+    #[test]
+    fn find_by_run_id_success() {
+        let conn = get_test_db_connection();
+
+        let test_run = insert_test_run(&conn);
+
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Eval).unwrap();
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Test).unwrap();
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Eval).unwrap();
+
+        let found_run_start_attempts = RunStartAttemptData::find_by_run_id(&conn, test_run.run_id)
+            .expect("Failed to retrieve test run_start_attempts by run_id.");
+
+        assert_eq!(found_run_start_attempts.len(), 2);
+        assert_eq!(
+            found_run_start_attempts[0].workflow,
+            RunCallWorkflowEnum::Test
+        );
+        assert_eq!(found_run_start_attempts[0].attempts, 1);
+        assert_eq!(
+            found_run_start_attempts[1].workflow,
+            RunCallWorkflowEnum::Eval
+        );
+        assert_eq!(found_run_start_attempts[1].attempts, 2);
+    }
+
+    #[test]
+    fn find_by_run_id_not_exists() {
+        let conn = get_test_db_connection();
+
+        let found_run_start_attempts =
+            RunStartAttemptData::find_by_run_id(&conn, Uuid::new_v4()).unwrap();
+
+        assert!(found_run_start_attempts.is_empty());
+    }
+
+    #[test]
+    fn find_due_retries_success() {
+        let conn = get_test_db_connection();
+
+        let test_run = insert_test_run(&conn);
+
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Test).unwrap();
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Eval).unwrap();
+
+        let now = Utc::now().naive_utc();
+        RunStartAttemptData::schedule_retry(
+            &conn,
+            test_run.run_id,
+            RunCallWorkflowEnum::Test,
+            now - chrono::Duration::seconds(5),
+        )
+        .unwrap();
+        RunStartAttemptData::schedule_retry(
+            &conn,
+            test_run.run_id,
+            RunCallWorkflowEnum::Eval,
+            now + chrono::Duration::seconds(60),
+        )
+        .unwrap();
+
+        let due_retries = RunStartAttemptData::find_due_retries(&conn, now)
+            .expect("Failed to retrieve due run_start_attempts");
+
+        assert_eq!(due_retries.len(), 1);
+        assert_eq!(due_retries[0].run_id, test_run.run_id);
+        assert_eq!(due_retries[0].workflow, RunCallWorkflowEnum::Test);
+    }
+
+    #[test]
+    fn claim_retry_success() {
+        let conn = get_test_db_connection();
+
+        let test_run = insert_test_run(&conn);
+
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Test).unwrap();
+
+        // Nothing to claim before a retry is scheduled
+        assert!(!RunStartAttemptData::claim_retry(
+            &conn,
+            test_run.run_id,
+            RunCallWorkflowEnum::Test
+        )
+        .unwrap());
+
+        let scheduled = RunStartAttemptData::schedule_retry(
+            &conn,
+            test_run.run_id,
+            RunCallWorkflowEnum::Test,
+            Utc::now().naive_utc(),
+        )
+        .unwrap();
+        assert!(scheduled.next_attempt_at.is_some());
+
+        // The first claim succeeds, and the second finds nothing left to claim
+        assert!(RunStartAttemptData::claim_retry(
+            &conn,
+            test_run.run_id,
+            RunCallWorkflowEnum::Test
+        )
+        .unwrap());
+        assert!(!RunStartAttemptData::claim_retry(
+            &conn,
+            test_run.run_id,
+            RunCallWorkflowEnum::Test
+        )
+        .unwrap());
+
+        let found_run_start_attempts =
+            RunStartAttemptData::find_by_run_id(&conn, test_run.run_id).unwrap();
+        assert!(found_run_start_attempts[0].next_attempt_at.is_none());
+    }
+
+    #[test]
+    fn delete_by_run_id_success() {
+        let conn = get_test_db_connection();
+
+        let test_run = insert_test_run(&conn);
+
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Test).unwrap();
+        RunStartAttemptData::increment(&conn, test_run.run_id, RunCallWorkflowEnum::Eval).unwrap();
+
+        let delete_result = RunStartAttemptData::delete_by_run_id(&conn, test_run.run_id).unwrap();
+
+        assert_eq!(delete_result, 2);
+
+        let found_run_start_attempts =
+            RunStartAttemptData::find_by_run_id(&conn, test_run.run_id).unwrap();
+
+        assert!(found_run_start_attempts.is_empty());
+    }
+}
@@ -0,0 +1,488 @@
+//! Contains structs and functions for doing operations on secrets.
+//!
+//! A secret is a named, sensitive value (e.g. an API token) that can be referenced in test and eval
+//! inputs as `secret:name` so the value itself never needs to be stored in a test or run.  Values
+//! are encrypted before they're stored, and the encrypted values are only loaded by the functions
+//! that explicitly ask for them, so they're never returned as part of a SecretData.  Represented in
+//! the database by the SECRET table.
+
+use crate::schema::secret;
+use crate::schema::secret::dsl::*;
+use crate::util;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The columns of the SECRET table that are loaded into a SecretData, i.e. everything except
+/// encrypted_value
+const SECRET_DATA_COLUMNS: (secret_id, name, description, created_at, created_by) =
+    (secret_id, name, description, created_at, created_by);
+
+/// Mapping to a secret as it exists in the SECRET table in the database, minus its encrypted value
+///
+/// An instance of this struct will be returned by any queries for secrets.
+#[derive(Queryable, Deserialize, Serialize, PartialEq, Debug)]
+pub struct SecretData {
+    pub secret_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub created_by: Option<String>,
+}
+
+/// Represents all possible parameters for a query of the SECRET table
+///
+/// All values are optional, so any combination can be used during a query.  Limit and offset are
+/// used for pagination.  Sort expects a comma-separated list of sort keys, optionally enclosed
+/// with either asc() or desc().  For example: asc(name),desc(description),secret_id
+#[derive(Deserialize, Serialize)]
+pub struct SecretQuery {
+    pub secret_id: Option<Uuid>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub created_before: Option<NaiveDateTime>,
+    pub created_after: Option<NaiveDateTime>,
+    pub created_by: Option<String>,
+    pub sort: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// A new secret to be inserted into the DB
+///
+/// name and encrypted_value are required fields, but description and created_by are not, so can
+/// be filled with `None`
+/// secret_id and created_at are populated automatically by the DB
+#[derive(Insertable)]
+#[table_name = "secret"]
+pub struct NewSecret {
+    pub name: String,
+    pub description: Option<String>,
+    pub encrypted_value: Vec<u8>,
+    pub created_by: Option<String>,
+}
+
+/// Represents fields to change when updating a secret
+///
+/// Only name, description, and encrypted_value can be modified after the secret has been created
+#[derive(AsChangeset, Debug)]
+#[table_name = "secret"]
+pub struct SecretChangeset {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub encrypted_value: Option<Vec<u8>>,
+}
+
+impl SecretData {
+    /// Queries the DB for a secret with the specified id
+    ///
+    /// Queries the DB using `conn` to retrieve the first row with a secret_id value of `id`
+    /// Returns a result containing either the retrieved secret as a SecretData instance or an
+    /// error if the query fails for some reason or if no secret is found matching the criteria
+    pub fn find_by_id(conn: &PgConnection, id: Uuid) -> Result<Self, diesel::result::Error> {
+        secret
+            .select(SECRET_DATA_COLUMNS)
+            .filter(secret_id.eq(id))
+            .first::<Self>(conn)
+    }
+
+    /// Queries the DB for secrets matching the specified query criteria
+    ///
+    /// Queries the DB using `conn` to retrieve secrets matching the criteria in `params`
+    /// Returns a result containing either a vector of the retrieved secrets as SecretData
+    /// instances or an error if the query fails for some reason
+    pub fn find(
+        conn: &PgConnection,
+        params: SecretQuery,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        // Put the query into a box (pointer) so it can be built dynamically
+        let mut query = secret.select(SECRET_DATA_COLUMNS).into_boxed();
+
+        // Add filters for each of the params if they have values
+        if let Some(param) = params.secret_id {
+            query = query.filter(secret_id.eq(param));
+        }
+        if let Some(param) = params.name {
+            query = query.filter(name.eq(param));
+        }
+        if let Some(param) = params.description {
+            query = query.filter(description.eq(param));
+        }
+        if let Some(param) = params.created_before {
+            query = query.filter(created_at.lt(param));
+        }
+        if let Some(param) = params.created_after {
+            query = query.filter(created_at.gt(param));
+        }
+        if let Some(param) = params.created_by {
+            query = query.filter(created_by.eq(param));
+        }
+
+        // If there is a sort param, parse it and add to the order by clause accordingly
+        if let Some(sort) = params.sort {
+            let sort = util::sort_string::parse_sort_string(&sort);
+            for sort_clause in sort {
+                match &*sort_clause.key {
+                    "secret_id" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(secret_id.asc());
+                        } else {
+                            query = query.then_order_by(secret_id.desc());
+                        }
+                    }
+                    "name" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(name.asc());
+                        } else {
+                            query = query.then_order_by(name.desc());
+                        }
+                    }
+                    "description" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(description.asc());
+                        } else {
+                            query = query.then_order_by(description.desc());
+                        }
+                    }
+                    "created_at" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(created_at.asc());
+                        } else {
+                            query = query.then_order_by(created_at.desc());
+                        }
+                    }
+                    "created_by" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(created_by.asc());
+                        } else {
+                            query = query.then_order_by(created_by.desc());
+                        }
+                    }
+                    // Don't add to the order by clause if the sort key isn't recognized
+                    &_ => {}
+                }
+            }
+        }
+
+        if let Some(param) = params.limit {
+            query = query.limit(param);
+        }
+        if let Some(param) = params.offset {
+            query = query.offset(param);
+        }
+
+        // Perform the query
+        query.load::<Self>(conn)
+    }
+
+    /// Queries the DB for the encrypted value of the secret with the specified name
+    ///
+    /// Returns a result containing either the encrypted value or an error if the query fails for
+    /// some reason or if no secret is found with that name
+    pub fn find_encrypted_value_by_name(
+        conn: &PgConnection,
+        secret_name: &str,
+    ) -> Result<Vec<u8>, diesel::result::Error> {
+        secret
+            .select(encrypted_value)
+            .filter(name.eq(secret_name))
+            .first::<Vec<u8>>(conn)
+    }
+
+    /// Queries the DB for the names and encrypted values of all secrets
+    ///
+    /// Returns a result containing either a vector of (name, encrypted value) pairs or an error if
+    /// the query fails for some reason
+    pub fn find_all_encrypted_values(
+        conn: &PgConnection,
+    ) -> Result<Vec<(String, Vec<u8>)>, diesel::result::Error> {
+        secret
+            .select((name, encrypted_value))
+            .load::<(String, Vec<u8>)>(conn)
+    }
+
+    /// Inserts a new secret into the DB
+    ///
+    /// Creates a new secret row in the DB using `conn` with the values specified in `params`
+    /// Returns a result containing either the new secret that was created or an error if the
+    /// insert fails for some reason
+    pub fn create(conn: &PgConnection, params: NewSecret) -> Result<Self, diesel::result::Error> {
+        diesel::insert_into(secret)
+            .values(&params)
+            .returning(SECRET_DATA_COLUMNS)
+            .get_result(conn)
+    }
+
+    /// Updates a specified secret in the DB
+    ///
+    /// Updates the secret row in the DB using `conn` specified by `id` with the values in `params`
+    /// Returns a result containing either the newly updated secret or an error if the update
+    /// fails for some reason
+    pub fn update(
+        conn: &PgConnection,
+        id: Uuid,
+        params: SecretChangeset,
+    ) -> Result<Self, diesel::result::Error> {
+        diesel::update(secret.filter(secret_id.eq(id)))
+            .set(params)
+            .returning(SECRET_DATA_COLUMNS)
+            .get_result(conn)
+    }
+
+    /// Deletes a specific secret in the DB
+    ///
+    /// Deletes the secret row in the DB using `conn` specified by `id`
+    /// Returns a result containing either the number of rows deleted or an error if the delete
+    /// fails for some reason
+    pub fn delete(conn: &PgConnection, id: Uuid) -> Result<usize, diesel::result::Error> {
+        diesel::delete(secret.filter(secret_id.eq(id))).execute(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::unit_test_util::*;
+    use uuid::Uuid;
+
+    fn insert_test_secrets(conn: &PgConnection) -> Vec<SecretData> {
+        let mut secrets = Vec::new();
+
+        for (secret_name, secret_description, secret_value) in [
+            ("Secret A", Some("First test secret"), vec![1u8, 2, 3]),
+            ("Secret B", None, vec![4u8, 5, 6]),
+            ("Secret C", Some("Third test secret"), vec![7u8, 8, 9]),
+        ] {
+            let new_secret = NewSecret {
+                name: String::from(secret_name),
+                description: secret_description.map(String::from),
+                encrypted_value: secret_value,
+                created_by: Some(String::from("Kevin@example.com")),
+            };
+
+            secrets
+                .push(SecretData::create(conn, new_secret).expect("Failed inserting test secret"));
+        }
+
+        secrets
+    }
+
+    fn insert_test_secret(conn: &PgConnection) -> SecretData {
+        let new_secret = NewSecret {
+            name: String::from("Kevin's Secret"),
+            description: Some(String::from("Kevin made this secret for testing")),
+            encrypted_value: vec![1u8, 2, 3, 4],
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        SecretData::create(conn, new_secret).expect("Failed inserting test secret")
+    }
+
+    #[test]
+    fn find_by_id_exists() {
+        let conn = get_test_db_connection();
+
+        let test_secret = insert_test_secret(&conn);
+
+        let found_secret = SecretData::find_by_id(&conn, test_secret.secret_id)
+            .expect("Failed to retrieve test secret by id.");
+
+        assert_eq!(found_secret, test_secret);
+    }
+
+    #[test]
+    fn find_by_id_not_exists() {
+        let conn = get_test_db_connection();
+
+        let nonexistent_secret = SecretData::find_by_id(&conn, Uuid::new_v4());
+
+        assert!(matches!(
+            nonexistent_secret,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn find_with_name() {
+        let conn = get_test_db_connection();
+
+        let test_secrets = insert_test_secrets(&conn);
+
+        let test_query = SecretQuery {
+            secret_id: None,
+            name: Some(String::from("Secret B")),
+            description: None,
+            created_before: None,
+            created_after: None,
+            created_by: None,
+            sort: None,
+            limit: None,
+            offset: None,
+        };
+
+        let found_secrets = SecretData::find(&conn, test_query).expect("Failed to find secrets");
+
+        assert_eq!(found_secrets.len(), 1);
+        assert_eq!(found_secrets[0], test_secrets[1]);
+    }
+
+    #[test]
+    fn find_with_sort_and_limit_and_offset() {
+        let conn = get_test_db_connection();
+
+        let test_secrets = insert_test_secrets(&conn);
+
+        let test_query = SecretQuery {
+            secret_id: None,
+            name: None,
+            description: None,
+            created_before: None,
+            created_after: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            sort: Some(String::from("desc(name)")),
+            limit: Some(2),
+            offset: Some(1),
+        };
+
+        let found_secrets = SecretData::find(&conn, test_query).expect("Failed to find secrets");
+
+        assert_eq!(found_secrets.len(), 2);
+        assert_eq!(found_secrets[0], test_secrets[1]);
+        assert_eq!(found_secrets[1], test_secrets[0]);
+    }
+
+    #[test]
+    fn find_encrypted_value_by_name_exists() {
+        let conn = get_test_db_connection();
+
+        insert_test_secret(&conn);
+
+        let found_value = SecretData::find_encrypted_value_by_name(&conn, "Kevin's Secret")
+            .expect("Failed to retrieve test secret value by name.");
+
+        assert_eq!(found_value, vec![1u8, 2, 3, 4]);
+    }
+
+    #[test]
+    fn find_encrypted_value_by_name_not_exists() {
+        let conn = get_test_db_connection();
+
+        let nonexistent_value = SecretData::find_encrypted_value_by_name(&conn, "Kevin's Secret");
+
+        assert!(matches!(
+            nonexistent_value,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn find_all_encrypted_values_success() {
+        let conn = get_test_db_connection();
+
+        insert_test_secrets(&conn);
+
+        let mut found_values =
+            SecretData::find_all_encrypted_values(&conn).expect("Failed to retrieve secret values");
+        found_values.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            found_values,
+            vec![
+                (String::from("Secret A"), vec![1u8, 2, 3]),
+                (String::from("Secret B"), vec![4u8, 5, 6]),
+                (String::from("Secret C"), vec![7u8, 8, 9]),
+            ]
+        );
+    }
+
+    #[test]
+    fn create_success() {
+        let conn = get_test_db_connection();
+
+        let test_secret = insert_test_secret(&conn);
+
+        assert_eq!(test_secret.name, "Kevin's Secret");
+        assert_eq!(
+            test_secret
+                .description
+                .expect("Inserted secret missing description"),
+            "Kevin made this secret for testing"
+        );
+        assert_eq!(
+            test_secret
+                .created_by
+                .expect("Inserted secret missing created_by"),
+            "Kevin@example.com"
+        );
+    }
+
+    #[test]
+    fn create_failure_same_name() {
+        let conn = get_test_db_connection();
+
+        insert_test_secret(&conn);
+
+        let copy_secret = NewSecret {
+            name: String::from("Kevin's Secret"),
+            description: None,
+            encrypted_value: vec![5u8],
+            created_by: None,
+        };
+
+        let new_secret = SecretData::create(&conn, copy_secret);
+
+        assert!(matches!(
+            new_secret,
+            Err(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            ),)
+        ));
+    }
+
+    #[test]
+    fn update_success() {
+        let conn = get_test_db_connection();
+
+        let test_secret = insert_test_secret(&conn);
+
+        let changes = SecretChangeset {
+            name: Some(String::from("TestTestTestTest")),
+            description: Some(String::from("TESTTESTTESTTEST")),
+            encrypted_value: Some(vec![9u8, 9, 9]),
+        };
+
+        let updated_secret = SecretData::update(&conn, test_secret.secret_id, changes)
+            .expect("Failed to update secret");
+
+        assert_eq!(updated_secret.name, String::from("TestTestTestTest"));
+        assert_eq!(
+            updated_secret.description.unwrap(),
+            String::from("TESTTESTTESTTEST")
+        );
+        assert_eq!(
+            SecretData::find_encrypted_value_by_name(&conn, "TestTestTestTest").unwrap(),
+            vec![9u8, 9, 9]
+        );
+    }
+
+    #[test]
+    fn delete_success() {
+        let conn = get_test_db_connection();
+
+        let test_secret = insert_test_secret(&conn);
+
+        let delete_result = SecretData::delete(&conn, test_secret.secret_id).unwrap();
+
+        assert_eq!(delete_result, 1);
+
+        let deleted_secret = SecretData::find_by_id(&conn, test_secret.secret_id);
+
+        assert!(matches!(
+            deleted_secret,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+}
@@ -27,6 +27,7 @@ pub struct SoftwareBuildData {
     pub image_url: Option<String>,
     pub created_at: NaiveDateTime,
     pub finished_at: Option<NaiveDateTime>,
+    pub cromwell_server: Option<String>,
 }
 
 /// Represents all possible parameters for a query of the SOFTWARE_BUILD table
@@ -67,8 +68,8 @@ pub struct NewSoftwareBuild {
 
 /// Represents fields to change when updating a software build
 ///
-/// Only build_job_id, status, image_url, and finished_at can be modified after the software build has
-/// been created
+/// Only build_job_id, status, image_url, finished_at, and cromwell_server can be modified after the
+/// software build has been created
 #[derive(Deserialize, Serialize, AsChangeset, Debug)]
 #[table_name = "software_build"]
 pub struct SoftwareBuildChangeset {
@@ -76,6 +77,7 @@ pub struct SoftwareBuildChangeset {
     pub finished_at: Option<NaiveDateTime>,
     pub build_job_id: Option<String>,
     pub status: Option<BuildStatusEnum>,
+    pub cromwell_server: Option<String>,
 }
 
 impl SoftwareBuildData {
@@ -102,6 +104,20 @@ impl SoftwareBuildData {
             .load::<Self>(conn)
     }
 
+    /// Queries the DB for software_builds that have been `Created` since before `cutoff`
+    ///
+    /// Returns result containing either a vector of the retrieved software_builds or a diesel error
+    /// if retrieving the rows fails for some reason
+    pub fn find_created_before(
+        conn: &PgConnection,
+        cutoff: NaiveDateTime,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        software_build
+            .filter(status.eq(BuildStatusEnum::Created))
+            .filter(created_at.lt(cutoff))
+            .load::<Self>(conn)
+    }
+
     /// Queries the DB for the most recent builds for each software_version associated with the run
     /// specified by `id` via the RUN_SOFTWARE_VERSION table
     ///
@@ -430,6 +446,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -444,6 +463,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -458,6 +480,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(conn, new_test).expect("Failed inserting test test")
@@ -532,6 +558,44 @@ mod tests {
         assert_eq!(found_software_builds[0], test_software_builds[2]);
     }
 
+    #[test]
+    fn find_created_before_success() {
+        let conn = get_test_db_connection();
+
+        insert_software_builds_with_versions(&conn);
+        let test_software_build = insert_test_software_build(&conn);
+        let created_software_build = SoftwareBuildData::create(
+            &conn,
+            NewSoftwareBuild {
+                software_version_id: test_software_build.software_version_id,
+                build_job_id: None,
+                status: BuildStatusEnum::Created,
+                image_url: None,
+                finished_at: None,
+            },
+        )
+        .expect("Failed inserting test software_build");
+
+        // created_at is set to the start of the test transaction, so use a cutoff after that
+        let found_software_builds = SoftwareBuildData::find_created_before(
+            &conn,
+            Utc::now().naive_utc() + chrono::Duration::minutes(1),
+        )
+        .expect("Failed to find software_builds");
+
+        assert_eq!(found_software_builds.len(), 1);
+        assert_eq!(found_software_builds[0], created_software_build);
+
+        // And make sure none are returned for a cutoff before that
+        let found_software_builds = SoftwareBuildData::find_created_before(
+            &conn,
+            Utc::now().naive_utc() - chrono::Duration::minutes(1),
+        )
+        .expect("Failed to find software_builds");
+
+        assert!(found_software_builds.is_empty());
+    }
+
     #[test]
     // Not actually a perfect test of the functionality of the `find_most_recent_builds_for_run`
     // because most recent is determined by `created_at`, and, since `created_at` is set within
@@ -844,6 +908,7 @@ mod tests {
             finished_at: None,
             build_job_id: None,
             status: Some(BuildStatusEnum::Succeeded),
+            cromwell_server: None,
         };
 
         let updated_software_build =
@@ -5,7 +5,7 @@
 //! WDL (but not new inputs) for execution or evaluation, a new template is required.  Represented
 //! in the database by the TEMPLATE table.
 
-use crate::custom_sql_types::{RunStatusEnum, RUN_FAILURE_STATUSES};
+use crate::custom_sql_types::{RunStatusEnum, WorkflowLanguageEnum, RUN_FAILURE_STATUSES};
 use crate::models::pipeline::PipelineData;
 use crate::schema::pipeline;
 use crate::schema::run;
@@ -19,6 +19,7 @@ use diesel::dsl::{all, any};
 use diesel::prelude::*;
 use log::error;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 
 /// Mapping to a template as it exists in the TEMPLATE table in the database.
@@ -36,6 +37,9 @@ pub struct TemplateData {
     pub eval_wdl_dependencies: Option<String>,
     pub created_at: NaiveDateTime,
     pub created_by: Option<String>,
+    pub cromwell_server: Option<String>,
+    pub workflow_option_defaults: Option<Value>,
+    pub workflow_language: WorkflowLanguageEnum,
 }
 
 /// Represents all possible parameters for a query of the TEMPLATE table
@@ -57,6 +61,7 @@ pub struct TemplateQuery {
     pub created_before: Option<NaiveDateTime>,
     pub created_after: Option<NaiveDateTime>,
     pub created_by: Option<String>,
+    pub workflow_language: Option<WorkflowLanguageEnum>,
     pub sort: Option<String>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
@@ -64,9 +69,10 @@ pub struct TemplateQuery {
 
 /// A new template to be inserted into the DB
 ///
-/// name, pipeline_id, test_wdl, and eval_wdl are required fields, but description and created_by
-/// are not, so can be filled with `None`; template_id and created_at are populated automatically
-/// by the DB
+/// name, pipeline_id, test_wdl, and eval_wdl are required fields, but description, created_by,
+/// cromwell_server, workflow_option_defaults, and workflow_language are not, so can be filled with
+/// `None`; template_id and created_at are populated automatically by the DB, and workflow_language
+/// defaults to wdl
 #[derive(Deserialize, Serialize, Insertable)]
 #[table_name = "template"]
 pub struct NewTemplate {
@@ -78,12 +84,15 @@ pub struct NewTemplate {
     pub eval_wdl: String,
     pub eval_wdl_dependencies: Option<String>,
     pub created_by: Option<String>,
+    pub cromwell_server: Option<String>,
+    pub workflow_option_defaults: Option<Value>,
+    pub workflow_language: Option<WorkflowLanguageEnum>,
 }
 
 /// Represents fields to change when updating a template
 ///
 /// Only name and description can be modified if the template has a non-failed run attached to it.
-/// test_wdl and eval_wdl can be modified as long as that is not the case
+/// test_wdl, eval_wdl, and workflow_language can be modified as long as that is not the case
 #[derive(Deserialize, Serialize, AsChangeset, Clone)]
 #[table_name = "template"]
 pub struct TemplateChangeset {
@@ -93,6 +102,9 @@ pub struct TemplateChangeset {
     pub test_wdl_dependencies: Option<String>,
     pub eval_wdl: Option<String>,
     pub eval_wdl_dependencies: Option<String>,
+    pub cromwell_server: Option<String>,
+    pub workflow_option_defaults: Option<Value>,
+    pub workflow_language: Option<WorkflowLanguageEnum>,
 }
 
 /// Represents an error generated by an attempt at updating a row in the TEMPLATE table
@@ -197,6 +209,9 @@ impl TemplateData {
         if let Some(param) = params.created_by {
             query = query.filter(created_by.eq(param));
         }
+        if let Some(param) = params.workflow_language {
+            query = query.filter(workflow_language.eq(param));
+        }
 
         // If there is a sort param, parse it and add to the order by clause accordingly
         if let Some(sort) = params.sort {
@@ -336,7 +351,7 @@ impl TemplateData {
     /// Updates the template row in the DB using `conn` specified by `id` with the values in
     /// `params`, provided the update parameters are allowed.  If the template has any non-failed
     /// runs associated with it, then attempting to update the test_wdl, test_wdl_dependencies,
-    /// eval_wdl, or eval_wdl_dependencies fields will result in an error
+    /// eval_wdl, eval_wdl_dependencies, or workflow_language fields will result in an error
     ///
     /// Returns a result containing either the newly updated template or an error if the update
     /// fails for some reason
@@ -345,17 +360,18 @@ impl TemplateData {
         id: Uuid,
         params: TemplateChangeset,
     ) -> Result<Self, UpdateError> {
-        // If trying to update the test_wdl, eval_wdl, or their dependencies, verify that no
-        // non-failed runs exist for this template
+        // If trying to update the test_wdl, eval_wdl, their dependencies, or the language they're
+        // written in, verify that no non-failed runs exist for this template
         if params.test_wdl.is_some()
             || params.eval_wdl.is_some()
             || params.test_wdl_dependencies.is_some()
             || params.eval_wdl_dependencies.is_some()
+            || params.workflow_language.is_some()
         {
             match Self::has_nonfailed_runs(conn, id) {
                 // If there is a nonfailed run, return an error
                 Ok(true) => {
-                    let err = UpdateError::Prohibited(String::from("Attempted to update test_wdl, eval_wdl, and/or their dependencies when a non-failed run already exists for this template.  Doing so is prohibited"));
+                    let err = UpdateError::Prohibited(String::from("Attempted to update test_wdl, eval_wdl, their dependencies, and/or workflow_language when a non-failed run already exists for this template.  Doing so is prohibited"));
                     error!("Failed to update due to error: {}", err);
                     return Err(err);
                 }
@@ -436,6 +452,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -450,6 +469,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(conn, new_template).expect("Failed inserting test template")
@@ -465,6 +487,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(conn, new_test).expect("Failed inserting test test")
@@ -602,6 +628,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline")
@@ -619,6 +648,9 @@ mod tests {
             eval_wdl: String::from("eval3test"),
             eval_wdl_dependencies: Some(String::from("eval3testdep")),
             created_by: Some(String::from("Test@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         templates.push(
@@ -634,6 +666,9 @@ mod tests {
             eval_wdl: String::from("eval2test"),
             eval_wdl_dependencies: Some(String::from("eval2testdep")),
             created_by: Some(String::from("Test@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         templates.push(
@@ -649,6 +684,9 @@ mod tests {
             eval_wdl: String::from("eval1test"),
             eval_wdl_dependencies: Some(String::from("eval1testdep")),
             created_by: Some(String::from("Test@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         templates.push(
@@ -733,6 +771,7 @@ mod tests {
             sort: None,
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -766,6 +805,7 @@ mod tests {
             sort: None,
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -798,6 +838,7 @@ mod tests {
             sort: None,
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -830,6 +871,7 @@ mod tests {
             sort: Some(String::from("name")),
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -864,6 +906,7 @@ mod tests {
             sort: None,
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -896,6 +939,7 @@ mod tests {
             sort: None,
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -928,6 +972,7 @@ mod tests {
             sort: None,
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -960,6 +1005,7 @@ mod tests {
             sort: None,
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -992,6 +1038,7 @@ mod tests {
             sort: None,
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -1024,6 +1071,7 @@ mod tests {
             sort: Some(String::from("description,desc(name)")),
             limit: Some(2),
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -1049,6 +1097,7 @@ mod tests {
             sort: Some(String::from("description,desc(name)")),
             limit: Some(2),
             offset: Some(2),
+            workflow_language: None,
         };
 
         let found_templates =
@@ -1081,6 +1130,7 @@ mod tests {
             sort: None,
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -1104,6 +1154,7 @@ mod tests {
             sort: None,
             limit: None,
             offset: None,
+            workflow_language: None,
         };
 
         let found_templates =
@@ -1150,6 +1201,9 @@ mod tests {
             eval_wdl: test_template.eval_wdl,
             eval_wdl_dependencies: test_template.eval_wdl_dependencies,
             created_by: test_template.created_by,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let new_template = TemplateData::create(&conn, copy_template);
@@ -1178,6 +1232,9 @@ mod tests {
             test_wdl_dependencies: Some(String::from("testtesttestdep")),
             eval_wdl: Some(String::from("evalevaleval")),
             eval_wdl_dependencies: Some(String::from("evalevalevaldep")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let updated_template = TemplateData::update(&conn, test_template.template_id, changes)
@@ -1215,6 +1272,9 @@ mod tests {
             test_wdl_dependencies: None,
             eval_wdl: None,
             eval_wdl_dependencies: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let updated_template = TemplateData::update(&conn, test_template.template_id, changes)
@@ -1241,6 +1301,9 @@ mod tests {
             test_wdl_dependencies: None,
             eval_wdl: Some(String::from("evalevaleval")),
             eval_wdl_dependencies: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let updated_template = TemplateData::update(&conn, test_templates[1].template_id, changes);
@@ -1269,6 +1332,9 @@ mod tests {
             test_wdl_dependencies: Some(String::from("testtesttestdep")),
             eval_wdl: Some(String::from("evalevaleval")),
             eval_wdl_dependencies: Some(String::from("evalevalevaldep")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let updated_template = TemplateData::update(&conn, test_template.template_id, changes);
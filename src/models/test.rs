@@ -34,6 +34,10 @@ pub struct TestData {
     pub eval_option_defaults: Option<Value>,
     pub created_at: NaiveDateTime,
     pub created_by: Option<String>,
+    pub max_concurrent_runs: Option<i32>,
+    pub priority: i32,
+    pub max_runtime_in_secs: Option<i32>,
+    pub cromwell_server: Option<String>,
 }
 
 /// Represents all possible parameters for a query of the TEST table
@@ -63,8 +67,10 @@ pub struct TestQuery {
 /// A new test to be inserted into the DB
 ///
 /// name and template_id are required fields, but description, test_input_defaults,
-/// eval_input_defaults, and created_by are not, so can be filled with `None`
-/// test_id and created_at are populated automatically by the DB
+/// eval_input_defaults, created_by, max_concurrent_runs, priority, max_runtime_in_secs, and
+/// cromwell_server are not, so can be filled with `None`
+/// test_id and created_at are populated automatically by the DB, as is priority (with 0) if it is
+/// not specified
 #[derive(Deserialize, Serialize, Insertable)]
 #[table_name = "test"]
 pub struct NewTest {
@@ -76,6 +82,10 @@ pub struct NewTest {
     pub eval_input_defaults: Option<Value>,
     pub eval_option_defaults: Option<Value>,
     pub created_by: Option<String>,
+    pub max_concurrent_runs: Option<i32>,
+    pub priority: Option<i32>,
+    pub max_runtime_in_secs: Option<i32>,
+    pub cromwell_server: Option<String>,
 }
 
 /// Represents fields to change when updating a test
@@ -90,6 +100,10 @@ pub struct TestChangeset {
     pub test_option_defaults: Option<Value>,
     pub eval_input_defaults: Option<Value>,
     pub eval_option_defaults: Option<Value>,
+    pub max_concurrent_runs: Option<i32>,
+    pub priority: Option<i32>,
+    pub max_runtime_in_secs: Option<i32>,
+    pub cromwell_server: Option<String>,
 }
 
 /// Represents an error generated by an attempt at updating a row in the TEST table
@@ -402,6 +416,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -416,6 +433,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -430,6 +450,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: Some(serde_json::from_str("{\"eval_option\":\"test\"}").unwrap()),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(conn, new_test).expect("Failed inserting test test")
@@ -447,6 +471,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -461,6 +488,9 @@ mod tests {
             eval_wdl: String::from("evalevaleval"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(conn, new_template).expect("Failed inserting test template")
@@ -482,6 +512,10 @@ mod tests {
                 serde_json::from_str("{\"eval_option\":\"test3\"}").unwrap(),
             ),
             created_by: Some(String::from("Test@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         tests.push(TestData::create(conn, new_test).expect("Failed inserting test test"));
@@ -499,6 +533,10 @@ mod tests {
                 serde_json::from_str("{\"eval_option\":\"test2\"}").unwrap(),
             ),
             created_by: Some(String::from("Test@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         tests.push(TestData::create(conn, new_test).expect("Failed inserting test test"));
@@ -516,6 +554,10 @@ mod tests {
                 serde_json::from_str("{\"eval_option\":\"test1\"}").unwrap(),
             ),
             created_by: Some(String::from("Test@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         tests.push(TestData::create(conn, new_test).expect("Failed inserting test test"));
@@ -1135,6 +1177,10 @@ mod tests {
             eval_input_defaults: test_test.eval_input_defaults,
             eval_option_defaults: test_test.eval_option_defaults,
             created_by: test_test.created_by,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let new_test = TestData::create(&conn, copy_test);
@@ -1166,6 +1212,10 @@ mod tests {
             eval_option_defaults: Some(
                 serde_json::from_str("{\"eval_option\":\"test2\"}").unwrap(),
             ),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let updated_test =
@@ -1208,6 +1258,10 @@ mod tests {
             test_option_defaults: None,
             eval_input_defaults: None,
             eval_option_defaults: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let updated_test =
@@ -1234,6 +1288,10 @@ mod tests {
             test_option_defaults: None,
             eval_input_defaults: None,
             eval_option_defaults: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let updated_test = TestData::update(&conn, test_tests[1].test_id, changes);
@@ -1261,6 +1319,10 @@ mod tests {
             test_option_defaults: None,
             eval_input_defaults: None,
             eval_option_defaults: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let updated_test = TestData::update(&conn, test_test.test_id, changes);
@@ -1284,6 +1346,10 @@ mod tests {
             ),
             eval_input_defaults: None,
             eval_option_defaults: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let updated_test = TestData::update(&conn, test_test.test_id, changes);
@@ -1305,6 +1371,10 @@ mod tests {
             test_option_defaults: None,
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test2\"}").unwrap()),
             eval_option_defaults: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let updated_test = TestData::update(&conn, test_test.test_id, changes);
@@ -1328,6 +1398,10 @@ mod tests {
             eval_option_defaults: Some(
                 serde_json::from_str("{\"eval_option\":\"test2\"}").unwrap(),
             ),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let updated_test = TestData::update(&conn, test_test.test_id, changes);
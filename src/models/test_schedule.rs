@@ -0,0 +1,635 @@
+//! Contains structs and functions for doing operations on test schedules.
+//!
+//! A test schedule is a cron expression associated with a test, along with optional inputs and
+//! options to use in place of the test's defaults.  A run of the test is started each time the
+//! schedule comes due.  Represented in the database by the TEST_SCHEDULE table.
+
+use crate::schema::test_schedule;
+use crate::schema::test_schedule::dsl::*;
+use crate::util;
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Mapping to a test schedule as it exists in the TEST_SCHEDULE table in the database.
+///
+/// An instance of this struct will be returned by any queries for test schedules.
+#[derive(Queryable, Deserialize, Serialize, PartialEq, Debug)]
+pub struct TestScheduleData {
+    pub test_schedule_id: Uuid,
+    pub test_id: Uuid,
+    pub cron_expression: String,
+    pub test_input: Option<Value>,
+    pub test_options: Option<Value>,
+    pub eval_input: Option<Value>,
+    pub eval_options: Option<Value>,
+    pub enabled: bool,
+    pub last_scheduled_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub created_by: Option<String>,
+}
+
+/// Represents all possible parameters for a query of the TEST_SCHEDULE table
+///
+/// All values are optional, so any combination can be used during a query.  Limit and offset are
+/// used for pagination.  Sort expects a comma-separated list of sort keys, optionally enclosed
+/// with either asc() or desc().  For example: asc(created_at),desc(cron_expression)
+#[derive(Deserialize)]
+pub struct TestScheduleQuery {
+    pub test_schedule_id: Option<Uuid>,
+    pub test_id: Option<Uuid>,
+    pub cron_expression: Option<String>,
+    pub enabled: Option<bool>,
+    pub created_before: Option<NaiveDateTime>,
+    pub created_after: Option<NaiveDateTime>,
+    pub created_by: Option<String>,
+    pub sort: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// A new test schedule to be inserted into the DB
+///
+/// test_id, cron_expression, and enabled are required fields, but test_input, test_options,
+/// eval_input, eval_options, and created_by are not, so can be filled with `None`
+/// test_schedule_id, last_scheduled_at, and created_at are populated automatically by the DB
+#[derive(Deserialize, Serialize, Insertable)]
+#[table_name = "test_schedule"]
+pub struct NewTestSchedule {
+    pub test_id: Uuid,
+    pub cron_expression: String,
+    pub test_input: Option<Value>,
+    pub test_options: Option<Value>,
+    pub eval_input: Option<Value>,
+    pub eval_options: Option<Value>,
+    pub enabled: bool,
+    pub created_by: Option<String>,
+}
+
+/// Represents fields to change when updating a test schedule
+///
+/// The test a schedule belongs to cannot be changed after the schedule has been created
+#[derive(Deserialize, Serialize, AsChangeset)]
+#[table_name = "test_schedule"]
+pub struct TestScheduleChangeset {
+    pub cron_expression: Option<String>,
+    pub test_input: Option<Value>,
+    pub test_options: Option<Value>,
+    pub eval_input: Option<Value>,
+    pub eval_options: Option<Value>,
+    pub enabled: Option<bool>,
+}
+
+impl TestScheduleData {
+    /// Queries the DB for a test schedule with the specified test_id and test_schedule_id
+    ///
+    /// Queries the DB using `conn` to retrieve the first row with a test_id value of
+    /// `query_test_id` and a test_schedule_id value of `id`
+    /// Returns a result containing either the retrieved test schedule as a TestScheduleData
+    /// instance or an error if the query fails for some reason or if no test schedule is found
+    /// matching the criteria
+    pub fn find_by_test_and_id(
+        conn: &PgConnection,
+        query_test_id: Uuid,
+        id: Uuid,
+    ) -> Result<Self, diesel::result::Error> {
+        test_schedule
+            .filter(test_id.eq(query_test_id))
+            .filter(test_schedule_id.eq(id))
+            .first::<Self>(conn)
+    }
+
+    /// Queries the DB for test schedules matching the specified query criteria
+    ///
+    /// Queries the DB using `conn` to retrieve test schedules matching the crieria in `params`
+    /// Returns a result containing either a vector of the retrieved test schedules as
+    /// TestScheduleData instances or an error if the query fails for some reason
+    pub fn find(
+        conn: &PgConnection,
+        params: TestScheduleQuery,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        // Put the query into a box (pointer) so it can be built dynamically
+        let mut query = test_schedule.into_boxed();
+
+        // Add filters for each of the params if they have values
+        if let Some(param) = params.test_schedule_id {
+            query = query.filter(test_schedule_id.eq(param));
+        }
+        if let Some(param) = params.test_id {
+            query = query.filter(test_id.eq(param));
+        }
+        if let Some(param) = params.cron_expression {
+            query = query.filter(cron_expression.eq(param));
+        }
+        if let Some(param) = params.enabled {
+            query = query.filter(enabled.eq(param));
+        }
+        if let Some(param) = params.created_before {
+            query = query.filter(created_at.lt(param));
+        }
+        if let Some(param) = params.created_after {
+            query = query.filter(created_at.gt(param));
+        }
+        if let Some(param) = params.created_by {
+            query = query.filter(created_by.eq(param));
+        }
+
+        // If there is a sort param, parse it and add to the order by clause accordingly
+        if let Some(sort) = params.sort {
+            let sort = util::sort_string::parse_sort_string(&sort);
+            for sort_clause in sort {
+                match &sort_clause.key[..] {
+                    "test_schedule_id" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(test_schedule_id.asc());
+                        } else {
+                            query = query.then_order_by(test_schedule_id.desc());
+                        }
+                    }
+                    "test_id" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(test_id.asc());
+                        } else {
+                            query = query.then_order_by(test_id.desc());
+                        }
+                    }
+                    "cron_expression" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(cron_expression.asc());
+                        } else {
+                            query = query.then_order_by(cron_expression.desc());
+                        }
+                    }
+                    "enabled" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(enabled.asc());
+                        } else {
+                            query = query.then_order_by(enabled.desc());
+                        }
+                    }
+                    "last_scheduled_at" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(last_scheduled_at.asc());
+                        } else {
+                            query = query.then_order_by(last_scheduled_at.desc());
+                        }
+                    }
+                    "created_at" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(created_at.asc());
+                        } else {
+                            query = query.then_order_by(created_at.desc());
+                        }
+                    }
+                    "created_by" => {
+                        if sort_clause.ascending {
+                            query = query.then_order_by(created_by.asc());
+                        } else {
+                            query = query.then_order_by(created_by.desc());
+                        }
+                    }
+                    // Don't add to the order by clause if the sort key isn't recognized
+                    &_ => {}
+                }
+            }
+        }
+
+        if let Some(param) = params.limit {
+            query = query.limit(param);
+        }
+        if let Some(param) = params.offset {
+            query = query.offset(param);
+        }
+
+        // Perform the query
+        query.load::<Self>(conn)
+    }
+
+    /// Queries the DB for test schedules that are enabled
+    ///
+    /// Queries the DB using `conn` to retrieve all test schedules with an enabled value of true
+    /// Returns a result containing either a vector of the retrieved test schedules as
+    /// TestScheduleData instances or an error if the query fails for some reason
+    pub fn find_enabled(conn: &PgConnection) -> Result<Vec<Self>, diesel::result::Error> {
+        test_schedule
+            .filter(enabled.eq(true))
+            .order_by(created_at.asc())
+            .load::<Self>(conn)
+    }
+
+    /// Inserts a new test schedule into the DB
+    ///
+    /// Creates a new test schedule row in the DB using `conn` with the values specified in
+    /// `params`
+    /// Returns a result containing either the new test schedule that was created or an error if
+    /// the insert fails for some reason
+    pub fn create(
+        conn: &PgConnection,
+        params: NewTestSchedule,
+    ) -> Result<Self, diesel::result::Error> {
+        diesel::insert_into(test_schedule)
+            .values(&params)
+            .get_result(conn)
+    }
+
+    /// Updates a specified test schedule in the DB
+    ///
+    /// Updates the test schedule row in the DB using `conn` specified by `query_test_id` and `id`
+    /// with the values in `params`
+    /// Returns a result containing either the newly updated test schedule or an error if the
+    /// update fails for some reason
+    pub fn update(
+        conn: &PgConnection,
+        query_test_id: Uuid,
+        id: Uuid,
+        params: TestScheduleChangeset,
+    ) -> Result<Self, diesel::result::Error> {
+        diesel::update(
+            test_schedule
+                .filter(test_id.eq(query_test_id))
+                .filter(test_schedule_id.eq(id)),
+        )
+        .set(params)
+        .get_result(conn)
+    }
+
+    /// Sets the last_scheduled_at value for the test schedule specified by `id` in the DB
+    ///
+    /// Updates the test schedule row in the DB using `conn` specified by `id` to have a
+    /// last_scheduled_at value of `scheduled_at`
+    /// Returns a result containing either the newly updated test schedule or an error if the
+    /// update fails for some reason
+    pub fn update_last_scheduled_at(
+        conn: &PgConnection,
+        id: Uuid,
+        scheduled_at: NaiveDateTime,
+    ) -> Result<Self, diesel::result::Error> {
+        diesel::update(test_schedule.filter(test_schedule_id.eq(id)))
+            .set(last_scheduled_at.eq(scheduled_at))
+            .get_result(conn)
+    }
+
+    /// Deletes a specific test schedule in the DB
+    ///
+    /// Deletes the test schedule row in the DB using `conn` specified by `query_test_id` and `id`
+    /// Returns a result containing either the number of rows deleted or an error if the delete
+    /// fails for some reason
+    pub fn delete(
+        conn: &PgConnection,
+        query_test_id: Uuid,
+        id: Uuid,
+    ) -> Result<usize, diesel::result::Error> {
+        diesel::delete(
+            test_schedule
+                .filter(test_id.eq(query_test_id))
+                .filter(test_schedule_id.eq(id)),
+        )
+        .execute(conn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::unit_test_util::*;
+    use chrono::NaiveDate;
+    use uuid::Uuid;
+
+    fn insert_test_test(conn: &PgConnection) -> TestData {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's Template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing")),
+            test_wdl: String::from("testtest"),
+            test_wdl_dependencies: None,
+            eval_wdl: String::from("evaltest"),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: Some(serde_json::from_str("{\"test\":\"test\"}").unwrap()),
+            test_option_defaults: None,
+            eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        TestData::create(conn, new_test).expect("Failed inserting test test")
+    }
+
+    fn insert_test_test_schedules(conn: &PgConnection) -> Vec<TestScheduleData> {
+        let test = insert_test_test(conn);
+
+        let mut test_schedules = Vec::new();
+
+        let new_test_schedule = NewTestSchedule {
+            test_id: test.test_id,
+            cron_expression: String::from("0 2 * * *"),
+            test_input: Some(serde_json::from_str("{\"test\":\"nightly\"}").unwrap()),
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: true,
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        test_schedules.push(
+            TestScheduleData::create(conn, new_test_schedule)
+                .expect("Failed inserting test test_schedule"),
+        );
+
+        let new_test_schedule = NewTestSchedule {
+            test_id: test.test_id,
+            cron_expression: String::from("0 4 * * 1"),
+            test_input: None,
+            test_options: None,
+            eval_input: Some(serde_json::from_str("{\"eval\":\"weekly\"}").unwrap()),
+            eval_options: None,
+            enabled: false,
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        test_schedules.push(
+            TestScheduleData::create(conn, new_test_schedule)
+                .expect("Failed inserting test test_schedule"),
+        );
+
+        let new_test_schedule = NewTestSchedule {
+            test_id: test.test_id,
+            cron_expression: String::from("*/30 * * * *"),
+            test_input: None,
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: true,
+            created_by: Some(String::from("Kelvin@example.com")),
+        };
+
+        test_schedules.push(
+            TestScheduleData::create(conn, new_test_schedule)
+                .expect("Failed inserting test test_schedule"),
+        );
+
+        test_schedules
+    }
+
+    #[test]
+    fn find_by_test_and_id_exists() {
+        let conn = get_test_db_connection();
+
+        let test_test_schedules = insert_test_test_schedules(&conn);
+
+        let found_test_schedule = TestScheduleData::find_by_test_and_id(
+            &conn,
+            test_test_schedules[0].test_id,
+            test_test_schedules[0].test_schedule_id,
+        )
+        .expect("Failed to retrieve test test_schedule by id.");
+
+        assert_eq!(found_test_schedule, test_test_schedules[0]);
+    }
+
+    #[test]
+    fn find_by_test_and_id_not_exists() {
+        let conn = get_test_db_connection();
+
+        let nonexistent_test_schedule =
+            TestScheduleData::find_by_test_and_id(&conn, Uuid::new_v4(), Uuid::new_v4());
+
+        assert!(matches!(
+            nonexistent_test_schedule,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[test]
+    fn find_with_enabled() {
+        let conn = get_test_db_connection();
+
+        let test_test_schedules = insert_test_test_schedules(&conn);
+
+        let test_query = TestScheduleQuery {
+            test_schedule_id: None,
+            test_id: Some(test_test_schedules[0].test_id),
+            cron_expression: None,
+            enabled: Some(false),
+            created_before: None,
+            created_after: None,
+            created_by: None,
+            sort: None,
+            limit: None,
+            offset: None,
+        };
+
+        let found_test_schedules =
+            TestScheduleData::find(&conn, test_query).expect("Failed to find test_schedules");
+
+        assert_eq!(found_test_schedules.len(), 1);
+        assert_eq!(found_test_schedules[0], test_test_schedules[1]);
+    }
+
+    #[test]
+    fn find_with_sort_and_limit_and_offset() {
+        let conn = get_test_db_connection();
+
+        let test_test_schedules = insert_test_test_schedules(&conn);
+
+        let test_query = TestScheduleQuery {
+            test_schedule_id: None,
+            test_id: Some(test_test_schedules[0].test_id),
+            cron_expression: None,
+            enabled: None,
+            created_before: None,
+            created_after: None,
+            created_by: None,
+            sort: Some(String::from("desc(cron_expression)")),
+            limit: Some(2),
+            offset: Some(1),
+        };
+
+        let found_test_schedules =
+            TestScheduleData::find(&conn, test_query).expect("Failed to find test_schedules");
+
+        assert_eq!(found_test_schedules.len(), 2);
+        assert_eq!(found_test_schedules[0], test_test_schedules[0]);
+        assert_eq!(found_test_schedules[1], test_test_schedules[2]);
+    }
+
+    #[test]
+    fn find_with_created_by() {
+        let conn = get_test_db_connection();
+
+        let test_test_schedules = insert_test_test_schedules(&conn);
+
+        let test_query = TestScheduleQuery {
+            test_schedule_id: None,
+            test_id: None,
+            cron_expression: None,
+            enabled: None,
+            created_before: None,
+            created_after: None,
+            created_by: Some(String::from("Kelvin@example.com")),
+            sort: None,
+            limit: None,
+            offset: None,
+        };
+
+        let found_test_schedules =
+            TestScheduleData::find(&conn, test_query).expect("Failed to find test_schedules");
+
+        assert_eq!(found_test_schedules.len(), 1);
+        assert_eq!(found_test_schedules[0], test_test_schedules[2]);
+    }
+
+    #[test]
+    fn find_enabled_success() {
+        let conn = get_test_db_connection();
+
+        let test_test_schedules = insert_test_test_schedules(&conn);
+
+        let found_test_schedules =
+            TestScheduleData::find_enabled(&conn).expect("Failed to find enabled test_schedules");
+
+        assert_eq!(found_test_schedules.len(), 2);
+        assert!(found_test_schedules.contains(&test_test_schedules[0]));
+        assert!(found_test_schedules.contains(&test_test_schedules[2]));
+    }
+
+    #[test]
+    fn create_success() {
+        let conn = get_test_db_connection();
+
+        let test = insert_test_test(&conn);
+
+        let new_test_schedule = NewTestSchedule {
+            test_id: test.test_id,
+            cron_expression: String::from("0 2 * * *"),
+            test_input: Some(serde_json::from_str("{\"test\":\"nightly\"}").unwrap()),
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: true,
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        let test_test_schedule = TestScheduleData::create(&conn, new_test_schedule)
+            .expect("Failed inserting test test_schedule");
+
+        assert_eq!(test_test_schedule.test_id, test.test_id);
+        assert_eq!(test_test_schedule.cron_expression, "0 2 * * *");
+        assert_eq!(
+            test_test_schedule.test_input.unwrap(),
+            serde_json::from_str::<Value>("{\"test\":\"nightly\"}").unwrap()
+        );
+        assert!(test_test_schedule.enabled);
+        assert!(test_test_schedule.last_scheduled_at.is_none());
+    }
+
+    #[test]
+    fn update_success() {
+        let conn = get_test_db_connection();
+
+        let test_test_schedules = insert_test_test_schedules(&conn);
+
+        let changes = TestScheduleChangeset {
+            cron_expression: Some(String::from("0 3 * * *")),
+            test_input: None,
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: Some(false),
+        };
+
+        let updated_test_schedule = TestScheduleData::update(
+            &conn,
+            test_test_schedules[0].test_id,
+            test_test_schedules[0].test_schedule_id,
+            changes,
+        )
+        .expect("Failed to update test_schedule");
+
+        assert_eq!(updated_test_schedule.cron_expression, "0 3 * * *");
+        assert!(!updated_test_schedule.enabled);
+        assert_eq!(
+            updated_test_schedule.test_input,
+            test_test_schedules[0].test_input
+        );
+    }
+
+    #[test]
+    fn update_last_scheduled_at_success() {
+        let conn = get_test_db_connection();
+
+        let test_test_schedules = insert_test_test_schedules(&conn);
+
+        let scheduled_at = NaiveDate::from_ymd(2022, 4, 12).and_hms(2, 0, 0);
+
+        let updated_test_schedule = TestScheduleData::update_last_scheduled_at(
+            &conn,
+            test_test_schedules[0].test_schedule_id,
+            scheduled_at,
+        )
+        .expect("Failed to update test_schedule");
+
+        assert_eq!(updated_test_schedule.last_scheduled_at, Some(scheduled_at));
+    }
+
+    #[test]
+    fn delete_success() {
+        let conn = get_test_db_connection();
+
+        let test_test_schedules = insert_test_test_schedules(&conn);
+
+        let delete_result = TestScheduleData::delete(
+            &conn,
+            test_test_schedules[0].test_id,
+            test_test_schedules[0].test_schedule_id,
+        )
+        .unwrap();
+
+        assert_eq!(delete_result, 1);
+
+        let deleted_test_schedule = TestScheduleData::find_by_test_and_id(
+            &conn,
+            test_test_schedules[0].test_id,
+            test_test_schedules[0].test_schedule_id,
+        );
+
+        assert!(matches!(
+            deleted_test_schedule,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+}
@@ -309,6 +309,7 @@ mod tests {
             created_at: Utc::now().naive_utc(),
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: None,
+            cromwell_server: None,
         };
         let test_run_string = serde_json::to_string_pretty(&test_run).unwrap();
 
@@ -400,6 +401,7 @@ mod tests {
                 "number_result":5,
             })),
             errors: None,
+            cromwell_server: None,
         };
         let test_run_string = serde_json::to_string_pretty(&test_run).unwrap();
 
@@ -465,6 +467,7 @@ mod tests {
                 "run_csv_zip":"gs://test_bucket/run_csvs.zip"
             })),
             cromwell_job_id: Some(String::from("as9283-054asdf32893a-sdfawe9")),
+            cromwell_server: None,
         };
 
         let expected_results = vec![
@@ -0,0 +1,318 @@
+//! Defines functionality for attaching credentials to requests to cromwell
+//!
+//! Cromwell servers that sit behind an auth proxy need an Authorization header on each request.
+//! The credentials can be a static bearer token, a username and password for basic auth, or a
+//! bearer token read from a file or printed by a command.  Tokens from files and commands are
+//! cached, and read again once they are older than the configured refresh interval or after
+//! cromwell rejects them.  Reading a token file or running a token command blocks, so it is done on
+//! the actix threadpool, and token commands are killed if they run longer than their timeout
+
+use crate::config::CromwellAuthConfig;
+use actix_web::client::ClientRequest;
+use actix_web::error::BlockingError;
+use actix_web::web;
+use log::debug;
+use std::fmt;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Struct for attaching credentials to requests to a cromwell server
+#[derive(Clone)]
+pub struct CromwellAuth {
+    config: CromwellAuthConfig,
+    /// The most recently retrieved token (and when it was retrieved), for the token file and token
+    /// command modes.  Shared among clones so a refreshed token is used by all of them
+    cached_token: Arc<Mutex<Option<(String, Instant)>>>,
+}
+
+/// Error type for possible errors returned by retrieving credentials
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Command(String),
+    EmptyToken(String),
+    Timeout(String),
+    Canceled,
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "Error Io {}", e),
+            Error::Command(e) => write!(f, "Error Command {}", e),
+            Error::EmptyToken(e) => write!(f, "Error EmptyToken {}", e),
+            Error::Timeout(e) => write!(f, "Error Timeout {}", e),
+            Error::Canceled => write!(f, "Error Canceled"),
+        }
+    }
+}
+
+// Implementing From for each of the error types so they map more easily
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl CromwellAuth {
+    /// Creates a new CromwellAuth that will attach the credentials specified by `config`
+    pub fn new(config: CromwellAuthConfig) -> CromwellAuth {
+        CromwellAuth {
+            config,
+            cached_token: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Adds the Authorization header to `request`, retrieving the token first if it hasn't been
+    /// retrieved yet or needs to be refreshed.  Returns an error if retrieving the token fails
+    pub async fn authorize(&self, request: ClientRequest) -> Result<ClientRequest, Error> {
+        match &self.config {
+            CromwellAuthConfig::Bearer(bearer_config) => {
+                Ok(request.bearer_auth(bearer_config.token()))
+            }
+            CromwellAuthConfig::Basic(basic_config) => Ok(request.basic_auth(
+                basic_config.username(),
+                Some(basic_config.password()),
+            )),
+            CromwellAuthConfig::TokenFile(_) | CromwellAuthConfig::TokenCommand(_) => {
+                Ok(request.bearer_auth(self.get_token().await?))
+            }
+        }
+    }
+
+    /// Returns true if the credentials can change when they are retrieved again (i.e. they come
+    /// from a file or command), so it's worth retrying a request that cromwell rejected
+    pub fn is_refreshable(&self) -> bool {
+        matches!(
+            self.config,
+            CromwellAuthConfig::TokenFile(_) | CromwellAuthConfig::TokenCommand(_)
+        )
+    }
+
+    /// Clears the cached token, so it will be retrieved again for the next request
+    pub fn invalidate(&self) {
+        *self
+            .cached_token
+            .lock()
+            .expect("Failed to lock cromwell auth token cache") = None;
+    }
+
+    /// Returns the cached token if there is one and it hasn't passed its refresh interval.
+    /// Otherwise, reads it from the file or runs the command to get it (on the actix threadpool,
+    /// without holding the lock on the cache), and caches it
+    async fn get_token(&self) -> Result<String, Error> {
+        let refresh_interval: Option<Duration> = match &self.config {
+            CromwellAuthConfig::TokenFile(token_file_config) => token_file_config
+                .refresh_interval_in_secs()
+                .map(Duration::from_secs),
+            CromwellAuthConfig::TokenCommand(token_command_config) => token_command_config
+                .refresh_interval_in_secs()
+                .map(Duration::from_secs),
+            _ => None,
+        };
+        if let Some((token, retrieved_at)) = &*self
+            .cached_token
+            .lock()
+            .expect("Failed to lock cromwell auth token cache")
+        {
+            match refresh_interval {
+                Some(refresh_interval) if retrieved_at.elapsed() >= refresh_interval => {}
+                _ => return Ok(token.clone()),
+            }
+        }
+        let config = self.config.clone();
+        let token = match web::block(move || CromwellAuth::retrieve_token(&config)).await {
+            Ok(token) => token,
+            Err(BlockingError::Error(e)) => return Err(e),
+            Err(BlockingError::Canceled) => return Err(Error::Canceled),
+        };
+        *self
+            .cached_token
+            .lock()
+            .expect("Failed to lock cromwell auth token cache") =
+            Some((token.clone(), Instant::now()));
+        Ok(token)
+    }
+
+    /// Reads the token from the file or runs the command to get it, depending on `config`.  This
+    /// blocks, so it should be run on the threadpool
+    fn retrieve_token(config: &CromwellAuthConfig) -> Result<String, Error> {
+        match config {
+            CromwellAuthConfig::TokenFile(token_file_config) => {
+                debug!(
+                    "Reading cromwell auth token from {}",
+                    token_file_config.path()
+                );
+                let token = std::fs::read_to_string(token_file_config.path())?;
+                CromwellAuth::trim_token(&token, token_file_config.path())
+            }
+            CromwellAuthConfig::TokenCommand(token_command_config) => {
+                CromwellAuth::run_token_command(
+                    token_command_config.command(),
+                    Duration::from_secs(token_command_config.timeout_in_secs()),
+                )
+            }
+            _ => Err(Error::EmptyToken(String::from(
+                "No token source configured",
+            ))),
+        }
+    }
+
+    /// Runs `command` (the program followed by its arguments) and returns the token it prints to
+    /// stdout.  Returns an error if the command can't be run, exits unsuccessfully, doesn't print
+    /// a token, or doesn't finish within `timeout` (in which case it is killed)
+    fn run_token_command(command: &[String], timeout: Duration) -> Result<String, Error> {
+        let (program, args) = match command.split_first() {
+            Some(program_and_args) => program_and_args,
+            None => return Err(Error::Command(String::from("Token command is empty"))),
+        };
+        debug!("Running {} to get cromwell auth token", program);
+        let mut child = Command::new(program)
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let started_at = Instant::now();
+        while child.try_wait()?.is_none() {
+            if started_at.elapsed() >= timeout {
+                // Kill it so it doesn't hang around
+                if let Err(e) = child.kill() {
+                    debug!("Failed to kill token command {} due to: {}", program, e);
+                }
+                child.wait()?;
+                return Err(Error::Timeout(format!(
+                    "Token command {} did not finish within {} seconds",
+                    program,
+                    timeout.as_secs()
+                )));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(Error::Command(format!(
+                "Token command {} exited with {}: {}",
+                program,
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        CromwellAuth::trim_token(&String::from_utf8_lossy(&output.stdout), program)
+    }
+
+    /// Trims surrounding whitespace (e.g. a trailing newline) from `token`, which came from
+    /// `source`.  Returns an error if there's nothing left
+    fn trim_token(token: &str, source: &str) -> Result<String, Error> {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(Error::EmptyToken(format!(
+                "No cromwell auth token found from {}",
+                source
+            )));
+        }
+        Ok(String::from(token))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{
+        CromwellAuthConfig, CromwellTokenCommandAuthConfig, CromwellTokenFileAuthConfig,
+    };
+    use crate::requests::cromwell_auth::{CromwellAuth, Error};
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[actix_rt::test]
+    async fn test_get_token_from_file_cached_until_invalidated() {
+        let mut token_file = NamedTempFile::new().unwrap();
+        writeln!(token_file, "first_token").unwrap();
+        let auth = CromwellAuth::new(CromwellAuthConfig::TokenFile(
+            CromwellTokenFileAuthConfig::new(
+                String::from(token_file.path().to_str().unwrap()),
+                None,
+            ),
+        ));
+
+        assert_eq!(auth.get_token().await.unwrap(), "first_token");
+
+        // Changing the file shouldn't change the token until it's invalidated
+        std::fs::write(token_file.path(), "second_token").unwrap();
+        assert_eq!(auth.get_token().await.unwrap(), "first_token");
+
+        auth.invalidate();
+        assert_eq!(auth.get_token().await.unwrap(), "second_token");
+    }
+
+    #[actix_rt::test]
+    async fn test_get_token_from_file_refresh_interval() {
+        let mut token_file = NamedTempFile::new().unwrap();
+        write!(token_file, "first_token").unwrap();
+        let auth = CromwellAuth::new(CromwellAuthConfig::TokenFile(
+            CromwellTokenFileAuthConfig::new(
+                String::from(token_file.path().to_str().unwrap()),
+                Some(0),
+            ),
+        ));
+
+        assert_eq!(auth.get_token().await.unwrap(), "first_token");
+
+        // With a refresh interval of 0, the file should be read every time
+        std::fs::write(token_file.path(), "second_token").unwrap();
+        assert_eq!(auth.get_token().await.unwrap(), "second_token");
+    }
+
+    #[actix_rt::test]
+    async fn test_get_token_from_file_empty() {
+        let token_file = NamedTempFile::new().unwrap();
+        let auth = CromwellAuth::new(CromwellAuthConfig::TokenFile(
+            CromwellTokenFileAuthConfig::new(
+                String::from(token_file.path().to_str().unwrap()),
+                None,
+            ),
+        ));
+
+        assert!(matches!(auth.get_token().await, Err(Error::EmptyToken(_))));
+    }
+
+    #[actix_rt::test]
+    async fn test_get_token_from_command() {
+        let auth = CromwellAuth::new(CromwellAuthConfig::TokenCommand(
+            CromwellTokenCommandAuthConfig::new(
+                vec![String::from("echo"), String::from("command_token")],
+                None,
+                30,
+            ),
+        ));
+
+        assert_eq!(auth.get_token().await.unwrap(), "command_token");
+        assert!(auth.is_refreshable());
+    }
+
+    #[actix_rt::test]
+    async fn test_get_token_from_command_failed() {
+        let auth = CromwellAuth::new(CromwellAuthConfig::TokenCommand(
+            CromwellTokenCommandAuthConfig::new(vec![String::from("false")], None, 30),
+        ));
+
+        assert!(matches!(auth.get_token().await, Err(Error::Command(_))));
+    }
+
+    #[actix_rt::test]
+    async fn test_get_token_from_command_timeout() {
+        let auth = CromwellAuth::new(CromwellAuthConfig::TokenCommand(
+            CromwellTokenCommandAuthConfig::new(
+                vec![String::from("sleep"), String::from("10")],
+                None,
+                1,
+            ),
+        ));
+
+        let started_at = std::time::Instant::now();
+        assert!(matches!(auth.get_token().await, Err(Error::Timeout(_))));
+        assert!(started_at.elapsed() < std::time::Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,897 @@
+//! Defines the interface CARROT uses for running workflows, so they can be run either through
+//! cromwell's native api or through a GA4GH WES api
+//!
+//! The managers work with workflow metadata in the format cromwell's metadata endpoint returns it
+//! (status values like "Running" and "Succeeded", timestamps like "2020-09-24T18:54:05.000Z",
+//! outputs keyed by fully-qualified output name, etc.), so the WES implementation translates the
+//! responses it gets into that format
+
+use crate::config::{Config, ExecutionBackendConfig};
+use crate::custom_sql_types::WorkflowLanguageEnum;
+use crate::requests::cromwell_requests::{
+    CromwellClient, CromwellRequestError, MetadataParams, StartJobParams, WorkflowIdAndStatus,
+    WorkflowQueryParams, WorkflowTypeEnum, WorkflowTypeVersionEnum,
+};
+use crate::requests::wes_requests::{RunWorkflowParams, WesClient};
+use crate::util::temp_storage;
+use actix_web::client::Client;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::debug;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// The files and labels for a workflow to be submitted
+pub struct WorkflowSubmission<'a> {
+    pub wdl_file_path: &'a Path,
+    pub wdl_deps_file_path: Option<&'a Path>,
+    pub inputs_file_path: &'a Path,
+    pub options_file_path: Option<&'a Path>,
+    pub workflow_language: WorkflowLanguageEnum,
+    pub labels: &'a Value,
+}
+
+/// The operations CARROT needs from a workflow execution engine.  Metadata is returned in the
+/// format cromwell uses (see the module docs)
+///
+/// The futures returned by the clients' requests can't be sent between threads, so neither can
+/// the ones returned by these methods
+#[async_trait(?Send)]
+pub trait ExecutionBackend {
+    /// Returns the name identifying this CARROT instance in the labels of the workflows it submits
+    fn instance_name(&self) -> &str;
+
+    /// Returns the underlying cromwell client if this backend runs workflows through cromwell's
+    /// native api, for features only cromwell supports (e.g. querying workflows by label)
+    fn as_cromwell(&self) -> Option<&CromwellClient> {
+        None
+    }
+
+    /// Submits the workflow described by `submission`, returning its id and status
+    async fn submit_workflow(
+        &self,
+        submission: WorkflowSubmission<'_>,
+    ) -> Result<WorkflowIdAndStatus, CromwellRequestError>;
+
+    /// Gets the id and status (and, where available, submission, start, and end) for each of the
+    /// workflows in `workflow_ids`.  Returns a map of workflow ids to their statuses.  Workflows
+    /// the engine doesn't return a status for will not be in the map
+    async fn get_workflow_statuses(
+        &self,
+        workflow_ids: &[String],
+    ) -> Result<HashMap<String, Map<String, Value>>, CromwellRequestError>;
+
+    /// Gets the status, submission, end, outputs, and failures for the workflow with id
+    /// `workflow_id`
+    async fn get_workflow_status_metadata(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Map<String, Value>, CromwellRequestError>;
+
+    /// Gets the outputs (under the `outputs` key) for the workflow with id `workflow_id`
+    async fn get_workflow_output_metadata(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Map<String, Value>, CromwellRequestError>;
+
+    /// Gets the calls (under the `calls` key, keyed by task name) for the workflow with id
+    /// `workflow_id`
+    async fn get_workflow_call_metadata(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Map<String, Value>, CromwellRequestError>;
+
+    /// Aborts the workflow with id `workflow_id`, returning its id and status
+    async fn abort_workflow(
+        &self,
+        workflow_id: &str,
+    ) -> Result<WorkflowIdAndStatus, CromwellRequestError>;
+}
+
+/// Creates the client for the execution backend specified in `carrot_config`, which will use
+/// `client` to submit requests to it
+pub fn create_execution_backend(
+    client: Client,
+    carrot_config: &Config,
+) -> Rc<dyn ExecutionBackend> {
+    let cromwell_config = carrot_config.cromwell();
+    match carrot_config.execution_backend() {
+        ExecutionBackendConfig::Cromwell => Rc::new(CromwellClient::new(
+            client,
+            cromwell_config.address(),
+            cromwell_config.instance_name(),
+            cromwell_config.auth().cloned(),
+        )),
+        ExecutionBackendConfig::Wes(wes_config) => Rc::new(WesClient::new(
+            client,
+            wes_config.address(),
+            wes_config.workflow_type_version(),
+            cromwell_config.instance_name(),
+        )),
+    }
+}
+
+#[async_trait(?Send)]
+impl ExecutionBackend for CromwellClient {
+    fn instance_name(&self) -> &str {
+        CromwellClient::instance_name(self)
+    }
+
+    fn as_cromwell(&self) -> Option<&CromwellClient> {
+        Some(self)
+    }
+
+    async fn submit_workflow(
+        &self,
+        submission: WorkflowSubmission<'_>,
+    ) -> Result<WorkflowIdAndStatus, CromwellRequestError> {
+        // Write the labels to a file so they can be submitted along with the workflow
+        let labels_file = temp_storage::get_temp_file(submission.labels.to_string().as_bytes())?;
+        // Cromwell works out the WDL version from the WDL itself, but only supports v1.0 for CWL
+        let (workflow_type, workflow_type_version) = match submission.workflow_language {
+            WorkflowLanguageEnum::Wdl => (WorkflowTypeEnum::WDL, None),
+            WorkflowLanguageEnum::Cwl => (
+                WorkflowTypeEnum::CWL,
+                Some(WorkflowTypeVersionEnum::VOnePointZero),
+            ),
+        };
+        // Build request parameters
+        let cromwell_params = StartJobParams {
+            labels: Some(PathBuf::from(labels_file.path())),
+            workflow_dependencies: submission.wdl_deps_file_path.map(PathBuf::from),
+            workflow_inputs: Some(PathBuf::from(submission.inputs_file_path)),
+            workflow_inputs_2: None,
+            workflow_inputs_3: None,
+            workflow_inputs_4: None,
+            workflow_inputs_5: None,
+            workflow_on_hold: None,
+            workflow_options: submission.options_file_path.map(PathBuf::from),
+            workflow_root: None,
+            workflow_source: Some(PathBuf::from(submission.wdl_file_path)),
+            workflow_type: Some(workflow_type),
+            workflow_type_version,
+            workflow_url: None,
+        };
+        // Submit request to start job
+        self.start_job(cromwell_params).await
+    }
+
+    async fn get_workflow_statuses(
+        &self,
+        workflow_ids: &[String],
+    ) -> Result<HashMap<String, Map<String, Value>>, CromwellRequestError> {
+        let params = WorkflowQueryParams {
+            id: Some(workflow_ids.to_vec()),
+            ..Default::default()
+        };
+        let response = self.query_workflows(&params).await?;
+        let results = match response
+            .get("results")
+            .and_then(|results| results.as_array())
+        {
+            Some(results) => results,
+            None => {
+                return Err(CromwellRequestError::Failed(String::from(
+                    "Cromwell query request did not return results",
+                )))
+            }
+        };
+        let mut workflow_statuses: HashMap<String, Map<String, Value>> = HashMap::new();
+        for result in results {
+            if let Some(result) = result.as_object() {
+                if let Some(id) = result.get("id").and_then(|id| id.as_str()) {
+                    workflow_statuses.insert(String::from(id), result.to_owned());
+                }
+            }
+        }
+        Ok(workflow_statuses)
+    }
+
+    async fn get_workflow_status_metadata(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Map<String, Value>, CromwellRequestError> {
+        // We only care about status, outputs, and end since we just want to know if the status
+        // has changed, and the end time and outputs if it finished.  We also need submission to
+        // check whether a job has been running for too long, and failures so we can record why a
+        // job failed
+        get_metadata_keys(
+            self,
+            workflow_id,
+            &["status", "end", "outputs", "submission", "failures"],
+        )
+        .await
+    }
+
+    async fn get_workflow_output_metadata(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Map<String, Value>, CromwellRequestError> {
+        get_metadata_keys(self, workflow_id, &["outputs"]).await
+    }
+
+    async fn get_workflow_call_metadata(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Map<String, Value>, CromwellRequestError> {
+        // Cromwell applies these to the keys within each call as well, so we need to include each
+        // of the call fields we want in addition to calls itself
+        get_metadata_keys(
+            self,
+            workflow_id,
+            &[
+                "calls",
+                "shardIndex",
+                "attempt",
+                "executionStatus",
+                "backendStatus",
+                "returnCode",
+                "dockerImageUsed",
+                "runtimeAttributes",
+                "start",
+                "end",
+            ],
+        )
+        .await
+    }
+
+    async fn abort_workflow(
+        &self,
+        workflow_id: &str,
+    ) -> Result<WorkflowIdAndStatus, CromwellRequestError> {
+        self.abort_job(workflow_id).await
+    }
+}
+
+/// Retrieves the metadata for the keys in `include_keys` for the workflow with id `workflow_id`
+/// from cromwell using `cromwell_client`
+async fn get_metadata_keys(
+    cromwell_client: &CromwellClient,
+    workflow_id: &str,
+    include_keys: &[&str],
+) -> Result<Map<String, Value>, CromwellRequestError> {
+    let params = MetadataParams {
+        exclude_key: None,
+        expand_sub_workflows: None,
+        include_key: Some(include_keys.iter().map(|key| String::from(*key)).collect()),
+        metadata_source: None,
+    };
+    match cromwell_client.get_metadata(workflow_id, &params).await? {
+        Value::Object(metadata) => Ok(metadata),
+        other => Err(CromwellRequestError::Failed(format!(
+            "Cromwell metadata request returned non-object: {}",
+            other
+        ))),
+    }
+}
+
+#[async_trait(?Send)]
+impl ExecutionBackend for WesClient {
+    fn instance_name(&self) -> &str {
+        WesClient::instance_name(self)
+    }
+
+    async fn submit_workflow(
+        &self,
+        submission: WorkflowSubmission<'_>,
+    ) -> Result<WorkflowIdAndStatus, CromwellRequestError> {
+        // WES takes the inputs and options as json strings instead of files
+        let workflow_params = std::fs::read_to_string(submission.inputs_file_path)?;
+        let workflow_engine_parameters = match submission.options_file_path {
+            Some(options_file_path) => Some(std::fs::read_to_string(options_file_path)?),
+            None => None,
+        };
+        // The workflow is referred to by the name of its attachment
+        let workflow_url = match submission
+            .wdl_file_path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+        {
+            Some(file_name) => String::from(file_name),
+            None => {
+                return Err(CromwellRequestError::Failed(format!(
+                    "Failed to get file name for WDL at {:?}",
+                    submission.wdl_file_path
+                )))
+            }
+        };
+        // The configured version only applies to WDL, and CWL is always v1.0
+        let (workflow_type, workflow_type_version) = match submission.workflow_language {
+            WorkflowLanguageEnum::Wdl => (
+                WorkflowTypeEnum::WDL,
+                String::from(self.workflow_type_version()),
+            ),
+            WorkflowLanguageEnum::Cwl => (
+                WorkflowTypeEnum::CWL,
+                WorkflowTypeVersionEnum::VOnePointZero.to_string(),
+            ),
+        };
+        let mut workflow_attachments = vec![PathBuf::from(submission.wdl_file_path)];
+        if let Some(wdl_deps_file_path) = submission.wdl_deps_file_path {
+            workflow_attachments.push(PathBuf::from(wdl_deps_file_path));
+        }
+        let params = RunWorkflowParams {
+            workflow_params: Some(workflow_params),
+            workflow_type: workflow_type.to_string(),
+            workflow_type_version,
+            tags: Some(submission.labels.to_string()),
+            workflow_engine_parameters,
+            workflow_url,
+            workflow_attachments,
+        };
+        let run_id = self.run_workflow(params).await?;
+        Ok(WorkflowIdAndStatus {
+            id: run_id.run_id,
+            status: String::from("Submitted"),
+        })
+    }
+
+    async fn get_workflow_statuses(
+        &self,
+        workflow_ids: &[String],
+    ) -> Result<HashMap<String, Map<String, Value>>, CromwellRequestError> {
+        // WES doesn't support querying for multiple runs by id, so we have to get them one at a time
+        let mut workflow_statuses: HashMap<String, Map<String, Value>> = HashMap::new();
+        for workflow_id in workflow_ids {
+            match self.get_run_status(workflow_id).await {
+                Ok(run_status) => {
+                    let mut status = Map::new();
+                    status.insert(String::from("id"), Value::from(run_status.run_id.clone()));
+                    status.insert(
+                        String::from("status"),
+                        Value::from(get_cromwell_status_for_wes_state(&run_status.state)),
+                    );
+                    workflow_statuses.insert(run_status.run_id, status);
+                }
+                // Leave out runs the server couldn't find, like cromwell does
+                Err(CromwellRequestError::Failed(e)) => {
+                    debug!("Failed to get status for WES run {}: {}", workflow_id, e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(workflow_statuses)
+    }
+
+    async fn get_workflow_status_metadata(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Map<String, Value>, CromwellRequestError> {
+        let run_log = self.get_run_log(workflow_id).await?;
+        let state = run_log
+            .get("state")
+            .and_then(Value::as_str)
+            .unwrap_or("UNKNOWN");
+        let mut metadata = Map::new();
+        metadata.insert(String::from("id"), Value::from(workflow_id));
+        metadata.insert(
+            String::from("status"),
+            Value::from(get_cromwell_status_for_wes_state(state)),
+        );
+        // WES doesn't report when a run was submitted, so the start time is the closest we can get
+        if let Some(start) = get_log_timestamp(run_log.get("run_log"), "start_time") {
+            metadata.insert(String::from("submission"), Value::from(start.clone()));
+            metadata.insert(String::from("start"), Value::from(start));
+        }
+        if let Some(end) = get_log_timestamp(run_log.get("run_log"), "end_time") {
+            metadata.insert(String::from("end"), Value::from(end));
+        }
+        if let Some(outputs) = run_log.get("outputs").filter(|outputs| outputs.is_object()) {
+            metadata.insert(String::from("outputs"), outputs.clone());
+        }
+        // WES doesn't report failure messages, so build one from the state and the run's stderr
+        if matches!(state, "EXECUTOR_ERROR" | "SYSTEM_ERROR") {
+            let message = match run_log
+                .get("run_log")
+                .and_then(|log| log.get("stderr"))
+                .and_then(Value::as_str)
+            {
+                Some(stderr) => format!("WES run ended with state {} (stderr: {})", state, stderr),
+                None => format!("WES run ended with state {}", state),
+            };
+            metadata.insert(
+                String::from("failures"),
+                json!([{"message": message, "causedBy": []}]),
+            );
+        }
+        Ok(metadata)
+    }
+
+    async fn get_workflow_output_metadata(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Map<String, Value>, CromwellRequestError> {
+        let run_log = self.get_run_log(workflow_id).await?;
+        let mut metadata = Map::new();
+        if let Some(outputs) = run_log.get("outputs").filter(|outputs| outputs.is_object()) {
+            metadata.insert(String::from("outputs"), outputs.clone());
+        }
+        Ok(metadata)
+    }
+
+    async fn get_workflow_call_metadata(
+        &self,
+        workflow_id: &str,
+    ) -> Result<Map<String, Value>, CromwellRequestError> {
+        let run_log = self.get_run_log(workflow_id).await?;
+        // WES reports a flat list of task logs, so group them by name.  It doesn't distinguish
+        // shards from attempts, so each log for the same task is treated as another attempt
+        let mut calls: Map<String, Value> = Map::new();
+        if let Some(task_logs) = run_log.get("task_logs").and_then(Value::as_array) {
+            for task_log in task_logs {
+                let task_name = match task_log.get("name").and_then(Value::as_str) {
+                    Some(task_name) => task_name,
+                    None => continue,
+                };
+                let task_calls = calls
+                    .entry(String::from(task_name))
+                    .or_insert_with(|| Value::Array(Vec::new()));
+                if let Value::Array(task_calls) = task_calls {
+                    let mut call = Map::new();
+                    call.insert(String::from("shardIndex"), Value::from(-1));
+                    call.insert(
+                        String::from("attempt"),
+                        Value::from(task_calls.len() as i64 + 1),
+                    );
+                    if let Some(exit_code) = task_log.get("exit_code").and_then(Value::as_i64) {
+                        call.insert(String::from("returnCode"), Value::from(exit_code));
+                    }
+                    if let Some(start) = get_log_timestamp(Some(task_log), "start_time") {
+                        call.insert(String::from("start"), Value::from(start));
+                    }
+                    if let Some(end) = get_log_timestamp(Some(task_log), "end_time") {
+                        call.insert(String::from("end"), Value::from(end));
+                    }
+                    task_calls.push(Value::Object(call));
+                }
+            }
+        }
+        let mut metadata = Map::new();
+        metadata.insert(String::from("calls"), Value::Object(calls));
+        Ok(metadata)
+    }
+
+    async fn abort_workflow(
+        &self,
+        workflow_id: &str,
+    ) -> Result<WorkflowIdAndStatus, CromwellRequestError> {
+        let run_id = self.cancel_run(workflow_id).await?;
+        Ok(WorkflowIdAndStatus {
+            id: run_id.run_id,
+            status: String::from("Aborting"),
+        })
+    }
+}
+
+/// Returns the cromwell status equivalent to the WES state `state`
+fn get_cromwell_status_for_wes_state(state: &str) -> &'static str {
+    match state {
+        "QUEUED" => "Submitted",
+        "INITIALIZING" => "Starting",
+        "RUNNING" => "Running",
+        "PAUSED" => "On Hold",
+        "COMPLETE" => "Succeeded",
+        "EXECUTOR_ERROR" | "SYSTEM_ERROR" => "Failed",
+        "CANCELING" => "Aborting",
+        "CANCELED" => "Aborted",
+        _ => "Unknown",
+    }
+}
+
+/// Extracts the RFC 3339 timestamp at `key` in the WES log `log` and returns it in the format
+/// cromwell uses, or None if it is missing or can't be parsed
+fn get_log_timestamp(log: Option<&Value>, key: &str) -> Option<String> {
+    log.and_then(|log| log.get(key))
+        .and_then(Value::as_str)
+        .and_then(|timestamp| DateTime::parse_from_rfc3339(timestamp).ok())
+        .map(|timestamp| {
+            timestamp
+                .with_timezone(&Utc)
+                .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+                .to_string()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::custom_sql_types::WorkflowLanguageEnum;
+    use crate::requests::cromwell_requests::CromwellClient;
+    use crate::requests::execution_backend::{ExecutionBackend, WorkflowSubmission};
+    use crate::requests::wes_requests::WesClient;
+    use crate::util::temp_storage;
+    use actix_web::client::Client;
+    use mockito::Matcher;
+    use serde_json::{json, Value};
+    use std::path::PathBuf;
+
+    fn create_test_wes_backend() -> WesClient {
+        WesClient::new(Client::default(), &mockito::server_url(), "1.0", "carrot")
+    }
+
+    fn create_test_cromwell_backend() -> CromwellClient {
+        CromwellClient::new(Client::default(), &mockito::server_url(), "carrot", None)
+    }
+
+    #[actix_rt::test]
+    async fn test_submit_workflow_cromwell() {
+        let test_backend = create_test_cromwell_backend();
+
+        let test_wdl_path = PathBuf::from("testdata/routes/template/valid_wdl.wdl");
+        let test_inputs_file =
+            temp_storage::get_temp_file(json!({"myWorkflow.test": "test"}).to_string().as_bytes())
+                .unwrap();
+        let test_labels = json!({"carrot-instance": "carrot"});
+
+        let mock = mockito::mock("POST", "/api/workflows/v1")
+            .match_body(Matcher::Regex(String::from(
+                "\"carrot-instance\":\"carrot\"",
+            )))
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(
+                json!({"id": "53709600-d114-4194-a7f7-9e41211ca2ce", "status": "Submitted"})
+                    .to_string(),
+            )
+            .create();
+
+        let response = test_backend
+            .submit_workflow(WorkflowSubmission {
+                wdl_file_path: &test_wdl_path,
+                wdl_deps_file_path: None,
+                inputs_file_path: test_inputs_file.path(),
+                options_file_path: None,
+                workflow_language: WorkflowLanguageEnum::Wdl,
+                labels: &test_labels,
+            })
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        assert_eq!(response.id, "53709600-d114-4194-a7f7-9e41211ca2ce");
+        assert_eq!(response.status, "Submitted");
+    }
+
+    #[actix_rt::test]
+    async fn test_submit_workflow_wes() {
+        let test_backend = create_test_wes_backend();
+
+        let test_wdl_path = PathBuf::from("testdata/routes/template/valid_wdl.wdl");
+        let test_inputs_file =
+            temp_storage::get_temp_file(json!({"myWorkflow.test": "test"}).to_string().as_bytes())
+                .unwrap();
+        let test_labels = json!({"carrot-instance": "carrot"});
+
+        let mock = mockito::mock("POST", "/ga4gh/wes/v1/runs")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(String::from(
+                    "name=\"workflow_params\"\r\n\r\n\\{\"myWorkflow.test\":\"test\"\\}",
+                )),
+                Matcher::Regex(String::from("name=\"workflow_type_version\"\r\n\r\n1.0")),
+                Matcher::Regex(String::from("name=\"workflow_url\"\r\n\r\nvalid_wdl.wdl")),
+                Matcher::Regex(String::from(
+                    "name=\"tags\"\r\n\r\n\\{\"carrot-instance\":\"carrot\"\\}",
+                )),
+            ]))
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(json!({"run_id": "53709600-d114-4194-a7f7-9e41211ca2ce"}).to_string())
+            .create();
+
+        let response = test_backend
+            .submit_workflow(WorkflowSubmission {
+                wdl_file_path: &test_wdl_path,
+                wdl_deps_file_path: None,
+                inputs_file_path: test_inputs_file.path(),
+                options_file_path: None,
+                workflow_language: WorkflowLanguageEnum::Wdl,
+                labels: &test_labels,
+            })
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        assert_eq!(response.id, "53709600-d114-4194-a7f7-9e41211ca2ce");
+        assert_eq!(response.status, "Submitted");
+    }
+
+    #[actix_rt::test]
+    async fn test_submit_workflow_cromwell_cwl() {
+        let test_backend = create_test_cromwell_backend();
+
+        let test_cwl_path = PathBuf::from("testdata/validation/cwl/valid_workflow.cwl");
+        let test_inputs_file =
+            temp_storage::get_temp_file(json!({"in_greeting": "test"}).to_string().as_bytes())
+                .unwrap();
+        let test_labels = json!({"carrot-instance": "carrot"});
+
+        let mock = mockito::mock("POST", "/api/workflows/v1")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(String::from("name=\"workflowType\"\r\n\r\nCWL")),
+                Matcher::Regex(String::from("name=\"workflowTypeVersion\"\r\n\r\nv1.0")),
+            ]))
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(
+                json!({"id": "53709600-d114-4194-a7f7-9e41211ca2ce", "status": "Submitted"})
+                    .to_string(),
+            )
+            .create();
+
+        let response = test_backend
+            .submit_workflow(WorkflowSubmission {
+                wdl_file_path: &test_cwl_path,
+                wdl_deps_file_path: None,
+                inputs_file_path: test_inputs_file.path(),
+                options_file_path: None,
+                workflow_language: WorkflowLanguageEnum::Cwl,
+                labels: &test_labels,
+            })
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        assert_eq!(response.id, "53709600-d114-4194-a7f7-9e41211ca2ce");
+    }
+
+    #[actix_rt::test]
+    async fn test_submit_workflow_wes_cwl() {
+        let test_backend = create_test_wes_backend();
+
+        let test_cwl_path = PathBuf::from("testdata/validation/cwl/valid_workflow.cwl");
+        let test_inputs_file =
+            temp_storage::get_temp_file(json!({"in_greeting": "test"}).to_string().as_bytes())
+                .unwrap();
+        let test_labels = json!({"carrot-instance": "carrot"});
+
+        let mock = mockito::mock("POST", "/ga4gh/wes/v1/runs")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(String::from("name=\"workflow_type\"\r\n\r\nCWL")),
+                Matcher::Regex(String::from("name=\"workflow_type_version\"\r\n\r\nv1.0")),
+                Matcher::Regex(String::from(
+                    "name=\"workflow_url\"\r\n\r\nvalid_workflow.cwl",
+                )),
+            ]))
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(json!({"run_id": "53709600-d114-4194-a7f7-9e41211ca2ce"}).to_string())
+            .create();
+
+        let response = test_backend
+            .submit_workflow(WorkflowSubmission {
+                wdl_file_path: &test_cwl_path,
+                wdl_deps_file_path: None,
+                inputs_file_path: test_inputs_file.path(),
+                options_file_path: None,
+                workflow_language: WorkflowLanguageEnum::Cwl,
+                labels: &test_labels,
+            })
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        assert_eq!(response.id, "53709600-d114-4194-a7f7-9e41211ca2ce");
+    }
+
+    #[actix_rt::test]
+    async fn test_get_workflow_statuses_wes() {
+        let test_backend = create_test_wes_backend();
+
+        let running_mock = mockito::mock(
+            "GET",
+            "/ga4gh/wes/v1/runs/11111111-1111-1111-1111-111111111111/status",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(
+            json!({"run_id": "11111111-1111-1111-1111-111111111111", "state": "RUNNING"})
+                .to_string(),
+        )
+        .create();
+        let missing_mock = mockito::mock(
+            "GET",
+            "/ga4gh/wes/v1/runs/22222222-2222-2222-2222-222222222222/status",
+        )
+        .with_status(404)
+        .with_header("content_type", "application/json")
+        .with_body(json!({"msg": "Run not found", "status_code": 404}).to_string())
+        .create();
+
+        let statuses = test_backend
+            .get_workflow_statuses(&[
+                String::from("11111111-1111-1111-1111-111111111111"),
+                String::from("22222222-2222-2222-2222-222222222222"),
+            ])
+            .await
+            .unwrap();
+
+        running_mock.assert();
+        missing_mock.assert();
+
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(
+            statuses["11111111-1111-1111-1111-111111111111"]["status"],
+            "Running"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_get_workflow_status_metadata_wes_succeeded() {
+        let test_backend = create_test_wes_backend();
+
+        let mock = mockito::mock(
+            "GET",
+            "/ga4gh/wes/v1/runs/53709600-d114-4194-a7f7-9e41211ca2ce",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(
+            json!({
+                "run_id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+                "state": "COMPLETE",
+                "run_log": {
+                    "start_time": "2020-09-24T18:54:05Z",
+                    "end_time": "2020-09-24T15:07:46-04:00"
+                },
+                "task_logs": [],
+                "outputs": {
+                    "myWorkflow.out": "hello"
+                }
+            })
+            .to_string(),
+        )
+        .create();
+
+        let metadata = test_backend
+            .get_workflow_status_metadata("53709600-d114-4194-a7f7-9e41211ca2ce")
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        assert_eq!(
+            Value::Object(metadata),
+            json!({
+                "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+                "status": "Succeeded",
+                "submission": "2020-09-24T18:54:05.000Z",
+                "start": "2020-09-24T18:54:05.000Z",
+                "end": "2020-09-24T19:07:46.000Z",
+                "outputs": {
+                    "myWorkflow.out": "hello"
+                }
+            })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_get_workflow_status_metadata_wes_failed() {
+        let test_backend = create_test_wes_backend();
+
+        let mock = mockito::mock(
+            "GET",
+            "/ga4gh/wes/v1/runs/53709600-d114-4194-a7f7-9e41211ca2ce",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(
+            json!({
+                "run_id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+                "state": "EXECUTOR_ERROR",
+                "run_log": {
+                    "start_time": "2020-09-24T18:54:05Z",
+                    "end_time": "2020-09-24T19:07:46Z",
+                    "stderr": "https://example.com/stderr"
+                }
+            })
+            .to_string(),
+        )
+        .create();
+
+        let metadata = test_backend
+            .get_workflow_status_metadata("53709600-d114-4194-a7f7-9e41211ca2ce")
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        assert_eq!(metadata["status"], "Failed");
+        assert_eq!(
+            metadata["failures"],
+            json!([{
+                "message": "WES run ended with state EXECUTOR_ERROR (stderr: https://example.com/stderr)",
+                "causedBy": []
+            }])
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_get_workflow_call_metadata_wes() {
+        let test_backend = create_test_wes_backend();
+
+        let mock = mockito::mock(
+            "GET",
+            "/ga4gh/wes/v1/runs/53709600-d114-4194-a7f7-9e41211ca2ce",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(
+            json!({
+                "run_id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+                "state": "RUNNING",
+                "task_logs": [
+                    {
+                        "name": "myWorkflow.hello",
+                        "start_time": "2020-09-24T18:54:05Z",
+                        "end_time": "2020-09-24T18:58:05Z",
+                        "exit_code": 1
+                    },
+                    {
+                        "name": "myWorkflow.hello",
+                        "start_time": "2020-09-24T18:58:10Z"
+                    }
+                ]
+            })
+            .to_string(),
+        )
+        .create();
+
+        let metadata = test_backend
+            .get_workflow_call_metadata("53709600-d114-4194-a7f7-9e41211ca2ce")
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        assert_eq!(
+            Value::Object(metadata),
+            json!({
+                "calls": {
+                    "myWorkflow.hello": [
+                        {
+                            "shardIndex": -1,
+                            "attempt": 1,
+                            "returnCode": 1,
+                            "start": "2020-09-24T18:54:05.000Z",
+                            "end": "2020-09-24T18:58:05.000Z"
+                        },
+                        {
+                            "shardIndex": -1,
+                            "attempt": 2,
+                            "start": "2020-09-24T18:58:10.000Z"
+                        }
+                    ]
+                }
+            })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn test_abort_workflow_wes() {
+        let test_backend = create_test_wes_backend();
+
+        let mock = mockito::mock(
+            "POST",
+            "/ga4gh/wes/v1/runs/53709600-d114-4194-a7f7-9e41211ca2ce/cancel",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(json!({"run_id": "53709600-d114-4194-a7f7-9e41211ca2ce"}).to_string())
+        .create();
+
+        let response = test_backend
+            .abort_workflow("53709600-d114-4194-a7f7-9e41211ca2ce")
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        assert_eq!(response.id, "53709600-d114-4194-a7f7-9e41211ca2ce");
+        assert_eq!(response.status, "Aborting");
+    }
+}
@@ -1,6 +1,9 @@
 //! Contains modules for making requests
 
 // Declared as public to be accessed elsewhere
+pub mod cromwell_auth;
 pub mod cromwell_requests;
+pub mod execution_backend;
 pub mod github_requests;
 pub mod test_resource_requests;
+pub mod wes_requests;
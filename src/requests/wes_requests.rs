@@ -0,0 +1,374 @@
+//! Module for making requests to a GA4GH Workflow Execution Service (WES) api
+//!
+//! For more information on the endpoints, see the
+//! [WES API documentation](https://ga4gh.github.io/workflow-execution-service-schemas/docs/).
+//! Errors are reported as CromwellRequestErrors, since the ways a request can fail are the same
+//! for either api and the rest of CARROT already knows how to handle them
+
+use crate::requests::cromwell_requests::CromwellRequestError;
+use actix_multipart_rfc7578::client::multipart;
+use actix_web::client::{Client, ClientResponse};
+use actix_web::dev::{Decompress, Payload};
+use actix_web::http::StatusCode;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Parameters for submitting a run to a WES server
+///
+/// `workflow_params`, `tags`, and `workflow_engine_parameters` are json strings.
+/// `workflow_url` should be the file name of the attachment (in `workflow_attachments`)
+/// containing the workflow
+pub struct RunWorkflowParams {
+    pub workflow_params: Option<String>,
+    pub workflow_type: String,
+    pub workflow_type_version: String,
+    pub tags: Option<String>,
+    pub workflow_engine_parameters: Option<String>,
+    pub workflow_url: String,
+    pub workflow_attachments: Vec<PathBuf>,
+}
+
+/// Expected return value from WES for submitting or canceling a run
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RunId {
+    pub run_id: String,
+}
+
+/// Expected return value from WES for getting the status of a run
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RunStatus {
+    pub run_id: String,
+    pub state: String,
+}
+
+/// Struct for submitting requests to a WES server
+#[derive(Clone)]
+pub struct WesClient {
+    client: Client,
+    address: String,
+    workflow_type_version: String,
+    instance_name: String,
+}
+
+impl WesClient {
+    /// Creates a new WesClient that will use `client` to submit requests to the WES server at
+    /// `address`, reporting submitted workflows as WDL `workflow_type_version`.  `instance_name`
+    /// identifies this CARROT instance in the tags of the workflows it submits
+    pub fn new(
+        client: Client,
+        address: &str,
+        workflow_type_version: &str,
+        instance_name: &str,
+    ) -> WesClient {
+        WesClient {
+            client,
+            address: String::from(address),
+            workflow_type_version: String::from(workflow_type_version),
+            instance_name: String::from(instance_name),
+        }
+    }
+
+    /// Returns the version of WDL reported to the WES server when submitting workflows
+    pub fn workflow_type_version(&self) -> &str {
+        &self.workflow_type_version
+    }
+
+    /// Returns the name identifying this CARROT instance in the tags of the workflows it submits
+    pub fn instance_name(&self) -> &str {
+        &self.instance_name
+    }
+
+    /// Submits a run to the WES server
+    ///
+    /// Submits a request to the /ga4gh/wes/v1/runs mapping, with the form values specified in
+    /// `params`.  Returns either the id of the new run or one of the following errors wrapped in a
+    /// CromwellRequestError:
+    /// Io if there is an issue reading files when creating the form data
+    /// Request if there is an issue sending the request
+    /// Payload if there is an issue getting the response body
+    /// Utf8 if there is an issue converting the response body to Utf8
+    /// Failed if WES returns a non-success status
+    /// Json if there is an issue parsing the response body to a RunId struct
+    pub async fn run_workflow(
+        &self,
+        params: RunWorkflowParams,
+    ) -> Result<RunId, CromwellRequestError> {
+        // Create a multipart form and fill in fields from params
+        let form: multipart::Form = WesClient::assemble_form_data(params)?;
+
+        // Make request
+        let response = self
+            .client
+            .post(format!("{}/ga4gh/wes/v1/runs", self.address))
+            .content_type(form.content_type())
+            .send_body(multipart::Body::from(form))
+            .await;
+
+        let body_utf8 = WesClient::get_response_body(response).await?;
+
+        // Parse response body into RunId
+        Ok(serde_json::from_str(&body_utf8)?)
+    }
+
+    /// Retrieves the full log for a run from the WES server
+    ///
+    /// Submits a request to the /ga4gh/wes/v1/runs/{run_id} mapping.  Returns either the response,
+    /// which contains the state, run log, task logs, and outputs for the run, or one of the
+    /// following errors wrapped in a CromwellRequestError:
+    /// Request if there is an issue sending the request
+    /// Payload if there is an issue getting the response body
+    /// Utf8 if there is an issue converting the response body to Utf8
+    /// Failed if WES returns a non-success status
+    /// Json if there is an issue parsing the response body to Json
+    pub async fn get_run_log(&self, run_id: &str) -> Result<Value, CromwellRequestError> {
+        let response = self
+            .client
+            .get(format!("{}/ga4gh/wes/v1/runs/{}", self.address, run_id))
+            .send()
+            .await;
+
+        let body_utf8 = WesClient::get_response_body(response).await?;
+
+        Ok(serde_json::from_str(&body_utf8)?)
+    }
+
+    /// Retrieves the state of a run from the WES server
+    ///
+    /// Submits a request to the /ga4gh/wes/v1/runs/{run_id}/status mapping.  Returns either the id
+    /// and state of the run or one of the following errors wrapped in a CromwellRequestError:
+    /// Request if there is an issue sending the request
+    /// Payload if there is an issue getting the response body
+    /// Utf8 if there is an issue converting the response body to Utf8
+    /// Failed if WES returns a non-success status
+    /// Json if there is an issue parsing the response body to a RunStatus struct
+    pub async fn get_run_status(&self, run_id: &str) -> Result<RunStatus, CromwellRequestError> {
+        let response = self
+            .client
+            .get(format!(
+                "{}/ga4gh/wes/v1/runs/{}/status",
+                self.address, run_id
+            ))
+            .send()
+            .await;
+
+        let body_utf8 = WesClient::get_response_body(response).await?;
+
+        Ok(serde_json::from_str(&body_utf8)?)
+    }
+
+    /// Cancels a run on the WES server
+    ///
+    /// Submits a request to the /ga4gh/wes/v1/runs/{run_id}/cancel mapping.  Returns either the id
+    /// of the canceled run or one of the following errors wrapped in a CromwellRequestError:
+    /// Request if there is an issue sending the request
+    /// Payload if there is an issue getting the response body
+    /// Utf8 if there is an issue converting the response body to Utf8
+    /// Failed if WES returns a non-success status (e.g. if the run has already finished)
+    /// Json if there is an issue parsing the response body to a RunId struct
+    pub async fn cancel_run(&self, run_id: &str) -> Result<RunId, CromwellRequestError> {
+        let response = self
+            .client
+            .post(format!(
+                "{}/ga4gh/wes/v1/runs/{}/cancel",
+                self.address, run_id
+            ))
+            .send()
+            .await;
+
+        let body_utf8 = WesClient::get_response_body(response).await?;
+
+        Ok(serde_json::from_str(&body_utf8)?)
+    }
+
+    /// Reads the body of `response` and returns it as a String, or returns an error if sending the
+    /// request failed, reading the body fails, or the response has a non-success status
+    async fn get_response_body(
+        response: Result<ClientResponse<Decompress<Payload>>, actix_web::client::SendRequestError>,
+    ) -> Result<String, CromwellRequestError> {
+        let mut response = response?;
+
+        // Get response body and convert it into bytes
+        let response_body = response.body().await?;
+        let body_utf8 = std::str::from_utf8(response_body.as_ref())?;
+
+        debug!("{}", body_utf8);
+
+        // If it didn't return a success status code, that's an error
+        let status: StatusCode = response.status();
+        if status.is_server_error() {
+            return Err(CromwellRequestError::ServerError(format!(
+                "WES request returned status:{} body:{}",
+                status, body_utf8
+            )));
+        }
+        if !status.is_success() {
+            return Err(CromwellRequestError::Failed(format!(
+                "WES request returned status:{} body:{}",
+                status, body_utf8
+            )));
+        }
+
+        Ok(String::from(body_utf8))
+    }
+
+    /// Assembles data specified in `params` into a Form object for making an http request
+    ///
+    /// Returns either a completed form to be used for submitting the run or an io error if there
+    /// is an issue reading a file
+    fn assemble_form_data<'a>(
+        params: RunWorkflowParams,
+    ) -> Result<multipart::Form<'a>, std::io::Error> {
+        let mut form = multipart::Form::default();
+
+        if let Some(value) = params.workflow_params {
+            form.add_text("workflow_params", value);
+        }
+        form.add_text("workflow_type", params.workflow_type);
+        form.add_text("workflow_type_version", params.workflow_type_version);
+        if let Some(value) = params.tags {
+            form.add_text("tags", value);
+        }
+        if let Some(value) = params.workflow_engine_parameters {
+            form.add_text("workflow_engine_parameters", value);
+        }
+        form.add_text("workflow_url", params.workflow_url);
+        for attachment in params.workflow_attachments {
+            form.add_file("workflow_attachment", attachment)?;
+        }
+
+        Ok(form)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RunWorkflowParams, WesClient};
+    use crate::requests::cromwell_requests::CromwellRequestError;
+    use actix_web::client::Client;
+    use mockito::Matcher;
+    use serde_json::json;
+    use std::path::PathBuf;
+
+    fn create_test_wes_client() -> WesClient {
+        WesClient::new(Client::default(), &mockito::server_url(), "1.0", "carrot")
+    }
+
+    #[actix_rt::test]
+    async fn test_run_workflow() {
+        let test_wes_client = create_test_wes_client();
+
+        let mock = mockito::mock("POST", "/ga4gh/wes/v1/runs")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(String::from("name=\"workflow_type\"\r\n\r\nWDL")),
+                Matcher::Regex(String::from("name=\"workflow_url\"\r\n\r\nvalid_wdl.wdl")),
+                Matcher::Regex(String::from(
+                    "name=\"tags\"\r\n\r\n\\{\"carrot-instance\":\"carrot\"\\}",
+                )),
+            ]))
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(json!({"run_id": "53709600-d114-4194-a7f7-9e41211ca2ce"}).to_string())
+            .create();
+
+        let params = RunWorkflowParams {
+            workflow_params: Some(json!({"myWorkflow.test": "test"}).to_string()),
+            workflow_type: String::from("WDL"),
+            workflow_type_version: String::from("1.0"),
+            tags: Some(json!({"carrot-instance": "carrot"}).to_string()),
+            workflow_engine_parameters: None,
+            workflow_url: String::from("valid_wdl.wdl"),
+            workflow_attachments: vec![PathBuf::from("testdata/routes/template/valid_wdl.wdl")],
+        };
+
+        let response = test_wes_client.run_workflow(params).await.unwrap();
+
+        mock.assert();
+
+        assert_eq!(response.run_id, "53709600-d114-4194-a7f7-9e41211ca2ce");
+    }
+
+    #[actix_rt::test]
+    async fn test_get_run_log() {
+        let test_wes_client = create_test_wes_client();
+
+        let mock_response_body = json!({
+            "run_id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+            "state": "COMPLETE",
+            "run_log": {
+                "start_time": "2020-09-24T18:54:05Z",
+                "end_time": "2020-09-24T19:07:46Z"
+            },
+            "task_logs": [],
+            "outputs": {
+                "myWorkflow.out": "hello"
+            }
+        });
+        let mock = mockito::mock(
+            "GET",
+            "/ga4gh/wes/v1/runs/53709600-d114-4194-a7f7-9e41211ca2ce",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(mock_response_body.to_string())
+        .create();
+
+        let response = test_wes_client
+            .get_run_log("53709600-d114-4194-a7f7-9e41211ca2ce")
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        assert_eq!(response, mock_response_body);
+    }
+
+    #[actix_rt::test]
+    async fn test_get_run_status() {
+        let test_wes_client = create_test_wes_client();
+
+        let mock = mockito::mock(
+            "GET",
+            "/ga4gh/wes/v1/runs/53709600-d114-4194-a7f7-9e41211ca2ce/status",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(
+            json!({"run_id": "53709600-d114-4194-a7f7-9e41211ca2ce", "state": "RUNNING"})
+                .to_string(),
+        )
+        .create();
+
+        let response = test_wes_client
+            .get_run_status("53709600-d114-4194-a7f7-9e41211ca2ce")
+            .await
+            .unwrap();
+
+        mock.assert();
+
+        assert_eq!(response.state, "RUNNING");
+    }
+
+    #[actix_rt::test]
+    async fn test_cancel_run_failed() {
+        let test_wes_client = create_test_wes_client();
+
+        let mock = mockito::mock(
+            "POST",
+            "/ga4gh/wes/v1/runs/53709600-d114-4194-a7f7-9e41211ca2ce/cancel",
+        )
+        .with_status(404)
+        .with_header("content_type", "application/json")
+        .with_body(json!({"msg": "Run not found", "status_code": 404}).to_string())
+        .create();
+
+        let response = test_wes_client
+            .cancel_run("53709600-d114-4194-a7f7-9e41211ca2ce")
+            .await;
+
+        mock.assert();
+
+        assert!(matches!(response, Err(CromwellRequestError::Failed(_))));
+    }
+}
@@ -0,0 +1,177 @@
+//! Defines REST APIs for administrative operations
+//!
+//! Contains functions for processing requests to reconcile the workflows running in cromwell with
+//! the records in the DB, along with their URIs
+
+use crate::db;
+use crate::manager::workflow_reconciler::WorkflowReconciler;
+use crate::routes::error_handling::ErrorBody;
+use actix_web::web::Query;
+use actix_web::{web, HttpResponse};
+use log::error;
+use serde::{Deserialize, Serialize};
+
+/// Represents the set of possible query parameters that can be received by the reconcile mapping
+#[derive(Deserialize, Serialize)]
+struct ReconcileQueryParams {
+    abort: Option<bool>,
+}
+
+/// Handles requests to /admin/reconcile_workflows for finding orphaned cromwell workflows
+///
+/// This function is called by Actix-Web when a post request is made to the
+/// /admin/reconcile_workflows mapping.  It uses `workflow_reconciler` to query cromwell for the
+/// active workflows submitted by this CARROT instance and checks them against the records in the
+/// DB, using a connection from `pool`.  If the `abort` query param is true, any workflows that
+/// don't have a matching record or whose record has already finished are aborted.  Returns a
+/// report of the orphaned workflows, or an error message if querying cromwell or the DB fails
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn reconcile_workflows(
+    query_params: Query<ReconcileQueryParams>,
+    pool: web::Data<db::DbPool>,
+    workflow_reconciler: web::Data<WorkflowReconciler>,
+) -> HttpResponse {
+    let abort = query_params.abort.unwrap_or(false);
+    // Get DB connection
+    let conn = pool.get().expect("Failed to get DB connection from pool");
+    match workflow_reconciler.reconcile(&conn, abort).await {
+        Ok(report) => HttpResponse::Ok().json(report),
+        Err(e) => {
+            error!("{}", e);
+            HttpResponse::InternalServerError().json(ErrorBody {
+                title: "Server error".to_string(),
+                status: 500,
+                detail: format!("Error while attempting to reconcile workflows: {}", e),
+            })
+        }
+    }
+}
+
+/// Attaches the REST mappings in this file to a service config
+///
+/// To be called when configuring the Actix-Web app service.  Registers the mappings in this file
+/// as part of the service defined in `cfg`
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/admin/reconcile_workflows").route(web::post().to(reconcile_workflows)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manager::server_router::ServerRouter;
+    use crate::manager::workflow_reconciler::ReconciliationReport;
+    use crate::unit_test_util::{get_test_db_pool, load_default_config};
+    use actix_web::client::Client;
+    use actix_web::{http, test, App};
+    use serde_json::json;
+
+    fn create_test_workflow_reconciler() -> WorkflowReconciler {
+        WorkflowReconciler::new(ServerRouter::new(Client::default(), &load_default_config()))
+    }
+
+    #[actix_rt::test]
+    async fn reconcile_workflows_success() {
+        let pool = get_test_db_pool();
+
+        let mock_response_body = json!({
+            "results": [
+                {
+                    "id": "77777777-7777-7777-7777-777777777777",
+                    "status": "Running",
+                    "labels": {
+                        "carrot-instance": "carrot",
+                        "carrot-stage": "report"
+                    }
+                }
+            ],
+            "totalResultsCount": 1
+        });
+        let query_mock = mockito::mock("POST", "/api/workflows/v1/query")
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .create();
+        let abort_mock = mockito::mock(
+            "POST",
+            "/api/workflows/v1/77777777-7777-7777-7777-777777777777/abort",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(
+            json!({
+                "id": "77777777-7777-7777-7777-777777777777",
+                "status": "Aborting"
+            })
+            .to_string(),
+        )
+        .create();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(create_test_workflow_reconciler())
+                .configure(init_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/admin/reconcile_workflows?abort=true")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        query_mock.assert();
+        abort_mock.assert();
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_report: ReconciliationReport = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_report.checked, 1);
+        assert_eq!(test_report.orphaned.len(), 1);
+        assert_eq!(test_report.orphaned[0].cromwell_server, "default");
+        assert_eq!(
+            test_report.orphaned[0].cromwell_job_id,
+            "77777777-7777-7777-7777-777777777777"
+        );
+        assert!(test_report.orphaned[0].aborted);
+    }
+
+    #[actix_rt::test]
+    async fn reconcile_workflows_failure_cromwell() {
+        let pool = get_test_db_pool();
+
+        let query_mock = mockito::mock("POST", "/api/workflows/v1/query")
+            .with_status(500)
+            .with_header("content_type", "application/json")
+            .with_body(json!({"status": "error", "message": "Oh no"}).to_string())
+            .create();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(create_test_workflow_reconciler())
+                .configure(init_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/admin/reconcile_workflows")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        query_mock.assert();
+
+        assert_eq!(resp.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Server error");
+        assert_eq!(error_body.status, 500);
+    }
+}
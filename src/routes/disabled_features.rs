@@ -27,6 +27,17 @@ pub fn software_building_disabled_mapping() -> HttpResponse {
     )
 }
 
+/// Returns an http response with a message explaining that secrets are disabled
+pub fn secrets_disabled_mapping() -> HttpResponse {
+    HttpResponse::UnprocessableEntity().json(
+        ErrorBody{
+            title: "Secrets disabled".to_string(),
+            status: 422,
+            detail: "You are trying to access a secret-related endpoint, but the secrets feature is disabled for this CARROT server".to_string(),
+        }
+    )
+}
+
 /// If using gs uris for wdls is enabled, returns Ok(()), otherwise returns an error containing an
 /// HttpResponse explaining that using gs uris for wdls is disabled
 pub fn is_gs_uris_for_wdls_enabled(
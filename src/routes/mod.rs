@@ -1,11 +1,14 @@
 //! Contains modules for defining REST API mappings
 
 // Declare route modules as public so they can be accessed elsewhere
+pub mod admin;
 pub mod pipeline;
 pub mod report;
 pub mod result;
 pub mod run;
+pub mod run_group;
 pub mod run_report;
+pub mod secret;
 pub mod software;
 pub mod software_build;
 pub mod software_version;
@@ -14,6 +17,7 @@ pub mod template;
 pub mod template_report;
 pub mod template_result;
 pub mod test;
+pub mod test_schedule;
 
 mod disabled_features;
 mod error_handling;
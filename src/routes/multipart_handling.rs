@@ -7,7 +7,8 @@ use actix_web::dev::RequestHead;
 use actix_web::web::{BufMut, BytesMut};
 use actix_web::HttpResponse;
 use futures::{StreamExt, TryStreamExt};
-use log::warn;
+use log::{error, warn};
+use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt;
 use std::io::Write;
@@ -205,6 +206,28 @@ pub async fn extract_data_from_multipart(
     Ok((string_map, file_map))
 }
 
+/// Removes the value for `field` from `text_data_map` and parses it as json.  Returns Ok(None) if
+/// there is no value for `field`, or an error response if the value is not valid json
+pub fn get_json_from_multipart_text_field(
+    text_data_map: &mut HashMap<String, String>,
+    field: &str,
+) -> Result<Option<Value>, HttpResponse> {
+    match text_data_map.remove(field) {
+        Some(text) => match serde_json::from_str(&text) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) => {
+                error!("{}", e);
+                Err(HttpResponse::BadRequest().json(ErrorBody {
+                    title: "Invalid json".to_string(),
+                    status: 400,
+                    detail: format!("Failed to parse {} as json with error: {}", field, e),
+                }))
+            }
+        },
+        None => Ok(None),
+    }
+}
+
 /// Header guard function for checking if the content type of a request head (`req`) is multipart
 ///
 /// This is necessary (instead of just using `guard::Header("Content-Type","multipart/form-data")`)
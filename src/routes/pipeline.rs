@@ -312,6 +312,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline")
@@ -327,6 +330,9 @@ mod tests {
             eval_wdl: String::from("evalevaleval"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(conn, new_template).expect("Failed inserting test template")
@@ -458,6 +464,9 @@ mod tests {
             name: String::from("Kevin's test"),
             description: Some(String::from("Kevin's test description")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let req = test::TestRequest::post()
@@ -498,6 +507,9 @@ mod tests {
             name: pipeline.name.clone(),
             description: Some(String::from("Kevin's test description")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let req = test::TestRequest::post()
@@ -527,6 +539,9 @@ mod tests {
         let pipeline_change = PipelineChangeset {
             name: Some(String::from("Kevin's test change")),
             description: Some(String::from("Kevin's test description2")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let req = test::TestRequest::put()
@@ -560,6 +575,9 @@ mod tests {
         let pipeline_change = PipelineChangeset {
             name: Some(String::from("Kevin's test change")),
             description: Some(String::from("Kevin's test description2")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let req = test::TestRequest::put()
@@ -590,6 +608,9 @@ mod tests {
         let pipeline_change = PipelineChangeset {
             name: Some(String::from("Kevin's test change")),
             description: Some(String::from("Kevin's test description2")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let req = test::TestRequest::put()
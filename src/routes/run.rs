@@ -3,20 +3,35 @@
 //! Contains functions for processing requests to search runs, along with
 //! their URI mappings
 
-use crate::custom_sql_types::RunStatusEnum;
+use crate::custom_sql_types::{RerunModeEnum, RunCallWorkflowEnum, RunStatusEnum};
 use crate::db;
+use crate::manager::log_retriever::LogRetriever;
 use crate::manager::test_runner;
 use crate::manager::test_runner::TestRunner;
 use crate::models::run::{DeleteError, RunData, RunQuery, RunWithResultsAndErrorsData};
+use crate::models::run_call::{RunCallData, RunCallQuery};
+use crate::models::run_group::{NewRunGroup, RunGroupData};
+use crate::models::run_in_group::RunInGroupData;
+use crate::models::run_input_source::RunInputSourceData;
+use crate::models::run_is_rerun::RunIsRerunData;
+use crate::models::test::TestData;
 use crate::routes::error_handling::{default_500, ErrorBody};
+use crate::routes::multipart_handling;
+use crate::routes::util::wdl_inputs_error_to_error_body;
+use crate::util::batch_csv;
+use crate::util::secrets::{self, SecretEncryptor, SecretRedactor};
+use crate::validation::womtool::WomtoolRunner;
+use actix_multipart::Multipart;
 use actix_web::dev::HttpResponseBuilder;
 use actix_web::http::StatusCode;
-use actix_web::{error::BlockingError, web, HttpRequest, HttpResponse, Responder};
-use chrono::NaiveDateTime;
+use actix_web::{error::BlockingError, guard, web, HttpRequest, HttpResponse, Responder};
+use chrono::{NaiveDateTime, Utc};
+use diesel::PgConnection;
 use log::error;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use serde_json::Value;
+use std::collections::HashSet;
 use uuid::Uuid;
 
 /// Represents the part of a run query that is received as a request body
@@ -60,6 +75,97 @@ pub struct NewRunIncomplete {
     pub created_by: Option<String>,
 }
 
+/// Represents the request body for rerunning a run
+///
+/// The mapping for rerunning a run expects the id of the run to rerun as a path param.  `mode`
+/// specifies whether to rerun the full run or only the eval step, and defaults to a full rerun if
+/// not specified
+#[derive(Deserialize, Serialize)]
+pub struct RerunIncomplete {
+    pub mode: Option<RerunModeEnum>,
+    pub name: Option<String>,
+    pub created_by: Option<String>,
+}
+
+/// Represents the part of a run_call query that is received as query params
+///
+/// The mapping for querying the calls for a run has the run_id as a path param and the other
+/// parameters are expected as query params.  A RunCallQuery can be built from the instance of this
+/// and the id from the path
+#[derive(Deserialize)]
+pub struct RunCallQueryIncomplete {
+    pub workflow: Option<RunCallWorkflowEnum>,
+    pub cromwell_job_id: Option<String>,
+    pub task_name: Option<String>,
+    pub execution_status: Option<String>,
+    pub backend_status: Option<String>,
+    pub return_code: Option<i32>,
+    pub docker_image: Option<String>,
+    pub started_before: Option<NaiveDateTime>,
+    pub started_after: Option<NaiveDateTime>,
+    pub finished_before: Option<NaiveDateTime>,
+    pub finished_after: Option<NaiveDateTime>,
+    pub sort: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Represents the request body for starting a batch of runs for a test
+///
+/// The mapping for starting a batch of runs expects the test_id as a path param.  Each entry in
+/// `runs` is used to start one run, and all of the runs are added to a new run group with the
+/// specified `name` and `description`.  `created_by` is used as the created_by value for the run
+/// group and for any runs that do not specify their own
+#[derive(Deserialize, Serialize)]
+pub struct NewRunBatchIncomplete {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub runs: Vec<NewRunIncomplete>,
+    pub created_by: Option<String>,
+}
+
+/// Represents the set of possible query parameters that can be received by the mappings for
+/// starting runs
+#[derive(Deserialize, Serialize)]
+struct StartRunQueryParams {
+    skip_input_validation: Option<bool>,
+}
+
+impl StartRunQueryParams {
+    /// Returns `womtool_runner` if the run's inputs should be validated against the WDLs, or None
+    /// if the request asked to skip that
+    fn get_womtool_runner_for_validation<'a>(
+        &self,
+        womtool_runner: &'a WomtoolRunner,
+    ) -> Option<&'a WomtoolRunner> {
+        match self.skip_input_validation {
+            Some(true) => None,
+            _ => Some(womtool_runner),
+        }
+    }
+}
+
+/// Represents the outcome of attempting to start a single run from a batch
+///
+/// `index` is the position of the run within the batch (0-based, not counting a CSV header row).
+/// If the run was started successfully, `run` will be filled; otherwise, `error` will contain the
+/// reason it could not be started, and `run` will be filled if the run was created before it
+/// failed
+#[derive(Deserialize, Serialize)]
+pub struct RunBatchRowResult {
+    pub index: usize,
+    pub run: Option<RunData>,
+    pub error: Option<ErrorBody>,
+}
+
+/// Represents the response body for a batch run submission, containing the run group created for
+/// the batch and the result for each run in the batch
+#[derive(Deserialize, Serialize)]
+pub struct RunBatchResult {
+    pub run_group: RunGroupData,
+    pub runs: Vec<RunBatchRowResult>,
+}
+
 /// Handles requests to /runs/{id} for retrieving run info by run_id
 ///
 /// This function is called by Actix-Web when a get request is made to the /runs/{id} mapping
@@ -150,6 +256,7 @@ async fn find_for_test(
         pipeline_id: None,
         template_id: None,
         test_id: Some(id),
+        run_group_id: None,
         name: query.name,
         status: query.status,
         test_input: query.test_input,
@@ -236,6 +343,7 @@ async fn find_for_template(
         pipeline_id: None,
         template_id: Some(id),
         test_id: None,
+        run_group_id: None,
         name: query.name,
         status: query.status,
         test_input: query.test_input,
@@ -322,6 +430,7 @@ async fn find_for_pipeline(
         pipeline_id: Some(id),
         template_id: None,
         test_id: None,
+        run_group_id: None,
         name: query.name,
         status: query.status,
         test_input: query.test_input,
@@ -373,20 +482,111 @@ async fn find_for_pipeline(
     })
 }
 
+/// Handles requests to /run_groups/{id}/runs for retrieving run info by query parameters and
+/// run group id
+///
+/// This function is called by Actix-Web when a get request is made to the /run_groups/{id}/runs
+/// mapping
+/// It deserializes the query params to a RunQuery, connects to the db via a connection from
+/// `pool`, and returns the retrieved runs, or an error message if there is no matching
+/// run or some other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find_for_run_group(
+    id: web::Path<String>,
+    web::Query(query): web::Query<RunQueryIncomplete>,
+    pool: web::Data<db::DbPool>,
+) -> impl Responder {
+    //Parse ID into Uuid
+    let id = match Uuid::parse_str(&id) {
+        Ok(id) => id,
+        Err(e) => {
+            error!("{}", e);
+            // If it doesn't parse successfully, return an error to the user
+            return Ok(HttpResponse::BadRequest().json(ErrorBody {
+                title: "ID formatted incorrectly".to_string(),
+                status: 400,
+                detail: "ID must be formatted as a Uuid".to_string(),
+            }));
+        }
+    };
+
+    // Create RunQuery based on id and query
+    let query = RunQuery {
+        pipeline_id: None,
+        template_id: None,
+        test_id: None,
+        run_group_id: Some(id),
+        name: query.name,
+        status: query.status,
+        test_input: query.test_input,
+        test_options: query.test_options,
+        eval_input: query.eval_input,
+        eval_options: query.eval_options,
+        test_cromwell_job_id: query.test_cromwell_job_id,
+        eval_cromwell_job_id: query.eval_cromwell_job_id,
+        created_before: query.created_before,
+        created_after: query.created_after,
+        created_by: query.created_by,
+        finished_before: query.finished_before,
+        finished_after: query.finished_after,
+        sort: query.sort,
+        limit: query.limit,
+        offset: query.offset,
+    };
+
+    //Query DB for runs in new thread
+    web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match RunWithResultsAndErrorsData::find(&conn, query) {
+            Ok(runs) => Ok(runs),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    .map(|results| {
+        // If no run is found, return a 404
+        if results.is_empty() {
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No run found".to_string(),
+                status: 404,
+                detail: "No runs found with the specified parameters".to_string(),
+            })
+        } else {
+            // If there is no error, return a response with the retrieved data
+            HttpResponse::Ok().json(results)
+        }
+    })
+    .map_err(|e| {
+        // If there is an error, return a 500
+        error!("{}", e);
+        default_500(&e)
+    })
+}
+
 /// Handles requests to /tests/{id}/runs for starting a run for a test
 ///
 /// This function is called by Actix-Web when a post request is made to the /tests/{id}/runs mapping
 /// It deserializes the request body to a NewRunIncomplete, retrieves the WDLs and json defaults from
 /// the template and test tables, generates a WDL for running the test and evaluation in succession,
-/// submits the job to cromwell, and inserts the run record into the DB.
+/// submits the job to cromwell, and inserts the run record into the DB.  Before creating the run,
+/// its inputs are checked against the inputs of the WDLs unless the skip_input_validation query
+/// param is true
 ///
 /// # Panics
 /// Panics if attempting to connect to the database results in an error
 async fn run_for_test(
     id: web::Path<String>,
+    web::Query(query_params): web::Query<StartRunQueryParams>,
     web::Json(run_inputs): web::Json<NewRunIncomplete>,
     pool: web::Data<db::DbPool>,
     test_runner: web::Data<TestRunner>,
+    womtool_runner: web::Data<WomtoolRunner>,
 ) -> HttpResponse {
     // Get DB connection
     let conn = pool.get().expect("Failed to get DB connection from pool");
@@ -401,63 +601,14 @@ async fn run_for_test(
             run_inputs.eval_input,
             run_inputs.eval_options,
             run_inputs.created_by,
+            None,
+            query_params.get_womtool_runner_for_validation(&womtool_runner),
         )
         .await
     {
         Ok(run) => HttpResponse::Ok().json(run),
         Err(err) => {
-            let error_body = match err {
-                test_runner::Error::DuplicateName => ErrorBody {
-                    title: "Run with specified name already exists".to_string(),
-                    status: 400,
-                    detail: "If a custom run name is specified, it must be unique.".to_string(),
-                },
-                test_runner::Error::Cromwell(e) => ErrorBody {
-                    title: "Server error".to_string(),
-                    status: 500,
-                    detail: format!("Submitting job to Cromwell failed with error: {}", e),
-                },
-                test_runner::Error::TempFile(_) => ErrorBody {
-                    title: "Server error".to_string(),
-                    status: 500,
-                    detail: "Encountered error while attempting to create temp file for submitting test to cromwell".to_string(),
-                },
-                test_runner::Error::Uuid(_) => ErrorBody {
-                    title: "ID formatted incorrectly".to_string(),
-                    status: 400,
-                    detail: "ID must be formatted as a Uuid".to_string(),
-                },
-                test_runner::Error::DB(e) => ErrorBody {
-                    title: "Server error".to_string(),
-                    status: 500,
-                    detail: format!("Error while attempting to query the database: {}", e),
-                },
-                test_runner::Error::Json => ErrorBody {
-                    title: "Server error".to_string(),
-                    status: 500,
-                    detail: "Encountered error while attempting to parse input json".to_string(),
-                },
-                test_runner::Error::SoftwareNotFound(name) => ErrorBody {
-                    title: "No such software exists".to_string(),
-                    status: 400,
-                    detail: format!("No software registered with the name: {}", name),
-                },
-                test_runner::Error::Build(e) => ErrorBody {
-                    title: "Server error".to_string(),
-                    status: 500,
-                    detail: format!("Error while attempting to build software docker image: {}", e),
-                },
-                test_runner::Error::MissingOutputKey(k) => ErrorBody {
-                    title: "Server error".to_string(),
-                    status: 500,
-                    detail: format!("Error while attempting to retrieve key ({}) from cromwell outputs to fill as input to eval wdl", k),
-                },
-                test_runner::Error::ResourceRequest(e) => ErrorBody {
-                    title: "Server error".to_string(),
-                    status: 500,
-                    detail: format!("Error while attempting to retrieve WDL: {}", e)
-                }
-            };
+            let error_body = create_run_error_to_error_body(err);
             HttpResponseBuilder::new(
                 StatusCode::from_u16(error_body.status)
                     .expect("Failed to parse status code. This shouldn't happen"),
@@ -467,6 +618,415 @@ async fn run_for_test(
     }
 }
 
+/// Converts `err`, returned from attempting to create a run using a TestRunner, into an ErrorBody
+/// with an appropriate status, title, and detail for returning to the user
+fn create_run_error_to_error_body(err: test_runner::Error) -> ErrorBody {
+    match err {
+        test_runner::Error::DuplicateName => ErrorBody {
+            title: "Run with specified name already exists".to_string(),
+            status: 400,
+            detail: "If a custom run name is specified, it must be unique.".to_string(),
+        },
+        test_runner::Error::Cromwell(e) => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: format!("Submitting job to Cromwell failed with error: {}", e),
+        },
+        test_runner::Error::TempFile(_) => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: "Encountered error while attempting to create temp file for submitting test to cromwell".to_string(),
+        },
+        test_runner::Error::Uuid(_) => ErrorBody {
+            title: "ID formatted incorrectly".to_string(),
+            status: 400,
+            detail: "ID must be formatted as a Uuid".to_string(),
+        },
+        test_runner::Error::DB(e) => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: format!("Error while attempting to query the database: {}", e),
+        },
+        test_runner::Error::Json => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: "Encountered error while attempting to parse input json".to_string(),
+        },
+        test_runner::Error::SoftwareNotFound(name) => ErrorBody {
+            title: "No such software exists".to_string(),
+            status: 400,
+            detail: format!("No software registered with the name: {}", name),
+        },
+        test_runner::Error::Build(e) => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: format!("Error while attempting to build software docker image: {}", e),
+        },
+        test_runner::Error::MissingOutputKey(k) => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: format!("Error while attempting to retrieve key ({}) from cromwell outputs to fill as input to eval wdl", k),
+        },
+        test_runner::Error::ResourceRequest(e) => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: format!("Error while attempting to retrieve WDL: {}", e)
+        },
+        test_runner::Error::AlreadyFinished => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: "Run finished before it could be started".to_string()
+        },
+        test_runner::Error::RerunNotAllowed(msg) => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: format!("Encountered an unexpected rerun error: {}", msg)
+        },
+        test_runner::Error::Server(e) => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: format!("Error while attempting to choose a cromwell server for the run: {}", e)
+        },
+        test_runner::Error::Inputs(e) => wdl_inputs_error_to_error_body(e),
+        test_runner::Error::Secrets(e) => match e {
+            secrets::Error::NotFound(_) | secrets::Error::NotConfigured(_) => ErrorBody {
+                title: "Invalid secret reference".to_string(),
+                status: 400,
+                detail: format!("Failed to fill in a secret referenced in the inputs: {}", e)
+            },
+            _ => ErrorBody {
+                title: "Server error".to_string(),
+                status: 500,
+                detail: format!("Error while attempting to fill in a secret referenced in the inputs: {}", e)
+            },
+        },
+        test_runner::Error::InputReference(msg) => ErrorBody {
+            title: "Invalid input reference".to_string(),
+            status: 400,
+            detail: format!("Failed to fill in an output of another run referenced in the inputs: {}", msg)
+        },
+    }
+}
+
+/// Handles requests to /tests/{id}/runs/batch with content-type application/json for starting a
+/// batch of runs for a test
+///
+/// Wrapper for [`run_batch_for_test`] for handling json requests. This function is called by
+/// Actix-Web when a post request is made to the /tests/{id}/runs/batch mapping with the
+/// content-type header set to application/json
+/// It deserializes the request body to a NewRunBatchIncomplete and starts a run for each of the
+/// entries in its `runs`
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn run_batch_for_test_from_json(
+    id: web::Path<String>,
+    web::Query(query_params): web::Query<StartRunQueryParams>,
+    web::Json(batch_inputs): web::Json<NewRunBatchIncomplete>,
+    pool: web::Data<db::DbPool>,
+    test_runner: web::Data<TestRunner>,
+    womtool_runner: web::Data<WomtoolRunner>,
+) -> HttpResponse {
+    run_batch_for_test(
+        &id,
+        batch_inputs,
+        pool,
+        test_runner,
+        query_params.get_womtool_runner_for_validation(&womtool_runner),
+    )
+    .await
+}
+
+/// Handles requests to /tests/{id}/runs/batch with content-type multipart/form-data for starting a
+/// batch of runs for a test
+///
+/// Wrapper for [`run_batch_for_test`] for handling multipart requests. This function is called by
+/// Actix-Web when a post request is made to the /tests/{id}/runs/batch mapping with the
+/// content-type header set to multipart/form-data
+/// It builds a NewRunBatchIncomplete from the fields in `payload`, with one run for each row of
+/// the runs_csv file (see [`crate::util::batch_csv`] for the expected format), and starts those
+/// runs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn run_batch_for_test_from_multipart(
+    id: web::Path<String>,
+    web::Query(query_params): web::Query<StartRunQueryParams>,
+    payload: Multipart,
+    pool: web::Data<db::DbPool>,
+    test_runner: web::Data<TestRunner>,
+    womtool_runner: web::Data<WomtoolRunner>,
+) -> HttpResponse {
+    // Process the payload
+    let batch_inputs = match get_new_run_batch_from_multipart(payload).await {
+        Ok(batch_inputs) => batch_inputs,
+        Err(error_response) => return error_response,
+    };
+    run_batch_for_test(
+        &id,
+        batch_inputs,
+        pool,
+        test_runner,
+        query_params.get_womtool_runner_for_validation(&womtool_runner),
+    )
+    .await
+}
+
+/// Starts a run for the test specified by `id` for each entry in `batch_inputs.runs` and ties them
+/// together in a new run group
+///
+/// Creates a run group using the name, description, and created_by from `batch_inputs`
+/// (generating a name if one is not specified), and then attempts to start each run with
+/// `test_runner`.  Failing to start a run does not stop the rest of the batch; instead, the error
+/// is recorded in the result for that run.  If `womtool_runner` is provided, it's used to check each
+/// run's inputs against the WDLs before starting it.  Returns the run group and the result for each
+/// run, or an error response if the batch is empty, the test does not exist, or the run group
+/// cannot be created
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn run_batch_for_test(
+    id: &str,
+    batch_inputs: NewRunBatchIncomplete,
+    pool: web::Data<db::DbPool>,
+    test_runner: web::Data<TestRunner>,
+    womtool_runner: Option<&WomtoolRunner>,
+) -> HttpResponse {
+    // Parse ID into Uuid
+    let test_id = match Uuid::parse_str(id) {
+        Ok(test_id) => test_id,
+        Err(e) => {
+            error!("{}", e);
+            // If it doesn't parse successfully, return an error to the user
+            return HttpResponse::BadRequest().json(ErrorBody {
+                title: "ID formatted incorrectly".to_string(),
+                status: 400,
+                detail: "ID must be formatted as a Uuid".to_string(),
+            });
+        }
+    };
+    // Make sure there's actually something to run
+    if batch_inputs.runs.is_empty() {
+        return HttpResponse::BadRequest().json(ErrorBody {
+            title: "No runs specified".to_string(),
+            status: 400,
+            detail: "A batch must contain at least one run".to_string(),
+        });
+    }
+    // Get DB connection
+    let conn = pool.get().expect("Failed to get DB connection from pool");
+    // Retrieve the test so we can make sure it exists and use its name for the group name
+    let test = match TestData::find_by_id(&conn, test_id) {
+        Ok(test) => test,
+        Err(diesel::NotFound) => {
+            return HttpResponse::NotFound().json(ErrorBody {
+                title: "No test found".to_string(),
+                status: 404,
+                detail: "No test found with the specified ID".to_string(),
+            });
+        }
+        Err(e) => {
+            error!("{}", e);
+            return HttpResponse::InternalServerError().json(ErrorBody {
+                title: "Server error".to_string(),
+                status: 500,
+                detail: format!("Error while attempting to query the database: {}", e),
+            });
+        }
+    };
+    // Create the run group for the batch
+    let batch_created_by = batch_inputs.created_by;
+    let new_run_group = NewRunGroup {
+        name: batch_inputs
+            .name
+            .unwrap_or_else(|| format!("{}_batch_{}", test.name, Utc::now())),
+        description: batch_inputs.description,
+        created_by: batch_created_by.clone(),
+    };
+    let run_group = match RunGroupData::create(&conn, new_run_group) {
+        Ok(run_group) => run_group,
+        Err(diesel::result::Error::DatabaseError(
+            diesel::result::DatabaseErrorKind::UniqueViolation,
+            _,
+        )) => {
+            return HttpResponse::BadRequest().json(ErrorBody {
+                title: "Run group with specified name already exists".to_string(),
+                status: 400,
+                detail: "If a custom batch name is specified, it must be unique.".to_string(),
+            });
+        }
+        Err(e) => {
+            error!("{}", e);
+            return HttpResponse::InternalServerError().json(ErrorBody {
+                title: "Server error".to_string(),
+                status: 500,
+                detail: format!("Error while attempting to insert run group: {}", e),
+            });
+        }
+    };
+    // Start each of the runs, keeping track of the result for each
+    let mut run_results: Vec<RunBatchRowResult> = Vec::new();
+    // Keep track of the runs we've already reported so we can identify runs that were created but
+    // failed to start
+    let mut started_run_ids: HashSet<Uuid> = HashSet::new();
+    for (index, run_inputs) in batch_inputs.runs.into_iter().enumerate() {
+        let created_by = run_inputs.created_by.or_else(|| batch_created_by.clone());
+        let run_result = match test_runner
+            .create_run(
+                &conn,
+                id,
+                run_inputs.name,
+                run_inputs.test_input,
+                run_inputs.test_options,
+                run_inputs.eval_input,
+                run_inputs.eval_options,
+                created_by,
+                Some(run_group.run_group_id),
+                womtool_runner,
+            )
+            .await
+        {
+            Ok(run) => {
+                started_run_ids.insert(run.run_id);
+                RunBatchRowResult {
+                    index,
+                    run: Some(run),
+                    error: None,
+                }
+            }
+            Err(e) => {
+                error!("{}", e);
+                // If the run was created before it failed, it will already be in the group, so
+                // look for it so we can report which run it was
+                let failed_run =
+                    find_new_run_in_group(&conn, run_group.run_group_id, &started_run_ids);
+                if let Some(failed_run) = &failed_run {
+                    started_run_ids.insert(failed_run.run_id);
+                }
+                RunBatchRowResult {
+                    index,
+                    run: failed_run,
+                    error: Some(create_run_error_to_error_body(e)),
+                }
+            }
+        };
+        run_results.push(run_result);
+    }
+
+    HttpResponse::Ok().json(RunBatchResult {
+        run_group,
+        runs: run_results,
+    })
+}
+
+/// Retrieves the run in the run group specified by `run_group_id` that is not in `known_run_ids`,
+/// if there is one
+///
+/// Used for finding a run that was created as part of a batch but failed to start.  Returns None
+/// if there is no such run or if there is an error querying the DB (in which case the error is
+/// logged)
+fn find_new_run_in_group(
+    conn: &PgConnection,
+    run_group_id: Uuid,
+    known_run_ids: &HashSet<Uuid>,
+) -> Option<RunData> {
+    let run_in_groups = match RunInGroupData::find_by_run_group_id(conn, run_group_id) {
+        Ok(run_in_groups) => run_in_groups,
+        Err(e) => {
+            error!("{}", e);
+            return None;
+        }
+    };
+    let run_id = run_in_groups
+        .into_iter()
+        .map(|run_in_group| run_in_group.run_id)
+        .find(|run_id| !known_run_ids.contains(run_id))?;
+    match RunData::find_by_id(conn, run_id) {
+        Ok(run) => Some(run),
+        Err(e) => {
+            error!("{}", e);
+            None
+        }
+    }
+}
+
+/// Builds a NewRunBatchIncomplete from the fields in `payload`
+///
+/// Expects a runs_csv file field, with one run per row, and optional name, description,
+/// created_by, test_options, and eval_options text fields.  test_options and eval_options, if
+/// present, must be json and are applied to every run in the batch.  Returns an error response if
+/// processing the payload fails, if test_options or eval_options are not valid json, or if the
+/// runs_csv file cannot be parsed
+async fn get_new_run_batch_from_multipart(
+    payload: Multipart,
+) -> Result<NewRunBatchIncomplete, HttpResponse> {
+    // The fields we expect from the multipart payload
+    const EXPECTED_TEXT_FIELDS: [&str; 5] = [
+        "name",
+        "description",
+        "created_by",
+        "test_options",
+        "eval_options",
+    ];
+    const EXPECTED_FILE_FIELDS: [&str; 1] = ["runs_csv"];
+    // The fields that are required from the multipart payload
+    const REQUIRED_FILE_FIELDS: [&str; 1] = ["runs_csv"];
+    // Get the data from the multipart payload
+    let (mut text_data_map, mut file_data_map) = multipart_handling::extract_data_from_multipart(
+        payload,
+        &EXPECTED_TEXT_FIELDS.to_vec(),
+        &EXPECTED_FILE_FIELDS.to_vec(),
+        &[].to_vec(),
+        &REQUIRED_FILE_FIELDS.to_vec(),
+    )
+    .await?;
+    // Parse the options, which apply to every run in the batch
+    let test_options =
+        multipart_handling::get_json_from_multipart_text_field(&mut text_data_map, "test_options")?;
+    let eval_options =
+        multipart_handling::get_json_from_multipart_text_field(&mut text_data_map, "eval_options")?;
+    // Parse the runs from the csv
+    let runs_csv = file_data_map
+        .remove("runs_csv")
+        .expect("Failed to retrieve runs_csv from file_data_map. This should not happen.");
+    let runs_csv_contents = match std::fs::read(runs_csv.path()) {
+        Ok(contents) => contents,
+        Err(e) => return Err(default_500(&e)),
+    };
+    let csv_rows = match batch_csv::parse_batch_csv(runs_csv_contents.as_slice()) {
+        Ok(csv_rows) => csv_rows,
+        Err(e) => {
+            error!("{}", e);
+            return Err(HttpResponse::BadRequest().json(ErrorBody {
+                title: "Failed to parse runs_csv".to_string(),
+                status: 400,
+                detail: format!(
+                    "Encountered the following error while attempting to parse runs_csv: {}",
+                    e
+                ),
+            }));
+        }
+    };
+    let runs: Vec<NewRunIncomplete> = csv_rows
+        .into_iter()
+        .map(|row| NewRunIncomplete {
+            name: row.name,
+            test_input: row.test_input,
+            test_options: test_options.clone(),
+            eval_input: row.eval_input,
+            eval_options: eval_options.clone(),
+            created_by: None,
+        })
+        .collect();
+
+    Ok(NewRunBatchIncomplete {
+        name: text_data_map.remove("name"),
+        description: text_data_map.remove("description"),
+        runs,
+        created_by: text_data_map.remove("created_by"),
+    })
+}
+
 /// Handles DELETE requests to /runs/{id} for deleting runs
 ///
 /// This function is called by Actix-Web when a delete request is made to the /runs/{id} mapping
@@ -537,412 +1097,2336 @@ async fn delete_by_id(req: HttpRequest, pool: web::Data<db::DbPool>) -> impl Res
     })
 }
 
-/// Attaches the REST mappings in this file to a service config
+/// Handles requests to /runs/{id}/abort for aborting a run
 ///
-/// To be called when configuring the Actix-Web app service.  Registers the mappings in this file
-/// as part of the service defined in `cfg`
-pub fn init_routes(cfg: &mut web::ServiceConfig) {
-    cfg.service(
-        web::resource("/tests/{id}/runs")
-            .route(web::get().to(find_for_test))
-            .route(web::post().to(run_for_test)),
-    );
-    cfg.service(
-        web::resource("/runs/{id}")
-            .route(web::get().to(find_by_id))
-            .route(web::delete().to(delete_by_id)),
-    );
-    cfg.service(web::resource("/templates/{id}/runs").route(web::get().to(find_for_template)));
-    cfg.service(web::resource("/pipelines/{id}/runs").route(web::get().to(find_for_pipeline)));
+/// This function is called by Actix-Web when a post request is made to the /runs/{id}/abort mapping
+/// It parses the id from `req`, retrieves the specified run, and attempts to abort it using
+/// `test_runner`, returning the updated run or an error message if some error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn abort_by_id(
+    req: HttpRequest,
+    pool: web::Data<db::DbPool>,
+    test_runner: web::Data<TestRunner>,
+) -> HttpResponse {
+    // Pull id param from path
+    let id = &req.match_info().get("id").unwrap();
+
+    // Parse ID into Uuid
+    let id = match Uuid::parse_str(id) {
+        Ok(id) => id,
+        Err(e) => {
+            error!("{}", e);
+            // If it doesn't parse successfully, return an error to the user
+            return HttpResponse::BadRequest().json(ErrorBody {
+                title: "ID formatted incorrectly".to_string(),
+                status: 400,
+                detail: "ID must be formatted as a Uuid".to_string(),
+            });
+        }
+    };
+
+    // Get DB connection
+    let conn = pool.get().expect("Failed to get DB connection from pool");
+    // Retrieve the run so we know what state it's in
+    let run = match RunData::find_by_id(&conn, id) {
+        Ok(run) => run,
+        Err(diesel::NotFound) => {
+            return HttpResponse::NotFound().json(ErrorBody {
+                title: "No run found".to_string(),
+                status: 404,
+                detail: "No run found with the specified ID".to_string(),
+            });
+        }
+        Err(e) => {
+            error!("{}", e);
+            return HttpResponse::InternalServerError().json(ErrorBody {
+                title: "Server error".to_string(),
+                status: 500,
+                detail: format!("Error while attempting to query the database: {}", e),
+            });
+        }
+    };
+    // Abort it
+    match test_runner.abort_run(&conn, &run).await {
+        Ok(run) => HttpResponse::Ok().json(run),
+        Err(err) => {
+            error!("{}", err);
+            let error_body = match err {
+                test_runner::Error::AlreadyFinished => ErrorBody {
+                    title: "Cannot abort".to_string(),
+                    status: 403,
+                    detail: format!(
+                        "Cannot abort a run that has already finished (status: {})",
+                        run.status
+                    ),
+                },
+                test_runner::Error::Cromwell(e) => ErrorBody {
+                    title: "Server error".to_string(),
+                    status: 500,
+                    detail: format!(
+                        "Submitting abort request to Cromwell failed with error: {}",
+                        e
+                    ),
+                },
+                test_runner::Error::DB(e) => ErrorBody {
+                    title: "Server error".to_string(),
+                    status: 500,
+                    detail: format!("Error while attempting to query the database: {}", e),
+                },
+                e => ErrorBody {
+                    title: "Server error".to_string(),
+                    status: 500,
+                    detail: format!("Error while attempting to abort run: {}", e),
+                },
+            };
+            HttpResponseBuilder::new(
+                StatusCode::from_u16(error_body.status)
+                    .expect("Failed to parse status code. This shouldn't happen"),
+            )
+            .json(error_body)
+        }
+    }
+}
+
+/// Handles POST requests to /runs/{id}/rerun for rerunning a run
+///
+/// This function is called by Actix-Web when a post request is made to the /runs/{id}/rerun
+/// mapping.  It parses the id from `req`, deserializes the request body to a RerunIncomplete,
+/// retrieves the specified run, and creates a new run from it using `test_runner`, either rerunning
+/// the full run or only its eval step, depending on the mode specified.  Returns the new run, or an
+/// error message if the run cannot be rerun or some other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn rerun_by_id(
+    req: HttpRequest,
+    web::Json(rerun_params): web::Json<RerunIncomplete>,
+    pool: web::Data<db::DbPool>,
+    test_runner: web::Data<TestRunner>,
+) -> HttpResponse {
+    // Pull id param from path
+    let id = &req.match_info().get("id").unwrap();
+
+    // Parse ID into Uuid
+    let id = match Uuid::parse_str(id) {
+        Ok(id) => id,
+        Err(e) => {
+            error!("{}", e);
+            // If it doesn't parse successfully, return an error to the user
+            return HttpResponse::BadRequest().json(ErrorBody {
+                title: "ID formatted incorrectly".to_string(),
+                status: 400,
+                detail: "ID must be formatted as a Uuid".to_string(),
+            });
+        }
+    };
+
+    // Get DB connection
+    let conn = pool.get().expect("Failed to get DB connection from pool");
+    // Retrieve the run to rerun
+    let run = match RunData::find_by_id(&conn, id) {
+        Ok(run) => run,
+        Err(diesel::NotFound) => {
+            return HttpResponse::NotFound().json(ErrorBody {
+                title: "No run found".to_string(),
+                status: 404,
+                detail: "No run found with the specified ID".to_string(),
+            });
+        }
+        Err(e) => {
+            error!("{}", e);
+            return HttpResponse::InternalServerError().json(ErrorBody {
+                title: "Server error".to_string(),
+                status: 500,
+                detail: format!("Error while attempting to query the database: {}", e),
+            });
+        }
+    };
+    // Rerun it
+    match test_runner
+        .rerun_run(
+            &conn,
+            &run,
+            rerun_params.mode.unwrap_or(RerunModeEnum::Full),
+            rerun_params.name,
+            rerun_params.created_by,
+        )
+        .await
+    {
+        Ok(run) => HttpResponse::Ok().json(run),
+        Err(err) => {
+            error!("{}", err);
+            let error_body = match err {
+                test_runner::Error::RerunNotAllowed(msg) => ErrorBody {
+                    title: "Cannot rerun".to_string(),
+                    status: 403,
+                    detail: msg,
+                },
+                test_runner::Error::DuplicateName => ErrorBody {
+                    title: "Run with specified name already exists".to_string(),
+                    status: 400,
+                    detail: "If a custom run name is specified, it must be unique.".to_string(),
+                },
+                test_runner::Error::Cromwell(e) => ErrorBody {
+                    title: "Server error".to_string(),
+                    status: 500,
+                    detail: format!("Sending request to Cromwell failed with error: {}", e),
+                },
+                test_runner::Error::DB(e) => ErrorBody {
+                    title: "Server error".to_string(),
+                    status: 500,
+                    detail: format!("Error while attempting to query the database: {}", e),
+                },
+                e => ErrorBody {
+                    title: "Server error".to_string(),
+                    status: 500,
+                    detail: format!("Error while attempting to rerun run: {}", e),
+                },
+            };
+            HttpResponseBuilder::new(
+                StatusCode::from_u16(error_body.status)
+                    .expect("Failed to parse status code. This shouldn't happen"),
+            )
+            .json(error_body)
+        }
+    }
+}
+
+/// Handles requests to /runs/{id}/reruns for retrieving rerun records involving a run
+///
+/// This function is called by Actix-Web when a get request is made to the /runs/{id}/reruns
+/// mapping.  It parses the id from `req`, connects to the db via a connection from `pool`, and
+/// returns the records linking the specified run to the run it was rerun from and to any reruns of
+/// it, or an error message if there are no such records or some other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find_reruns_by_id(req: HttpRequest, pool: web::Data<db::DbPool>) -> impl Responder {
+    // Pull id param from path
+    let id = &req.match_info().get("id").unwrap();
+
+    // Parse ID into Uuid
+    let id = match Uuid::parse_str(id) {
+        Ok(id) => id,
+        Err(e) => {
+            error!("{}", e);
+            // If it doesn't parse successfully, return an error to the user
+            return Ok(HttpResponse::BadRequest().json(ErrorBody {
+                title: "ID formatted incorrectly".to_string(),
+                status: 400,
+                detail: "ID must be formatted as a Uuid".to_string(),
+            }));
+        }
+    };
+
+    // Query DB for reruns in new thread
+    web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match RunIsRerunData::find_for_run(&conn, id) {
+            Ok(reruns) => Ok(reruns),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    .map(|results| {
+        // If no reruns are found, return a 404
+        if results.is_empty() {
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No reruns found".to_string(),
+                status: 404,
+                detail: "No reruns found for the specified run".to_string(),
+            })
+        } else {
+            // If there is no error, return a response with the retrieved data
+            HttpResponse::Ok().json(results)
+        }
+    })
+    .map_err(|e| {
+        // If there is an error, return a 500
+        error!("{}", e);
+        default_500(&e)
+    })
+}
+
+/// Handles requests to /runs/{id}/input_sources for retrieving the sources of a run's inputs that
+/// were filled in from the outputs of other runs
+///
+/// This function is called by Actix-Web when a get request is made to the /runs/{id}/input_sources
+/// mapping.  It parses the id from `req`, connects to the db via a connection from `pool`, and
+/// returns the records of which run each of the specified run's `run_output:` and
+/// `latest_successful_output:` inputs were resolved from, or an error message if there are no such
+/// records or some other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find_input_sources_by_id(req: HttpRequest, pool: web::Data<db::DbPool>) -> impl Responder {
+    // Pull id param from path
+    let id = &req.match_info().get("id").unwrap();
+
+    // Parse ID into Uuid
+    let id = match Uuid::parse_str(id) {
+        Ok(id) => id,
+        Err(e) => {
+            error!("{}", e);
+            // If it doesn't parse successfully, return an error to the user
+            return Ok(HttpResponse::BadRequest().json(ErrorBody {
+                title: "ID formatted incorrectly".to_string(),
+                status: 400,
+                detail: "ID must be formatted as a Uuid".to_string(),
+            }));
+        }
+    };
+
+    // Query DB for input sources in new thread
+    web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match RunInputSourceData::find_by_run_id(&conn, id) {
+            Ok(input_sources) => Ok(input_sources),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    .map(|results| {
+        // If no input sources are found, return a 404
+        if results.is_empty() {
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No input sources found".to_string(),
+                status: 404,
+                detail: "No inputs resolved from other runs found for the specified run"
+                    .to_string(),
+            })
+        } else {
+            // If there is no error, return a response with the retrieved data
+            HttpResponse::Ok().json(results)
+        }
+    })
+    .map_err(|e| {
+        // If there is an error, return a 500
+        error!("{}", e);
+        default_500(&e)
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::custom_sql_types::ResultTypeEnum;
-    use crate::models::pipeline::{NewPipeline, PipelineData};
-    use crate::models::result::{NewResult, ResultData};
-    use crate::models::run::{NewRun, RunData};
-    use crate::models::run_error::{NewRunError, RunErrorData};
-    use crate::models::run_result::{NewRunResult, RunResultData};
-    use crate::models::template::{NewTemplate, TemplateData};
-    use crate::models::test::{NewTest, TestData};
-    use crate::requests::cromwell_requests::CromwellClient;
-    use crate::requests::test_resource_requests::TestResourceClient;
-    use crate::unit_test_util::*;
-    use actix_web::client::Client;
-    use actix_web::{http, test, App};
-    use chrono::format::StrftimeItems;
-    use diesel::PgConnection;
-    use rand::distributions::Alphanumeric;
-    use rand::prelude::*;
-    use serde_json::json;
-    use std::fs::read_to_string;
-    use uuid::Uuid;
+/// Handles requests to /runs/{id}/calls for retrieving the cromwell calls for a run
+///
+/// This function is called by Actix-Web when a get request is made to the /runs/{id}/calls
+/// mapping.  It parses the id from `id`, deserializes the query params to a RunCallQuery, connects
+/// to the db via a connection from `pool`, and returns the calls from the test and eval workflows
+/// of the specified run, or an error message if there are no matching calls or some other error
+/// occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find_calls_by_id(
+    id: web::Path<String>,
+    web::Query(query): web::Query<RunCallQueryIncomplete>,
+    pool: web::Data<db::DbPool>,
+) -> impl Responder {
+    // Parse ID into Uuid
+    let id = match Uuid::parse_str(&*id) {
+        Ok(id) => id,
+        Err(e) => {
+            error!("{}", e);
+            // If it doesn't parse successfully, return an error to the user
+            return Ok(HttpResponse::BadRequest().json(ErrorBody {
+                title: "ID formatted incorrectly".to_string(),
+                status: 400,
+                detail: "ID must be formatted as a Uuid".to_string(),
+            }));
+        }
+    };
+
+    // Create RunCallQuery based on id and query
+    let query = RunCallQuery {
+        run_id: Some(id),
+        workflow: query.workflow,
+        cromwell_job_id: query.cromwell_job_id,
+        task_name: query.task_name,
+        execution_status: query.execution_status,
+        backend_status: query.backend_status,
+        return_code: query.return_code,
+        docker_image: query.docker_image,
+        started_before: query.started_before,
+        started_after: query.started_after,
+        finished_before: query.finished_before,
+        finished_after: query.finished_after,
+        sort: query.sort,
+        limit: query.limit,
+        offset: query.offset,
+    };
+
+    // Query DB for calls in new thread
+    web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match RunCallData::find(&conn, query) {
+            Ok(calls) => Ok(calls),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    .map(|results| {
+        // If no calls are found, return a 404
+        if results.is_empty() {
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No calls found".to_string(),
+                status: 404,
+                detail: "No calls found for the specified run with the specified parameters"
+                    .to_string(),
+            })
+        } else {
+            // If there is no error, return a response with the retrieved data
+            HttpResponse::Ok().json(results)
+        }
+    })
+    .map_err(|e| {
+        // If there is an error, return a 500
+        error!("{}", e);
+        default_500(&e)
+    })
+}
+
+/// Handles requests to /runs/{id}/logs for retrieving the cromwell logs for a run
+///
+/// This function is called by Actix-Web when a get request is made to the /runs/{id}/logs
+/// mapping.  It parses the id from `req`, retrieves the specified run, and uses `log_retriever` to
+/// retrieve the locations of the stdout and stderr files for each call in the run's test and eval
+/// workflows from cromwell, along with the contents of each file that carrot is able to read.
+/// Returns the logs, or an error message if the run has not been submitted to cromwell or some
+/// other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find_logs_by_id(
+    req: HttpRequest,
+    pool: web::Data<db::DbPool>,
+    log_retriever: web::Data<LogRetriever>,
+    secret_encryptor: Option<web::Data<SecretEncryptor>>,
+) -> HttpResponse {
+    // Retrieve the run so we know which cromwell jobs to get logs for
+    let run = match find_submitted_run_for_request(&req, &pool) {
+        Ok(run) => run,
+        Err(error_response) => return error_response,
+    };
+    // Load the secrets so we can redact them from the logs
+    let secret_encryptor = secret_encryptor
+        .as_ref()
+        .map(|encryptor| encryptor.get_ref());
+    let secret_redactor = match load_secret_redactor(&pool, secret_encryptor) {
+        Ok(secret_redactor) => secret_redactor,
+        Err(error_response) => return error_response,
+    };
+    match log_retriever.get_logs_for_run(&run).await {
+        Ok(logs) => HttpResponse::Ok().json(redact_secrets_from_json(&secret_redactor, &logs)),
+        Err(e) => {
+            error!("{}", e);
+            HttpResponse::InternalServerError().json(ErrorBody {
+                title: "Server error".to_string(),
+                status: 500,
+                detail: secret_redactor.redact_str(&format!(
+                    "Error while attempting to retrieve logs from Cromwell: {}",
+                    e
+                )),
+            })
+        }
+    }
+}
+
+/// Handles requests to /runs/{id}/failures for retrieving the cromwell failure messages for a run
+///
+/// This function is called by Actix-Web when a get request is made to the /runs/{id}/failures
+/// mapping.  It parses the id from `req`, retrieves the specified run, and uses `log_retriever` to
+/// retrieve the failures reported by cromwell for the run's test and eval workflows.  Returns the
+/// failures, or an error message if the run has not been submitted to cromwell or some other error
+/// occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find_failures_by_id(
+    req: HttpRequest,
+    pool: web::Data<db::DbPool>,
+    log_retriever: web::Data<LogRetriever>,
+    secret_encryptor: Option<web::Data<SecretEncryptor>>,
+) -> HttpResponse {
+    // Retrieve the run so we know which cromwell jobs to get failures for
+    let run = match find_submitted_run_for_request(&req, &pool) {
+        Ok(run) => run,
+        Err(error_response) => return error_response,
+    };
+    // Load the secrets so we can redact them from the failures
+    let secret_encryptor = secret_encryptor
+        .as_ref()
+        .map(|encryptor| encryptor.get_ref());
+    let secret_redactor = match load_secret_redactor(&pool, secret_encryptor) {
+        Ok(secret_redactor) => secret_redactor,
+        Err(error_response) => return error_response,
+    };
+    match log_retriever.get_failures_for_run(&run).await {
+        Ok(failures) => {
+            HttpResponse::Ok().json(redact_secrets_from_json(&secret_redactor, &failures))
+        }
+        Err(e) => {
+            error!("{}", e);
+            HttpResponse::InternalServerError().json(ErrorBody {
+                title: "Server error".to_string(),
+                status: 500,
+                detail: secret_redactor.redact_str(&format!(
+                    "Error while attempting to retrieve failures from Cromwell: {}",
+                    e
+                )),
+            })
+        }
+    }
+}
+
+/// Loads the values of all secrets using a connection from `pool` so they can be redacted from
+/// output retrieved from cromwell (which may contain secret values that were passed to the
+/// workflows).  If `secret_encryptor` is None, secrets are not enabled, so there's nothing to
+/// redact.  Returns an error response if the secrets can't be loaded, since the output can't be
+/// returned safely without redacting them
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+fn load_secret_redactor(
+    pool: &db::DbPool,
+    secret_encryptor: Option<&SecretEncryptor>,
+) -> Result<SecretRedactor, HttpResponse> {
+    let conn = pool.get().expect("Failed to get DB connection from pool");
+    SecretRedactor::load(&conn, secret_encryptor).map_err(|e| {
+        error!("{}", e);
+        HttpResponse::InternalServerError().json(ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: format!(
+                "Error while attempting to load secrets to redact from Cromwell output: {}",
+                e
+            ),
+        })
+    })
+}
+
+/// Converts `output` to json with the values of any secrets in it replaced by `secret_redactor`
+fn redact_secrets_from_json<T: Serialize>(secret_redactor: &SecretRedactor, output: &T) -> Value {
+    let mut output_json: Value = json!(output);
+    secret_redactor.redact_json(&mut output_json);
+    output_json
+}
+
+/// Parses the id from `req` and retrieves the corresponding run from the DB using a connection
+/// from `pool`.  Returns the run, or an error response if the id is invalid, the run does not
+/// exist, the run has not been submitted to cromwell, or there is an error querying the DB
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+fn find_submitted_run_for_request(
+    req: &HttpRequest,
+    pool: &db::DbPool,
+) -> Result<RunData, HttpResponse> {
+    // Pull id param from path
+    let id = &req.match_info().get("id").unwrap();
+
+    // Parse ID into Uuid
+    let id = match Uuid::parse_str(id) {
+        Ok(id) => id,
+        Err(e) => {
+            error!("{}", e);
+            // If it doesn't parse successfully, return an error to the user
+            return Err(HttpResponse::BadRequest().json(ErrorBody {
+                title: "ID formatted incorrectly".to_string(),
+                status: 400,
+                detail: "ID must be formatted as a Uuid".to_string(),
+            }));
+        }
+    };
+
+    // Get DB connection
+    let conn = pool.get().expect("Failed to get DB connection from pool");
+    // Retrieve the run
+    let run = match RunData::find_by_id(&conn, id) {
+        Ok(run) => run,
+        Err(diesel::NotFound) => {
+            return Err(HttpResponse::NotFound().json(ErrorBody {
+                title: "No run found".to_string(),
+                status: 404,
+                detail: "No run found with the specified ID".to_string(),
+            }));
+        }
+        Err(e) => {
+            error!("{}", e);
+            return Err(HttpResponse::InternalServerError().json(ErrorBody {
+                title: "Server error".to_string(),
+                status: 500,
+                detail: format!("Error while attempting to query the database: {}", e),
+            }));
+        }
+    };
+    // If it hasn't been submitted to cromwell, there's nothing to retrieve
+    if run.test_cromwell_job_id.is_none() && run.eval_cromwell_job_id.is_none() {
+        return Err(HttpResponse::NotFound().json(ErrorBody {
+            title: "No Cromwell jobs found".to_string(),
+            status: 404,
+            detail: format!(
+                "The specified run has not been submitted to Cromwell (status: {})",
+                run.status
+            ),
+        }));
+    }
+
+    Ok(run)
+}
+
+/// Attaches the REST mappings in this file to a service config
+///
+/// To be called when configuring the Actix-Web app service.  Registers the mappings in this file
+/// as part of the service defined in `cfg`
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/tests/{id}/runs")
+            .route(web::get().to(find_for_test))
+            .route(web::post().to(run_for_test)),
+    );
+    cfg.service(
+        web::resource("/tests/{id}/runs/batch")
+            .route(
+                web::route()
+                    .guard(guard::Post())
+                    .guard(guard::Header("Content-Type", "application/json"))
+                    .to(run_batch_for_test_from_json),
+            )
+            .route(
+                web::route()
+                    .guard(guard::Post())
+                    .guard(guard::fn_guard(
+                        multipart_handling::multipart_content_type_guard,
+                    ))
+                    .to(run_batch_for_test_from_multipart),
+            ),
+    );
+    cfg.service(
+        web::resource("/runs/{id}")
+            .route(web::get().to(find_by_id))
+            .route(web::delete().to(delete_by_id)),
+    );
+    cfg.service(web::resource("/runs/{id}/abort").route(web::post().to(abort_by_id)));
+    cfg.service(web::resource("/runs/{id}/rerun").route(web::post().to(rerun_by_id)));
+    cfg.service(web::resource("/runs/{id}/reruns").route(web::get().to(find_reruns_by_id)));
+    cfg.service(
+        web::resource("/runs/{id}/input_sources").route(web::get().to(find_input_sources_by_id)),
+    );
+    cfg.service(web::resource("/runs/{id}/calls").route(web::get().to(find_calls_by_id)));
+    cfg.service(web::resource("/runs/{id}/logs").route(web::get().to(find_logs_by_id)));
+    cfg.service(web::resource("/runs/{id}/failures").route(web::get().to(find_failures_by_id)));
+    cfg.service(web::resource("/templates/{id}/runs").route(web::get().to(find_for_template)));
+    cfg.service(web::resource("/pipelines/{id}/runs").route(web::get().to(find_for_pipeline)));
+    cfg.service(web::resource("/run_groups/{id}/runs").route(web::get().to(find_for_run_group)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetryConfig;
+    use crate::custom_sql_types::ResultTypeEnum;
+    use crate::manager::log_retriever::{RunFailures, RunLogs, WorkflowFailures};
+    use crate::manager::server_router::ServerRouter;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::result::{NewResult, ResultData};
+    use crate::models::run::{NewRun, RunChangeset, RunData};
+    use crate::models::run_call::NewRunCall;
+    use crate::models::run_error::{NewRunError, RunErrorData};
+    use crate::models::run_in_group::NewRunInGroup;
+    use crate::models::run_input_source::NewRunInputSource;
+    use crate::models::run_is_rerun::NewRunIsRerun;
+    use crate::models::run_result::{NewRunResult, RunResultData};
+    use crate::models::secret::{NewSecret, SecretData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::requests::test_resource_requests::TestResourceClient;
+    use crate::unit_test_util::*;
+    use actix_web::client::Client;
+    use actix_web::web::Bytes;
+    use actix_web::{http, test, App};
+    use chrono::format::StrftimeItems;
+    use chrono::Utc;
+    use diesel::PgConnection;
+    use rand::distributions::Alphanumeric;
+    use rand::prelude::*;
+    use serde_json::json;
+    use std::fs::read_to_string;
+    use uuid::Uuid;
+
+    fn create_test_run_with_results(conn: &PgConnection) -> RunWithResultsAndErrorsData {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline 2"),
+            description: Some(String::from("Kevin made this pipeline for testing 2")),
+            created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's test template2"),
+            pipeline_id: pipeline.pipeline_id,
+            description: None,
+            test_wdl: format!("{}/test", mockito::server_url()),
+            test_wdl_dependencies: None,
+            eval_wdl: format!("{}/eval", mockito::server_url()),
+            eval_wdl_dependencies: None,
+            created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template = TemplateData::create(&conn, new_template).expect("Failed to insert test");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: Some(serde_json::from_str("{\"test\":\"test\"}").unwrap()),
+            test_option_defaults: Some(json!({"test_option":"test"})),
+            eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
+            eval_option_defaults: Some(json!({"eval_option": "test"})),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        create_test_run_with_results_and_test_id(conn, test.test_id)
+    }
+
+    fn create_test_run_with_results_and_test_id(
+        conn: &PgConnection,
+        test_id: Uuid,
+    ) -> RunWithResultsAndErrorsData {
+        let test_run = create_test_run_with_test_id(conn, test_id);
+
+        let test_results = create_test_results_with_run_id(&conn, &test_run.run_id);
+
+        let test_errors = insert_test_run_errors_with_run_id(&conn, test_run.run_id);
+
+        RunWithResultsAndErrorsData {
+            run_id: test_run.run_id,
+            test_id: test_run.test_id,
+            name: test_run.name,
+            status: test_run.status,
+            test_input: test_run.test_input,
+            test_options: test_run.test_options,
+            eval_input: test_run.eval_input,
+            eval_options: test_run.eval_options,
+            test_cromwell_job_id: test_run.test_cromwell_job_id,
+            eval_cromwell_job_id: test_run.eval_cromwell_job_id,
+            created_at: test_run.created_at,
+            created_by: test_run.created_by,
+            finished_at: test_run.finished_at,
+            results: Some(test_results),
+            errors: Some(test_errors),
+            cromwell_server: None,
+        }
+    }
+
+    fn insert_test_run_errors_with_run_id(conn: &PgConnection, id: Uuid) -> Value {
+        let new_run_error = NewRunError {
+            run_id: id,
+            error: String::from("A bad thing happened, but not too bad"),
+        };
+
+        let new_run_error = RunErrorData::create(conn, new_run_error).unwrap();
+
+        let another_run_error = NewRunError {
+            run_id: id,
+            error: String::from("You botched it"),
+        };
+
+        let another_run_error = RunErrorData::create(conn, another_run_error).unwrap();
+
+        let fmt = StrftimeItems::new("%Y-%m-%d %H:%M:%S%.3f");
+
+        return json!([
+            format!(
+                "{}: {}",
+                new_run_error
+                    .created_at
+                    .format_with_items(fmt.clone())
+                    .to_string(),
+                new_run_error.error
+            ),
+            format!(
+                "{}: {}",
+                another_run_error
+                    .created_at
+                    .format_with_items(fmt.clone())
+                    .to_string(),
+                another_run_error.error
+            )
+        ]);
+    }
+
+    fn create_test_results_with_run_id(conn: &PgConnection, id: &Uuid) -> Value {
+        let new_result = NewResult {
+            name: String::from("Name1"),
+            result_type: ResultTypeEnum::Numeric,
+            description: Some(String::from("Description4")),
+            created_by: Some(String::from("Test@example.com")),
+        };
+
+        let new_result =
+            ResultData::create(conn, new_result).expect("Failed inserting test result");
+
+        let rand_result: u64 = rand::random();
+
+        let new_run_result = NewRunResult {
+            run_id: id.clone(),
+            result_id: new_result.result_id.clone(),
+            value: rand_result.to_string(),
+        };
+
+        let new_run_result =
+            RunResultData::create(conn, new_run_result).expect("Failed inserting test run_result");
+
+        let new_result2 = NewResult {
+            name: String::from("Name2"),
+            result_type: ResultTypeEnum::File,
+            description: Some(String::from("Description3")),
+            created_by: Some(String::from("Test@example.com")),
+        };
+
+        let new_result2 =
+            ResultData::create(conn, new_result2).expect("Failed inserting test result");
+
+        let mut rng = thread_rng();
+        let rand_result: String = std::iter::repeat(())
+            .map(|()| rng.sample(Alphanumeric))
+            .take(7)
+            .collect();
+
+        let new_run_result2 = NewRunResult {
+            run_id: id.clone(),
+            result_id: new_result2.result_id.clone(),
+            value: String::from(rand_result),
+        };
+
+        let new_run_result2 =
+            RunResultData::create(conn, new_run_result2).expect("Failed inserting test run_result");
+
+        return json!({
+            new_result.name: new_run_result.value,
+            new_result2.name: new_run_result2.value
+        });
+    }
+
+    fn create_run_with_test_and_template(
+        conn: &PgConnection,
+    ) -> (TemplateData, TestData, RunWithResultsAndErrorsData) {
+        let new_template = create_test_template(conn);
+        let new_test = create_test_test_with_template_id(conn, new_template.template_id);
+        let new_run = create_test_run_with_results_and_test_id(conn, new_test.test_id);
+
+        (new_template, new_test, new_run)
+    }
+
+    fn create_test_template(conn: &PgConnection) -> TemplateData {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's test template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: None,
+            test_wdl: format!("{}/test", mockito::server_url()),
+            test_wdl_dependencies: None,
+            eval_wdl: format!("{}/eval", mockito::server_url()),
+            eval_wdl_dependencies: None,
+            created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        TemplateData::create(&conn, new_template).expect("Failed to insert test")
+    }
+
+    fn create_test_test_with_template_id(conn: &PgConnection, id: Uuid) -> TestData {
+        let new_test = NewTest {
+            name: String::from("Kevin's test test"),
+            template_id: id,
+            description: None,
+            test_input_defaults: Some(json!({"in_greeting": "Yo"})),
+            test_option_defaults: None,
+            eval_input_defaults: Some(json!({"in_output_filename": "greeting.txt"})),
+            eval_option_defaults: Some(json!({"option": "true"})),
+            created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        TestData::create(&conn, new_test).expect("Failed to insert test")
+    }
+
+    fn create_test_run_with_test_id(conn: &PgConnection, id: Uuid) -> RunData {
+        let new_run = NewRun {
+            name: String::from("Kevin's Run"),
+            test_id: id,
+            status: RunStatusEnum::TestSubmitted,
+            test_input: json!({"in_greeted": "Cool Person", "in_greeting": "Yo"}),
+            test_options: Some(json!({"test": "test"})),
+            eval_input: json!({"in_output_filename": "test_greeting.txt", "in_output_filename": "greeting.txt"}),
+            eval_options: None,
+            test_cromwell_job_id: Some(String::from("123456789")),
+            eval_cromwell_job_id: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: None,
+        };
+
+        RunData::create(conn, new_run).expect("Failed inserting test run")
+    }
+
+    fn create_test_run_with_nonfailed_state(conn: &PgConnection) -> RunData {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's test template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: None,
+            test_wdl: format!("{}/test", mockito::server_url()),
+            test_wdl_dependencies: None,
+            eval_wdl: format!("{}/eval", mockito::server_url()),
+            eval_wdl_dependencies: None,
+            created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template = TemplateData::create(&conn, new_template).expect("Failed to insert test");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's test test"),
+            template_id: template.template_id,
+            description: None,
+            test_input_defaults: Some(json!({"in_greeting": "Yo"})),
+            test_option_defaults: Some(json!({"an_option": "true"})),
+            eval_input_defaults: Some(json!({"in_output_filename": "greeting.txt"})),
+            eval_option_defaults: Some(json!({"a_different_option": "false"})),
+            created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(&conn, new_test).expect("Failed to insert test");
+
+        let new_run = NewRun {
+            name: String::from("Kevin's Run"),
+            test_id: test.test_id,
+            status: RunStatusEnum::TestSubmitted,
+            test_input: json!({"in_greeted": "Cool Person", "in_greeting": "Yo"}),
+            test_options: Some(json!({"an_option": "true"})),
+            eval_input: json!({"in_output_filename": "test_greeting.txt", "in_output_filename": "greeting.txt"}),
+            eval_options: Some(json!({"a_different_option": "false"})),
+            test_cromwell_job_id: Some(String::from("123456789")),
+            eval_cromwell_job_id: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: None,
+        };
+
+        RunData::create(conn, new_run).expect("Failed inserting test run")
+    }
+
+    fn create_test_run_with_failed_state(conn: &PgConnection) -> RunData {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's test template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: None,
+            test_wdl: format!("{}/test", mockito::server_url()),
+            test_wdl_dependencies: None,
+            eval_wdl: format!("{}/eval", mockito::server_url()),
+            eval_wdl_dependencies: None,
+            created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(&conn, new_template).expect("Failed to insert test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's test test"),
+            template_id: template.template_id,
+            description: None,
+            test_input_defaults: Some(json!({"in_greeting": "Yo"})),
+            test_option_defaults: Some(json!({"option": "value"})),
+            eval_input_defaults: Some(json!({"in_output_filename": "greeting.txt"})),
+            eval_option_defaults: Some(json!({"option":"different_value"})),
+            created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(&conn, new_test).expect("Failed to insert test");
+
+        let new_run = NewRun {
+            name: String::from("Kevin's Run"),
+            test_id: test.test_id,
+            status: RunStatusEnum::TestFailed,
+            test_input: json!({"in_greeted": "Cool Person", "in_greeting": "Yo"}),
+            test_options: Some(json!({"in_greeting": "Yo"})),
+            eval_input: json!({"in_output_filename": "test_greeting.txt", "in_output_filename": "greeting.txt"}),
+            eval_options: Some(json!({"option":"different_value"})),
+            test_cromwell_job_id: Some(String::from("123456789")),
+            eval_cromwell_job_id: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: None,
+        };
+
+        RunData::create(conn, new_run).expect("Failed inserting test run")
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_success() {
+        let pool = get_test_db_pool();
+
+        let new_run = create_test_run_with_results(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/runs/{}", new_run.run_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_run: RunWithResultsAndErrorsData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_run, new_run);
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        create_test_run_with_results(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/runs/{}", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.detail, "No run found with the specified ID");
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_failure_bad_uuid() {
+        let pool = get_test_db_pool();
+
+        create_test_run_with_results(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get().uri("/runs/123456789").to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "ID formatted incorrectly");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
+    }
+
+    #[actix_rt::test]
+    async fn find_for_test_success() {
+        let pool = get_test_db_pool();
+
+        let new_run = create_test_run_with_results(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/tests/{}/runs", new_run.test_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_runs: Vec<RunWithResultsAndErrorsData> = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_runs.len(), 1);
+        assert_eq!(test_runs[0], new_run);
+    }
+
+    #[actix_rt::test]
+    async fn find_for_test_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        create_test_run_with_results(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/tests/{}/runs", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No runs found with the specified parameters"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn find_for_test_failure_bad_uuid() {
+        let pool = get_test_db_pool();
+
+        create_test_run_with_results(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/tests/123456789/runs")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "ID formatted incorrectly");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
+    }
+
+    #[actix_rt::test]
+    async fn find_for_template_success() {
+        let pool = get_test_db_pool();
+
+        let (_, new_test, new_run) = create_run_with_test_and_template(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/templates/{}/runs", new_test.template_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_runs: Vec<RunWithResultsAndErrorsData> = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_runs.len(), 1);
+        assert_eq!(test_runs[0], new_run);
+    }
+
+    #[actix_rt::test]
+    async fn find_for_template_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        create_run_with_test_and_template(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/templates/{}/runs", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No runs found with the specified parameters"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn find_for_template_failure_bad_uuid() {
+        let pool = get_test_db_pool();
+
+        create_run_with_test_and_template(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/templates/123456789/runs")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "ID formatted incorrectly");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
+    }
+
+    #[actix_rt::test]
+    async fn find_for_pipeline_success() {
+        let pool = get_test_db_pool();
+
+        let (new_template, _, new_run) = create_run_with_test_and_template(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/pipelines/{}/runs", new_template.pipeline_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_runs: Vec<RunWithResultsAndErrorsData> = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_runs.len(), 1);
+        assert_eq!(test_runs[0], new_run);
+    }
+
+    #[actix_rt::test]
+    async fn find_for_pipeline_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        create_run_with_test_and_template(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/pipelines/{}/runs", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No runs found with the specified parameters"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn find_for_pipeline_failure_bad_uuid() {
+        let pool = get_test_db_pool();
+
+        create_run_with_test_and_template(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/pipelines/123456789/runs")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "ID formatted incorrectly");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
+    }
+
+    #[actix_rt::test]
+    async fn find_for_run_group_success() {
+        let pool = get_test_db_pool();
+
+        let (_, _, new_run) = create_run_with_test_and_template(&pool.get().unwrap());
+        let run_group = RunGroupData::create(
+            &pool.get().unwrap(),
+            NewRunGroup {
+                name: String::from("Kevin's Run Group"),
+                description: None,
+                created_by: None,
+            },
+        )
+        .expect("Failed to insert test run group");
+        RunInGroupData::create(
+            &pool.get().unwrap(),
+            NewRunInGroup {
+                run_id: new_run.run_id,
+                run_group_id: run_group.run_group_id,
+            },
+        )
+        .expect("Failed to insert test run_in_group");
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/run_groups/{}/runs", run_group.run_group_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_runs: Vec<RunWithResultsAndErrorsData> = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_runs.len(), 1);
+        assert_eq!(test_runs[0], new_run);
+    }
+
+    #[actix_rt::test]
+    async fn find_for_run_group_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        create_run_with_test_and_template(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/run_groups/{}/runs", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No runs found with the specified parameters"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn run_test() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let test_template = create_test_template(&pool.get().unwrap());
+        let test_test =
+            create_test_test_with_template_id(&pool.get().unwrap(), test_template.template_id);
+
+        let test_input = json!({"in_greeted": "Cool Person"});
+        let test_options = Some(json!({"option": "Value"}));
+        let eval_input = json!({"in_output_filename": "test_greeting.txt"});
+        let eval_options = Some(json!({"other_option": "true"}));
+        let new_run = NewRunIncomplete {
+            name: None,
+            test_input: Some(test_input.clone()),
+            test_options: test_options.clone(),
+            eval_input: Some(eval_input.clone()),
+            eval_options: eval_options.clone(),
+            created_by: None,
+        };
+
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/run/test_wdl.wdl").unwrap())
+            .expect(1)
+            .create();
+
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .create();
+
+        // Start up app for testing
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        // Make request
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/tests/{}/runs?skip_input_validation=true",
+                test_test.test_id
+            ))
+            .set_json(&new_run)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        cromwell_mock.assert();
+
+        let result = test::read_body(resp).await;
+        let test_run: RunData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_run.test_id, test_test.test_id);
+        assert_eq!(test_run.status, RunStatusEnum::TestSubmitted);
+        assert_eq!(
+            test_run.test_cromwell_job_id,
+            Some("53709600-d114-4194-a7f7-9e41211ca2ce".to_string())
+        );
+        let mut test_input_to_compare = json!({});
+        json_patch::merge(
+            &mut test_input_to_compare,
+            &test_test.test_input_defaults.unwrap(),
+        );
+        json_patch::merge(&mut test_input_to_compare, &test_input);
+        let mut eval_input_to_compare = json!({});
+        json_patch::merge(
+            &mut eval_input_to_compare,
+            &test_test.eval_input_defaults.unwrap(),
+        );
+        json_patch::merge(&mut eval_input_to_compare, &eval_input);
+        let mut eval_options_to_compare = json!({});
+        json_patch::merge(
+            &mut eval_options_to_compare,
+            &test_test.eval_option_defaults.unwrap(),
+        );
+        json_patch::merge(&mut eval_options_to_compare, &eval_options.unwrap());
+        assert_eq!(test_run.test_input, test_input_to_compare);
+        assert_eq!(test_run.test_options, test_options);
+        assert_eq!(test_run.eval_input, eval_input_to_compare);
+        assert_eq!(test_run.eval_options.unwrap(), eval_options_to_compare);
+    }
+
+    #[actix_rt::test]
+    async fn run_test_failure_taken_name() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let test_template = create_test_template(&pool.get().unwrap());
+        let test_test =
+            create_test_test_with_template_id(&pool.get().unwrap(), test_template.template_id);
+        let test_run = create_test_run_with_test_id(&pool.get().unwrap(), test_test.test_id);
+
+        let test_input = json!({"in_greeted": "Cool Person"});
+        let test_options = Some(json!({"option": "Value"}));
+        let eval_input = json!({"in_output_filename": "test_greeting.txt"});
+        let eval_options = Some(json!({"other_option": "true"}));
+        let new_run = NewRunIncomplete {
+            name: Some(test_run.name.clone()),
+            test_input: Some(test_input.clone()),
+            test_options: test_options.clone(),
+            eval_input: Some(eval_input.clone()),
+            eval_options: eval_options.clone(),
+            created_by: None,
+        };
+
+        // Start up app for testing
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        // Make request
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/tests/{}/runs?skip_input_validation=true",
+                test_test.test_id
+            ))
+            .set_json(&new_run)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let test_error: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(
+            test_error,
+            ErrorBody {
+                title: "Run with specified name already exists".to_string(),
+                status: 400,
+                detail: "If a custom run name is specified, it must be unique.".to_string(),
+            }
+        );
+    }
+
+    #[actix_rt::test]
+    async fn run_test_failure_invalid_inputs() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let test_template = create_test_template(&pool.get().unwrap());
+        let test_test =
+            create_test_test_with_template_id(&pool.get().unwrap(), test_template.template_id);
+
+        let new_run = NewRunIncomplete {
+            name: None,
+            test_input: Some(json!({"greeting_workflow.not_an_input": "Cool Person"})),
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            created_by: None,
+        };
+
+        // Define mockito mappings for wdls
+        let test_wdl_mock = mockito::mock("GET", "/test")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/run/test_wdl.wdl").unwrap())
+            .create();
+        let eval_wdl_mock = mockito::mock("GET", "/eval")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/run/eval_wdl.wdl").unwrap())
+            .create();
+
+        // Start up app for testing
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        // Make request
+        let req = test::TestRequest::post()
+            .uri(&format!("/tests/{}/runs", test_test.test_id))
+            .set_json(&new_run)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        test_wdl_mock.assert();
+        eval_wdl_mock.assert();
+
+        let result = test::read_body(resp).await;
+        let test_error: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_error.title, "Invalid inputs");
+        assert_eq!(test_error.status, 400);
+        assert!(test_error
+            .detail
+            .contains("test WDL has no input named greeting_workflow.not_an_input"));
+    }
+
+    #[actix_rt::test]
+    async fn run_batch_from_json_success_with_row_failure() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let test_template = create_test_template(&pool.get().unwrap());
+        let test_test =
+            create_test_test_with_template_id(&pool.get().unwrap(), test_template.template_id);
+        let test_run = create_test_run_with_test_id(&pool.get().unwrap(), test_test.test_id);
+
+        let new_batch = NewRunBatchIncomplete {
+            name: Some(String::from("Kevin's batch")),
+            description: Some(String::from("Kevin made this batch for testing")),
+            runs: vec![
+                NewRunIncomplete {
+                    name: Some(String::from("first_batch_run")),
+                    test_input: Some(json!({"in_greeted": "Cool Person"})),
+                    test_options: None,
+                    eval_input: None,
+                    eval_options: None,
+                    created_by: None,
+                },
+                NewRunIncomplete {
+                    name: Some(test_run.name.clone()),
+                    test_input: Some(json!({"in_greeted": "Other Person"})),
+                    test_options: None,
+                    eval_input: None,
+                    eval_options: None,
+                    created_by: None,
+                },
+            ],
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/run/test_wdl.wdl").unwrap())
+            .expect(1)
+            .create();
+
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .expect(1)
+            .create();
+
+        // Start up app for testing
+        let mut app = test::init_service(
+            App::new()
+                .data(pool.clone())
+                .data(test_runner)
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        // Make request
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/tests/{}/runs/batch?skip_input_validation=true",
+                test_test.test_id
+            ))
+            .set_json(&new_batch)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        let result = test::read_body(resp).await;
+        let batch_result: RunBatchResult = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(batch_result.run_group.name, "Kevin's batch");
+        assert_eq!(
+            batch_result.run_group.created_by,
+            Some(String::from("Kevin@example.com"))
+        );
+        assert_eq!(batch_result.runs.len(), 2);
+
+        let first_result = &batch_result.runs[0];
+        assert_eq!(first_result.index, 0);
+        assert!(first_result.error.is_none());
+        let first_run = first_result.run.as_ref().unwrap();
+        assert_eq!(first_run.name, "first_batch_run");
+        assert_eq!(first_run.status, RunStatusEnum::TestSubmitted);
+        assert_eq!(
+            first_run.test_input,
+            json!({"in_greeting": "Yo", "in_greeted": "Cool Person"})
+        );
+        assert_eq!(
+            first_run.created_by,
+            Some(String::from("Kevin@example.com"))
+        );
+
+        let second_result = &batch_result.runs[1];
+        assert_eq!(second_result.index, 1);
+        assert!(second_result.run.is_none());
+        assert_eq!(
+            second_result.error,
+            Some(ErrorBody {
+                title: "Run with specified name already exists".to_string(),
+                status: 400,
+                detail: "If a custom run name is specified, it must be unique.".to_string(),
+            })
+        );
+
+        // Check that only the successful run was added to the group
+        let runs_in_group = RunInGroupData::find_by_run_group_id(
+            &pool.get().unwrap(),
+            batch_result.run_group.run_group_id,
+        )
+        .unwrap();
+        assert_eq!(runs_in_group.len(), 1);
+        assert_eq!(runs_in_group[0].run_id, first_run.run_id);
+    }
+
+    #[actix_rt::test]
+    async fn run_batch_from_json_success_with_start_failure() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let test_template = create_test_template(&pool.get().unwrap());
+        let test_test =
+            create_test_test_with_template_id(&pool.get().unwrap(), test_template.template_id);
+
+        let new_batch = NewRunBatchIncomplete {
+            name: Some(String::from("Kevin's batch")),
+            description: None,
+            runs: vec![NewRunIncomplete {
+                name: Some(String::from("failed_batch_run")),
+                test_input: Some(json!({"in_greeted": "Cool Person"})),
+                test_options: None,
+                eval_input: None,
+                eval_options: None,
+                created_by: None,
+            }],
+            created_by: None,
+        };
+
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/run/test_wdl.wdl").unwrap())
+            .expect(1)
+            .create();
+
+        // Define mockito mapping for cromwell response, which rejects the submission
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(400)
+            .with_header("content_type", "application/json")
+            .with_body(json!({"status": "fail", "message": "Bad workflow"}).to_string())
+            .expect(1)
+            .create();
+
+        // Start up app for testing
+        let mut app = test::init_service(
+            App::new()
+                .data(pool.clone())
+                .data(test_runner)
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        // Make request
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/tests/{}/runs/batch?skip_input_validation=true",
+                test_test.test_id
+            ))
+            .set_json(&new_batch)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        wdl_mock.assert();
+        cromwell_mock.assert();
+
+        let result = test::read_body(resp).await;
+        let batch_result: RunBatchResult = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(batch_result.runs.len(), 1);
+
+        // The run was created before it failed, so it should be reported with the error
+        let failed_result = &batch_result.runs[0];
+        assert_eq!(failed_result.index, 0);
+        assert!(failed_result.error.is_some());
+        let failed_run = failed_result.run.as_ref().unwrap();
+        assert_eq!(failed_run.name, "failed_batch_run");
+        assert_eq!(failed_run.status, RunStatusEnum::CarrotFailed);
+
+        // Check that the failed run was added to the group
+        let runs_in_group = RunInGroupData::find_by_run_group_id(
+            &pool.get().unwrap(),
+            batch_result.run_group.run_group_id,
+        )
+        .unwrap();
+        assert_eq!(runs_in_group.len(), 1);
+        assert_eq!(runs_in_group[0].run_id, failed_run.run_id);
+    }
+
+    #[actix_rt::test]
+    async fn run_batch_from_multipart_success() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let test_template = create_test_template(&pool.get().unwrap());
+        let test_test =
+            create_test_test_with_template_id(&pool.get().unwrap(), test_template.template_id);
 
-    fn create_test_run_with_results(conn: &PgConnection) -> RunWithResultsAndErrorsData {
-        let new_pipeline = NewPipeline {
-            name: String::from("Kevin's Pipeline 2"),
-            description: Some(String::from("Kevin made this pipeline for testing 2")),
-            created_by: Some(String::from("Kevin2@example.com")),
-        };
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/run/test_wdl.wdl").unwrap())
+            .expect(2)
+            .create();
 
-        let pipeline =
-            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .expect(2)
+            .create();
 
-        let new_template = NewTemplate {
-            name: String::from("Kevin's test template2"),
-            pipeline_id: pipeline.pipeline_id,
-            description: None,
-            test_wdl: format!("{}/test", mockito::server_url()),
-            test_wdl_dependencies: None,
-            eval_wdl: format!("{}/eval", mockito::server_url()),
-            eval_wdl_dependencies: None,
-            created_by: None,
-        };
+        // Start up app for testing
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
 
-        let template = TemplateData::create(&conn, new_template).expect("Failed to insert test");
+        // Load the multipart body we'll send in the request
+        let multipart_body = read_to_string("testdata/routes/run/valid_batch_multipart.txt")
+            .unwrap()
+            // Multipart needs carriage returns
+            .replace("\n", "\r\n");
+        let multipart_body_bytes = Bytes::from(multipart_body);
 
-        let new_test = NewTest {
-            name: String::from("Kevin's Test"),
-            template_id: template.template_id,
-            description: Some(String::from("Kevin made this test for testing")),
-            test_input_defaults: Some(serde_json::from_str("{\"test\":\"test\"}").unwrap()),
-            test_option_defaults: Some(json!({"test_option":"test"})),
-            eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
-            eval_option_defaults: Some(json!({"eval_option": "test"})),
-            created_by: Some(String::from("Kevin@example.com")),
-        };
+        let content_length = multipart_body_bytes.len();
 
-        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+        let req = test::TestRequest::post()
+            .uri(&format!("/tests/{}/runs/batch?skip_input_validation=true", test_test.test_id))
+            .header("Content-Type", "multipart/form-data; boundary=\"---------------------------974767299852498929531610575\"")
+            .header("Content-Length", content_length)
+            .set_payload(multipart_body_bytes)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
 
-        create_test_run_with_results_and_test_id(conn, test.test_id)
-    }
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        wdl_mock.assert();
+        cromwell_mock.assert();
 
-    fn create_test_run_with_results_and_test_id(
-        conn: &PgConnection,
-        test_id: Uuid,
-    ) -> RunWithResultsAndErrorsData {
-        let test_run = create_test_run_with_test_id(conn, test_id);
+        let result = test::read_body(resp).await;
+        let batch_result: RunBatchResult = serde_json::from_slice(&result).unwrap();
 
-        let test_results = create_test_results_with_run_id(&conn, &test_run.run_id);
+        assert_eq!(batch_result.run_group.name, "Kevin's batch");
+        assert_eq!(batch_result.runs.len(), 2);
 
-        let test_errors = insert_test_run_errors_with_run_id(&conn, test_run.run_id);
+        let first_run = batch_result.runs[0].run.as_ref().unwrap();
+        assert_eq!(first_run.name, "first_batch_run");
+        assert_eq!(
+            first_run.test_input,
+            json!({"in_greeting": "Yo", "in_greeted": "Cool Person"})
+        );
+        assert_eq!(first_run.test_options, Some(json!({"option": "Value"})));
+        assert_eq!(
+            first_run.eval_input,
+            json!({"in_output_filename": "test_greeting.txt"})
+        );
+        assert_eq!(
+            first_run.created_by,
+            Some(String::from("Kevin@example.com"))
+        );
 
-        RunWithResultsAndErrorsData {
-            run_id: test_run.run_id,
-            test_id: test_run.test_id,
-            name: test_run.name,
-            status: test_run.status,
-            test_input: test_run.test_input,
-            test_options: test_run.test_options,
-            eval_input: test_run.eval_input,
-            eval_options: test_run.eval_options,
-            test_cromwell_job_id: test_run.test_cromwell_job_id,
-            eval_cromwell_job_id: test_run.eval_cromwell_job_id,
-            created_at: test_run.created_at,
-            created_by: test_run.created_by,
-            finished_at: test_run.finished_at,
-            results: Some(test_results),
-            errors: Some(test_errors),
-        }
+        let second_run = batch_result.runs[1].run.as_ref().unwrap();
+        assert_eq!(second_run.name, "second_batch_run");
+        assert_eq!(
+            second_run.test_input,
+            json!({"in_greeting": "Yo", "in_greeted": "Other Person"})
+        );
+        assert_eq!(
+            second_run.eval_input,
+            json!({"in_output_filename": "greeting.txt"})
+        );
     }
 
-    fn insert_test_run_errors_with_run_id(conn: &PgConnection, id: Uuid) -> Value {
-        let new_run_error = NewRunError {
-            run_id: id,
-            error: String::from("A bad thing happened, but not too bad"),
+    #[actix_rt::test]
+    async fn run_batch_failure_no_test() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let new_batch = NewRunBatchIncomplete {
+            name: None,
+            description: None,
+            runs: vec![NewRunIncomplete {
+                name: None,
+                test_input: Some(json!({"in_greeted": "Cool Person"})),
+                test_options: None,
+                eval_input: None,
+                eval_options: None,
+                created_by: None,
+            }],
+            created_by: None,
         };
 
-        let new_run_error = RunErrorData::create(conn, new_run_error).unwrap();
+        // Start up app for testing
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
 
-        let another_run_error = NewRunError {
-            run_id: id,
-            error: String::from("You botched it"),
+        // Make request
+        let req = test::TestRequest::post()
+            .uri(&format!("/tests/{}/runs/batch", Uuid::new_v4()))
+            .set_json(&new_batch)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No test found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.detail, "No test found with the specified ID");
+    }
+
+    #[actix_rt::test]
+    async fn run_batch_failure_no_runs() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let test_template = create_test_template(&pool.get().unwrap());
+        let test_test =
+            create_test_test_with_template_id(&pool.get().unwrap(), test_template.template_id);
+
+        let new_batch = NewRunBatchIncomplete {
+            name: None,
+            description: None,
+            runs: Vec::new(),
+            created_by: None,
         };
 
-        let another_run_error = RunErrorData::create(conn, another_run_error).unwrap();
+        // Start up app for testing
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
 
-        let fmt = StrftimeItems::new("%Y-%m-%d %H:%M:%S%.3f");
+        // Make request
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/tests/{}/runs/batch?skip_input_validation=true",
+                test_test.test_id
+            ))
+            .set_json(&new_batch)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
 
-        return json!([
-            format!(
-                "{}: {}",
-                new_run_error
-                    .created_at
-                    .format_with_items(fmt.clone())
-                    .to_string(),
-                new_run_error.error
-            ),
-            format!(
-                "{}: {}",
-                another_run_error
-                    .created_at
-                    .format_with_items(fmt.clone())
-                    .to_string(),
-                another_run_error.error
-            )
-        ]);
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No runs specified");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "A batch must contain at least one run");
     }
 
-    fn create_test_results_with_run_id(conn: &PgConnection, id: &Uuid) -> Value {
-        let new_result = NewResult {
-            name: String::from("Name1"),
-            result_type: ResultTypeEnum::Numeric,
-            description: Some(String::from("Description4")),
-            created_by: Some(String::from("Test@example.com")),
+    #[actix_rt::test]
+    async fn run_batch_failure_bad_uuid() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let new_batch = NewRunBatchIncomplete {
+            name: None,
+            description: None,
+            runs: Vec::new(),
+            created_by: None,
         };
 
-        let new_result =
-            ResultData::create(conn, new_result).expect("Failed inserting test result");
+        // Start up app for testing
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        // Make request
+        let req = test::TestRequest::post()
+            .uri("/tests/123456789/runs/batch")
+            .set_json(&new_batch)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
 
-        let rand_result: u64 = rand::random();
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
 
-        let new_run_result = NewRunResult {
-            run_id: id.clone(),
-            result_id: new_result.result_id.clone(),
-            value: rand_result.to_string(),
-        };
+        assert_eq!(error_body.title, "ID formatted incorrectly");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
+    }
 
-        let new_run_result =
-            RunResultData::create(conn, new_run_result).expect("Failed inserting test run_result");
+    #[actix_rt::test]
+    async fn delete_success() {
+        let pool = get_test_db_pool();
 
-        let new_result2 = NewResult {
-            name: String::from("Name2"),
-            result_type: ResultTypeEnum::File,
-            description: Some(String::from("Description3")),
-            created_by: Some(String::from("Test@example.com")),
-        };
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
 
-        let new_result2 =
-            ResultData::create(conn, new_result2).expect("Failed inserting test result");
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
 
-        let mut rng = thread_rng();
-        let rand_result: String = std::iter::repeat(())
-            .map(|()| rng.sample(Alphanumeric))
-            .take(7)
-            .collect();
+        let req = test::TestRequest::delete()
+            .uri(&format!("/runs/{}", run.run_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
 
-        let new_run_result2 = NewRunResult {
-            run_id: id.clone(),
-            result_id: new_result2.result_id.clone(),
-            value: String::from(rand_result),
-        };
+        assert_eq!(resp.status(), http::StatusCode::OK);
 
-        let new_run_result2 =
-            RunResultData::create(conn, new_run_result2).expect("Failed inserting test run_result");
+        let result = test::read_body(resp).await;
+        let message: Value = serde_json::from_slice(&result).unwrap();
 
-        return json!({
-            new_result.name: new_run_result.value,
-            new_result2.name: new_run_result2.value
+        let expected_message = json!({
+            "message": "Successfully deleted 1 row"
         });
+
+        assert_eq!(message, expected_message)
     }
 
-    fn create_run_with_test_and_template(
-        conn: &PgConnection,
-    ) -> (TemplateData, TestData, RunWithResultsAndErrorsData) {
-        let new_template = create_test_template(conn);
-        let new_test = create_test_test_with_template_id(conn, new_template.template_id);
-        let new_run = create_test_run_with_results_and_test_id(conn, new_test.test_id);
+    #[actix_rt::test]
+    async fn delete_failure_no_run() {
+        let pool = get_test_db_pool();
 
-        (new_template, new_test, new_run)
-    }
+        let _run = create_test_run_with_failed_state(&pool.get().unwrap());
 
-    fn create_test_template(conn: &PgConnection) -> TemplateData {
-        let new_pipeline = NewPipeline {
-            name: String::from("Kevin's Pipeline"),
-            description: Some(String::from("Kevin made this pipeline for testing")),
-            created_by: Some(String::from("Kevin@example.com")),
-        };
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
 
-        let pipeline =
-            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+        let req = test::TestRequest::delete()
+            .uri(&format!("/runs/{}", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
 
-        let new_template = NewTemplate {
-            name: String::from("Kevin's test template"),
-            pipeline_id: pipeline.pipeline_id,
-            description: None,
-            test_wdl: format!("{}/test", mockito::server_url()),
-            test_wdl_dependencies: None,
-            eval_wdl: format!("{}/eval", mockito::server_url()),
-            eval_wdl_dependencies: None,
-            created_by: None,
-        };
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
 
-        TemplateData::create(&conn, new_template).expect("Failed to insert test")
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.detail, "No run found for the specified id");
     }
 
-    fn create_test_test_with_template_id(conn: &PgConnection, id: Uuid) -> TestData {
-        let new_test = NewTest {
-            name: String::from("Kevin's test test"),
-            template_id: id,
-            description: None,
-            test_input_defaults: Some(json!({"in_greeting": "Yo"})),
-            test_option_defaults: None,
-            eval_input_defaults: Some(json!({"in_output_filename": "greeting.txt"})),
-            eval_option_defaults: Some(json!({"option": "true"})),
-            created_by: None,
-        };
+    #[actix_rt::test]
+    async fn delete_failure_not_allowed() {
+        let pool = get_test_db_pool();
 
-        TestData::create(&conn, new_test).expect("Failed to insert test")
-    }
+        let run = create_test_run_with_nonfailed_state(&pool.get().unwrap());
 
-    fn create_test_run_with_test_id(conn: &PgConnection, id: Uuid) -> RunData {
-        let new_run = NewRun {
-            name: String::from("Kevin's Run"),
-            test_id: id,
-            status: RunStatusEnum::TestSubmitted,
-            test_input: json!({"in_greeted": "Cool Person", "in_greeting": "Yo"}),
-            test_options: Some(json!({"test": "test"})),
-            eval_input: json!({"in_output_filename": "test_greeting.txt", "in_output_filename": "greeting.txt"}),
-            eval_options: None,
-            test_cromwell_job_id: Some(String::from("123456789")),
-            eval_cromwell_job_id: None,
-            created_by: Some(String::from("Kevin@example.com")),
-            finished_at: None,
-        };
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
 
-        RunData::create(conn, new_run).expect("Failed inserting test run")
-    }
+        let req = test::TestRequest::delete()
+            .uri(&format!("/runs/{}", run.run_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
 
-    fn create_test_run_with_nonfailed_state(conn: &PgConnection) -> RunData {
-        let new_pipeline = NewPipeline {
-            name: String::from("Kevin's Pipeline"),
-            description: Some(String::from("Kevin made this pipeline for testing")),
-            created_by: Some(String::from("Kevin@example.com")),
-        };
+        assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
 
-        let pipeline =
-            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
 
-        let new_template = NewTemplate {
-            name: String::from("Kevin's test template"),
-            pipeline_id: pipeline.pipeline_id,
-            description: None,
-            test_wdl: format!("{}/test", mockito::server_url()),
-            test_wdl_dependencies: None,
-            eval_wdl: format!("{}/eval", mockito::server_url()),
-            eval_wdl_dependencies: None,
-            created_by: None,
-        };
+        assert_eq!(error_body.title, "Cannot delete");
+        assert_eq!(error_body.status, 403);
+        assert_eq!(
+            error_body.detail,
+            "Cannot delete a run if it has a non-failed status"
+        );
+    }
 
-        let template = TemplateData::create(&conn, new_template).expect("Failed to insert test");
+    #[actix_rt::test]
+    async fn delete_failure_bad_uuid() {
+        let pool = get_test_db_pool();
 
-        let new_test = NewTest {
-            name: String::from("Kevin's test test"),
-            template_id: template.template_id,
-            description: None,
-            test_input_defaults: Some(json!({"in_greeting": "Yo"})),
-            test_option_defaults: Some(json!({"an_option": "true"})),
-            eval_input_defaults: Some(json!({"in_output_filename": "greeting.txt"})),
-            eval_option_defaults: Some(json!({"a_different_option": "false"})),
-            created_by: None,
-        };
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
 
-        let test = TestData::create(&conn, new_test).expect("Failed to insert test");
+        let req = test::TestRequest::delete()
+            .uri("/runs/123456789")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
 
-        let new_run = NewRun {
-            name: String::from("Kevin's Run"),
-            test_id: test.test_id,
-            status: RunStatusEnum::TestSubmitted,
-            test_input: json!({"in_greeted": "Cool Person", "in_greeting": "Yo"}),
-            test_options: Some(json!({"an_option": "true"})),
-            eval_input: json!({"in_output_filename": "test_greeting.txt", "in_output_filename": "greeting.txt"}),
-            eval_options: Some(json!({"a_different_option": "false"})),
-            test_cromwell_job_id: Some(String::from("123456789")),
-            eval_cromwell_job_id: None,
-            created_by: Some(String::from("Kevin@example.com")),
-            finished_at: None,
-        };
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
 
-        RunData::create(conn, new_run).expect("Failed inserting test run")
-    }
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
 
-    fn create_test_run_with_failed_state(conn: &PgConnection) -> RunData {
-        let new_pipeline = NewPipeline {
-            name: String::from("Kevin's Pipeline"),
-            description: Some(String::from("Kevin made this pipeline for testing")),
-            created_by: Some(String::from("Kevin@example.com")),
-        };
+        assert_eq!(error_body.title, "ID formatted incorrectly");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
+    }
 
-        let pipeline =
-            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+    #[actix_rt::test]
+    async fn abort_success() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
 
-        let new_template = NewTemplate {
-            name: String::from("Kevin's test template"),
-            pipeline_id: pipeline.pipeline_id,
-            description: None,
-            test_wdl: format!("{}/test", mockito::server_url()),
-            test_wdl_dependencies: None,
-            eval_wdl: format!("{}/eval", mockito::server_url()),
-            eval_wdl_dependencies: None,
-            created_by: None,
-        };
+        let run = create_test_run_with_nonfailed_state(&pool.get().unwrap());
 
-        let template =
-            TemplateData::create(&conn, new_template).expect("Failed to insert test template");
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "123456789",
+          "status": "Aborting"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1/123456789/abort")
+            .with_status(200)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .create();
 
-        let new_test = NewTest {
-            name: String::from("Kevin's test test"),
-            template_id: template.template_id,
-            description: None,
-            test_input_defaults: Some(json!({"in_greeting": "Yo"})),
-            test_option_defaults: Some(json!({"option": "value"})),
-            eval_input_defaults: Some(json!({"in_output_filename": "greeting.txt"})),
-            eval_option_defaults: Some(json!({"option":"different_value"})),
-            created_by: None,
-        };
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .configure(init_routes),
+        )
+        .await;
 
-        let test = TestData::create(&conn, new_test).expect("Failed to insert test");
+        let req = test::TestRequest::post()
+            .uri(&format!("/runs/{}/abort", run.run_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
 
-        let new_run = NewRun {
-            name: String::from("Kevin's Run"),
-            test_id: test.test_id,
-            status: RunStatusEnum::TestFailed,
-            test_input: json!({"in_greeted": "Cool Person", "in_greeting": "Yo"}),
-            test_options: Some(json!({"in_greeting": "Yo"})),
-            eval_input: json!({"in_output_filename": "test_greeting.txt", "in_output_filename": "greeting.txt"}),
-            eval_options: Some(json!({"option":"different_value"})),
-            test_cromwell_job_id: Some(String::from("123456789")),
-            eval_cromwell_job_id: None,
-            created_by: Some(String::from("Kevin@example.com")),
-            finished_at: None,
-        };
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        cromwell_mock.assert();
 
-        RunData::create(conn, new_run).expect("Failed inserting test run")
+        let result = test::read_body(resp).await;
+        let test_run: RunData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_run.run_id, run.run_id);
+        assert_eq!(test_run.status, RunStatusEnum::TestAborting);
     }
 
     #[actix_rt::test]
-    async fn find_by_id_success() {
+    async fn abort_failure_already_finished() {
         let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
 
-        let new_run = create_test_run_with_results(&pool.get().unwrap());
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .configure(init_routes),
+        )
+        .await;
 
-        let req = test::TestRequest::get()
-            .uri(&format!("/runs/{}", new_run.run_id))
+        let req = test::TestRequest::post()
+            .uri(&format!("/runs/{}/abort", run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
-        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
 
         let result = test::read_body(resp).await;
-        let test_run: RunWithResultsAndErrorsData = serde_json::from_slice(&result).unwrap();
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(test_run, new_run);
+        assert_eq!(error_body.title, "Cannot abort");
+        assert_eq!(error_body.status, 403);
+        assert_eq!(
+            error_body.detail,
+            "Cannot abort a run that has already finished (status: test_failed)"
+        );
     }
 
     #[actix_rt::test]
-    async fn find_by_id_failure_not_found() {
+    async fn abort_failure_no_run() {
         let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
 
-        create_test_run_with_results(&pool.get().unwrap());
-
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .configure(init_routes),
+        )
+        .await;
 
-        let req = test::TestRequest::get()
-            .uri(&format!("/runs/{}", Uuid::new_v4()))
+        let req = test::TestRequest::post()
+            .uri(&format!("/runs/{}/abort", Uuid::new_v4()))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
@@ -957,14 +3441,29 @@ mod tests {
     }
 
     #[actix_rt::test]
-    async fn find_by_id_failure_bad_uuid() {
+    async fn abort_failure_bad_uuid() {
         let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
 
-        create_test_run_with_results(&pool.get().unwrap());
-
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .configure(init_routes),
+        )
+        .await;
 
-        let req = test::TestRequest::get().uri("/runs/123456789").to_request();
+        let req = test::TestRequest::post()
+            .uri("/runs/123456789/abort")
+            .to_request();
         let resp = test::call_service(&mut app, req).await;
 
         assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
@@ -978,63 +3477,198 @@ mod tests {
     }
 
     #[actix_rt::test]
-    async fn find_for_test_success() {
+    async fn rerun_success() {
         let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
 
-        let new_run = create_test_run_with_results(&pool.get().unwrap());
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
+        let run = RunData::update(
+            &pool.get().unwrap(),
+            run.run_id,
+            RunChangeset {
+                name: None,
+                status: None,
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                finished_at: Some(Utc::now().naive_utc()),
+                cromwell_server: None,
+            },
+        )
+        .unwrap();
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        // Define mockito mapping for wdl
+        let wdl_mock = mockito::mock("GET", "/test")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/run/test_wdl.wdl").unwrap())
+            .expect(1)
+            .create();
 
-        let req = test::TestRequest::get()
-            .uri(&format!("/tests/{}/runs", new_run.test_id))
+        // Define mockito mapping for cromwell response
+        let mock_response_body = json!({
+          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
+          "status": "Submitted"
+        });
+        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
+            .with_status(201)
+            .with_header("content_type", "application/json")
+            .with_body(mock_response_body.to_string())
+            .create();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .configure(init_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/runs/{}/rerun", run.run_id))
+            .set_json(&RerunIncomplete {
+                mode: Some(RerunModeEnum::Full),
+                name: Some(String::from("Kevin's Rerun")),
+                created_by: None,
+            })
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
         assert_eq!(resp.status(), http::StatusCode::OK);
+        wdl_mock.assert();
+        cromwell_mock.assert();
 
         let result = test::read_body(resp).await;
-        let test_runs: Vec<RunWithResultsAndErrorsData> = serde_json::from_slice(&result).unwrap();
+        let test_run: RunData = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(test_runs.len(), 1);
-        assert_eq!(test_runs[0], new_run);
+        assert_eq!(test_run.name, "Kevin's Rerun");
+        assert_eq!(test_run.test_id, run.test_id);
+        assert_eq!(test_run.status, RunStatusEnum::TestSubmitted);
+        assert_eq!(test_run.test_input, run.test_input);
+        assert_eq!(test_run.eval_input, run.eval_input);
     }
 
     #[actix_rt::test]
-    async fn find_for_test_failure_not_found() {
+    async fn rerun_failure_not_finished() {
         let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
 
-        create_test_run_with_results(&pool.get().unwrap());
+        let run = create_test_run_with_nonfailed_state(&pool.get().unwrap());
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .configure(init_routes),
+        )
+        .await;
 
-        let req = test::TestRequest::get()
-            .uri(&format!("/tests/{}/runs", Uuid::new_v4()))
+        let req = test::TestRequest::post()
+            .uri(&format!("/runs/{}/rerun", run.run_id))
+            .set_json(&RerunIncomplete {
+                mode: None,
+                name: None,
+                created_by: None,
+            })
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
-        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+        assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
 
         let result = test::read_body(resp).await;
         let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(error_body.title, "No run found");
-        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.title, "Cannot rerun");
+        assert_eq!(error_body.status, 403);
         assert_eq!(
             error_body.detail,
-            "No runs found with the specified parameters"
+            "Run has not finished (status: test_submitted)"
         );
     }
 
     #[actix_rt::test]
-    async fn find_for_test_failure_bad_uuid() {
+    async fn rerun_failure_no_run() {
         let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
 
-        create_test_run_with_results(&pool.get().unwrap());
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .configure(init_routes),
+        )
+        .await;
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let req = test::TestRequest::post()
+            .uri(&format!("/runs/{}/rerun", Uuid::new_v4()))
+            .set_json(&RerunIncomplete {
+                mode: None,
+                name: None,
+                created_by: None,
+            })
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
 
-        let req = test::TestRequest::get()
-            .uri("/tests/123456789/runs")
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.detail, "No run found with the specified ID");
+    }
+
+    #[actix_rt::test]
+    async fn rerun_failure_bad_uuid() {
+        let pool = get_test_db_pool();
+        let test_runner = TestRunner::new(
+            ServerRouter::new(Client::default(), &load_default_config()),
+            TestResourceClient::new(Client::default(), None),
+            None,
+            None,
+            RetryConfig::default(),
+            None,
+            None,
+        );
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(test_runner)
+                .configure(init_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/runs/123456789/rerun")
+            .set_json(&RerunIncomplete {
+                mode: None,
+                name: None,
+                created_by: None,
+            })
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
@@ -1049,108 +3683,220 @@ mod tests {
     }
 
     #[actix_rt::test]
-    async fn find_for_template_success() {
+    async fn find_reruns_by_id_success() {
         let pool = get_test_db_pool();
 
-        let (_, new_test, new_run) = create_run_with_test_and_template(&pool.get().unwrap());
+        let source_run = create_test_run_with_failed_state(&pool.get().unwrap());
+        let rerun = RunData::create(
+            &pool.get().unwrap(),
+            NewRun {
+                name: String::from("Kevin's Rerun"),
+                test_id: source_run.test_id,
+                status: RunStatusEnum::EvalSubmitted,
+                test_input: source_run.test_input.clone(),
+                test_options: source_run.test_options.clone(),
+                eval_input: source_run.eval_input.clone(),
+                eval_options: source_run.eval_options.clone(),
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: Some(String::from("12345678910")),
+                created_by: None,
+                finished_at: None,
+            },
+        )
+        .unwrap();
+        let run_is_rerun = RunIsRerunData::create(
+            &pool.get().unwrap(),
+            NewRunIsRerun {
+                run_id: rerun.run_id,
+                source_run_id: source_run.run_id,
+                mode: RerunModeEnum::EvalOnly,
+            },
+        )
+        .unwrap();
 
         let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
 
         let req = test::TestRequest::get()
-            .uri(&format!("/templates/{}/runs", new_test.template_id))
+            .uri(&format!("/runs/{}/reruns", source_run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
         assert_eq!(resp.status(), http::StatusCode::OK);
 
         let result = test::read_body(resp).await;
-        let test_runs: Vec<RunWithResultsAndErrorsData> = serde_json::from_slice(&result).unwrap();
+        let test_run_is_reruns: Vec<RunIsRerunData> = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(test_runs.len(), 1);
-        assert_eq!(test_runs[0], new_run);
+        assert_eq!(test_run_is_reruns, vec![run_is_rerun]);
     }
 
     #[actix_rt::test]
-    async fn find_for_template_failure_not_found() {
+    async fn find_reruns_by_id_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/runs/{}/reruns", run.run_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No reruns found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.detail, "No reruns found for the specified run");
+    }
+
+    #[actix_rt::test]
+    async fn find_input_sources_by_id_success() {
         let pool = get_test_db_pool();
 
-        create_run_with_test_and_template(&pool.get().unwrap());
+        let source_run = create_test_run_with_failed_state(&pool.get().unwrap());
+        let run = RunData::create(
+            &pool.get().unwrap(),
+            NewRun {
+                name: String::from("Kevin's Chained Run"),
+                test_id: source_run.test_id,
+                status: RunStatusEnum::TestSubmitted,
+                test_input: json!({"test_workflow.in_file": "gs://example/out_file.txt"}),
+                test_options: None,
+                eval_input: json!({}),
+                eval_options: None,
+                test_cromwell_job_id: Some(String::from("12345678910")),
+                eval_cromwell_job_id: None,
+                created_by: None,
+                finished_at: None,
+            },
+        )
+        .unwrap();
+        let run_input_source = RunInputSourceData::create(
+            &pool.get().unwrap(),
+            NewRunInputSource {
+                run_id: run.run_id,
+                workflow: RunCallWorkflowEnum::Test,
+                input_key: String::from("test_workflow.in_file"),
+                reference: format!("run_output:{}.test_workflow.out_file", source_run.name),
+                source_run_id: source_run.run_id,
+                value: json!("gs://example/out_file.txt"),
+            },
+        )
+        .unwrap();
 
         let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
 
         let req = test::TestRequest::get()
-            .uri(&format!("/templates/{}/runs", Uuid::new_v4()))
+            .uri(&format!("/runs/{}/input_sources", run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
-        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+        assert_eq!(resp.status(), http::StatusCode::OK);
 
         let result = test::read_body(resp).await;
-        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+        let test_run_input_sources: Vec<RunInputSourceData> =
+            serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(error_body.title, "No run found");
-        assert_eq!(error_body.status, 404);
-        assert_eq!(
-            error_body.detail,
-            "No runs found with the specified parameters"
-        );
+        assert_eq!(test_run_input_sources, vec![run_input_source]);
     }
 
     #[actix_rt::test]
-    async fn find_for_template_failure_bad_uuid() {
+    async fn find_input_sources_by_id_failure_not_found() {
         let pool = get_test_db_pool();
 
-        create_run_with_test_and_template(&pool.get().unwrap());
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
 
         let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
 
         let req = test::TestRequest::get()
-            .uri("/templates/123456789/runs")
+            .uri(&format!("/runs/{}/input_sources", run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
-        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
 
         let result = test::read_body(resp).await;
         let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(error_body.title, "ID formatted incorrectly");
-        assert_eq!(error_body.status, 400);
-        assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
+        assert_eq!(error_body.title, "No input sources found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No inputs resolved from other runs found for the specified run"
+        );
     }
 
     #[actix_rt::test]
-    async fn find_for_pipeline_success() {
+    async fn find_calls_by_id_success() {
         let pool = get_test_db_pool();
 
-        let (new_template, _, new_run) = create_run_with_test_and_template(&pool.get().unwrap());
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
+        let run_calls = RunCallData::batch_create(
+            &pool.get().unwrap(),
+            vec![
+                NewRunCall {
+                    run_id: run.run_id,
+                    workflow: RunCallWorkflowEnum::Test,
+                    cromwell_job_id: String::from("123456789"),
+                    task_name: String::from("test_workflow.hello"),
+                    shard_index: -1,
+                    attempt: 1,
+                    execution_status: Some(String::from("Done")),
+                    backend_status: Some(String::from("Done")),
+                    return_code: Some(0),
+                    docker_image: Some(String::from("ubuntu:latest")),
+                    runtime_attributes: Some(json!({"cpu": "1"})),
+                    started_at: Some(Utc::now().naive_utc()),
+                    finished_at: Some(Utc::now().naive_utc()),
+                },
+                NewRunCall {
+                    run_id: run.run_id,
+                    workflow: RunCallWorkflowEnum::Eval,
+                    cromwell_job_id: String::from("12345678901"),
+                    task_name: String::from("eval_workflow.compare"),
+                    shard_index: -1,
+                    attempt: 1,
+                    execution_status: Some(String::from("Failed")),
+                    backend_status: Some(String::from("Done")),
+                    return_code: Some(1),
+                    docker_image: Some(String::from("ubuntu:latest")),
+                    runtime_attributes: None,
+                    started_at: Some(Utc::now().naive_utc()),
+                    finished_at: Some(Utc::now().naive_utc()),
+                },
+            ],
+        )
+        .unwrap();
 
         let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
 
         let req = test::TestRequest::get()
-            .uri(&format!("/pipelines/{}/runs", new_template.pipeline_id))
+            .uri(&format!("/runs/{}/calls?workflow=eval", run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
         assert_eq!(resp.status(), http::StatusCode::OK);
 
         let result = test::read_body(resp).await;
-        let test_runs: Vec<RunWithResultsAndErrorsData> = serde_json::from_slice(&result).unwrap();
+        let test_run_calls: Vec<RunCallData> = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(test_runs.len(), 1);
-        assert_eq!(test_runs[0], new_run);
+        assert_eq!(test_run_calls.len(), 1);
+        assert_eq!(test_run_calls[0], run_calls[1]);
     }
 
     #[actix_rt::test]
-    async fn find_for_pipeline_failure_not_found() {
+    async fn find_calls_by_id_failure_not_found() {
         let pool = get_test_db_pool();
 
-        create_run_with_test_and_template(&pool.get().unwrap());
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
 
         let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
 
         let req = test::TestRequest::get()
-            .uri(&format!("/pipelines/{}/runs", Uuid::new_v4()))
+            .uri(&format!("/runs/{}/calls", run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
@@ -1159,24 +3905,22 @@ mod tests {
         let result = test::read_body(resp).await;
         let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(error_body.title, "No run found");
+        assert_eq!(error_body.title, "No calls found");
         assert_eq!(error_body.status, 404);
         assert_eq!(
             error_body.detail,
-            "No runs found with the specified parameters"
+            "No calls found for the specified run with the specified parameters"
         );
     }
 
     #[actix_rt::test]
-    async fn find_for_pipeline_failure_bad_uuid() {
+    async fn find_calls_by_id_failure_bad_uuid() {
         let pool = get_test_db_pool();
 
-        create_run_with_test_and_template(&pool.get().unwrap());
-
         let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
 
         let req = test::TestRequest::get()
-            .uri("/pipelines/123456789/runs")
+            .uri("/runs/123456789/calls")
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
@@ -1190,252 +3934,307 @@ mod tests {
         assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
     }
 
+    fn create_test_log_retriever() -> LogRetriever {
+        let client = Client::default();
+        let server_router = ServerRouter::new(client.clone(), &load_default_config());
+        let test_resource_client = TestResourceClient::new(client, None);
+        LogRetriever::new(server_router, test_resource_client)
+    }
+
     #[actix_rt::test]
-    async fn run_test() {
+    async fn find_logs_by_id_success() {
         let pool = get_test_db_pool();
-        let test_runner = TestRunner::new(
-            CromwellClient::new(Client::default(), &mockito::server_url()),
-            TestResourceClient::new(Client::default(), None),
-            None,
-        );
-
-        let test_template = create_test_template(&pool.get().unwrap());
-        let test_test =
-            create_test_test_with_template_id(&pool.get().unwrap(), test_template.template_id);
-
-        let test_input = json!({"in_greeted": "Cool Person"});
-        let test_options = Some(json!({"option": "Value"}));
-        let eval_input = json!({"in_output_filename": "test_greeting.txt"});
-        let eval_options = Some(json!({"other_option": "true"}));
-        let new_run = NewRunIncomplete {
-            name: None,
-            test_input: Some(test_input.clone()),
-            test_options: test_options.clone(),
-            eval_input: Some(eval_input.clone()),
-            eval_options: eval_options.clone(),
-            created_by: None,
-        };
 
-        // Define mockito mapping for wdl
-        let wdl_mock = mockito::mock("GET", "/test")
-            .with_status(200)
-            .with_body(read_to_string("testdata/routes/run/test_wdl.wdl").unwrap())
-            .expect(1)
-            .create();
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
 
-        // Define mockito mapping for cromwell response
         let mock_response_body = json!({
-          "id": "53709600-d114-4194-a7f7-9e41211ca2ce",
-          "status": "Submitted"
+          "id": "123456789",
+          "calls": {
+            "test_workflow.hello": [
+              {
+                "stdout": "/this/file/does/not/exist/stdout",
+                "stderr": "/this/file/does/not/exist/stderr",
+                "shardIndex": -1,
+                "attempt": 1
+              }
+            ]
+          }
         });
-        let cromwell_mock = mockito::mock("POST", "/api/workflows/v1")
-            .with_status(201)
+        let mock = mockito::mock("GET", "/api/workflows/v1/123456789/logs")
+            .with_status(200)
             .with_header("content_type", "application/json")
             .with_body(mock_response_body.to_string())
             .create();
 
-        // Start up app for testing
         let mut app = test::init_service(
             App::new()
                 .data(pool)
-                .data(test_runner)
+                .data(create_test_log_retriever())
                 .configure(init_routes),
         )
         .await;
 
-        // Make request
-        let req = test::TestRequest::post()
-            .uri(&format!("/tests/{}/runs", test_test.test_id))
-            .set_json(&new_run)
+        let req = test::TestRequest::get()
+            .uri(&format!("/runs/{}/logs", run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
+        mock.assert();
+
         assert_eq!(resp.status(), http::StatusCode::OK);
-        cromwell_mock.assert();
 
         let result = test::read_body(resp).await;
-        let test_run: RunData = serde_json::from_slice(&result).unwrap();
+        let test_logs: RunLogs = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(test_run.test_id, test_test.test_id);
-        assert_eq!(test_run.status, RunStatusEnum::TestSubmitted);
+        assert!(test_logs.eval.is_none());
+        let test_workflow_logs = test_logs.test.unwrap();
+        assert_eq!(test_workflow_logs.cromwell_job_id, "123456789");
+        let call_logs = &test_workflow_logs.calls["test_workflow.hello"][0];
         assert_eq!(
-            test_run.test_cromwell_job_id,
-            Some("53709600-d114-4194-a7f7-9e41211ca2ce".to_string())
-        );
-        let mut test_input_to_compare = json!({});
-        json_patch::merge(
-            &mut test_input_to_compare,
-            &test_test.test_input_defaults.unwrap(),
-        );
-        json_patch::merge(&mut test_input_to_compare, &test_input);
-        let mut eval_input_to_compare = json!({});
-        json_patch::merge(
-            &mut eval_input_to_compare,
-            &test_test.eval_input_defaults.unwrap(),
-        );
-        json_patch::merge(&mut eval_input_to_compare, &eval_input);
-        let mut eval_options_to_compare = json!({});
-        json_patch::merge(
-            &mut eval_options_to_compare,
-            &test_test.eval_option_defaults.unwrap(),
+            call_logs.stdout.as_deref(),
+            Some("/this/file/does/not/exist/stdout")
         );
-        json_patch::merge(&mut eval_options_to_compare, &eval_options.unwrap());
-        assert_eq!(test_run.test_input, test_input_to_compare);
-        assert_eq!(test_run.test_options, test_options);
-        assert_eq!(test_run.eval_input, eval_input_to_compare);
-        assert_eq!(test_run.eval_options.unwrap(), eval_options_to_compare);
+        assert!(call_logs.stdout_contents.is_none());
     }
 
     #[actix_rt::test]
-    async fn run_test_failure_taken_name() {
+    async fn find_logs_by_id_failure_cromwell() {
         let pool = get_test_db_pool();
-        let test_runner = TestRunner::new(
-            CromwellClient::new(Client::default(), &mockito::server_url()),
-            TestResourceClient::new(Client::default(), None),
-            None,
-        );
 
-        let test_template = create_test_template(&pool.get().unwrap());
-        let test_test =
-            create_test_test_with_template_id(&pool.get().unwrap(), test_template.template_id);
-        let test_run = create_test_run_with_test_id(&pool.get().unwrap(), test_test.test_id);
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
 
-        let test_input = json!({"in_greeted": "Cool Person"});
-        let test_options = Some(json!({"option": "Value"}));
-        let eval_input = json!({"in_output_filename": "test_greeting.txt"});
-        let eval_options = Some(json!({"other_option": "true"}));
-        let new_run = NewRunIncomplete {
-            name: Some(test_run.name.clone()),
-            test_input: Some(test_input.clone()),
-            test_options: test_options.clone(),
-            eval_input: Some(eval_input.clone()),
-            eval_options: eval_options.clone(),
-            created_by: None,
-        };
+        let mock = mockito::mock("GET", "/api/workflows/v1/123456789/logs")
+            .with_status(500)
+            .with_body("Cromwell is unhappy")
+            .create();
 
-        // Start up app for testing
         let mut app = test::init_service(
             App::new()
                 .data(pool)
-                .data(test_runner)
+                .data(create_test_log_retriever())
                 .configure(init_routes),
         )
         .await;
 
-        // Make request
-        let req = test::TestRequest::post()
-            .uri(&format!("/tests/{}/runs", test_test.test_id))
-            .set_json(&new_run)
+        let req = test::TestRequest::get()
+            .uri(&format!("/runs/{}/logs", run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
-        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        mock.assert();
+
+        assert_eq!(resp.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
 
         let result = test::read_body(resp).await;
-        let test_error: ErrorBody = serde_json::from_slice(&result).unwrap();
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(
-            test_error,
-            ErrorBody {
-                title: "Run with specified name already exists".to_string(),
-                status: 400,
-                detail: "If a custom run name is specified, it must be unique.".to_string(),
-            }
-        );
+        assert_eq!(error_body.title, "Server error");
+        assert_eq!(error_body.status, 500);
     }
 
     #[actix_rt::test]
-    async fn delete_success() {
+    async fn find_logs_by_id_failure_not_submitted() {
         let pool = get_test_db_pool();
 
-        let run = create_test_run_with_failed_state(&pool.get().unwrap());
+        let failed_run = create_test_run_with_failed_state(&pool.get().unwrap());
+        let run = RunData::create(
+            &pool.get().unwrap(),
+            NewRun {
+                name: String::from("Kevin's Unsubmitted Run"),
+                test_id: failed_run.test_id,
+                status: RunStatusEnum::Created,
+                test_input: json!({}),
+                test_options: None,
+                eval_input: json!({}),
+                eval_options: None,
+                test_cromwell_job_id: None,
+                eval_cromwell_job_id: None,
+                created_by: None,
+                finished_at: None,
+            },
+        )
+        .unwrap();
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(create_test_log_retriever())
+                .configure(init_routes),
+        )
+        .await;
 
-        let req = test::TestRequest::delete()
-            .uri(&format!("/runs/{}", run.run_id))
+        let req = test::TestRequest::get()
+            .uri(&format!("/runs/{}/logs", run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
-        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
 
         let result = test::read_body(resp).await;
-        let message: Value = serde_json::from_slice(&result).unwrap();
-
-        let expected_message = json!({
-            "message": "Successfully deleted 1 row"
-        });
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(message, expected_message)
+        assert_eq!(error_body.title, "No Cromwell jobs found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "The specified run has not been submitted to Cromwell (status: created)"
+        );
     }
 
     #[actix_rt::test]
-    async fn delete_failure_no_run() {
+    async fn find_failures_by_id_success() {
         let pool = get_test_db_pool();
 
-        let _run = create_test_run_with_failed_state(&pool.get().unwrap());
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let failures = json!([
+          {
+            "message": "Workflow failed",
+            "causedBy": [
+              {
+                "message": "Job test_workflow.hello:NA:1 exited with return code 1",
+                "causedBy": []
+              }
+            ]
+          }
+        ]);
+        let mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/123456789/metadata?includeKey=failures",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(json!({"id": "123456789", "failures": failures}).to_string())
+        .create();
 
-        let req = test::TestRequest::delete()
-            .uri(&format!("/runs/{}", Uuid::new_v4()))
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(create_test_log_retriever())
+                .configure(init_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/runs/{}/failures", run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
-        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+        mock.assert();
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
 
         let result = test::read_body(resp).await;
-        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+        let test_failures: RunFailures = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(error_body.title, "No run found");
-        assert_eq!(error_body.status, 404);
-        assert_eq!(error_body.detail, "No run found for the specified id");
+        assert_eq!(
+            test_failures,
+            RunFailures {
+                test: Some(WorkflowFailures {
+                    cromwell_job_id: String::from("123456789"),
+                    failures: failures.as_array().unwrap().to_owned(),
+                }),
+                eval: None,
+            }
+        );
     }
 
     #[actix_rt::test]
-    async fn delete_failure_not_allowed() {
+    async fn find_failures_by_id_success_redacted() {
         let pool = get_test_db_pool();
 
-        let run = create_test_run_with_nonfailed_state(&pool.get().unwrap());
+        let run = create_test_run_with_failed_state(&pool.get().unwrap());
+        let secret_encryptor = secrets::get_secret_encryptor(&load_default_config()).unwrap();
+        SecretData::create(
+            &pool.get().unwrap(),
+            NewSecret {
+                name: String::from("kevins_token"),
+                description: None,
+                encrypted_value: secret_encryptor.encrypt("hunter2").unwrap(),
+                created_by: None,
+            },
+        )
+        .unwrap();
+
+        let failures = json!([
+          {
+            "message": "Workflow failed",
+            "causedBy": [
+              {
+                "message": "Failed to log in with token hunter2",
+                "causedBy": []
+              }
+            ]
+          }
+        ]);
+        let mock = mockito::mock(
+            "GET",
+            "/api/workflows/v1/123456789/metadata?includeKey=failures",
+        )
+        .with_status(200)
+        .with_header("content_type", "application/json")
+        .with_body(json!({"id": "123456789", "failures": failures}).to_string())
+        .create();
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(create_test_log_retriever())
+                .data(secret_encryptor)
+                .configure(init_routes),
+        )
+        .await;
 
-        let req = test::TestRequest::delete()
-            .uri(&format!("/runs/{}", run.run_id))
+        let req = test::TestRequest::get()
+            .uri(&format!("/runs/{}/failures", run.run_id))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
-        assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
+        mock.assert();
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
 
         let result = test::read_body(resp).await;
-        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+        let test_failures: RunFailures = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(error_body.title, "Cannot delete");
-        assert_eq!(error_body.status, 403);
         assert_eq!(
-            error_body.detail,
-            "Cannot delete a run if it has a non-failed status"
+            test_failures.test.unwrap().failures,
+            vec![json!({
+                "message": "Workflow failed",
+                "causedBy": [
+                  {
+                    "message": "Failed to log in with token [REDACTED]",
+                    "causedBy": []
+                  }
+                ]
+            })]
         );
     }
 
     #[actix_rt::test]
-    async fn delete_failure_bad_uuid() {
+    async fn find_failures_by_id_failure_not_found() {
         let pool = get_test_db_pool();
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(create_test_log_retriever())
+                .configure(init_routes),
+        )
+        .await;
 
-        let req = test::TestRequest::delete()
-            .uri("/runs/123456789")
+        let req = test::TestRequest::get()
+            .uri(&format!("/runs/{}/failures", Uuid::new_v4()))
             .to_request();
         let resp = test::call_service(&mut app, req).await;
 
-        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
 
         let result = test::read_body(resp).await;
         let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
 
-        assert_eq!(error_body.title, "ID formatted incorrectly");
-        assert_eq!(error_body.status, 400);
-        assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
+        assert_eq!(error_body.title, "No run found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.detail, "No run found with the specified ID");
     }
 }
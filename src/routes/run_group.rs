@@ -0,0 +1,941 @@
+//! Defines REST API mappings for operations on run groups
+//!
+//! Contains functions for processing requests to create, update, delete, and search run groups,
+//! and to add runs to and remove runs from them, along with their URI mappings
+
+use crate::db;
+use crate::models::run_group::{
+    NewRunGroup, RunGroupChangeset, RunGroupData, RunGroupQuery, RunGroupWithStatusData,
+};
+use crate::models::run_in_group::{NewRunInGroup, RunInGroupData};
+use crate::routes::error_handling::{default_500, ErrorBody};
+use crate::routes::util::parse_id;
+use actix_web::{error::BlockingError, web, HttpResponse};
+use log::error;
+use serde_json::json;
+
+/// Handles requests to /run_groups/{id} for retrieving run group info by run_group_id
+///
+/// This function is called by Actix-Web when a get request is made to the /run_groups/{id} mapping
+/// It parses the id, connects to the db via a connection from `pool`, and returns the retrieved
+/// run group, along with the aggregated status of its runs, or an error message if there is no
+/// matching run group or some other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find_by_id(
+    id: web::Path<String>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Parse ID into Uuid
+    let id = parse_id(&id)?;
+
+    // Query DB for run group in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        RunGroupWithStatusData::find_by_id(&conn, id)
+    })
+    .await
+    // If there is no error, return a response with the retrieved data
+    .map(|results| HttpResponse::Ok().json(results))
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If no run group is found, return a 404
+            BlockingError::Error(diesel::NotFound) => HttpResponse::NotFound().json(ErrorBody {
+                title: "No run group found".to_string(),
+                status: 404,
+                detail: "No run group found with the specified ID".to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })?;
+
+    Ok(res)
+}
+
+/// Handles requests to /run_groups for retrieving run group info by query parameters
+///
+/// This function is called by Actix-Web when a get request is made to the /run_groups mapping
+/// It deserializes the query params to a RunGroupQuery, connects to the db via a connection from
+/// `pool`, and returns the retrieved run groups, along with the aggregated status of the runs in
+/// each, or an error message if there is no matching run group or some other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find(
+    web::Query(query): web::Query<RunGroupQuery>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Query DB for run groups in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match RunGroupWithStatusData::find(&conn, query) {
+            Ok(run_groups) => Ok(run_groups),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    .map(|results| {
+        // If there are no results, return a 404
+        if results.is_empty() {
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No run groups found".to_string(),
+                status: 404,
+                detail: "No run groups found with the specified parameters".to_string(),
+            })
+        } else {
+            // If there is no error, return a response with the retrieved data
+            HttpResponse::Ok().json(results)
+        }
+    })
+    .map_err(|e| {
+        error!("{}", e);
+        // If there is an error, return a 500
+        default_500(&e)
+    })?;
+
+    Ok(res)
+}
+
+/// Handles requests to /run_groups for creating run groups
+///
+/// This function is called by Actix-Web when a post request is made to the /run_groups mapping
+/// It deserializes the request body to a NewRunGroup, connects to the db via a connection from
+/// `pool`, creates a run group with the specified parameters, and returns the created run group,
+/// or an error message if creating the run group fails for some reason
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn create(
+    web::Json(new_run_group): web::Json<NewRunGroup>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Insert in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match RunGroupData::create(&conn, new_run_group) {
+            Ok(run_group) => Ok(run_group),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, return a response with the created run group
+    .map(|results| HttpResponse::Ok().json(results))
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If there's already a group with this name, return a 400
+            BlockingError::Error(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            )) => HttpResponse::BadRequest().json(ErrorBody {
+                title: "Run group with specified name already exists".to_string(),
+                status: 400,
+                detail: "Run group names must be unique.".to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })?;
+
+    Ok(res)
+}
+
+/// Handles requests to /run_groups/{id} for updating a run group
+///
+/// This function is called by Actix-Web when a put request is made to the /run_groups/{id}
+/// mapping
+/// It deserializes the request body to a RunGroupChangeset, connects to the db via a connection
+/// from `pool`, updates the specified run group, and returns the updated run group or an error
+/// message if some error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn update(
+    id: web::Path<String>,
+    web::Json(run_group_changes): web::Json<RunGroupChangeset>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Parse ID into Uuid
+    let id = parse_id(&id)?;
+
+    // Update in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match RunGroupData::update(&conn, id, run_group_changes) {
+            Ok(run_group) => Ok(run_group),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, return a response with the updated run group
+    .map(|results| HttpResponse::Ok().json(results))
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If no run group is found, return a 404
+            BlockingError::Error(diesel::NotFound) => HttpResponse::NotFound().json(ErrorBody {
+                title: "No run group found".to_string(),
+                status: 404,
+                detail: "No run group found with the specified ID".to_string(),
+            }),
+            // If the new name is already taken, return a 400
+            BlockingError::Error(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            )) => HttpResponse::BadRequest().json(ErrorBody {
+                title: "Run group with specified name already exists".to_string(),
+                status: 400,
+                detail: "Run group names must be unique.".to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })?;
+
+    Ok(res)
+}
+
+/// Handles DELETE requests to /run_groups/{id} for deleting run group rows by run_group_id
+///
+/// This function is called by Actix-Web when a delete request is made to the /run_groups/{id}
+/// mapping
+/// It parses the id, connects to the db via a connection from `pool`, and attempts to delete the
+/// specified run group (the runs in the group are not deleted), returns the number or rows
+/// deleted or an error message if some error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn delete_by_id(
+    id: web::Path<String>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Parse ID into Uuid
+    let id = parse_id(&id)?;
+
+    // Delete run group in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match RunGroupData::delete(&conn, id) {
+            Ok(delete_count) => Ok(delete_count),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, verify that a row was deleted
+    .map(|results| {
+        if results > 0 {
+            let message = format!("Successfully deleted {} row", results);
+            HttpResponse::Ok().json(json!({ "message": message }))
+        } else {
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No run group found".to_string(),
+                status: 404,
+                detail: "No run group found for the specified id".to_string(),
+            })
+        }
+    })
+    .map_err(|e| {
+        error!("{}", e);
+        // If there is an error, return a 500
+        default_500(&e)
+    })?;
+
+    Ok(res)
+}
+
+/// Handles POST requests to /run_groups/{id}/runs/{run_id} for adding a run to a run group
+///
+/// This function is called by Actix-Web when a post request is made to the
+/// /run_groups/{id}/runs/{run_id} mapping
+/// It parses the ids, connects to the db via a connection from `pool`, and attempts to add the
+/// specified run to the specified run group, returning the mapping that was created or an error
+/// message if the run or run group does not exist, the run is already in the group, or some other
+/// error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn add_run(
+    path: web::Path<(String, String)>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Parse IDs into Uuids
+    let (id, run_id) = path.into_inner();
+    let id = parse_id(&id)?;
+    let run_id = parse_id(&run_id)?;
+
+    // Insert in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match RunInGroupData::create(
+            &conn,
+            NewRunInGroup {
+                run_id,
+                run_group_id: id,
+            },
+        ) {
+            Ok(run_in_group) => Ok(run_in_group),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, return a response with the created mapping
+    .map(|results| HttpResponse::Ok().json(results))
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If the run or run group doesn't exist, return a 404
+            BlockingError::Error(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::ForeignKeyViolation,
+                _,
+            )) => HttpResponse::NotFound().json(ErrorBody {
+                title: "No run or run group found".to_string(),
+                status: 404,
+                detail: "No run or run group found with the specified IDs".to_string(),
+            }),
+            // If the run is already in the group, return a 400
+            BlockingError::Error(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            )) => HttpResponse::BadRequest().json(ErrorBody {
+                title: "Run already in run group".to_string(),
+                status: 400,
+                detail: "The specified run is already a member of the specified run group"
+                    .to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })?;
+
+    Ok(res)
+}
+
+/// Handles DELETE requests to /run_groups/{id}/runs/{run_id} for removing a run from a run group
+///
+/// This function is called by Actix-Web when a delete request is made to the
+/// /run_groups/{id}/runs/{run_id} mapping
+/// It parses the ids, connects to the db via a connection from `pool`, and attempts to remove the
+/// specified run from the specified run group (the run itself is not deleted), returning the
+/// number of rows deleted or an error message if the run is not in the group or some other error
+/// occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn remove_run(
+    path: web::Path<(String, String)>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Parse IDs into Uuids
+    let (id, run_id) = path.into_inner();
+    let id = parse_id(&id)?;
+    let run_id = parse_id(&run_id)?;
+
+    // Delete in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match RunInGroupData::delete(&conn, run_id, id) {
+            Ok(delete_count) => Ok(delete_count),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, verify that a row was deleted
+    .map(|results| {
+        if results > 0 {
+            let message = format!("Successfully deleted {} row", results);
+            HttpResponse::Ok().json(json!({ "message": message }))
+        } else {
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No run found in run group".to_string(),
+                status: 404,
+                detail: "The specified run is not a member of the specified run group".to_string(),
+            })
+        }
+    })
+    .map_err(|e| {
+        error!("{}", e);
+        // If there is an error, return a 500
+        default_500(&e)
+    })?;
+
+    Ok(res)
+}
+
+/// Attaches the REST mappings in this file to a service config
+///
+/// To be called when configuring the Actix-Web app service.  Registers the mappings in this file
+/// as part of the service defined in `cfg`
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/run_groups/{id}")
+            .route(web::get().to(find_by_id))
+            .route(web::put().to(update))
+            .route(web::delete().to(delete_by_id)),
+    );
+    cfg.service(
+        web::resource("/run_groups")
+            .route(web::get().to(find))
+            .route(web::post().to(create)),
+    );
+    cfg.service(
+        web::resource("/run_groups/{id}/runs/{run_id}")
+            .route(web::post().to(add_run))
+            .route(web::delete().to(remove_run)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{NewRun, RunData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::unit_test_util::*;
+    use actix_web::{http, test, App};
+    use diesel::PgConnection;
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    fn create_test_run_group(conn: &PgConnection) -> RunGroupData {
+        let new_run_group = NewRunGroup {
+            name: String::from("Kevin's Run Group"),
+            description: Some(String::from("Kevin made this run group for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        RunGroupData::create(conn, new_run_group).expect("Failed inserting test run group")
+    }
+
+    fn create_test_run(conn: &PgConnection) -> RunData {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's Template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing")),
+            test_wdl: String::from("testtest"),
+            test_wdl_dependencies: None,
+            eval_wdl: String::from("evaltest"),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: None,
+            test_option_defaults: None,
+            eval_input_defaults: None,
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let test = TestData::create(conn, new_test).expect("Failed inserting test test");
+
+        let new_run = NewRun {
+            test_id: test.test_id,
+            name: String::from("Kevin's Run"),
+            status: RunStatusEnum::Succeeded,
+            test_input: serde_json::from_str("{}").unwrap(),
+            test_options: None,
+            eval_input: serde_json::from_str("{}").unwrap(),
+            eval_options: None,
+            test_cromwell_job_id: Some(String::from("1234567890")),
+            eval_cromwell_job_id: Some(String::from("12345678902")),
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: Some(chrono::Utc::now().naive_utc()),
+        };
+
+        RunData::create(conn, new_run).expect("Failed inserting test run")
+    }
+
+    fn create_test_run_in_group(conn: &PgConnection) -> (RunGroupData, RunData) {
+        let run_group = create_test_run_group(conn);
+        let run = create_test_run(conn);
+
+        RunInGroupData::create(
+            conn,
+            NewRunInGroup {
+                run_id: run.run_id,
+                run_group_id: run_group.run_group_id,
+            },
+        )
+        .expect("Failed inserting test run_in_group");
+
+        (run_group, run)
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_success() {
+        let pool = get_test_db_pool();
+
+        let (run_group, _) = create_test_run_in_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/run_groups/{}", run_group.run_group_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_run_group: RunGroupWithStatusData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_run_group.run_group_id, run_group.run_group_id);
+        assert_eq!(test_run_group.name, run_group.name);
+        assert_eq!(test_run_group.status.total, 1);
+        assert_eq!(test_run_group.status.succeeded, 1);
+        assert!(test_run_group.status.all_finished);
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        create_test_run_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/run_groups/{}", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run group found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No run group found with the specified ID"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_failure_bad_uuid() {
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/run_groups/123456789")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "ID formatted incorrectly");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
+    }
+
+    #[actix_rt::test]
+    async fn find_success() {
+        let pool = get_test_db_pool();
+
+        let run_group = create_test_run_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/run_groups?name=Kevin%27s%20Run%20Group")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_run_groups: Vec<RunGroupWithStatusData> = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_run_groups.len(), 1);
+        assert_eq!(test_run_groups[0].run_group_id, run_group.run_group_id);
+        assert_eq!(test_run_groups[0].status.total, 0);
+        assert!(!test_run_groups[0].status.all_finished);
+    }
+
+    #[actix_rt::test]
+    async fn find_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        create_test_run_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/run_groups?name=Gibberish")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run groups found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No run groups found with the specified parameters"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn create_success() {
+        let pool = get_test_db_pool();
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let new_run_group = NewRunGroup {
+            name: String::from("Kevin's Run Group"),
+            description: Some(String::from("Kevin's run group description")),
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/run_groups")
+            .set_json(&new_run_group)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_run_group: RunGroupData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_run_group.name, new_run_group.name);
+        assert_eq!(test_run_group.description, new_run_group.description);
+        assert_eq!(test_run_group.created_by, new_run_group.created_by);
+    }
+
+    #[actix_rt::test]
+    async fn create_failure_duplicate_name() {
+        let pool = get_test_db_pool();
+
+        let run_group = create_test_run_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let new_run_group = NewRunGroup {
+            name: run_group.name,
+            description: None,
+            created_by: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/run_groups")
+            .set_json(&new_run_group)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(
+            error_body.title,
+            "Run group with specified name already exists"
+        );
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "Run group names must be unique.");
+    }
+
+    #[actix_rt::test]
+    async fn update_success() {
+        let pool = get_test_db_pool();
+
+        let run_group = create_test_run_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let run_group_change = RunGroupChangeset {
+            name: Some(String::from("Kevin's renamed run group")),
+            description: Some(String::from("Kevin's new description")),
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/run_groups/{}", run_group.run_group_id))
+            .set_json(&run_group_change)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_run_group: RunGroupData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_run_group.name, run_group_change.name.unwrap());
+        assert_eq!(test_run_group.description, run_group_change.description);
+    }
+
+    #[actix_rt::test]
+    async fn update_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        create_test_run_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let run_group_change = RunGroupChangeset {
+            name: Some(String::from("Kevin's renamed run group")),
+            description: None,
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/run_groups/{}", Uuid::new_v4()))
+            .set_json(&run_group_change)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run group found");
+        assert_eq!(error_body.status, 404);
+    }
+
+    #[actix_rt::test]
+    async fn delete_success() {
+        let pool = get_test_db_pool();
+
+        let (run_group, run) = create_test_run_in_group(&pool.get().unwrap());
+
+        let mut app =
+            test::init_service(App::new().data(pool.clone()).configure(init_routes)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/run_groups/{}", run_group.run_group_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let message: Value = serde_json::from_slice(&result).unwrap();
+
+        let expected_message = json!({
+            "message": "Successfully deleted 1 row"
+        });
+
+        assert_eq!(message, expected_message);
+
+        // Make sure the run itself wasn't deleted
+        RunData::find_by_id(&pool.get().unwrap(), run.run_id)
+            .expect("Run should not have been deleted with its group");
+    }
+
+    #[actix_rt::test]
+    async fn delete_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        create_test_run_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/run_groups/{}", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run group found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.detail, "No run group found for the specified id");
+    }
+
+    #[actix_rt::test]
+    async fn add_run_success() {
+        let pool = get_test_db_pool();
+
+        let run_group = create_test_run_group(&pool.get().unwrap());
+        let run = create_test_run(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/run_groups/{}/runs/{}",
+                run_group.run_group_id, run.run_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let run_in_group: RunInGroupData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(run_in_group.run_id, run.run_id);
+        assert_eq!(run_in_group.run_group_id, run_group.run_group_id);
+    }
+
+    #[actix_rt::test]
+    async fn add_run_failure_already_in_group() {
+        let pool = get_test_db_pool();
+
+        let (run_group, run) = create_test_run_in_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/run_groups/{}/runs/{}",
+                run_group.run_group_id, run.run_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Run already in run group");
+        assert_eq!(error_body.status, 400);
+    }
+
+    #[actix_rt::test]
+    async fn add_run_failure_no_run() {
+        let pool = get_test_db_pool();
+
+        let run_group = create_test_run_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/run_groups/{}/runs/{}",
+                run_group.run_group_id,
+                Uuid::new_v4()
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run or run group found");
+        assert_eq!(error_body.status, 404);
+    }
+
+    #[actix_rt::test]
+    async fn remove_run_success() {
+        let pool = get_test_db_pool();
+
+        let (run_group, run) = create_test_run_in_group(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/run_groups/{}/runs/{}",
+                run_group.run_group_id, run.run_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let message: Value = serde_json::from_slice(&result).unwrap();
+
+        let expected_message = json!({
+            "message": "Successfully deleted 1 row"
+        });
+
+        assert_eq!(message, expected_message);
+    }
+
+    #[actix_rt::test]
+    async fn remove_run_failure_not_in_group() {
+        let pool = get_test_db_pool();
+
+        let run_group = create_test_run_group(&pool.get().unwrap());
+        let run = create_test_run(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/run_groups/{}/runs/{}",
+                run_group.run_group_id, run.run_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run found in run group");
+        assert_eq!(error_body.status, 404);
+    }
+}
@@ -320,6 +320,14 @@ async fn create(
                     status: 500,
                     detail: format!("Error while attempting to convert run data into CSV to include in report: {}", e),
                 },
+                report_builder::Error::Server(e) => ErrorBody {
+                    title: "Cromwell server error".to_string(),
+                    status: 500,
+                    detail: format!(
+                        "Error while attempting to choose a cromwell server to generate report: {}",
+                        e
+                    ),
+                },
             };
             Ok(HttpResponseBuilder::new(
                 StatusCode::from_u16(error_body.status)
@@ -451,6 +459,7 @@ mod tests {
     use super::*;
     use crate::custom_sql_types::{ReportStatusEnum, ResultTypeEnum, RunStatusEnum};
     use crate::db::DbPool;
+    use crate::manager::server_router::ServerRouter;
     use crate::models::pipeline::{NewPipeline, PipelineData};
     use crate::models::report::{NewReport, ReportData};
     use crate::models::result::{NewResult, ResultData};
@@ -461,7 +470,6 @@ mod tests {
     use crate::models::template_report::{NewTemplateReport, TemplateReportData};
     use crate::models::template_result::{NewTemplateResult, TemplateResultData};
     use crate::models::test::{NewTest, TestData};
-    use crate::requests::cromwell_requests::CromwellClient;
     use crate::storage::gcloud_storage::GCloudClient;
     use crate::unit_test_util::*;
     use actix_web::{client::Client, http, test, App};
@@ -477,6 +485,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -491,6 +502,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -505,6 +519,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let test = TestData::create(conn, new_test).expect("Failed inserting test test");
@@ -552,6 +570,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
         };
 
         RunReportData::create(conn, new_run_report).expect("Failed inserting test run_report")
@@ -581,6 +600,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -595,6 +617,9 @@ mod tests {
             eval_wdl: format!("{}/eval.wdl", mockito::server_url()),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -609,6 +634,10 @@ mod tests {
             eval_input_defaults: None,
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let test = TestData::create(conn, new_test).expect("Failed inserting test test");
@@ -731,6 +760,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
         };
 
         RunReportData::create(conn, new_run_report).expect("Failed inserting test run_report")
@@ -749,6 +779,7 @@ mod tests {
             results: None,
             created_by: Some(String::from("Kevin@example.com")),
             finished_at: Some(Utc::now().naive_utc()),
+            cromwell_server: None,
         };
 
         RunReportData::create(conn, new_run_report).expect("Failed inserting test run_report")
@@ -775,13 +806,12 @@ mod tests {
             }
             None => None,
         };
-        let cromwell_client: CromwellClient =
-            CromwellClient::new(http_client.clone(), carrot_config.cromwell().address());
+        let server_router: ServerRouter = ServerRouter::new(http_client.clone(), &carrot_config);
         // Create report builder
         let reporting_config = carrot_config
             .reporting()
             .expect("Cannot create report builder for testing without reporting config");
-        ReportBuilder::new(cromwell_client.clone(), gcloud_client.expect("Failed to unwrap gcloud_client to create report builder.  This should not happen").clone(), reporting_config)
+        ReportBuilder::new(server_router, gcloud_client.expect("Failed to unwrap gcloud_client to create report builder.  This should not happen").clone(), reporting_config)
     }
 
     #[actix_rt::test]
@@ -0,0 +1,754 @@
+//! Defines REST API mappings for operations on secrets
+//!
+//! Contains functions for processing requests to create, update, delete, and search secrets, along
+//! with their URI mappings.  Secret values are encrypted before they're stored and are never
+//! returned by any of these mappings
+
+use crate::db;
+use crate::models::secret::{NewSecret, SecretChangeset, SecretData, SecretQuery};
+use crate::routes::disabled_features;
+use crate::routes::error_handling::{default_500, ErrorBody};
+use crate::routes::util::parse_id;
+use crate::util::secrets::{SecretEncryptor, SECRET_NAME_REGEX};
+use actix_web::{error::BlockingError, web, HttpResponse};
+use log::error;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// Represents the part of a new secret that is received as a request body
+///
+/// The value is encrypted before the secret is inserted into the DB
+#[derive(Deserialize, Serialize)]
+pub struct NewSecretIncomplete {
+    pub name: String,
+    pub description: Option<String>,
+    pub value: String,
+    pub created_by: Option<String>,
+}
+
+/// Represents the fields of a secret that can be changed in an update request
+///
+/// If a value is specified, it is encrypted before the secret is updated in the DB
+#[derive(Deserialize, Serialize)]
+pub struct SecretChangesetIncomplete {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub value: Option<String>,
+}
+
+/// Handles requests to /secrets/{id} for retrieving secret info by secret_id
+///
+/// This function is called by Actix-Web when a get request is made to the /secrets/{id} mapping
+/// It parses the id, connects to the db via a connection from `pool`, and returns the retrieved
+/// secret (without its value), or an error message if there is no matching secret or some other
+/// error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find_by_id(
+    id: web::Path<String>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Parse ID into Uuid
+    let id = parse_id(&id)?;
+
+    // Query DB for secret in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        SecretData::find_by_id(&conn, id)
+    })
+    .await
+    // If there is no error, return a response with the retrieved data
+    .map(|results| HttpResponse::Ok().json(results))
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If no secret is found, return a 404
+            BlockingError::Error(diesel::NotFound) => HttpResponse::NotFound().json(ErrorBody {
+                title: "No secret found".to_string(),
+                status: 404,
+                detail: "No secret found with the specified ID".to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })?;
+
+    Ok(res)
+}
+
+/// Handles requests to /secrets for retrieving secret info by query parameters
+///
+/// This function is called by Actix-Web when a get request is made to the /secrets mapping
+/// It deserializes the query params to a SecretQuery, connects to the db via a connection from
+/// `pool`, and returns the retrieved secrets (without their values), or an error message if there
+/// is no matching secret or some other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find(
+    web::Query(query): web::Query<SecretQuery>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Query DB for secrets in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match SecretData::find(&conn, query) {
+            Ok(secrets) => Ok(secrets),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    .map(|results| {
+        // If there are no results, return a 404
+        if results.is_empty() {
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No secrets found".to_string(),
+                status: 404,
+                detail: "No secrets found with the specified parameters".to_string(),
+            })
+        } else {
+            // If there is no error, return a response with the retrieved data
+            HttpResponse::Ok().json(results)
+        }
+    })
+    .map_err(|e| {
+        error!("{}", e);
+        // If there is an error, return a 500
+        default_500(&e)
+    })?;
+
+    Ok(res)
+}
+
+/// Handles requests to /secrets for creating secrets
+///
+/// This function is called by Actix-Web when a post request is made to the /secrets mapping
+/// It deserializes the request body to a NewSecretIncomplete, encrypts its value using
+/// `secret_encryptor`, connects to the db via a connection from `pool`, creates a secret with the
+/// specified parameters, and returns the created secret (without its value), or an error message
+/// if creating the secret fails for some reason
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn create(
+    web::Json(new_secret): web::Json<NewSecretIncomplete>,
+    pool: web::Data<db::DbPool>,
+    secret_encryptor: web::Data<SecretEncryptor>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Make sure the name can be referenced in inputs
+    validate_secret_name(&new_secret.name)?;
+    // Encrypt the value so we're not storing it in plain text
+    let encrypted_value = encrypt_secret_value(&secret_encryptor, &new_secret.value)?;
+    let new_secret = NewSecret {
+        name: new_secret.name,
+        description: new_secret.description,
+        encrypted_value,
+        created_by: new_secret.created_by,
+    };
+
+    // Insert in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match SecretData::create(&conn, new_secret) {
+            Ok(secret) => Ok(secret),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, return a response with the created secret
+    .map(|results| HttpResponse::Ok().json(results))
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If there's already a secret with this name, return a 400
+            BlockingError::Error(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            )) => HttpResponse::BadRequest().json(ErrorBody {
+                title: "Secret with specified name already exists".to_string(),
+                status: 400,
+                detail: "Secret names must be unique.".to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })?;
+
+    Ok(res)
+}
+
+/// Handles requests to /secrets/{id} for updating a secret
+///
+/// This function is called by Actix-Web when a put request is made to the /secrets/{id} mapping
+/// It deserializes the request body to a SecretChangesetIncomplete, encrypts the new value (if
+/// there is one) using `secret_encryptor`, connects to the db via a connection from `pool`,
+/// updates the specified secret, and returns the updated secret (without its value) or an error
+/// message if some error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn update(
+    id: web::Path<String>,
+    web::Json(secret_changes): web::Json<SecretChangesetIncomplete>,
+    pool: web::Data<db::DbPool>,
+    secret_encryptor: web::Data<SecretEncryptor>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Parse ID into Uuid
+    let id = parse_id(&id)?;
+    // Make sure the new name, if there is one, can be referenced in inputs
+    if let Some(name) = &secret_changes.name {
+        validate_secret_name(name)?;
+    }
+    // Encrypt the new value, if there is one
+    let encrypted_value = match &secret_changes.value {
+        Some(value) => Some(encrypt_secret_value(&secret_encryptor, value)?),
+        None => None,
+    };
+    let secret_changes = SecretChangeset {
+        name: secret_changes.name,
+        description: secret_changes.description,
+        encrypted_value,
+    };
+
+    // Update in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match SecretData::update(&conn, id, secret_changes) {
+            Ok(secret) => Ok(secret),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, return a response with the updated secret
+    .map(|results| HttpResponse::Ok().json(results))
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If no secret is found, return a 404
+            BlockingError::Error(diesel::NotFound) => HttpResponse::NotFound().json(ErrorBody {
+                title: "No secret found".to_string(),
+                status: 404,
+                detail: "No secret found with the specified ID".to_string(),
+            }),
+            // If the new name is already taken, return a 400
+            BlockingError::Error(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            )) => HttpResponse::BadRequest().json(ErrorBody {
+                title: "Secret with specified name already exists".to_string(),
+                status: 400,
+                detail: "Secret names must be unique.".to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })?;
+
+    Ok(res)
+}
+
+/// Handles DELETE requests to /secrets/{id} for deleting secret rows by secret_id
+///
+/// This function is called by Actix-Web when a delete request is made to the /secrets/{id} mapping
+/// It parses the id, connects to the db via a connection from `pool`, and attempts to delete the
+/// specified secret, returns the number or rows deleted or an error message if some error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn delete_by_id(
+    id: web::Path<String>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    // Parse ID into Uuid
+    let id = parse_id(&id)?;
+
+    // Delete secret in new thread
+    let res = web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match SecretData::delete(&conn, id) {
+            Ok(delete_count) => Ok(delete_count),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, verify that a row was deleted
+    .map(|results| {
+        if results > 0 {
+            let message = format!("Successfully deleted {} row", results);
+            HttpResponse::Ok().json(json!({ "message": message }))
+        } else {
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No secret found".to_string(),
+                status: 404,
+                detail: "No secret found for the specified id".to_string(),
+            })
+        }
+    })
+    .map_err(|e| {
+        error!("{}", e);
+        // If there is an error, return a 500
+        default_500(&e)
+    })?;
+
+    Ok(res)
+}
+
+/// Returns an error response if `name` can't be used as a secret name (i.e. it contains characters
+/// other than letters, numbers, underscores, periods, and hyphens, so it couldn't be referenced as
+/// secret:name in inputs)
+fn validate_secret_name(name: &str) -> Result<(), HttpResponse> {
+    if SECRET_NAME_REGEX.is_match(name) {
+        Ok(())
+    } else {
+        Err(HttpResponse::BadRequest().json(ErrorBody {
+            title: "Invalid secret name".to_string(),
+            status: 400,
+            detail:
+                "Secret names may only contain letters, numbers, underscores, periods, and hyphens"
+                    .to_string(),
+        }))
+    }
+}
+
+/// Encrypts `value` using `secret_encryptor`, or returns an error response if encrypting fails
+fn encrypt_secret_value(
+    secret_encryptor: &SecretEncryptor,
+    value: &str,
+) -> Result<Vec<u8>, HttpResponse> {
+    secret_encryptor.encrypt(value).map_err(|e| {
+        error!("{}", e);
+        HttpResponse::InternalServerError().json(ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: format!(
+                "Encountered error while attempting to encrypt secret value: {}",
+                e
+            ),
+        })
+    })
+}
+
+/// Attaches the REST mappings in this file to a service config
+///
+/// To be called when configuring the Actix-Web app service.  Registers the mappings in this file
+/// as part of the service defined in `cfg`
+pub fn init_routes(cfg: &mut web::ServiceConfig, enable_secrets: bool) {
+    // Only map routes to secret functions if secrets are enabled
+    if enable_secrets {
+        init_routes_secrets_enabled(cfg);
+    } else {
+        init_routes_secrets_disabled(cfg);
+    }
+}
+
+/// Attaches the REST mappings in this file to a service config for if secrets are enabled
+fn init_routes_secrets_enabled(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/secrets/{id}")
+            .route(web::get().to(find_by_id))
+            .route(web::put().to(update))
+            .route(web::delete().to(delete_by_id)),
+    );
+    cfg.service(
+        web::resource("/secrets")
+            .route(web::get().to(find))
+            .route(web::post().to(create)),
+    );
+}
+
+/// Attaches a secrets-disabled error message REST mapping to a service cfg
+fn init_routes_secrets_disabled(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/secrets")
+            .route(web::route().to(disabled_features::secrets_disabled_mapping)),
+    );
+    cfg.service(
+        web::resource("/secrets/{id}")
+            .route(web::route().to(disabled_features::secrets_disabled_mapping)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::unit_test_util::*;
+    use crate::util::secrets;
+    use actix_web::{http, test, App};
+    use diesel::PgConnection;
+    use serde_json::Value;
+    use uuid::Uuid;
+
+    fn create_test_secret_encryptor() -> SecretEncryptor {
+        secrets::get_secret_encryptor(&load_default_config())
+            .expect("Failed to create test secret encryptor")
+    }
+
+    fn create_test_secret(conn: &PgConnection) -> SecretData {
+        let new_secret = NewSecret {
+            name: String::from("kevins_secret"),
+            description: Some(String::from("Kevin made this secret for testing")),
+            encrypted_value: create_test_secret_encryptor().encrypt("hunter2").unwrap(),
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        SecretData::create(conn, new_secret).expect("Failed inserting test secret")
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_success() {
+        let pool = get_test_db_pool();
+
+        let new_secret = create_test_secret(&pool.get().unwrap());
+
+        let mut app =
+            test::init_service(App::new().data(pool).configure(init_routes_secrets_enabled)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/secrets/{}", new_secret.secret_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        // Make sure the value isn't included anywhere in the response
+        assert!(!String::from_utf8(result.to_vec())
+            .unwrap()
+            .contains("value"));
+        let test_secret: SecretData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_secret, new_secret);
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        create_test_secret(&pool.get().unwrap());
+
+        let mut app =
+            test::init_service(App::new().data(pool).configure(init_routes_secrets_enabled)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/secrets/{}", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No secret found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.detail, "No secret found with the specified ID");
+    }
+
+    #[actix_rt::test]
+    async fn find_success() {
+        let pool = get_test_db_pool();
+
+        let new_secret = create_test_secret(&pool.get().unwrap());
+
+        let mut app =
+            test::init_service(App::new().data(pool).configure(init_routes_secrets_enabled)).await;
+
+        let req = test::TestRequest::get()
+            .uri("/secrets?name=kevins_secret")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_secrets: Vec<SecretData> = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_secrets.len(), 1);
+        assert_eq!(test_secrets[0], new_secret);
+    }
+
+    #[actix_rt::test]
+    async fn create_success() {
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool.clone())
+                .data(create_test_secret_encryptor())
+                .configure(init_routes_secrets_enabled),
+        )
+        .await;
+
+        let new_secret = NewSecretIncomplete {
+            name: String::from("kevins_secret"),
+            description: Some(String::from("Kevin made this secret for testing")),
+            value: String::from("hunter2"),
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/secrets")
+            .set_json(&new_secret)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        assert!(!String::from_utf8(result.to_vec())
+            .unwrap()
+            .contains("hunter2"));
+        let test_secret: SecretData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_secret.name, new_secret.name);
+        assert_eq!(test_secret.description, new_secret.description);
+        assert_eq!(test_secret.created_by, new_secret.created_by);
+
+        // Make sure the value was encrypted before it was stored
+        let encrypted_value =
+            SecretData::find_encrypted_value_by_name(&pool.get().unwrap(), "kevins_secret")
+                .unwrap();
+        assert_ne!(encrypted_value, "hunter2".as_bytes());
+        assert_eq!(
+            create_test_secret_encryptor()
+                .decrypt(&encrypted_value)
+                .unwrap(),
+            "hunter2"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn create_failure_invalid_name() {
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(create_test_secret_encryptor())
+                .configure(init_routes_secrets_enabled),
+        )
+        .await;
+
+        let new_secret = NewSecretIncomplete {
+            name: String::from("Kevin's Secret"),
+            description: None,
+            value: String::from("hunter2"),
+            created_by: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/secrets")
+            .set_json(&new_secret)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Invalid secret name");
+        assert_eq!(error_body.status, 400);
+    }
+
+    #[actix_rt::test]
+    async fn create_failure_duplicate_name() {
+        let pool = get_test_db_pool();
+
+        create_test_secret(&pool.get().unwrap());
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(create_test_secret_encryptor())
+                .configure(init_routes_secrets_enabled),
+        )
+        .await;
+
+        let new_secret = NewSecretIncomplete {
+            name: String::from("kevins_secret"),
+            description: None,
+            value: String::from("hunter3"),
+            created_by: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/secrets")
+            .set_json(&new_secret)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(
+            error_body.title,
+            "Secret with specified name already exists"
+        );
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "Secret names must be unique.");
+    }
+
+    #[actix_rt::test]
+    async fn update_success() {
+        let pool = get_test_db_pool();
+
+        let secret = create_test_secret(&pool.get().unwrap());
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool.clone())
+                .data(create_test_secret_encryptor())
+                .configure(init_routes_secrets_enabled),
+        )
+        .await;
+
+        let secret_change = SecretChangesetIncomplete {
+            name: None,
+            description: Some(String::from("Kevin changed this secret")),
+            value: Some(String::from("hunter3")),
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/secrets/{}", secret.secret_id))
+            .set_json(&secret_change)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_secret: SecretData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_secret.name, secret.name);
+        assert_eq!(
+            test_secret.description.unwrap(),
+            "Kevin changed this secret"
+        );
+
+        let encrypted_value =
+            SecretData::find_encrypted_value_by_name(&pool.get().unwrap(), "kevins_secret")
+                .unwrap();
+        assert_eq!(
+            create_test_secret_encryptor()
+                .decrypt(&encrypted_value)
+                .unwrap(),
+            "hunter3"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn update_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(create_test_secret_encryptor())
+                .configure(init_routes_secrets_enabled),
+        )
+        .await;
+
+        let secret_change = SecretChangesetIncomplete {
+            name: None,
+            description: Some(String::from("Kevin changed this secret")),
+            value: None,
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/secrets/{}", Uuid::new_v4()))
+            .set_json(&secret_change)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No secret found");
+        assert_eq!(error_body.status, 404);
+    }
+
+    #[actix_rt::test]
+    async fn delete_success() {
+        let pool = get_test_db_pool();
+
+        let secret = create_test_secret(&pool.get().unwrap());
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool.clone())
+                .configure(init_routes_secrets_enabled),
+        )
+        .await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!("/secrets/{}", secret.secret_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let message: Value = serde_json::from_slice(&result).unwrap();
+
+        let expected_message = json!({
+            "message": "Successfully deleted 1 row"
+        });
+
+        assert_eq!(message, expected_message);
+
+        let deleted_secret = SecretData::find_by_id(&pool.get().unwrap(), secret.secret_id);
+
+        assert!(matches!(
+            deleted_secret,
+            Err(diesel::result::Error::NotFound)
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn find_failure_secrets_disabled() {
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .configure(init_routes_secrets_disabled),
+        )
+        .await;
+
+        let req = test::TestRequest::get().uri("/secrets").to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::UNPROCESSABLE_ENTITY);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Secrets disabled");
+        assert_eq!(error_body.status, 422);
+        assert_eq!(error_body.detail, "You are trying to access a secret-related endpoint, but the secrets feature is disabled for this CARROT server");
+    }
+}
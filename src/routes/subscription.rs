@@ -6,6 +6,7 @@
 use crate::custom_sql_types::EntityTypeEnum;
 use crate::db;
 use crate::models::pipeline::PipelineData;
+use crate::models::run_group::RunGroupData;
 use crate::models::subscription::{
     NewSubscription, SubscriptionData, SubscriptionDeleteParams, SubscriptionQuery,
 };
@@ -152,6 +153,31 @@ async fn create_for_pipeline(
     .await
 }
 
+/// Handles POST requests to /run_groups/{id}/subscriptions for creating a subscription to a
+/// run group
+///
+/// This function is called by Actix-Web when a post request is made to the
+/// /run_groups/{id}/subscriptions mapping. It deserializes the request body to a
+/// SubscriptionIncomplete, extracts the id from the path, and uses that to create a new
+/// subscription to that run group.  If successful, it returns the subscription data back to the
+/// user.  If unsuccessful, it sends them an error.
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn create_for_run_group(
+    id: web::Path<String>,
+    web::Json(new_sub): web::Json<SubscriptionIncomplete>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    create(
+        id.to_string(),
+        new_sub.email,
+        EntityTypeEnum::RunGroup,
+        pool,
+    )
+    .await
+}
+
 /// Handles POST requests to /templates/{id}/subscriptions for creating a subscription to a
 /// template
 ///
@@ -280,6 +306,31 @@ async fn delete_for_pipeline(
     .await
 }
 
+/// Handles DELETE requests to /run_groups/{id}/subscriptions for deleting a subscription to a
+/// run group
+///
+/// This function is called by Actix-Web when a delete request is made to the
+/// /run_groups/{id}/subscriptions mapping. It deserializes the request body to a
+/// SubscriptionIncomplete, extracts the id from the path, and uses that to attempt to delete
+/// matching subscriptions from the DB.  If successful, it returns the count of deleted rows back
+/// to the user.  If unsuccessful, it sends them an error.
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn delete_for_run_group(
+    id: web::Path<String>,
+    web::Query(new_sub): web::Query<SubscriptionIncomplete>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    delete(
+        id.to_string(),
+        new_sub.email,
+        EntityTypeEnum::RunGroup,
+        pool,
+    )
+    .await
+}
+
 /// Handles DELETE requests to /templates/{id}/subscriptions for deleting a subscription to a
 /// template
 ///
@@ -413,6 +464,30 @@ async fn find_for_pipeline(
     find(query, pool).await
 }
 
+/// Handles requests to /run_groups/{id}/subscriptions for retrieving subscription info by query
+/// parameters
+///
+/// This function is called by Actix-Web when a get request is made to the
+/// /run_groups/{id}/subscriptions mapping, It deserializes the query params to a
+/// SubscriptionQuery, fills in the entity_id from `id`, connects to the db via a connection from
+/// `pool`, and returns the retrieved subscriptions, or an error message if there is no matching
+/// subscription or some other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find_for_run_group(
+    id: web::Path<String>,
+    web::Query(mut query): web::Query<SubscriptionQuery>,
+    pool: web::Data<db::DbPool>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let entity_id = parse_id(&id)?;
+    // Fill in id and type in query
+    query.entity_id = Some(entity_id);
+    query.entity_type = Some(EntityTypeEnum::RunGroup);
+    // Do the search
+    find(query, pool).await
+}
+
 /// Handles requests to /templates/{id}/subscriptions for retrieving subscription info by query
 /// parameters
 ///
@@ -492,6 +567,10 @@ async fn verify_existence(
             Ok(_) => Ok(()),
             Err(e) => Err(e),
         },
+        EntityTypeEnum::RunGroup => match RunGroupData::find_by_id(&conn, id) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(e),
+        },
         EntityTypeEnum::Template => match TemplateData::find_by_id(&conn, id) {
             Ok(_) => Ok(()),
             Err(e) => Err(e),
@@ -513,6 +592,10 @@ async fn verify_existence(
                         "No pipeline found".to_string(),
                         "No pipeline found with the specified ID".to_string(),
                     ),
+                    EntityTypeEnum::RunGroup => (
+                        "No run group found".to_string(),
+                        "No run group found with the specified ID".to_string(),
+                    ),
                     EntityTypeEnum::Template => (
                         "No template found".to_string(),
                         "No template found with the specified ID".to_string(),
@@ -545,6 +628,12 @@ pub fn init_routes(cfg: &mut web::ServiceConfig) {
             .route(web::post().to(create_for_pipeline))
             .route(web::get().to(find_for_pipeline)),
     );
+    cfg.service(
+        web::resource("/run_groups/{id}/subscriptions")
+            .route(web::delete().to(delete_for_run_group))
+            .route(web::post().to(create_for_run_group))
+            .route(web::get().to(find_for_run_group)),
+    );
     cfg.service(
         web::resource("/templates/{id}/subscriptions")
             .route(web::delete().to(delete_for_template))
@@ -567,6 +656,7 @@ mod tests {
     use super::*;
     use crate::custom_sql_types::EntityTypeEnum;
     use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run_group::NewRunGroup;
     use crate::models::subscription::{NewSubscription, SubscriptionData};
     use crate::models::template::{NewTemplate, TemplateData};
     use crate::models::test::{NewTest, TestData};
@@ -581,6 +671,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline")
@@ -591,6 +684,9 @@ mod tests {
             name: String::from("Kevin's Pipeline2"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -605,6 +701,9 @@ mod tests {
             eval_wdl: String::from("evalevaleval"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(conn, new_template).expect("Failed inserting test template")
@@ -615,6 +714,9 @@ mod tests {
             name: String::from("Kevin's Pipeline3"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -629,6 +731,9 @@ mod tests {
             eval_wdl: String::from("evalevaleval"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -643,11 +748,37 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(conn, new_test).expect("Failed inserting test test")
     }
 
+    fn create_test_run_group(conn: &PgConnection) -> RunGroupData {
+        let new_run_group = NewRunGroup {
+            name: String::from("Kevin's Run Group"),
+            description: Some(String::from("Kevin made this run group for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        RunGroupData::create(conn, new_run_group).expect("Failed inserting test run group")
+    }
+
+    fn create_test_run_group_subscription(conn: &PgConnection) -> SubscriptionData {
+        let new_run_group = create_test_run_group(conn);
+        let new_subscription = NewSubscription {
+            entity_type: EntityTypeEnum::RunGroup,
+            entity_id: new_run_group.run_group_id,
+            email: String::from("Kevin@example.com"),
+        };
+
+        SubscriptionData::create(conn, new_subscription)
+            .expect("Failed to insert test run group subscription")
+    }
+
     fn create_test_subscriptions(conn: &PgConnection) -> [SubscriptionData; 3] {
         let new_pipeline = create_test_pipeline(conn);
         let new_subscription1 = NewSubscription {
@@ -971,6 +1102,116 @@ mod tests {
         assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
     }
 
+    #[actix_rt::test]
+    async fn find_for_run_group_success() {
+        let pool = get_test_db_pool();
+
+        let new_sub = create_test_run_group_subscription(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/run_groups/{}/subscriptions", new_sub.entity_id))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_subs: Vec<SubscriptionData> = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_subs.len(), 1);
+        assert_eq!(test_subs[0], new_sub);
+    }
+
+    #[actix_rt::test]
+    async fn create_for_run_group_success() {
+        let pool = get_test_db_pool();
+
+        let new_run_group = create_test_run_group(&pool.get().unwrap());
+
+        let new_subscription = SubscriptionIncomplete {
+            email: String::from("Kevin@example.com"),
+        };
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/run_groups/{}/subscriptions",
+                new_run_group.run_group_id
+            ))
+            .set_json(&new_subscription)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_subscription: SubscriptionData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_subscription.entity_id, new_run_group.run_group_id);
+        assert_eq!(test_subscription.entity_type, EntityTypeEnum::RunGroup);
+        assert_eq!(test_subscription.email, String::from("Kevin@example.com"));
+    }
+
+    #[actix_rt::test]
+    async fn create_for_run_group_failure_no_run_group() {
+        let pool = get_test_db_pool();
+
+        let new_subscription = SubscriptionIncomplete {
+            email: String::from("Kevin@example.com"),
+        };
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/run_groups/{}/subscriptions", Uuid::new_v4()))
+            .set_json(&new_subscription)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No run group found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No run group found with the specified ID"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn delete_for_run_group_success() {
+        let pool = get_test_db_pool();
+
+        let new_sub = create_test_run_group_subscription(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/run_groups/{}/subscriptions?email=Kevin%40example.com",
+                new_sub.entity_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let message: Value = serde_json::from_slice(&result).unwrap();
+
+        let expected_message = json!({
+            "message": "Successfully deleted 1 row(s)"
+        });
+
+        assert_eq!(message, expected_message)
+    }
+
     #[actix_rt::test]
     async fn find_for_any_success() {
         let pool = get_test_db_pool();
@@ -4,6 +4,7 @@
 //! their URI mappings
 
 use crate::config::{Config, GCloudConfig};
+use crate::custom_sql_types::WorkflowLanguageEnum;
 use crate::db;
 use crate::models::template::{
     NewTemplate, TemplateChangeset, TemplateData, TemplateQuery, UpdateError,
@@ -15,6 +16,7 @@ use crate::routes::multipart_handling;
 use crate::routes::util::parse_id;
 use crate::storage::gcloud_storage;
 use crate::util::wdl_storage::WdlStorageClient;
+use crate::validation::cwl;
 use crate::validation::womtool;
 use crate::validation::womtool::WomtoolRunner;
 use actix_multipart::Multipart;
@@ -22,7 +24,7 @@ use actix_web::{error::BlockingError, guard, HttpRequest, HttpResponse, Responde
 use diesel::r2d2::{ConnectionManager, PooledConnection};
 use diesel::PgConnection;
 use log::{debug, error};
-use serde_json::json;
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::fmt;
 use std::io::Cursor;
@@ -225,6 +227,11 @@ async fn create_from_json(
 
     let conn = pool.get().expect("Failed to get DB connection from pool");
 
+    // Templates are WDL unless they say otherwise
+    let workflow_language: WorkflowLanguageEnum = new_template
+        .workflow_language
+        .unwrap_or(WorkflowLanguageEnum::Wdl);
+
     // Store and validate the WDLs
     let (test_wdl_location, test_wdl_dependencies_location): (String, Option<String>) =
         validate_and_store_wdl(
@@ -235,6 +242,7 @@ async fn create_from_json(
             &new_template.test_wdl,
             new_template.test_wdl_dependencies.as_deref(),
             WdlType::Test,
+            workflow_language,
             &new_template.name,
         )
         .await?;
@@ -247,6 +255,7 @@ async fn create_from_json(
             &new_template.eval_wdl,
             new_template.eval_wdl_dependencies.as_deref(),
             WdlType::Eval,
+            workflow_language,
             &new_template.name,
         )
         .await?;
@@ -261,6 +270,9 @@ async fn create_from_json(
         eval_wdl: eval_wdl_location,
         eval_wdl_dependencies: eval_wdl_dependencies_location,
         created_by: new_template.created_by,
+        cromwell_server: new_template.cromwell_server,
+        workflow_option_defaults: new_template.workflow_option_defaults,
+        workflow_language: new_template.workflow_language,
     };
 
     create(req, new_new_template, conn).await
@@ -418,6 +430,7 @@ async fn update_from_json(
             test_wdl_data.as_deref(),
             test_wdl_dependency_data.as_deref(),
             WdlType::Test,
+            template_changes.workflow_language,
             id,
             &womtool_runner,
             &wdl_storage_client,
@@ -445,6 +458,7 @@ async fn update_from_json(
             eval_wdl_data.as_deref(),
             eval_wdl_dependency_data.as_deref(),
             WdlType::Eval,
+            template_changes.workflow_language,
             id,
             &womtool_runner,
             &wdl_storage_client,
@@ -823,7 +837,7 @@ async fn get_new_template_from_multipart(
     conn: &PgConnection,
 ) -> Result<NewTemplate, HttpResponse> {
     // The fields we expect from the multipart payload
-    const EXPECTED_TEXT_FIELDS: [&'static str; 8] = [
+    const EXPECTED_TEXT_FIELDS: [&'static str; 11] = [
         "name",
         "description",
         "pipeline_id",
@@ -832,6 +846,9 @@ async fn get_new_template_from_multipart(
         "test_wdl_dependencies",
         "eval_wdl",
         "eval_wdl_dependencies",
+        "cromwell_server",
+        "workflow_option_defaults",
+        "workflow_language",
     ];
     const EXPECTED_FILE_FIELDS: [&'static str; 4] = [
         "test_wdl_file",
@@ -883,6 +900,16 @@ async fn get_new_template_from_multipart(
     let name: String = text_data_map
         .remove("name")
         .expect("Failed to retrieve name from text_data_map.  This should not happen.");
+    // Parse the workflow option defaults before doing anything with the wdls, so we don't store
+    // them if the request is invalid
+    let workflow_option_defaults: Option<Value> =
+        multipart_handling::get_json_from_multipart_text_field(
+            &mut text_data_map,
+            "workflow_option_defaults",
+        )?;
+    // Same for the workflow language, which we need to know how to validate the wdls
+    let workflow_language: Option<WorkflowLanguageEnum> =
+        get_workflow_language_from_multipart_text_field(&mut text_data_map)?;
     // Make sure we have values for test wdl and eval wdl
     let test_wdl_contents: Vec<u8> = match get_wdl_data_from_multipart_maps(
         &mut text_data_map,
@@ -947,6 +974,7 @@ async fn get_new_template_from_multipart(
         &test_wdl_contents,
         test_wdl_dependency_data.as_deref(),
         WdlType::Test,
+        workflow_language.unwrap_or(WorkflowLanguageEnum::Wdl),
         &name,
     )
     .await?;
@@ -955,6 +983,7 @@ async fn get_new_template_from_multipart(
         &eval_wdl_contents,
         eval_wdl_dependency_data.as_deref(),
         WdlType::Eval,
+        workflow_language.unwrap_or(WorkflowLanguageEnum::Wdl),
         &name,
     )
     .await?;
@@ -998,9 +1027,42 @@ async fn get_new_template_from_multipart(
         eval_wdl: eval_wdl_location,
         eval_wdl_dependencies: eval_wdl_dependencies_location,
         created_by: text_data_map.remove("created_by"),
+        cromwell_server: text_data_map.remove("cromwell_server"),
+        workflow_option_defaults,
+        workflow_language,
     })
 }
 
+/// Attempts to remove the value for "workflow_language" from `text_data_map` and parse it as a
+/// WorkflowLanguageEnum.  Returns Ok(None) if there is no value, or an error response if the value
+/// is not a supported workflow language
+fn get_workflow_language_from_multipart_text_field(
+    text_data_map: &mut HashMap<String, String>,
+) -> Result<Option<WorkflowLanguageEnum>, HttpResponse> {
+    match text_data_map.remove("workflow_language") {
+        Some(workflow_language) => {
+            match serde_json::from_value(Value::String(workflow_language.clone())) {
+                Ok(workflow_language) => Ok(Some(workflow_language)),
+                Err(e) => {
+                    debug!(
+                        "Failed to parse workflow_language {} with error: {}",
+                        workflow_language, e
+                    );
+                    Err(HttpResponse::BadRequest().json(ErrorBody {
+                        title: "Invalid workflow_language".to_string(),
+                        status: 400,
+                        detail: format!(
+                            "Value for workflow_language must be either \"wdl\" or \"cwl\", but found \"{}\"",
+                            workflow_language
+                        ),
+                    }))
+                }
+            }
+        }
+        None => Ok(None),
+    }
+}
+
 /// Attempts to retrieve the value for `key` from `data_map`. If the value exists and starts with
 /// gs:// checks if that is allowed according to `gcloud_config`. If not, returns an error response.
 /// Otherwise, returns Ok(())
@@ -1031,13 +1093,16 @@ async fn get_template_changeset_from_multipart(
     conn: &PgConnection,
 ) -> Result<TemplateChangeset, HttpResponse> {
     // The fields we expect from the multipart payload
-    const EXPECTED_TEXT_FIELDS: [&'static str; 6] = [
+    const EXPECTED_TEXT_FIELDS: [&'static str; 9] = [
         "name",
         "description",
         "test_wdl",
         "test_wdl_dependencies",
         "eval_wdl",
         "eval_wdl_dependencies",
+        "cromwell_server",
+        "workflow_option_defaults",
+        "workflow_language",
     ];
     const EXPECTED_FILE_FIELDS: [&'static str; 4] = [
         "test_wdl_file",
@@ -1076,6 +1141,15 @@ async fn get_template_changeset_from_multipart(
         "eval_wdl_dependencies",
         gcloud_config,
     )?;
+    // Parse the workflow option defaults before doing anything with the wdls, so we don't store
+    // them if the request is invalid
+    let workflow_option_defaults: Option<Value> =
+        multipart_handling::get_json_from_multipart_text_field(
+            &mut text_data_map,
+            "workflow_option_defaults",
+        )?;
+    let workflow_language: Option<WorkflowLanguageEnum> =
+        get_workflow_language_from_multipart_text_field(&mut text_data_map)?;
 
     // Get test wdl and dependency data if present
     let test_wdl_data: Option<Vec<u8>> = get_wdl_data_from_multipart_maps(
@@ -1100,6 +1174,7 @@ async fn get_template_changeset_from_multipart(
             test_wdl_data.as_deref(),
             test_wdl_dependency_data.as_deref(),
             WdlType::Test,
+            workflow_language,
             template_id,
             womtool_runner,
             wdl_storage_client,
@@ -1130,6 +1205,7 @@ async fn get_template_changeset_from_multipart(
             eval_wdl_data.as_deref(),
             eval_wdl_dependency_data.as_deref(),
             WdlType::Eval,
+            workflow_language,
             template_id,
             womtool_runner,
             wdl_storage_client,
@@ -1147,6 +1223,9 @@ async fn get_template_changeset_from_multipart(
         test_wdl_dependencies: test_wdl_dependency_location,
         eval_wdl: eval_wdl_location,
         eval_wdl_dependencies: eval_wdl_dependency_location,
+        cromwell_server: text_data_map.remove("cromwell_server"),
+        workflow_option_defaults,
+        workflow_language,
     })
 }
 
@@ -1170,12 +1249,16 @@ async fn get_template_changeset_from_multipart(
 /// 4. If neither `wdl_data_opt` nor `wdl_dependency_data_opt` is provided, returns None for both
 ///    locations
 ///    (e.g. `Ok((None, None))`)
+///
+/// Validation is done according to `workflow_language` if it is provided (i.e. the language is also
+/// being updated), or the template's current language otherwise.
 /// In the case of any step in this process resulting in an error, an HttpResponse with an
 /// appropriate error message is returned
 async fn validate_and_store_wdl_and_dependencies_for_update(
     wdl_data_opt: Option<&[u8]>,
     wdl_dependency_data_opt: Option<&[u8]>,
     wdl_type: WdlType,
+    workflow_language: Option<WorkflowLanguageEnum>,
     template_id: Uuid,
     womtool_runner: &WomtoolRunner,
     wdl_storage_client: &WdlStorageClient,
@@ -1183,6 +1266,19 @@ async fn validate_and_store_wdl_and_dependencies_for_update(
     conn: &PgConnection,
     identifier: &str,
 ) -> Result<(Option<String>, Option<String>), HttpResponse> {
+    // If we have anything to validate, figure out which language to validate it as
+    let workflow_language: WorkflowLanguageEnum = match workflow_language {
+        Some(workflow_language) => workflow_language,
+        None if wdl_data_opt.is_some() || wdl_dependency_data_opt.is_some() => {
+            match TemplateData::find_by_id(conn, template_id) {
+                Ok(template) => template.workflow_language,
+                Err(e) => {
+                    return Err(default_500(&format!("Failed to retrieve template data for wdl validation with error: {}", e)));
+                }
+            }
+        }
+        None => WorkflowLanguageEnum::Wdl,
+    };
     // Attempt to validate and store whatever wdl and dependency data is provided. The process is
     // different depending on what combination of those two things we have
     let wdl_and_dependencies_locations: (Option<String>, Option<String>) = match wdl_data_opt {
@@ -1195,6 +1291,7 @@ async fn validate_and_store_wdl_and_dependencies_for_update(
                         wdl_data,
                         Some(wdl_dependency_data),
                         wdl_type,
+                        workflow_language,
                         identifier,
                     )
                     .await?;
@@ -1245,6 +1342,7 @@ async fn validate_and_store_wdl_and_dependencies_for_update(
                         wdl_data,
                         wdl_dependency_data.as_deref(),
                         wdl_type,
+                        workflow_language,
                         identifier,
                     )
                     .await?;
@@ -1280,6 +1378,7 @@ async fn validate_and_store_wdl_and_dependencies_for_update(
                         &wdl_data,
                         Some(wdl_dependency_data),
                         wdl_type,
+                        workflow_language,
                         identifier,
                     )
                     .await?;
@@ -1468,8 +1567,8 @@ async fn retrieve_resource(
 }
 
 /// Convenience function for downloading the wdl at `wdl_location`, validating it, storing it, and
-/// returning its stored location. `wdl_type` refers to whether the wdl is a test or eval wdl.
-/// `identifier` should be an identifier for the entity to which the wdl belongs (e.g. the
+/// returning its stored location. `wdl_type` refers to whether the wdl is a test or eval wdl, and
+/// `workflow_language` to the language it's written in. `identifier` should be an identifier for the entity to which the wdl belongs (e.g. the
 /// template's name or id)
 async fn validate_and_store_wdl(
     test_resource_client: &TestResourceClient,
@@ -1479,6 +1578,7 @@ async fn validate_and_store_wdl(
     wdl_location: &str,
     wdl_dependencies_location: Option<&str>,
     wdl_type: WdlType,
+    workflow_language: WorkflowLanguageEnum,
     identifier: &str,
 ) -> Result<(String, Option<String>), HttpResponse> {
     // Get the wdl contents from their location
@@ -1496,6 +1596,7 @@ async fn validate_and_store_wdl(
         &wdl_data,
         wdl_dependencies_data.as_deref(),
         wdl_type,
+        workflow_language,
         identifier,
     )
     .await?;
@@ -1521,13 +1622,34 @@ async fn validate_and_store_wdl(
 /// `identifier` (e.g. Submitted test WDL failed WDL validation).  If the validation errors out for
 /// some reason, returns a 500 response with a message explaining that the validation failed. Also
 /// returns an appropriate error response if writing the wdl data to a temp dir fails
+///
+/// If `workflow_language` is CWL, womtool is skipped and `wdl_data` is checked with
+/// [`cwl::cwl_validate`] instead
 async fn validate_wdl(
     womtool_runner: &WomtoolRunner,
     wdl_data: &[u8],
     wdl_dependency_data: Option<&[u8]>,
     wdl_type: WdlType,
+    workflow_language: WorkflowLanguageEnum,
     identifier: &str,
 ) -> Result<(), HttpResponse> {
+    // Womtool only understands WDL, so CWL gets its own validation
+    if workflow_language == WorkflowLanguageEnum::Cwl {
+        return cwl::cwl_validate(wdl_data).map_err(|e| {
+            debug!(
+                "Invalid {} CWL submitted for template {} with error {}",
+                wdl_type, identifier, e
+            );
+            HttpResponse::BadRequest().json(ErrorBody {
+                title: "Invalid CWL".to_string(),
+                status: 400,
+                detail: format!(
+                    "Submitted {} CWL failed CWL validation with message: {}",
+                    wdl_type, e
+                ),
+            })
+        });
+    }
     // Write the wdl and dependencies (if provided) to a temp dir to validate
     let (wdl_validation_dir, wdl_validation_file_path): (TempDir, PathBuf) =
         write_wdl_data_to_temp_dir(wdl_data, wdl_dependency_data)?;
@@ -1797,6 +1919,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline")
@@ -1814,6 +1939,9 @@ mod tests {
             eval_wdl: String::from("evalevaleval"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(conn, new_template).expect("Failed inserting test template")
@@ -1846,6 +1974,9 @@ mod tests {
             eval_wdl: String::from(eval_wdl_location),
             eval_wdl_dependencies,
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(conn, new_template).expect("Failed inserting test template")
@@ -1956,6 +2087,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(conn, new_test).expect("Failed inserting test test")
@@ -2284,6 +2419,9 @@ mod tests {
             eval_wdl: different_valid_wdl_address,
             eval_wdl_dependencies: Some(different_valid_wdl_deps_address),
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let req = test::TestRequest::post()
@@ -2612,6 +2750,9 @@ mod tests {
             eval_wdl: valid_wdl_address,
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let req = test::TestRequest::post()
@@ -2664,6 +2805,9 @@ mod tests {
             eval_wdl: invalid_wdl_address,
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let req = test::TestRequest::post()
@@ -2686,6 +2830,133 @@ mod tests {
         );
     }
 
+    #[actix_rt::test]
+    async fn create_success_cwl() {
+        // Set up config, test resource client, womtool runner, and wdl_storage_client which are needed for this mapping
+        let test_config = load_default_config();
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let wdl_storage_client =
+            WdlStorageClient::new_local(init_wdl_temp_dir().as_local().unwrap().clone());
+        let womtool_runner = WomtoolRunner::new(test_config.validation().womtool_location());
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool.clone())
+                .data(test_config)
+                .data(test_resource_client)
+                .data(womtool_runner)
+                .data(wdl_storage_client)
+                .configure(init_routes),
+        )
+        .await;
+
+        let pipeline = insert_test_pipeline(&pool.get().unwrap());
+
+        let test_cwl = read_to_string("testdata/validation/cwl/valid_workflow.cwl").unwrap();
+        let cwl_mock = mockito::mock("GET", "/test/cwl_resource")
+            .with_status(201)
+            .with_header("content_type", "text/plain")
+            .with_body(test_cwl)
+            .expect(2)
+            .create();
+        let cwl_address = format!("{}/test/cwl_resource", mockito::server_url());
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's CWL test"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin's CWL test description")),
+            test_wdl: cwl_address.clone(),
+            test_wdl_dependencies: None,
+            eval_wdl: cwl_address,
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: Some(WorkflowLanguageEnum::Cwl),
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/templates")
+            .set_json(&new_template)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        cwl_mock.assert();
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_template: TemplateData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_template.name, new_template.name);
+        assert_eq!(test_template.workflow_language, WorkflowLanguageEnum::Cwl);
+    }
+
+    #[actix_rt::test]
+    async fn create_failure_invalid_cwl() {
+        // Set up config, test resource client, and womtool runner which are needed for this mapping
+        let test_config = load_default_config();
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let wdl_storage_client =
+            WdlStorageClient::new_local(init_wdl_temp_dir().as_local().unwrap().clone());
+        let womtool_runner = WomtoolRunner::new(test_config.validation().womtool_location());
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool.clone())
+                .data(test_config)
+                .data(test_resource_client)
+                .data(womtool_runner)
+                .data(wdl_storage_client)
+                .configure(init_routes),
+        )
+        .await;
+
+        let pipeline = insert_test_pipeline(&pool.get().unwrap());
+
+        // A WDL isn't valid CWL
+        let test_wdl = read_to_string("testdata/routes/template/valid_wdl.wdl").unwrap();
+        let wdl_mock = mockito::mock("GET", "/test/wdl_as_cwl_resource")
+            .with_status(201)
+            .with_header("content_type", "text/plain")
+            .with_body(test_wdl)
+            .create();
+        let wdl_address = format!("{}/test/wdl_as_cwl_resource", mockito::server_url());
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's invalid CWL test"),
+            pipeline_id: pipeline.pipeline_id,
+            description: None,
+            test_wdl: wdl_address.clone(),
+            test_wdl_dependencies: None,
+            eval_wdl: wdl_address,
+            eval_wdl_dependencies: None,
+            created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: Some(WorkflowLanguageEnum::Cwl),
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/templates")
+            .set_json(&new_template)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        wdl_mock.assert();
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Invalid CWL");
+        assert_eq!(error_body.status, 400);
+    }
+
     #[actix_rt::test]
     async fn create_with_multipart_failure_invalid_linked_wdl() {
         // Set up config, test resource client, womtool runner, and wdl_storage_client which are needed for this mapping
@@ -2907,6 +3178,9 @@ mod tests {
             test_wdl_dependencies: Some(valid_wdl_deps_address),
             eval_wdl: Some(different_valid_wdl_address),
             eval_wdl_dependencies: Some(different_valid_wdl_deps_address),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let req = test::TestRequest::put()
@@ -3057,6 +3331,124 @@ mod tests {
         );
     }
 
+    #[actix_rt::test]
+    async fn update_with_multipart_success_workflow_option_defaults() {
+        // Set up config, test resource client, and womtool runner which are needed for this mapping
+        let test_config = load_default_config();
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let wdl_storage_client =
+            WdlStorageClient::new_local(init_wdl_temp_dir().as_local().unwrap().clone());
+        let womtool_runner = WomtoolRunner::new(test_config.validation().womtool_location());
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool.clone())
+                .data(test_config)
+                .data(test_resource_client)
+                .data(womtool_runner)
+                .data(wdl_storage_client)
+                .configure(init_routes),
+        )
+        .await;
+
+        let template = create_test_template(&pool.get().unwrap());
+
+        // Load the multipart body we'll send in the request
+        let multipart_body = read_to_string(
+            "testdata/routes/template/valid_update_multipart_workflow_option_defaults.txt",
+        )
+        .unwrap()
+        // Multipart needs carriage returns
+        .replace("\n", "\r\n");
+        let multipart_body_bytes = Bytes::from(multipart_body);
+
+        let content_length = multipart_body_bytes.len();
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/templates/{}", template.template_id))
+            .header("Content-Type", "multipart/form-data; boundary=\"---------------------------974767299852498929531610575\"")
+            .header("Content-Length", content_length)
+            .set_payload(multipart_body_bytes)
+            .to_request();
+
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_template: TemplateData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_template.name, "Updated template");
+        assert_eq!(test_template.cromwell_server, Some(String::from("hpc")));
+        assert_eq!(
+            test_template.workflow_option_defaults,
+            Some(json!({
+                "read_from_cache": false,
+                "final_workflow_outputs_dir": "gs://example/outputs"
+            }))
+        );
+    }
+
+    #[actix_rt::test]
+    async fn update_with_multipart_failure_invalid_workflow_option_defaults() {
+        // Set up config, test resource client, and womtool runner which are needed for this mapping
+        let test_config = load_default_config();
+        let test_resource_client = TestResourceClient::new(Client::default(), None);
+        let wdl_storage_client =
+            WdlStorageClient::new_local(init_wdl_temp_dir().as_local().unwrap().clone());
+        let womtool_runner = WomtoolRunner::new(test_config.validation().womtool_location());
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool.clone())
+                .data(test_config)
+                .data(test_resource_client)
+                .data(womtool_runner)
+                .data(wdl_storage_client)
+                .configure(init_routes),
+        )
+        .await;
+
+        let template = create_test_template(&pool.get().unwrap());
+
+        // Load the multipart body we'll send in the request
+        let multipart_body = read_to_string(
+            "testdata/routes/template/invalid_update_multipart_workflow_option_defaults.txt",
+        )
+        .unwrap()
+        // Multipart needs carriage returns
+        .replace("\n", "\r\n");
+        let multipart_body_bytes = Bytes::from(multipart_body);
+
+        let content_length = multipart_body_bytes.len();
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/templates/{}", template.template_id))
+            .header("Content-Type", "multipart/form-data; boundary=\"---------------------------974767299852498929531610575\"")
+            .header("Content-Length", content_length)
+            .set_payload(multipart_body_bytes)
+            .to_request();
+
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Invalid json");
+        assert_eq!(error_body.status, 400);
+
+        // The template shouldn't have changed
+        let stored_template =
+            TemplateData::find_by_id(&pool.get().unwrap(), template.template_id).unwrap();
+        assert_eq!(stored_template.name, template.name);
+        assert_eq!(stored_template.workflow_option_defaults, None);
+    }
+
     #[actix_rt::test]
     async fn update_failure_bad_uuid() {
         // Set up config, test resource client, and womtool runner which are needed for this mapping
@@ -3087,6 +3479,9 @@ mod tests {
             test_wdl_dependencies: None,
             eval_wdl: None,
             eval_wdl_dependencies: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let req = test::TestRequest::put()
@@ -3198,6 +3593,9 @@ mod tests {
             test_wdl_dependencies: None,
             eval_wdl: None,
             eval_wdl_dependencies: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let req = test::TestRequest::put()
@@ -3311,6 +3709,9 @@ mod tests {
             test_wdl_dependencies: None,
             eval_wdl: None,
             eval_wdl_dependencies: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let req = test::TestRequest::put()
@@ -3422,6 +3823,9 @@ mod tests {
             test_wdl_dependencies: None,
             eval_wdl: Some(invalid_wdl_address),
             eval_wdl_dependencies: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let req = test::TestRequest::put()
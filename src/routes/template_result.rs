@@ -3,15 +3,22 @@
 //! Contains functions for processing requests to create, update, and search template_result
 //! mappings, along with their URI mappings
 
+use crate::custom_sql_types::{ResultTypeEnum, WorkflowLanguageEnum};
 use crate::db;
+use crate::models::result::{NewResult, ResultData};
+use crate::models::template::TemplateData;
 use crate::models::template_result::{
     DeleteError, NewTemplateResult, TemplateResultData, TemplateResultQuery,
 };
+use crate::requests::test_resource_requests::TestResourceClient;
 use crate::routes::error_handling::{default_500, ErrorBody};
+use crate::validation::wdl_inputs;
+use crate::validation::womtool::WomtoolRunner;
 use actix_web::{error::BlockingError, web, HttpRequest, HttpResponse, Responder};
+use diesel::PgConnection;
 use log::error;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Map, Value};
 use uuid::Uuid;
 
 /// Represents the part of a new template_result mapping that is received as a request body
@@ -26,6 +33,37 @@ struct NewTemplateResultIncomplete {
     pub created_by: Option<String>,
 }
 
+/// Represents the part of a request for generating template_result mappings that is received as a
+/// request body
+#[derive(Deserialize, Serialize)]
+struct GenerateTemplateResultsIncomplete {
+    pub created_by: Option<String>,
+}
+
+/// A template_result mapping created for an output of a template's eval WDL, along with the result
+/// it maps to and whether that result was created too or already existed
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct GeneratedTemplateResult {
+    pub result: ResultData,
+    pub template_result: TemplateResultData,
+    pub result_created: bool,
+}
+
+/// An output of a template's eval WDL that a template_result mapping was not created for, and why
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct SkippedTemplateResult {
+    pub result_key: String,
+    pub reason: String,
+}
+
+/// Represents the outcome of generating template_result mappings for a template: the mappings that
+/// were created and the outputs that were skipped
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+struct GenerateTemplateResultsResult {
+    pub created: Vec<GeneratedTemplateResult>,
+    pub skipped: Vec<SkippedTemplateResult>,
+}
+
 /// Handles requests to /templates/{id}/results/{result_id} for retrieving template_result mapping
 /// info by template_id and result_id
 ///
@@ -248,6 +286,251 @@ async fn create(
     })
 }
 
+/// Handles requests to /templates/{id}/results/generate for creating template_result mappings for
+/// each of the outputs of a template's eval WDL
+///
+/// This function is called by Actix-Web when a post request is made to the
+/// /templates/{id}/results/generate mapping
+/// It parses the id from `req`, retrieves the template's eval WDL with `test_resource_client`, gets
+/// its outputs with `womtool_runner`, and then, for each output that isn't already mapped, maps it
+/// to a result named for the output (creating the result if there isn't one).  The result type is
+/// inferred from the output type: File for `File` outputs, Numeric for `Int` and `Float` outputs,
+/// and Text for anything else.  Returns the mappings that were created and the outputs that were
+/// skipped (with the reason), or an error message if some error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn generate(
+    req: HttpRequest,
+    request_body: Option<web::Json<GenerateTemplateResultsIncomplete>>,
+    pool: web::Data<db::DbPool>,
+    test_resource_client: web::Data<TestResourceClient>,
+    womtool_runner: web::Data<WomtoolRunner>,
+) -> HttpResponse {
+    // Pull id param from path
+    let id = &req.match_info().get("id").unwrap();
+
+    // Parse ID into Uuid
+    let id = match Uuid::parse_str(id) {
+        Ok(id) => id,
+        Err(e) => {
+            error!("{}", e);
+            // If it doesn't parse successfully, return an error to the user
+            return HttpResponse::BadRequest().json(ErrorBody {
+                title: "ID formatted incorrectly".to_string(),
+                status: 400,
+                detail: "ID must be formatted as a Uuid".to_string(),
+            });
+        }
+    };
+
+    // The body is optional, since created_by is the only thing in it
+    let created_by: Option<String> = match request_body {
+        Some(web::Json(request_body)) => request_body.created_by,
+        None => None,
+    };
+
+    // Get the template so we can get its eval wdl
+    let template_pool = pool.clone();
+    let template = match web::block(move || {
+        let conn = template_pool
+            .get()
+            .expect("Failed to get DB connection from pool");
+        TemplateData::find_by_id(&conn, id)
+    })
+    .await
+    {
+        Ok(template) => template,
+        Err(BlockingError::Error(diesel::result::Error::NotFound)) => {
+            return HttpResponse::NotFound().json(ErrorBody {
+                title: "No template found".to_string(),
+                status: 404,
+                detail: "No template found with the specified ID".to_string(),
+            });
+        }
+        Err(e) => {
+            error!("{}", e);
+            return default_500(&e);
+        }
+    };
+
+    // Womtool can't get outputs from CWL
+    if template.workflow_language == WorkflowLanguageEnum::Cwl {
+        return HttpResponse::BadRequest().json(ErrorBody {
+            title: "Unsupported workflow language".to_string(),
+            status: 400,
+            detail: "Generating template_result mappings is only supported for templates with WDL workflows".to_string(),
+        });
+    }
+
+    // Get the outputs of the eval wdl
+    let eval_outputs: Map<String, Value> = match wdl_inputs::get_wdl_outputs(
+        &womtool_runner,
+        &test_resource_client,
+        &template.eval_wdl,
+        template.eval_wdl_dependencies.as_deref(),
+    )
+    .await
+    {
+        Ok(eval_outputs) => eval_outputs,
+        Err(e) => {
+            error!("{}", e);
+            return HttpResponse::InternalServerError().json(ErrorBody {
+                title: "Server error".to_string(),
+                status: 500,
+                detail: format!(
+                    "Error while attempting to get the outputs of the eval WDL: {}",
+                    e
+                ),
+            });
+        }
+    };
+
+    // Create the mappings in new thread
+    web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        generate_template_results(&conn, &template, &eval_outputs, created_by)
+    })
+    .await
+    // If there is no error, return a response with the created and skipped mappings
+    .map(|results| HttpResponse::Ok().json(results))
+    .unwrap_or_else(|e| {
+        error!("{}", e);
+        // For any errors, return a 500
+        default_500(&e)
+    })
+}
+
+/// Creates a template_result mapping for each output in `eval_outputs` (a map of output names to
+/// their types, as returned by womtool outputs) for `template`, creating results for them if
+/// necessary
+///
+/// Each output is mapped to the result with the same name as the output (without the workflow
+/// name), which is created with a type inferred from the output's type if it doesn't exist.
+/// Outputs are skipped if they are already mapped, if an existing result with their name has a
+/// different type, or if that result is already mapped to `template` under a different key.
+/// Returns the created mappings and skipped outputs, or an error if a DB operation fails
+fn generate_template_results(
+    conn: &PgConnection,
+    template: &TemplateData,
+    eval_outputs: &Map<String, Value>,
+    created_by: Option<String>,
+) -> Result<GenerateTemplateResultsResult, diesel::result::Error> {
+    // Get the existing mappings for this template so we know what to skip
+    let existing_template_results: Vec<TemplateResultData> = TemplateResultData::find(
+        conn,
+        TemplateResultQuery {
+            template_id: Some(template.template_id),
+            result_id: None,
+            result_key: None,
+            created_before: None,
+            created_after: None,
+            created_by: None,
+            sort: None,
+            limit: None,
+            offset: None,
+        },
+    )?;
+
+    let mut created: Vec<GeneratedTemplateResult> = Vec::new();
+    let mut skipped: Vec<SkippedTemplateResult> = Vec::new();
+
+    for (output_key, output_type) in eval_outputs {
+        // Skip the output if it's already mapped
+        if let Some(existing_template_result) = existing_template_results
+            .iter()
+            .find(|template_result| &template_result.result_key == output_key)
+        {
+            skipped.push(SkippedTemplateResult {
+                result_key: output_key.clone(),
+                reason: format!(
+                    "Output is already mapped to result with id {}",
+                    existing_template_result.result_id
+                ),
+            });
+            continue;
+        }
+        let result_type: ResultTypeEnum = infer_result_type(output_type.as_str().unwrap_or(""));
+        // Name the result after the output, without the workflow name
+        let result_name: &str = match output_key.rfind('.') {
+            Some(index) => &output_key[index + 1..],
+            None => output_key,
+        };
+        // Use the result with that name if there is one, or make a new one if not
+        let (result, result_created) = match ResultData::find_by_name(conn, result_name) {
+            Ok(existing_result) => {
+                if existing_result.result_type != result_type {
+                    skipped.push(SkippedTemplateResult {
+                        result_key: output_key.clone(),
+                        reason: format!(
+                            "A result named {} already exists with type {}, but the output's type is {}",
+                            result_name, existing_result.result_type, result_type
+                        ),
+                    });
+                    continue;
+                }
+                if let Some(existing_template_result) = existing_template_results
+                    .iter()
+                    .find(|template_result| template_result.result_id == existing_result.result_id)
+                {
+                    skipped.push(SkippedTemplateResult {
+                        result_key: output_key.clone(),
+                        reason: format!(
+                            "Result named {} is already mapped to this template with result_key {}",
+                            result_name, existing_template_result.result_key
+                        ),
+                    });
+                    continue;
+                }
+                (existing_result, false)
+            }
+            Err(diesel::result::Error::NotFound) => (
+                ResultData::create(
+                    conn,
+                    NewResult {
+                        name: String::from(result_name),
+                        result_type,
+                        description: Some(format!(
+                            "Generated from output {} of the eval WDL for template {}",
+                            output_key, template.name
+                        )),
+                        created_by: created_by.clone(),
+                    },
+                )?,
+                true,
+            ),
+            Err(e) => return Err(e),
+        };
+        let template_result = TemplateResultData::create(
+            conn,
+            NewTemplateResult {
+                template_id: template.template_id,
+                result_id: result.result_id,
+                result_key: output_key.clone(),
+                created_by: created_by.clone(),
+            },
+        )?;
+        created.push(GeneratedTemplateResult {
+            result,
+            template_result,
+            result_created,
+        });
+    }
+
+    Ok(GenerateTemplateResultsResult { created, skipped })
+}
+
+/// Returns the result type to use for a WDL output with the type `output_type` (as described by
+/// womtool outputs): File for `File`, Numeric for `Int` and `Float`, and Text for anything else
+fn infer_result_type(output_type: &str) -> ResultTypeEnum {
+    match output_type.trim_end_matches('?') {
+        "File" => ResultTypeEnum::File,
+        "Int" | "Float" => ResultTypeEnum::Numeric,
+        _ => ResultTypeEnum::Text,
+    }
+}
+
 /// Handles DELETE requests to /templates/{id}/results/{result_id} for deleting template_result
 /// mappings
 ///
@@ -340,6 +623,9 @@ async fn delete_by_id(req: HttpRequest, pool: web::Data<db::DbPool>) -> impl Res
 /// To be called when configuring the Actix-Web app service.  Registers the mappings in this file
 /// as part of the service defined in `cfg`
 pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    // This has to be registered before /templates/{id}/results/{result_id} so "generate" isn't
+    // parsed as a result_id
+    cfg.service(web::resource("/templates/{id}/results/generate").route(web::post().to(generate)));
     cfg.service(
         web::resource("/templates/{id}/results/{result_id}")
             .route(web::get().to(find_by_id))
@@ -356,12 +642,14 @@ mod tests {
     use crate::models::pipeline::{NewPipeline, PipelineData};
     use crate::models::result::{NewResult, ResultData};
     use crate::models::run::{NewRun, RunData};
-    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::template::{NewTemplate, TemplateChangeset, TemplateData};
     use crate::models::test::{NewTest, TestData};
     use crate::unit_test_util::*;
+    use actix_web::client::Client;
     use actix_web::{http, test, App};
     use diesel::PgConnection;
     use serde_json::Value;
+    use std::fs::read_to_string;
     use uuid::Uuid;
 
     fn create_test_template_and_result(conn: &PgConnection) -> (TemplateData, ResultData) {
@@ -369,6 +657,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -383,6 +674,9 @@ mod tests {
             eval_wdl: String::from("evaltest"),
             eval_wdl_dependencies: None,
             created_by: Some(String::from("Kevin2@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template =
@@ -424,6 +718,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(conn, new_test).expect("Failed inserting test test")
@@ -663,6 +961,191 @@ mod tests {
         assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
     }
 
+    #[actix_rt::test]
+    async fn generate_success() {
+        let pool = get_test_db_pool();
+
+        let (template, _) = create_test_template_and_result(&pool.get().unwrap());
+        // Point the template at a mock eval wdl
+        let template = TemplateData::update(
+            &pool.get().unwrap(),
+            template.template_id,
+            TemplateChangeset {
+                name: None,
+                description: None,
+                test_wdl: None,
+                test_wdl_dependencies: None,
+                eval_wdl: Some(format!("{}/eval", mockito::server_url())),
+                eval_wdl_dependencies: None,
+                cromwell_server: None,
+                workflow_option_defaults: None,
+                workflow_language: None,
+            },
+        )
+        .unwrap();
+        // A result that matches one of the outputs, so it should be reused
+        let count_result = ResultData::create(
+            &pool.get().unwrap(),
+            NewResult {
+                name: String::from("out_count"),
+                result_type: ResultTypeEnum::Numeric,
+                description: None,
+                created_by: None,
+            },
+        )
+        .unwrap();
+        // A result with the name of one of the outputs but the wrong type, so that output should
+        // be skipped
+        ResultData::create(
+            &pool.get().unwrap(),
+            NewResult {
+                name: String::from("out_greeting"),
+                result_type: ResultTypeEnum::File,
+                description: None,
+                created_by: None,
+            },
+        )
+        .unwrap();
+
+        // Define mockito mapping for the eval wdl
+        let eval_wdl_mock = mockito::mock("GET", "/eval")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/template_result/eval_wdl.wdl").unwrap())
+            .create();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!(
+                "/templates/{}/results/generate",
+                template.template_id
+            ))
+            .set_json(&GenerateTemplateResultsIncomplete {
+                created_by: Some(String::from("Kevin@example.com")),
+            })
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        eval_wdl_mock.assert();
+
+        let result = test::read_body(resp).await;
+        let generate_result: GenerateTemplateResultsResult =
+            serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(generate_result.created.len(), 2);
+        let count_mapping = generate_result
+            .created
+            .iter()
+            .find(|created| created.template_result.result_key == "eval_workflow.out_count")
+            .expect("Missing mapping for eval_workflow.out_count");
+        assert_eq!(count_mapping.result, count_result);
+        assert!(!count_mapping.result_created);
+        let file_mapping = generate_result
+            .created
+            .iter()
+            .find(|created| created.template_result.result_key == "eval_workflow.out_file")
+            .expect("Missing mapping for eval_workflow.out_file");
+        assert_eq!(file_mapping.result.name, "out_file");
+        assert_eq!(file_mapping.result.result_type, ResultTypeEnum::File);
+        assert_eq!(
+            file_mapping.template_result.result_id,
+            file_mapping.result.result_id
+        );
+        assert_eq!(
+            file_mapping.template_result.created_by,
+            Some(String::from("Kevin@example.com"))
+        );
+        assert!(file_mapping.result_created);
+
+        assert_eq!(
+            generate_result.skipped,
+            vec![SkippedTemplateResult {
+                result_key: String::from("eval_workflow.out_greeting"),
+                reason: String::from("A result named out_greeting already exists with type file, but the output's type is text"),
+            }]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn generate_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/templates/{}/results/generate", Uuid::new_v4()))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No template found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.detail, "No template found with the specified ID");
+    }
+
+    #[actix_rt::test]
+    async fn generate_failure_bad_uuid() {
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        let req = test::TestRequest::post()
+            .uri("/templates/123456789/results/generate")
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "ID formatted incorrectly");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
+    }
+
+    #[test]
+    fn test_infer_result_type() {
+        assert_eq!(infer_result_type("File"), ResultTypeEnum::File);
+        assert_eq!(infer_result_type("File?"), ResultTypeEnum::File);
+        assert_eq!(infer_result_type("Int"), ResultTypeEnum::Numeric);
+        assert_eq!(infer_result_type("Float"), ResultTypeEnum::Numeric);
+        assert_eq!(infer_result_type("String"), ResultTypeEnum::Text);
+        assert_eq!(infer_result_type("Array[File]"), ResultTypeEnum::Text);
+    }
+
     #[actix_rt::test]
     async fn delete_success() {
         let pool = get_test_db_pool();
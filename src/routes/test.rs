@@ -4,13 +4,41 @@
 //! their URI mappings
 
 use crate::db;
+use crate::models::template::TemplateData;
 use crate::models::test::{NewTest, TestChangeset, TestData, TestQuery, UpdateError};
+use crate::requests::test_resource_requests::TestResourceClient;
 use crate::routes::error_handling::{default_500, ErrorBody};
+use crate::routes::util::wdl_inputs_error_to_error_body;
+use crate::validation::wdl_inputs;
+use crate::validation::wdl_inputs::ReferenceProblem;
+use crate::validation::womtool::WomtoolRunner;
+use actix_web::dev::HttpResponseBuilder;
+use actix_web::http::StatusCode;
 use actix_web::{error::BlockingError, web, HttpRequest, HttpResponse, Responder};
 use log::error;
-use serde_json::json;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use uuid::Uuid;
 
+/// Represents the set of possible query parameters that can be received by the create and update
+/// mappings
+#[derive(Deserialize, Serialize)]
+struct InputValidationQueryParams {
+    skip_input_validation: Option<bool>,
+}
+
+/// Body for the error response returned when test inputs contain references that can't be resolved
+///
+/// Has the same fields as an ErrorBody, plus `problems`, which lists each reference that can't be
+/// resolved and why
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct InvalidReferencesErrorBody {
+    title: String,
+    status: u16,
+    detail: String,
+    problems: Vec<ReferenceProblem>,
+}
+
 /// Handles requests to /tests/{id} for retrieving test info by test_id
 ///
 /// This function is called by Actix-Web when a get request is made to the /tests/{id} mapping
@@ -120,14 +148,49 @@ async fn find(
 /// This function is called by Actix-Web when a post request is made to the /tests mapping
 /// It deserializes the request body to a NewTest, connects to the db via a connection from
 /// `pool`, creates a test with the specified parameters, and returns the created test, or
-/// an error message if creating the test fails for some reason
+/// an error message if creating the test fails for some reason.  Before creating the test, its
+/// input defaults are checked against the inputs of its template's WDLs using `womtool_runner`
+/// and the references in them are checked, unless the skip_input_validation query param is true
 ///
 /// # Panics
 /// Panics if attempting to connect to the database results in an error
 async fn create(
+    web::Query(query_params): web::Query<InputValidationQueryParams>,
     web::Json(new_test): web::Json<NewTest>,
     pool: web::Data<db::DbPool>,
+    test_resource_client: web::Data<TestResourceClient>,
+    womtool_runner: web::Data<WomtoolRunner>,
 ) -> impl Responder {
+    // Make sure the max runtime makes sense
+    if let Err(error_response) = validate_max_runtime(new_test.max_runtime_in_secs) {
+        return Ok(error_response);
+    }
+
+    // Check the input defaults against the template's WDLs, unless we've been told not to
+    if query_params.skip_input_validation != Some(true) {
+        let template_id = new_test.template_id;
+        let template_pool = pool.clone();
+        let template_result = web::block(move || {
+            let conn = template_pool
+                .get()
+                .expect("Failed to get DB connection from pool");
+            TemplateData::find_by_id(&conn, template_id)
+        })
+        .await;
+        if let Err(error_response) = validate_input_defaults(
+            template_result,
+            new_test.test_input_defaults.as_ref(),
+            new_test.eval_input_defaults.as_ref(),
+            &pool,
+            &test_resource_client,
+            &womtool_runner,
+        )
+        .await
+        {
+            return Ok(error_response);
+        }
+    }
+
     //Insert in new thread
     web::block(move || {
         let conn = pool.get().expect("Failed to get DB connection from pool");
@@ -155,14 +218,19 @@ async fn create(
 /// This function is called by Actix-Web when a put request is made to the /tests/{id} mapping
 /// It deserializes the request body to a TestChangeset, connects to the db via a connection
 /// from `pool`, updates the specified test, and returns the updated test or an error
-/// message if some error occurs
+/// message if some error occurs.  If the input defaults are being updated, they are first checked
+/// against the inputs of the test's template's WDLs using `womtool_runner` and the references in
+/// them are checked, unless the skip_input_validation query param is true
 ///
 /// # Panics
 /// Panics if attempting to connect to the database results in an error
 async fn update(
     id: web::Path<String>,
+    web::Query(query_params): web::Query<InputValidationQueryParams>,
     web::Json(test_changes): web::Json<TestChangeset>,
     pool: web::Data<db::DbPool>,
+    test_resource_client: web::Data<TestResourceClient>,
+    womtool_runner: web::Data<WomtoolRunner>,
 ) -> impl Responder {
     //Parse ID into Uuid
     let id = match Uuid::parse_str(&*id) {
@@ -178,6 +246,39 @@ async fn update(
         }
     };
 
+    // Make sure the max runtime makes sense
+    if let Err(error_response) = validate_max_runtime(test_changes.max_runtime_in_secs) {
+        return Ok(error_response);
+    }
+
+    // Check any new input defaults against the template's WDLs, unless we've been told not to
+    if query_params.skip_input_validation != Some(true)
+        && (test_changes.test_input_defaults.is_some()
+            || test_changes.eval_input_defaults.is_some())
+    {
+        let template_pool = pool.clone();
+        let template_result = web::block(move || {
+            let conn = template_pool
+                .get()
+                .expect("Failed to get DB connection from pool");
+            let test = TestData::find_by_id(&conn, id)?;
+            TemplateData::find_by_id(&conn, test.template_id)
+        })
+        .await;
+        if let Err(error_response) = validate_input_defaults(
+            template_result,
+            test_changes.test_input_defaults.as_ref(),
+            test_changes.eval_input_defaults.as_ref(),
+            &pool,
+            &test_resource_client,
+            &womtool_runner,
+        )
+        .await
+        {
+            return Ok(error_response);
+        }
+    }
+
     //Update in new thread
     web::block(move || {
         let conn = pool.get().expect("Failed to get DB connection from pool");
@@ -208,6 +309,94 @@ async fn update(
     })
 }
 
+/// Checks `test_inputs` and `eval_inputs` against the inputs of the WDLs for the template in
+/// `template_result`, and checks that the `test_output:` and `image_build:` references in them can
+/// be resolved
+///
+/// Required inputs are not checked, since they can be supplied when a run is started.  If the
+/// template could not be found, the check is skipped so the missing template can be reported by
+/// the operation that follows.  Returns an error response if the inputs do not match, if there are
+/// references that can't be resolved (with a list of the problems with them), or if there is an
+/// error retrieving the template or checking the inputs
+async fn validate_input_defaults(
+    template_result: Result<TemplateData, BlockingError<diesel::result::Error>>,
+    test_inputs: Option<&Value>,
+    eval_inputs: Option<&Value>,
+    pool: &db::DbPool,
+    test_resource_client: &TestResourceClient,
+    womtool_runner: &WomtoolRunner,
+) -> Result<(), HttpResponse> {
+    let template = match template_result {
+        Ok(template) => template,
+        Err(BlockingError::Error(diesel::result::Error::NotFound)) => return Ok(()),
+        Err(e) => {
+            error!("{}", e);
+            return Err(default_500(&e));
+        }
+    };
+    let conn = pool.get().expect("Failed to get DB connection from pool");
+    let validation_result = match wdl_inputs::validate_inputs_for_template(
+        womtool_runner,
+        test_resource_client,
+        &template,
+        test_inputs,
+        eval_inputs,
+        false,
+    )
+    .await
+    {
+        Ok(_) => {
+            wdl_inputs::validate_references_for_template(
+                &conn,
+                womtool_runner,
+                test_resource_client,
+                &template,
+                test_inputs,
+                eval_inputs,
+            )
+            .await
+        }
+        Err(e) => Err(e),
+    };
+    match validation_result {
+        Ok(_) => Ok(()),
+        Err(wdl_inputs::Error::InvalidReferences(problems)) => {
+            error!("Found invalid references in inputs: {:?}", problems);
+            Err(HttpResponse::BadRequest().json(InvalidReferencesErrorBody {
+                title: "Invalid references".to_string(),
+                status: 400,
+                detail: "Some test_output or image_build references in the inputs cannot be resolved (to skip this check, set skip_input_validation=true)".to_string(),
+                problems,
+            }))
+        }
+        Err(e) => {
+            error!("{}", e);
+            let error_body = wdl_inputs_error_to_error_body(e);
+            Err(HttpResponseBuilder::new(
+                StatusCode::from_u16(error_body.status)
+                    .expect("Failed to parse status code. This shouldn't happen"),
+            )
+            .json(error_body))
+        }
+    }
+}
+
+/// Checks that `max_runtime_in_secs`, if specified, is greater than 0, since no workflow could
+/// finish within a max runtime of 0 (or fewer) seconds.  Returns a BadRequest response if not
+fn validate_max_runtime(max_runtime_in_secs: Option<i32>) -> Result<(), HttpResponse> {
+    match max_runtime_in_secs {
+        Some(max_runtime) if max_runtime <= 0 => Err(HttpResponse::BadRequest().json(ErrorBody {
+            title: "Invalid max runtime".to_string(),
+            status: 400,
+            detail: format!(
+                "max_runtime_in_secs must be greater than 0, but was {}",
+                max_runtime
+            ),
+        })),
+        _ => Ok(()),
+    }
+}
+
 /// Handles DELETE requests to /tests/{id} for deleting test rows by test_id
 ///
 /// This function is called by Actix-Web when a delete request is made to the /tests/{id}
@@ -271,7 +460,8 @@ async fn delete_by_id(req: HttpRequest, pool: web::Data<db::DbPool>) -> impl Res
             )) => HttpResponse::Forbidden().json(ErrorBody {
                 title: "Cannot delete".to_string(),
                 status: 403,
-                detail: "Cannot delete a test if there are runs mapped to it".to_string(),
+                detail: "Cannot delete a test if there are runs or schedules mapped to it"
+                    .to_string(),
             }),
             // For other errors, return a 500
             _ => default_500(&e),
@@ -305,10 +495,12 @@ mod tests {
     use crate::models::run::{NewRun, RunData};
     use crate::models::template::{NewTemplate, TemplateData};
     use crate::unit_test_util::*;
+    use actix_web::client::Client;
     use actix_web::{http, test, App};
     use chrono::Utc;
     use diesel::PgConnection;
     use serde_json::{json, Value};
+    use std::fs::read_to_string;
     use uuid::Uuid;
 
     fn create_test_test(conn: &PgConnection) -> TestData {
@@ -316,6 +508,9 @@ mod tests {
             name: String::from("Kevin's Pipeline 2"),
             description: Some(String::from("Kevin made this pipeline for testing 2")),
             created_by: Some(String::from("Kevin2@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -330,6 +525,9 @@ mod tests {
             eval_wdl: format!("{}/eval", mockito::server_url()),
             eval_wdl_dependencies: None,
             created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         let template = TemplateData::create(&conn, new_template).expect("Failed to insert test");
@@ -343,6 +541,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
             eval_option_defaults: Some(serde_json::from_str("{\"eval_option\":\"test\"}").unwrap()),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         TestData::create(conn, new_test).expect("Failed inserting test test")
@@ -353,6 +555,9 @@ mod tests {
             name: String::from("Kevin's Pipeline"),
             description: Some(String::from("Kevin made this pipeline for testing")),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
         };
 
         let pipeline =
@@ -367,6 +572,9 @@ mod tests {
             eval_wdl: format!("{}/eval", mockito::server_url()),
             eval_wdl_dependencies: None,
             created_by: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
         };
 
         TemplateData::create(&conn, new_template).expect("Failed to insert test")
@@ -613,7 +821,16 @@ mod tests {
 
         let template = create_test_template(&pool.get().unwrap());
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
 
         let new_test = NewTest {
             name: String::from("Kevin's test"),
@@ -628,10 +845,14 @@ mod tests {
                 serde_json::from_str("{\"eval_option\":\"test2\"}").unwrap(),
             ),
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let req = test::TestRequest::post()
-            .uri("/tests")
+            .uri("/tests?skip_input_validation=true")
             .set_json(&new_test)
             .to_request();
         let resp = test::call_service(&mut app, req).await;
@@ -681,13 +902,73 @@ mod tests {
         );
     }
 
+    #[actix_rt::test]
+    async fn create_failure_invalid_max_runtime() {
+        let pool = get_test_db_pool();
+
+        let template = create_test_template(&pool.get().unwrap());
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        let new_test = NewTest {
+            name: String::from("Kevin's test"),
+            template_id: template.template_id,
+            description: None,
+            test_input_defaults: None,
+            test_option_defaults: None,
+            eval_input_defaults: None,
+            eval_option_defaults: None,
+            created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: Some(0),
+            cromwell_server: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/tests?skip_input_validation=true")
+            .set_json(&new_test)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Invalid max runtime");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(
+            error_body.detail,
+            "max_runtime_in_secs must be greater than 0, but was 0"
+        );
+    }
+
     #[actix_rt::test]
     async fn create_failure() {
         let pool = get_test_db_pool();
 
         let test = create_test_test(&pool.get().unwrap());
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
 
         let new_test = NewTest {
             name: test.name.clone(),
@@ -698,6 +979,10 @@ mod tests {
             eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test2\"}").unwrap()),
             eval_option_defaults: None,
             created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let req = test::TestRequest::post()
@@ -716,6 +1001,155 @@ mod tests {
         assert_eq!(error_body.status, 500);
     }
 
+    #[actix_rt::test]
+    async fn create_failure_invalid_inputs() {
+        let pool = get_test_db_pool();
+
+        let template = create_test_template(&pool.get().unwrap());
+
+        // Define mockito mappings for the wdls
+        let test_wdl_mock = mockito::mock("GET", "/test")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/test/test_wdl.wdl").unwrap())
+            .create();
+        let eval_wdl_mock = mockito::mock("GET", "/eval")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/test/eval_wdl.wdl").unwrap())
+            .create();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        let new_test = NewTest {
+            name: String::from("Kevin's test"),
+            template_id: template.template_id,
+            description: None,
+            test_input_defaults: Some(json!({"greeting_workflow.in_greeted": ["Kevin"]})),
+            test_option_defaults: None,
+            eval_input_defaults: Some(json!({"greeting_workflow.not_an_input": "test"})),
+            eval_option_defaults: None,
+            created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/tests")
+            .set_json(&new_test)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        test_wdl_mock.assert();
+        eval_wdl_mock.assert();
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Invalid inputs");
+        assert_eq!(error_body.status, 400);
+        assert!(error_body.detail.contains(
+            "Value for test WDL input greeting_workflow.in_greeted should be String, but found [\"Kevin\"]"
+        ));
+        assert!(error_body
+            .detail
+            .contains("eval WDL has no input named greeting_workflow.not_an_input"));
+    }
+
+    #[actix_rt::test]
+    async fn create_failure_invalid_references() {
+        let pool = get_test_db_pool();
+
+        let template = create_test_template(&pool.get().unwrap());
+
+        // Define mockito mappings for the wdls (the test wdl is retrieved once for checking inputs
+        // and once for checking test_output references)
+        let test_wdl_mock = mockito::mock("GET", "/test")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/test/test_wdl.wdl").unwrap())
+            .expect(2)
+            .create();
+        let eval_wdl_mock = mockito::mock("GET", "/eval")
+            .with_status(200)
+            .with_body(read_to_string("testdata/routes/test/eval_wdl.wdl").unwrap())
+            .create();
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        let new_test = NewTest {
+            name: String::from("Kevin's test"),
+            template_id: template.template_id,
+            description: None,
+            test_input_defaults: Some(
+                json!({"greeting_workflow.in_greeted": "image_build:NotSoftware|1a4c5eb5"}),
+            ),
+            test_option_defaults: None,
+            eval_input_defaults: Some(
+                json!({"greeting_file_workflow.in_greeting": "test_output:greeting_workflow.out_greting"}),
+            ),
+            eval_option_defaults: None,
+            created_by: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri("/tests")
+            .set_json(&new_test)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+        test_wdl_mock.assert();
+        eval_wdl_mock.assert();
+
+        let result = test::read_body(resp).await;
+        let error_body: InvalidReferencesErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Invalid references");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(
+            error_body.problems,
+            vec![
+                ReferenceProblem {
+                    wdl: String::from("test WDL"),
+                    input: String::from("greeting_workflow.in_greeted"),
+                    reference: String::from("image_build:NotSoftware|1a4c5eb5"),
+                    problem: String::from("No software exists with name NotSoftware"),
+                },
+                ReferenceProblem {
+                    wdl: String::from("eval WDL"),
+                    input: String::from("greeting_file_workflow.in_greeting"),
+                    reference: String::from("test_output:greeting_workflow.out_greting"),
+                    problem: String::from(
+                        "test WDL has no output named greeting_workflow.out_greting (outputs are: greeting_workflow.out_greeting)"
+                    ),
+                },
+            ]
+        );
+    }
+
     #[actix_rt::test]
     async fn update_success() {
         let pool = get_test_db_pool();
@@ -723,7 +1157,16 @@ mod tests {
         let test = create_test_test(&pool.get().unwrap());
         insert_failed_test_runs_with_test_id(&pool.get().unwrap(), test.test_id);
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
 
         let test_change = TestChangeset {
             name: Some(String::from("Kevin's test change")),
@@ -732,10 +1175,17 @@ mod tests {
             test_option_defaults: Some(json!({"test_option": "2"})),
             eval_input_defaults: Some(json!({"eval": "3"})),
             eval_option_defaults: Some(json!({"eval_option": "4"})),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let req = test::TestRequest::put()
-            .uri(&format!("/tests/{}", test.test_id))
+            .uri(&format!(
+                "/tests/{}?skip_input_validation=true",
+                test.test_id
+            ))
             .set_json(&test_change)
             .to_request();
         let resp = test::call_service(&mut app, req).await;
@@ -784,7 +1234,16 @@ mod tests {
 
         create_test_test(&pool.get().unwrap());
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
 
         let test_change = TestChangeset {
             name: Some(String::from("Kevin's test change")),
@@ -793,6 +1252,10 @@ mod tests {
             test_option_defaults: None,
             eval_input_defaults: None,
             eval_option_defaults: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let req = test::TestRequest::put()
@@ -812,6 +1275,55 @@ mod tests {
         assert_eq!(error_body.detail, "ID must be formatted as a Uuid");
     }
 
+    #[actix_rt::test]
+    async fn update_failure_invalid_max_runtime() {
+        let pool = get_test_db_pool();
+
+        let test = create_test_test(&pool.get().unwrap());
+
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
+
+        let test_change = TestChangeset {
+            name: None,
+            description: None,
+            test_input_defaults: None,
+            test_option_defaults: None,
+            eval_input_defaults: None,
+            eval_option_defaults: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: Some(-60),
+            cromwell_server: None,
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!("/tests/{}", test.test_id))
+            .set_json(&test_change)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Invalid max runtime");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(
+            error_body.detail,
+            "max_runtime_in_secs must be greater than 0, but was -60"
+        );
+    }
+
     #[actix_rt::test]
     async fn update_failure_prohibited_params() {
         let pool = get_test_db_pool();
@@ -819,7 +1331,16 @@ mod tests {
         let test_test = create_test_test(&pool.get().unwrap());
         insert_non_failed_test_run_with_test_id(&pool.get().unwrap(), test_test.test_id);
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
 
         let test_change = TestChangeset {
             name: Some(String::from("Kevin's test change")),
@@ -828,10 +1349,17 @@ mod tests {
             test_option_defaults: None,
             eval_input_defaults: None,
             eval_option_defaults: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let req = test::TestRequest::put()
-            .uri(&format!("/tests/{}", test_test.test_id))
+            .uri(&format!(
+                "/tests/{}?skip_input_validation=true",
+                test_test.test_id
+            ))
             .set_json(&test_change)
             .to_request();
         let resp = test::call_service(&mut app, req).await;
@@ -856,7 +1384,16 @@ mod tests {
 
         create_test_test(&pool.get().unwrap());
 
-        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+        let mut app = test::init_service(
+            App::new()
+                .data(pool)
+                .data(TestResourceClient::new(Client::default(), None))
+                .data(WomtoolRunner::new(
+                    load_default_config().validation().womtool_location(),
+                ))
+                .configure(init_routes),
+        )
+        .await;
 
         let test_change = TestChangeset {
             name: Some(String::from("Kevin's test change")),
@@ -865,6 +1402,10 @@ mod tests {
             test_option_defaults: None,
             eval_input_defaults: None,
             eval_option_defaults: None,
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
         };
 
         let req = test::TestRequest::put()
@@ -954,7 +1495,7 @@ mod tests {
         assert_eq!(error_body.status, 403);
         assert_eq!(
             error_body.detail,
-            "Cannot delete a test if there are runs mapped to it"
+            "Cannot delete a test if there are runs or schedules mapped to it"
         );
     }
 
@@ -0,0 +1,945 @@
+//! Defines REST API mappings for operations on test schedules
+//!
+//! Contains functions for processing requests to create, update, delete, and search the schedules
+//! for tests, along with their URI mappings
+
+use crate::db;
+use crate::models::test_schedule::{
+    NewTestSchedule, TestScheduleChangeset, TestScheduleData, TestScheduleQuery,
+};
+use crate::routes::error_handling::{default_500, ErrorBody};
+use crate::util::cron_parsing;
+use actix_web::{error::BlockingError, web, HttpRequest, HttpResponse, Responder};
+use log::error;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Represents the part of a new test schedule that is received as a request body
+///
+/// The mapping for creating test schedules has test_id as a path param and the rest of the
+/// fields are expected as part of the request body.  enabled is optional, and defaults to true.
+/// A NewTestSchedule cannot be deserialized from the request body, so this is used instead, and
+/// then a NewTestSchedule can be built from the instance of this and the id from the path
+#[derive(Deserialize, Serialize)]
+struct NewTestScheduleIncomplete {
+    pub cron_expression: String,
+    pub test_input: Option<Value>,
+    pub test_options: Option<Value>,
+    pub eval_input: Option<Value>,
+    pub eval_options: Option<Value>,
+    pub enabled: Option<bool>,
+    pub created_by: Option<String>,
+}
+
+/// Parses `id` as a Uuid, returning a 400 response describing the problem (using `id_name` as the
+/// name of the id) if it is not formatted correctly
+fn parse_id(id: &str, id_name: &str) -> Result<Uuid, HttpResponse> {
+    match Uuid::parse_str(id) {
+        Ok(id) => Ok(id),
+        Err(e) => {
+            error!("{}", e);
+            // If it doesn't parse successfully, return an error to the user
+            Err(HttpResponse::BadRequest().json(ErrorBody {
+                title: format!("{} formatted incorrectly", id_name),
+                status: 400,
+                detail: format!("{} must be formatted as a Uuid", id_name),
+            }))
+        }
+    }
+}
+
+/// Checks that `cron_expression` is a valid cron expression, returning a 400 response describing
+/// the problem if it is not
+fn validate_cron_expression(cron_expression: &str) -> Result<(), HttpResponse> {
+    match cron_parsing::parse_cron_expression(cron_expression) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            error!("{}", e);
+            Err(HttpResponse::BadRequest().json(ErrorBody {
+                title: "Invalid cron expression".to_string(),
+                status: 400,
+                detail: format!("{}", e),
+            }))
+        }
+    }
+}
+
+/// Handles requests to /tests/{id}/schedules/{schedule_id} for retrieving test schedule info by
+/// test_id and test_schedule_id
+///
+/// This function is called by Actix-Web when a get request is made to the
+/// /tests/{id}/schedules/{schedule_id} mapping
+/// It parses the id and schedule_id from `req`, connects to the db via a connection from `pool`,
+/// and returns the retrieved test schedule, or an error message if there is no matching test
+/// schedule or some other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find_by_id(req: HttpRequest, pool: web::Data<db::DbPool>) -> impl Responder {
+    // Pull id params from path and parse them into Uuids
+    let id = match parse_id(req.match_info().get("id").unwrap(), "ID") {
+        Ok(id) => id,
+        Err(response) => return Ok(response),
+    };
+    let schedule_id = match parse_id(req.match_info().get("schedule_id").unwrap(), "Schedule ID") {
+        Ok(schedule_id) => schedule_id,
+        Err(response) => return Ok(response),
+    };
+
+    // Query DB for test schedule in new thread
+    web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match TestScheduleData::find_by_test_and_id(&conn, id, schedule_id) {
+            Ok(test_schedule) => Ok(test_schedule),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    .map(|results| {
+        // If there is no error, return a response with the retrieved data
+        HttpResponse::Ok().json(results)
+    })
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If no test schedule is found, return a 404
+            BlockingError::Error(diesel::NotFound) => HttpResponse::NotFound().json(ErrorBody {
+                title: "No test schedule found".to_string(),
+                status: 404,
+                detail: "No test schedule found with the specified ID".to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })
+}
+
+/// Handles requests to /tests/{id}/schedules for retrieving test schedule info by query
+/// parameters and test id
+///
+/// This function is called by Actix-Web when a get request is made to the /tests/{id}/schedules
+/// mapping
+/// It deserializes the query params to a TestScheduleQuery, connects to the db via a connection
+/// from `pool`, and returns the retrieved test schedules, or an error message if there is no
+/// matching test schedule or some other error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn find(
+    id: web::Path<String>,
+    web::Query(mut query): web::Query<TestScheduleQuery>,
+    pool: web::Data<db::DbPool>,
+) -> impl Responder {
+    // Parse ID into Uuid
+    let id = match parse_id(&id, "ID") {
+        Ok(id) => id,
+        Err(response) => return Ok(response),
+    };
+
+    // Set test_id as part of query object
+    query.test_id = Some(id);
+
+    // Query DB for test schedules in new thread
+    web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match TestScheduleData::find(&conn, query) {
+            Ok(test_schedules) => Ok(test_schedules),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    .map(|results| {
+        if results.is_empty() {
+            // If no test schedule is found, return a 404
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No test schedule found".to_string(),
+                status: 404,
+                detail: "No test schedules found with the specified parameters".to_string(),
+            })
+        } else {
+            // If there is no error, return a response with the retrieved data
+            HttpResponse::Ok().json(results)
+        }
+    })
+    .map_err(|e| {
+        error!("{}", e);
+        // For any errors, return a 500
+        default_500(&e)
+    })
+}
+
+/// Handles requests to /tests/{id}/schedules for creating test schedules
+///
+/// This function is called by Actix-Web when a post request is made to the /tests/{id}/schedules
+/// mapping
+/// It deserializes the request body to a NewTestScheduleIncomplete, checks that its cron
+/// expression is valid, connects to the db via a connection from `pool`, creates a test schedule
+/// with the specified parameters, and returns the created test schedule, or an error message if
+/// creating the test schedule fails for some reason
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn create(
+    id: web::Path<String>,
+    web::Json(new_test_schedule): web::Json<NewTestScheduleIncomplete>,
+    pool: web::Data<db::DbPool>,
+) -> impl Responder {
+    // Parse ID into Uuid
+    let id = match parse_id(&id, "ID") {
+        Ok(id) => id,
+        Err(response) => return Ok(response),
+    };
+
+    // Make sure the cron expression is valid before we store it
+    if let Err(response) = validate_cron_expression(&new_test_schedule.cron_expression) {
+        return Ok(response);
+    }
+
+    // Create a NewTestSchedule using the id and the data from the request body
+    let new_test_schedule = NewTestSchedule {
+        test_id: id,
+        cron_expression: new_test_schedule.cron_expression,
+        test_input: new_test_schedule.test_input,
+        test_options: new_test_schedule.test_options,
+        eval_input: new_test_schedule.eval_input,
+        eval_options: new_test_schedule.eval_options,
+        enabled: new_test_schedule.enabled.unwrap_or(true),
+        created_by: new_test_schedule.created_by,
+    };
+
+    // Insert in new thread
+    web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match TestScheduleData::create(&conn, new_test_schedule) {
+            Ok(test_schedule) => Ok(test_schedule),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, return a response with the created data
+    .map(|results| HttpResponse::Ok().json(results))
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If the test doesn't exist, return a 404
+            BlockingError::Error(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::ForeignKeyViolation,
+                _,
+            )) => HttpResponse::NotFound().json(ErrorBody {
+                title: "No test found".to_string(),
+                status: 404,
+                detail: "No test found with the specified ID".to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })
+}
+
+/// Handles requests to /tests/{id}/schedules/{schedule_id} for updating a test schedule
+///
+/// This function is called by Actix-Web when a put request is made to the
+/// /tests/{id}/schedules/{schedule_id} mapping
+/// It deserializes the request body to a TestScheduleChangeset, checks that its cron expression
+/// (if there is one) is valid, connects to the db via a connection from `pool`, updates the
+/// specified test schedule, and returns the updated test schedule or an error message if some
+/// error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn update(
+    req: HttpRequest,
+    web::Json(test_schedule_changes): web::Json<TestScheduleChangeset>,
+    pool: web::Data<db::DbPool>,
+) -> impl Responder {
+    // Pull id params from path and parse them into Uuids
+    let id = match parse_id(req.match_info().get("id").unwrap(), "ID") {
+        Ok(id) => id,
+        Err(response) => return Ok(response),
+    };
+    let schedule_id = match parse_id(req.match_info().get("schedule_id").unwrap(), "Schedule ID") {
+        Ok(schedule_id) => schedule_id,
+        Err(response) => return Ok(response),
+    };
+
+    // Make sure the cron expression is valid before we store it
+    if let Some(cron_expression) = &test_schedule_changes.cron_expression {
+        if let Err(response) = validate_cron_expression(cron_expression) {
+            return Ok(response);
+        }
+    }
+
+    // Update in new thread
+    web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match TestScheduleData::update(&conn, id, schedule_id, test_schedule_changes) {
+            Ok(test_schedule) => Ok(test_schedule),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, return a response with the updated data
+    .map(|results| HttpResponse::Ok().json(results))
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If no test schedule is found, return a 404
+            BlockingError::Error(diesel::NotFound) => HttpResponse::NotFound().json(ErrorBody {
+                title: "No test schedule found".to_string(),
+                status: 404,
+                detail: "No test schedule found with the specified ID".to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })
+}
+
+/// Handles DELETE requests to /tests/{id}/schedules/{schedule_id} for deleting test schedules
+///
+/// This function is called by Actix-Web when a delete request is made to the
+/// /tests/{id}/schedules/{schedule_id} mapping
+/// It parses the id and schedule_id from `req`, connects to the db via a connection from `pool`,
+/// and attempts to delete the specified test schedule, or an error message if some error occurs
+///
+/// # Panics
+/// Panics if attempting to connect to the database results in an error
+async fn delete_by_id(req: HttpRequest, pool: web::Data<db::DbPool>) -> impl Responder {
+    // Pull id params from path and parse them into Uuids
+    let id = match parse_id(req.match_info().get("id").unwrap(), "ID") {
+        Ok(id) => id,
+        Err(response) => return Ok(response),
+    };
+    let schedule_id = match parse_id(req.match_info().get("schedule_id").unwrap(), "Schedule ID") {
+        Ok(schedule_id) => schedule_id,
+        Err(response) => return Ok(response),
+    };
+
+    // Delete in new thread
+    web::block(move || {
+        let conn = pool.get().expect("Failed to get DB connection from pool");
+
+        match TestScheduleData::delete(&conn, id, schedule_id) {
+            Ok(delete_count) => Ok(delete_count),
+            Err(e) => {
+                error!("{}", e);
+                Err(e)
+            }
+        }
+    })
+    .await
+    // If there is no error, verify that a row was deleted
+    .map(|results| {
+        if results > 0 {
+            let message = format!("Successfully deleted {} row", results);
+            HttpResponse::Ok().json(json!({ "message": message }))
+        } else {
+            HttpResponse::NotFound().json(ErrorBody {
+                title: "No test schedule found".to_string(),
+                status: 404,
+                detail: "No test schedule found for the specified id".to_string(),
+            })
+        }
+    })
+    .map_err(|e| {
+        error!("{}", e);
+        match e {
+            // If the schedule has started runs, it can't be deleted, so return a 403
+            BlockingError::Error(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::ForeignKeyViolation,
+                _,
+            )) => HttpResponse::Forbidden().json(ErrorBody {
+                title: "Cannot delete".to_string(),
+                status: 403,
+                detail: "Cannot delete a test schedule if it has started runs.  Disable it instead"
+                    .to_string(),
+            }),
+            // For other errors, return a 500
+            _ => default_500(&e),
+        }
+    })
+}
+
+/// Attaches the REST mappings in this file to a service config
+///
+/// To be called when configuring the Actix-Web app service.  Registers the mappings in this file
+/// as part of the service defined in `cfg`
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/tests/{id}/schedules/{schedule_id}")
+            .route(web::get().to(find_by_id))
+            .route(web::put().to(update))
+            .route(web::delete().to(delete_by_id)),
+    );
+    cfg.service(
+        web::resource("/tests/{id}/schedules")
+            .route(web::get().to(find))
+            .route(web::post().to(create)),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_sql_types::RunStatusEnum;
+    use crate::models::pipeline::{NewPipeline, PipelineData};
+    use crate::models::run::{NewRun, RunData};
+    use crate::models::run_is_from_schedule::{NewRunIsFromSchedule, RunIsFromScheduleData};
+    use crate::models::template::{NewTemplate, TemplateData};
+    use crate::models::test::{NewTest, TestData};
+    use crate::unit_test_util::*;
+    use actix_web::{http, test, App};
+    use chrono::Utc;
+    use diesel::PgConnection;
+
+    fn create_test_test(conn: &PgConnection) -> TestData {
+        let new_pipeline = NewPipeline {
+            name: String::from("Kevin's Pipeline"),
+            description: Some(String::from("Kevin made this pipeline for testing")),
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            cromwell_server: None,
+            workflow_option_defaults: None,
+        };
+
+        let pipeline =
+            PipelineData::create(conn, new_pipeline).expect("Failed inserting test pipeline");
+
+        let new_template = NewTemplate {
+            name: String::from("Kevin's Template"),
+            pipeline_id: pipeline.pipeline_id,
+            description: Some(String::from("Kevin made this template for testing")),
+            test_wdl: format!("{}/test", mockito::server_url()),
+            test_wdl_dependencies: None,
+            eval_wdl: format!("{}/eval", mockito::server_url()),
+            eval_wdl_dependencies: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            cromwell_server: None,
+            workflow_option_defaults: None,
+            workflow_language: None,
+        };
+
+        let template =
+            TemplateData::create(conn, new_template).expect("Failed inserting test template");
+
+        let new_test = NewTest {
+            name: String::from("Kevin's Test"),
+            template_id: template.template_id,
+            description: Some(String::from("Kevin made this test for testing")),
+            test_input_defaults: Some(serde_json::from_str("{\"test\":\"test\"}").unwrap()),
+            test_option_defaults: None,
+            eval_input_defaults: Some(serde_json::from_str("{\"eval\":\"test\"}").unwrap()),
+            eval_option_defaults: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            max_concurrent_runs: None,
+            priority: None,
+            max_runtime_in_secs: None,
+            cromwell_server: None,
+        };
+
+        TestData::create(conn, new_test).expect("Failed inserting test test")
+    }
+
+    fn create_test_test_schedule(conn: &PgConnection) -> TestScheduleData {
+        let test = create_test_test(conn);
+
+        let new_test_schedule = NewTestSchedule {
+            test_id: test.test_id,
+            cron_expression: String::from("0 2 * * *"),
+            test_input: Some(json!({"test":"nightly"})),
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: true,
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        TestScheduleData::create(conn, new_test_schedule)
+            .expect("Failed inserting test test_schedule")
+    }
+
+    fn insert_test_run_from_schedule(conn: &PgConnection, test_schedule: &TestScheduleData) {
+        let new_run = NewRun {
+            test_id: test_schedule.test_id,
+            name: String::from("Kevin's Scheduled Run"),
+            status: RunStatusEnum::TestSubmitted,
+            test_input: json!({"test":"nightly"}),
+            test_options: None,
+            eval_input: json!({}),
+            eval_options: None,
+            test_cromwell_job_id: Some(String::from("1234567890")),
+            eval_cromwell_job_id: None,
+            created_by: Some(String::from("Kevin@example.com")),
+            finished_at: None,
+        };
+
+        let run = RunData::create(conn, new_run).expect("Failed inserting test run");
+
+        RunIsFromScheduleData::create(
+            conn,
+            NewRunIsFromSchedule {
+                run_id: run.run_id,
+                test_schedule_id: test_schedule.test_schedule_id,
+                scheduled_for: Utc::now().naive_utc(),
+            },
+        )
+        .expect("Failed inserting test run_is_from_schedule");
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_success() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/tests/{}/schedules/{}",
+                test_schedule.test_id, test_schedule.test_schedule_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_test_schedule: TestScheduleData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_test_schedule, test_schedule);
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/tests/{}/schedules/{}",
+                Uuid::new_v4(),
+                test_schedule.test_schedule_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No test schedule found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No test schedule found with the specified ID"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn find_by_id_failure_bad_uuid() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/tests/{}/schedules/123456789",
+                test_schedule.test_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Schedule ID formatted incorrectly");
+        assert_eq!(error_body.status, 400);
+        assert_eq!(error_body.detail, "Schedule ID must be formatted as a Uuid");
+    }
+
+    #[actix_rt::test]
+    async fn find_success() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/tests/{}/schedules?enabled=true",
+                test_schedule.test_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_test_schedules: Vec<TestScheduleData> = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_test_schedules.len(), 1);
+        assert_eq!(test_test_schedules[0], test_schedule);
+    }
+
+    #[actix_rt::test]
+    async fn find_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!(
+                "/tests/{}/schedules?enabled=false",
+                test_schedule.test_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No test schedule found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No test schedules found with the specified parameters"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn create_success() {
+        let pool = get_test_db_pool();
+
+        let test = create_test_test(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let new_test_schedule = NewTestScheduleIncomplete {
+            cron_expression: String::from("30 1 * * 1-5"),
+            test_input: Some(json!({"test":"weekdays"})),
+            test_options: None,
+            eval_input: None,
+            eval_options: Some(json!({"option":"value"})),
+            enabled: None,
+            created_by: Some(String::from("Kevin@example.com")),
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/tests/{}/schedules", test.test_id))
+            .set_json(&new_test_schedule)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_test_schedule: TestScheduleData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_test_schedule.test_id, test.test_id);
+        assert_eq!(test_test_schedule.cron_expression, "30 1 * * 1-5");
+        assert_eq!(
+            test_test_schedule.test_input,
+            Some(json!({"test":"weekdays"}))
+        );
+        assert_eq!(
+            test_test_schedule.eval_options,
+            Some(json!({"option":"value"}))
+        );
+        assert!(test_test_schedule.enabled);
+        assert_eq!(
+            test_test_schedule.created_by,
+            Some(String::from("Kevin@example.com"))
+        );
+    }
+
+    #[actix_rt::test]
+    async fn create_failure_invalid_cron_expression() {
+        let pool = get_test_db_pool();
+
+        let test = create_test_test(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let new_test_schedule = NewTestScheduleIncomplete {
+            cron_expression: String::from("every weekday at 1:30"),
+            test_input: None,
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: None,
+            created_by: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/tests/{}/schedules", test.test_id))
+            .set_json(&new_test_schedule)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Invalid cron expression");
+        assert_eq!(error_body.status, 400);
+    }
+
+    #[actix_rt::test]
+    async fn create_failure_no_test() {
+        let pool = get_test_db_pool();
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let new_test_schedule = NewTestScheduleIncomplete {
+            cron_expression: String::from("30 1 * * 1-5"),
+            test_input: None,
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: None,
+            created_by: None,
+        };
+
+        let req = test::TestRequest::post()
+            .uri(&format!("/tests/{}/schedules", Uuid::new_v4()))
+            .set_json(&new_test_schedule)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No test found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(error_body.detail, "No test found with the specified ID");
+    }
+
+    #[actix_rt::test]
+    async fn update_success() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let test_schedule_change = TestScheduleChangeset {
+            cron_expression: Some(String::from("0 3 * * *")),
+            test_input: None,
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: Some(false),
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!(
+                "/tests/{}/schedules/{}",
+                test_schedule.test_id, test_schedule.test_schedule_id
+            ))
+            .set_json(&test_schedule_change)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let test_test_schedule: TestScheduleData = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(test_test_schedule.cron_expression, "0 3 * * *");
+        assert!(!test_test_schedule.enabled);
+        assert_eq!(test_test_schedule.test_input, test_schedule.test_input);
+    }
+
+    #[actix_rt::test]
+    async fn update_failure_invalid_cron_expression() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let test_schedule_change = TestScheduleChangeset {
+            cron_expression: Some(String::from("0 25 * * *")),
+            test_input: None,
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: None,
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!(
+                "/tests/{}/schedules/{}",
+                test_schedule.test_id, test_schedule.test_schedule_id
+            ))
+            .set_json(&test_schedule_change)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::BAD_REQUEST);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Invalid cron expression");
+        assert_eq!(error_body.status, 400);
+    }
+
+    #[actix_rt::test]
+    async fn update_failure_not_found() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let test_schedule_change = TestScheduleChangeset {
+            cron_expression: None,
+            test_input: None,
+            test_options: None,
+            eval_input: None,
+            eval_options: None,
+            enabled: Some(false),
+        };
+
+        let req = test::TestRequest::put()
+            .uri(&format!(
+                "/tests/{}/schedules/{}",
+                test_schedule.test_id,
+                Uuid::new_v4()
+            ))
+            .set_json(&test_schedule_change)
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No test schedule found");
+        assert_eq!(error_body.status, 404);
+    }
+
+    #[actix_rt::test]
+    async fn delete_success() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/tests/{}/schedules/{}",
+                test_schedule.test_id, test_schedule.test_schedule_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let result = test::read_body(resp).await;
+        let message: Value = serde_json::from_slice(&result).unwrap();
+
+        let expected_message = json!({
+            "message": "Successfully deleted 1 row"
+        });
+
+        assert_eq!(message, expected_message)
+    }
+
+    #[actix_rt::test]
+    async fn delete_failure_no_test_schedule() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/tests/{}/schedules/{}",
+                test_schedule.test_id,
+                Uuid::new_v4()
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "No test schedule found");
+        assert_eq!(error_body.status, 404);
+        assert_eq!(
+            error_body.detail,
+            "No test schedule found for the specified id"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn delete_failure_has_runs() {
+        let pool = get_test_db_pool();
+
+        let test_schedule = create_test_test_schedule(&pool.get().unwrap());
+        insert_test_run_from_schedule(&pool.get().unwrap(), &test_schedule);
+
+        let mut app = test::init_service(App::new().data(pool).configure(init_routes)).await;
+
+        let req = test::TestRequest::delete()
+            .uri(&format!(
+                "/tests/{}/schedules/{}",
+                test_schedule.test_id, test_schedule.test_schedule_id
+            ))
+            .to_request();
+        let resp = test::call_service(&mut app, req).await;
+
+        assert_eq!(resp.status(), http::StatusCode::FORBIDDEN);
+
+        let result = test::read_body(resp).await;
+        let error_body: ErrorBody = serde_json::from_slice(&result).unwrap();
+
+        assert_eq!(error_body.title, "Cannot delete");
+        assert_eq!(error_body.status, 403);
+        assert_eq!(
+            error_body.detail,
+            "Cannot delete a test schedule if it has started runs.  Disable it instead"
+        );
+    }
+}
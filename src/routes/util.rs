@@ -1,6 +1,7 @@
 //! Contains utility functions shared by multiple of the modules within the `routes` module
 
 use crate::routes::error_handling::ErrorBody;
+use crate::validation::wdl_inputs;
 use actix_web::HttpResponse;
 use log::error;
 use uuid::Uuid;
@@ -24,3 +25,35 @@ pub fn parse_id(id: &str) -> Result<Uuid, HttpResponse> {
         }
     }
 }
+
+/// Converts `err`, returned from checking inputs against the WDLs of a template, into an ErrorBody
+/// with an appropriate status, title, and detail for returning to the user
+pub fn wdl_inputs_error_to_error_body(err: wdl_inputs::Error) -> ErrorBody {
+    match err {
+        wdl_inputs::Error::Invalid(problems) => ErrorBody {
+            title: "Invalid inputs".to_string(),
+            status: 400,
+            detail: format!(
+                "Inputs do not match the inputs of the WDLs (to skip this check, set skip_input_validation=true): {}",
+                problems.join("; ")
+            ),
+        },
+        wdl_inputs::Error::InvalidReferences(problems) => ErrorBody {
+            title: "Invalid references".to_string(),
+            status: 400,
+            detail: format!(
+                "Some test_output or image_build references in the inputs cannot be resolved (to skip this check, set skip_input_validation=true): {}",
+                problems
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<String>>()
+                    .join("; ")
+            ),
+        },
+        _ => ErrorBody {
+            title: "Server error".to_string(),
+            status: 500,
+            detail: format!("Error while attempting to validate inputs against WDLs: {}", err),
+        },
+    }
+}
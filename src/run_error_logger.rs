@@ -1,4 +1,5 @@
 use crate::models::run_error::{NewRunError, RunErrorData};
+use crate::util::secrets::{SecretEncryptor, SecretRedactor};
 use diesel::PgConnection;
 use log::error;
 use uuid::Uuid;
@@ -22,3 +23,31 @@ pub fn log_error(conn: &PgConnection, run_id: Uuid, message: String) {
         );
     }
 }
+
+/// Does the same as `log_error`, but first replaces the values of any secrets in `message` with
+/// REDACTED, for messages that might contain output from cromwell.  If `secret_encryptor` is None,
+/// secrets are not enabled, so the message is logged as is.  If the secrets can't be loaded to
+/// redact them, the message is withheld and a run error saying so is inserted instead
+pub fn log_error_redacting_secrets(
+    conn: &PgConnection,
+    run_id: Uuid,
+    message: String,
+    secret_encryptor: Option<&SecretEncryptor>,
+) {
+    match SecretRedactor::load(conn, secret_encryptor) {
+        Ok(secret_redactor) => log_error(conn, run_id, secret_redactor.redact_str(&message)),
+        Err(e) => {
+            error!(
+                "Failed to load secrets to redact run error for run: {} with error: {}",
+                run_id, e
+            );
+            log_error(
+                conn,
+                run_id,
+                String::from(
+                    "Encountered an error, but the message was withheld because secrets could not be loaded to redact it",
+                ),
+            );
+        }
+    }
+}
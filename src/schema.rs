@@ -13,6 +13,9 @@ table! {
         description -> Nullable<Text>,
         created_at -> Timestamptz,
         created_by -> Nullable<Text>,
+        max_concurrent_runs -> Nullable<Int4>,
+        cromwell_server -> Nullable<Text>,
+        workflow_option_defaults -> Nullable<Jsonb>,
     }
 }
 
@@ -48,6 +51,7 @@ table! {
         created_at -> Timestamptz,
         created_by -> Nullable<Text>,
         finished_at -> Nullable<Timestamptz>,
+        cromwell_server -> Nullable<Text>,
     }
 }
 
@@ -64,6 +68,7 @@ table! {
 
 table! {
     use diesel::sql_types::*;
+    use crate::custom_sql_types::Workflow_language_enum;
 
     template (template_id) {
         template_id -> Uuid,
@@ -76,6 +81,9 @@ table! {
         eval_wdl_dependencies -> Nullable<Text>,
         created_at -> Timestamptz,
         created_by -> Nullable<Text>,
+        cromwell_server -> Nullable<Text>,
+        workflow_option_defaults -> Nullable<Jsonb>,
+        workflow_language -> Workflow_language_enum,
     }
 }
 
@@ -105,6 +113,10 @@ table! {
         eval_option_defaults -> Nullable<Jsonb>,
         created_at -> Timestamptz,
         created_by -> Nullable<Text>,
+        max_concurrent_runs -> Nullable<Int4>,
+        priority -> Int4,
+        max_runtime_in_secs -> Nullable<Int4>,
+        cromwell_server -> Nullable<Text>,
     }
 }
 
@@ -126,6 +138,7 @@ table! {
         created_at -> Timestamptz,
         created_by -> Nullable<Text>,
         finished_at -> Nullable<Timestamptz>,
+        cromwell_server -> Nullable<Text>,
         results -> Nullable<Jsonb>,
         errors -> Nullable<Jsonb>,
     }
@@ -189,6 +202,7 @@ table! {
         image_url -> Nullable<Text>,
         created_at -> Timestamptz,
         finished_at -> Nullable<Timestamptz>,
+        cromwell_server -> Nullable<Text>,
     }
 }
 
@@ -243,6 +257,7 @@ table! {
         created_at -> Timestamptz,
         created_by -> Nullable<Text>,
         finished_at -> Nullable<Timestamptz>,
+        cromwell_server -> Nullable<Text>,
     }
 }
 
@@ -267,7 +282,138 @@ table! {
     }
 }
 
+table! {
+    use diesel::sql_types::*;
+    use crate::custom_sql_types::Rerun_mode_enum;
+
+    run_is_rerun(run_id) {
+        run_id -> Uuid,
+        source_run_id -> Uuid,
+        mode -> Rerun_mode_enum,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+
+    test_schedule(test_schedule_id) {
+        test_schedule_id -> Uuid,
+        test_id -> Uuid,
+        cron_expression -> Text,
+        test_input -> Nullable<Jsonb>,
+        test_options -> Nullable<Jsonb>,
+        eval_input -> Nullable<Jsonb>,
+        eval_options -> Nullable<Jsonb>,
+        enabled -> Bool,
+        last_scheduled_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        created_by -> Nullable<Text>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+
+    run_is_from_schedule(run_id) {
+        run_id -> Uuid,
+        test_schedule_id -> Uuid,
+        scheduled_for -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+
+    run_group(run_group_id) {
+        run_group_id -> Uuid,
+        name -> Text,
+        description -> Nullable<Text>,
+        created_at -> Timestamptz,
+        created_by -> Nullable<Text>,
+        notified_at -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+
+    run_in_group(run_id, run_group_id) {
+        run_id -> Uuid,
+        run_group_id -> Uuid,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::custom_sql_types::Run_call_workflow_enum;
+
+    run_call(run_call_id) {
+        run_call_id -> Uuid,
+        run_id -> Uuid,
+        workflow -> Run_call_workflow_enum,
+        cromwell_job_id -> Text,
+        task_name -> Text,
+        shard_index -> Int4,
+        attempt -> Int4,
+        execution_status -> Nullable<Text>,
+        backend_status -> Nullable<Text>,
+        return_code -> Nullable<Int4>,
+        docker_image -> Nullable<Text>,
+        runtime_attributes -> Nullable<Jsonb>,
+        started_at -> Nullable<Timestamptz>,
+        finished_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::custom_sql_types::Run_call_workflow_enum;
+
+    run_start_attempt(run_id, workflow) {
+        run_id -> Uuid,
+        workflow -> Run_call_workflow_enum,
+        attempts -> Int4,
+        last_attempted_at -> Timestamptz,
+        next_attempt_at -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+
+    secret(secret_id) {
+        secret_id -> Uuid,
+        name -> Text,
+        description -> Nullable<Text>,
+        encrypted_value -> Binary,
+        created_at -> Timestamptz,
+        created_by -> Nullable<Text>,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+    use crate::custom_sql_types::Run_call_workflow_enum;
+
+    run_input_source(run_id, workflow, input_key) {
+        run_id -> Uuid,
+        workflow -> Run_call_workflow_enum,
+        input_key -> Text,
+        reference -> Text,
+        source_run_id -> Uuid,
+        value -> Jsonb,
+        created_at -> Timestamptz,
+    }
+}
+
+joinable!(run -> test(test_id));
 joinable!(test -> template(template_id));
+joinable!(run_in_group -> run(run_id));
+joinable!(run_in_group -> run_group(run_group_id));
 joinable!(software_version -> software(software_id));
 
 allow_tables_to_appear_in_same_query!(
@@ -289,4 +435,13 @@ allow_tables_to_appear_in_same_query!(
     template_report,
     run_report,
     run_error,
+    run_is_rerun,
+    test_schedule,
+    run_is_from_schedule,
+    run_group,
+    run_in_group,
+    run_call,
+    run_start_attempt,
+    secret,
+    run_input_source,
 );
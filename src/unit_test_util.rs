@@ -6,6 +6,7 @@ use crate::db;
 use diesel::pg::PgConnection;
 use diesel::Connection;
 use dotenv;
+use std::collections::HashMap;
 use std::env;
 use std::fs::read_to_string;
 use std::io::Write;
@@ -68,7 +69,15 @@ pub fn load_default_config() -> Config {
     // Set up the WDL temp dir, since we can't load that from the test env file
     let wdl_storage_config = init_wdl_temp_dir();
     // Make a cromwell config that uses the mockito url
-    let cromwell_config = CromwellConfig::new(mockito::server_url());
+    let cromwell_config = CromwellConfig::new(
+        mockito::server_url(),
+        String::from("carrot"),
+        String::from("default"),
+        Vec::new(),
+        HashMap::new(),
+        None,
+        None,
+    );
     // Load rest of config from test config file
     let config_string = read_to_string("testdata/test_config.yml")
         .expect("Failed to load testdata/test_config.yml");
@@ -0,0 +1,233 @@
+//! Defines functions for reading run input overrides for batch run submissions from CSV files
+//!
+//! Each row in a batch CSV corresponds to one run.  The header row determines how each column is
+//! used:
+//! 1. A column with the header `name` specifies the name of the run
+//! 2. A column with a header prefixed with `eval_input:` specifies a value for the eval input key
+//!    following the prefix
+//! 3. A column with a header prefixed with `test_input:`, or with no recognized prefix, specifies a
+//!    value for the test input key following the prefix (or the full header if there is no prefix)
+//!
+//! Empty cells are ignored, so the value for that key will be filled from the test's defaults.
+//! Cell values that start with `[` or `{` (ignoring leading and trailing whitespace) are parsed as
+//! JSON, so arrays and objects can be specified.  All other values, including ones that look like
+//! numbers, booleans, or null, are treated as strings (cromwell converts strings to the types its
+//! inputs expect)
+
+use serde_json::{Map, Value};
+use std::fmt;
+use std::fmt::Formatter;
+use std::io::Read;
+
+#[derive(Debug)]
+pub enum Error {
+    CSV(csv::Error),
+    /// Indicates a column header that cannot be mapped to an input key
+    InvalidColumn(String),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::CSV(e) => write!(f, "batch_csv Error CSV {}", e),
+            Error::InvalidColumn(c) => write!(f, "batch_csv Error InvalidColumn {}", c),
+        }
+    }
+}
+
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Error {
+        Error::CSV(e)
+    }
+}
+
+/// Represents the values parsed from a single row of a batch CSV
+#[derive(Debug, PartialEq)]
+pub struct BatchCsvRow {
+    pub name: Option<String>,
+    pub test_input: Option<Value>,
+    pub eval_input: Option<Value>,
+}
+
+/// Enum for the destination of a column's values within a BatchCsvRow
+enum ColumnType {
+    Name,
+    TestInput(String),
+    EvalInput(String),
+}
+
+/// Reads the CSV data from `reader` and returns a BatchCsvRow for each non-header row, with the
+/// values in that row mapped according to the header row.  Returns an error if reading the CSV data
+/// fails or if a column header is empty or invalid
+pub fn parse_batch_csv<R: Read>(reader: R) -> Result<Vec<BatchCsvRow>, Error> {
+    let mut csv_reader = csv::Reader::from_reader(reader);
+    // Figure out where each column's values should go based on the headers
+    let column_types: Vec<ColumnType> = csv_reader
+        .headers()?
+        .iter()
+        .map(get_column_type)
+        .collect::<Result<Vec<ColumnType>, Error>>()?;
+    // Build a BatchCsvRow for each record
+    let mut rows: Vec<BatchCsvRow> = Vec::new();
+    for record in csv_reader.records() {
+        let record = record?;
+        let mut name: Option<String> = None;
+        let mut test_input: Map<String, Value> = Map::new();
+        let mut eval_input: Map<String, Value> = Map::new();
+        for (column_type, cell) in column_types.iter().zip(record.iter()) {
+            // Skip empty cells so the defaults will be used for that key
+            if cell.is_empty() {
+                continue;
+            }
+            match column_type {
+                ColumnType::Name => name = Some(String::from(cell)),
+                ColumnType::TestInput(key) => {
+                    test_input.insert(key.clone(), parse_cell_value(cell));
+                }
+                ColumnType::EvalInput(key) => {
+                    eval_input.insert(key.clone(), parse_cell_value(cell));
+                }
+            }
+        }
+        rows.push(BatchCsvRow {
+            name,
+            test_input: if test_input.is_empty() {
+                None
+            } else {
+                Some(Value::Object(test_input))
+            },
+            eval_input: if eval_input.is_empty() {
+                None
+            } else {
+                Some(Value::Object(eval_input))
+            },
+        });
+    }
+
+    Ok(rows)
+}
+
+/// Determines the ColumnType for the column with the header `header`.  Returns an error if
+/// `header` (or the key following its prefix) is empty
+fn get_column_type(header: &str) -> Result<ColumnType, Error> {
+    let header = header.trim();
+    let column_type = if header == "name" {
+        ColumnType::Name
+    } else if let Some(key) = header.strip_prefix("eval_input:") {
+        ColumnType::EvalInput(String::from(key.trim()))
+    } else if let Some(key) = header.strip_prefix("test_input:") {
+        ColumnType::TestInput(String::from(key.trim()))
+    } else {
+        ColumnType::TestInput(String::from(header))
+    };
+    // Make sure we don't have any empty keys
+    match &column_type {
+        ColumnType::TestInput(key) | ColumnType::EvalInput(key) if key.is_empty() => {
+            Err(Error::InvalidColumn(String::from(header)))
+        }
+        _ => Ok(column_type),
+    }
+}
+
+/// Parses `cell` as JSON if it looks like a JSON array or object, returning the parsed value if
+/// successful and `cell` as a JSON string otherwise
+fn parse_cell_value(cell: &str) -> Value {
+    let trimmed_cell = cell.trim();
+    if trimmed_cell.starts_with('[') || trimmed_cell.starts_with('{') {
+        if let Ok(value) = serde_json::from_str(trimmed_cell) {
+            return value;
+        }
+    }
+    Value::String(String::from(cell))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::batch_csv::{parse_batch_csv, BatchCsvRow, Error};
+    use serde_json::json;
+
+    #[test]
+    fn parse_batch_csv_success() {
+        let csv_data = "name,in_greeting,test_input:in_count,eval_input:in_expected\n\
+            first_run,Hello,3,\"{\"\"a\"\":1}\"\n\
+            ,Hi,,Goodbye\n";
+
+        let rows = parse_batch_csv(csv_data.as_bytes()).unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                BatchCsvRow {
+                    name: Some(String::from("first_run")),
+                    test_input: Some(json!({"in_greeting": "Hello", "in_count": "3"})),
+                    eval_input: Some(json!({"in_expected": {"a": 1}})),
+                },
+                BatchCsvRow {
+                    name: None,
+                    test_input: Some(json!({"in_greeting": "Hi"})),
+                    eval_input: Some(json!({"in_expected": "Goodbye"})),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_batch_csv_scalar_values_stay_strings() {
+        let csv_data = "in_maybe,in_flag,in_big,in_id,in_list,in_bad_json\n\
+            null,true,1e3,00123,\" [1, 2] \",{not json}\n";
+
+        let rows = parse_batch_csv(csv_data.as_bytes()).unwrap();
+
+        assert_eq!(
+            rows,
+            vec![BatchCsvRow {
+                name: None,
+                test_input: Some(json!({
+                    "in_maybe": "null",
+                    "in_flag": "true",
+                    "in_big": "1e3",
+                    "in_id": "00123",
+                    "in_list": [1, 2],
+                    "in_bad_json": "{not json}"
+                })),
+                eval_input: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_batch_csv_empty_rows() {
+        let csv_data = "name,in_greeting\n,\n";
+
+        let rows = parse_batch_csv(csv_data.as_bytes()).unwrap();
+
+        assert_eq!(
+            rows,
+            vec![BatchCsvRow {
+                name: None,
+                test_input: None,
+                eval_input: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_batch_csv_failure_invalid_column() {
+        let csv_data = "name,eval_input:\nfirst_run,Hello\n";
+
+        let error = parse_batch_csv(csv_data.as_bytes()).unwrap_err();
+
+        assert!(matches!(error, Error::InvalidColumn(c) if c == "eval_input:"));
+    }
+
+    #[test]
+    fn parse_batch_csv_failure_wrong_length() {
+        let csv_data = "name,in_greeting\nfirst_run,Hello,extra\n";
+
+        let error = parse_batch_csv(csv_data.as_bytes()).unwrap_err();
+
+        assert!(matches!(error, Error::CSV(_)));
+    }
+}
@@ -0,0 +1,145 @@
+//! Provides functions for parsing cron expressions and finding the times they specify
+//!
+//! Cron expressions may be in the standard five-field format (minute, hour, day of month, month,
+//! day of week), or may additionally include a leading seconds field and/or a trailing year field.
+//! All times are interpreted as UTC
+
+use chrono::{Duration, NaiveDateTime, TimeZone, Utc};
+use cron::Schedule;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug)]
+pub enum Error {
+    Parse(String),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Parse(e) => write!(f, "Cron Parsing Error {}", e),
+        }
+    }
+}
+
+/// Parses `cron_expression` into a Schedule.  If `cron_expression` is in the standard five-field
+/// format, it is treated as firing at second 0 of each minute it specifies.  Returns an error if
+/// `cron_expression` is not a valid cron expression
+pub fn parse_cron_expression(cron_expression: &str) -> Result<Schedule, Error> {
+    // The cron crate expects a seconds field, so add one if this is a standard cron expression
+    let cron_expression = if cron_expression.split_whitespace().count() == 5 {
+        format!("0 {}", cron_expression.trim())
+    } else {
+        String::from(cron_expression.trim())
+    };
+    match Schedule::from_str(&cron_expression) {
+        Ok(schedule) => Ok(schedule),
+        Err(e) => Err(Error::Parse(format!(
+            "Failed to parse {} as a cron expression: {}",
+            cron_expression, e
+        ))),
+    }
+}
+
+/// Returns up to `limit` of the most recent times specified by `schedule` that are after `start`
+/// and no later than `end`, in ascending order
+pub fn get_most_recent_occurrences(
+    schedule: &Schedule,
+    start: NaiveDateTime,
+    end: NaiveDateTime,
+    limit: usize,
+) -> Vec<NaiveDateTime> {
+    // The schedule iterator will only give us times strictly before the time it starts from, and
+    // it works at the resolution of seconds, so start from a second after `end` so it will be
+    // included, and then skip anything after it
+    let iterator_start = Utc.from_utc_datetime(&(end + Duration::seconds(1)));
+    let mut occurrences: Vec<NaiveDateTime> = schedule
+        .after(&iterator_start)
+        .rev()
+        .map(|occurrence| occurrence.naive_utc())
+        .skip_while(|occurrence| *occurrence > end)
+        .take_while(|occurrence| *occurrence > start)
+        .take(limit)
+        .collect();
+    occurrences.reverse();
+    occurrences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn parse_cron_expression_standard() {
+        let schedule = parse_cron_expression("30 2 * * *").unwrap();
+        let start = NaiveDate::from_ymd(2022, 4, 1).and_hms(0, 0, 0);
+        let next = schedule
+            .after(&Utc.from_utc_datetime(&start))
+            .next()
+            .unwrap();
+        assert_eq!(
+            next.naive_utc(),
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(2, 30, 0)
+        );
+    }
+
+    #[test]
+    fn parse_cron_expression_with_seconds() {
+        let schedule = parse_cron_expression("15 30 2 * * *").unwrap();
+        let start = NaiveDate::from_ymd(2022, 4, 1).and_hms(0, 0, 0);
+        let next = schedule
+            .after(&Utc.from_utc_datetime(&start))
+            .next()
+            .unwrap();
+        assert_eq!(
+            next.naive_utc(),
+            NaiveDate::from_ymd(2022, 4, 1).and_hms(2, 30, 15)
+        );
+    }
+
+    #[test]
+    fn parse_cron_expression_failure() {
+        let result = parse_cron_expression("every day at noon");
+        assert!(matches!(result, Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn get_most_recent_occurrences_all() {
+        let schedule = parse_cron_expression("0 * * * *").unwrap();
+        let start = NaiveDate::from_ymd(2022, 4, 1).and_hms(0, 0, 0);
+        let end = NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 0, 0);
+        let occurrences = get_most_recent_occurrences(&schedule, start, end, 10);
+        assert_eq!(
+            occurrences,
+            vec![
+                NaiveDate::from_ymd(2022, 4, 1).and_hms(1, 0, 0),
+                NaiveDate::from_ymd(2022, 4, 1).and_hms(2, 0, 0),
+                NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 0, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn get_most_recent_occurrences_limited() {
+        let schedule = parse_cron_expression("0 * * * *").unwrap();
+        let start = NaiveDate::from_ymd(2022, 4, 1).and_hms(0, 0, 0);
+        let end = NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 30, 0);
+        let occurrences = get_most_recent_occurrences(&schedule, start, end, 1);
+        assert_eq!(
+            occurrences,
+            vec![NaiveDate::from_ymd(2022, 4, 1).and_hms(3, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn get_most_recent_occurrences_none() {
+        let schedule = parse_cron_expression("0 * * * *").unwrap();
+        let start = NaiveDate::from_ymd(2022, 4, 1).and_hms(0, 0, 0);
+        let end = NaiveDate::from_ymd(2022, 4, 1).and_hms(0, 30, 0);
+        let occurrences = get_most_recent_occurrences(&schedule, start, end, 10);
+        assert!(occurrences.is_empty());
+    }
+}
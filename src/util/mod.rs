@@ -1,8 +1,11 @@
 //! Contains modules with functionality that is used elsewhere but that I can't find a better home
 //! for
+pub mod batch_csv;
+pub mod cron_parsing;
 pub mod git_repos;
 pub mod gs_uri_parsing;
 pub mod run_csv;
+pub mod secrets;
 pub mod sort_string;
 pub mod temp_storage;
 pub mod wdl_storage;
@@ -379,7 +379,8 @@ mod tests {
                     "output_file": "gs://example/path/to/file.mp4",
                     "output_number": 7
                 })),
-                errors: None
+                errors: None,
+                cromwell_server: None,
             },
             RunWithResultsAndErrorsData {
                 run_id: Uuid::parse_str("4f27abe7-2cfa-42c3-acc2-ce5344b0e471").unwrap(),
@@ -408,7 +409,8 @@ mod tests {
                     "output_file": "gs://example/path/to/different/file.mp4",
                     "output_number": 5
                 })),
-                errors: None
+                errors: None,
+                cromwell_server: None,
             },
             RunWithResultsAndErrorsData {
                 run_id: Uuid::parse_str("4b2d5150-7d86-4a11-888c-b78dc030ba4f").unwrap(),
@@ -439,7 +441,8 @@ mod tests {
                     "output_number": 5,
                     "new_result": "yes"
                 })),
-                errors: None
+                errors: None,
+                cromwell_server: None,
             },
             RunWithResultsAndErrorsData {
                 run_id: Uuid::parse_str("cf4b5cb2-976e-4f9c-91ba-2b421d61199b").unwrap(),
@@ -464,7 +467,8 @@ mod tests {
                 results: None,
                 errors: Some(json!([
                     "Carrot failed to start the run because of a weird error"
-                ]))
+                ])),
+                cromwell_server: None,
             },
         ]
     }
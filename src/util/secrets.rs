@@ -0,0 +1,433 @@
+//! Contains functionality for encrypting and decrypting secrets, resolving `secret:name`
+//! references to their values, and redacting secret values from text before it is stored or
+//! returned to a user
+//!
+//! Secret values are encrypted with AES-256-GCM using the key from the secrets config.  The stored
+//! encrypted value is the nonce, followed by the ciphertext, followed by the authentication tag
+
+use crate::config::{Config, SecretsConfig};
+use crate::models::secret::SecretData;
+use core::fmt;
+use diesel::PgConnection;
+use openssl::error::ErrorStack;
+use openssl::rand::rand_bytes;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use regex::Regex;
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::error;
+use std::string::FromUtf8Error;
+
+lazy_static! {
+    /// Regex for matching input values that reference a secret, in the form secret:name
+    pub static ref SECRET_REGEX: Regex = Regex::new(r"^secret:([\w.\-]+)$").unwrap();
+    /// Regex for validating secret names, so every secret can be referenced with SECRET_REGEX
+    pub static ref SECRET_NAME_REGEX: Regex = Regex::new(r"^[\w.\-]+$").unwrap();
+}
+
+/// Length in bytes of the key used for encryption
+const KEY_LENGTH: usize = 32;
+/// Length in bytes of the nonce stored at the start of each encrypted value
+const NONCE_LENGTH: usize = 12;
+/// Length in bytes of the authentication tag stored at the end of each encrypted value
+const TAG_LENGTH: usize = 16;
+/// Text that secret values are replaced with when they're redacted
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Enum of possible errors from working with secrets
+#[derive(Debug)]
+pub enum Error {
+    Base64(base64::DecodeError),
+    InvalidKey(String),
+    InvalidEncryptedValue,
+    OpenSSL(ErrorStack),
+    Utf8(FromUtf8Error),
+    DB(diesel::result::Error),
+    NotFound(String),
+    NotConfigured(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Base64(e) => write!(f, "Secrets Error Base64 {}", e),
+            Error::InvalidKey(msg) => write!(f, "Secrets Error InvalidKey {}", msg),
+            Error::InvalidEncryptedValue => write!(
+                f,
+                "Secrets Error InvalidEncryptedValue Encrypted value is too short to be valid"
+            ),
+            Error::OpenSSL(e) => write!(f, "Secrets Error OpenSSL {}", e),
+            Error::Utf8(e) => write!(f, "Secrets Error Utf8 {}", e),
+            Error::DB(e) => write!(f, "Secrets Error DB {}", e),
+            Error::NotFound(secret_name) => write!(
+                f,
+                "Secrets Error NotFound No secret exists with name {}",
+                secret_name
+            ),
+            Error::NotConfigured(secret_name) => write!(
+                f,
+                "Secrets Error NotConfigured Secret {} is referenced, but secrets are not configured",
+                secret_name
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+// Implementing From for each of the error types so they map more easily
+impl From<base64::DecodeError> for Error {
+    fn from(e: base64::DecodeError) -> Error {
+        Error::Base64(e)
+    }
+}
+impl From<ErrorStack> for Error {
+    fn from(e: ErrorStack) -> Error {
+        Error::OpenSSL(e)
+    }
+}
+impl From<FromUtf8Error> for Error {
+    fn from(e: FromUtf8Error) -> Error {
+        Error::Utf8(e)
+    }
+}
+impl From<diesel::result::Error> for Error {
+    fn from(e: diesel::result::Error) -> Error {
+        Error::DB(e)
+    }
+}
+
+/// Struct for encrypting and decrypting secret values
+#[derive(Clone)]
+pub struct SecretEncryptor {
+    key: Vec<u8>,
+}
+
+impl SecretEncryptor {
+    /// Creates a new SecretEncryptor using the encryption key in `secrets_config`.  Returns an
+    /// error if the key is not valid base64 or does not decode to 32 bytes
+    pub fn new(secrets_config: &SecretsConfig) -> Result<SecretEncryptor, Error> {
+        let key = base64::decode(secrets_config.encryption_key())?;
+        if key.len() != KEY_LENGTH {
+            return Err(Error::InvalidKey(format!(
+                "Encryption key must be {} bytes, but it is {} bytes",
+                KEY_LENGTH,
+                key.len()
+            )));
+        }
+        Ok(SecretEncryptor { key })
+    }
+
+    /// Encrypts `value` with a random nonce, returning the nonce, ciphertext, and tag together
+    pub fn encrypt(&self, value: &str) -> Result<Vec<u8>, Error> {
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rand_bytes(&mut nonce)?;
+        let mut tag = [0u8; TAG_LENGTH];
+        let ciphertext = encrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(&nonce),
+            &[],
+            value.as_bytes(),
+            &mut tag,
+        )?;
+        let mut encrypted_value = Vec::with_capacity(NONCE_LENGTH + ciphertext.len() + TAG_LENGTH);
+        encrypted_value.extend_from_slice(&nonce);
+        encrypted_value.extend_from_slice(&ciphertext);
+        encrypted_value.extend_from_slice(&tag);
+        Ok(encrypted_value)
+    }
+
+    /// Decrypts `encrypted_value`, which is expected to be in the form returned by `encrypt`.
+    /// Returns an error if it has been tampered with or was encrypted with a different key
+    pub fn decrypt(&self, encrypted_value: &[u8]) -> Result<String, Error> {
+        if encrypted_value.len() < NONCE_LENGTH + TAG_LENGTH {
+            return Err(Error::InvalidEncryptedValue);
+        }
+        let (nonce, rest) = encrypted_value.split_at(NONCE_LENGTH);
+        let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LENGTH);
+        let value = decrypt_aead(
+            Cipher::aes_256_gcm(),
+            &self.key,
+            Some(nonce),
+            &[],
+            ciphertext,
+            tag,
+        )?;
+        Ok(String::from_utf8(value)?)
+    }
+}
+
+/// Creates a SecretEncryptor using the secrets config in `carrot_config`, or returns None if
+/// secrets are not configured
+///
+/// # Panics
+/// Panics if the encryption key in the secrets config is invalid
+pub fn get_secret_encryptor(carrot_config: &Config) -> Option<SecretEncryptor> {
+    carrot_config.secrets().map(|secrets_config| {
+        SecretEncryptor::new(secrets_config)
+            .expect("Failed to create secret encryptor from encryption_key in secrets config")
+    })
+}
+
+/// Returns the name of the secret referenced by `value` if it is a secret reference (in the form
+/// secret:name), or None if it isn't
+pub fn get_secret_name(value: &str) -> Option<&str> {
+    SECRET_REGEX
+        .captures(value)
+        .and_then(|captures| captures.get(1))
+        .map(|secret_name| secret_name.as_str())
+}
+
+/// Retrieves and decrypts the value of the secret called `secret_name`.  Returns a NotConfigured
+/// error if `secret_encryptor` is None (meaning secrets are not configured), or a NotFound error if
+/// there is no secret with that name
+pub fn get_secret_value(
+    conn: &PgConnection,
+    secret_encryptor: Option<&SecretEncryptor>,
+    secret_name: &str,
+) -> Result<String, Error> {
+    let secret_encryptor = match secret_encryptor {
+        Some(secret_encryptor) => secret_encryptor,
+        None => return Err(Error::NotConfigured(String::from(secret_name))),
+    };
+    let encrypted_value = match SecretData::find_encrypted_value_by_name(conn, secret_name) {
+        Ok(encrypted_value) => encrypted_value,
+        Err(diesel::result::Error::NotFound) => {
+            return Err(Error::NotFound(String::from(secret_name)))
+        }
+        Err(e) => return Err(Error::DB(e)),
+    };
+    secret_encryptor.decrypt(&encrypted_value)
+}
+
+/// Struct for replacing the values of secrets with REDACTED in text that might be shown to a user
+pub struct SecretRedactor {
+    secret_values: Vec<String>,
+}
+
+impl SecretRedactor {
+    /// Loads and decrypts the values of all the secrets in the DB so they can be redacted.  If
+    /// `secret_encryptor` is None, secrets are not configured, so there is nothing to redact.
+    /// Returns an error if any secret can't be retrieved or decrypted, since its value couldn't be
+    /// redacted
+    pub fn load(
+        conn: &PgConnection,
+        secret_encryptor: Option<&SecretEncryptor>,
+    ) -> Result<SecretRedactor, Error> {
+        let mut secret_values: Vec<String> = Vec::new();
+        if let Some(secret_encryptor) = secret_encryptor {
+            for (_, encrypted_value) in SecretData::find_all_encrypted_values(conn)? {
+                let secret_value = secret_encryptor.decrypt(&encrypted_value)?;
+                if !secret_value.is_empty() {
+                    secret_values.push(secret_value);
+                }
+            }
+        }
+        // Redact longer values first so a value that contains another isn't only partially redacted
+        secret_values.sort_by_key(|secret_value| Reverse(secret_value.len()));
+        Ok(SecretRedactor { secret_values })
+    }
+
+    /// Returns a copy of `text` with any secret values replaced with REDACTED
+    pub fn redact_str(&self, text: &str) -> String {
+        let mut redacted_text = String::from(text);
+        for secret_value in &self.secret_values {
+            if redacted_text.contains(secret_value.as_str()) {
+                redacted_text = redacted_text.replace(secret_value.as_str(), REDACTED);
+            }
+        }
+        redacted_text
+    }
+
+    /// Replaces any secret values in the strings within `value` with REDACTED
+    pub fn redact_json(&self, value: &mut Value) {
+        match value {
+            Value::String(string_value) => {
+                *string_value = self.redact_str(string_value);
+            }
+            Value::Array(array_value) => {
+                for element in array_value {
+                    self.redact_json(element);
+                }
+            }
+            Value::Object(object_value) => {
+                for (_, element) in object_value.iter_mut() {
+                    self.redact_json(element);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::SecretsConfig;
+    use crate::models::secret::{NewSecret, SecretData};
+    use crate::unit_test_util::get_test_db_connection;
+    use crate::util::secrets::{
+        get_secret_name, get_secret_value, Error, SecretEncryptor, SecretRedactor,
+    };
+    use diesel::PgConnection;
+    use serde_json::json;
+
+    fn create_test_secret_encryptor() -> SecretEncryptor {
+        SecretEncryptor::new(&SecretsConfig::new(String::from(
+            "MDEyMzQ1Njc4OWFiY2RlZjAxMjM0NTY3ODlhYmNkZWY=",
+        )))
+        .unwrap()
+    }
+
+    fn insert_test_secret(
+        conn: &PgConnection,
+        secret_encryptor: &SecretEncryptor,
+        name: &str,
+        value: &str,
+    ) {
+        SecretData::create(
+            conn,
+            NewSecret {
+                name: String::from(name),
+                description: None,
+                encrypted_value: secret_encryptor.encrypt(value).unwrap(),
+                created_by: None,
+            },
+        )
+        .expect("Failed to insert test secret");
+    }
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let secret_encryptor = create_test_secret_encryptor();
+
+        let encrypted_value = secret_encryptor.encrypt("hunter2").unwrap();
+        assert!(!encrypted_value
+            .windows("hunter2".len())
+            .any(|window| window == "hunter2".as_bytes()));
+        // Nonces are random, so encrypting the same value twice shouldn't give the same result
+        assert_ne!(
+            encrypted_value,
+            secret_encryptor.encrypt("hunter2").unwrap()
+        );
+
+        assert_eq!(
+            secret_encryptor.decrypt(&encrypted_value).unwrap(),
+            "hunter2"
+        );
+    }
+
+    #[test]
+    fn test_decrypt_failure_wrong_key() {
+        let secret_encryptor = create_test_secret_encryptor();
+        let other_secret_encryptor = SecretEncryptor::new(&SecretsConfig::new(String::from(
+            "ZmVkY2JhOTg3NjU0MzIxMGZlZGNiYTk4NzY1NDMyMTA=",
+        )))
+        .unwrap();
+
+        let encrypted_value = secret_encryptor.encrypt("hunter2").unwrap();
+
+        assert!(matches!(
+            other_secret_encryptor.decrypt(&encrypted_value),
+            Err(Error::OpenSSL(_))
+        ));
+    }
+
+    #[test]
+    fn test_new_failure_invalid_key() {
+        assert!(matches!(
+            SecretEncryptor::new(&SecretsConfig::new(String::from("dG9vc2hvcnQ="))),
+            Err(Error::InvalidKey(_))
+        ));
+        assert!(matches!(
+            SecretEncryptor::new(&SecretsConfig::new(String::from("not base64!"))),
+            Err(Error::Base64(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_secret_name() {
+        assert_eq!(get_secret_name("secret:my_token"), Some("my_token"));
+        assert_eq!(get_secret_name("secret:"), None);
+        assert_eq!(get_secret_name("my secret:my_token"), None);
+        assert_eq!(get_secret_name("test_output:workflow.out"), None);
+    }
+
+    #[test]
+    fn test_get_secret_value_success() {
+        let conn = get_test_db_connection();
+        let secret_encryptor = create_test_secret_encryptor();
+        insert_test_secret(&conn, &secret_encryptor, "my_token", "hunter2");
+
+        let secret_value = get_secret_value(&conn, Some(&secret_encryptor), "my_token").unwrap();
+
+        assert_eq!(secret_value, "hunter2");
+    }
+
+    #[test]
+    fn test_get_secret_value_failure_not_found() {
+        let conn = get_test_db_connection();
+        let secret_encryptor = create_test_secret_encryptor();
+
+        let failure = get_secret_value(&conn, Some(&secret_encryptor), "my_token").unwrap_err();
+
+        assert!(matches!(failure, Error::NotFound(_)));
+    }
+
+    #[test]
+    fn test_get_secret_value_failure_not_configured() {
+        let conn = get_test_db_connection();
+        let secret_encryptor = create_test_secret_encryptor();
+        insert_test_secret(&conn, &secret_encryptor, "my_token", "hunter2");
+
+        let failure = get_secret_value(&conn, None, "my_token").unwrap_err();
+
+        assert!(matches!(failure, Error::NotConfigured(_)));
+    }
+
+    #[test]
+    fn test_redact() {
+        let conn = get_test_db_connection();
+        let secret_encryptor = create_test_secret_encryptor();
+        insert_test_secret(&conn, &secret_encryptor, "my_token", "hunter2");
+        insert_test_secret(
+            &conn,
+            &secret_encryptor,
+            "my_longer_token",
+            "hunter2hunter3",
+        );
+
+        let secret_redactor = SecretRedactor::load(&conn, Some(&secret_encryptor)).unwrap();
+
+        assert_eq!(
+            secret_redactor.redact_str("Failed to log in with hunter2 or hunter2hunter3"),
+            "Failed to log in with [REDACTED] or [REDACTED]"
+        );
+
+        let mut json_value = json!({
+            "workflow.token": "hunter2",
+            "workflow.logs": ["Using hunter2hunter3", 5],
+            "workflow.nested": {"value": "not secret"}
+        });
+        secret_redactor.redact_json(&mut json_value);
+        assert_eq!(
+            json_value,
+            json!({
+                "workflow.token": "[REDACTED]",
+                "workflow.logs": ["Using [REDACTED]", 5],
+                "workflow.nested": {"value": "not secret"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_redact_not_configured() {
+        let conn = get_test_db_connection();
+        let secret_encryptor = create_test_secret_encryptor();
+        insert_test_secret(&conn, &secret_encryptor, "my_token", "hunter2");
+
+        let secret_redactor = SecretRedactor::load(&conn, None).unwrap();
+
+        assert_eq!(secret_redactor.redact_str("hunter2"), "hunter2");
+    }
+}
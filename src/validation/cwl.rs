@@ -0,0 +1,136 @@
+//! Contains functions for validating CWL workflows
+//!
+//! There isn't a CWL counterpart to womtool that we can rely on being installed, so this is a
+//! lightweight structural check that catches files that aren't CWL at all (e.g. a WDL uploaded to
+//! a CWL template).  Anything more thorough is left to cromwell when the workflow is submitted
+
+use core::fmt;
+use serde_yaml::{Mapping, Value};
+use std::error;
+
+/// The values of `class` that are allowed for a top level CWL process
+const CWL_PROCESS_CLASSES: [&str; 4] =
+    ["Workflow", "CommandLineTool", "ExpressionTool", "Operation"];
+
+/// Enum of possible errors from validating a CWL workflow
+#[derive(Debug)]
+pub enum Error {
+    Yaml(serde_yaml::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Yaml(e) => write!(f, "CWL Yaml Error {}", e),
+            Error::Invalid(msg) => write!(f, "CWL Invalid Error {}", msg),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+// Implementing From for each of the error types so they map more easily
+impl From<serde_yaml::Error> for Error {
+    fn from(e: serde_yaml::Error) -> Error {
+        Error::Yaml(e)
+    }
+}
+
+/// Checks that `cwl_data` looks like a CWL document
+///
+/// Returns Ok(()) if `cwl_data` is a YAML or JSON object with a `cwlVersion` and either a `class`
+/// that is a CWL process class or a `$graph` of processes (as in a packed workflow).  Returns an
+/// Invalid error if not, or a Yaml error if it can't be parsed at all
+pub fn cwl_validate(cwl_data: &[u8]) -> Result<(), Error> {
+    let document: Value = serde_yaml::from_slice(cwl_data)?;
+    let document: &Mapping = match document.as_mapping() {
+        Some(mapping) => mapping,
+        None => {
+            return Err(Error::Invalid(String::from(
+                "Top level of CWL document must be an object",
+            )))
+        }
+    };
+    // Every CWL document has to declare its version
+    match document.get(&Value::from("cwlVersion")) {
+        Some(Value::String(_)) => {}
+        Some(_) => {
+            return Err(Error::Invalid(String::from(
+                "cwlVersion of CWL document must be a string",
+            )))
+        }
+        None => {
+            return Err(Error::Invalid(String::from(
+                "CWL document is missing cwlVersion",
+            )))
+        }
+    }
+    // Packed workflows have their processes in a $graph list instead of a class at the top level
+    if let Some(graph) = document.get(&Value::from("$graph")) {
+        return match graph.as_sequence() {
+            Some(processes) if !processes.is_empty() => {
+                for process in processes {
+                    validate_process_class(
+                        process
+                            .as_mapping()
+                            .and_then(|process| process.get(&Value::from("class"))),
+                    )?;
+                }
+                Ok(())
+            }
+            _ => Err(Error::Invalid(String::from(
+                "$graph of CWL document must be a non-empty list of processes",
+            ))),
+        };
+    }
+    validate_process_class(document.get(&Value::from("class")))
+}
+
+/// Returns an Invalid error if `class` is not one of the CWL process classes
+fn validate_process_class(class: Option<&Value>) -> Result<(), Error> {
+    match class {
+        Some(Value::String(class)) if CWL_PROCESS_CLASSES.contains(&class.as_str()) => Ok(()),
+        Some(class) => Err(Error::Invalid(format!(
+            "class of CWL process must be one of {:?}, but found {:?}",
+            CWL_PROCESS_CLASSES, class
+        ))),
+        None => Err(Error::Invalid(String::from("CWL process is missing class"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::validation::cwl::{cwl_validate, Error};
+    use std::fs::read;
+
+    #[test]
+    fn test_cwl_validate_valid() {
+        let cwl_data = read("testdata/validation/cwl/valid_workflow.cwl").unwrap();
+
+        assert!(cwl_validate(&cwl_data).is_ok());
+    }
+
+    #[test]
+    fn test_cwl_validate_valid_packed() {
+        let cwl_data = read("testdata/validation/cwl/valid_packed_workflow.cwl").unwrap();
+
+        assert!(cwl_validate(&cwl_data).is_ok());
+    }
+
+    #[test]
+    fn test_cwl_validate_missing_class() {
+        let cwl_data = read("testdata/validation/cwl/invalid_workflow_missing_class.cwl").unwrap();
+
+        let failure = cwl_validate(&cwl_data).unwrap_err();
+        assert!(matches!(failure, Error::Invalid(_)));
+    }
+
+    #[test]
+    fn test_cwl_validate_not_cwl() {
+        let cwl_data = read("testdata/validation/cwl/invalid_workflow_not_yaml.cwl").unwrap();
+
+        let failure = cwl_validate(&cwl_data).unwrap_err();
+        assert!(matches!(failure, Error::Invalid(_) | Error::Yaml(_)));
+    }
+}
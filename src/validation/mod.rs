@@ -1,2 +1,4 @@
 //! Contains modules for performing validation of various aspects of test configurations
+pub mod cwl;
+pub mod wdl_inputs;
 pub mod womtool;
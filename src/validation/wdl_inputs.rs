@@ -0,0 +1,763 @@
+//! Contains functions for checking test and eval inputs against the inputs WDLs declare
+//!
+//! Uses the output of womtool inputs (see [`WomtoolRunner::womtool_inputs`]) as the signature for a
+//! WDL, and reports inputs that aren't in the signature, required inputs that are missing, and
+//! values that can't be the type the WDL expects, so problems can be reported before a workflow is
+//! submitted to cromwell instead of after cromwell rejects it
+//!
+//! Also checks the `test_output:` and `image_build:` references in inputs, which CARROT resolves
+//! itself, against the outputs of the test WDL (from womtool outputs) and the software in the DB
+
+use crate::custom_sql_types::WorkflowLanguageEnum;
+use crate::manager::test_runner::{IMAGE_BUILD_REGEX, TEST_OUTPUT_REGEX};
+use crate::models::software::SoftwareData;
+use crate::models::template::TemplateData;
+use crate::requests::test_resource_requests;
+use crate::requests::test_resource_requests::TestResourceClient;
+use crate::validation::womtool;
+use crate::validation::womtool::WomtoolRunner;
+use core::fmt;
+use diesel::PgConnection;
+use log::debug;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::error;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Enum of possible errors from checking inputs against a WDL
+#[derive(Debug)]
+pub enum Error {
+    Womtool(womtool::Error),
+    Request(test_resource_requests::Error),
+    IO(std::io::Error),
+    Zip(zip::result::ZipError),
+    DB(diesel::result::Error),
+    Invalid(Vec<String>),
+    InvalidReferences(Vec<ReferenceProblem>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Womtool(e) => write!(f, "WDL Inputs Womtool Error {}", e),
+            Error::Request(e) => write!(f, "WDL Inputs Request Error {}", e),
+            Error::IO(e) => write!(f, "WDL Inputs IO Error {}", e),
+            Error::Zip(e) => write!(f, "WDL Inputs Zip Error {}", e),
+            Error::DB(e) => write!(f, "WDL Inputs DB Error {}", e),
+            Error::Invalid(problems) => {
+                write!(f, "WDL Inputs Invalid Error {}", problems.join("; "))
+            }
+            Error::InvalidReferences(problems) => write!(
+                f,
+                "WDL Inputs InvalidReferences Error {}",
+                problems
+                    .iter()
+                    .map(ReferenceProblem::to_string)
+                    .collect::<Vec<String>>()
+                    .join("; ")
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+// Implementing From for each of the error types so they map more easily
+impl From<womtool::Error> for Error {
+    fn from(e: womtool::Error) -> Error {
+        Error::Womtool(e)
+    }
+}
+impl From<test_resource_requests::Error> for Error {
+    fn from(e: test_resource_requests::Error) -> Error {
+        Error::Request(e)
+    }
+}
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Error {
+        Error::IO(e)
+    }
+}
+impl From<zip::result::ZipError> for Error {
+    fn from(e: zip::result::ZipError) -> Error {
+        Error::Zip(e)
+    }
+}
+impl From<diesel::result::Error> for Error {
+    fn from(e: diesel::result::Error) -> Error {
+        Error::DB(e)
+    }
+}
+
+/// A `test_output:` or `image_build:` reference in a set of inputs that can't be resolved
+///
+/// `wdl` identifies the WDL the inputs are for (e.g. `eval WDL`), `input` is the name of the input
+/// with the reference as its value, `reference` is the value, and `problem` describes what's wrong
+/// with it
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ReferenceProblem {
+    pub wdl: String,
+    pub input: String,
+    pub reference: String,
+    pub problem: String,
+}
+
+impl fmt::Display for ReferenceProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Value {} for {} input {}: {}",
+            self.reference, self.wdl, self.input, self.problem
+        )
+    }
+}
+
+/// The type of a WDL input, parsed from the type description womtool inputs gives for it
+#[derive(Debug, PartialEq)]
+enum WdlInputType {
+    Boolean,
+    Int,
+    Float,
+    String,
+    File,
+    /// An array type, and whether it's required to be non-empty (e.g. `Array[Int]+`)
+    Array(Box<WdlInputType>, bool),
+    Map(Box<WdlInputType>, Box<WdlInputType>),
+    Pair(Box<WdlInputType>, Box<WdlInputType>),
+    Optional(Box<WdlInputType>),
+    /// Structs, Object, and anything else we don't check the contents of
+    Other(String),
+}
+
+impl fmt::Display for WdlInputType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WdlInputType::Boolean => write!(f, "Boolean"),
+            WdlInputType::Int => write!(f, "Int"),
+            WdlInputType::Float => write!(f, "Float"),
+            WdlInputType::String => write!(f, "String"),
+            WdlInputType::File => write!(f, "File"),
+            WdlInputType::Array(inner, true) => write!(f, "Array[{}]+", inner),
+            WdlInputType::Array(inner, false) => write!(f, "Array[{}]", inner),
+            WdlInputType::Map(key, value) => write!(f, "Map[{}, {}]", key, value),
+            WdlInputType::Pair(left, right) => write!(f, "Pair[{}, {}]", left, right),
+            WdlInputType::Optional(inner) => write!(f, "{}?", inner),
+            WdlInputType::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl WdlInputType {
+    /// Parses a WDL type (e.g. `Array[Pair[String, Int]]+`) from `type_string`.  Types that can't
+    /// be parsed become `Other`
+    fn parse(type_string: &str) -> WdlInputType {
+        let type_string = type_string.trim();
+        if let Some(inner) = type_string.strip_suffix('?') {
+            return WdlInputType::Optional(Box::new(WdlInputType::parse(inner)));
+        }
+        let (type_string, non_empty) = match type_string.strip_suffix('+') {
+            Some(type_string) => (type_string, true),
+            None => (type_string, false),
+        };
+        // Split compound types into their name and type parameters
+        if let (Some(open), true) = (type_string.find('['), type_string.ends_with(']')) {
+            let name = &type_string[..open];
+            let params =
+                WdlInputType::split_type_params(&type_string[open + 1..type_string.len() - 1]);
+            match (name, params.as_slice()) {
+                ("Array", [inner]) => {
+                    return WdlInputType::Array(Box::new(WdlInputType::parse(inner)), non_empty)
+                }
+                ("Map", [key, value]) => {
+                    return WdlInputType::Map(
+                        Box::new(WdlInputType::parse(key)),
+                        Box::new(WdlInputType::parse(value)),
+                    )
+                }
+                ("Pair", [left, right]) => {
+                    return WdlInputType::Pair(
+                        Box::new(WdlInputType::parse(left)),
+                        Box::new(WdlInputType::parse(right)),
+                    )
+                }
+                _ => return WdlInputType::Other(String::from(type_string)),
+            }
+        }
+        match type_string {
+            "Boolean" => WdlInputType::Boolean,
+            "Int" => WdlInputType::Int,
+            "Float" => WdlInputType::Float,
+            "String" => WdlInputType::String,
+            "File" => WdlInputType::File,
+            _ => WdlInputType::Other(String::from(type_string)),
+        }
+    }
+
+    /// Splits `params` (e.g. `String, Array[Int]`) on the commas that aren't nested inside another
+    /// type's parameters
+    fn split_type_params(params: &str) -> Vec<&str> {
+        let mut split_params: Vec<&str> = Vec::new();
+        let mut depth: usize = 0;
+        let mut start: usize = 0;
+        for (index, c) in params.char_indices() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    split_params.push(params[start..index].trim());
+                    start = index + 1;
+                }
+                _ => {}
+            }
+        }
+        split_params.push(params[start..].trim());
+        split_params
+    }
+
+    /// Returns true if `value` can be coerced to this type by cromwell
+    ///
+    /// Strings are accepted for primitive types if they parse as that type, since cromwell will
+    /// coerce them
+    fn accepts(&self, value: &Value) -> bool {
+        match (self, value) {
+            (WdlInputType::Optional(_), Value::Null) => true,
+            (WdlInputType::Optional(inner), _) => inner.accepts(value),
+            (WdlInputType::Other(_), _) => true,
+            (_, Value::Null) => false,
+            (WdlInputType::Boolean, Value::Bool(_)) => true,
+            (WdlInputType::Boolean, Value::String(string_val)) => {
+                string_val == "true" || string_val == "false"
+            }
+            (WdlInputType::Int, Value::Number(number_val)) => number_val.is_i64(),
+            (WdlInputType::Int, Value::String(string_val)) => string_val.parse::<i64>().is_ok(),
+            (WdlInputType::Float, Value::Number(_)) => true,
+            (WdlInputType::Float, Value::String(string_val)) => string_val.parse::<f64>().is_ok(),
+            (WdlInputType::String, Value::String(_))
+            | (WdlInputType::String, Value::Number(_))
+            | (WdlInputType::String, Value::Bool(_)) => true,
+            (WdlInputType::File, Value::String(_)) => true,
+            (WdlInputType::Array(inner, non_empty), Value::Array(array_val)) => {
+                !(*non_empty && array_val.is_empty())
+                    && array_val.iter().all(|element| inner.accepts(element))
+            }
+            (WdlInputType::Map(_, value_type), Value::Object(map_val)) => map_val
+                .values()
+                .all(|map_value| value_type.accepts(map_value)),
+            (WdlInputType::Pair(left, right), Value::Object(pair_val)) => {
+                match (pair_val.get("left"), pair_val.get("right")) {
+                    (Some(left_val), Some(right_val)) => {
+                        left.accepts(left_val) && right.accepts(right_val)
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Checks `inputs` against `input_signature` (the output of womtool inputs for a WDL), and returns
+/// a description of each problem found
+///
+/// Problems are inputs that aren't in `input_signature`, values that can't be the type the WDL
+/// expects, and, if `check_required` is true, required inputs that are missing from `inputs`.
+/// String values starting with `test_output:`, `run_output:`, or `latest_successful_output:` are
+/// filled in by CARROT before the WDL is run, so they count as present but their types aren't
+/// checked.  `wdl_name` is used to identify the WDL in the problem descriptions
+pub fn check_inputs(
+    input_signature: &Map<String, Value>,
+    inputs: &Map<String, Value>,
+    check_required: bool,
+    wdl_name: &str,
+) -> Vec<String> {
+    let mut problems: Vec<String> = Vec::new();
+    for (key, value) in inputs {
+        let input_type: WdlInputType = match input_signature.get(key) {
+            Some(input_description) => parse_input_description(input_description).0,
+            None => {
+                problems.push(format!("{} has no input named {}", wdl_name, key));
+                continue;
+            }
+        };
+        if let Some(string_val) = value.as_str() {
+            if string_val.starts_with("test_output:")
+                || string_val.starts_with("run_output:")
+                || string_val.starts_with("latest_successful_output:")
+            {
+                continue;
+            }
+        }
+        if !input_type.accepts(value) {
+            problems.push(format!(
+                "Value for {} input {} should be {}, but found {}",
+                wdl_name, key, input_type, value
+            ));
+        }
+    }
+    if check_required {
+        for (key, input_description) in input_signature {
+            let (input_type, is_required) = parse_input_description(input_description);
+            if is_required && !inputs.contains_key(key) {
+                problems.push(format!(
+                    "Missing required {} input {} ({})",
+                    wdl_name, key, input_type
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Parses an input description from womtool inputs (e.g. `Int (optional, default = 5)`) into
+/// the input's type and whether a value is required for it
+fn parse_input_description(input_description: &Value) -> (WdlInputType, bool) {
+    let input_description: &str = input_description.as_str().unwrap_or_default();
+    match input_description.find(" (optional") {
+        Some(index) => (WdlInputType::parse(&input_description[..index]), false),
+        None => {
+            let input_type = WdlInputType::parse(input_description);
+            let is_required = !matches!(input_type, WdlInputType::Optional(_));
+            (input_type, is_required)
+        }
+    }
+}
+
+/// Checks `test_inputs` and `eval_inputs` against the input signatures of `template`'s test and
+/// eval WDLs, respectively
+///
+/// Retrieves the WDLs (and their dependencies) with `test_resource_client` and runs womtool inputs
+/// on them with `womtool_runner`.  WDLs with no inputs to check aren't retrieved, and CWL templates
+/// aren't checked at all.  Returns an Invalid error with all the problems found if there are any
+/// (see [`check_inputs`]), or a different error if checking fails for some other reason
+pub async fn validate_inputs_for_template(
+    womtool_runner: &WomtoolRunner,
+    test_resource_client: &TestResourceClient,
+    template: &TemplateData,
+    test_inputs: Option<&Value>,
+    eval_inputs: Option<&Value>,
+    check_required: bool,
+) -> Result<(), Error> {
+    // Womtool can't get inputs from CWL
+    if template.workflow_language == WorkflowLanguageEnum::Cwl {
+        return Ok(());
+    }
+    let mut problems: Vec<String> = Vec::new();
+    for (wdl_name, inputs, wdl_location, wdl_dependencies_location) in [
+        (
+            "test WDL",
+            test_inputs,
+            &template.test_wdl,
+            &template.test_wdl_dependencies,
+        ),
+        (
+            "eval WDL",
+            eval_inputs,
+            &template.eval_wdl,
+            &template.eval_wdl_dependencies,
+        ),
+    ] {
+        let inputs: &Map<String, Value> = match inputs {
+            Some(Value::Object(inputs)) => inputs,
+            Some(_) => {
+                problems.push(format!("Inputs for {} must be a JSON object", wdl_name));
+                continue;
+            }
+            None => continue,
+        };
+        let input_signature: Map<String, Value> = run_womtool_on_wdl(
+            womtool_runner,
+            test_resource_client,
+            wdl_location,
+            wdl_dependencies_location.as_deref(),
+            WomtoolRunner::womtool_inputs,
+        )
+        .await?;
+        problems.extend(check_inputs(
+            &input_signature,
+            inputs,
+            check_required,
+            wdl_name,
+        ));
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Invalid(problems))
+    }
+}
+
+/// Checks the `test_output:` and `image_build:` references in `test_inputs` and `eval_inputs`
+///
+/// `image_build:` references must be formatted as `image_build:{software_name}|{commit}` and name
+/// software that exists in the DB.  `test_output:` references can only be used in eval inputs and,
+/// for WDL templates, must name an output of `template`'s test WDL (retrieved with
+/// `test_resource_client` and parsed with womtool outputs using `womtool_runner`).  The test
+/// outputs of CWL templates can't be determined ahead of time, so those `test_output:` references
+/// are not checked.  Returns an InvalidReferences error with all the problems found if there are
+/// any, or a different error if checking fails for some other reason
+pub async fn validate_references_for_template(
+    conn: &PgConnection,
+    womtool_runner: &WomtoolRunner,
+    test_resource_client: &TestResourceClient,
+    template: &TemplateData,
+    test_inputs: Option<&Value>,
+    eval_inputs: Option<&Value>,
+) -> Result<(), Error> {
+    let mut problems: Vec<ReferenceProblem> = Vec::new();
+    // We'll only get the test outputs if we find a test_output reference that needs them
+    let mut test_outputs: Option<Map<String, Value>> = None;
+    for (wdl_name, inputs) in [("test WDL", test_inputs), ("eval WDL", eval_inputs)] {
+        // Inputs that aren't objects can't contain references
+        let inputs: &Map<String, Value> = match inputs {
+            Some(Value::Object(inputs)) => inputs,
+            _ => continue,
+        };
+        for (key, value) in inputs {
+            let reference: &str = match value.as_str() {
+                Some(reference) => reference,
+                None => continue,
+            };
+            let problem: Option<String> = if reference.starts_with("image_build:") {
+                check_image_build_reference(conn, reference)?
+            } else if reference.starts_with("test_output:") {
+                if wdl_name == "test WDL" {
+                    Some(String::from(
+                        "test_output references can only be used in eval inputs",
+                    ))
+                } else if template.workflow_language == WorkflowLanguageEnum::Cwl {
+                    None
+                } else if !TEST_OUTPUT_REGEX.is_match(reference) {
+                    Some(String::from(
+                        "test_output references must be formatted as test_output:{workflow_name}.{output_name}",
+                    ))
+                } else {
+                    if test_outputs.is_none() {
+                        test_outputs = Some(
+                            get_wdl_outputs(
+                                womtool_runner,
+                                test_resource_client,
+                                &template.test_wdl,
+                                template.test_wdl_dependencies.as_deref(),
+                            )
+                            .await?,
+                        );
+                    }
+                    check_test_output_reference(
+                        test_outputs
+                            .as_ref()
+                            .expect("Failed to unwrap test outputs. This should not happen"),
+                        reference,
+                    )
+                }
+            } else {
+                None
+            };
+            if let Some(problem) = problem {
+                problems.push(ReferenceProblem {
+                    wdl: String::from(wdl_name),
+                    input: key.clone(),
+                    reference: String::from(reference),
+                    problem,
+                });
+            }
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::InvalidReferences(problems))
+    }
+}
+
+/// Returns a description of the problem with `reference` (an `image_build:` reference) if it is
+/// formatted incorrectly or names software that isn't in the DB, or None if it's fine
+fn check_image_build_reference(
+    conn: &PgConnection,
+    reference: &str,
+) -> Result<Option<String>, Error> {
+    if !IMAGE_BUILD_REGEX.is_match(reference) {
+        return Ok(Some(String::from(
+            "image_build references must be formatted as image_build:{software_name}|{commit}",
+        )));
+    }
+    let software_name: &str = reference
+        .trim_start_matches("image_build:")
+        .split('|')
+        .next()
+        .unwrap_or_default();
+    match SoftwareData::find_by_name_ignore_case(conn, software_name) {
+        Ok(_) => Ok(None),
+        Err(diesel::result::Error::NotFound) => Ok(Some(format!(
+            "No software exists with name {}",
+            software_name
+        ))),
+        Err(e) => Err(Error::DB(e)),
+    }
+}
+
+/// Returns a description of the problem with `reference` (a `test_output:` reference) if it doesn't
+/// name one of `test_outputs`, or None if it does
+fn check_test_output_reference(
+    test_outputs: &Map<String, Value>,
+    reference: &str,
+) -> Option<String> {
+    let output_key: &str = reference.trim_start_matches("test_output:");
+    if test_outputs.contains_key(output_key) {
+        None
+    } else {
+        let mut output_names: Vec<&str> = test_outputs.keys().map(String::as_str).collect();
+        output_names.sort_unstable();
+        Some(format!(
+            "test WDL has no output named {} (outputs are: {})",
+            output_key,
+            output_names.join(", ")
+        ))
+    }
+}
+
+/// Retrieves the WDL at `wdl_location` (and its dependencies at `wdl_dependencies_location`, if
+/// any) with `test_resource_client` and returns its outputs, as parsed by womtool outputs using
+/// `womtool_runner` (a map of fully-qualified output names to their types)
+pub async fn get_wdl_outputs(
+    womtool_runner: &WomtoolRunner,
+    test_resource_client: &TestResourceClient,
+    wdl_location: &str,
+    wdl_dependencies_location: Option<&str>,
+) -> Result<Map<String, Value>, Error> {
+    run_womtool_on_wdl(
+        womtool_runner,
+        test_resource_client,
+        wdl_location,
+        wdl_dependencies_location,
+        WomtoolRunner::womtool_outputs,
+    )
+    .await
+}
+
+/// A WomtoolRunner method that runs a womtool utility on a WDL and returns its JSON output (i.e.
+/// [`WomtoolRunner::womtool_inputs`] or [`WomtoolRunner::womtool_outputs`])
+type WomtoolCommand = fn(&WomtoolRunner, &Path) -> Result<Map<String, Value>, womtool::Error>;
+
+/// Retrieves the WDL at `wdl_location` and the zip of its dependencies at
+/// `wdl_dependencies_location` (if there is one), writes them to a temp dir so the WDL's imports can
+/// be resolved, and runs `womtool_command` (e.g. [`WomtoolRunner::womtool_inputs`]) on the WDL
+async fn run_womtool_on_wdl(
+    womtool_runner: &WomtoolRunner,
+    test_resource_client: &TestResourceClient,
+    wdl_location: &str,
+    wdl_dependencies_location: Option<&str>,
+    womtool_command: WomtoolCommand,
+) -> Result<Map<String, Value>, Error> {
+    let wdl_data: Vec<u8> = test_resource_client
+        .get_resource_as_bytes(wdl_location)
+        .await?;
+    let wdl_temp_dir = tempfile::tempdir()?;
+    let mut wdl_file_path: PathBuf = PathBuf::from(wdl_temp_dir.path());
+    wdl_file_path.push("wdl.wdl");
+    std::fs::write(&wdl_file_path, &wdl_data)?;
+    if let Some(wdl_dependencies_location) = wdl_dependencies_location {
+        let wdl_dependencies_data: Vec<u8> = test_resource_client
+            .get_resource_as_bytes(wdl_dependencies_location)
+            .await?;
+        zip::ZipArchive::new(Cursor::new(wdl_dependencies_data))?.extract(wdl_temp_dir.path())?;
+    }
+    debug!("Running womtool on WDL at {}", wdl_location);
+    Ok(womtool_command(womtool_runner, &wdl_file_path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::software::{NewSoftware, SoftwareData};
+    use crate::unit_test_util::get_test_db_connection;
+    use crate::validation::wdl_inputs::{
+        check_image_build_reference, check_inputs, check_test_output_reference, WdlInputType,
+    };
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_wdl_input_type() {
+        assert_eq!(WdlInputType::parse("Int"), WdlInputType::Int);
+        assert_eq!(
+            WdlInputType::parse("String?"),
+            WdlInputType::Optional(Box::new(WdlInputType::String))
+        );
+        assert_eq!(
+            WdlInputType::parse("Array[Pair[String, Array[Int]]]+"),
+            WdlInputType::Array(
+                Box::new(WdlInputType::Pair(
+                    Box::new(WdlInputType::String),
+                    Box::new(WdlInputType::Array(Box::new(WdlInputType::Int), false))
+                )),
+                true
+            )
+        );
+        assert_eq!(
+            WdlInputType::parse("Map[String, File]"),
+            WdlInputType::Map(Box::new(WdlInputType::String), Box::new(WdlInputType::File))
+        );
+        assert_eq!(
+            WdlInputType::parse("MyStruct"),
+            WdlInputType::Other(String::from("MyStruct"))
+        );
+    }
+
+    #[test]
+    fn test_check_inputs_valid() {
+        let input_signature = json!({
+            "myWorkflow.person": "String? (optional)",
+            "myWorkflow.times": "Int (optional, default = 3)",
+            "myWorkflow.in_file": "File",
+            "myWorkflow.flags": "Array[Boolean]+",
+            "myWorkflow.pair": "Pair[Int, Float]"
+        });
+        let inputs = json!({
+            "myWorkflow.times": "4",
+            "myWorkflow.in_file": "gs://example/in_file.txt",
+            "myWorkflow.flags": [true, false],
+            "myWorkflow.pair": {"left": 1, "right": 2.5}
+        });
+
+        let problems = check_inputs(
+            input_signature.as_object().unwrap(),
+            inputs.as_object().unwrap(),
+            true,
+            "test WDL",
+        );
+
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_check_inputs_invalid() {
+        let input_signature = json!({
+            "myWorkflow.person": "String? (optional)",
+            "myWorkflow.times": "Int (optional, default = 3)",
+            "myWorkflow.in_file": "File",
+            "myWorkflow.flags": "Array[Boolean]+"
+        });
+        let inputs = json!({
+            "myWorkflow.persn": "Kevin",
+            "myWorkflow.times": "three",
+            "myWorkflow.flags": []
+        });
+
+        let mut problems = check_inputs(
+            input_signature.as_object().unwrap(),
+            inputs.as_object().unwrap(),
+            true,
+            "test WDL",
+        );
+        problems.sort();
+
+        assert_eq!(
+            problems,
+            vec![
+                "Missing required test WDL input myWorkflow.in_file (File)",
+                "Value for test WDL input myWorkflow.flags should be Array[Boolean]+, but found []",
+                "Value for test WDL input myWorkflow.times should be Int, but found \"three\"",
+                "test WDL has no input named myWorkflow.persn",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_inputs_not_required() {
+        let input_signature = json!({
+            "evalWorkflow.in_file": "File",
+            "evalWorkflow.truth_file": "File"
+        });
+        let inputs = json!({
+            "evalWorkflow.in_file": "test_output:myWorkflow.out_file",
+        });
+
+        let problems = check_inputs(
+            input_signature.as_object().unwrap(),
+            inputs.as_object().unwrap(),
+            false,
+            "eval WDL",
+        );
+
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_check_inputs_run_output_references() {
+        let input_signature = json!({
+            "myWorkflow.times": "Int",
+            "myWorkflow.in_file": "File"
+        });
+        let inputs = json!({
+            "myWorkflow.times": "latest_successful_output:Kevin's Test.evalWorkflow.count",
+            "myWorkflow.in_file": "run_output:Kevin's Run.myWorkflow.out_file"
+        });
+
+        let problems = check_inputs(
+            input_signature.as_object().unwrap(),
+            inputs.as_object().unwrap(),
+            true,
+            "test WDL",
+        );
+
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+
+    #[test]
+    fn test_check_image_build_reference() {
+        let conn = get_test_db_connection();
+        SoftwareData::create(
+            &conn,
+            NewSoftware {
+                name: String::from("TestSoftware"),
+                description: None,
+                repository_url: String::from("git://example.com/example/test.git"),
+                created_by: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            check_image_build_reference(
+                &conn,
+                "image_build:TestSoftware|1a4c5eb5fc4921b2642b6ded863894b3745a5dc7"
+            )
+            .unwrap(),
+            None
+        );
+        assert_eq!(
+            check_image_build_reference(&conn, "image_build:OtherSoftware|1a4c5eb5").unwrap(),
+            Some(String::from("No software exists with name OtherSoftware"))
+        );
+        assert_eq!(
+            check_image_build_reference(&conn, "image_build:TestSoftware").unwrap(),
+            Some(String::from(
+                "image_build references must be formatted as image_build:{software_name}|{commit}"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_check_test_output_reference() {
+        let test_outputs = json!({
+            "myWorkflow.greeting": "String",
+            "myWorkflow.out_file": "File"
+        });
+
+        assert_eq!(
+            check_test_output_reference(
+                test_outputs.as_object().unwrap(),
+                "test_output:myWorkflow.greeting"
+            ),
+            None
+        );
+        assert_eq!(
+            check_test_output_reference(
+                test_outputs.as_object().unwrap(),
+                "test_output:myWorkflow.greting"
+            ),
+            Some(String::from(
+                "test WDL has no output named myWorkflow.greting (outputs are: myWorkflow.greeting, myWorkflow.out_file)"
+            ))
+        );
+    }
+}
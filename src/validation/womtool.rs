@@ -2,6 +2,7 @@
 //! validating WDLs
 
 use core::fmt;
+use serde_json::{Map, Value};
 use std::error;
 use std::fs::read_to_string;
 use std::path::Path;
@@ -89,13 +90,10 @@ impl WomtoolRunner {
 
     /// Runs the womtool inputs utility on the WDL at the specified path
     ///
-    /// Returns the WOMtool output if parsing the WDL inputs is successful, or an error if there is some
-    /// issue running WOMtool
-    ///
-    /// This is function is currently not in use, but it's functionality will likely be necessary in the
-    /// future, so it is included
-    #[allow(dead_code)]
-    fn womtool_inputs(&self, wdl_path: &Path) -> Result<Vec<u8>, Error> {
+    /// Returns the WOMtool output (a map of fully-qualified input names to descriptions of their
+    /// types, e.g. `"Int (optional, default = 5)"`) if parsing the WDL inputs is successful, or an
+    /// error if there is some issue running WOMtool
+    pub fn womtool_inputs(&self, wdl_path: &Path) -> Result<Map<String, Value>, Error> {
         // Run womtool validate on the wdl
         let output = Command::new("sh")
             .arg("-c")
@@ -108,7 +106,7 @@ impl WomtoolRunner {
 
         // Return the output or an error depending on WOMtool's status
         if output.status.success() {
-            Ok(output.stdout)
+            Ok(serde_json::from_slice(&output.stdout)?)
         } else {
             let error_msg = match String::from_utf8(output.stderr) {
                 Ok(msg) => msg,
@@ -127,6 +125,38 @@ impl WomtoolRunner {
             )))
         }
     }
+
+    /// Runs the womtool outputs utility on the WDL at the specified path
+    ///
+    /// Returns the WOMtool output (a map of fully-qualified output names to their types, e.g.
+    /// `"File"`) if parsing the WDL outputs is successful, or an error if there is some issue running
+    /// WOMtool
+    pub fn womtool_outputs(&self, wdl_path: &Path) -> Result<Map<String, Value>, Error> {
+        // Run womtool outputs on the wdl
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(format!(
+                "java -jar {} outputs {}",
+                self.womtool_location,
+                wdl_path.display()
+            ))
+            .output()?;
+
+        // Return the output or an error depending on WOMtool's status
+        if output.status.success() {
+            Ok(serde_json::from_slice(&output.stdout)?)
+        } else {
+            let error_msg = match String::from_utf8(output.stderr) {
+                Ok(msg) => msg,
+                Err(e) => format!("Failed to get error message from womtool with error {}", e),
+            };
+            Err(Error::Invalid(format!(
+                "Womtool outputs encountered error: {}\nwhile attempting to parse outputs for wdl: {}",
+                error_msg,
+                wdl_path.display()
+            )))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -134,6 +164,7 @@ mod tests {
     use crate::unit_test_util::load_default_config;
     use crate::validation::womtool::{Error, WomtoolRunner};
     use actix_web::client::Client;
+    use serde_json::Value;
     use std::collections::HashMap;
     use std::fs::read_to_string;
     use std::path::Path;
@@ -161,4 +192,18 @@ mod tests {
             .unwrap_err();
         assert!(matches!(failure, Error::Invalid(_)));
     }
+
+    #[actix_rt::test]
+    async fn test_womtool_outputs() {
+        let config = load_default_config();
+        let womtool_runner = WomtoolRunner::new(config.validation().womtool_location());
+
+        let outputs = womtool_runner
+            .womtool_outputs(&Path::new("testdata/validation/womtool/valid_wdl.wdl"))
+            .unwrap();
+        assert_eq!(
+            outputs.get("myWorkflow.greeting"),
+            Some(&Value::String(String::from("String")))
+        );
+    }
 }